@@ -37,7 +37,7 @@ mod place_over_frame;
 fn main() {
     // Create event loop
     let mut event_loop = EventLoop::new();
-    let context = VulkanoContext::new(VulkanoConfig::default());
+    let context = VulkanoContext::new(VulkanoConfig::default()).unwrap();
     let mut windows = VulkanoWindows::default();
     let _id = windows.create_window(
         &event_loop,
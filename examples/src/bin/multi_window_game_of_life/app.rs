@@ -101,7 +101,7 @@ impl App {
 impl Default for App {
     fn default() -> Self {
         App {
-            context: VulkanoContext::new(VulkanoConfig::default()),
+            context: VulkanoContext::new(VulkanoConfig::default()).unwrap(),
             windows: VulkanoWindows::default(),
             pipelines: HashMap::new(),
         }
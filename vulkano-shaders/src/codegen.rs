@@ -18,9 +18,13 @@ use std::{
     iter::Iterator,
     path::Path,
 };
-use vulkano::shader::{
-    reflect,
-    spirv::{Spirv, SpirvError},
+use vulkano::{
+    descriptor_set::layout::DescriptorType,
+    shader::{
+        reflect,
+        spirv::{Decoration, Instruction, Spirv, SpirvError},
+        DescriptorRequirements,
+    },
 };
 
 pub(super) fn path_to_str(path: &Path) -> &str {
@@ -193,8 +197,11 @@ pub fn compile(
         compile_options.add_macro_definition(macro_name.as_ref(), Some(macro_value.as_ref()));
     }
 
-    #[cfg(feature = "shaderc-debug")]
-    compile_options.set_generate_debug_info();
+    // Debug printf messages are only useful with debug info, so that the validation layers can
+    // report the source location of the `debugPrintfEXT` call.
+    if cfg!(feature = "shaderc-debug") || uses_debug_printf(code) {
+        compile_options.set_generate_debug_info();
+    }
 
     let content = compiler
         .compile_into_spirv(code, ty, root_source_path, "main", Some(&compile_options))
@@ -205,12 +212,24 @@ pub fn compile(
     Ok((content, includes))
 }
 
+/// Returns whether the shader source enables the `GL_EXT_debug_printf` extension.
+fn uses_debug_printf(code: &str) -> bool {
+    code.lines().any(|line| {
+        let mut words = line.split_whitespace();
+        words.next() == Some("#extension")
+            && words.next().map_or(false, |name| {
+                name.trim_end_matches(':') == "GL_EXT_debug_printf"
+            })
+    })
+}
+
 pub(super) fn reflect<'a>(
     prefix: &'a str,
     words: &[u32],
     types_meta: &TypesMeta,
     input_paths: impl IntoIterator<Item = &'a str>,
     shared_constants: bool,
+    inline_uniform_blocks: &[(u32, u32)],
     types_registry: &'a mut HashMap<String, RegisteredType>,
 ) -> Result<(TokenStream, TokenStream), Error> {
     let spirv = Spirv::new(words)?;
@@ -240,8 +259,15 @@ pub(super) fn reflect<'a>(
         quote! { &::vulkano::shader::spirv::Capability::#name }
     });
     let spirv_extensions = reflect::spirv_extensions(&spirv);
-    let entry_points = reflect::entry_points(&spirv)
-        .map(|(name, model, info)| entry_point::write_entry_point(&name, model, &info));
+    let entry_points = reflect::entry_points(&spirv).map(|(name, model, mut info)| {
+        mark_inline_uniform_blocks(
+            prefix,
+            &spirv,
+            &mut info.descriptor_requirements,
+            inline_uniform_blocks,
+        );
+        entry_point::write_entry_point(&name, model, &info)
+    });
 
     let specialization_constants =
         structs::write_specialization_constants(prefix, &spirv, shared_constants, types_registry);
@@ -283,6 +309,86 @@ pub(super) fn reflect<'a>(
     Ok((shader_code, structs))
 }
 
+/// Restricts the listed uniform blocks to the `InlineUniformBlock` descriptor type, with the size
+/// of the block in bytes as the descriptor count.
+fn mark_inline_uniform_blocks(
+    prefix: &str,
+    spirv: &Spirv,
+    descriptor_requirements: &mut HashMap<(u32, u32), DescriptorRequirements>,
+    inline_uniform_blocks: &[(u32, u32)],
+) {
+    for &(set, binding) in inline_uniform_blocks {
+        // The entry point doesn't use this binding.
+        let reqs = match descriptor_requirements.get_mut(&(set, binding)) {
+            Some(reqs) => reqs,
+            None => continue,
+        };
+
+        if !reqs
+            .descriptor_types
+            .contains(&DescriptorType::InlineUniformBlock)
+        {
+            panic!(
+                "Binding {} of set {} is listed in `inline_uniform_blocks`, but is not a \
+                non-arrayed uniform block",
+                binding, set,
+            );
+        }
+
+        let block_type = spirv
+            .iter_global()
+            .find_map(|instruction| match *instruction {
+                Instruction::Variable {
+                    result_type_id,
+                    result_id,
+                    ..
+                } => {
+                    let id_info = spirv.id(result_id);
+                    let variable_set =
+                        id_info
+                            .iter_decoration()
+                            .find_map(|instruction| match *instruction {
+                                Instruction::Decorate {
+                                    decoration: Decoration::DescriptorSet { descriptor_set },
+                                    ..
+                                } => Some(descriptor_set),
+                                _ => None,
+                            });
+                    let variable_binding =
+                        id_info
+                            .iter_decoration()
+                            .find_map(|instruction| match *instruction {
+                                Instruction::Decorate {
+                                    decoration: Decoration::Binding { binding_point },
+                                    ..
+                                } => Some(binding_point),
+                                _ => None,
+                            });
+
+                    if variable_set == Some(set) && variable_binding == Some(binding) {
+                        match *spirv.id(result_type_id).instruction() {
+                            Instruction::TypePointer { ty, .. } => Some(ty),
+                            _ => None,
+                        }
+                    } else {
+                        None
+                    }
+                }
+                _ => None,
+            })
+            .unwrap();
+
+        // Inline uniform block sizes must be a multiple of 4.
+        let size = structs::type_from_id(prefix, spirv, block_type)
+            .2
+            .expect("inline uniform blocks must have a size that is known at compile time");
+        let size = ((size + 3) & !3) as u32;
+
+        reqs.descriptor_types = vec![DescriptorType::InlineUniformBlock];
+        reqs.descriptor_count = Some(size);
+    }
+}
+
 #[derive(Debug)]
 pub enum Error {
     IoError(IoError),
@@ -339,6 +445,19 @@ mod tests {
         Spirv::new(&insts).unwrap();
     }
 
+    #[test]
+    fn detect_debug_printf() {
+        assert!(uses_debug_printf(
+            "#version 450\n#extension GL_EXT_debug_printf : enable\nvoid main() {}"
+        ));
+        assert!(uses_debug_printf(
+            "  #extension GL_EXT_debug_printf: require"
+        ));
+        assert!(!uses_debug_printf(
+            "#version 450\n// GL_EXT_debug_printf\nvoid main() {}"
+        ));
+    }
+
     #[test]
     fn test_bad_alignment() {
         // vec3/mat3/mat3x* are problematic in arrays since their rust
@@ -435,6 +554,39 @@ mod tests {
         structs::write_structs("", &spirv, &TypesMeta::default(), &mut HashMap::default());
     }
 
+    #[test]
+    fn test_buffer_reference() {
+        let includes: [PathBuf; 0] = [];
+        let defines: [(String, String); 0] = [];
+        let (comp, _) = compile(
+            None,
+            &Path::new(""),
+            "
+        #version 450
+        #extension GL_EXT_buffer_reference : require
+        layout(buffer_reference, std430) buffer Node {
+            Node next;
+            uint value;
+        };
+        layout(push_constant) uniform PushConstants {
+            Node head;
+        };
+        void main() {}
+        ",
+            ShaderKind::Vertex,
+            &includes,
+            &defines,
+            Some(EnvVersion::Vulkan1_2),
+            None,
+        )
+        .unwrap();
+        let spirv = Spirv::new(comp.as_binary()).unwrap();
+        let structs =
+            structs::write_structs("", &spirv, &TypesMeta::default(), &mut HashMap::default())
+                .to_string();
+        assert!(structs.contains(":: vulkano :: buffer :: RawDeviceAddress < Node >"));
+    }
+
     #[test]
     fn test_include_resolution() {
         let root_path = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
@@ -777,4 +929,93 @@ mod tests {
         }
         panic!("Could not find entrypoint");
     }
+
+    #[test]
+    fn inline_uniform_blocks_marked() {
+        let includes: [PathBuf; 0] = [];
+        let defines: [(String, String); 0] = [];
+        let (comp, _) = compile(
+            None,
+            &Path::new(""),
+            "
+        #version 450
+
+        layout(set = 0, binding = 0) uniform Inline {
+            vec4 color;
+            float scale;
+        } inline_block;
+
+        layout(set = 0, binding = 1) uniform Uniform {
+            vec4 color;
+        } ubo;
+
+        layout(set = 0, binding = 2) buffer Buffer {
+            vec4 data;
+        } bo;
+
+        void main() {
+            bo.data = inline_block.color * inline_block.scale + ubo.color;
+        }
+        ",
+            ShaderKind::Compute,
+            &includes,
+            &defines,
+            None,
+            None,
+        )
+        .unwrap();
+        let spirv = Spirv::new(comp.as_binary()).unwrap();
+
+        let (_, _, mut info) = reflect::entry_points(&spirv)
+            .next()
+            .expect("Could not find entrypoint");
+        mark_inline_uniform_blocks("", &spirv, &mut info.descriptor_requirements, &[(0, 0)]);
+
+        let inline_reqs = &info.descriptor_requirements[&(0, 0)];
+        assert_eq!(
+            inline_reqs.descriptor_types,
+            [DescriptorType::InlineUniformBlock]
+        );
+        assert_eq!(inline_reqs.descriptor_count, Some(20));
+
+        let uniform_reqs = &info.descriptor_requirements[&(0, 1)];
+        assert!(uniform_reqs
+            .descriptor_types
+            .contains(&DescriptorType::UniformBuffer));
+        assert_eq!(uniform_reqs.descriptor_count, Some(1));
+    }
+
+    #[test]
+    #[should_panic]
+    fn inline_uniform_blocks_not_uniform_block() {
+        let includes: [PathBuf; 0] = [];
+        let defines: [(String, String); 0] = [];
+        let (comp, _) = compile(
+            None,
+            &Path::new(""),
+            "
+        #version 450
+
+        layout(set = 0, binding = 0) buffer Buffer {
+            vec4 data;
+        } bo;
+
+        void main() {
+            bo.data = vec4(1.0);
+        }
+        ",
+            ShaderKind::Compute,
+            &includes,
+            &defines,
+            None,
+            None,
+        )
+        .unwrap();
+        let spirv = Spirv::new(comp.as_binary()).unwrap();
+
+        let (_, _, mut info) = reflect::entry_points(&spirv)
+            .next()
+            .expect("Could not find entrypoint");
+        mark_inline_uniform_blocks("", &spirv, &mut info.descriptor_requirements, &[(0, 0)]);
+    }
 }
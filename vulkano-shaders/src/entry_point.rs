@@ -36,6 +36,7 @@ pub(super) fn write_entry_point(
         write_specialization_constant_requirements(&info.specialization_constant_requirements);
     let input_interface = write_interface(&info.input_interface);
     let output_interface = write_interface(&info.output_interface);
+    let transform_feedback = info.transform_feedback;
 
     quote! {
         (
@@ -48,6 +49,7 @@ pub(super) fn write_entry_point(
                 specialization_constant_requirements: #specialization_constant_requirements.into_iter().collect(),
                 input_interface: #input_interface,
                 output_interface: #output_interface,
+                transform_feedback: #transform_feedback,
             },
         ),
     }
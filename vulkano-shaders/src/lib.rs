@@ -164,6 +164,12 @@
 //! The generated code must be supported by the device at runtime. If not, then an error will be
 //! returned when calling `Shader::load`.
 //!
+//! ## Debug info
+//!
+//! Debug info is generated for shaders that enable the `GL_EXT_debug_printf` extension, so that
+//! the validation layers can report where `debugPrintfEXT` was called. To generate debug info for
+//! all shaders, enable the `shaderc-debug` feature of this crate.
+//!
 //! ## `types_meta: { use a::b; #[derive(Clone, Default, PartialEq ...)] impl Eq }`
 //!
 //! Extends implementations of Rust structs that represent Shader structs.
@@ -200,6 +206,18 @@
 //! interface and bytecode. See [`src/descriptor_sets.rs`][descriptor_sets]
 //! for the exact logic.
 //!
+//! ## `inline_uniform_blocks: [(set, binding), ...]`
+//!
+//! Marks uniform blocks that are provided to the shader through inline uniform block
+//! descriptors instead of uniform buffers. For each listed binding, the generated descriptor
+//! requirements only accept `DescriptorType::InlineUniformBlock`, and their `descriptor_count` is
+//! the size of the block in bytes. Descriptor set layouts that are created from the requirements
+//! then have inline uniform block bindings of the right size, which can be written with
+//! `WriteDescriptorSet::inline_uniform_block`.
+//!
+//! The listed bindings must be non-arrayed uniform blocks, and the `inline_uniform_block` feature
+//! must be enabled on the device.
+//!
 //! ## `dump: true`
 //!
 //! The crate fails to compile but prints the generated rust code to stdout.
@@ -238,7 +256,8 @@ use std::{
 };
 use syn::{
     parse::{Parse, ParseStream, Result},
-    Ident, ItemUse, LitBool, LitStr, Meta, MetaList, NestedMeta, Path as SynPath, TypeImplTrait,
+    Ident, ItemUse, LitBool, LitInt, LitStr, Meta, MetaList, NestedMeta, Path as SynPath,
+    TypeImplTrait,
 };
 
 mod codegen;
@@ -360,6 +379,7 @@ impl RegisteredType {
 struct MacroInput {
     dump: bool,
     include_directories: Vec<String>,
+    inline_uniform_blocks: Vec<(u32, u32)>,
     macro_defines: Vec<(String, String)>,
     shared_constants: bool,
     shaders: HashMap<String, (ShaderKind, SourceKind)>,
@@ -373,6 +393,7 @@ impl Parse for MacroInput {
         let mut dump = None;
         let mut exact_entrypoint_interface = None;
         let mut include_directories = Vec::new();
+        let mut inline_uniform_blocks = Vec::new();
         let mut macro_defines = Vec::new();
         let mut shared_constants = None;
         let mut shaders = HashMap::default();
@@ -590,6 +611,24 @@ impl Parse for MacroInput {
                         }
                     }
                 }
+                "inline_uniform_blocks" => {
+                    let array_input;
+                    bracketed!(array_input in input);
+
+                    while !array_input.is_empty() {
+                        let tuple_input;
+                        parenthesized!(tuple_input in array_input);
+
+                        let set: LitInt = tuple_input.parse()?;
+                        tuple_input.parse::<Token![,]>()?;
+                        let binding: LitInt = tuple_input.parse()?;
+                        inline_uniform_blocks.push((set.base10_parse()?, binding.base10_parse()?));
+
+                        if !array_input.is_empty() {
+                            array_input.parse::<Token![,]>()?;
+                        }
+                    }
+                }
                 "spirv_version" => {
                     let version: LitStr = input.parse()?;
                     spirv_version = Some(match version.value().as_ref() {
@@ -773,6 +812,7 @@ impl Parse for MacroInput {
         Ok(Self {
             dump: dump.unwrap_or(false),
             include_directories,
+            inline_uniform_blocks,
             macro_defines,
             shared_constants: shared_constants.unwrap_or(false),
             shaders: shaders
@@ -829,6 +869,7 @@ pub fn shader(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
                 &input.types_meta,
                 empty(),
                 input.shared_constants,
+                &input.inline_uniform_blocks,
                 &mut types_registry,
             )
             .unwrap()
@@ -891,6 +932,7 @@ pub fn shader(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
                 &input.types_meta,
                 input_paths,
                 input.shared_constants,
+                &input.inline_uniform_blocks,
                 &mut types_registry,
             )
             .unwrap()
@@ -13,7 +13,7 @@ use heck::ToUpperCamelCase;
 use proc_macro2::{Span, TokenStream};
 use std::{borrow::Cow, mem};
 use syn::{Ident, LitStr};
-use vulkano::shader::spirv::{Decoration, Id, Instruction, Spirv};
+use vulkano::shader::spirv::{Decoration, Id, Instruction, Spirv, StorageClass};
 
 /// Translates all the structs that are contained in the SPIR-V document as Rust structs.
 pub(super) fn write_structs<'a>(
@@ -640,6 +640,20 @@ pub(super) fn type_from_id(
                 element_align,
             )
         }
+        &Instruction::TypePointer {
+            storage_class: StorageClass::PhysicalStorageBuffer,
+            ty,
+            ..
+        } => {
+            let (pointee_type, pointee_type_string) = pointee_type_from_id(shader, spirv, ty);
+
+            (
+                quote! { ::vulkano::buffer::RawDeviceAddress<#pointee_type> },
+                Cow::from(format!("RawDeviceAddress<{}>", pointee_type_string)),
+                Some(mem::size_of::<u64>()),
+                mem::align_of::<u64>(),
+            )
+        }
         Instruction::TypeStruct { member_types, .. } => {
             // TODO: take the Offset member decorate into account?
             let size = if !has_defined_layout(spirv, type_id) {
@@ -697,6 +711,46 @@ pub(super) fn type_from_id(
     }
 }
 
+/// Returns the type name to use as the type parameter of a `RawDeviceAddress`.
+///
+/// Structs are only referred to by name, since a `buffer_reference` type can contain pointers to
+/// itself.
+fn pointee_type_from_id(
+    shader: &str,
+    spirv: &Spirv,
+    type_id: Id,
+) -> (TokenStream, Cow<'static, str>) {
+    let id_info = spirv.id(type_id);
+
+    match id_info.instruction() {
+        Instruction::TypeStruct { .. } => {
+            let name_string = id_info
+                .iter_name()
+                .find_map(|instruction| match instruction {
+                    Instruction::Name { name, .. } => Some(Cow::from(name.clone())),
+                    _ => None,
+                })
+                .unwrap_or_else(|| Cow::from("__unnamed"));
+            let name = format_ident!("{}", name_string);
+
+            (quote! { #name }, name_string)
+        }
+        &Instruction::TypeRuntimeArray { element_type, .. } => {
+            let (element_type, element_type_string) =
+                pointee_type_from_id(shader, spirv, element_type);
+
+            (
+                quote! { [#element_type] },
+                Cow::from(format!("[{}]", element_type_string)),
+            )
+        }
+        _ => {
+            let (ty, signature, _, _) = type_from_id(shader, spirv, type_id);
+            (ty, signature)
+        }
+    }
+}
+
 /// Writes the `SpecializationConstants` struct that contains the specialization constants and
 /// implements the `Default` and the `vulkano::shader::SpecializationConstants` traits.
 pub(super) fn write_specialization_constants<'a>(
@@ -7,23 +7,45 @@
 // notice may not be copied, modified, or distributed except
 // according to those terms.
 
-use std::sync::Arc;
+use std::{
+    env,
+    error::Error,
+    fmt::{Display, Error as FmtError, Formatter},
+    sync::Arc,
+};
 use vulkano::{
     device::{
         physical::{PhysicalDevice, PhysicalDeviceType},
-        Device, DeviceCreateInfo, DeviceExtensions, Features, Queue, QueueCreateInfo,
+        Device, DeviceCreateInfo, DeviceCreationError, DeviceExtensions, Features, Queue,
+        QueueCreateInfo,
     },
     instance::{
-        debug::{DebugUtilsMessenger, DebugUtilsMessengerCreateInfo},
-        Instance, InstanceCreateInfo, InstanceExtensions,
+        debug::{
+            DebugUtilsMessenger, DebugUtilsMessengerCreateInfo, DebugUtilsMessengerCreationError,
+        },
+        Instance, InstanceCreateInfo, InstanceCreationError, InstanceExtensions,
     },
-    Version, VulkanLibrary,
+    LoadingError, Version, VulkanError, VulkanLibrary,
 };
 
+/// The environment variable that can be set to pin the physical device that [`VulkanoContext`]
+/// uses, if [`VulkanoConfig::allow_device_override`] is `true`.
+///
+/// The value is either the index of the physical device in enumeration order, or a part of its
+/// name, which is matched case-insensitively. For example `VULKANO_DEVICE=1` or
+/// `VULKANO_DEVICE=radeon`.
+pub const DEVICE_OVERRIDE_VAR: &str = "VULKANO_DEVICE";
+
 /// A configuration struct to pass various creation options to create [`VulkanoContext`].
 ///
 /// Instance extensions that are required for surface creation will be appended to the config when
 /// creating [`VulkanoContext`].
+///
+/// Physical devices that don't support the required extensions and features, or that don't have a
+/// graphics queue, are never selected. Optional extensions and features are enabled if the
+/// selected device supports them and everything that they depend on, and the extensions and
+/// features that the enabled extensions and features depend on are enabled automatically. See
+/// [`VulkanoContext::negotiation_report`] for what ended up enabled.
 pub struct VulkanoConfig {
     pub instance_create_info: InstanceCreateInfo,
 
@@ -31,16 +53,29 @@ pub struct VulkanoConfig {
     /// for printing debug information at runtime.
     pub debug_create_info: Option<DebugUtilsMessengerCreateInfo>,
 
-    /// Pass filter function for your physical device selection. See default for example.
+    /// Pass filter function for your physical device selection. Devices that don't meet the
+    /// requirements of the config are filtered out before this is called.
     pub device_filter_fn: Arc<dyn Fn(&PhysicalDevice) -> bool>,
 
     /// Pass priority order function for your physical device selection. See default for example.
     pub device_priority_fn: Arc<dyn Fn(&PhysicalDevice) -> u32>,
 
+    /// Device extensions that the device must support.
     pub device_extensions: DeviceExtensions,
 
+    /// Device extensions that are enabled if the device supports them.
+    pub optional_device_extensions: DeviceExtensions,
+
+    /// Device features that the device must support.
     pub device_features: Features,
 
+    /// Device features that are enabled if the device supports them.
+    pub optional_device_features: Features,
+
+    /// Whether the [`DEVICE_OVERRIDE_VAR`] environment variable is allowed to pin the physical
+    /// device, instead of selecting it with `device_priority_fn`.
+    pub allow_device_override: bool,
+
     /// Print your selected device name at start.
     pub print_device_name: bool,
 }
@@ -48,10 +83,6 @@ pub struct VulkanoConfig {
 impl Default for VulkanoConfig {
     #[inline]
     fn default() -> Self {
-        let device_extensions = DeviceExtensions {
-            khr_swapchain: true,
-            ..DeviceExtensions::empty()
-        };
         VulkanoConfig {
             instance_create_info: InstanceCreateInfo {
                 application_version: Version::V1_3,
@@ -65,9 +96,7 @@ impl Default for VulkanoConfig {
                 ..Default::default()
             },
             debug_create_info: None,
-            device_filter_fn: Arc::new(move |p| {
-                p.supported_extensions().contains(&device_extensions)
-            }),
+            device_filter_fn: Arc::new(|_| true),
             device_priority_fn: Arc::new(|p| match p.properties().device_type {
                 PhysicalDeviceType::DiscreteGpu => 1,
                 PhysicalDeviceType::IntegratedGpu => 2,
@@ -77,12 +106,41 @@ impl Default for VulkanoConfig {
                 _ => 6,
             }),
             print_device_name: false,
-            device_extensions,
+            device_extensions: DeviceExtensions {
+                khr_swapchain: true,
+                ..DeviceExtensions::empty()
+            },
+            optional_device_extensions: DeviceExtensions::empty(),
             device_features: Features::empty(),
+            optional_device_features: Features::empty(),
+            allow_device_override: true,
         }
     }
 }
 
+/// What was enabled on the device of a [`VulkanoContext`], compared to what was requested in
+/// [`VulkanoConfig`].
+#[derive(Clone, Debug)]
+pub struct NegotiationReport {
+    /// All extensions that were enabled on the device.
+    pub enabled_extensions: DeviceExtensions,
+
+    /// All features that were enabled on the device.
+    pub enabled_features: Features,
+
+    /// Extensions that were enabled because other enabled extensions or features depend on them,
+    /// without being requested in the config.
+    pub dependency_extensions: DeviceExtensions,
+
+    /// Optional extensions that were not enabled because the device doesn't support them, or
+    /// doesn't support an extension that they depend on.
+    pub unavailable_extensions: DeviceExtensions,
+
+    /// Optional features that were not enabled because the device doesn't support them, or
+    /// doesn't support a feature that they depend on.
+    pub unavailable_features: Features,
+}
+
 /// A utility struct to create, access and hold alive Vulkano device, instance and queues.
 ///
 /// Vulkano context is used in the creation of your graphics or compute pipelines, images and
@@ -95,7 +153,7 @@ impl Default for VulkanoConfig {
 /// use vulkano_util::context::{VulkanoConfig, VulkanoContext};
 ///
 /// fn test() {
-///     let context = VulkanoContext::new(VulkanoConfig::default());
+///     let context = VulkanoContext::new(VulkanoConfig::default()).unwrap();
 ///     // Then create event loop, windows, pipelines, etc.
 /// }
 /// ```
@@ -105,58 +163,96 @@ pub struct VulkanoContext {
     device: Arc<Device>,
     graphics_queue: Arc<Queue>,
     compute_queue: Arc<Queue>,
+    transfer_queue: Arc<Queue>,
+    negotiation_report: NegotiationReport,
 }
 
 impl Default for VulkanoContext {
+    /// Creates a [`VulkanoContext`] with the default config.
+    ///
+    /// # Panics
+    ///
+    /// - Panics if creating the context fails.
     #[inline]
     fn default() -> Self {
-        VulkanoContext::new(VulkanoConfig::default())
+        VulkanoContext::new(VulkanoConfig::default()).expect("Failed to create Vulkano context")
     }
 }
 
 impl VulkanoContext {
     /// Creates a new [`VulkanoContext`].
-    ///
-    /// # Panics
-    ///
-    /// - Panics where the underlying Vulkano struct creations fail
-    pub fn new(mut config: VulkanoConfig) -> Self {
-        let library = match VulkanLibrary::new() {
-            Ok(x) => x,
-            #[cfg(target_os = "macos")]
-            Err(vulkano::library::LoadingError::LibraryLoadFailure(err)) => {
-                panic!("Failed to load Vulkan library: {}. Did you install vulkanSDK from https://vulkan.lunarg.com/sdk/home ?", err);
-            }
-            Err(err) => {
-                panic!("Failed to load Vulkan library: {}.", err);
-            }
-        };
+    pub fn new(mut config: VulkanoConfig) -> Result<Self, VulkanoContextError> {
+        let library = VulkanLibrary::new()?;
 
         // Append required extensions
         config.instance_create_info.enabled_extensions = vulkano_win::required_extensions(&library)
             .union(&config.instance_create_info.enabled_extensions);
 
         // Create instance
-        let instance =
-            Instance::new(library, config.instance_create_info).expect("Failed to create instance");
+        let instance = Instance::new(library, config.instance_create_info)?;
 
         // Create debug callback
-        let _debug_utils_messenger =
-            config
-                .debug_create_info
-                .take()
-                .map(|dbg_create_info| unsafe {
-                    DebugUtilsMessenger::new(instance.clone(), dbg_create_info)
-                        .expect("Failed to create debug callback")
-                });
+        let _debug_utils_messenger = config
+            .debug_create_info
+            .take()
+            .map(|dbg_create_info| unsafe {
+                DebugUtilsMessenger::new(instance.clone(), dbg_create_info)
+            })
+            .transpose()?;
 
         // Get prioritized device
+        let device_override = if config.allow_device_override {
+            env::var(DEVICE_OVERRIDE_VAR).ok()
+        } else {
+            None
+        };
+        let mut override_matched = false;
+        let mut rejected_devices = Vec::new();
         let physical_device = instance
-            .enumerate_physical_devices()
-            .expect("Failed to enumerate physical devices")
-            .filter(|p| (config.device_filter_fn)(p))
-            .min_by_key(|p| (config.device_priority_fn)(p))
-            .expect("Failed to create physical device");
+            .enumerate_physical_devices()?
+            .enumerate()
+            .filter(|(index, p)| match &device_override {
+                Some(value) => {
+                    let matches = match value.parse::<usize>() {
+                        Ok(override_index) => *index == override_index,
+                        Err(_) => p
+                            .properties()
+                            .device_name
+                            .to_lowercase()
+                            .contains(&value.to_lowercase()),
+                    };
+                    override_matched |= matches;
+                    matches
+                }
+                None => true,
+            })
+            .map(|(_, p)| p)
+            .filter(|p| {
+                match Self::check_physical_device(
+                    p,
+                    &config.device_extensions,
+                    &config.device_features,
+                    &config.device_filter_fn,
+                ) {
+                    Ok(()) => true,
+                    Err(reason) => {
+                        rejected_devices.push(RejectedDevice {
+                            device_name: p.properties().device_name.clone(),
+                            reason,
+                        });
+                        false
+                    }
+                }
+            })
+            .min_by_key(|p| (config.device_priority_fn)(p));
+
+        let physical_device = match (physical_device, device_override) {
+            (Some(physical_device), _) => physical_device,
+            (None, Some(value)) if !override_matched => {
+                return Err(VulkanoContextError::DeviceOverrideNotFound { value })
+            }
+            (None, _) => return Err(VulkanoContextError::NoSuitableDevice { rejected_devices }),
+        };
         // Print used device
         if config.print_device_name {
             println!(
@@ -166,29 +262,91 @@ impl VulkanoContext {
             );
         }
 
-        // Create device
-        let (device, graphics_queue, compute_queue) = Self::create_device(
-            physical_device,
-            config.device_extensions,
-            config.device_features,
+        // Negotiate extensions and features. Optional ones are dropped if the device doesn't
+        // support them, or doesn't support something that they depend on.
+        let usable_extensions = physical_device.usable_extensions();
+        let usable_features = physical_device.usable_features();
+        let requested_extensions = config.device_extensions.union(
+            &config
+                .optional_device_extensions
+                .intersection(&usable_extensions),
+        );
+        let requested_features = config.device_features.union(
+            &config
+                .optional_device_features
+                .intersection(&usable_features),
         );
+        let enabled_extensions = physical_device
+            .extensions_with_dependencies(&requested_extensions, &requested_features);
+        let enabled_features =
+            physical_device.features_with_dependencies(&requested_features, &requested_extensions);
+        let negotiation_report = NegotiationReport {
+            enabled_extensions,
+            enabled_features,
+            dependency_extensions: enabled_extensions.difference(&requested_extensions),
+            unavailable_extensions: config
+                .optional_device_extensions
+                .difference(&usable_extensions),
+            unavailable_features: config.optional_device_features.difference(&usable_features),
+        };
+
+        // Create device
+        let (device, graphics_queue, compute_queue, transfer_queue) =
+            Self::create_device(physical_device, enabled_extensions, enabled_features)?;
 
-        Self {
+        Ok(Self {
             instance,
             _debug_utils_messenger,
             device,
             graphics_queue,
             compute_queue,
+            transfer_queue,
+            negotiation_report,
+        })
+    }
+
+    /// Checks that a physical device meets the requirements of the config.
+    fn check_physical_device(
+        physical_device: &PhysicalDevice,
+        device_extensions: &DeviceExtensions,
+        device_features: &Features,
+        device_filter_fn: &Arc<dyn Fn(&PhysicalDevice) -> bool>,
+    ) -> Result<(), RejectionReason> {
+        let missing_extensions = device_extensions.difference(&physical_device.usable_extensions());
+
+        if missing_extensions != DeviceExtensions::empty() {
+            return Err(RejectionReason::MissingExtensions(missing_extensions));
         }
+
+        let missing_features = device_features.difference(&physical_device.usable_features());
+
+        if missing_features != Features::empty() {
+            return Err(RejectionReason::MissingFeatures(missing_features));
+        }
+
+        if !physical_device
+            .queue_family_properties()
+            .iter()
+            .any(|q| q.queue_flags.graphics)
+        {
+            return Err(RejectionReason::NoGraphicsQueue);
+        }
+
+        if !device_filter_fn(physical_device) {
+            return Err(RejectionReason::FilteredOut);
+        }
+
+        Ok(())
     }
 
     /// Creates vulkano device with required queue families and required extensions. Creates a
-    /// separate queue for compute if possible. If not, same queue as graphics is used.
+    /// separate queue for compute and for transfers if possible. If not, same queue as graphics is
+    /// used.
     fn create_device(
         physical_device: Arc<PhysicalDevice>,
         device_extensions: DeviceExtensions,
         features: Features,
-    ) -> (Arc<Device>, Arc<Queue>, Arc<Queue>) {
+    ) -> Result<(Arc<Device>, Arc<Queue>, Arc<Queue>, Arc<Queue>), DeviceCreationError> {
         let queue_family_graphics = physical_device
             .queue_family_properties()
             .iter()
@@ -196,7 +354,7 @@ impl VulkanoContext {
             .map(|(i, q)| (i as u32, q))
             .find(|(_i, q)| q.queue_flags.graphics)
             .map(|(i, _)| i)
-            .expect("Could not find a queue that supports graphics");
+            .unwrap();
         // Try finding a separate queue for compute
         let queue_family_compute = physical_device
             .queue_family_properties()
@@ -205,26 +363,40 @@ impl VulkanoContext {
             .map(|(i, q)| (i as u32, q))
             .find(|(i, q)| q.queue_flags.compute && *i != queue_family_graphics)
             .map(|(i, _)| i);
-        let is_separate_compute_queue = queue_family_compute.is_some();
-
-        let queue_create_infos = if let Some(queue_family_compute) = queue_family_compute {
-            vec![
-                QueueCreateInfo {
-                    queue_family_index: queue_family_graphics,
-                    ..Default::default()
-                },
-                QueueCreateInfo {
-                    queue_family_index: queue_family_compute,
-                    ..Default::default()
-                },
-            ]
-        } else {
-            vec![QueueCreateInfo {
-                queue_family_index: queue_family_graphics,
-                ..Default::default()
-            }]
+        // Try finding a separate queue for transfers, preferring a family that is dedicated to
+        // transfers, which is usually backed by a DMA engine
+        let queue_family_transfer = {
+            let candidates = || {
+                physical_device
+                    .queue_family_properties()
+                    .iter()
+                    .enumerate()
+                    .map(|(i, q)| (i as u32, q))
+                    .filter(|(i, q)| {
+                        q.queue_flags.transfer
+                            && *i != queue_family_graphics
+                            && Some(*i) != queue_family_compute
+                    })
+            };
+            candidates()
+                .find(|(_i, q)| !q.queue_flags.graphics && !q.queue_flags.compute)
+                .or_else(|| candidates().next())
+                .map(|(i, _)| i)
         };
 
+        let queue_create_infos = [
+            Some(queue_family_graphics),
+            queue_family_compute,
+            queue_family_transfer,
+        ]
+        .into_iter()
+        .flatten()
+        .map(|queue_family_index| QueueCreateInfo {
+            queue_family_index,
+            ..Default::default()
+        })
+        .collect();
+
         let (device, mut queues) = {
             Device::new(
                 physical_device,
@@ -234,16 +406,20 @@ impl VulkanoContext {
                     queue_create_infos,
                     ..Default::default()
                 },
-            )
-            .expect("Failed to create device")
+            )?
         };
         let gfx_queue = queues.next().unwrap();
-        let compute_queue = if is_separate_compute_queue {
+        let compute_queue = if queue_family_compute.is_some() {
             queues.next().unwrap()
         } else {
             gfx_queue.clone()
         };
-        (device, gfx_queue, compute_queue)
+        let transfer_queue = if queue_family_transfer.is_some() {
+            queues.next().unwrap()
+        } else {
+            gfx_queue.clone()
+        };
+        Ok((device, gfx_queue, compute_queue, transfer_queue))
     }
 
     /// Returns the name of the device.
@@ -267,6 +443,12 @@ impl VulkanoContext {
             .max_memory_allocation_count as u32
     }
 
+    /// Returns what was enabled on the device, compared to what was requested in the config.
+    #[inline]
+    pub fn negotiation_report(&self) -> &NegotiationReport {
+        &self.negotiation_report
+    }
+
     /// Returns the instance.
     #[inline]
     pub fn instance(&self) -> &Arc<Instance> {
@@ -292,4 +474,171 @@ impl VulkanoContext {
     pub fn compute_queue(&self) -> &Arc<Queue> {
         &self.compute_queue
     }
+
+    /// Returns the transfer queue.
+    ///
+    /// Depending on your device, this might be the same as graphics queue. See
+    /// [`UploadScheduler`](crate::upload::UploadScheduler) for batching uploads onto this queue.
+    #[inline]
+    pub fn transfer_queue(&self) -> &Arc<Queue> {
+        &self.transfer_queue
+    }
+}
+
+/// Error that can happen when creating a [`VulkanoContext`].
+#[derive(Debug)]
+pub enum VulkanoContextError {
+    /// Loading the Vulkan library failed.
+    LoadingError(LoadingError),
+
+    /// Creating the instance failed.
+    InstanceCreationError(InstanceCreationError),
+
+    /// Creating the debug callback failed.
+    DebugUtilsMessengerCreationError(DebugUtilsMessengerCreationError),
+
+    /// Enumerating the physical devices failed.
+    VulkanError(VulkanError),
+
+    /// No physical device meets the requirements of the config.
+    NoSuitableDevice {
+        /// The physical devices that were considered, and why they were rejected.
+        rejected_devices: Vec<RejectedDevice>,
+    },
+
+    /// The [`DEVICE_OVERRIDE_VAR`] environment variable is set, but no physical device matches
+    /// its value.
+    DeviceOverrideNotFound { value: String },
+
+    /// Creating the device failed.
+    DeviceCreationError(DeviceCreationError),
+}
+
+impl Error for VulkanoContextError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            Self::LoadingError(err) => Some(err),
+            Self::InstanceCreationError(err) => Some(err),
+            Self::DebugUtilsMessengerCreationError(err) => Some(err),
+            Self::VulkanError(err) => Some(err),
+            Self::DeviceCreationError(err) => Some(err),
+            _ => None,
+        }
+    }
+}
+
+impl Display for VulkanoContextError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result<(), FmtError> {
+        match self {
+            #[cfg(target_os = "macos")]
+            Self::LoadingError(LoadingError::LibraryLoadFailure(_)) => write!(
+                f,
+                "failed to load the Vulkan library; did you install vulkanSDK from \
+                https://vulkan.lunarg.com/sdk/home ?",
+            ),
+            Self::LoadingError(_) => write!(f, "failed to load the Vulkan library"),
+            Self::InstanceCreationError(_) => write!(f, "failed to create the instance"),
+            Self::DebugUtilsMessengerCreationError(_) => {
+                write!(f, "failed to create the debug callback")
+            }
+            Self::VulkanError(_) => write!(f, "failed to enumerate the physical devices"),
+            Self::NoSuitableDevice { rejected_devices } => {
+                write!(f, "no physical device meets the requirements")?;
+
+                for rejected_device in rejected_devices {
+                    write!(
+                        f,
+                        "; {}: {}",
+                        rejected_device.device_name, rejected_device.reason,
+                    )?;
+                }
+
+                Ok(())
+            }
+            Self::DeviceOverrideNotFound { value } => write!(
+                f,
+                "no physical device matches the {} environment variable value `{}`",
+                DEVICE_OVERRIDE_VAR, value,
+            ),
+            Self::DeviceCreationError(_) => write!(f, "failed to create the device"),
+        }
+    }
+}
+
+impl From<LoadingError> for VulkanoContextError {
+    #[inline]
+    fn from(err: LoadingError) -> Self {
+        Self::LoadingError(err)
+    }
+}
+
+impl From<InstanceCreationError> for VulkanoContextError {
+    #[inline]
+    fn from(err: InstanceCreationError) -> Self {
+        Self::InstanceCreationError(err)
+    }
+}
+
+impl From<DebugUtilsMessengerCreationError> for VulkanoContextError {
+    #[inline]
+    fn from(err: DebugUtilsMessengerCreationError) -> Self {
+        Self::DebugUtilsMessengerCreationError(err)
+    }
+}
+
+impl From<VulkanError> for VulkanoContextError {
+    #[inline]
+    fn from(err: VulkanError) -> Self {
+        Self::VulkanError(err)
+    }
+}
+
+impl From<DeviceCreationError> for VulkanoContextError {
+    #[inline]
+    fn from(err: DeviceCreationError) -> Self {
+        Self::DeviceCreationError(err)
+    }
+}
+
+/// A physical device that was not selected by [`VulkanoContext`].
+#[derive(Clone, Debug)]
+pub struct RejectedDevice {
+    /// The name of the physical device.
+    pub device_name: String,
+
+    /// Why the physical device was rejected.
+    pub reason: RejectionReason,
+}
+
+/// Why a physical device was not selected by [`VulkanoContext`].
+#[derive(Clone, Debug)]
+pub enum RejectionReason {
+    /// The physical device doesn't support these required extensions, or an extension that they
+    /// depend on.
+    MissingExtensions(DeviceExtensions),
+
+    /// The physical device doesn't support these required features, or a feature that they depend
+    /// on.
+    MissingFeatures(Features),
+
+    /// The physical device has no queue family that supports graphics.
+    NoGraphicsQueue,
+
+    /// The physical device was rejected by [`VulkanoConfig::device_filter_fn`].
+    FilteredOut,
+}
+
+impl Display for RejectionReason {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result<(), FmtError> {
+        match self {
+            Self::MissingExtensions(extensions) => {
+                write!(f, "missing required extensions {:?}", extensions)
+            }
+            Self::MissingFeatures(features) => {
+                write!(f, "missing required features {:?}", features)
+            }
+            Self::NoGraphicsQueue => write!(f, "no queue family supports graphics"),
+            Self::FilteredOut => write!(f, "rejected by the device filter"),
+        }
+    }
 }
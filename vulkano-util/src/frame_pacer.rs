@@ -0,0 +1,364 @@
+// Copyright (c) 2022 The vulkano developers
+// Licensed under the Apache License, Version 2.0
+// <LICENSE-APACHE or
+// https://www.apache.org/licenses/LICENSE-2.0> or the MIT
+// license <LICENSE-MIT or https://opensource.org/licenses/MIT>,
+// at your option. All files in the project carrying such
+// notice may not be copied, modified, or distributed except
+// according to those terms.
+
+use std::{
+    num::NonZeroU64,
+    sync::Arc,
+    time::{Duration, Instant},
+};
+use vulkano::{
+    command_buffer::{AutoCommandBufferBuilder, CommandBufferUsage, PrimaryAutoCommandBuffer},
+    device::Queue,
+    query::{QueryPool, QueryPoolCreateInfo, QueryResultFlags, QueryType},
+    swapchain::{self, PresentFuture, Swapchain, SwapchainAbstract, SwapchainPresentInfo},
+    sync::{FenceSignalFuture, FlushError, GpuFuture, PipelineStage},
+};
+
+type FrameFuture = Arc<FenceSignalFuture<PresentFuture<Box<dyn GpuFuture>>>>;
+
+/// Configuration of a [`FramePacer`].
+#[derive(Debug, Clone, Copy)]
+pub struct FramePacerConfig {
+    /// The maximum number of frames that the CPU may record ahead of the GPU. Each frame in flight
+    /// has its own fence, which is waited on before the frame is reused.
+    pub frames_in_flight: usize,
+    /// If set, limits the latency between presenting a frame and displaying it to this many
+    /// presents, by waiting with [`wait_for_present`](vulkano::swapchain::wait_for_present)
+    /// before starting a new frame.
+    ///
+    /// Only has an effect if the `present_id` and `present_wait` features are enabled on the
+    /// device.
+    pub target_latency: Option<u64>,
+    /// Whether to measure the GPU time of each frame with timestamp queries.
+    ///
+    /// Only has an effect if the queue family supports timestamps.
+    pub gpu_timestamps: bool,
+}
+
+impl Default for FramePacerConfig {
+    #[inline]
+    fn default() -> Self {
+        FramePacerConfig {
+            frames_in_flight: 2,
+            target_latency: None,
+            gpu_timestamps: true,
+        }
+    }
+}
+
+/// Timing statistics of the last completed frame.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct FrameStats {
+    /// The number of frames that have been presented.
+    pub frame_count: u64,
+    /// The time between the starts of the two last frames.
+    pub frame_interval: Duration,
+    /// The CPU time spent between the start of the last frame and its presentation.
+    pub cpu_time: Duration,
+    /// The GPU time spent executing the last completed frame, if timestamps are available.
+    pub gpu_time: Option<Duration>,
+}
+
+struct FrameSlot {
+    future: Option<FrameFuture>,
+    timestamps_written: bool,
+}
+
+/// Limits the number of frames in flight and measures frame times.
+///
+/// Each frame goes through [`FramePacer::wait_for_frame`] before acquiring a swapchain image,
+/// [`FramePacer::begin_frame`] after acquiring it, and [`FramePacer::present`] at the end.
+/// [`VulkanoWindowRenderer`](crate::renderer::VulkanoWindowRenderer) does this automatically.
+pub struct FramePacer {
+    queue: Arc<Queue>,
+    config: FramePacerConfig,
+    frames: Vec<FrameSlot>,
+    current_frame: usize,
+    previous_frame_end: Option<FrameFuture>,
+    query_pool: Option<Arc<QueryPool>>,
+    timestamp_mask: u64,
+    present_swapchain: Option<Arc<dyn SwapchainAbstract>>,
+    last_present_id: u64,
+    frame_start: Option<Instant>,
+    stats: FrameStats,
+}
+
+impl FramePacer {
+    /// Creates a new [`FramePacer`] that presents on `queue`.
+    pub fn new(queue: Arc<Queue>, config: FramePacerConfig) -> FramePacer {
+        let frames_in_flight = config.frames_in_flight.max(1);
+        let timestamp_valid_bits = queue.device().physical_device().queue_family_properties()
+            [queue.queue_family_index() as usize]
+            .timestamp_valid_bits;
+
+        let query_pool = match timestamp_valid_bits {
+            Some(_) if config.gpu_timestamps => QueryPool::new(
+                queue.device().clone(),
+                QueryPoolCreateInfo {
+                    query_count: 2 * frames_in_flight as u32,
+                    ..QueryPoolCreateInfo::query_type(QueryType::Timestamp)
+                },
+            )
+            .ok(),
+            _ => None,
+        };
+        let timestamp_mask = match timestamp_valid_bits {
+            Some(bits) if bits < 64 => (1 << bits) - 1,
+            _ => u64::MAX,
+        };
+
+        FramePacer {
+            queue,
+            config: FramePacerConfig {
+                frames_in_flight,
+                ..config
+            },
+            frames: (0..frames_in_flight)
+                .map(|_| FrameSlot {
+                    future: None,
+                    timestamps_written: false,
+                })
+                .collect(),
+            current_frame: 0,
+            previous_frame_end: None,
+            query_pool,
+            timestamp_mask,
+            present_swapchain: None,
+            last_present_id: 0,
+            frame_start: None,
+            stats: FrameStats::default(),
+        }
+    }
+
+    /// Returns the configuration of the pacer.
+    #[inline]
+    pub fn config(&self) -> &FramePacerConfig {
+        &self.config
+    }
+
+    /// Returns the timing statistics of the last completed frame.
+    #[inline]
+    pub fn stats(&self) -> FrameStats {
+        self.stats
+    }
+
+    /// Waits until a frame can be started: the frame that last used the current frame slot must
+    /// be finished on the GPU, and if a latency target is set, the presentation that many frames
+    /// ago must have been displayed.
+    ///
+    /// Call this before acquiring the next swapchain image.
+    pub fn wait_for_frame<W>(&mut self, swapchain: &Arc<Swapchain<W>>)
+    where
+        W: Send + Sync + 'static,
+    {
+        self.wait_for_slot(self.current_frame);
+
+        if let Some(target_latency) = self.config.target_latency {
+            let features = self.queue.device().enabled_features();
+
+            if features.present_id
+                && features.present_wait
+                && self.is_present_swapchain(swapchain)
+                && self.last_present_id > target_latency
+            {
+                // Errors mean that the swapchain is out of date or the present is taking too
+                // long. In both cases, pacing is pointless for this frame.
+                let _ = swapchain::wait_for_present(
+                    swapchain.clone(),
+                    self.last_present_id - target_latency,
+                    Some(Duration::from_millis(100)),
+                );
+            }
+        }
+
+        let now = Instant::now();
+
+        if let Some(frame_start) = self.frame_start.replace(now) {
+            self.stats.frame_interval = now - frame_start;
+        }
+    }
+
+    /// Starts recording the GPU work of a frame after `acquire_future`, which is usually the future
+    /// returned when acquiring the swapchain image.
+    ///
+    /// Execute your command buffers after the returned future, then call [`FramePacer::present`].
+    pub fn begin_frame(&mut self, acquire_future: impl GpuFuture + 'static) -> Box<dyn GpuFuture> {
+        let future = match self.previous_frame_end.clone() {
+            Some(previous_frame_end) => previous_frame_end.join(acquire_future).boxed(),
+            None => acquire_future.boxed(),
+        };
+
+        let slot = self.current_frame as u32;
+        let command_buffer = self.timestamp_command_buffer(|builder, query_pool| unsafe {
+            builder
+                .reset_query_pool(query_pool.clone(), 2 * slot..2 * slot + 2)
+                .unwrap()
+                .write_timestamp(query_pool, 2 * slot, PipelineStage::TopOfPipe)
+                .unwrap();
+        });
+
+        match command_buffer {
+            Some(command_buffer) => future
+                .then_execute(self.queue.clone(), command_buffer)
+                .unwrap()
+                .boxed(),
+            None => future,
+        }
+    }
+
+    /// Presents a swapchain image after `before`, and flushes the frame.
+    ///
+    /// If the `present_id` feature is enabled, the present operation is given an id that is used
+    /// for latency targeting.
+    pub fn present<W>(
+        &mut self,
+        before: Box<dyn GpuFuture>,
+        swapchain: Arc<Swapchain<W>>,
+        image_index: u32,
+    ) -> Result<(), FlushError>
+    where
+        W: Send + Sync + 'static,
+    {
+        let slot = self.current_frame as u32;
+        let command_buffer = self.timestamp_command_buffer(|builder, query_pool| unsafe {
+            builder
+                .write_timestamp(query_pool, 2 * slot + 1, PipelineStage::BottomOfPipe)
+                .unwrap();
+        });
+        let timestamps_written = command_buffer.is_some();
+        let before = match command_buffer {
+            Some(command_buffer) => before
+                .then_execute(self.queue.clone(), command_buffer)
+                .unwrap()
+                .boxed(),
+            None => before,
+        };
+
+        if !self.is_present_swapchain(&swapchain) {
+            self.present_swapchain = Some(swapchain.clone());
+            self.last_present_id = 0;
+        }
+
+        let present_id = if self.queue.device().enabled_features().present_id {
+            self.last_present_id += 1;
+            NonZeroU64::new(self.last_present_id)
+        } else {
+            None
+        };
+
+        if let Some(frame_start) = self.frame_start {
+            self.stats.cpu_time = frame_start.elapsed();
+        }
+
+        let result = before
+            .then_swapchain_present(
+                self.queue.clone(),
+                SwapchainPresentInfo {
+                    present_id,
+                    ..SwapchainPresentInfo::swapchain_image_index(swapchain, image_index)
+                },
+            )
+            .then_signal_fence_and_flush();
+
+        match result {
+            Ok(future) => {
+                let future = Arc::new(future);
+                let frame = &mut self.frames[self.current_frame];
+                frame.future = Some(future.clone());
+                frame.timestamps_written = timestamps_written;
+
+                self.previous_frame_end = Some(future);
+                self.current_frame = (self.current_frame + 1) % self.frames.len();
+                self.stats.frame_count += 1;
+
+                Ok(())
+            }
+            Err(err) => {
+                self.previous_frame_end = None;
+                Err(err)
+            }
+        }
+    }
+
+    /// Waits until all frames in flight are finished on the GPU.
+    pub fn wait_idle(&mut self) {
+        for index in 0..self.frames.len() {
+            self.wait_for_slot(index);
+        }
+    }
+
+    fn is_present_swapchain<W>(&self, swapchain: &Arc<Swapchain<W>>) -> bool
+    where
+        W: Send + Sync + 'static,
+    {
+        self.present_swapchain
+            .as_ref()
+            .map_or(false, |present_swapchain| {
+                **present_swapchain == *(swapchain.clone() as Arc<dyn SwapchainAbstract>)
+            })
+    }
+
+    fn wait_for_slot(&mut self, index: usize) {
+        let frame = &mut self.frames[index];
+
+        if let Some(future) = frame.future.take() {
+            if let Err(err) = future.wait(None) {
+                println!("Failed to wait for frame: {:?}", err);
+                return;
+            }
+
+            if frame.timestamps_written {
+                frame.timestamps_written = false;
+                self.read_gpu_time(index as u32);
+            }
+        }
+    }
+
+    fn read_gpu_time(&mut self, slot: u32) {
+        let query_pool = match &self.query_pool {
+            Some(query_pool) => query_pool,
+            None => return,
+        };
+
+        let mut timestamps = [0u64; 2];
+
+        if let Ok(true) = query_pool
+            .queries_range(2 * slot..2 * slot + 2)
+            .unwrap()
+            .get_results(&mut timestamps, QueryResultFlags::empty())
+        {
+            let ticks = timestamps[1].wrapping_sub(timestamps[0]) & self.timestamp_mask;
+            let timestamp_period = self
+                .queue
+                .device()
+                .physical_device()
+                .properties()
+                .timestamp_period;
+            self.stats.gpu_time = Some(Duration::from_nanos(
+                (ticks as f64 * timestamp_period as f64) as u64,
+            ));
+        }
+    }
+
+    fn timestamp_command_buffer(
+        &self,
+        record: impl FnOnce(&mut AutoCommandBufferBuilder<PrimaryAutoCommandBuffer>, Arc<QueryPool>),
+    ) -> Option<PrimaryAutoCommandBuffer> {
+        let query_pool = self.query_pool.clone()?;
+        let mut builder = AutoCommandBufferBuilder::primary(
+            self.queue.device().clone(),
+            self.queue.queue_family_index(),
+            CommandBufferUsage::OneTimeSubmit,
+        )
+        .ok()?;
+        record(&mut builder, query_pool);
+
+        builder.build().ok()
+    }
+}
@@ -11,5 +11,8 @@
 #![warn(rust_2018_idioms, rust_2021_compatibility)]
 
 pub mod context;
+pub mod frame_pacer;
+pub mod render_graph;
 pub mod renderer;
+pub mod upload;
 pub mod window;
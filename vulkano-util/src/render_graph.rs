@@ -0,0 +1,1653 @@
+// Copyright (c) 2022 The vulkano developers
+// Licensed under the Apache License, Version 2.0
+// <LICENSE-APACHE or
+// https://www.apache.org/licenses/LICENSE-2.0> or the MIT
+// license <LICENSE-MIT or https://opensource.org/licenses/MIT>,
+// at your option. All files in the project carrying such
+// notice may not be copied, modified, or distributed except
+// according to those terms.
+
+//! A render graph that orders, culls and records the passes of a frame.
+//!
+//! Each frame, passes are declared on a [`FrameGraph`] along with the images and buffers they
+//! read and write. Images are either transient, in which case the graph creates them and reuses
+//! them across frames and between passes whose lifetimes don't overlap, or imported, such as a
+//! swapchain image. When the frame is executed, the graph:
+//!
+//! - culls the passes whose results are never used by an imported resource or by a pass with side
+//!   effects,
+//! - assigns pooled images to the transient images, reusing the same image for transient images
+//!   whose lifetimes don't overlap,
+//! - splits the passes in batches per queue, and submits them in order so that compute work that
+//!   doesn't depend on graphics work can run in parallel on a separate compute queue,
+//! - begins and ends rendering for passes with attachments, using dynamic rendering if it is
+//!   enabled on the device and a generated `RenderPass` otherwise.
+//!
+//! Because the passes declare how each image is used, the
+//! [`AutoCommandBufferBuilder`] can insert the pipeline barriers and layout transitions for the
+//! whole frame, and the graph inserts semaphores wherever a batch depends on a batch of another
+//! queue.
+//!
+//! ## Examples
+//!
+//! ```no_run
+//! use vulkano::{format::Format, image::ImageViewAbstract, sync::GpuFuture};
+//! use vulkano_util::render_graph::{AttachmentLoad, QueueKind, RenderGraph};
+//! # use std::sync::Arc;
+//! # let mut graph: RenderGraph = return;
+//! # let swapchain_view: Arc<dyn ImageViewAbstract> = return;
+//! # let acquire_future: Box<dyn GpuFuture> = return;
+//! let mut frame = graph.begin_frame();
+//! let albedo = frame.create_image("albedo", [1024, 768], Format::R8G8B8A8_UNORM);
+//! let depth = frame.create_image("depth", [1024, 768], Format::D16_UNORM);
+//! let target = frame.import_image("target", swapchain_view);
+//!
+//! frame
+//!     .add_pass("gbuffer", QueueKind::Graphics)
+//!     .color_attachment(albedo, AttachmentLoad::Clear([0.0; 4].into()))
+//!     .depth_stencil_attachment(depth, AttachmentLoad::Clear(1.0.into()))
+//!     .execute(|context| {
+//!         // Bind a pipeline created for `context.render_pass_type()` and draw.
+//!     });
+//! frame
+//!     .add_pass("lighting", QueueKind::Graphics)
+//!     .sampled_image(albedo)
+//!     .color_attachment(target, AttachmentLoad::DontCare)
+//!     .execute(|context| {
+//!         // Sample `context.image(albedo)` and draw a fullscreen triangle.
+//!     });
+//!
+//! let future = frame.execute(acquire_future).unwrap();
+//! ```
+
+use std::{
+    collections::{HashMap, HashSet},
+    error::Error,
+    fmt::{Display, Error as FmtError, Formatter},
+    sync::Arc,
+};
+use vulkano::{
+    buffer::BufferAccess,
+    command_buffer::{
+        AutoCommandBufferBuilder, BuildError, CommandBufferBeginError, CommandBufferExecError,
+        CommandBufferUsage, PrimaryAutoCommandBuffer, RenderPassBeginInfo, RenderPassError,
+        RenderingAttachmentInfo, RenderingInfo, SubpassContents,
+    },
+    device::{Device, DeviceOwned, Queue},
+    format::{ClearValue, Format},
+    image::{
+        view::{ImageView, ImageViewCreationError},
+        ImageAccess, ImageCreateFlags, ImageCreationError, ImageDimensions, ImageLayout,
+        ImageUsage, ImageViewAbstract, StorageImage,
+    },
+    pipeline::graphics::render_pass::{PipelineRenderPassType, PipelineRenderingCreateInfo},
+    render_pass::{
+        AttachmentDescription, AttachmentReference, Framebuffer, FramebufferCreateInfo,
+        FramebufferCreationError, LoadOp, RenderPass, RenderPassCreateInfo,
+        RenderPassCreationError, StoreOp, Subpass, SubpassDescription,
+    },
+    sync::{self, FlushError, GpuFuture, Sharing},
+};
+
+/// The number of frames that a pooled image may stay unused before it is destroyed.
+const POOL_RETENTION_FRAMES: u64 = 3;
+
+/// Identifies an image of a [`FrameGraph`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct ImageId(usize);
+
+/// Identifies a buffer of a [`FrameGraph`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct BufferId(usize);
+
+/// The queue that a pass is executed on.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum QueueKind {
+    Graphics,
+    /// Executed on the compute queue of the graph if it has one, and on the graphics queue
+    /// otherwise.
+    Compute,
+}
+
+/// How passes with attachments are recorded.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum RenderingMode {
+    /// Uses dynamic rendering if the `dynamic_rendering` feature is enabled on the device, and a
+    /// render pass otherwise.
+    Auto,
+    /// Uses `begin_rendering`. The `dynamic_rendering` feature must be enabled on the device.
+    DynamicRendering,
+    /// Uses `begin_render_pass`, with a single-subpass render pass that is created for each
+    /// combination of attachment formats and load and store operations.
+    RenderPass,
+}
+
+/// What happens to the contents of an attachment at the start of a pass.
+#[derive(Clone, Copy, Debug)]
+pub enum AttachmentLoad {
+    /// The previous contents are preserved. The image must have been written by an earlier pass
+    /// or be imported.
+    Load,
+    /// The attachment is cleared with the value.
+    Clear(ClearValue),
+    /// The previous contents are undefined.
+    DontCare,
+}
+
+impl AttachmentLoad {
+    fn load_op(&self) -> LoadOp {
+        match self {
+            AttachmentLoad::Load => LoadOp::Load,
+            AttachmentLoad::Clear(_) => LoadOp::Clear,
+            AttachmentLoad::DontCare => LoadOp::DontCare,
+        }
+    }
+
+    fn clear_value(&self) -> Option<ClearValue> {
+        match self {
+            AttachmentLoad::Clear(clear_value) => Some(*clear_value),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug)]
+enum ImageUse {
+    ColorAttachment(AttachmentLoad),
+    DepthStencilAttachment(AttachmentLoad),
+    Sampled,
+    StorageRead,
+    StorageWrite,
+    TransferSrc,
+    TransferDst,
+}
+
+impl ImageUse {
+    /// Whether the pass depends on the previous contents of the image.
+    fn reads(&self) -> bool {
+        match self {
+            ImageUse::ColorAttachment(load) | ImageUse::DepthStencilAttachment(load) => {
+                matches!(load, AttachmentLoad::Load)
+            }
+            ImageUse::Sampled | ImageUse::StorageRead | ImageUse::TransferSrc => true,
+            ImageUse::StorageWrite | ImageUse::TransferDst => false,
+        }
+    }
+
+    fn writes(&self) -> bool {
+        match self {
+            ImageUse::ColorAttachment(_)
+            | ImageUse::DepthStencilAttachment(_)
+            | ImageUse::StorageWrite
+            | ImageUse::TransferDst => true,
+            ImageUse::Sampled | ImageUse::StorageRead | ImageUse::TransferSrc => false,
+        }
+    }
+
+    fn usage(&self) -> ImageUsage {
+        match self {
+            ImageUse::ColorAttachment(_) => ImageUsage {
+                color_attachment: true,
+                ..ImageUsage::empty()
+            },
+            ImageUse::DepthStencilAttachment(_) => ImageUsage {
+                depth_stencil_attachment: true,
+                ..ImageUsage::empty()
+            },
+            ImageUse::Sampled => ImageUsage {
+                sampled: true,
+                ..ImageUsage::empty()
+            },
+            ImageUse::StorageRead | ImageUse::StorageWrite => ImageUsage {
+                storage: true,
+                ..ImageUsage::empty()
+            },
+            ImageUse::TransferSrc => ImageUsage {
+                transfer_src: true,
+                ..ImageUsage::empty()
+            },
+            ImageUse::TransferDst => ImageUsage {
+                transfer_dst: true,
+                ..ImageUsage::empty()
+            },
+        }
+    }
+}
+
+enum VirtualImage {
+    Transient { extent: [u32; 2], format: Format },
+    Imported(Arc<dyn ImageViewAbstract>),
+}
+
+struct VirtualImageNode {
+    name: String,
+    image: VirtualImage,
+}
+
+struct VirtualBufferNode {
+    name: String,
+    buffer: Arc<dyn BufferAccess>,
+}
+
+type PassFn<'a> = Box<dyn FnOnce(&mut PassContext<'_>) + 'a>;
+
+struct PassNode<'a> {
+    name: String,
+    queue: QueueKind,
+    images: Vec<(ImageId, ImageUse)>,
+    buffers: Vec<(BufferId, bool)>,
+    side_effects: bool,
+    execute: Option<PassFn<'a>>,
+}
+
+impl<'a> PassNode<'a> {
+    fn attachments(&self) -> impl Iterator<Item = (ImageId, ImageUse)> + '_ {
+        self.images.iter().copied().filter(|(_, image_use)| {
+            matches!(
+                image_use,
+                ImageUse::ColorAttachment(_) | ImageUse::DepthStencilAttachment(_)
+            )
+        })
+    }
+}
+
+struct PooledImage {
+    extent: [u32; 2],
+    format: Format,
+    usage: ImageUsage,
+    view: Arc<ImageView<StorageImage>>,
+    last_used_frame: u64,
+    in_use: bool,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+struct RenderPassKey {
+    color_attachments: Vec<(Format, LoadOp, StoreOp)>,
+    depth_stencil_attachment: Option<(Format, LoadOp, StoreOp)>,
+}
+
+/// Persistent state of a render graph: the queues, the pool of transient images and the cache of
+/// render passes.
+///
+/// Call [`RenderGraph::begin_frame`] each frame to declare and execute the passes of the frame.
+/// The futures of consecutive frames must be chained, for example with a
+/// [`FramePacer`](crate::frame_pacer::FramePacer), because transient images are reused from one
+/// frame to the next.
+pub struct RenderGraph {
+    graphics_queue: Arc<Queue>,
+    compute_queue: Option<Arc<Queue>>,
+    dynamic_rendering: bool,
+    image_pool: Vec<PooledImage>,
+    render_passes: HashMap<RenderPassKey, Arc<RenderPass>>,
+    frame_index: u64,
+}
+
+impl RenderGraph {
+    /// Creates a new [`RenderGraph`] that executes all passes on `graphics_queue`.
+    pub fn new(graphics_queue: Arc<Queue>, rendering_mode: RenderingMode) -> RenderGraph {
+        let dynamic_rendering = match rendering_mode {
+            RenderingMode::Auto => graphics_queue.device().enabled_features().dynamic_rendering,
+            RenderingMode::DynamicRendering => true,
+            RenderingMode::RenderPass => false,
+        };
+
+        RenderGraph {
+            graphics_queue,
+            compute_queue: None,
+            dynamic_rendering,
+            image_pool: Vec::new(),
+            render_passes: HashMap::default(),
+            frame_index: 0,
+        }
+    }
+
+    /// Executes [`QueueKind::Compute`] passes on `compute_queue`.
+    ///
+    /// Has no effect if `compute_queue` belongs to the same queue family as the graphics queue.
+    /// Otherwise, transient images are created with concurrent sharing between both families,
+    /// and imported resources that are used by compute passes must be shared concurrently too.
+    ///
+    /// # Panics
+    ///
+    /// - Panics if `compute_queue` doesn't belong to the same device as the graphics queue.
+    pub fn with_compute_queue(mut self, compute_queue: Arc<Queue>) -> RenderGraph {
+        assert_eq!(self.graphics_queue.device(), compute_queue.device());
+
+        if compute_queue.queue_family_index() != self.graphics_queue.queue_family_index() {
+            // Images of the pool would have the wrong sharing mode.
+            self.image_pool.clear();
+            self.compute_queue = Some(compute_queue);
+        }
+
+        self
+    }
+
+    /// Returns the device of the graph.
+    #[inline]
+    pub fn device(&self) -> &Arc<Device> {
+        self.graphics_queue.device()
+    }
+
+    /// Returns the graphics queue.
+    #[inline]
+    pub fn graphics_queue(&self) -> &Arc<Queue> {
+        &self.graphics_queue
+    }
+
+    /// Returns the compute queue, if it belongs to a different queue family than the graphics
+    /// queue.
+    #[inline]
+    pub fn compute_queue(&self) -> Option<&Arc<Queue>> {
+        self.compute_queue.as_ref()
+    }
+
+    /// Returns whether passes with attachments are recorded with dynamic rendering.
+    #[inline]
+    pub fn uses_dynamic_rendering(&self) -> bool {
+        self.dynamic_rendering
+    }
+
+    /// Starts declaring the passes of a new frame.
+    pub fn begin_frame<'a>(&mut self) -> FrameGraph<'_, 'a> {
+        FrameGraph {
+            graph: self,
+            images: Vec::new(),
+            buffers: Vec::new(),
+            passes: Vec::new(),
+        }
+    }
+
+    fn queue(&self, queue_kind: QueueKind) -> &Arc<Queue> {
+        match (queue_kind, &self.compute_queue) {
+            (QueueKind::Compute, Some(compute_queue)) => compute_queue,
+            _ => &self.graphics_queue,
+        }
+    }
+
+    fn queue_family_indices(&self) -> Vec<u32> {
+        let mut queue_family_indices = vec![self.graphics_queue.queue_family_index()];
+        queue_family_indices.extend(
+            self.compute_queue
+                .iter()
+                .map(|queue| queue.queue_family_index()),
+        );
+
+        queue_family_indices
+    }
+
+    /// Takes a free image from the pool that matches, or creates one.
+    fn allocate_image(
+        &mut self,
+        extent: [u32; 2],
+        format: Format,
+        usage: ImageUsage,
+    ) -> Result<usize, RenderGraphError> {
+        let frame_index = self.frame_index;
+        let found = self.image_pool.iter().position(|pooled| {
+            !pooled.in_use
+                && pooled.extent == extent
+                && pooled.format == format
+                && pooled.usage.contains(&usage)
+        });
+
+        let index = match found {
+            Some(index) => index,
+            None => {
+                let image = StorageImage::with_usage(
+                    self.device().clone(),
+                    ImageDimensions::Dim2d {
+                        width: extent[0],
+                        height: extent[1],
+                        array_layers: 1,
+                    },
+                    format,
+                    usage,
+                    ImageCreateFlags::empty(),
+                    self.queue_family_indices(),
+                )?;
+                self.image_pool.push(PooledImage {
+                    extent,
+                    format,
+                    usage,
+                    view: ImageView::new_default(image)?,
+                    last_used_frame: frame_index,
+                    in_use: false,
+                });
+
+                self.image_pool.len() - 1
+            }
+        };
+
+        let pooled = &mut self.image_pool[index];
+        pooled.in_use = true;
+        pooled.last_used_frame = frame_index;
+
+        Ok(index)
+    }
+
+    fn render_pass(&mut self, key: RenderPassKey) -> Result<Arc<RenderPass>, RenderGraphError> {
+        if let Some(render_pass) = self.render_passes.get(&key) {
+            return Ok(render_pass.clone());
+        }
+
+        let color_attachments = key
+            .color_attachments
+            .iter()
+            .map(|&(format, load_op, store_op)| AttachmentDescription {
+                format: Some(format),
+                load_op,
+                store_op,
+                initial_layout: ImageLayout::ColorAttachmentOptimal,
+                final_layout: ImageLayout::ColorAttachmentOptimal,
+                ..Default::default()
+            });
+        let depth_stencil_attachment =
+            key.depth_stencil_attachment
+                .map(|(format, load_op, store_op)| {
+                    let (stencil_load_op, stencil_store_op) = if format.aspects().stencil {
+                        (load_op, store_op)
+                    } else {
+                        (LoadOp::DontCare, StoreOp::DontCare)
+                    };
+
+                    AttachmentDescription {
+                        format: Some(format),
+                        load_op,
+                        store_op,
+                        stencil_load_op,
+                        stencil_store_op,
+                        initial_layout: ImageLayout::DepthStencilAttachmentOptimal,
+                        final_layout: ImageLayout::DepthStencilAttachmentOptimal,
+                        ..Default::default()
+                    }
+                });
+        let color_attachment_count = key.color_attachments.len() as u32;
+
+        let render_pass = RenderPass::new(
+            self.device().clone(),
+            RenderPassCreateInfo {
+                attachments: color_attachments.chain(depth_stencil_attachment).collect(),
+                subpasses: vec![SubpassDescription {
+                    color_attachments: (0..color_attachment_count)
+                        .map(|attachment| {
+                            Some(AttachmentReference {
+                                attachment,
+                                layout: ImageLayout::ColorAttachmentOptimal,
+                                ..Default::default()
+                            })
+                        })
+                        .collect(),
+                    depth_stencil_attachment: key.depth_stencil_attachment.map(|_| {
+                        AttachmentReference {
+                            attachment: color_attachment_count,
+                            layout: ImageLayout::DepthStencilAttachmentOptimal,
+                            ..Default::default()
+                        }
+                    }),
+                    ..Default::default()
+                }],
+                ..Default::default()
+            },
+        )?;
+        self.render_passes.insert(key, render_pass.clone());
+
+        Ok(render_pass)
+    }
+}
+
+/// The passes and resources of a single frame of a [`RenderGraph`].
+///
+/// Passes must be declared in an order where each pass comes after the passes that write the
+/// resources it reads.
+pub struct FrameGraph<'g, 'a> {
+    graph: &'g mut RenderGraph,
+    images: Vec<VirtualImageNode>,
+    buffers: Vec<VirtualBufferNode>,
+    passes: Vec<PassNode<'a>>,
+}
+
+impl<'g, 'a> FrameGraph<'g, 'a> {
+    /// Declares a transient 2D image that is created and owned by the graph.
+    ///
+    /// The image only lives for the duration of the frame. Transient images whose lifetimes don't
+    /// overlap, and that have the same extent and format, may be backed by the same image. Its
+    /// usage is derived from the passes that use it.
+    pub fn create_image(
+        &mut self,
+        name: impl Into<String>,
+        extent: [u32; 2],
+        format: Format,
+    ) -> ImageId {
+        self.images.push(VirtualImageNode {
+            name: name.into(),
+            image: VirtualImage::Transient { extent, format },
+        });
+
+        ImageId(self.images.len() - 1)
+    }
+
+    /// Declares an image that is owned outside of the graph, such as a swapchain image.
+    ///
+    /// Passes that write imported images are never culled.
+    pub fn import_image(
+        &mut self,
+        name: impl Into<String>,
+        image_view: Arc<dyn ImageViewAbstract>,
+    ) -> ImageId {
+        self.images.push(VirtualImageNode {
+            name: name.into(),
+            image: VirtualImage::Imported(image_view),
+        });
+
+        ImageId(self.images.len() - 1)
+    }
+
+    /// Declares a buffer that is owned outside of the graph.
+    ///
+    /// Passes that write imported buffers are never culled.
+    pub fn import_buffer(
+        &mut self,
+        name: impl Into<String>,
+        buffer: Arc<dyn BufferAccess>,
+    ) -> BufferId {
+        self.buffers.push(VirtualBufferNode {
+            name: name.into(),
+            buffer,
+        });
+
+        BufferId(self.buffers.len() - 1)
+    }
+
+    /// Starts declaring a pass that is executed on the queue of `queue_kind`.
+    ///
+    /// The pass is added to the frame when [`PassBuilder::execute`] is called.
+    pub fn add_pass<'f>(
+        &'f mut self,
+        name: impl Into<String>,
+        queue_kind: QueueKind,
+    ) -> PassBuilder<'f, 'g, 'a> {
+        PassBuilder {
+            frame: self,
+            pass: PassNode {
+                name: name.into(),
+                queue: queue_kind,
+                images: Vec::new(),
+                buffers: Vec::new(),
+                side_effects: false,
+                execute: None,
+            },
+        }
+    }
+
+    /// Culls the unused passes, assigns the transient images, and records and submits the passes
+    /// after `before`.
+    ///
+    /// `before` is usually the future of acquiring the swapchain image. Batches of compute passes
+    /// that don't use imported resources, nor resources written by earlier graphics passes, don't
+    /// wait for it.
+    ///
+    /// Every batch is flushed before the next one is recorded, so the returned future has
+    /// already been flushed.
+    pub fn execute(
+        self,
+        before: impl GpuFuture + 'static,
+    ) -> Result<Box<dyn GpuFuture>, RenderGraphError> {
+        let FrameGraph {
+            graph,
+            images,
+            buffers,
+            mut passes,
+        } = self;
+        graph.frame_index += 1;
+
+        let alive = cull_passes(&images, &passes);
+        let live_passes: Vec<usize> = (0..passes.len()).filter(|&i| alive[i]).collect();
+
+        // Validate the passes and compute the usage and lifetime of the transient images
+        let mut usages = vec![ImageUsage::empty(); images.len()];
+        let mut lifetimes: Vec<Option<(usize, usize)>> = vec![None; images.len()];
+
+        for &pass_index in &live_passes {
+            let pass = &passes[pass_index];
+            let mut extent = None;
+
+            for &(ImageId(image), image_use) in &pass.images {
+                let node = &images[image];
+
+                if let VirtualImage::Transient { .. } = node.image {
+                    if image_use.reads() && lifetimes[image].is_none() {
+                        return Err(RenderGraphError::ReadBeforeWrite {
+                            pass: pass.name.clone(),
+                            image: node.name.clone(),
+                        });
+                    }
+
+                    usages[image] |= image_use.usage();
+                    let lifetime = lifetimes[image].get_or_insert((pass_index, pass_index));
+                    lifetime.1 = pass_index;
+                }
+
+                if let ImageUse::ColorAttachment(_) | ImageUse::DepthStencilAttachment(_) =
+                    image_use
+                {
+                    let image_extent = match &node.image {
+                        VirtualImage::Transient { extent, .. } => *extent,
+                        VirtualImage::Imported(view) => view.dimensions().width_height(),
+                    };
+
+                    if *extent.get_or_insert(image_extent) != image_extent {
+                        return Err(RenderGraphError::AttachmentExtentMismatch {
+                            pass: pass.name.clone(),
+                        });
+                    }
+                }
+            }
+
+            if graph.compute_queue.is_some() && pass.queue == QueueKind::Compute {
+                let queue_family_indices = graph.queue_family_indices();
+                let is_shared = |sharing: &Sharing<_>| match sharing {
+                    Sharing::Concurrent(indices) => queue_family_indices
+                        .iter()
+                        .all(|index| <[u32]>::contains(indices, index)),
+                    Sharing::Exclusive => false,
+                };
+
+                for &(ImageId(image), _) in &pass.images {
+                    if let VirtualImage::Imported(view) = &images[image].image {
+                        if !is_shared(view.image().inner().image.sharing()) {
+                            return Err(RenderGraphError::ExclusiveSharing {
+                                resource: images[image].name.clone(),
+                            });
+                        }
+                    }
+                }
+
+                for &(BufferId(buffer), _) in &pass.buffers {
+                    if !is_shared(buffers[buffer].buffer.inner().buffer.sharing()) {
+                        return Err(RenderGraphError::ExclusiveSharing {
+                            resource: buffers[buffer].name.clone(),
+                        });
+                    }
+                }
+            }
+        }
+
+        // Assign pooled images to the transient images, reusing the images of transient images
+        // that are no longer used
+        for pooled in &mut graph.image_pool {
+            pooled.in_use = false;
+        }
+
+        let mut physical = vec![None; images.len()];
+
+        for &pass_index in &live_passes {
+            for &(ImageId(image), _) in &passes[pass_index].images {
+                if let VirtualImage::Transient { extent, format } = images[image].image {
+                    if physical[image].is_none() {
+                        physical[image] =
+                            Some(graph.allocate_image(extent, format, usages[image])?);
+                    }
+                }
+            }
+
+            for (image, lifetime) in lifetimes.iter().enumerate() {
+                if matches!(lifetime, Some((_, last)) if *last == pass_index) {
+                    graph.image_pool[physical[image].unwrap()].in_use = false;
+                }
+            }
+        }
+
+        let views: Vec<Option<Arc<dyn ImageViewAbstract>>> = images
+            .iter()
+            .enumerate()
+            .map(|(image, node)| match &node.image {
+                VirtualImage::Transient { .. } => physical[image].map(|index| {
+                    graph.image_pool[index].view.clone() as Arc<dyn ImageViewAbstract>
+                }),
+                VirtualImage::Imported(view) => Some(view.clone()),
+            })
+            .collect();
+        let buffer_list: Vec<Arc<dyn BufferAccess>> =
+            buffers.iter().map(|node| node.buffer.clone()).collect();
+
+        // Destroy the pooled images that haven't been used for a while. From here on, the indices
+        // in `physical` only identify images and can't be used to index the pool.
+        let frame_index = graph.frame_index;
+        graph
+            .image_pool
+            .retain(|pooled| pooled.last_used_frame + POOL_RETENTION_FRAMES >= frame_index);
+
+        // Split the passes in batches of consecutive passes on the same queue
+        let mut batches: Vec<Batch> = Vec::new();
+
+        for &pass_index in &live_passes {
+            let queue_kind = match graph.compute_queue {
+                Some(_) => passes[pass_index].queue,
+                None => QueueKind::Graphics,
+            };
+
+            match batches.last_mut() {
+                Some(batch) if batch.queue_kind == queue_kind => batch.passes.push(pass_index),
+                _ => batches.push(Batch {
+                    queue_kind,
+                    passes: vec![pass_index],
+                    images: HashSet::default(),
+                    buffer_reads: HashSet::default(),
+                    buffer_writes: HashSet::default(),
+                    external: false,
+                }),
+            }
+
+            let batch = batches.last_mut().unwrap();
+            let pass = &passes[pass_index];
+
+            for &(ImageId(image), _) in &pass.images {
+                let key = match images[image].image {
+                    VirtualImage::Transient { .. } => ResourceKey::Pooled(physical[image].unwrap()),
+                    VirtualImage::Imported(_) => {
+                        batch.external = true;
+                        ResourceKey::Imported(image)
+                    }
+                };
+                batch.images.insert(key);
+            }
+
+            for &(BufferId(buffer), write) in &pass.buffers {
+                batch.external = true;
+
+                if write {
+                    batch.buffer_writes.insert(buffer);
+                } else {
+                    batch.buffer_reads.insert(buffer);
+                }
+            }
+        }
+
+        // Record and submit the batches. Each queue has a chain of batches; when a batch depends
+        // on a batch of the other chain, that chain is merged into this one with a semaphore.
+        let mut chains: [Option<Chain>; 2] = [
+            Some(Chain {
+                future: before.boxed(),
+                batches: Vec::new(),
+                external: true,
+            }),
+            None,
+        ];
+
+        for batch_index in 0..batches.len() {
+            let batch = &batches[batch_index];
+            let queue = graph.queue(batch.queue_kind).clone();
+            let this = match batch.queue_kind {
+                QueueKind::Graphics => 0,
+                QueueKind::Compute => 1,
+            };
+            let other = 1 - this;
+
+            let mut builder = AutoCommandBufferBuilder::primary(
+                graph.device().clone(),
+                queue.queue_family_index(),
+                CommandBufferUsage::OneTimeSubmit,
+            )?;
+
+            for &pass_index in &batch.passes {
+                let execute = passes[pass_index].execute.take();
+                record_pass(
+                    graph,
+                    &mut builder,
+                    pass_index,
+                    &passes[pass_index],
+                    execute,
+                    &images,
+                    &lifetimes,
+                    &views,
+                    &buffer_list,
+                )?;
+            }
+
+            let command_buffer = builder.build()?;
+
+            let depends_on_other = chains[other].as_ref().map_or(false, |chain| {
+                (chain.external && batch.external)
+                    || chain
+                        .batches
+                        .iter()
+                        .any(|&previous| batch.depends_on(&batches[previous]))
+            });
+            let mut chain = chains[this].take().unwrap_or_else(|| Chain {
+                future: sync::now(graph.device().clone()).boxed(),
+                batches: Vec::new(),
+                external: false,
+            });
+
+            if depends_on_other {
+                let other_chain = chains[other].take().unwrap();
+                chain.future = chain
+                    .future
+                    .join(other_chain.future.then_signal_semaphore())
+                    .boxed();
+                chain.batches.extend(other_chain.batches);
+                chain.external |= other_chain.external;
+            }
+
+            chain.future = chain.future.then_execute(queue, command_buffer)?.boxed();
+
+            // The transient images are layout tracked, so the layout that a batch expects them
+            // in is taken from their state when the batch is recorded. Submit the batch before
+            // recording the next one, so that the next one sees the layouts this one leaves.
+            chain.future.flush()?;
+            chain.batches.push(batch_index);
+            chain.external |= batch.external;
+            chains[this] = Some(chain);
+        }
+
+        let [graphics_chain, compute_chain] = chains;
+
+        Ok(match (graphics_chain, compute_chain) {
+            (Some(graphics_chain), Some(compute_chain)) => graphics_chain
+                .future
+                .join(compute_chain.future.then_signal_semaphore())
+                .boxed(),
+            (Some(graphics_chain), None) => graphics_chain.future,
+            (None, Some(compute_chain)) => compute_chain.future.then_signal_semaphore().boxed(),
+            (None, None) => unreachable!(),
+        })
+    }
+}
+
+/// Declares the resources that a pass uses. Created with [`FrameGraph::add_pass`].
+pub struct PassBuilder<'f, 'g, 'a> {
+    frame: &'f mut FrameGraph<'g, 'a>,
+    pass: PassNode<'a>,
+}
+
+impl<'f, 'g, 'a> PassBuilder<'f, 'g, 'a> {
+    /// Renders to `image` as the next color attachment. The attachment index in the fragment
+    /// shader is the number of color attachments declared before.
+    #[inline]
+    pub fn color_attachment(mut self, image: ImageId, load: AttachmentLoad) -> Self {
+        self.pass
+            .images
+            .push((image, ImageUse::ColorAttachment(load)));
+        self
+    }
+
+    /// Renders to `image` as the depth/stencil attachment.
+    #[inline]
+    pub fn depth_stencil_attachment(mut self, image: ImageId, load: AttachmentLoad) -> Self {
+        self.pass
+            .images
+            .push((image, ImageUse::DepthStencilAttachment(load)));
+        self
+    }
+
+    /// Samples `image` in a shader.
+    #[inline]
+    pub fn sampled_image(mut self, image: ImageId) -> Self {
+        self.pass.images.push((image, ImageUse::Sampled));
+        self
+    }
+
+    /// Reads `image` as a storage image.
+    #[inline]
+    pub fn read_storage_image(mut self, image: ImageId) -> Self {
+        self.pass.images.push((image, ImageUse::StorageRead));
+        self
+    }
+
+    /// Writes `image` as a storage image.
+    #[inline]
+    pub fn write_storage_image(mut self, image: ImageId) -> Self {
+        self.pass.images.push((image, ImageUse::StorageWrite));
+        self
+    }
+
+    /// Copies or blits from `image`.
+    #[inline]
+    pub fn transfer_src(mut self, image: ImageId) -> Self {
+        self.pass.images.push((image, ImageUse::TransferSrc));
+        self
+    }
+
+    /// Copies, blits or clears to `image`.
+    #[inline]
+    pub fn transfer_dst(mut self, image: ImageId) -> Self {
+        self.pass.images.push((image, ImageUse::TransferDst));
+        self
+    }
+
+    /// Reads `buffer`.
+    #[inline]
+    pub fn read_buffer(mut self, buffer: BufferId) -> Self {
+        self.pass.buffers.push((buffer, false));
+        self
+    }
+
+    /// Writes `buffer`.
+    #[inline]
+    pub fn write_buffer(mut self, buffer: BufferId) -> Self {
+        self.pass.buffers.push((buffer, true));
+        self
+    }
+
+    /// Marks the pass as having effects that are not visible to the graph, so that it is never
+    /// culled.
+    #[inline]
+    pub fn side_effects(mut self) -> Self {
+        self.pass.side_effects = true;
+        self
+    }
+
+    /// Adds the pass to the frame. `execute` is called to record the commands of the pass, unless
+    /// the pass is culled.
+    ///
+    /// For passes with attachments, rendering has already begun when `execute` is called.
+    pub fn execute(mut self, execute: impl FnOnce(&mut PassContext<'_>) + 'a) {
+        self.pass.execute = Some(Box::new(execute));
+        self.frame.passes.push(self.pass);
+    }
+}
+
+/// Gives access to the command buffer and resources while recording a pass.
+pub struct PassContext<'b> {
+    builder: &'b mut AutoCommandBufferBuilder<PrimaryAutoCommandBuffer>,
+    images: &'b [Option<Arc<dyn ImageViewAbstract>>],
+    buffers: &'b [Arc<dyn BufferAccess>],
+    render_pass_type: Option<PipelineRenderPassType>,
+    extent: [u32; 2],
+}
+
+impl<'b> PassContext<'b> {
+    /// Returns the command buffer builder to record the commands of the pass into.
+    #[inline]
+    pub fn builder(&mut self) -> &mut AutoCommandBufferBuilder<PrimaryAutoCommandBuffer> {
+        self.builder
+    }
+
+    /// Returns the image view of `image`.
+    ///
+    /// # Panics
+    ///
+    /// - Panics if `image` is a transient image that is not used by any pass that is executed.
+    #[inline]
+    pub fn image(&self, image: ImageId) -> &Arc<dyn ImageViewAbstract> {
+        self.images[image.0]
+            .as_ref()
+            .expect("the image is not used by any pass that is executed")
+    }
+
+    /// Returns `buffer`.
+    #[inline]
+    pub fn buffer(&self, buffer: BufferId) -> &Arc<dyn BufferAccess> {
+        &self.buffers[buffer.0]
+    }
+
+    /// For passes with attachments, returns what graphics pipelines must be created for to be
+    /// used in the pass.
+    ///
+    /// With render passes, the same render pass is returned every frame for the same attachment
+    /// formats and load and store operations, so pipelines can be cached by render pass.
+    #[inline]
+    pub fn render_pass_type(&self) -> Option<&PipelineRenderPassType> {
+        self.render_pass_type.as_ref()
+    }
+
+    /// For passes with attachments, returns the extent of the attachments. Otherwise returns
+    /// `[0, 0]`.
+    #[inline]
+    pub fn extent(&self) -> [u32; 2] {
+        self.extent
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+enum ResourceKey {
+    Pooled(usize),
+    Imported(usize),
+}
+
+struct Batch {
+    queue_kind: QueueKind,
+    passes: Vec<usize>,
+    images: HashSet<ResourceKey>,
+    buffer_reads: HashSet<usize>,
+    buffer_writes: HashSet<usize>,
+    /// Whether the batch uses imported resources, and must therefore wait for the future that the
+    /// frame is executed after.
+    external: bool,
+}
+
+impl Batch {
+    /// Returns whether the batch must be executed after `previous`. Sharing any image is a
+    /// dependency, because the image may need a layout transition.
+    fn depends_on(&self, previous: &Batch) -> bool {
+        !self.images.is_disjoint(&previous.images)
+            || !self.buffer_writes.is_disjoint(&previous.buffer_reads)
+            || !self.buffer_writes.is_disjoint(&previous.buffer_writes)
+            || !self.buffer_reads.is_disjoint(&previous.buffer_writes)
+    }
+}
+
+struct Chain {
+    future: Box<dyn GpuFuture>,
+    batches: Vec<usize>,
+    external: bool,
+}
+
+/// Returns which passes contribute to an imported resource or have side effects.
+fn cull_passes(images: &[VirtualImageNode], passes: &[PassNode<'_>]) -> Vec<bool> {
+    let mut needed: Vec<bool> = images
+        .iter()
+        .map(|node| matches!(node.image, VirtualImage::Imported(_)))
+        .collect();
+    let mut alive = vec![false; passes.len()];
+
+    for (pass_index, pass) in passes.iter().enumerate().rev() {
+        alive[pass_index] = pass.side_effects
+            || pass.buffers.iter().any(|&(_, write)| write)
+            || pass
+                .images
+                .iter()
+                .any(|&(ImageId(image), image_use)| image_use.writes() && needed[image]);
+
+        if alive[pass_index] {
+            for &(ImageId(image), image_use) in &pass.images {
+                if image_use.reads() || matches!(image_use, ImageUse::StorageWrite) {
+                    needed[image] = true;
+                }
+            }
+        }
+    }
+
+    alive
+}
+
+#[allow(clippy::too_many_arguments)]
+fn record_pass(
+    graph: &mut RenderGraph,
+    builder: &mut AutoCommandBufferBuilder<PrimaryAutoCommandBuffer>,
+    pass_index: usize,
+    pass: &PassNode<'_>,
+    execute: Option<PassFn<'_>>,
+    images: &[VirtualImageNode],
+    lifetimes: &[Option<(usize, usize)>],
+    views: &[Option<Arc<dyn ImageViewAbstract>>],
+    buffers: &[Arc<dyn BufferAccess>],
+) -> Result<(), RenderGraphError> {
+    let attachments: Vec<_> = pass
+        .attachments()
+        .map(|(ImageId(image), image_use)| {
+            let view = views[image].clone().unwrap();
+            // The contents are stored if the image is imported, or used by a later pass.
+            let store_op = match (&images[image].image, lifetimes[image]) {
+                (VirtualImage::Imported(_), _) => StoreOp::Store,
+                (_, Some((_, last))) if last > pass_index => StoreOp::Store,
+                _ => StoreOp::DontCare,
+            };
+
+            (view, image_use, store_op)
+        })
+        .collect();
+
+    let mut context = PassContext {
+        builder,
+        images: views,
+        buffers,
+        render_pass_type: None,
+        extent: [0, 0],
+    };
+
+    if attachments.is_empty() {
+        if let Some(execute) = execute {
+            execute(&mut context);
+        }
+
+        return Ok(());
+    }
+
+    context.extent = attachments[0].0.dimensions().width_height();
+    let load = |image_use: ImageUse| match image_use {
+        ImageUse::ColorAttachment(load) | ImageUse::DepthStencilAttachment(load) => load,
+        _ => unreachable!(),
+    };
+    let (color_attachments, depth_stencil_attachment): (Vec<_>, Vec<_>) = attachments
+        .into_iter()
+        .partition(|(_, image_use, _)| matches!(image_use, ImageUse::ColorAttachment(_)));
+    let depth_stencil_attachment = depth_stencil_attachment.into_iter().last();
+
+    if graph.dynamic_rendering {
+        let attachment_info =
+            |(view, image_use, store_op): &(Arc<dyn ImageViewAbstract>, ImageUse, StoreOp)| {
+                let load = load(*image_use);
+
+                RenderingAttachmentInfo {
+                    load_op: load.load_op(),
+                    store_op: *store_op,
+                    clear_value: load.clear_value(),
+                    ..RenderingAttachmentInfo::image_view(view.clone())
+                }
+            };
+        let depth_stencil_format = depth_stencil_attachment
+            .as_ref()
+            .map(|(view, _, _)| view.format().unwrap());
+        let has_depth = depth_stencil_format.map_or(false, |format| format.aspects().depth);
+        let has_stencil = depth_stencil_format.map_or(false, |format| format.aspects().stencil);
+
+        context.render_pass_type = Some(PipelineRenderPassType::BeginRendering(
+            PipelineRenderingCreateInfo {
+                color_attachment_formats: color_attachments
+                    .iter()
+                    .map(|(view, _, _)| view.format())
+                    .collect(),
+                depth_attachment_format: depth_stencil_format.filter(|_| has_depth),
+                stencil_attachment_format: depth_stencil_format.filter(|_| has_stencil),
+                ..Default::default()
+            },
+        ));
+        context.builder.begin_rendering(RenderingInfo {
+            color_attachments: color_attachments
+                .iter()
+                .map(|attachment| Some(attachment_info(attachment)))
+                .collect(),
+            depth_attachment: depth_stencil_attachment
+                .as_ref()
+                .filter(|_| has_depth)
+                .map(attachment_info),
+            stencil_attachment: depth_stencil_attachment
+                .as_ref()
+                .filter(|_| has_stencil)
+                .map(attachment_info),
+            ..Default::default()
+        })?;
+
+        if let Some(execute) = execute {
+            execute(&mut context);
+        }
+
+        context.builder.end_rendering()?;
+    } else {
+        let key = RenderPassKey {
+            color_attachments: color_attachments
+                .iter()
+                .map(|(view, image_use, store_op)| {
+                    (
+                        view.format().unwrap(),
+                        load(*image_use).load_op(),
+                        *store_op,
+                    )
+                })
+                .collect(),
+            depth_stencil_attachment: depth_stencil_attachment.as_ref().map(
+                |(view, image_use, store_op)| {
+                    (
+                        view.format().unwrap(),
+                        load(*image_use).load_op(),
+                        *store_op,
+                    )
+                },
+            ),
+        };
+        let render_pass = graph.render_pass(key)?;
+        let attachments: Vec<_> = color_attachments
+            .iter()
+            .chain(depth_stencil_attachment.iter())
+            .collect();
+        let framebuffer = Framebuffer::new(
+            render_pass.clone(),
+            FramebufferCreateInfo {
+                attachments: attachments
+                    .iter()
+                    .map(|(view, _, _)| view.clone())
+                    .collect(),
+                ..Default::default()
+            },
+        )?;
+
+        context.render_pass_type = Some(PipelineRenderPassType::BeginRenderPass(
+            Subpass::from(render_pass, 0).unwrap(),
+        ));
+        context.builder.begin_render_pass(
+            RenderPassBeginInfo {
+                clear_values: attachments
+                    .iter()
+                    .map(|(_, image_use, _)| load(*image_use).clear_value())
+                    .collect(),
+                ..RenderPassBeginInfo::framebuffer(framebuffer)
+            },
+            SubpassContents::Inline,
+        )?;
+
+        if let Some(execute) = execute {
+            execute(&mut context);
+        }
+
+        context.builder.end_render_pass()?;
+    }
+
+    Ok(())
+}
+
+/// Error that can happen when executing a [`FrameGraph`].
+#[derive(Clone, Debug)]
+pub enum RenderGraphError {
+    /// A pass reads a transient image that no earlier pass writes.
+    ReadBeforeWrite { pass: String, image: String },
+
+    /// The attachments of a pass don't all have the same extent.
+    AttachmentExtentMismatch { pass: String },
+
+    /// A compute pass uses an imported resource that is not shared concurrently between the
+    /// graphics and compute queue families.
+    ExclusiveSharing { resource: String },
+
+    /// Creating a transient image failed.
+    ImageCreationError(ImageCreationError),
+
+    /// Creating the view of a transient image failed.
+    ImageViewCreationError(ImageViewCreationError),
+
+    /// Creating a render pass failed.
+    RenderPassCreationError(RenderPassCreationError),
+
+    /// Creating a framebuffer failed.
+    FramebufferCreationError(FramebufferCreationError),
+
+    /// Beginning a command buffer failed.
+    CommandBufferBeginError(CommandBufferBeginError),
+
+    /// Beginning or ending rendering failed.
+    RenderPassError(RenderPassError),
+
+    /// Building a command buffer failed.
+    BuildError(BuildError),
+
+    /// Executing a command buffer failed.
+    CommandBufferExecError(CommandBufferExecError),
+
+    /// Submitting a batch failed.
+    FlushError(FlushError),
+}
+
+impl Error for RenderGraphError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            Self::ImageCreationError(err) => Some(err),
+            Self::ImageViewCreationError(err) => Some(err),
+            Self::RenderPassCreationError(err) => Some(err),
+            Self::FramebufferCreationError(err) => Some(err),
+            Self::CommandBufferBeginError(err) => Some(err),
+            Self::RenderPassError(err) => Some(err),
+            Self::BuildError(err) => Some(err),
+            Self::CommandBufferExecError(err) => Some(err),
+            Self::FlushError(err) => Some(err),
+            _ => None,
+        }
+    }
+}
+
+impl Display for RenderGraphError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result<(), FmtError> {
+        match self {
+            Self::ReadBeforeWrite { pass, image } => write!(
+                f,
+                "pass `{}` reads the transient image `{}`, but no earlier pass writes it",
+                pass, image,
+            ),
+            Self::AttachmentExtentMismatch { pass } => write!(
+                f,
+                "the attachments of pass `{}` don't all have the same extent",
+                pass,
+            ),
+            Self::ExclusiveSharing { resource } => write!(
+                f,
+                "the imported resource `{}` is used by a compute pass, but is not shared \
+                concurrently between the graphics and compute queue families",
+                resource,
+            ),
+            Self::ImageCreationError(_) => write!(f, "creating a transient image failed"),
+            Self::ImageViewCreationError(_) => {
+                write!(f, "creating the view of a transient image failed")
+            }
+            Self::RenderPassCreationError(_) => write!(f, "creating a render pass failed"),
+            Self::FramebufferCreationError(_) => write!(f, "creating a framebuffer failed"),
+            Self::CommandBufferBeginError(_) => write!(f, "beginning a command buffer failed"),
+            Self::RenderPassError(_) => write!(f, "beginning or ending rendering failed"),
+            Self::BuildError(_) => write!(f, "building a command buffer failed"),
+            Self::CommandBufferExecError(_) => write!(f, "executing a command buffer failed"),
+            Self::FlushError(_) => write!(f, "submitting a batch failed"),
+        }
+    }
+}
+
+impl From<ImageCreationError> for RenderGraphError {
+    #[inline]
+    fn from(err: ImageCreationError) -> Self {
+        Self::ImageCreationError(err)
+    }
+}
+
+impl From<ImageViewCreationError> for RenderGraphError {
+    #[inline]
+    fn from(err: ImageViewCreationError) -> Self {
+        Self::ImageViewCreationError(err)
+    }
+}
+
+impl From<RenderPassCreationError> for RenderGraphError {
+    #[inline]
+    fn from(err: RenderPassCreationError) -> Self {
+        Self::RenderPassCreationError(err)
+    }
+}
+
+impl From<FramebufferCreationError> for RenderGraphError {
+    #[inline]
+    fn from(err: FramebufferCreationError) -> Self {
+        Self::FramebufferCreationError(err)
+    }
+}
+
+impl From<CommandBufferBeginError> for RenderGraphError {
+    #[inline]
+    fn from(err: CommandBufferBeginError) -> Self {
+        Self::CommandBufferBeginError(err)
+    }
+}
+
+impl From<RenderPassError> for RenderGraphError {
+    #[inline]
+    fn from(err: RenderPassError) -> Self {
+        Self::RenderPassError(err)
+    }
+}
+
+impl From<BuildError> for RenderGraphError {
+    #[inline]
+    fn from(err: BuildError) -> Self {
+        Self::BuildError(err)
+    }
+}
+
+impl From<CommandBufferExecError> for RenderGraphError {
+    #[inline]
+    fn from(err: CommandBufferExecError) -> Self {
+        Self::CommandBufferExecError(err)
+    }
+}
+
+impl From<FlushError> for RenderGraphError {
+    #[inline]
+    fn from(err: FlushError) -> Self {
+        Self::FlushError(err)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use vulkano::{
+        command_buffer::{ClearColorImageInfo, CopyImageInfo},
+        device::{DeviceCreateInfo, QueueCreateInfo},
+        instance::{Instance, InstanceCreateInfo},
+        VulkanLibrary,
+    };
+
+    fn transient(name: &str) -> VirtualImageNode {
+        VirtualImageNode {
+            name: name.into(),
+            image: VirtualImage::Transient {
+                extent: [4, 4],
+                format: Format::R8G8B8A8_UNORM,
+            },
+        }
+    }
+
+    fn pass(images: Vec<(ImageId, ImageUse)>) -> PassNode<'static> {
+        PassNode {
+            name: String::new(),
+            queue: QueueKind::Graphics,
+            images,
+            buffers: Vec::new(),
+            side_effects: false,
+            execute: None,
+        }
+    }
+
+    fn batch(images: &[usize], buffer_reads: &[usize], buffer_writes: &[usize]) -> Batch {
+        Batch {
+            queue_kind: QueueKind::Graphics,
+            passes: Vec::new(),
+            images: images.iter().map(|&i| ResourceKey::Pooled(i)).collect(),
+            buffer_reads: buffer_reads.iter().copied().collect(),
+            buffer_writes: buffer_writes.iter().copied().collect(),
+            external: false,
+        }
+    }
+
+    /// Creates a device with a queue that supports graphics, and a queue of another queue family
+    /// that supports compute if there is one. Returns `None` if there is no Vulkan
+    /// implementation.
+    fn queues() -> Option<(Arc<Queue>, Option<Arc<Queue>>)> {
+        let library = VulkanLibrary::new().ok()?;
+        let instance =
+            Instance::new(library, InstanceCreateInfo::application_from_cargo_toml()).ok()?;
+        let physical_device = instance.enumerate_physical_devices().ok()?.next()?;
+        let queue_family_properties = physical_device.queue_family_properties();
+        let graphics_index = queue_family_properties
+            .iter()
+            .position(|q| q.queue_flags.graphics)?;
+        let compute_index = queue_family_properties
+            .iter()
+            .enumerate()
+            .position(|(i, q)| i != graphics_index && q.queue_flags.compute);
+        let (_device, mut queues) = Device::new(
+            physical_device,
+            DeviceCreateInfo {
+                queue_create_infos: [Some(graphics_index), compute_index]
+                    .into_iter()
+                    .flatten()
+                    .map(|index| QueueCreateInfo {
+                        queue_family_index: index as u32,
+                        ..Default::default()
+                    })
+                    .collect(),
+                ..Default::default()
+            },
+        )
+        .ok()?;
+
+        Some((queues.next()?, queues.next()))
+    }
+
+    #[test]
+    fn cull_unused_passes() {
+        let images = vec![transient("unused"), transient("used")];
+        let passes = vec![
+            pass(vec![(
+                ImageId(0),
+                ImageUse::ColorAttachment(AttachmentLoad::DontCare),
+            )]),
+            pass(vec![(
+                ImageId(1),
+                ImageUse::ColorAttachment(AttachmentLoad::DontCare),
+            )]),
+            PassNode {
+                side_effects: true,
+                ..pass(vec![(ImageId(1), ImageUse::Sampled)])
+            },
+        ];
+
+        assert_eq!(cull_passes(&images, &passes), [false, true, true]);
+    }
+
+    #[test]
+    fn cull_passes_writing_imported_resources() {
+        let (queue, _) = match queues() {
+            Some(queues) => queues,
+            None => return,
+        };
+        let image = StorageImage::new(
+            queue.device().clone(),
+            ImageDimensions::Dim2d {
+                width: 4,
+                height: 4,
+                array_layers: 1,
+            },
+            Format::R8G8B8A8_UNORM,
+            [queue.queue_family_index()],
+        )
+        .unwrap();
+        let images = vec![
+            transient("intermediate"),
+            VirtualImageNode {
+                name: "target".into(),
+                image: VirtualImage::Imported(ImageView::new_default(image).unwrap()),
+            },
+        ];
+        let passes = vec![
+            pass(vec![(ImageId(0), ImageUse::StorageWrite)]),
+            pass(vec![
+                (ImageId(0), ImageUse::StorageRead),
+                (ImageId(1), ImageUse::StorageWrite),
+            ]),
+            // Overwrites the intermediate image, but nothing reads it afterwards.
+            pass(vec![(ImageId(0), ImageUse::TransferDst)]),
+        ];
+
+        assert_eq!(cull_passes(&images, &passes), [true, true, false]);
+    }
+
+    #[test]
+    fn cull_keeps_buffer_writes() {
+        let images = vec![transient("unused")];
+        let passes = vec![
+            PassNode {
+                buffers: vec![(BufferId(0), false)],
+                ..pass(vec![(ImageId(0), ImageUse::StorageWrite)])
+            },
+            PassNode {
+                buffers: vec![(BufferId(0), true)],
+                ..pass(Vec::new())
+            },
+        ];
+
+        assert_eq!(cull_passes(&images, &passes), [false, true]);
+    }
+
+    #[test]
+    fn batch_dependencies() {
+        assert!(batch(&[0], &[], &[]).depends_on(&batch(&[0], &[], &[])));
+        assert!(!batch(&[0], &[], &[]).depends_on(&batch(&[1], &[], &[])));
+        assert!(!batch(&[], &[0], &[]).depends_on(&batch(&[], &[0], &[])));
+        assert!(batch(&[], &[0], &[]).depends_on(&batch(&[], &[], &[0])));
+        assert!(batch(&[], &[], &[0]).depends_on(&batch(&[], &[0], &[])));
+        assert!(batch(&[], &[], &[0]).depends_on(&batch(&[], &[], &[0])));
+    }
+
+    #[test]
+    fn read_before_write() {
+        let (queue, _) = match queues() {
+            Some(queues) => queues,
+            None => return,
+        };
+        let mut graph = RenderGraph::new(queue.clone(), RenderingMode::RenderPass);
+        let mut frame = graph.begin_frame();
+        let image = frame.create_image("image", [4, 4], Format::R8G8B8A8_UNORM);
+        frame
+            .add_pass("read", QueueKind::Graphics)
+            .sampled_image(image)
+            .side_effects()
+            .execute(|_| ());
+
+        assert!(matches!(
+            frame.execute(sync::now(queue.device().clone())),
+            Err(RenderGraphError::ReadBeforeWrite { .. })
+        ));
+    }
+
+    #[test]
+    fn transient_images_across_batches_and_frames() {
+        let (queue, compute_queue) = match queues() {
+            Some(queues) => queues,
+            None => return,
+        };
+
+        // With a separate compute queue, every pass starts a new batch on the other queue, and
+        // each batch must see the layouts that the previous one leaves the transient images in.
+        let mut graph = RenderGraph::new(queue.clone(), RenderingMode::RenderPass);
+
+        if let Some(compute_queue) = compute_queue {
+            graph = graph.with_compute_queue(compute_queue);
+        }
+
+        let target = StorageImage::with_usage(
+            queue.device().clone(),
+            ImageDimensions::Dim2d {
+                width: 4,
+                height: 4,
+                array_layers: 1,
+            },
+            Format::R8G8B8A8_UNORM,
+            ImageUsage {
+                transfer_dst: true,
+                ..ImageUsage::empty()
+            },
+            ImageCreateFlags::empty(),
+            graph.queue_family_indices(),
+        )
+        .unwrap();
+        let target_view = ImageView::new_default(target).unwrap();
+
+        for _ in 0..3 {
+            let mut frame = graph.begin_frame();
+            let first = frame.create_image("first", [4, 4], Format::R8G8B8A8_UNORM);
+            let second = frame.create_image("second", [4, 4], Format::R8G8B8A8_UNORM);
+            let target = frame.import_image("target", target_view.clone());
+
+            frame
+                .add_pass("clear first", QueueKind::Compute)
+                .transfer_dst(first)
+                .execute(move |context| {
+                    let image = context.image(first).image();
+                    context
+                        .builder()
+                        .clear_color_image(ClearColorImageInfo::image(image))
+                        .unwrap();
+                });
+            frame
+                .add_pass("copy to second", QueueKind::Graphics)
+                .transfer_src(first)
+                .transfer_dst(second)
+                .execute(move |context| {
+                    let src = context.image(first).image();
+                    let dst = context.image(second).image();
+                    context
+                        .builder()
+                        .copy_image(CopyImageInfo::images(src, dst))
+                        .unwrap();
+                });
+            frame
+                .add_pass("copy to target", QueueKind::Compute)
+                .transfer_src(second)
+                .transfer_dst(target)
+                .execute(move |context| {
+                    let src = context.image(second).image();
+                    let dst = context.image(target).image();
+                    context
+                        .builder()
+                        .copy_image(CopyImageInfo::images(src, dst))
+                        .unwrap();
+                });
+
+            frame
+                .execute(sync::now(queue.device().clone()))
+                .unwrap()
+                .then_signal_fence_and_flush()
+                .unwrap()
+                .wait(None)
+                .unwrap();
+        }
+
+        // The two transient images are alive at the same time, so they can't share an image.
+        assert_eq!(graph.image_pool.len(), 2);
+    }
+}
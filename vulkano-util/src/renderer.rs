@@ -7,7 +7,11 @@
 // notice may not be copied, modified, or distributed except
 // according to those terms.
 
-use crate::{context::VulkanoContext, window::WindowDescriptor};
+use crate::{
+    context::VulkanoContext,
+    frame_pacer::{FramePacer, FrameStats},
+    window::WindowDescriptor,
+};
 use ahash::HashMap;
 use std::sync::Arc;
 use vulkano::{
@@ -18,9 +22,8 @@ use vulkano::{
     },
     swapchain::{
         self, AcquireError, Surface, Swapchain, SwapchainCreateInfo, SwapchainCreationError,
-        SwapchainPresentInfo,
     },
-    sync::{self, FlushError, GpuFuture},
+    sync::{FlushError, GpuFuture},
 };
 use vulkano_win::create_surface_from_winit;
 use winit::window::Window;
@@ -39,6 +42,9 @@ pub const DEFAULT_IMAGE_FORMAT: Format = Format::R8G8B8A8_UNORM;
 /// Begin rendering with [`VulkanoWindowRenderer::acquire`] and finish with
 /// [`VulkanoWindowRenderer::present`]. Between those, you should execute your command buffers.
 ///
+/// Frames are paced with a [`FramePacer`], configured through
+/// [`WindowDescriptor::frame_pacing`].
+///
 /// The intended usage of this struct is through [`crate::window::VulkanoWindows`].
 pub struct VulkanoWindowRenderer {
     surface: Arc<Surface<Window>>,
@@ -50,7 +56,7 @@ pub struct VulkanoWindowRenderer {
     /// Use associated functions to get access to these.
     additional_image_views: HashMap<usize, DeviceImageView>,
     recreate_swapchain: bool,
-    previous_frame_end: Option<Box<dyn GpuFuture>>,
+    frame_pacer: FramePacer,
     image_index: u32,
     present_mode: vulkano::swapchain::PresentMode,
 }
@@ -77,7 +83,10 @@ impl VulkanoWindowRenderer {
             swapchain_create_info_modify,
         );
 
-        let previous_frame_end = Some(sync::now(vulkano_context.device().clone()).boxed());
+        let frame_pacer = FramePacer::new(
+            vulkano_context.graphics_queue().clone(),
+            descriptor.frame_pacing,
+        );
 
         VulkanoWindowRenderer {
             surface,
@@ -87,7 +96,7 @@ impl VulkanoWindowRenderer {
             final_views,
             additional_image_views: HashMap::default(),
             recreate_swapchain: false,
-            previous_frame_end,
+            frame_pacer,
             image_index: 0,
             present_mode: descriptor.present_mode,
         }
@@ -189,6 +198,25 @@ impl VulkanoWindowRenderer {
         self.surface.window()
     }
 
+    /// Returns the frame pacer of this window.
+    #[inline]
+    pub fn frame_pacer(&self) -> &FramePacer {
+        &self.frame_pacer
+    }
+
+    /// Timing statistics of the last completed frame.
+    #[inline]
+    pub fn frame_stats(&self) -> FrameStats {
+        self.frame_pacer.stats()
+    }
+
+    /// Returns whether the window is minimized, in which case nothing can be presented to it.
+    #[inline]
+    pub fn is_minimized(&self) -> bool {
+        let size = self.window().inner_size();
+        size.width == 0 || size.height == 0
+    }
+
     /// Size of the physical window.
     #[inline]
     pub fn window_size(&self) -> [f32; 2] {
@@ -263,14 +291,26 @@ impl VulkanoWindowRenderer {
     /// swapchain image has been acquired and previous frame ended.
     /// Execute your command buffers after calling this function and finish rendering by calling
     /// [`VulkanoWindowRenderer::present`].
+    ///
+    /// This blocks until the number of frames in flight is below the limit of the frame pacer.
+    /// While the window is minimized, `Err(AcquireError::OutOfDate)` is returned and you should
+    /// skip the frame.
     #[inline]
     pub fn acquire(&mut self) -> std::result::Result<Box<dyn GpuFuture>, AcquireError> {
+        // Nothing can be presented to a minimized window, and the swapchain can't be recreated
+        // with a zero extent. Wait until it's restored.
+        if self.is_minimized() {
+            return Err(AcquireError::OutOfDate);
+        }
+
         // Recreate swap chain if needed (when resizing of window occurs or swapchain is outdated)
         // Also resize render views if needed
         if self.recreate_swapchain {
             self.recreate_swapchain_and_views();
         }
 
+        self.frame_pacer.wait_for_frame(&self.swapchain);
+
         // Acquire next image in the swapchain
         let (image_index, suboptimal, acquire_future) =
             match swapchain::acquire_next_image(self.swapchain.clone(), None) {
@@ -287,9 +327,7 @@ impl VulkanoWindowRenderer {
         // Update our image index
         self.image_index = image_index;
 
-        let future = self.previous_frame_end.take().unwrap().join(acquire_future);
-
-        Ok(future.boxed())
+        Ok(self.frame_pacer.begin_frame(acquire_future))
     }
 
     /// Finishes rendering by presenting the swapchain. Pass your last future as an input to this
@@ -300,38 +338,21 @@ impl VulkanoWindowRenderer {
     /// on.
     #[inline]
     pub fn present(&mut self, after_future: Box<dyn GpuFuture>, wait_future: bool) {
-        let future = after_future
-            .then_swapchain_present(
-                self.graphics_queue.clone(),
-                SwapchainPresentInfo::swapchain_image_index(
-                    self.swapchain.clone(),
-                    self.image_index,
-                ),
-            )
-            .then_signal_fence_and_flush();
-        match future {
-            Ok(mut future) => {
+        match self
+            .frame_pacer
+            .present(after_future, self.swapchain.clone(), self.image_index)
+        {
+            Ok(()) => {
                 if wait_future {
-                    match future.wait(None) {
-                        Ok(x) => x,
-                        Err(err) => println!("{:?}", err),
-                    }
                     // wait allows you to organize resource waiting yourself.
-                } else {
-                    future.cleanup_finished();
+                    self.frame_pacer.wait_idle();
                 }
-
-                self.previous_frame_end = Some(future.boxed());
             }
             Err(FlushError::OutOfDate) => {
                 self.recreate_swapchain = true;
-                self.previous_frame_end =
-                    Some(sync::now(self.graphics_queue.device().clone()).boxed());
             }
             Err(e) => {
                 println!("Failed to flush future: {:?}", e);
-                self.previous_frame_end =
-                    Some(sync::now(self.graphics_queue.device().clone()).boxed());
             }
         }
     }
@@ -0,0 +1,489 @@
+// Copyright (c) 2022 The vulkano developers
+// Licensed under the Apache License, Version 2.0
+// <LICENSE-APACHE or
+// https://www.apache.org/licenses/LICENSE-2.0> or the MIT
+// license <LICENSE-MIT or https://opensource.org/licenses/MIT>,
+// at your option. All files in the project carrying such
+// notice may not be copied, modified, or distributed except
+// according to those terms.
+
+use crate::context::VulkanoContext;
+use std::{
+    error::Error,
+    fmt::{Display, Error as FmtError, Formatter},
+    mem::take,
+    sync::Arc,
+};
+use vulkano::{
+    buffer::{
+        staging_ring::{StagingRing, StagingRingCreateInfo},
+        BufferAccess, BufferContents, BufferUsage,
+    },
+    command_buffer::{
+        AutoCommandBufferBuilder, BuildError, CommandBufferBeginError, CommandBufferExecError,
+        CommandBufferUsage, CopyBufferInfo, CopyBufferToImageInfo, CopyError,
+        OwnershipTransferError, PrimaryAutoCommandBuffer,
+    },
+    device::{DeviceOwned, Queue},
+    image::ImageAccess,
+    memory::DeviceMemoryError,
+    sync::{self, FlushError, GpuFuture, QueueFamilyTransfer, Sharing},
+    VulkanObject,
+};
+
+/// The future returned by [`UploadScheduler::submit`].
+pub type UploadFuture = Box<dyn GpuFuture + Send>;
+
+/// Batches buffer and image uploads, and submits them on a transfer queue so that streaming data
+/// doesn't stall the queue that renders.
+///
+/// Uploads are recorded into a single command buffer until [`UploadScheduler::submit`] is called,
+/// which returns a future that the rendering code joins before using the uploaded resources. The
+/// data is staged in a [`StagingRing`], which moves on to its next frame on every submission.
+///
+/// # Queue family ownership
+///
+/// If the transfer queue and the destination queue belong to different queue families, the
+/// ownership of every destination resource created with [`Sharing::Exclusive`] is released by
+/// the transfer queue family at the end of the upload, and acquired by the destination queue
+/// family in a second submission on the destination queue.
+///
+/// The transfer queue family doesn't acquire the destination resources before writing to them,
+/// so an upload that only writes part of an exclusive resource leaves the rest of its contents
+/// undefined. Creating the resources with [`Sharing::Concurrent`] over the queue families
+/// returned by [`UploadScheduler::queue_family_indices`] avoids this, as well as the ownership
+/// transfers.
+///
+/// ## Examples
+///
+/// ```no_run
+/// use vulkano::buffer::{BufferUsage, DeviceLocalBuffer};
+/// use vulkano_util::{context::VulkanoContext, upload::UploadScheduler};
+///
+/// fn test(context: &VulkanoContext) {
+///     let mut uploads = UploadScheduler::from_context(context).unwrap();
+///     let buffer = DeviceLocalBuffer::<[u32]>::array(
+///         context.device().clone(),
+///         4,
+///         BufferUsage {
+///             transfer_dst: true,
+///             vertex_buffer: true,
+///             ..BufferUsage::empty()
+///         },
+///         uploads.queue_family_indices(),
+///     )
+///     .unwrap();
+///
+///     uploads.upload_buffer([1, 2, 3, 4], buffer).unwrap();
+///     let upload_future = uploads.submit().unwrap();
+///     // Join `upload_future` with the future of the next frame.
+/// }
+/// ```
+pub struct UploadScheduler {
+    transfer_queue: Arc<Queue>,
+    destination_queue: Arc<Queue>,
+    staging_ring: Arc<StagingRing>,
+    builder: Option<AutoCommandBufferBuilder<PrimaryAutoCommandBuffer>>,
+    ownership_transfers: Vec<OwnershipTransfer>,
+    pending_uploads: usize,
+    submitted_batches: u64,
+}
+
+impl UploadScheduler {
+    /// Creates a new [`UploadScheduler`] that records uploads on `transfer_queue`, for resources
+    /// that are then used on `destination_queue`.
+    ///
+    /// # Panics
+    ///
+    /// - Panics if the two queues don't belong to the same device.
+    pub fn new(
+        transfer_queue: Arc<Queue>,
+        destination_queue: Arc<Queue>,
+    ) -> Result<UploadScheduler, DeviceMemoryError> {
+        assert_eq!(transfer_queue.device(), destination_queue.device());
+
+        let staging_ring = StagingRing::new(
+            transfer_queue.device().clone(),
+            StagingRingCreateInfo {
+                usage: BufferUsage {
+                    transfer_src: true,
+                    ..BufferUsage::empty()
+                },
+                ..Default::default()
+            },
+        )?;
+
+        Ok(UploadScheduler {
+            transfer_queue,
+            destination_queue,
+            staging_ring,
+            builder: None,
+            ownership_transfers: Vec::new(),
+            pending_uploads: 0,
+            submitted_batches: 0,
+        })
+    }
+
+    /// Creates a new [`UploadScheduler`] that uploads on the transfer queue of `context`, for
+    /// resources that are used on its graphics queue.
+    #[inline]
+    pub fn from_context(context: &VulkanoContext) -> Result<UploadScheduler, DeviceMemoryError> {
+        UploadScheduler::new(
+            context.transfer_queue().clone(),
+            context.graphics_queue().clone(),
+        )
+    }
+
+    /// Returns the queue that uploads are submitted to.
+    #[inline]
+    pub fn transfer_queue(&self) -> &Arc<Queue> {
+        &self.transfer_queue
+    }
+
+    /// Returns the queue that the uploaded resources are used on.
+    #[inline]
+    pub fn destination_queue(&self) -> &Arc<Queue> {
+        &self.destination_queue
+    }
+
+    /// Returns the ring that the uploaded data is staged in.
+    #[inline]
+    pub fn staging_ring(&self) -> &Arc<StagingRing> {
+        &self.staging_ring
+    }
+
+    /// Returns the queue family indices that destination resources can be shared between to
+    /// avoid ownership transfers.
+    ///
+    /// Pass this to the `queue_family_indices` parameter when creating the resources.
+    pub fn queue_family_indices(&self) -> Vec<u32> {
+        let transfer = self.transfer_queue.queue_family_index();
+        let destination = self.destination_queue.queue_family_index();
+
+        if transfer == destination {
+            vec![transfer]
+        } else {
+            vec![transfer, destination]
+        }
+    }
+
+    /// Returns the number of uploads that are recorded but not yet submitted.
+    #[inline]
+    pub fn pending_uploads(&self) -> usize {
+        self.pending_uploads
+    }
+
+    /// Returns the number of batches that have been submitted so far.
+    #[inline]
+    pub fn submitted_batches(&self) -> u64 {
+        self.submitted_batches
+    }
+
+    /// Records an upload of `data` into `destination`, through the staging ring.
+    ///
+    /// `destination` must have the `transfer_dst` usage.
+    ///
+    /// # Panics
+    ///
+    /// - Panics if `data` is empty.
+    pub fn upload_buffer<T, I>(
+        &mut self,
+        data: I,
+        destination: Arc<dyn BufferAccess>,
+    ) -> Result<(), UploadError>
+    where
+        [T]: BufferContents,
+        I: IntoIterator<Item = T>,
+        I::IntoIter: ExactSizeIterator,
+    {
+        let staging_buffer = self.staging_ring.from_iter(data)?;
+        self.copy_buffer(CopyBufferInfo::buffers(staging_buffer, destination))
+    }
+
+    /// Records an upload of `data` into all mip level 0 layers of `destination`, through the
+    /// staging ring.
+    ///
+    /// `destination` must have the `transfer_dst` usage.
+    ///
+    /// # Panics
+    ///
+    /// - Panics if `data` is empty.
+    pub fn upload_image<T, I>(
+        &mut self,
+        data: I,
+        destination: Arc<dyn ImageAccess>,
+    ) -> Result<(), UploadError>
+    where
+        [T]: BufferContents,
+        I: IntoIterator<Item = T>,
+        I::IntoIter: ExactSizeIterator,
+    {
+        let staging_buffer = self.staging_ring.from_iter(data)?;
+        self.copy_buffer_to_image(CopyBufferToImageInfo::buffer_image(
+            staging_buffer,
+            destination,
+        ))
+    }
+
+    /// Records a copy between two existing buffers.
+    pub fn copy_buffer(
+        &mut self,
+        copy_buffer_info: impl Into<CopyBufferInfo>,
+    ) -> Result<(), UploadError> {
+        let copy_buffer_info = copy_buffer_info.into();
+        let dst_buffer = copy_buffer_info.dst_buffer.clone();
+        self.builder()?.copy_buffer(copy_buffer_info)?;
+        self.pending_uploads += 1;
+
+        let inner = dst_buffer.inner();
+        if self.needs_ownership_transfer(matches!(inner.buffer.sharing(), Sharing::Exclusive))
+            && !self
+                .ownership_transfers
+                .iter()
+                .any(|transfer| match transfer {
+                    OwnershipTransfer::Buffer(buffer) => {
+                        buffer.inner().buffer.internal_object() == inner.buffer.internal_object()
+                    }
+                    OwnershipTransfer::Image(_) => false,
+                })
+        {
+            self.ownership_transfers
+                .push(OwnershipTransfer::Buffer(dst_buffer));
+        }
+
+        Ok(())
+    }
+
+    /// Records a copy from an existing buffer to an image.
+    pub fn copy_buffer_to_image(
+        &mut self,
+        copy_buffer_to_image_info: CopyBufferToImageInfo,
+    ) -> Result<(), UploadError> {
+        let dst_image = copy_buffer_to_image_info.dst_image.clone();
+        self.builder()?
+            .copy_buffer_to_image(copy_buffer_to_image_info)?;
+        self.pending_uploads += 1;
+
+        let inner = dst_image.inner();
+        if self.needs_ownership_transfer(matches!(inner.image.sharing(), Sharing::Exclusive))
+            && !self
+                .ownership_transfers
+                .iter()
+                .any(|transfer| match transfer {
+                    OwnershipTransfer::Image(image) => {
+                        image.inner().image.internal_object() == inner.image.internal_object()
+                    }
+                    OwnershipTransfer::Buffer(_) => false,
+                })
+        {
+            self.ownership_transfers
+                .push(OwnershipTransfer::Image(dst_image));
+        }
+
+        Ok(())
+    }
+
+    /// Submits all pending uploads on the transfer queue.
+    ///
+    /// Returns `None` if there was nothing to upload. Otherwise, the returned future signals a
+    /// semaphore once the uploads are complete; join it with the future of the work that uses
+    /// the uploaded resources on the destination queue. If any ownership transfers were needed,
+    /// the acquiring command buffer has already been submitted on the destination queue.
+    pub fn submit(&mut self) -> Result<Option<UploadFuture>, UploadError> {
+        let mut builder = match self.builder.take() {
+            Some(builder) => builder,
+            None => return Ok(None),
+        };
+        self.pending_uploads = 0;
+        let ownership_transfers = take(&mut self.ownership_transfers);
+        let queue_family_transfer = QueueFamilyTransfer {
+            source_index: self.transfer_queue.queue_family_index(),
+            destination_index: self.destination_queue.queue_family_index(),
+        };
+
+        // SAFETY: Every release is matched by an acquire below, which is submitted after the
+        // release and waits for its semaphore. Nothing is recorded after the releases.
+        for ownership_transfer in &ownership_transfers {
+            unsafe { ownership_transfer.record(&mut builder, queue_family_transfer)? };
+        }
+
+        let command_buffer = builder.build()?;
+        let future = sync::now(self.transfer_queue.device().clone())
+            .then_execute(self.transfer_queue.clone(), command_buffer)?
+            .then_signal_semaphore_and_flush()?;
+        self.staging_ring.next_frame();
+        self.submitted_batches += 1;
+
+        if ownership_transfers.is_empty() {
+            return Ok(Some(future.boxed_send()));
+        }
+
+        // The acquiring command buffer is only recorded once the release has been submitted, so
+        // that it picks up the layouts that the uploads left the images in.
+        let mut builder = AutoCommandBufferBuilder::primary(
+            self.destination_queue.device().clone(),
+            self.destination_queue.queue_family_index(),
+            CommandBufferUsage::OneTimeSubmit,
+        )?;
+
+        for ownership_transfer in &ownership_transfers {
+            unsafe { ownership_transfer.record(&mut builder, queue_family_transfer)? };
+        }
+
+        let command_buffer = builder.build()?;
+        let future = future
+            .then_execute(self.destination_queue.clone(), command_buffer)?
+            .then_signal_semaphore_and_flush()?;
+
+        Ok(Some(future.boxed_send()))
+    }
+
+    fn builder(
+        &mut self,
+    ) -> Result<&mut AutoCommandBufferBuilder<PrimaryAutoCommandBuffer>, UploadError> {
+        if self.builder.is_none() {
+            self.builder = Some(AutoCommandBufferBuilder::primary(
+                self.transfer_queue.device().clone(),
+                self.transfer_queue.queue_family_index(),
+                CommandBufferUsage::OneTimeSubmit,
+            )?);
+        }
+
+        Ok(self.builder.as_mut().unwrap())
+    }
+
+    fn needs_ownership_transfer(&self, exclusive: bool) -> bool {
+        exclusive
+            && self.transfer_queue.queue_family_index()
+                != self.destination_queue.queue_family_index()
+    }
+}
+
+/// A destination resource whose queue family ownership is transferred at submission.
+enum OwnershipTransfer {
+    Buffer(Arc<dyn BufferAccess>),
+    Image(Arc<dyn ImageAccess>),
+}
+
+impl OwnershipTransfer {
+    unsafe fn record(
+        &self,
+        builder: &mut AutoCommandBufferBuilder<PrimaryAutoCommandBuffer>,
+        transfer: QueueFamilyTransfer,
+    ) -> Result<(), OwnershipTransferError> {
+        match self {
+            Self::Buffer(buffer) => builder.transfer_buffer_ownership(buffer.clone(), transfer)?,
+            Self::Image(image) => builder.transfer_image_ownership(image.clone(), transfer)?,
+        };
+
+        Ok(())
+    }
+}
+
+/// Error that can happen when uploading with an [`UploadScheduler`].
+#[derive(Clone, Debug)]
+pub enum UploadError {
+    /// Allocating the staging buffer failed.
+    DeviceMemoryError(DeviceMemoryError),
+
+    /// Beginning the upload command buffer failed.
+    CommandBufferBeginError(CommandBufferBeginError),
+
+    /// Recording a copy command failed.
+    CopyError(CopyError),
+
+    /// Building the upload command buffer failed.
+    BuildError(BuildError),
+
+    /// Executing the upload command buffer failed.
+    CommandBufferExecError(CommandBufferExecError),
+
+    /// Submitting the upload command buffer failed.
+    FlushError(FlushError),
+
+    /// Recording a queue family ownership transfer failed.
+    OwnershipTransferError(OwnershipTransferError),
+}
+
+impl Error for UploadError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            Self::DeviceMemoryError(err) => Some(err),
+            Self::CommandBufferBeginError(err) => Some(err),
+            Self::CopyError(err) => Some(err),
+            Self::BuildError(err) => Some(err),
+            Self::CommandBufferExecError(err) => Some(err),
+            Self::FlushError(err) => Some(err),
+            Self::OwnershipTransferError(err) => Some(err),
+        }
+    }
+}
+
+impl Display for UploadError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result<(), FmtError> {
+        match self {
+            Self::DeviceMemoryError(_) => write!(f, "allocating the staging buffer failed"),
+            Self::CommandBufferBeginError(_) => {
+                write!(f, "beginning the upload command buffer failed")
+            }
+            Self::CopyError(_) => write!(f, "recording a copy command failed"),
+            Self::BuildError(_) => write!(f, "building the upload command buffer failed"),
+            Self::CommandBufferExecError(_) => {
+                write!(f, "executing the upload command buffer failed")
+            }
+            Self::FlushError(_) => write!(f, "submitting the upload command buffer failed"),
+            Self::OwnershipTransferError(_) => {
+                write!(f, "recording a queue family ownership transfer failed")
+            }
+        }
+    }
+}
+
+impl From<DeviceMemoryError> for UploadError {
+    #[inline]
+    fn from(err: DeviceMemoryError) -> Self {
+        Self::DeviceMemoryError(err)
+    }
+}
+
+impl From<CommandBufferBeginError> for UploadError {
+    #[inline]
+    fn from(err: CommandBufferBeginError) -> Self {
+        Self::CommandBufferBeginError(err)
+    }
+}
+
+impl From<CopyError> for UploadError {
+    #[inline]
+    fn from(err: CopyError) -> Self {
+        Self::CopyError(err)
+    }
+}
+
+impl From<BuildError> for UploadError {
+    #[inline]
+    fn from(err: BuildError) -> Self {
+        Self::BuildError(err)
+    }
+}
+
+impl From<CommandBufferExecError> for UploadError {
+    #[inline]
+    fn from(err: CommandBufferExecError) -> Self {
+        Self::CommandBufferExecError(err)
+    }
+}
+
+impl From<FlushError> for UploadError {
+    #[inline]
+    fn from(err: FlushError) -> Self {
+        Self::FlushError(err)
+    }
+}
+
+impl From<OwnershipTransferError> for UploadError {
+    #[inline]
+    fn from(err: OwnershipTransferError) -> Self {
+        Self::OwnershipTransferError(err)
+    }
+}
@@ -11,7 +11,9 @@
 // notice may not be copied, modified, or distributed except
 // according to those terms.
 
-use crate::{context::VulkanoContext, renderer::VulkanoWindowRenderer};
+use crate::{
+    context::VulkanoContext, frame_pacer::FramePacerConfig, renderer::VulkanoWindowRenderer,
+};
 use ahash::HashMap;
 use std::collections::hash_map::{Iter, IterMut};
 use vulkano::swapchain::{PresentMode, SwapchainCreateInfo};
@@ -32,7 +34,7 @@ use winit::{
 ///
 /// #[test]
 /// fn test() {
-///     let context = VulkanoContext::new(VulkanoConfig::default());
+///     let context = VulkanoContext::new(VulkanoConfig::default()).unwrap();
 ///     let event_loop = EventLoop::new();
 ///     let mut vulkano_windows = VulkanoWindows::default();
 ///     let _id1 = vulkano_windows.create_window(&event_loop, &context, &Default::default(), |_| {});
@@ -361,6 +363,8 @@ pub struct WindowDescriptor {
     pub mode: WindowMode,
     /// Sets whether the background of the window should be transparent.
     pub transparent: bool,
+    /// Sets the frames-in-flight limit, latency target and timing of the window's renderer.
+    pub frame_pacing: FramePacerConfig,
 }
 
 impl Default for WindowDescriptor {
@@ -380,6 +384,7 @@ impl Default for WindowDescriptor {
             cursor_visible: true,
             mode: WindowMode::Windowed,
             transparent: false,
+            frame_pacing: FramePacerConfig::default(),
         }
     }
 }
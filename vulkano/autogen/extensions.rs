@@ -173,6 +173,78 @@ fn device_extensions_output(members: &[ExtensionsMember]) -> TokenStream {
         }
     });
 
+    let add_dependencies_items =
+        members
+            .iter()
+            .filter_map(|ExtensionsMember { name, requires, .. }| {
+                let requires_items: Vec<_> = requires
+                    .iter()
+                    .filter(|require| !require.device_extensions.is_empty())
+                    .map(|require| {
+                        let require_items = require
+                            .api_version
+                            .iter()
+                            .map(|version| {
+                                let version = format_ident!("V{}_{}", version.0, version.1);
+                                quote! { api_version >= crate::Version::#version }
+                            })
+                            .chain(require.instance_extensions.iter().map(|ext| {
+                                quote! { instance_extensions.#ext }
+                            }))
+                            .chain(require.device_extensions.iter().map(|ext| {
+                                quote! { device_extensions.#ext }
+                            }));
+                        let device_extensions_items = &require.device_extensions;
+
+                        quote! {
+                            if !(#(#require_items)||*) {
+                                #(if supported.#device_extensions_items {
+                                    self.#device_extensions_items = true;
+                                } else)* {}
+                            }
+                        }
+                    })
+                    .collect();
+
+                (!requires_items.is_empty()).then(|| {
+                    quote! {
+                        if device_extensions.#name {
+                            #(#requires_items)*
+                        }
+                    }
+                })
+            });
+
+    let usable_items = members
+        .iter()
+        .filter(|ExtensionsMember { requires, .. }| !requires.is_empty())
+        .map(|ExtensionsMember { name, requires, .. }| {
+            let requires_items = requires.iter().map(|require| {
+                let require_items = require
+                    .api_version
+                    .iter()
+                    .map(|version| {
+                        let version = format_ident!("V{}_{}", version.0, version.1);
+                        quote! { api_version >= crate::Version::#version }
+                    })
+                    .chain(require.instance_extensions.iter().map(|ext| {
+                        quote! { instance_extensions.#ext }
+                    }))
+                    .chain(require.device_extensions.iter().map(|ext| {
+                        quote! { usable.#ext }
+                    }));
+
+                quote! { (#(#require_items)||*) }
+            });
+
+            quote! {
+                if usable.#name && !(#(#requires_items)&&*) {
+                    usable.#name = false;
+                    changed = true;
+                }
+            }
+        });
+
     quote! {
         #common
 
@@ -188,6 +260,39 @@ fn device_extensions_output(members: &[ExtensionsMember]) -> TokenStream {
                 #(#check_requirements_items)*
                 Ok(())
             }
+
+            /// Enables the supported device extensions that the enabled extensions depend on,
+            /// if the dependency isn't already met. This only looks at the extensions that were
+            /// enabled when it was called, so the caller must call it again until it stops
+            /// enabling extensions, to also add the dependencies of the newly enabled ones.
+            pub(crate) fn add_dependencies(
+                &mut self,
+                supported: &DeviceExtensions,
+                api_version: crate::Version,
+                instance_extensions: &crate::instance::InstanceExtensions,
+            ) {
+                let device_extensions = *self;
+                #(#add_dependencies_items)*
+            }
+
+            /// Returns the extensions of `supported` whose dependencies can all be met, either by
+            /// `api_version` and `instance_extensions`, or by other returned extensions.
+            pub(crate) fn usable(
+                supported: &DeviceExtensions,
+                api_version: crate::Version,
+                instance_extensions: &crate::instance::InstanceExtensions,
+            ) -> DeviceExtensions {
+                let mut usable = *supported;
+
+                loop {
+                    let mut changed = false;
+                    #(#usable_items)*
+
+                    if !changed {
+                        return usable;
+                    }
+                }
+            }
         }
     }
 }
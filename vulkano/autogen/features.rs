@@ -60,9 +60,9 @@ fn required_by_extensions(name: &str) -> &'static [(&'static str, &'static str)]
 }
 
 pub fn write(vk_data: &VkRegistryData) {
-    let features_output = features_output(&features_members(&vk_data.types));
-    let features_ffi_output =
-        features_ffi_output(&features_ffi_members(&vk_data.types, &vk_data.extensions));
+    let features_ffi_members = features_ffi_members(&vk_data.types, &vk_data.extensions);
+    let features_output = features_output(&features_members(&vk_data.types), &features_ffi_members);
+    let features_ffi_output = features_ffi_output(&features_ffi_members);
     write_file(
         "features.rs",
         format!(
@@ -89,7 +89,7 @@ struct FeaturesMember {
     optional: bool,
 }
 
-fn features_output(members: &[FeaturesMember]) -> TokenStream {
+fn features_output(members: &[FeaturesMember], ffi_members: &[FeaturesFfiMember]) -> TokenStream {
     let struct_items = members.iter().map(|FeaturesMember { name, doc, .. }| {
         quote! {
             #[doc = #doc]
@@ -158,6 +158,97 @@ fn features_output(members: &[FeaturesMember]) -> TokenStream {
         },
     );
 
+    let add_required_extensions_items = members.iter().filter(|member| member.optional).map(
+        |FeaturesMember {
+             name,
+             ffi_members: member_ffi_members,
+             ..
+         }| {
+            let ffi_members: Vec<_> = member_ffi_members
+                .iter()
+                .map(|(ffi_member, _)| {
+                    ffi_members
+                        .iter()
+                        .find(|ffi| &ffi.name == ffi_member)
+                        .unwrap()
+                })
+                .collect();
+            let provided_by_items =
+                ffi_members
+                    .iter()
+                    .map(|FeaturesFfiMember { provided_by, .. }| {
+                        quote! { [#(#provided_by),*].into_iter().any(|x| x) }
+                    });
+            let mut provided_by_device_extensions: Vec<&Ident> = Vec::new();
+
+            for ffi in &ffi_members {
+                for extension in &ffi.provided_by_device_extensions {
+                    if !provided_by_device_extensions.contains(&extension) {
+                        provided_by_device_extensions.push(extension);
+                    }
+                }
+            }
+
+            quote! {
+                if self.#name && !(#(#provided_by_items)||*) {
+                    #(if supported.#provided_by_device_extensions {
+                        device_extensions.#provided_by_device_extensions = true;
+                    } else)* {}
+                }
+            }
+        },
+    );
+
+    let add_dependencies_items = members.iter().map(
+        |FeaturesMember {
+             name,
+             requires_features,
+             required_by_extensions,
+             ..
+         }| {
+            let requires_features_items = requires_features.iter().map(|feature| {
+                quote! {
+                    if self.#name && supported.#feature {
+                        self.#feature = true;
+                    }
+                }
+            });
+            let required_by_extensions_items =
+                required_by_extensions.iter().map(|(version, extension)| {
+                    quote! {
+                        if extensions.#extension
+                            && api_version >= crate::Version::#version
+                            && supported.#name
+                        {
+                            self.#name = true;
+                        }
+                    }
+                });
+
+            quote! {
+                #(#requires_features_items)*
+                #(#required_by_extensions_items)*
+            }
+        },
+    );
+
+    let usable_items = members
+        .iter()
+        .filter(|member| !member.requires_features.is_empty())
+        .map(
+            |FeaturesMember {
+                 name,
+                 requires_features,
+                 ..
+             }| {
+                quote! {
+                    if !(#(supported.#requires_features)&&*) {
+                        usable.#name = false;
+                    }
+                }
+            },
+        );
+
     let empty_items = members.iter().map(|FeaturesMember { name, .. }| {
         quote! {
             #name: false,
@@ -336,6 +427,36 @@ fn features_output(members: &[FeaturesMember]) -> TokenStream {
                 Ok(())
             }
 
+            /// Enables the supported device extensions that provide the enabled features, if
+            /// none of the structures that contain a feature is available with `api_version` and
+            /// `device_extensions`.
+            pub(crate) fn add_required_extensions(
+                &self,
+                device_extensions: &mut crate::device::DeviceExtensions,
+                supported: &crate::device::DeviceExtensions,
+                api_version: crate::Version,
+            ) {
+                #(#add_required_extensions_items)*
+            }
+
+            /// Enables the supported features that the enabled features depend on, and the
+            /// supported features that must be enabled together with `extensions`.
+            pub(crate) fn add_dependencies(
+                &mut self,
+                supported: &Features,
+                api_version: crate::Version,
+                extensions: &crate::device::DeviceExtensions,
+            ) {
+                #(#add_dependencies_items)*
+            }
+
+            /// Returns the features of `supported` whose dependencies are also supported.
+            pub(crate) fn usable(supported: &Features) -> Features {
+                let mut usable = *supported;
+                #(#usable_items)*
+                usable
+            }
+
             /// Returns an `Features` object with none of the members set.
             #[inline]
             pub const fn empty() -> Self {
@@ -664,6 +785,7 @@ struct FeaturesFfiMember {
     name: Ident,
     ty: Ident,
     provided_by: Vec<TokenStream>,
+    provided_by_device_extensions: Vec<Ident>,
     conflicts: Vec<Ident>,
 }
 
@@ -730,6 +852,23 @@ fn features_ffi_members<'a>(
         .into_iter()
         .map(|(ty, provided_by)| {
             let ty_name = ty.name.as_ref().unwrap();
+            let provided_by_device_extensions = provided_by
+                .iter()
+                .filter(|provided_by| {
+                    extensions
+                        .get(**provided_by)
+                        .map_or(false, |ext| ext.ext_type.as_deref() == Some("device"))
+                })
+                .map(|provided_by| {
+                    format_ident!(
+                        "{}",
+                        provided_by
+                            .strip_prefix("VK_")
+                            .unwrap()
+                            .to_ascii_lowercase(),
+                    )
+                })
+                .collect();
             let provided_by = provided_by
                 .iter()
                 .map(|provided_by| {
@@ -776,6 +915,7 @@ fn features_ffi_members<'a>(
                 name: format_ident!("{}", ffi_member(ty_name)),
                 ty: format_ident!("{}", ty_name.strip_prefix("Vk").unwrap()),
                 provided_by,
+                provided_by_device_extensions,
                 conflicts: conflicts
                     .into_iter()
                     .map(|s| format_ident!("{}", s))
@@ -502,6 +502,14 @@ where
                 .flush_range(self.memory_range.clone())
                 .unwrap();
 
+            // Find the buffers whose device addresses were written, so that they are tracked
+            // when this buffer is used by a shader.
+            let referenced_buffers = self
+                .inner
+                .device()
+                .buffers_at_device_addresses(self.data.as_bytes());
+            self.inner.inner.set_referenced_buffers(&referenced_buffers);
+
             let mut state = self.inner.inner.state();
             state.cpu_write_unlock(self.buffer_range.clone());
         }
@@ -0,0 +1,369 @@
+// Copyright (c) 2022 The vulkano developers
+// Licensed under the Apache License, Version 2.0
+// <LICENSE-APACHE or
+// https://www.apache.org/licenses/LICENSE-2.0> or the MIT
+// license <LICENSE-MIT or https://opensource.org/licenses/MIT>,
+// at your option. All files in the project carrying such
+// notice may not be copied, modified, or distributed except
+// according to those terms.
+
+use super::BufferAccess;
+use crate::{device::DeviceOwned, DeviceSize};
+use bytemuck::{Pod, Zeroable};
+use std::{
+    fmt::{Debug, Error as FmtError, Formatter},
+    hash::{Hash, Hasher},
+    marker::PhantomData,
+    mem::size_of,
+    num::NonZeroU64,
+    sync::Arc,
+};
+
+/// A typed pointer to the contents of a buffer in device memory.
+///
+/// This is obtained from [`TypedBufferAccess::device_address`], and keeps the buffer alive. Its
+/// [raw value](Self::raw) can be put in push constants or in the contents of other buffers.
+///
+/// While a `DeviceAddress` to a buffer is alive, the buffer is automatically included in the
+/// resource tracking of a command buffer when its address is used:
+///
+/// - in push constants, passed to [`AutoCommandBufferBuilder::push_constants`];
+/// - in the contents of a [`CpuAccessibleBuffer`] written from the host, or of a
+///   [`DeviceLocalBuffer`] created from such a buffer, when that buffer is accessed by a shader.
+///
+/// The command buffer then keeps the buffer alive for as long as it exists. Addresses that reach
+/// the device in any other way, for example through a buffer written by a shader, must be
+/// declared with [`AutoCommandBufferBuilder::reference_buffer`].
+///
+/// Any 64-bit value in the pushed or written data that falls within a buffer with a live
+/// `DeviceAddress` is treated as a reference to it, even if it was not meant as an address.
+///
+/// [`TypedBufferAccess::device_address`]: crate::buffer::TypedBufferAccess::device_address
+/// [`AutoCommandBufferBuilder::push_constants`]: crate::command_buffer::AutoCommandBufferBuilder::push_constants
+/// [`AutoCommandBufferBuilder::reference_buffer`]: crate::command_buffer::AutoCommandBufferBuilder::reference_buffer
+/// [`CpuAccessibleBuffer`]: crate::buffer::CpuAccessibleBuffer
+/// [`DeviceLocalBuffer`]: crate::buffer::DeviceLocalBuffer
+pub struct DeviceAddress<T: ?Sized> {
+    buffer: Arc<dyn BufferAccess>,
+    address: RawDeviceAddress<T>,
+}
+
+impl<T: ?Sized> DeviceAddress<T> {
+    /// Registers the address of `buffer` with its device.
+    pub(crate) fn new(buffer: Arc<dyn BufferAccess>, address: NonZeroU64) -> Self {
+        buffer
+            .device()
+            .register_buffer_device_address(address.get(), &buffer);
+
+        DeviceAddress {
+            buffer,
+            address: RawDeviceAddress::from_raw(address.get()),
+        }
+    }
+
+    /// Returns the buffer that the address points to.
+    #[inline]
+    pub fn buffer(&self) -> &Arc<dyn BufferAccess> {
+        &self.buffer
+    }
+
+    /// Returns the raw address, to be put in push constants or in the contents of other buffers.
+    #[inline]
+    pub fn raw(&self) -> RawDeviceAddress<T> {
+        self.address
+    }
+
+    /// Reinterprets the address as pointing to a value of type `U`.
+    #[inline]
+    pub fn cast<U: ?Sized>(self) -> DeviceAddress<U> {
+        DeviceAddress {
+            buffer: self.buffer,
+            address: self.address.cast(),
+        }
+    }
+}
+
+impl<T> DeviceAddress<[T]> {
+    /// Returns the address of the element at `index`.
+    ///
+    /// # Panics
+    ///
+    /// - Panics if `index` is out of bounds of the buffer.
+    #[inline]
+    pub fn index(&self, index: DeviceSize) -> DeviceAddress<T> {
+        assert!((index + 1) * size_of::<T>() as DeviceSize <= self.buffer.size());
+
+        DeviceAddress {
+            buffer: self.buffer.clone(),
+            address: self.address.index(index),
+        }
+    }
+}
+
+impl<T: ?Sized> Clone for DeviceAddress<T> {
+    #[inline]
+    fn clone(&self) -> Self {
+        DeviceAddress {
+            buffer: self.buffer.clone(),
+            address: self.address,
+        }
+    }
+}
+
+impl<T: ?Sized> Debug for DeviceAddress<T> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result<(), FmtError> {
+        write!(f, "DeviceAddress({:#x})", self.address.raw())
+    }
+}
+
+impl<T: ?Sized> PartialEq for DeviceAddress<T> {
+    #[inline]
+    fn eq(&self, other: &Self) -> bool {
+        self.address == other.address
+    }
+}
+
+impl<T: ?Sized> Eq for DeviceAddress<T> {}
+
+impl<T: ?Sized> Hash for DeviceAddress<T> {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.address.hash(state);
+    }
+}
+
+impl<T: ?Sized> From<&DeviceAddress<T>> for RawDeviceAddress<T> {
+    #[inline]
+    fn from(val: &DeviceAddress<T>) -> Self {
+        val.address
+    }
+}
+
+/// The raw value of a [`DeviceAddress`], as seen by shaders.
+///
+/// This corresponds to a GLSL `buffer_reference` type, or a SPIR-V pointer in the
+/// `PhysicalStorageBuffer` storage class, and is the type that vulkano-shaders generates for
+/// them. It has the same layout as a `u64`, so it can be put in push constants or in the contents
+/// of other buffers.
+///
+/// A `RawDeviceAddress` does not keep its buffer alive by itself. The buffer is found again when
+/// the address is used, as long as the [`DeviceAddress`] that it was obtained from is alive.
+#[repr(transparent)]
+pub struct RawDeviceAddress<T: ?Sized> {
+    address: u64,
+    marker: PhantomData<fn() -> *const T>,
+}
+
+impl<T: ?Sized> RawDeviceAddress<T> {
+    /// Returns a null address.
+    #[inline]
+    pub const fn null() -> Self {
+        RawDeviceAddress {
+            address: 0,
+            marker: PhantomData,
+        }
+    }
+
+    /// Creates a `RawDeviceAddress` from a raw address.
+    ///
+    /// This is safe because the address isn't directly dereferencable. Unsafe code is required to
+    /// dereference the value in a shader.
+    #[inline]
+    pub const fn from_raw(address: u64) -> Self {
+        RawDeviceAddress {
+            address,
+            marker: PhantomData,
+        }
+    }
+
+    /// Returns the raw address.
+    #[inline]
+    pub const fn raw(self) -> u64 {
+        self.address
+    }
+
+    /// Returns the raw address, or `None` if the address is null.
+    #[inline]
+    pub const fn non_null(self) -> Option<NonZeroU64> {
+        NonZeroU64::new(self.address)
+    }
+
+    /// Returns whether the address is null.
+    #[inline]
+    pub const fn is_null(self) -> bool {
+        self.address == 0
+    }
+
+    /// Reinterprets the address as pointing to a value of type `U`.
+    #[inline]
+    pub const fn cast<U: ?Sized>(self) -> RawDeviceAddress<U> {
+        RawDeviceAddress {
+            address: self.address,
+            marker: PhantomData,
+        }
+    }
+}
+
+impl<T> RawDeviceAddress<[T]> {
+    /// Returns the address of the element at `index`.
+    ///
+    /// No bounds checking is performed.
+    #[inline]
+    pub const fn index(self, index: DeviceSize) -> RawDeviceAddress<T> {
+        RawDeviceAddress {
+            address: self.address + index * size_of::<T>() as DeviceSize,
+            marker: PhantomData,
+        }
+    }
+}
+
+impl<T: ?Sized> Clone for RawDeviceAddress<T> {
+    #[inline]
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<T: ?Sized> Copy for RawDeviceAddress<T> {}
+
+impl<T: ?Sized> Default for RawDeviceAddress<T> {
+    #[inline]
+    fn default() -> Self {
+        Self::null()
+    }
+}
+
+impl<T: ?Sized> Debug for RawDeviceAddress<T> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result<(), FmtError> {
+        write!(f, "RawDeviceAddress({:#x})", self.address)
+    }
+}
+
+impl<T: ?Sized> PartialEq for RawDeviceAddress<T> {
+    #[inline]
+    fn eq(&self, other: &Self) -> bool {
+        self.address == other.address
+    }
+}
+
+impl<T: ?Sized> Eq for RawDeviceAddress<T> {}
+
+impl<T: ?Sized> Hash for RawDeviceAddress<T> {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.address.hash(state);
+    }
+}
+
+impl<T: ?Sized> From<RawDeviceAddress<T>> for u64 {
+    #[inline]
+    fn from(val: RawDeviceAddress<T>) -> Self {
+        val.address
+    }
+}
+
+unsafe impl<T: ?Sized> Zeroable for RawDeviceAddress<T> {}
+
+unsafe impl<T: ?Sized + 'static> Pod for RawDeviceAddress<T> {}
+
+#[cfg(test)]
+mod tests {
+    use super::RawDeviceAddress;
+    use crate::{
+        buffer::{BufferAccess, BufferUsage, CpuAccessibleBuffer, TypedBufferAccess},
+        command_buffer::{AutoCommandBufferBuilder, CommandBufferUsage},
+        pipeline::layout::{PipelineLayout, PipelineLayoutCreateInfo, PushConstantRange},
+        shader::ShaderStages,
+    };
+    use std::{
+        mem::{align_of, size_of},
+        sync::Arc,
+    };
+
+    #[test]
+    fn layout() {
+        assert_eq!(size_of::<RawDeviceAddress<[f32]>>(), size_of::<u64>());
+        assert_eq!(align_of::<RawDeviceAddress<u32>>(), align_of::<u64>());
+    }
+
+    #[test]
+    fn index() {
+        let address = RawDeviceAddress::<[[f32; 4]]>::from_raw(0x1000);
+        assert_eq!(address.index(0).raw(), 0x1000);
+        assert_eq!(address.index(3).raw(), 0x1030);
+        assert!(RawDeviceAddress::<u32>::null().non_null().is_none());
+    }
+
+    #[test]
+    fn push_constants_keep_buffer_alive() {
+        let (device, queue) = gfx_dev_and_queue!(buffer_device_address);
+
+        let buffer = CpuAccessibleBuffer::from_data(
+            device.clone(),
+            BufferUsage {
+                storage_buffer: true,
+                shader_device_address: true,
+                ..BufferUsage::empty()
+            },
+            false,
+            0u32,
+        )
+        .unwrap();
+        let address = buffer.device_address().unwrap();
+        let weak = Arc::downgrade(&buffer);
+
+        let pipeline_layout = PipelineLayout::new(
+            device.clone(),
+            PipelineLayoutCreateInfo {
+                push_constant_ranges: vec![PushConstantRange {
+                    stages: ShaderStages {
+                        compute: true,
+                        ..ShaderStages::empty()
+                    },
+                    offset: 0,
+                    size: 8,
+                }],
+                ..Default::default()
+            },
+        )
+        .unwrap();
+
+        let mut builder = AutoCommandBufferBuilder::primary(
+            device,
+            queue.queue_family_index(),
+            CommandBufferUsage::OneTimeSubmit,
+        )
+        .unwrap();
+        builder.push_constants(pipeline_layout, 0, address.raw());
+        drop(address);
+        drop(buffer);
+
+        let command_buffer = builder.build().unwrap();
+        assert!(weak.upgrade().is_some());
+
+        drop(command_buffer);
+        assert!(weak.upgrade().is_none());
+    }
+
+    #[test]
+    fn written_address_referenced() {
+        let (device, _queue) = gfx_dev_and_queue!(buffer_device_address);
+
+        let usage = BufferUsage {
+            storage_buffer: true,
+            shader_device_address: true,
+            ..BufferUsage::empty()
+        };
+        let target =
+            CpuAccessibleBuffer::from_iter(device.clone(), usage, false, [0u32; 4]).unwrap();
+        let address = target.device_address().unwrap();
+
+        let container =
+            CpuAccessibleBuffer::from_data(device.clone(), usage, false, address.index(2).raw())
+                .unwrap();
+        let referenced = container.inner().buffer.referenced_buffers();
+        assert_eq!(referenced.len(), 1);
+        assert!(*referenced[0] == **address.buffer());
+
+        *container.write().unwrap() = RawDeviceAddress::null();
+        assert!(container.inner().buffer.referenced_buffers().is_empty());
+    }
+}
@@ -27,12 +27,12 @@ use crate::{
     device::{Device, DeviceOwned, Queue},
     memory::{
         pool::{
-            alloc_dedicated_with_exportable_fd, AllocFromRequirementsFilter, AllocLayout,
-            MappingRequirement, MemoryPoolAlloc, PotentialDedicatedAllocation,
+            alloc_dedicated_with_exportable_fd, alloc_import_from_fd, AllocFromRequirementsFilter,
+            AllocLayout, MappingRequirement, MemoryPoolAlloc, PotentialDedicatedAllocation,
             StandardMemoryPoolAlloc,
         },
-        DedicatedAllocation, DeviceMemoryError, ExternalMemoryHandleType, MemoryPool,
-        MemoryRequirements,
+        DedicatedAllocation, DeviceMemoryError, ExternalMemoryHandleType,
+        ExternalMemoryHandleTypes, MemoryPool, MemoryRequirements,
     },
     sync::{NowFuture, Sharing},
     DeviceSize,
@@ -212,6 +212,11 @@ where
                     .copied(),
             )?;
 
+            // The copied contents reference the same buffers as the source.
+            buffer
+                .inner
+                .set_referenced_buffers(&source.inner().buffer.referenced_buffers());
+
             let mut cbb = AutoCommandBufferBuilder::primary(
                 source.device().clone(),
                 queue.queue_family_index(),
@@ -268,6 +273,30 @@ where
         )?;
         DeviceLocalBuffer::from_buffer(source, usage, queue)
     }
+
+    /// Same as `from_data`, but also gives the buffer a name.
+    ///
+    /// See [`UnsafeBuffer::set_name`] for how the name is used.
+    ///
+    /// # Panics
+    ///
+    /// - Panics if `T` has zero size.
+    pub fn from_data_named(
+        data: T,
+        usage: BufferUsage,
+        queue: Arc<Queue>,
+        name: &str,
+    ) -> Result<
+        (Arc<DeviceLocalBuffer<T>>, DeviceLocalBufferFromBufferFuture),
+        DeviceLocalBufferCreationError,
+    > {
+        let (buffer, future) = DeviceLocalBuffer::from_data(data, usage, queue)?;
+        buffer
+            .set_name(Some(name))
+            .map_err(DeviceMemoryError::from)?;
+
+        Ok((buffer, future))
+    }
 }
 
 impl<T> DeviceLocalBuffer<[T]>
@@ -304,6 +333,38 @@ where
         )?;
         DeviceLocalBuffer::from_buffer(source, usage, queue)
     }
+
+    /// Same as `from_iter`, but also gives the buffer a name.
+    ///
+    /// See [`UnsafeBuffer::set_name`] for how the name is used.
+    ///
+    /// # Panics
+    ///
+    /// - Panics if `T` has zero size.
+    /// - Panics if `data` is empty.
+    pub fn from_iter_named<D>(
+        data: D,
+        usage: BufferUsage,
+        queue: Arc<Queue>,
+        name: &str,
+    ) -> Result<
+        (
+            Arc<DeviceLocalBuffer<[T]>>,
+            DeviceLocalBufferFromBufferFuture,
+        ),
+        DeviceLocalBufferCreationError,
+    >
+    where
+        D: IntoIterator<Item = T>,
+        D::IntoIter: ExactSizeIterator,
+    {
+        let (buffer, future) = DeviceLocalBuffer::from_iter(data, usage, queue)?;
+        buffer
+            .set_name(Some(name))
+            .map_err(DeviceMemoryError::from)?;
+
+        Ok((buffer, future))
+    }
 }
 
 impl<T> DeviceLocalBuffer<[T]>
@@ -354,7 +415,13 @@ where
     ) -> Result<Arc<DeviceLocalBuffer<T>>, DeviceMemoryError> {
         let queue_family_indices: SmallVec<[_; 4]> = queue_family_indices.into_iter().collect();
 
-        let (buffer, mem_reqs) = Self::build_buffer(&device, size, usage, &queue_family_indices)?;
+        let (buffer, mem_reqs) = Self::build_buffer(
+            &device,
+            size,
+            usage,
+            &queue_family_indices,
+            ExternalMemoryHandleTypes::empty(),
+        )?;
 
         let memory = MemoryPool::alloc_from_requirements(
             &device.standard_memory_pool(),
@@ -392,12 +459,40 @@ where
         usage: BufferUsage,
         queue_family_indices: impl IntoIterator<Item = u32>,
     ) -> Result<Arc<DeviceLocalBuffer<T>>, DeviceMemoryError> {
-        assert!(device.enabled_extensions().khr_external_memory_fd);
-        assert!(device.enabled_extensions().khr_external_memory);
+        Self::raw_with_exportable_handle_types(
+            device,
+            size,
+            usage,
+            queue_family_indices,
+            ExternalMemoryHandleTypes {
+                opaque_fd: true,
+                ..ExternalMemoryHandleTypes::empty()
+            },
+        )
+    }
 
+    /// Same as `raw`, but the memory is allocated so that it can be exported as any of
+    /// `export_handle_types` with [`export_fd`](Self::export_fd).
+    ///
+    /// # Panics
+    ///
+    /// - Panics if `size` is zero.
+    pub unsafe fn raw_with_exportable_handle_types(
+        device: Arc<Device>,
+        size: DeviceSize,
+        usage: BufferUsage,
+        queue_family_indices: impl IntoIterator<Item = u32>,
+        export_handle_types: ExternalMemoryHandleTypes,
+    ) -> Result<Arc<DeviceLocalBuffer<T>>, DeviceMemoryError> {
         let queue_family_indices: SmallVec<[_; 4]> = queue_family_indices.into_iter().collect();
 
-        let (buffer, mem_reqs) = Self::build_buffer(&device, size, usage, &queue_family_indices)?;
+        let (buffer, mem_reqs) = Self::build_buffer(
+            &device,
+            size,
+            usage,
+            &queue_family_indices,
+            export_handle_types,
+        )?;
 
         let memory = alloc_dedicated_with_exportable_fd(
             device,
@@ -412,6 +507,69 @@ where
                     AllocFromRequirementsFilter::Allowed
                 }
             },
+            export_handle_types,
+        )?;
+        let mem_offset = memory.offset();
+        debug_assert!((mem_offset % mem_reqs.alignment) == 0);
+        buffer.bind_memory(memory.memory(), mem_offset)?;
+
+        Ok(Arc::new(DeviceLocalBuffer {
+            inner: buffer,
+            memory,
+            queue_family_indices,
+            marker: PhantomData,
+        }))
+    }
+
+    /// Same as `raw`, but the memory of the buffer is imported from `file` instead of being
+    /// allocated.
+    ///
+    /// `handle_type` must be either [`ExternalMemoryHandleType::OpaqueFd`] or
+    /// [`ExternalMemoryHandleType::DmaBuf`].
+    ///
+    /// # Panics
+    ///
+    /// - Panics if `size` is zero.
+    ///
+    /// # Safety
+    ///
+    /// - `file` must satisfy the safety requirements of
+    ///   [`MemoryImportInfo::Fd`](crate::memory::MemoryImportInfo::Fd). If `handle_type` is
+    ///   `OpaqueFd`, the original buffer must have been created with the same `size`, `usage` and
+    ///   queue families.
+    pub unsafe fn raw_from_fd(
+        device: Arc<Device>,
+        size: DeviceSize,
+        usage: BufferUsage,
+        queue_family_indices: impl IntoIterator<Item = u32>,
+        handle_type: ExternalMemoryHandleType,
+        file: File,
+    ) -> Result<Arc<DeviceLocalBuffer<T>>, DeviceMemoryError> {
+        let queue_family_indices: SmallVec<[_; 4]> = queue_family_indices.into_iter().collect();
+
+        let (buffer, mem_reqs) = Self::build_buffer(
+            &device,
+            size,
+            usage,
+            &queue_family_indices,
+            handle_type.into(),
+        )?;
+
+        let memory = alloc_import_from_fd(
+            device,
+            &mem_reqs,
+            AllocLayout::Linear,
+            MappingRequirement::DoNotMap,
+            DedicatedAllocation::Buffer(&buffer),
+            |t| {
+                if t.property_flags.device_local {
+                    AllocFromRequirementsFilter::Preferred
+                } else {
+                    AllocFromRequirementsFilter::Allowed
+                }
+            },
+            handle_type,
+            file,
         )?;
         let mem_offset = memory.offset();
         debug_assert!((mem_offset % mem_reqs.alignment) == 0);
@@ -430,6 +588,7 @@ where
         size: DeviceSize,
         usage: BufferUsage,
         queue_family_indices: &SmallVec<[u32; 4]>,
+        external_memory_handle_types: ExternalMemoryHandleTypes,
     ) -> Result<(Arc<UnsafeBuffer>, MemoryRequirements), DeviceMemoryError> {
         let buffer = {
             match UnsafeBuffer::new(
@@ -442,11 +601,21 @@ where
                     },
                     size,
                     usage,
+                    external_memory_handle_types,
                     ..Default::default()
                 },
             ) {
                 Ok(b) => b,
                 Err(BufferCreationError::AllocError(err)) => return Err(err),
+                Err(BufferCreationError::RequirementNotMet {
+                    required_for,
+                    requires_one_of,
+                }) => {
+                    return Err(DeviceMemoryError::RequirementNotMet {
+                        required_for,
+                        requires_one_of,
+                    })
+                }
                 Err(_) => unreachable!(), // We don't use sparse binding, therefore the other
                                           // errors can't happen
             }
@@ -460,9 +629,19 @@ where
     /// requires `khr_external_memory_fd` and `khr_external_memory` extensions to be loaded.
     /// Only works on Linux/BSD.
     pub fn export_posix_fd(&self) -> Result<File, DeviceMemoryError> {
-        self.memory
-            .memory()
-            .export_fd(ExternalMemoryHandleType::OpaqueFd)
+        self.export_fd(ExternalMemoryHandleType::OpaqueFd)
+    }
+
+    /// Exports the memory of the buffer as a file descriptor of the given handle type.
+    ///
+    /// The buffer must have been created with
+    /// [`raw_with_exportable_handle_types`](Self::raw_with_exportable_handle_types), and
+    /// `handle_type` must be one of the handle types it was created with.
+    pub fn export_fd(
+        &self,
+        handle_type: ExternalMemoryHandleType,
+    ) -> Result<File, DeviceMemoryError> {
+        self.memory.memory().export_fd(handle_type)
     }
 }
 
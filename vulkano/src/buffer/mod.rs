@@ -35,6 +35,8 @@
 //!   CPU-accessible buffer.
 //! - A [`CpuBufferPool`](crate::buffer::cpu_pool::CpuBufferPool) is a ring buffer that can be used to
 //!   transfer data between the CPU and the GPU at a high rate.
+//! - A [`StagingRing`](crate::buffer::staging_ring::StagingRing) is a per-frame linear allocator,
+//!   suitable for uploading many small pieces of data of different types every frame.
 //! - A [`CpuAccessibleBuffer`](crate::buffer::cpu_access::CpuAccessibleBuffer) is a simple buffer that
 //!   can be used to prototype.
 //!
@@ -77,8 +79,10 @@
 pub use self::{
     cpu_access::CpuAccessibleBuffer,
     cpu_pool::CpuBufferPool,
+    device_address::{DeviceAddress, RawDeviceAddress},
     device_local::DeviceLocalBuffer,
     slice::BufferSlice,
+    sparse::SparseBuffer,
     sys::{BufferCreationError, SparseLevel},
     traits::{
         BufferAccess, BufferAccessObject, BufferDeviceAddressError, BufferInner, TypedBufferAccess,
@@ -98,9 +102,12 @@ use std::mem::size_of;
 pub mod cpu_access;
 pub mod cpu_pool;
 pub mod device_local;
+pub mod sparse;
+pub mod staging_ring;
 pub mod sys;
 pub mod view;
 
+mod device_address;
 mod slice;
 mod traits;
 mod usage;
@@ -0,0 +1,693 @@
+// Copyright (c) 2022 The vulkano developers
+// Licensed under the Apache License, Version 2.0
+// <LICENSE-APACHE or
+// https://www.apache.org/licenses/LICENSE-2.0> or the MIT
+// license <LICENSE-MIT or https://opensource.org/licenses/MIT>,
+// at your option. All files in the project carrying such
+// notice may not be copied, modified, or distributed except
+// according to those terms.
+
+//! Buffer whose memory is bound page by page.
+//!
+//! A `SparseBuffer` is created without any memory. Its memory is divided into pages of
+//! [`page_size`](SparseBuffer::page_size) bytes, which are individually made resident with
+//! [`bind_pages`](SparseBuffer::bind_pages) and released again with
+//! [`unbind_pages`](SparseBuffer::unbind_pages). Accessing a page that is not resident from a
+//! shader returns undefined values, but is otherwise safe.
+//!
+//! Creating a `SparseBuffer` requires the
+//! [`sparse_binding`](crate::device::Features::sparse_binding) and
+//! [`sparse_residency_buffer`](crate::device::Features::sparse_residency_buffer) features to be
+//! enabled on the device. Binding pages requires a queue whose queue family supports sparse
+//! binding.
+
+use super::{
+    sys::{SparseLevel, UnsafeBuffer, UnsafeBufferCreateInfo},
+    BufferAccess, BufferAccessObject, BufferInner, BufferUsage,
+};
+use crate::{
+    device::{Device, DeviceOwned, Queue},
+    memory::{
+        sparse::check_buffer_unused, BindSparseInfo, SparseBindFuture, SparseBufferMemoryBind,
+        SparseError, SparsePage, SparsePagePool,
+    },
+    sync::{now, GpuFuture, NowFuture, Sharing},
+    DeviceSize,
+};
+use parking_lot::Mutex;
+use smallvec::SmallVec;
+use std::{
+    hash::{Hash, Hasher},
+    ops::Range,
+    sync::Arc,
+};
+
+/// Buffer that is only partially backed by memory, with residency tracked per page.
+#[derive(Debug)]
+pub struct SparseBuffer {
+    inner: Arc<UnsafeBuffer>,
+    page_pool: Arc<SparsePagePool>,
+    // The page that is bound at each page index of the buffer, if any.
+    pages: Mutex<Vec<Option<SparsePage>>>,
+}
+
+impl SparseBuffer {
+    /// Creates a new `SparseBuffer` of `size` bytes, with no pages resident.
+    ///
+    /// A new page pool is created for the buffer.
+    ///
+    /// # Panics
+    ///
+    /// - Panics if `size` is zero.
+    /// - Panics if `usage` is empty.
+    pub fn new(
+        device: Arc<Device>,
+        size: DeviceSize,
+        usage: BufferUsage,
+        queue_family_indices: impl IntoIterator<Item = u32>,
+    ) -> Result<Arc<SparseBuffer>, SparseError> {
+        let inner = Self::build_buffer(&device, size, usage, queue_family_indices)?;
+        let page_pool = SparsePagePool::new(device, &inner.memory_requirements());
+
+        Ok(Self::from_parts(inner, page_pool))
+    }
+
+    /// Same as `new`, but takes pages from an existing page pool, which can be shared with other
+    /// sparse resources.
+    ///
+    /// # Panics
+    ///
+    /// - Panics if `size` is zero.
+    /// - Panics if `usage` is empty.
+    /// - Panics if `page_pool` was not created from `device`.
+    pub fn with_page_pool(
+        device: Arc<Device>,
+        size: DeviceSize,
+        usage: BufferUsage,
+        queue_family_indices: impl IntoIterator<Item = u32>,
+        page_pool: Arc<SparsePagePool>,
+    ) -> Result<Arc<SparseBuffer>, SparseError> {
+        assert_eq!(&device, page_pool.device());
+
+        let inner = Self::build_buffer(&device, size, usage, queue_family_indices)?;
+
+        if !page_pool.is_compatible(&inner.memory_requirements()) {
+            return Err(SparseError::PagePoolIncompatible);
+        }
+
+        Ok(Self::from_parts(inner, page_pool))
+    }
+
+    fn build_buffer(
+        device: &Arc<Device>,
+        size: DeviceSize,
+        usage: BufferUsage,
+        queue_family_indices: impl IntoIterator<Item = u32>,
+    ) -> Result<Arc<UnsafeBuffer>, SparseError> {
+        let queue_family_indices: SmallVec<[_; 4]> = queue_family_indices.into_iter().collect();
+
+        Ok(UnsafeBuffer::new(
+            device.clone(),
+            UnsafeBufferCreateInfo {
+                sharing: if queue_family_indices.len() >= 2 {
+                    Sharing::Concurrent(queue_family_indices)
+                } else {
+                    Sharing::Exclusive
+                },
+                size,
+                sparse: Some(SparseLevel {
+                    sparse_residency: true,
+                    ..SparseLevel::empty()
+                }),
+                usage,
+                ..Default::default()
+            },
+        )?)
+    }
+
+    fn from_parts(inner: Arc<UnsafeBuffer>, page_pool: Arc<SparsePagePool>) -> Arc<SparseBuffer> {
+        let memory_size = inner.memory_requirements().size;
+        let num_pages = (memory_size + page_pool.page_size() - 1) / page_pool.page_size();
+
+        Arc::new(SparseBuffer {
+            inner,
+            page_pool,
+            pages: Mutex::new((0..num_pages).map(|_| None).collect()),
+        })
+    }
+
+    /// Returns the size in bytes of a page of the buffer.
+    #[inline]
+    pub fn page_size(&self) -> DeviceSize {
+        self.page_pool.page_size()
+    }
+
+    /// Returns the number of pages that the buffer is divided into.
+    #[inline]
+    pub fn num_pages(&self) -> DeviceSize {
+        self.pages.lock().len() as DeviceSize
+    }
+
+    /// Returns the page index that contains the byte at `offset` in the buffer.
+    #[inline]
+    pub fn page_index(&self, offset: DeviceSize) -> DeviceSize {
+        offset / self.page_size()
+    }
+
+    /// Returns the pool that pages of the buffer are taken from.
+    #[inline]
+    pub fn page_pool(&self) -> &Arc<SparsePagePool> {
+        &self.page_pool
+    }
+
+    /// Returns whether memory has been bound to the page at index `page`.
+    ///
+    /// The residency is updated when the bind operation returned by `bind_pages` or
+    /// `unbind_pages` is submitted to the queue, not when it is executed.
+    ///
+    /// # Panics
+    ///
+    /// - Panics if `page` is out of range.
+    #[inline]
+    pub fn is_page_resident(&self, page: DeviceSize) -> bool {
+        self.pages.lock()[page as usize].is_some()
+    }
+
+    /// Returns the number of pages that have memory bound to them.
+    #[inline]
+    pub fn resident_pages(&self) -> DeviceSize {
+        self.pages
+            .lock()
+            .iter()
+            .filter(|page| page.is_some())
+            .count() as DeviceSize
+    }
+
+    /// Binds memory from the page pool to the pages in `pages`, on `queue`.
+    ///
+    /// Pages that are already resident are left untouched.
+    #[inline]
+    pub fn bind_pages(
+        self: &Arc<Self>,
+        queue: Arc<Queue>,
+        pages: Range<DeviceSize>,
+    ) -> Result<SparseBindFuture<NowFuture>, SparseError> {
+        let device = queue.device().clone();
+        self.bind_pages_after(now(device), queue, pages)
+    }
+
+    /// Same as `bind_pages`, but the bind operation is executed after an existing future.
+    ///
+    /// # Panics
+    ///
+    /// - Panics if the device of the buffer is not the same as the device of the future.
+    pub fn bind_pages_after<F>(
+        self: &Arc<Self>,
+        future: F,
+        queue: Arc<Queue>,
+        pages: Range<DeviceSize>,
+    ) -> Result<SparseBindFuture<F>, SparseError>
+    where
+        F: GpuFuture,
+    {
+        assert_eq!(self.device(), future.device());
+
+        let state = self.pages.lock();
+        self.validate_pages(&state, &pages)?;
+
+        let mut new_pages = Vec::new();
+
+        for page in pages.clone() {
+            if state[page as usize].is_none() {
+                new_pages.push((page, self.page_pool.allocate()?));
+            }
+        }
+
+        drop(state);
+
+        let page_size = self.page_size();
+        let memory_binds = new_pages
+            .iter()
+            .map(|(index, page)| SparseBufferMemoryBind {
+                resource_offset: index * page_size,
+                size: page_size,
+                memory: page.memory_bind(),
+            })
+            .collect();
+        let future = SparseBindFuture::new(
+            future,
+            queue,
+            BindSparseInfo {
+                buffer_binds: vec![(self.clone() as Arc<dyn BufferAccess>, memory_binds)],
+                ..Default::default()
+            },
+        )?;
+        let buffer = self.clone();
+
+        Ok(future.with_residency_update(move || {
+            let mut state = buffer.pages.lock();
+
+            // If another bind operation was submitted for the same pages in the meantime, its
+            // memory has now been replaced.
+            new_pages
+                .into_iter()
+                .filter_map(|(index, page)| state[index as usize].replace(page))
+                .collect()
+        }))
+    }
+
+    /// Unbinds the memory of the pages in `pages`, on `queue`.
+    ///
+    /// The unbound memory is returned to the page pool once the bind operation has finished
+    /// executing. Pages that are not resident are left untouched.
+    ///
+    /// Returns an error if the pages may still be in use by a command buffer that was submitted
+    /// before, and that isn't part of the future that the bind operation is executed after.
+    #[inline]
+    pub fn unbind_pages(
+        self: &Arc<Self>,
+        queue: Arc<Queue>,
+        pages: Range<DeviceSize>,
+    ) -> Result<SparseBindFuture<NowFuture>, SparseError> {
+        let device = queue.device().clone();
+        self.unbind_pages_after(now(device), queue, pages)
+    }
+
+    /// Same as `unbind_pages`, but the bind operation is executed after an existing future.
+    ///
+    /// # Panics
+    ///
+    /// - Panics if the device of the buffer is not the same as the device of the future.
+    pub fn unbind_pages_after<F>(
+        self: &Arc<Self>,
+        future: F,
+        queue: Arc<Queue>,
+        pages: Range<DeviceSize>,
+    ) -> Result<SparseBindFuture<F>, SparseError>
+    where
+        F: GpuFuture,
+    {
+        assert_eq!(self.device(), future.device());
+
+        let state = self.pages.lock();
+        self.validate_pages(&state, &pages)?;
+
+        let page_size = self.page_size();
+        let size = self.inner.size();
+        check_buffer_unused(
+            &future,
+            &self.inner,
+            (pages.start * page_size).min(size)..(pages.end * page_size).min(size),
+            &queue,
+        )?;
+
+        let unbound_pages: Vec<_> = pages
+            .filter(|&page| state[page as usize].is_some())
+            .collect();
+        drop(state);
+
+        let mut memory_binds: Vec<SparseBufferMemoryBind> = Vec::new();
+
+        // Consecutive resident pages are unbound with a single bind.
+        for &page in &unbound_pages {
+            match memory_binds.last_mut() {
+                Some(last) if last.resource_offset + last.size == page * page_size => {
+                    last.size += page_size;
+                }
+                _ => memory_binds.push(SparseBufferMemoryBind {
+                    resource_offset: page * page_size,
+                    size: page_size,
+                    memory: None,
+                }),
+            }
+        }
+
+        let future = SparseBindFuture::new(
+            future,
+            queue,
+            BindSparseInfo {
+                buffer_binds: vec![(self.clone() as Arc<dyn BufferAccess>, memory_binds)],
+                ..Default::default()
+            },
+        )?;
+        let buffer = self.clone();
+
+        Ok(future.with_residency_update(move || {
+            let mut state = buffer.pages.lock();
+
+            unbound_pages
+                .into_iter()
+                .filter_map(|page| state[page as usize].take())
+                .collect()
+        }))
+    }
+
+    fn validate_pages(
+        &self,
+        state: &[Option<SparsePage>],
+        pages: &Range<DeviceSize>,
+    ) -> Result<(), SparseError> {
+        assert!(pages.start <= pages.end);
+
+        if pages.end > state.len() as DeviceSize {
+            return Err(SparseError::PageOutOfRange {
+                pages_end: pages.end,
+                num_pages: state.len() as DeviceSize,
+            });
+        }
+
+        Ok(())
+    }
+}
+
+unsafe impl DeviceOwned for SparseBuffer {
+    fn device(&self) -> &Arc<Device> {
+        self.inner.device()
+    }
+}
+
+unsafe impl BufferAccess for SparseBuffer {
+    fn inner(&self) -> BufferInner<'_> {
+        BufferInner {
+            buffer: &self.inner,
+            offset: 0,
+        }
+    }
+
+    fn size(&self) -> DeviceSize {
+        self.inner.size()
+    }
+}
+
+impl BufferAccessObject for Arc<SparseBuffer> {
+    fn as_buffer_access_object(&self) -> Arc<dyn BufferAccess> {
+        self.clone()
+    }
+}
+
+impl PartialEq for SparseBuffer {
+    fn eq(&self, other: &Self) -> bool {
+        self.inner() == other.inner() && self.size() == other.size()
+    }
+}
+
+impl Eq for SparseBuffer {}
+
+impl Hash for SparseBuffer {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.inner().hash(state);
+        self.size().hash(state);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::SparseBuffer;
+    use crate::{
+        buffer::sys::UnsafeBuffer,
+        buffer::{BufferCreationError, BufferUsage},
+        command_buffer::{
+            AutoCommandBufferBuilder, CommandBufferUsage, FillBufferInfo, PrimaryCommandBuffer,
+        },
+        device::{Device, DeviceOwned, Queue},
+        image::{sys::UnsafeImage, ImageLayout},
+        memory::SparseError,
+        swapchain::PresentInfo,
+        sync::{
+            AccessCheckError, AccessError, AccessFlags, FlushError, GpuFuture, PipelineStages,
+            SubmitAnyBuilder,
+        },
+        DeviceSize, RequiresOneOf,
+    };
+    use std::{
+        ops::Range,
+        sync::{
+            atomic::{AtomicBool, Ordering},
+            Arc,
+        },
+    };
+
+    // Stands in for a `PresentFuture`, which can't be created without a window. Flushing it
+    // doesn't present anything.
+    struct DummyPresentFuture {
+        queue: Arc<Queue>,
+        flushed: Arc<AtomicBool>,
+    }
+
+    unsafe impl GpuFuture for DummyPresentFuture {
+        fn cleanup_finished(&mut self) {}
+
+        unsafe fn build_submission(&self) -> Result<SubmitAnyBuilder, FlushError> {
+            if self.flushed.load(Ordering::SeqCst) {
+                Ok(SubmitAnyBuilder::Empty)
+            } else {
+                Ok(SubmitAnyBuilder::QueuePresent(PresentInfo::default()))
+            }
+        }
+
+        fn flush(&self) -> Result<(), FlushError> {
+            self.flushed.store(true, Ordering::SeqCst);
+            Ok(())
+        }
+
+        unsafe fn signal_finished(&self) {}
+
+        fn queue_change_allowed(&self) -> bool {
+            false
+        }
+
+        fn queue(&self) -> Option<Arc<Queue>> {
+            Some(self.queue.clone())
+        }
+
+        fn check_buffer_access(
+            &self,
+            _buffer: &UnsafeBuffer,
+            _range: Range<DeviceSize>,
+            _exclusive: bool,
+            _queue: &Queue,
+        ) -> Result<Option<(PipelineStages, AccessFlags)>, AccessCheckError> {
+            Err(AccessCheckError::Unknown)
+        }
+
+        fn check_image_access(
+            &self,
+            _image: &UnsafeImage,
+            _range: Range<DeviceSize>,
+            _exclusive: bool,
+            _expected_layout: ImageLayout,
+            _queue: &Queue,
+        ) -> Result<Option<(PipelineStages, AccessFlags)>, AccessCheckError> {
+            Err(AccessCheckError::Unknown)
+        }
+
+        fn check_swapchain_image_acquired(
+            &self,
+            _image: &UnsafeImage,
+            _before: bool,
+        ) -> Result<(), AccessCheckError> {
+            Err(AccessCheckError::Unknown)
+        }
+    }
+
+    unsafe impl DeviceOwned for DummyPresentFuture {
+        fn device(&self) -> &Arc<Device> {
+            self.queue.device()
+        }
+    }
+
+    #[test]
+    fn missing_feature_sparse_residency_buffer() {
+        let (device, queue) = gfx_dev_and_queue!(sparse_binding);
+
+        match SparseBuffer::new(
+            device,
+            65536,
+            BufferUsage {
+                storage_buffer: true,
+                ..BufferUsage::empty()
+            },
+            [queue.queue_family_index()],
+        ) {
+            Err(SparseError::BufferCreationError(BufferCreationError::RequirementNotMet {
+                requires_one_of: RequiresOneOf { features, .. },
+                ..
+            })) if features.contains(&"sparse_residency_buffer") => (),
+            _ => panic!(),
+        }
+    }
+
+    #[test]
+    fn bind_and_unbind_pages() {
+        let (device, queue) = gfx_dev_and_queue!(sparse_binding, sparse_residency_buffer);
+
+        if !device.physical_device().queue_family_properties()[queue.queue_family_index() as usize]
+            .queue_flags
+            .sparse_binding
+        {
+            return;
+        }
+
+        let buffer = SparseBuffer::new(
+            device,
+            65536 * 4,
+            BufferUsage {
+                storage_buffer: true,
+                ..BufferUsage::empty()
+            },
+            [queue.queue_family_index()],
+        )
+        .unwrap();
+        let num_pages = buffer.num_pages();
+        assert!(num_pages >= 1);
+        assert!(!buffer.is_page_resident(0));
+
+        buffer
+            .bind_pages(queue.clone(), 0..num_pages)
+            .unwrap()
+            .then_signal_fence_and_flush()
+            .unwrap()
+            .wait(None)
+            .unwrap();
+        assert_eq!(buffer.resident_pages(), num_pages);
+
+        buffer
+            .unbind_pages(queue.clone(), 0..1)
+            .unwrap()
+            .then_signal_fence_and_flush()
+            .unwrap()
+            .wait(None)
+            .unwrap();
+        assert!(!buffer.is_page_resident(0));
+        assert_eq!(buffer.resident_pages(), num_pages - 1);
+
+        assert!(matches!(
+            buffer.bind_pages(queue, 0..num_pages + 1),
+            Err(SparseError::PageOutOfRange { .. })
+        ));
+    }
+
+    #[test]
+    fn residency_updated_on_submit() {
+        let (device, queue) = gfx_dev_and_queue!(sparse_binding, sparse_residency_buffer);
+
+        if !device.physical_device().queue_family_properties()[queue.queue_family_index() as usize]
+            .queue_flags
+            .sparse_binding
+        {
+            return;
+        }
+
+        let buffer = SparseBuffer::new(
+            device,
+            65536,
+            BufferUsage {
+                storage_buffer: true,
+                ..BufferUsage::empty()
+            },
+            [queue.queue_family_index()],
+        )
+        .unwrap();
+
+        let future = buffer.bind_pages(queue, 0..1).unwrap();
+        assert!(!buffer.is_page_resident(0));
+
+        future.flush().unwrap();
+        assert!(buffer.is_page_resident(0));
+    }
+
+    #[test]
+    fn bind_pages_after_present() {
+        let (device, queue) = gfx_dev_and_queue!(sparse_binding, sparse_residency_buffer);
+
+        if !device.physical_device().queue_family_properties()[queue.queue_family_index() as usize]
+            .queue_flags
+            .sparse_binding
+        {
+            return;
+        }
+
+        let buffer = SparseBuffer::new(
+            device,
+            65536,
+            BufferUsage {
+                storage_buffer: true,
+                ..BufferUsage::empty()
+            },
+            [queue.queue_family_index()],
+        )
+        .unwrap();
+
+        let flushed = Arc::new(AtomicBool::new(false));
+        let present_future = DummyPresentFuture {
+            queue: queue.clone(),
+            flushed: flushed.clone(),
+        };
+        buffer
+            .bind_pages_after(present_future, queue, 0..1)
+            .unwrap()
+            .then_signal_fence_and_flush()
+            .unwrap()
+            .wait(None)
+            .unwrap();
+
+        // The present is flushed before the bind operation is submitted.
+        assert!(flushed.load(Ordering::SeqCst));
+        assert!(buffer.is_page_resident(0));
+    }
+
+    #[test]
+    fn unbind_pages_in_use() {
+        let (device, queue) = gfx_dev_and_queue!(sparse_binding, sparse_residency_buffer);
+
+        if !device.physical_device().queue_family_properties()[queue.queue_family_index() as usize]
+            .queue_flags
+            .sparse_binding
+        {
+            return;
+        }
+
+        let buffer = SparseBuffer::new(
+            device.clone(),
+            65536,
+            BufferUsage {
+                transfer_dst: true,
+                ..BufferUsage::empty()
+            },
+            [queue.queue_family_index()],
+        )
+        .unwrap();
+        buffer
+            .bind_pages(queue.clone(), 0..buffer.num_pages())
+            .unwrap()
+            .then_signal_fence_and_flush()
+            .unwrap()
+            .wait(None)
+            .unwrap();
+
+        let mut cbb = AutoCommandBufferBuilder::primary(
+            device,
+            queue.queue_family_index(),
+            CommandBufferUsage::OneTimeSubmit,
+        )
+        .unwrap();
+        cbb.fill_buffer(FillBufferInfo::dst_buffer(buffer.clone()))
+            .unwrap();
+        let cb_future = cbb.build().unwrap().execute(queue.clone()).unwrap();
+
+        assert_eq!(
+            buffer.unbind_pages(queue.clone(), 0..1).err(),
+            Some(SparseError::AccessError(AccessError::AlreadyInUse))
+        );
+
+        // Unbinding after the command buffer has finished executing is allowed.
+        buffer
+            .unbind_pages_after(cb_future, queue, 0..1)
+            .unwrap()
+            .then_signal_fence_and_flush()
+            .unwrap()
+            .wait(None)
+            .unwrap();
+        assert!(!buffer.is_page_resident(0));
+    }
+}
@@ -0,0 +1,680 @@
+// Copyright (c) 2022 The vulkano developers
+// Licensed under the Apache License, Version 2.0
+// <LICENSE-APACHE or
+// https://www.apache.org/licenses/LICENSE-2.0> or the MIT
+// license <LICENSE-MIT or https://opensource.org/licenses/MIT>,
+// at your option. All files in the project carrying such
+// notice may not be copied, modified, or distributed except
+// according to those terms.
+
+//! Per-frame linear allocator for data that is uploaded to the GPU regularly.
+//!
+//! A [`StagingRing`] hands out suballocations of host-visible buffers, of any type that
+//! implements [`BufferContents`]. Within a frame, suballocations are bump-allocated one after the
+//! other, and more blocks of memory are allocated if the frame needs more space. When the ring
+//! comes back to a frame with [`next_frame`](StagingRing::next_frame), the memory of that frame
+//! is reused, unless some of its suballocations are still alive.
+//!
+//! # Example
+//!
+//! ```
+//! use vulkano::buffer::{
+//!     staging_ring::{StagingRing, StagingRingCreateInfo},
+//!     BufferUsage,
+//! };
+//!
+//! # let device: std::sync::Arc<vulkano::device::Device> = return;
+//! let ring = StagingRing::new(
+//!     device.clone(),
+//!     StagingRingCreateInfo {
+//!         usage: BufferUsage {
+//!             uniform_buffer: true,
+//!             ..BufferUsage::empty()
+//!         },
+//!         ..Default::default()
+//!     },
+//! )
+//! .unwrap();
+//!
+//! loop {
+//!     // Each frame, grab as many suballocations as needed.
+//!     let transform = ring.from_data([[1.0f32; 4]; 4]).unwrap();
+//!     let tint = ring.from_data([0.5f32, 0.5, 1.0, 1.0]).unwrap();
+//!
+//!     // ... record and submit command buffers using `transform` and `tint` ...
+//! #   break;
+//!
+//!     // Then move on to the next frame.
+//!     ring.next_frame();
+//! }
+//! ```
+
+use super::{
+    sys::{UnsafeBuffer, UnsafeBufferCreateInfo},
+    BufferAccess, BufferAccessObject, BufferContents, BufferCreationError, BufferInner,
+    BufferUsage, TypedBufferAccess,
+};
+use crate::{
+    device::{Device, DeviceOwned},
+    memory::{
+        pool::{choose_allocation_memory_type, AllocFromRequirementsFilter, MappingRequirement},
+        DedicatedAllocation, DeviceMemory, DeviceMemoryError, MappedDeviceMemory,
+        MemoryAllocateFlags, MemoryAllocateInfo,
+    },
+    DeviceSize, OomError,
+};
+use parking_lot::Mutex;
+use std::{
+    hash::{Hash, Hasher},
+    marker::PhantomData,
+    mem::{align_of, size_of},
+    ops::Range,
+    ptr,
+    sync::Arc,
+};
+
+/// Frame-indexed linear allocator for uploading data to the GPU.
+///
+/// See the [module-level documentation](self) for more information.
+#[derive(Debug)]
+pub struct StagingRing {
+    device: Arc<Device>,
+    usage: BufferUsage,
+    block_size: DeviceSize,
+
+    // The alignment of every suballocation, in bytes.
+    alignment: DeviceSize,
+
+    // The number of bytes that must be flushed together at the end of a suballocation, if the
+    // memory is not host-coherent.
+    atom_size: DeviceSize,
+
+    state: Mutex<StagingRingState>,
+}
+
+#[derive(Debug)]
+struct StagingRingState {
+    frames: Vec<StagingFrame>,
+    current_frame: usize,
+}
+
+#[derive(Debug, Default)]
+struct StagingFrame {
+    // The blocks that this frame allocates from, in order.
+    blocks: Vec<Arc<StagingBlock>>,
+
+    // Index in `blocks` of the block that is currently being allocated from.
+    current_block: usize,
+
+    // Offset of the first free byte in the current block.
+    offset: DeviceSize,
+}
+
+// One block of memory of the ring, with a buffer covering it.
+#[derive(Debug)]
+struct StagingBlock {
+    // Declared before `memory`, so that it is destroyed first.
+    buffer: Arc<UnsafeBuffer>,
+    memory: MappedDeviceMemory,
+}
+
+impl StagingRing {
+    /// Creates a new `StagingRing`.
+    ///
+    /// The first block of memory is allocated immediately.
+    ///
+    /// # Panics
+    ///
+    /// - Panics if `create_info.frames_in_flight` is 0.
+    /// - Panics if `create_info.block_size` is 0.
+    /// - Panics if `create_info.usage` is empty.
+    pub fn new(
+        device: Arc<Device>,
+        create_info: StagingRingCreateInfo,
+    ) -> Result<Arc<StagingRing>, DeviceMemoryError> {
+        let StagingRingCreateInfo {
+            frames_in_flight,
+            block_size,
+            usage,
+            _ne: _,
+        } = create_info;
+
+        assert!(frames_in_flight != 0);
+        assert!(block_size != 0);
+        assert!(!usage.is_empty());
+
+        let properties = device.physical_device().properties();
+        let mut alignment = 1;
+
+        if usage.uniform_buffer {
+            alignment = alignment.max(properties.min_uniform_buffer_offset_alignment);
+        }
+
+        if usage.storage_buffer {
+            alignment = alignment.max(properties.min_storage_buffer_offset_alignment);
+        }
+
+        if usage.uniform_texel_buffer || usage.storage_texel_buffer {
+            alignment = alignment.max(properties.min_texel_buffer_offset_alignment);
+        }
+
+        let mut frames: Vec<_> = (0..frames_in_flight)
+            .map(|_| StagingFrame::default())
+            .collect();
+        let first_block = StagingBlock::new(&device, usage, block_size)?;
+
+        // Non-coherent memory can only be written and flushed in multiples of the atom size.
+        let atom_size = if first_block.is_coherent() {
+            1
+        } else {
+            properties.non_coherent_atom_size
+        };
+        alignment = alignment.max(atom_size);
+
+        frames[0].blocks.push(Arc::new(first_block));
+
+        Ok(Arc::new(StagingRing {
+            device,
+            usage,
+            block_size,
+            alignment,
+            atom_size,
+            state: Mutex::new(StagingRingState {
+                frames,
+                current_frame: 0,
+            }),
+        }))
+    }
+
+    /// Returns the usage of the buffers of the ring.
+    #[inline]
+    pub fn usage(&self) -> &BufferUsage {
+        &self.usage
+    }
+
+    /// Returns the number of frames that the ring cycles through.
+    #[inline]
+    pub fn frames_in_flight(&self) -> u32 {
+        self.state.lock().frames.len() as u32
+    }
+
+    /// Returns the index of the frame that suballocations are currently taken from.
+    #[inline]
+    pub fn current_frame(&self) -> u32 {
+        self.state.lock().current_frame as u32
+    }
+
+    /// Returns the total size of the memory blocks that are currently held by the ring.
+    pub fn capacity(&self) -> DeviceSize {
+        self.state
+            .lock()
+            .frames
+            .iter()
+            .flat_map(|frame| frame.blocks.iter())
+            .map(|block| block.buffer.size())
+            .sum()
+    }
+
+    /// Moves on to the next frame, wrapping around after `frames_in_flight` frames.
+    ///
+    /// The memory blocks of the new current frame are reused from the start. A block that still
+    /// has suballocations alive, because a command buffer or a future using it has not been
+    /// cleaned up yet, is released from the ring instead, and is freed once its last
+    /// suballocation is dropped.
+    ///
+    /// Suballocations are usually dropped when calling
+    /// [`cleanup_finished`](crate::sync::GpuFuture::cleanup_finished) after the fence of the
+    /// frame has been signaled.
+    pub fn next_frame(&self) {
+        let mut state = self.state.lock();
+        let frame_count = state.frames.len();
+        state.current_frame = (state.current_frame + 1) % frame_count;
+
+        let current_frame = state.current_frame;
+        let frame = &mut state.frames[current_frame];
+        frame.blocks.retain(|block| Arc::strong_count(block) == 1);
+        frame.current_block = 0;
+        frame.offset = 0;
+    }
+
+    /// Puts `data` in a new suballocation of the current frame.
+    ///
+    /// If the current frame is full, a new block of memory is allocated.
+    pub fn from_data<T>(&self, data: T) -> Result<Arc<StagingRingSubbuffer<T>>, DeviceMemoryError>
+    where
+        T: BufferContents,
+    {
+        let size = size_of::<T>() as DeviceSize;
+        let (block, offset) = self.allocate(size, align_of::<T>() as DeviceSize)?;
+
+        if size != 0 {
+            unsafe {
+                let bytes = self.write(&block, offset, size);
+                bytes.copy_from_slice(data.as_bytes());
+                self.flush(&block, offset, size);
+            }
+        }
+
+        Ok(Arc::new(StagingRingSubbuffer {
+            block,
+            offset,
+            size,
+            marker: PhantomData,
+        }))
+    }
+
+    /// Puts all elements of `iter` in a new suballocation of the current frame.
+    ///
+    /// If the current frame is full, a new block of memory is allocated.
+    ///
+    /// # Panics
+    ///
+    /// - Panics if the length of the iterator didn't match the actual number of elements.
+    pub fn from_iter<T, I>(
+        &self,
+        iter: I,
+    ) -> Result<Arc<StagingRingSubbuffer<[T]>>, DeviceMemoryError>
+    where
+        [T]: BufferContents,
+        I: IntoIterator<Item = T>,
+        I::IntoIter: ExactSizeIterator,
+    {
+        let iter = iter.into_iter();
+        let len = iter.len() as DeviceSize;
+        let size = len * size_of::<T>() as DeviceSize;
+        let (block, offset) = self.allocate(size, align_of::<T>() as DeviceSize)?;
+
+        if size != 0 {
+            unsafe {
+                let bytes = self.write(&block, offset, size);
+                let mapping = <[T]>::from_bytes_mut(bytes).unwrap();
+
+                let mut written = 0;
+                for (o, i) in mapping.iter_mut().zip(iter) {
+                    ptr::write(o, i);
+                    written += 1;
+                }
+
+                assert_eq!(
+                    written, len,
+                    "Iterator passed to StagingRing::from_iter has a mismatch between reported \
+                    length and actual number of elements"
+                );
+
+                self.flush(&block, offset, size);
+            }
+        }
+
+        Ok(Arc::new(StagingRingSubbuffer {
+            block,
+            offset,
+            size,
+            marker: PhantomData,
+        }))
+    }
+
+    // Reserves `size` bytes in the current frame, and returns the block and the offset in it.
+    fn allocate(
+        &self,
+        size: DeviceSize,
+        alignment: DeviceSize,
+    ) -> Result<(Arc<StagingBlock>, DeviceSize), DeviceMemoryError> {
+        let alignment = self.alignment.max(alignment);
+        let mut state = self.state.lock();
+        let current_frame = state.current_frame;
+        let frame = &mut state.frames[current_frame];
+
+        loop {
+            if let Some(block) = frame.blocks.get(frame.current_block) {
+                let offset = align_up(frame.offset, alignment);
+
+                if offset + size <= block.buffer.size() {
+                    frame.offset = offset + size;
+                    return Ok((block.clone(), offset));
+                }
+
+                frame.current_block += 1;
+                frame.offset = 0;
+            } else {
+                let block_size = align_up(self.block_size.max(size), self.atom_size);
+                let block = StagingBlock::new(&self.device, self.usage, block_size)?;
+                frame.blocks.push(Arc::new(block));
+            }
+        }
+    }
+
+    // Returns the mapped bytes of a suballocation, padded at the end to the atom size.
+    //
+    // There must be no other references to the suballocation.
+    #[allow(clippy::mut_from_ref)]
+    unsafe fn write<'a>(
+        &self,
+        block: &'a StagingBlock,
+        offset: DeviceSize,
+        size: DeviceSize,
+    ) -> &'a mut [u8] {
+        let bytes = block
+            .memory
+            .write(self.mapped_range(block, offset, size))
+            .unwrap();
+        &mut bytes[..size as usize]
+    }
+
+    unsafe fn flush(&self, block: &StagingBlock, offset: DeviceSize, size: DeviceSize) {
+        block
+            .memory
+            .flush_range(self.mapped_range(block, offset, size))
+            .unwrap();
+    }
+
+    fn mapped_range(
+        &self,
+        block: &StagingBlock,
+        offset: DeviceSize,
+        size: DeviceSize,
+    ) -> Range<DeviceSize> {
+        let end =
+            align_up(offset + size, self.atom_size).min(block.memory.as_ref().allocation_size());
+
+        offset..end
+    }
+}
+
+unsafe impl DeviceOwned for StagingRing {
+    #[inline]
+    fn device(&self) -> &Arc<Device> {
+        &self.device
+    }
+}
+
+impl StagingBlock {
+    fn new(
+        device: &Arc<Device>,
+        usage: BufferUsage,
+        size: DeviceSize,
+    ) -> Result<StagingBlock, DeviceMemoryError> {
+        let buffer = match UnsafeBuffer::new(
+            device.clone(),
+            UnsafeBufferCreateInfo {
+                size,
+                usage,
+                ..Default::default()
+            },
+        ) {
+            Ok(b) => b,
+            Err(BufferCreationError::AllocError(err)) => return Err(err),
+            Err(BufferCreationError::RequirementNotMet {
+                required_for,
+                requires_one_of,
+            }) => {
+                return Err(DeviceMemoryError::RequirementNotMet {
+                    required_for,
+                    requires_one_of,
+                })
+            }
+            Err(BufferCreationError::MaxBufferSizeExceeded { .. }) => {
+                return Err(DeviceMemoryError::OomError(OomError::OutOfDeviceMemory))
+            }
+            Err(_) => unreachable!(), // We don't use sparse binding, therefore the other
+                                      // errors can't happen
+        };
+        let mem_reqs = buffer.memory_requirements();
+
+        let memory_type_index = choose_allocation_memory_type(
+            device,
+            &mem_reqs,
+            |m| {
+                if m.property_flags.host_cached {
+                    AllocFromRequirementsFilter::Allowed
+                } else {
+                    AllocFromRequirementsFilter::Preferred
+                }
+            },
+            MappingRequirement::Map,
+        );
+
+        let memory = DeviceMemory::allocate(
+            device.clone(),
+            MemoryAllocateInfo {
+                allocation_size: mem_reqs.size,
+                memory_type_index,
+                flags: MemoryAllocateFlags::for_device_address(device),
+                ..MemoryAllocateInfo::dedicated_allocation(DedicatedAllocation::Buffer(&buffer))
+            },
+        )?;
+        let memory = MappedDeviceMemory::new(memory, 0..mem_reqs.size)?;
+
+        unsafe {
+            buffer.bind_memory(memory.as_ref(), 0)?;
+        }
+
+        Ok(StagingBlock { buffer, memory })
+    }
+
+    fn is_coherent(&self) -> bool {
+        let memory_type_index = self.memory.as_ref().memory_type_index();
+
+        self.buffer
+            .device()
+            .physical_device()
+            .memory_properties()
+            .memory_types[memory_type_index as usize]
+            .property_flags
+            .host_coherent
+    }
+}
+
+#[inline]
+fn align_up(value: DeviceSize, alignment: DeviceSize) -> DeviceSize {
+    (value + alignment - 1) / alignment * alignment
+}
+
+/// Parameters to create a new `StagingRing`.
+#[derive(Clone, Debug)]
+pub struct StagingRingCreateInfo {
+    /// The number of frames that the ring cycles through.
+    ///
+    /// This should be at least the number of frames that can be in flight on the GPU at the same
+    /// time.
+    ///
+    /// The default value is `2`.
+    pub frames_in_flight: u32,
+
+    /// The size in bytes of each block of memory that is allocated.
+    ///
+    /// Suballocations that are larger than this get a block of their own.
+    ///
+    /// The default value is 4 MiB.
+    pub block_size: DeviceSize,
+
+    /// How the suballocations are going to be used.
+    ///
+    /// The suballocations are aligned to the minimum offset alignments that correspond to the
+    /// usage, such as `min_uniform_buffer_offset_alignment` if `uniform_buffer` is set.
+    ///
+    /// The default value is [`BufferUsage::empty()`], which must be overridden.
+    pub usage: BufferUsage,
+
+    pub _ne: crate::NonExhaustive,
+}
+
+impl Default for StagingRingCreateInfo {
+    #[inline]
+    fn default() -> Self {
+        Self {
+            frames_in_flight: 2,
+            block_size: 4 * 1024 * 1024,
+            usage: BufferUsage::empty(),
+            _ne: crate::NonExhaustive(()),
+        }
+    }
+}
+
+/// A suballocation of a [`StagingRing`].
+///
+/// The memory of the suballocation can only be reused by the ring once this object and all of
+/// its clones have been dropped.
+pub struct StagingRingSubbuffer<T>
+where
+    T: BufferContents + ?Sized,
+{
+    block: Arc<StagingBlock>,
+    offset: DeviceSize,
+    size: DeviceSize,
+    marker: PhantomData<Box<T>>,
+}
+
+unsafe impl<T> BufferAccess for StagingRingSubbuffer<T>
+where
+    T: BufferContents + ?Sized,
+{
+    #[inline]
+    fn inner(&self) -> BufferInner<'_> {
+        BufferInner {
+            buffer: &self.block.buffer,
+            offset: self.offset,
+        }
+    }
+
+    #[inline]
+    fn size(&self) -> DeviceSize {
+        self.size
+    }
+}
+
+impl<T> BufferAccessObject for Arc<StagingRingSubbuffer<T>>
+where
+    T: BufferContents + ?Sized,
+{
+    #[inline]
+    fn as_buffer_access_object(&self) -> Arc<dyn BufferAccess> {
+        self.clone()
+    }
+}
+
+unsafe impl<T> TypedBufferAccess for StagingRingSubbuffer<T>
+where
+    T: BufferContents + ?Sized,
+{
+    type Content = T;
+}
+
+unsafe impl<T> DeviceOwned for StagingRingSubbuffer<T>
+where
+    T: BufferContents + ?Sized,
+{
+    #[inline]
+    fn device(&self) -> &Arc<Device> {
+        self.block.buffer.device()
+    }
+}
+
+impl<T> PartialEq for StagingRingSubbuffer<T>
+where
+    T: BufferContents + ?Sized,
+{
+    #[inline]
+    fn eq(&self, other: &Self) -> bool {
+        self.inner() == other.inner() && self.size() == other.size()
+    }
+}
+
+impl<T> Eq for StagingRingSubbuffer<T> where T: BufferContents + ?Sized {}
+
+impl<T> Hash for StagingRingSubbuffer<T>
+where
+    T: BufferContents + ?Sized,
+{
+    #[inline]
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.inner().hash(state);
+        self.size().hash(state);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{StagingRing, StagingRingCreateInfo};
+    use crate::buffer::{BufferAccess, BufferUsage};
+
+    #[test]
+    fn uniform_alignment() {
+        let (device, _) = gfx_dev_and_queue!();
+        let ring = StagingRing::new(
+            device.clone(),
+            StagingRingCreateInfo {
+                usage: BufferUsage {
+                    uniform_buffer: true,
+                    ..BufferUsage::empty()
+                },
+                ..Default::default()
+            },
+        )
+        .unwrap();
+
+        let alignment = device
+            .physical_device()
+            .properties()
+            .min_uniform_buffer_offset_alignment;
+        let a = ring.from_data(1u32).unwrap();
+        let b = ring.from_data([2.0f32; 3]).unwrap();
+        assert_eq!(a.inner().offset % alignment, 0);
+        assert_eq!(b.inner().offset % alignment, 0);
+        assert!(b.inner().offset > a.inner().offset);
+    }
+
+    #[test]
+    fn grows_within_frame() {
+        let (device, _) = gfx_dev_and_queue!();
+        let ring = StagingRing::new(
+            device,
+            StagingRingCreateInfo {
+                block_size: 256,
+                usage: BufferUsage {
+                    transfer_src: true,
+                    ..BufferUsage::empty()
+                },
+                ..Default::default()
+            },
+        )
+        .unwrap();
+
+        let first_capacity = ring.capacity();
+        let _a = ring.from_iter([0u8; 200]).unwrap();
+        let _b = ring.from_iter([0u8; 200]).unwrap();
+        let _c = ring.from_iter([0u32; 1024]).unwrap();
+        assert!(ring.capacity() > first_capacity);
+    }
+
+    #[test]
+    fn reuse_after_drop() {
+        let (device, _) = gfx_dev_and_queue!();
+        let ring = StagingRing::new(
+            device,
+            StagingRingCreateInfo {
+                frames_in_flight: 1,
+                usage: BufferUsage {
+                    transfer_src: true,
+                    ..BufferUsage::empty()
+                },
+                ..Default::default()
+            },
+        )
+        .unwrap();
+
+        let a = ring.from_data(1u32).unwrap();
+        let a_offset = a.inner().offset;
+        drop(a);
+        ring.next_frame();
+
+        let b = ring.from_data(2u32).unwrap();
+        assert_eq!(b.inner().offset, a_offset);
+
+        // `b` is still alive, so its block can't be reused.
+        let capacity = ring.capacity();
+        ring.next_frame();
+        let _c = ring.from_data(3u32).unwrap();
+        assert_eq!(ring.capacity(), capacity);
+        assert_ne!(b.inner().buffer, _c.inner().buffer);
+    }
+}
@@ -26,7 +26,7 @@
 
 use super::{
     cpu_access::{ReadLockError, WriteLockError},
-    BufferUsage,
+    BufferAccess, BufferUsage,
 };
 use crate::{
     device::{Device, DeviceOwned},
@@ -46,7 +46,7 @@ use std::{
     mem::MaybeUninit,
     ops::Range,
     ptr,
-    sync::Arc,
+    sync::{Arc, Weak},
 };
 
 /// Data storage in a GPU-accessible location.
@@ -57,9 +57,13 @@ pub struct UnsafeBuffer {
 
     size: DeviceSize,
     usage: BufferUsage,
+    sharing: Sharing<SmallVec<[u32; 4]>>,
     external_memory_handle_types: ExternalMemoryHandleTypes,
+    name: Mutex<Option<String>>,
 
     state: Mutex<BufferState>,
+    // The buffers whose device addresses were found in the contents of this buffer.
+    referenced_buffers: Mutex<Vec<Weak<dyn BufferAccess>>>,
 }
 
 impl UnsafeBuffer {
@@ -100,6 +104,7 @@ impl UnsafeBuffer {
             sparse,
             usage,
             external_memory_handle_types,
+            name: _,
             _ne: _,
         } = create_info;
 
@@ -217,13 +222,14 @@ impl UnsafeBuffer {
             sparse,
             usage,
             external_memory_handle_types,
+            name: _,
             _ne: _,
         } = &create_info;
 
         let mut flags = ash::vk::BufferCreateFlags::empty();
 
         if let Some(sparse_level) = sparse {
-            flags |= sparse_level.into();
+            flags |= ash::vk::BufferCreateFlags::SPARSE_BINDING | sparse_level.into();
         }
 
         let (sharing_mode, p_queue_family_indices) = match sharing {
@@ -270,7 +276,17 @@ impl UnsafeBuffer {
             output.assume_init()
         };
 
-        Ok(Self::from_handle(device, handle, create_info))
+        let buffer = Self::from_handle(device, handle, create_info);
+
+        if let Some(name) = buffer.name.lock().as_deref() {
+            buffer.device.set_debug_utils_object_name_if_enabled(
+                ash::vk::ObjectType::BUFFER,
+                handle.as_raw(),
+                Some(name),
+            )?;
+        }
+
+        Ok(buffer)
     }
 
     /// Creates a new `UnsafeBuffer` from a raw object handle.
@@ -288,9 +304,10 @@ impl UnsafeBuffer {
         let UnsafeBufferCreateInfo {
             size,
             usage,
-            sharing: _,
+            sharing,
             sparse: _,
             external_memory_handle_types,
+            name,
             _ne: _,
         } = create_info;
 
@@ -300,9 +317,12 @@ impl UnsafeBuffer {
 
             size,
             usage,
+            sharing,
             external_memory_handle_types,
+            name: Mutex::new(name),
 
             state: Mutex::new(BufferState::new(size)),
+            referenced_buffers: Mutex::new(Vec::new()),
         })
     }
 
@@ -451,6 +471,24 @@ impl UnsafeBuffer {
         self.state.lock()
     }
 
+    /// Returns the buffers that are referenced by device address in the contents of this buffer,
+    /// and that are still alive.
+    pub(crate) fn referenced_buffers(&self) -> Vec<Arc<dyn BufferAccess>> {
+        self.referenced_buffers
+            .lock()
+            .iter()
+            .filter_map(Weak::upgrade)
+            .collect()
+    }
+
+    /// Sets the buffers that are referenced by device address in the contents of this buffer.
+    ///
+    /// Only weak references are kept, so that buffers that reference each other don't keep each
+    /// other alive.
+    pub(crate) fn set_referenced_buffers(&self, buffers: &[Arc<dyn BufferAccess>]) {
+        *self.referenced_buffers.lock() = buffers.iter().map(Arc::downgrade).collect();
+    }
+
     /// Returns the size of the buffer in bytes.
     #[inline]
     pub fn size(&self) -> DeviceSize {
@@ -463,12 +501,43 @@ impl UnsafeBuffer {
         &self.usage
     }
 
+    /// Returns the sharing mode the buffer was created with.
+    #[inline]
+    pub fn sharing(&self) -> &Sharing<SmallVec<[u32; 4]>> {
+        &self.sharing
+    }
+
     /// Returns the external memory handle types that are supported with this buffer.
     #[inline]
     pub fn external_memory_handle_types(&self) -> ExternalMemoryHandleTypes {
         self.external_memory_handle_types
     }
 
+    /// Returns the name of the buffer, if it has one.
+    #[inline]
+    pub fn name(&self) -> Option<String> {
+        self.name.lock().clone()
+    }
+
+    /// Sets or removes the name of the buffer.
+    ///
+    /// The name is used in the error messages of vulkano. If the `ext_debug_utils` extension is
+    /// enabled on the instance, it is also assigned to the buffer with
+    /// [`Device::set_debug_utils_object_name`], so that debugging tools show it.
+    pub fn set_name(&self, name: Option<&str>) -> Result<(), OomError> {
+        unsafe {
+            self.device.set_debug_utils_object_name_if_enabled(
+                ash::vk::ObjectType::BUFFER,
+                self.handle.as_raw(),
+                name,
+            )?;
+        }
+
+        *self.name.lock() = name.map(ToOwned::to_owned);
+
+        Ok(())
+    }
+
     /// Returns a key unique to each `UnsafeBuffer`. Can be used for the `conflicts_key` method.
     #[inline]
     pub fn key(&self) -> u64 {
@@ -550,6 +619,15 @@ pub struct UnsafeBufferCreateInfo {
     /// The default value is [`ExternalMemoryHandleTypes::empty()`].
     pub external_memory_handle_types: ExternalMemoryHandleTypes,
 
+    /// A name for the buffer, that is used in the error messages of vulkano.
+    ///
+    /// If the `ext_debug_utils` extension is enabled on the instance, the name is also assigned
+    /// to the buffer with [`Device::set_debug_utils_object_name`], so that debugging tools show
+    /// it. Otherwise, this doesn't require the extension.
+    ///
+    /// The default value is `None`.
+    pub name: Option<String>,
+
     pub _ne: crate::NonExhaustive,
 }
 
@@ -562,6 +640,7 @@ impl Default for UnsafeBufferCreateInfo {
             sparse: None,
             usage: BufferUsage::empty(),
             external_memory_handle_types: ExternalMemoryHandleTypes::empty(),
+            name: None,
             _ne: crate::NonExhaustive(()),
         }
     }
@@ -657,10 +736,12 @@ vulkan_bitflags! {
     #[non_exhaustive]
     SparseLevel = BufferCreateFlags(u32);
 
-    // TODO: document
-    sparse_residency = SPARSE_ALIASED,
+    /// The buffer can be partially resident: not every part of it needs to have memory bound to
+    /// it before it is used.
+    sparse_residency = SPARSE_RESIDENCY,
 
-    // TODO: document
+    /// The memory that is bound to the buffer can also be bound to other parts of the same
+    /// buffer, or to other resources, at the same time.
     sparse_aliased = SPARSE_ALIASED,
 }
 
@@ -687,6 +768,17 @@ impl BufferState {
         }
     }
 
+    /// Returns whether any part of `range` is currently locked by a queue operation.
+    pub(crate) fn is_gpu_locked(&self, range: Range<DeviceSize>) -> bool {
+        self.ranges
+            .range(&range)
+            .any(|(_range, state)| match state.current_access {
+                CurrentAccess::CpuExclusive => false,
+                CurrentAccess::GpuExclusive { .. } => true,
+                CurrentAccess::Shared { gpu_reads, .. } => gpu_reads > 0,
+            })
+    }
+
     pub(crate) fn check_cpu_read(&self, range: Range<DeviceSize>) -> Result<(), ReadLockError> {
         for (_range, state) in self.ranges.range(&range) {
             match &state.current_access {
@@ -905,6 +997,28 @@ mod tests {
         assert_eq!(&**buf.device() as *const Device, &*device as *const Device);
     }
 
+    #[test]
+    fn name() {
+        let (device, _) = gfx_dev_and_queue!();
+        let buf = UnsafeBuffer::new(
+            device,
+            UnsafeBufferCreateInfo {
+                size: 128,
+                usage: BufferUsage {
+                    transfer_dst: true,
+                    ..BufferUsage::empty()
+                },
+                name: Some("vertices".to_owned()),
+                ..Default::default()
+            },
+        )
+        .unwrap();
+        assert_eq!(buf.name().as_deref(), Some("vertices"));
+
+        buf.set_name(None).unwrap();
+        assert_eq!(buf.name(), None);
+    }
+
     #[test]
     fn missing_feature_sparse_binding() {
         let (device, _) = gfx_dev_and_queue!();
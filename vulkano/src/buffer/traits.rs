@@ -7,8 +7,10 @@
 // notice may not be copied, modified, or distributed except
 // according to those terms.
 
-use super::{sys::UnsafeBuffer, BufferContents, BufferSlice, BufferUsage};
-use crate::{device::DeviceOwned, DeviceSize, RequiresOneOf, SafeDeref, VulkanObject};
+use super::{sys::UnsafeBuffer, BufferContents, BufferSlice, BufferUsage, DeviceAddress};
+use crate::{
+    device::DeviceOwned, DeviceSize, OomError, RequiresOneOf, SafeDeref, Version, VulkanObject,
+};
 use std::{
     error::Error,
     fmt::{Debug, Display, Error as FmtError, Formatter},
@@ -35,6 +37,15 @@ pub unsafe trait BufferAccess: DeviceOwned + Send + Sync {
         self.inner().buffer.usage()
     }
 
+    /// Sets or removes the name of the buffer, for use in error messages and debugging tools.
+    ///
+    /// This names the whole underlying [`UnsafeBuffer`], which may be shared with other buffers.
+    /// See [`UnsafeBuffer::set_name`].
+    #[inline]
+    fn set_name(&self, name: Option<&str>) -> Result<(), OomError> {
+        self.inner().buffer.set_name(name)
+    }
+
     /// Returns a `BufferSlice` covering the whole buffer.
     #[inline]
     fn into_buffer_slice(self: &Arc<Self>) -> Arc<BufferSlice<Self::Content, Self>>
@@ -100,13 +111,17 @@ pub unsafe trait BufferAccess: DeviceOwned + Send + Sync {
                 ..Default::default()
             };
             let fns = device.fns();
-            let ptr = (fns.ext_buffer_device_address.get_buffer_device_address_ext)(
-                device.internal_object(),
-                &info,
-            );
+            let f = if device.enabled_extensions().ext_buffer_device_address {
+                fns.ext_buffer_device_address.get_buffer_device_address_ext
+            } else if device.api_version() >= Version::V1_2 {
+                fns.v1_2.get_buffer_device_address
+            } else {
+                fns.khr_buffer_device_address.get_buffer_device_address_khr
+            };
+            let ptr = f(device.internal_object(), &info);
 
             if ptr == 0 {
-                panic!("got null ptr from a valid GetBufferDeviceAddressEXT call");
+                panic!("got null ptr from a valid GetBufferDeviceAddress call");
             }
 
             Ok(NonZeroU64::new_unchecked(ptr + inner.offset))
@@ -161,6 +176,24 @@ pub unsafe trait TypedBufferAccess: BufferAccess {
     fn len(&self) -> DeviceSize {
         self.size() / Self::Content::size_of_element()
     }
+
+    /// Gets the typed device address for this buffer.
+    ///
+    /// The buffer must have been created with the
+    /// [`shader_device_address`](BufferUsage::shader_device_address) usage, and the
+    /// [`buffer_device_address`](crate::device::Features::buffer_device_address) feature must be
+    /// enabled on the device.
+    ///
+    /// The returned address keeps the buffer alive. See [`DeviceAddress`] for how it is tracked
+    /// when used in a command buffer.
+    #[inline]
+    fn device_address(&self) -> Result<DeviceAddress<Self::Content>, BufferDeviceAddressError>
+    where
+        Self: BufferAccessObject,
+    {
+        self.raw_device_address()
+            .map(|address| DeviceAddress::new(self.as_buffer_access_object(), address))
+    }
 }
 
 unsafe impl<T> TypedBufferAccess for T
@@ -23,7 +23,7 @@ use crate::{
     command_buffer::CommandBufferInheritanceRenderingInfo,
     device::{Device, DeviceOwned, Queue, QueueFamilyProperties},
     format::Format,
-    image::{sys::UnsafeImage, ImageAccess, ImageLayout, ImageSubresourceRange},
+    image::{sys::UnsafeImage, ImageAccess, ImageLayout, ImageSubresourceRange, ImageViewAbstract},
     query::{QueryControlFlags, QueryType},
     render_pass::{Framebuffer, Subpass},
     sync::{AccessCheckError, AccessFlags, GpuFuture, PipelineMemoryAccess, PipelineStages},
@@ -62,8 +62,15 @@ pub struct AutoCommandBufferBuilder<L, P = StandardCommandPoolBuilder> {
     // If we're inside a render pass, contains the render pass state.
     pub(super) render_pass_state: Option<RenderPassState>,
 
-    // If any queries are active, this hashmap contains their state.
-    pub(super) query_state: HashMap<ash::vk::QueryType, QueryState>,
+    // If any queries are active, this hashmap contains their state, keyed by query type and
+    // vertex stream index.
+    pub(super) query_state: HashMap<(ash::vk::QueryType, u32), QueryState>,
+
+    // If conditional rendering is active, contains its state.
+    pub(super) conditional_rendering_state: Option<ConditionalRenderingState>,
+
+    // Whether transform feedback is active.
+    pub(super) transform_feedback_active: bool,
 
     _data: PhantomData<L>,
 }
@@ -97,6 +104,7 @@ impl From<BeginRenderingState> for RenderPassStateType {
 pub(super) struct BeginRenderPassState {
     pub(super) subpass: Subpass,
     pub(super) framebuffer: Option<Arc<Framebuffer>>,
+    pub(super) attachments: Option<Vec<Arc<dyn ImageViewAbstract>>>,
 }
 
 pub(super) struct BeginRenderingState {
@@ -122,6 +130,11 @@ pub(super) struct QueryState {
     pub(super) in_subpass: bool,
 }
 
+// The state of active conditional rendering.
+pub(super) struct ConditionalRenderingState {
+    pub(super) in_subpass: bool,
+}
+
 impl AutoCommandBufferBuilder<PrimaryAutoCommandBuffer, StandardCommandPoolBuilder> {
     /// Starts recording a primary command buffer.
     #[inline]
@@ -175,8 +188,71 @@ impl AutoCommandBufferBuilder<SecondaryAutoCommandBuffer, StandardCommandPoolBui
     }
 }
 
+impl<P> AutoCommandBufferBuilder<PrimaryAutoCommandBuffer<P::Alloc>, P>
+where
+    P: CommandPoolBuilderAlloc,
+{
+    /// Starts recording a primary command buffer that is allocated from `pool`.
+    #[inline]
+    pub fn primary_from_pool<Cp>(
+        pool: &Cp,
+        usage: CommandBufferUsage,
+    ) -> Result<Self, CommandBufferBeginError>
+    where
+        Cp: CommandPool<Builder = P>,
+    {
+        let pool_builder_alloc = pool
+            .allocate(CommandBufferLevel::Primary, 1)?
+            .next()
+            .expect("Requested one command buffer from the command pool, but got zero.");
+
+        unsafe {
+            AutoCommandBufferBuilder::begin_with_pool_alloc(
+                pool_builder_alloc,
+                CommandBufferBeginInfo {
+                    usage,
+                    inheritance_info: None,
+                    _ne: crate::NonExhaustive(()),
+                },
+            )
+        }
+    }
+}
+
+impl<P> AutoCommandBufferBuilder<SecondaryAutoCommandBuffer<P::Alloc>, P>
+where
+    P: CommandPoolBuilderAlloc,
+{
+    /// Starts recording a secondary command buffer that is allocated from `pool`.
+    #[inline]
+    pub fn secondary_from_pool<Cp>(
+        pool: &Cp,
+        usage: CommandBufferUsage,
+        inheritance_info: CommandBufferInheritanceInfo,
+    ) -> Result<Self, CommandBufferBeginError>
+    where
+        Cp: CommandPool<Builder = P>,
+    {
+        let pool_builder_alloc = pool
+            .allocate(CommandBufferLevel::Secondary, 1)?
+            .next()
+            .expect("Requested one command buffer from the command pool, but got zero.");
+
+        unsafe {
+            AutoCommandBufferBuilder::begin_with_pool_alloc(
+                pool_builder_alloc,
+                CommandBufferBeginInfo {
+                    usage,
+                    inheritance_info: Some(inheritance_info),
+                    _ne: crate::NonExhaustive(()),
+                },
+            )
+        }
+    }
+}
+
 impl<L> AutoCommandBufferBuilder<L, StandardCommandPoolBuilder> {
-    // Actual constructor. Private.
+    // Allocates from the standard command pool of the current thread.
     //
     // `begin_info.inheritance_info` must match `level`.
     unsafe fn begin(
@@ -186,6 +262,33 @@ impl<L> AutoCommandBufferBuilder<L, StandardCommandPoolBuilder> {
         begin_info: CommandBufferBeginInfo,
     ) -> Result<AutoCommandBufferBuilder<L, StandardCommandPoolBuilder>, CommandBufferBeginError>
     {
+        device.with_standard_command_pool(queue_family_index, |pool| {
+            let pool_builder_alloc = pool
+                .allocate(level, 1)?
+                .next()
+                .expect("Requested one command buffer from the command pool, but got zero.");
+
+            AutoCommandBufferBuilder::begin_with_pool_alloc(pool_builder_alloc, begin_info)
+        })?
+    }
+}
+
+impl<L, P> AutoCommandBufferBuilder<L, P>
+where
+    P: CommandPoolBuilderAlloc,
+{
+    // Actual constructor. Private.
+    //
+    // `begin_info.inheritance_info` must match the level of `pool_builder_alloc`. If the command
+    // buffer was recorded before, it is implicitly reset.
+    unsafe fn begin_with_pool_alloc(
+        pool_builder_alloc: P,
+        begin_info: CommandBufferBeginInfo,
+    ) -> Result<AutoCommandBufferBuilder<L, P>, CommandBufferBeginError> {
+        let device = pool_builder_alloc.device().clone();
+        let queue_family_index = pool_builder_alloc.queue_family_index();
+        let level = pool_builder_alloc.inner().level();
+
         Self::validate_begin(&device, queue_family_index, level, &begin_info)?;
 
         let &CommandBufferBeginInfo {
@@ -202,6 +305,7 @@ impl<L> AutoCommandBufferBuilder<L, StandardCommandPoolBuilder> {
                 ref render_pass,
                 occlusion_query: _,
                 query_statistics_flags: _,
+                conditional_rendering: _,
                 _ne: _,
             } = inheritance_info;
 
@@ -225,6 +329,13 @@ impl<L> AutoCommandBufferBuilder<L, StandardCommandPoolBuilder> {
                             render_pass: BeginRenderPassState {
                                 subpass: info.subpass.clone(),
                                 framebuffer: info.framebuffer.clone(),
+                                // The image views of an imageless framebuffer are not known until
+                                // the render pass is begun.
+                                attachments: info
+                                    .framebuffer
+                                    .as_ref()
+                                    .filter(|framebuffer| !framebuffer.is_imageless())
+                                    .map(|framebuffer| framebuffer.attachments().to_vec()),
                             }
                             .into(),
                             view_mask: info.subpass.subpass_desc().view_mask,
@@ -250,25 +361,20 @@ impl<L> AutoCommandBufferBuilder<L, StandardCommandPoolBuilder> {
             }
         }
 
-        device.with_standard_command_pool(queue_family_index, |pool| {
-            let pool_builder_alloc = pool
-                .allocate(level, 1)?
-                .next()
-                .expect("Requested one command buffer from the command pool, but got zero.");
-
-            let inner = SyncCommandBufferBuilder::new(pool_builder_alloc.inner(), begin_info)?;
-
-            Ok(AutoCommandBufferBuilder {
-                inner,
-                pool_builder_alloc,
-                queue_family_index,
-                render_pass_state,
-                query_state: HashMap::default(),
-                inheritance_info,
-                usage,
-                _data: PhantomData,
-            })
-        })?
+        let inner = SyncCommandBufferBuilder::new(pool_builder_alloc.inner(), begin_info)?;
+
+        Ok(AutoCommandBufferBuilder {
+            inner,
+            pool_builder_alloc,
+            queue_family_index,
+            render_pass_state,
+            query_state: HashMap::default(),
+            conditional_rendering_state: None,
+            transform_feedback_active: false,
+            inheritance_info,
+            usage,
+            _data: PhantomData,
+        })
     }
 
     fn validate_begin(
@@ -293,6 +399,7 @@ impl<L> AutoCommandBufferBuilder<L, StandardCommandPoolBuilder> {
                 ref render_pass,
                 occlusion_query,
                 query_statistics_flags,
+                conditional_rendering,
                 _ne: _,
             } = inheritance_info;
 
@@ -488,6 +595,17 @@ impl<L> AutoCommandBufferBuilder<L, StandardCommandPoolBuilder> {
                     },
                 });
             }
+
+            // VUID-VkCommandBufferInheritanceConditionalRenderingInfoEXT-conditionalRenderingEnable-01977
+            if conditional_rendering && !device.enabled_features().inherited_conditional_rendering {
+                return Err(CommandBufferBeginError::RequirementNotMet {
+                    required_for: "`inheritance_info.conditional_rendering` is `true`",
+                    requires_one_of: RequiresOneOf {
+                        features: &["inherited_conditional_rendering"],
+                        ..Default::default()
+                    },
+                });
+            }
         } else {
             debug_assert!(level == CommandBufferLevel::Primary);
 
@@ -525,6 +643,14 @@ pub enum CommandBufferBeginError {
     /// The `max_multiview_view_count` limit has been exceeded.
     MaxMultiviewViewCountExceeded { view_count: u32, max: u32 },
 
+    /// The command buffer is being reset on a different thread than the one that owns the pool it
+    /// was allocated from.
+    PoolOwnedByOtherThread,
+
+    /// The command buffer is being reset, but it was allocated from a pool that was not created
+    /// with `reset_command_buffer` enabled.
+    ResetCommandBufferNotEnabled,
+
     /// The stencil attachment has a format that does not support that usage.
     StencilAttachmentFormatUsageNotSupported,
 }
@@ -569,6 +695,16 @@ impl Display for CommandBufferBeginError {
             Self::MaxMultiviewViewCountExceeded { .. } => {
                 write!(f, "the `max_multiview_view_count` limit has been exceeded")
             }
+            Self::PoolOwnedByOtherThread => write!(
+                f,
+                "the command buffer is being reset on a different thread than the one that owns \
+                the pool it was allocated from",
+            ),
+            Self::ResetCommandBufferNotEnabled => write!(
+                f,
+                "the command buffer is being reset, but it was allocated from a pool that was not \
+                created with `reset_command_buffer` enabled",
+            ),
             Self::StencilAttachmentFormatUsageNotSupported => write!(
                 f,
                 "the stencil attachment has a format that does not support that usage",
@@ -606,6 +742,14 @@ where
             return Err(BuildError::QueryActive);
         }
 
+        if self.conditional_rendering_state.is_some() {
+            return Err(BuildError::ConditionalRenderingActive);
+        }
+
+        if self.transform_feedback_active {
+            return Err(BuildError::TransformFeedbackActive);
+        }
+
         let submit_state = match self.usage {
             CommandBufferUsage::MultipleSubmit => SubmitState::ExclusiveUse {
                 in_use: AtomicBool::new(false),
@@ -634,6 +778,14 @@ where
             return Err(BuildError::QueryActive);
         }
 
+        if self.conditional_rendering_state.is_some() {
+            return Err(BuildError::ConditionalRenderingActive);
+        }
+
+        if self.transform_feedback_active {
+            return Err(BuildError::TransformFeedbackActive);
+        }
+
         let submit_state = match self.usage {
             CommandBufferUsage::MultipleSubmit => SubmitState::ExclusiveUse {
                 in_use: AtomicBool::new(false),
@@ -663,6 +815,12 @@ pub enum BuildError {
 
     /// A query is still active on the command buffer.
     QueryActive,
+
+    /// Conditional rendering is still active on the command buffer.
+    ConditionalRenderingActive,
+
+    /// Transform feedback is still active on the command buffer.
+    TransformFeedbackActive,
 }
 
 impl Error for BuildError {
@@ -682,6 +840,14 @@ impl Display for BuildError {
                 write!(f, "a render pass is still active on the command buffer")
             }
             Self::QueryActive => write!(f, "a query is still active on the command buffer"),
+            Self::ConditionalRenderingActive => write!(
+                f,
+                "conditional rendering is still active on the command buffer",
+            ),
+            Self::TransformFeedbackActive => write!(
+                f,
+                "transform feedback is still active on the command buffer",
+            ),
         }
     }
 }
@@ -717,6 +883,77 @@ pub struct PrimaryAutoCommandBuffer<P = StandardCommandPoolAlloc> {
     submit_state: SubmitState,
 }
 
+impl<P> PrimaryAutoCommandBuffer<P>
+where
+    P: CommandPoolAlloc,
+{
+    /// Resets the command buffer, and starts recording it again with the given `usage`.
+    ///
+    /// The resources that were used by the previous recording are released, and the same Vulkan
+    /// command buffer is reused instead of allocating a new one. The command buffer must have been
+    /// allocated from a pool that was created with `reset_command_buffer` enabled, which is the
+    /// case for `StandardCommandPool`.
+    ///
+    /// The command buffer must be reset on the thread that owns the pool it was allocated from,
+    /// which for `StandardCommandPool` is the thread that it was first recorded on. Otherwise,
+    /// [`CommandBufferBeginError::PoolOwnedByOtherThread`] is returned, and the command buffer is
+    /// dropped.
+    ///
+    /// Because this takes the command buffer by value, it can't be pending execution: anything
+    /// that submits the command buffer, such as the future returned by
+    /// [`execute`](PrimaryCommandBuffer::execute) or a primary command buffer that executes a
+    /// secondary one, keeps a reference to it until the GPU has finished executing it.
+    pub fn reset(
+        self,
+        usage: CommandBufferUsage,
+    ) -> Result<
+        AutoCommandBufferBuilder<PrimaryAutoCommandBuffer<P>, P::Builder>,
+        CommandBufferBeginError,
+    > {
+        let PrimaryAutoCommandBuffer {
+            inner,
+            _pool_alloc: pool_alloc,
+            submit_state: _,
+        } = self;
+
+        // VUID-vkBeginCommandBuffer-commandBuffer-00050
+        if !pool_alloc.inner().reset_command_buffer() {
+            return Err(CommandBufferBeginError::ResetCommandBufferNotEnabled);
+        }
+
+        // The command buffer is recorded into its pool, which must only be used by the thread that
+        // owns it. Finished command buffers can be sent to other threads, so check it here.
+        let builder_alloc = match pool_alloc.into_builder() {
+            Ok(builder_alloc) => builder_alloc,
+            Err(pool_alloc) => {
+                drop(inner);
+                drop(pool_alloc);
+                return Err(CommandBufferBeginError::PoolOwnedByOtherThread);
+            }
+        };
+
+        // The command buffer can't be pending execution, because we own it. This relies on every
+        // submission holding on to the command buffer until it has finished executing: futures
+        // block in their destructor until the queue is idle, and the queue keeps the resources of
+        // a submission alive until its fence is signaled. If a future is leaked instead, the
+        // command buffer is leaked with it, and can't be reset here.
+        //
+        // Release the resources of the previous recording before the command buffer is reused.
+        drop(inner);
+
+        unsafe {
+            AutoCommandBufferBuilder::begin_with_pool_alloc(
+                builder_alloc,
+                CommandBufferBeginInfo {
+                    usage,
+                    inheritance_info: None,
+                    _ne: crate::NonExhaustive(()),
+                },
+            )
+        }
+    }
+}
+
 unsafe impl<P> DeviceOwned for PrimaryAutoCommandBuffer<P> {
     fn device(&self) -> &Arc<Device> {
         self.inner.device()
@@ -826,6 +1063,67 @@ pub struct SecondaryAutoCommandBuffer<P = StandardCommandPoolAlloc> {
     submit_state: SubmitState,
 }
 
+impl<P> SecondaryAutoCommandBuffer<P>
+where
+    P: CommandPoolAlloc,
+{
+    /// Resets the command buffer, and starts recording it again with the given `usage` and
+    /// `inheritance_info`.
+    ///
+    /// See [`PrimaryAutoCommandBuffer::reset`] for more.
+    pub fn reset(
+        self,
+        usage: CommandBufferUsage,
+        inheritance_info: CommandBufferInheritanceInfo,
+    ) -> Result<
+        AutoCommandBufferBuilder<SecondaryAutoCommandBuffer<P>, P::Builder>,
+        CommandBufferBeginError,
+    > {
+        let SecondaryAutoCommandBuffer {
+            inner,
+            _pool_alloc: pool_alloc,
+            inheritance_info: _,
+            submit_state: _,
+        } = self;
+
+        // VUID-vkBeginCommandBuffer-commandBuffer-00050
+        if !pool_alloc.inner().reset_command_buffer() {
+            return Err(CommandBufferBeginError::ResetCommandBufferNotEnabled);
+        }
+
+        // The command buffer is recorded into its pool, which must only be used by the thread that
+        // owns it. Finished command buffers can be sent to other threads, so check it here.
+        let builder_alloc = match pool_alloc.into_builder() {
+            Ok(builder_alloc) => builder_alloc,
+            Err(pool_alloc) => {
+                drop(inner);
+                drop(pool_alloc);
+                return Err(CommandBufferBeginError::PoolOwnedByOtherThread);
+            }
+        };
+
+        // The command buffer can't be pending execution, because we own it. This relies on every
+        // submission holding on to the command buffer until it has finished executing: futures
+        // block in their destructor until the queue is idle, and the queue keeps the resources of
+        // a submission alive until its fence is signaled. If a future is leaked instead, the
+        // command buffer is leaked with it, and can't be reset here.
+        //
+        // Release the resources of the previous recording before the command buffer is reused.
+        drop(inner);
+
+        unsafe {
+            AutoCommandBufferBuilder::begin_with_pool_alloc(
+                builder_alloc,
+                CommandBufferBeginInfo {
+                    usage,
+                    inheritance_info: Some(inheritance_info),
+                    _ne: crate::NonExhaustive(()),
+                },
+            )
+        }
+    }
+}
+
 unsafe impl<P> DeviceOwned for SecondaryAutoCommandBuffer<P> {
     fn device(&self) -> &Arc<Device> {
         self.inner.device()
@@ -939,13 +1237,22 @@ enum SubmitState {
 mod tests {
     use super::*;
     use crate::{
-        buffer::{BufferUsage, CpuAccessibleBuffer},
+        buffer::{BufferUsage, CpuAccessibleBuffer, DeviceLocalBuffer},
         command_buffer::{
-            synced::SyncCommandBufferBuilderError, BufferCopy, CopyBufferInfoTyped, CopyError,
-            ExecuteCommandsError,
+            pool::FrameCommandPool, synced::SyncCommandBufferBuilderError, BufferCopy,
+            ConditionalRenderingError, CopyBufferInfoTyped, CopyError, ExecuteCommandsError,
+            OwnershipTransferError, QueryError, RenderPassBeginInfo, SubpassContents,
+            TransformFeedbackError,
         },
         device::{DeviceCreateInfo, QueueCreateInfo},
+        pipeline::{graphics::rasterization::RasterizationState, GraphicsPipeline, StateMode},
+        query::{QueryPool, QueryPoolCreateInfo},
+        render_pass::{FramebufferCreateInfo, RenderPass},
+        shader::{reflect::tests::empty_vertex_shader, ShaderModule},
+        sync::QueueFamilyTransfer,
+        VulkanObject,
     };
+    use std::thread;
 
     #[test]
     fn copy_buffer_dimensions() {
@@ -1187,4 +1494,389 @@ mod tests {
             })
         ));
     }
+
+    #[test]
+    fn conditional_rendering_extension_missing() {
+        let (device, queue) = gfx_dev_and_queue!();
+
+        let buffer = CpuAccessibleBuffer::from_data(
+            device.clone(),
+            BufferUsage {
+                conditional_rendering: true,
+                ..BufferUsage::empty()
+            },
+            true,
+            1_u32,
+        )
+        .unwrap();
+
+        let mut builder = AutoCommandBufferBuilder::primary(
+            device,
+            queue.queue_family_index(),
+            CommandBufferUsage::OneTimeSubmit,
+        )
+        .unwrap();
+
+        assert!(matches!(
+            builder.begin_conditional_rendering(buffer, false),
+            Err(ConditionalRenderingError::RequirementNotMet { .. })
+        ));
+    }
+
+    #[test]
+    fn transform_feedback_feature_missing() {
+        let (device, queue) = gfx_dev_and_queue!();
+
+        let mut builder = AutoCommandBufferBuilder::primary(
+            device,
+            queue.queue_family_index(),
+            CommandBufferUsage::OneTimeSubmit,
+        )
+        .unwrap();
+
+        assert!(matches!(
+            builder.end_transform_feedback(0, []),
+            Err(TransformFeedbackError::RequirementNotMet { .. })
+        ));
+    }
+
+    #[test]
+    fn begin_transform_feedback_pipeline() {
+        let (device, queue) = gfx_dev_and_queue!(
+            extensions: [ext_transform_feedback],
+            features: [transform_feedback]
+        );
+
+        let render_pass = RenderPass::empty_single_pass(device.clone()).unwrap();
+        let framebuffer = Framebuffer::new(
+            render_pass.clone(),
+            FramebufferCreateInfo {
+                extent: [64, 64],
+                layers: 1,
+                ..Default::default()
+            },
+        )
+        .unwrap();
+
+        let create_pipeline = |module: &ShaderModule| {
+            GraphicsPipeline::start()
+                .vertex_shader(module.entry_point("main").unwrap(), ())
+                .rasterization_state(RasterizationState {
+                    rasterizer_discard_enable: StateMode::Fixed(true),
+                    ..Default::default()
+                })
+                .render_pass(Subpass::from(render_pass.clone(), 0).unwrap())
+                .build(device.clone())
+                .unwrap()
+        };
+        let [module, transform_feedback_module] = [false, true].map(|transform_feedback| unsafe {
+            ShaderModule::from_words(device.clone(), &empty_vertex_shader(transform_feedback))
+                .unwrap()
+        });
+        let pipeline = create_pipeline(&module);
+        let transform_feedback_pipeline = create_pipeline(&transform_feedback_module);
+        assert!(!pipeline.transform_feedback());
+        assert!(transform_feedback_pipeline.transform_feedback());
+
+        let mut builder = AutoCommandBufferBuilder::primary(
+            device,
+            queue.queue_family_index(),
+            CommandBufferUsage::OneTimeSubmit,
+        )
+        .unwrap();
+        builder
+            .begin_render_pass(
+                RenderPassBeginInfo::framebuffer(framebuffer),
+                SubpassContents::Inline,
+            )
+            .unwrap();
+
+        assert!(matches!(
+            builder.begin_transform_feedback(0, []),
+            Err(TransformFeedbackError::PipelineNotBound)
+        ));
+
+        builder.bind_pipeline_graphics(pipeline);
+        assert!(matches!(
+            builder.begin_transform_feedback(0, []),
+            Err(TransformFeedbackError::PipelineNoTransformFeedback)
+        ));
+
+        builder.bind_pipeline_graphics(transform_feedback_pipeline);
+        builder.begin_transform_feedback(0, []).unwrap();
+        builder.end_transform_feedback(0, []).unwrap();
+        builder.end_render_pass().unwrap();
+    }
+
+    #[test]
+    fn query_indexed_extension_missing() {
+        let (device, queue) = gfx_dev_and_queue!();
+
+        let query_pool = QueryPool::new(
+            device.clone(),
+            QueryPoolCreateInfo {
+                query_count: 1,
+                ..QueryPoolCreateInfo::query_type(QueryType::Occlusion)
+            },
+        )
+        .unwrap();
+
+        let mut builder = AutoCommandBufferBuilder::primary(
+            device,
+            queue.queue_family_index(),
+            CommandBufferUsage::OneTimeSubmit,
+        )
+        .unwrap();
+
+        unsafe {
+            assert!(matches!(
+                builder.begin_query_indexed(query_pool, 0, QueryControlFlags::empty(), 0),
+                Err(QueryError::RequirementNotMet { .. })
+            ));
+        }
+    }
+
+    #[test]
+    fn query_indexed() {
+        let (device, queue) =
+            gfx_dev_and_queue!(extensions: [ext_transform_feedback], features: []);
+
+        let occlusion_pool = QueryPool::new(
+            device.clone(),
+            QueryPoolCreateInfo {
+                query_count: 1,
+                ..QueryPoolCreateInfo::query_type(QueryType::Occlusion)
+            },
+        )
+        .unwrap();
+
+        let mut builder = AutoCommandBufferBuilder::primary(
+            device.clone(),
+            queue.queue_family_index(),
+            CommandBufferUsage::OneTimeSubmit,
+        )
+        .unwrap();
+
+        unsafe {
+            // Only transform feedback stream queries have more than one stream.
+            assert!(matches!(
+                builder.begin_query_indexed(
+                    occlusion_pool.clone(),
+                    0,
+                    QueryControlFlags::empty(),
+                    1,
+                ),
+                Err(QueryError::IndexNotZero)
+            ));
+            builder
+                .begin_query_indexed(occlusion_pool.clone(), 0, QueryControlFlags::empty(), 0)
+                .unwrap();
+        }
+        builder.end_query_indexed(occlusion_pool, 0, 0).unwrap();
+
+        let properties = device.physical_device().properties();
+
+        if !properties.transform_feedback_queries.unwrap_or(false) {
+            return;
+        }
+
+        let max_transform_feedback_streams = properties.max_transform_feedback_streams.unwrap();
+        let stream_pool = QueryPool::new(
+            device.clone(),
+            QueryPoolCreateInfo {
+                query_count: 2,
+                ..QueryPoolCreateInfo::query_type(QueryType::TransformFeedbackStream)
+            },
+        )
+        .unwrap();
+
+        unsafe {
+            assert!(matches!(
+                builder.begin_query_indexed(
+                    stream_pool.clone(),
+                    0,
+                    QueryControlFlags::empty(),
+                    max_transform_feedback_streams,
+                ),
+                Err(QueryError::StreamIndexOutOfRange { .. })
+            ));
+
+            // `begin_query` queries stream 0, so it can't be queried again at the same time.
+            builder
+                .begin_query(stream_pool.clone(), 0, QueryControlFlags::empty())
+                .unwrap();
+            assert!(matches!(
+                builder.begin_query_indexed(stream_pool.clone(), 1, QueryControlFlags::empty(), 0,),
+                Err(QueryError::QueryIsActive)
+            ));
+
+            if max_transform_feedback_streams > 1 {
+                builder
+                    .begin_query_indexed(stream_pool.clone(), 1, QueryControlFlags::empty(), 1)
+                    .unwrap();
+                assert!(matches!(
+                    builder.end_query_indexed(stream_pool.clone(), 1, 0),
+                    Err(QueryError::QueryNotActive)
+                ));
+                builder
+                    .end_query_indexed(stream_pool.clone(), 1, 1)
+                    .unwrap();
+            }
+        }
+
+        builder.end_query(stream_pool, 0).unwrap();
+    }
+
+    #[test]
+    fn reset_reuses_command_buffer() {
+        let (device, queue) = gfx_dev_and_queue!();
+
+        let cb = AutoCommandBufferBuilder::primary(
+            device,
+            queue.queue_family_index(),
+            CommandBufferUsage::MultipleSubmit,
+        )
+        .unwrap()
+        .build()
+        .unwrap();
+        let raw = cb.inner().internal_object();
+
+        let cb = cb
+            .reset(CommandBufferUsage::OneTimeSubmit)
+            .unwrap()
+            .build()
+            .unwrap();
+        assert_eq!(cb.inner().internal_object(), raw);
+    }
+
+    #[test]
+    fn reset_on_other_thread() {
+        let (device, queue) = gfx_dev_and_queue!();
+
+        let cb = AutoCommandBufferBuilder::primary(
+            device,
+            queue.queue_family_index(),
+            CommandBufferUsage::MultipleSubmit,
+        )
+        .unwrap()
+        .build()
+        .unwrap();
+
+        // The command buffer was allocated from the standard pool of this thread.
+        let result = thread::spawn(move || cb.reset(CommandBufferUsage::OneTimeSubmit).err())
+            .join()
+            .unwrap();
+        assert!(matches!(
+            result,
+            Some(CommandBufferBeginError::PoolOwnedByOtherThread)
+        ));
+    }
+
+    #[test]
+    fn reset_command_buffer_not_enabled() {
+        let (device, queue) = gfx_dev_and_queue!();
+
+        let pool = Arc::new(FrameCommandPool::new(device, queue.queue_family_index()).unwrap());
+        let cb =
+            AutoCommandBufferBuilder::primary_from_pool(&pool, CommandBufferUsage::OneTimeSubmit)
+                .unwrap()
+                .build()
+                .unwrap();
+
+        assert!(matches!(
+            cb.reset(CommandBufferUsage::OneTimeSubmit),
+            Err(CommandBufferBeginError::ResetCommandBufferNotEnabled)
+        ));
+    }
+
+    #[test]
+    fn transfer_ownership_validation() {
+        let (device, queue) = gfx_dev_and_queue!();
+        let queue_family_index = queue.queue_family_index();
+
+        let buffer = CpuAccessibleBuffer::from_data(
+            device.clone(),
+            BufferUsage {
+                transfer_dst: true,
+                ..BufferUsage::empty()
+            },
+            false,
+            0_u32,
+        )
+        .unwrap();
+
+        let mut builder = AutoCommandBufferBuilder::primary(
+            device.clone(),
+            queue_family_index,
+            CommandBufferUsage::OneTimeSubmit,
+        )
+        .unwrap();
+
+        unsafe {
+            assert!(matches!(
+                builder.transfer_buffer_ownership(
+                    buffer.clone(),
+                    QueueFamilyTransfer {
+                        source_index: queue_family_index,
+                        destination_index: queue_family_index,
+                    },
+                ),
+                Err(OwnershipTransferError::QueueFamilyMismatch { .. })
+            ));
+            assert!(matches!(
+                builder.transfer_buffer_ownership(
+                    buffer.clone(),
+                    QueueFamilyTransfer {
+                        source_index: queue_family_index,
+                        destination_index: u32::MAX,
+                    },
+                ),
+                Err(OwnershipTransferError::QueueFamilyIndexOutOfRange { .. })
+            ));
+        }
+
+        // The rest needs a second queue family.
+        let other_queue_family_index =
+            match (0..device.physical_device().queue_family_properties().len() as u32)
+                .find(|&index| index != queue_family_index)
+            {
+                Some(index) => index,
+                None => return,
+            };
+
+        let concurrent_buffer = DeviceLocalBuffer::<[u32]>::array(
+            device,
+            1,
+            BufferUsage {
+                transfer_dst: true,
+                ..BufferUsage::empty()
+            },
+            [queue_family_index, other_queue_family_index],
+        )
+        .unwrap();
+
+        unsafe {
+            assert!(matches!(
+                builder.transfer_buffer_ownership(
+                    concurrent_buffer,
+                    QueueFamilyTransfer {
+                        source_index: queue_family_index,
+                        destination_index: other_queue_family_index,
+                    },
+                ),
+                Err(OwnershipTransferError::NotExclusive)
+            ));
+            builder
+                .transfer_buffer_ownership(
+                    buffer,
+                    QueueFamilyTransfer {
+                        source_index: queue_family_index,
+                        destination_index: other_queue_family_index,
+                    },
+                )
+                .unwrap();
+        }
+
+        builder.build().unwrap();
+    }
 }
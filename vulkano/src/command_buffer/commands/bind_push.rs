@@ -11,14 +11,18 @@ use crate::{
     buffer::{BufferAccess, BufferContents, TypedBufferAccess},
     command_buffer::{
         auto::RenderPassStateType,
-        synced::{Command, SetOrPush, SyncCommandBufferBuilder},
+        synced::{
+            Command, Resource, SetOrPush, SyncCommandBufferBuilder, SyncCommandBufferBuilderError,
+        },
         sys::UnsafeCommandBufferBuilder,
         AutoCommandBufferBuilder,
     },
     descriptor_set::{
-        check_descriptor_write, sys::UnsafeDescriptorSet, DescriptorSetResources,
-        DescriptorSetUpdateError, DescriptorSetWithOffsets, DescriptorSetsCollection,
-        DescriptorWriteInfo, WriteDescriptorSet,
+        check_descriptor_write,
+        sys::UnsafeDescriptorSet,
+        update_template::{DescriptorUpdateTemplateData, DescriptorUpdateTemplateType},
+        DescriptorSetResources, DescriptorSetUpdateError, DescriptorSetWithOffsets,
+        DescriptorSetsCollection, DescriptorWriteInfo, WriteDescriptorSet,
     },
     device::DeviceOwned,
     pipeline::{
@@ -30,6 +34,7 @@ use crate::{
         ComputePipeline, GraphicsPipeline, PipelineBindPoint, PipelineLayout,
     },
     shader::ShaderStages,
+    sync::{AccessFlags, PipelineMemoryAccess, PipelineStages},
     DeviceSize, RequirementNotMet, RequiresOneOf, VulkanObject,
 };
 use parking_lot::Mutex;
@@ -244,6 +249,7 @@ impl<L, P> AutoCommandBufferBuilder<L, P> {
     ///
     /// - Panics if the queue family of the command buffer does not support graphics operations.
     /// - Panics if `self` and `pipeline` do not belong to the same device.
+    /// - Panics if transform feedback is active.
     pub fn bind_pipeline_graphics(&mut self, pipeline: Arc<GraphicsPipeline>) -> &mut Self {
         self.validate_bind_pipeline_graphics(&pipeline).unwrap();
 
@@ -268,6 +274,11 @@ impl<L, P> AutoCommandBufferBuilder<L, P> {
         // VUID-vkCmdBindPipeline-commonparent
         assert_eq!(self.device(), pipeline.device());
 
+        // VUID-vkCmdBindPipeline-None-02323
+        if self.transform_feedback_active {
+            return Err(BindPushError::TransformFeedbackActive);
+        }
+
         if let Some(last_pipeline) = self
             .render_pass_state
             .as_ref()
@@ -395,6 +406,13 @@ impl<L, P> AutoCommandBufferBuilder<L, P> {
     /// - Panics if the size of `push_constants` is not a multiple of 4.
     /// - Panics if any of the bytes in `push_constants` do not fall within any of the pipeline
     ///   layout's push constant ranges.
+    /// - Panics if a buffer whose [device address](crate::buffer::DeviceAddress) is contained in
+    ///   `push_constants` is written by a previous command inside the current render pass.
+    ///
+    /// Buffers whose [`DeviceAddress`](crate::buffer::DeviceAddress) is alive and whose address is
+    /// contained in `push_constants` are automatically declared as being read by subsequent
+    /// commands, as with [`reference_buffer`](Self::reference_buffer). If a shader writes to a
+    /// buffer through its address, it must still be declared with `reference_buffer`.
     pub fn push_constants<Pc>(
         &mut self,
         pipeline_layout: Arc<PipelineLayout>,
@@ -419,6 +437,14 @@ impl<L, P> AutoCommandBufferBuilder<L, P> {
         self.validate_push_constants(&pipeline_layout, offset, push_constants)
             .unwrap();
 
+        let referenced_buffers = self.device().buffers_at_device_addresses(push_constants);
+
+        for buffer in referenced_buffers {
+            unsafe {
+                self.inner.reference_buffer(buffer, false).unwrap();
+            }
+        }
+
         let mut current_offset = offset;
         let mut remaining_size = size;
         for range in pipeline_layout
@@ -503,6 +529,49 @@ impl<L, P> AutoCommandBufferBuilder<L, P> {
         Ok(())
     }
 
+    /// Declares that `buffer` is accessed by subsequent dispatch or draw calls through its
+    /// [device address](crate::buffer::TypedBufferAccess::device_address).
+    ///
+    /// Buffers whose address is passed in push constants or written into a buffer from the host
+    /// are declared automatically for reading, see [`DeviceAddress`](crate::buffer::DeviceAddress).
+    /// This must be called for the buffers that are written through their address, and for
+    /// addresses that reach the device in other ways.
+    ///
+    /// The buffer is kept alive for as long as the command buffer, and is included in its
+    /// resource tracking as being read, and also written if `write` is `true`, by all commands.
+    /// An error is returned if this access conflicts with a previous access inside the current
+    /// render pass.
+    ///
+    /// # Panics
+    ///
+    /// - Panics if `self` and `buffer` do not belong to the same device.
+    /// - Panics if `buffer` was not created with the `shader_device_address` usage.
+    pub fn reference_buffer(
+        &mut self,
+        buffer: Arc<dyn BufferAccess>,
+        write: bool,
+    ) -> Result<&mut Self, SyncCommandBufferBuilderError> {
+        self.validate_reference_buffer(&buffer).unwrap();
+
+        unsafe {
+            self.inner.reference_buffer(buffer, write)?;
+        }
+
+        Ok(self)
+    }
+
+    fn validate_reference_buffer(&self, buffer: &dyn BufferAccess) -> Result<(), BindPushError> {
+        // VUID-VkBufferDeviceAddressInfo-buffer-02600
+        assert_eq!(self.device(), buffer.device());
+
+        // VUID-VkBufferDeviceAddressInfo-buffer-02601
+        if !buffer.usage().shader_device_address {
+            return Err(BindPushError::DeviceAddressBufferMissingUsage);
+        }
+
+        Ok(())
+    }
+
     /// Pushes descriptor data directly into the command buffer for future dispatch or draw calls.
     ///
     /// # Panics
@@ -602,6 +671,71 @@ impl<L, P> AutoCommandBufferBuilder<L, P> {
 
         Ok(())
     }
+
+    /// Pushes descriptor data directly into the command buffer for future dispatch or draw calls,
+    /// using a descriptor update template.
+    ///
+    /// The pipeline bind point, pipeline layout and set number are those that the template was
+    /// created with. The descriptor writes in `data` were already validated when it was created,
+    /// so this is cheaper than [`push_descriptor_set`](Self::push_descriptor_set).
+    ///
+    /// # Panics
+    ///
+    /// - Panics if the template of `data` was not created for push descriptors.
+    /// - Panics if the queue family of the command buffer does not support the pipeline bind
+    ///   point of the template.
+    /// - Panics if `self` and `data` do not belong to the same device.
+    pub fn push_descriptor_set_with_template(
+        &mut self,
+        data: Arc<DescriptorUpdateTemplateData>,
+    ) -> &mut Self {
+        self.validate_push_descriptor_set_with_template(&data)
+            .unwrap();
+
+        unsafe {
+            self.inner.push_descriptor_set_with_template(data);
+        }
+
+        self
+    }
+
+    fn validate_push_descriptor_set_with_template(
+        &self,
+        data: &DescriptorUpdateTemplateData,
+    ) -> Result<(), BindPushError> {
+        let template = data.template();
+
+        // VUID-vkCmdPushDescriptorSetWithTemplateKHR-commonparent
+        assert_eq!(self.device(), template.device());
+
+        let pipeline_bind_point = match template.template_type() {
+            DescriptorUpdateTemplateType::PushDescriptors {
+                pipeline_bind_point,
+                ..
+            } => *pipeline_bind_point,
+            DescriptorUpdateTemplateType::DescriptorSet => {
+                return Err(BindPushError::DescriptorUpdateTemplateNotPush)
+            }
+        };
+
+        let queue_family_properties = self.queue_family_properties();
+
+        // VUID-vkCmdPushDescriptorSetWithTemplateKHR-commandBuffer-00366
+        match pipeline_bind_point {
+            PipelineBindPoint::Compute => {
+                if !queue_family_properties.queue_flags.compute {
+                    return Err(BindPushError::NotSupportedByQueueFamily);
+                }
+            }
+            PipelineBindPoint::Graphics => {
+                if !queue_family_properties.queue_flags.graphics {
+                    return Err(BindPushError::NotSupportedByQueueFamily);
+                }
+            }
+        }
+
+        Ok(())
+    }
 }
 
 impl SyncCommandBufferBuilder {
@@ -766,6 +900,58 @@ impl SyncCommandBufferBuilder {
         self.current_state.push_constants_pipeline_layout = Some(pipeline_layout);
     }
 
+    /// Adds `buffer` to the resources of the command buffer, as accessed by the shaders through
+    /// its device address. No command is recorded.
+    pub unsafe fn reference_buffer(
+        &mut self,
+        buffer: Arc<dyn BufferAccess>,
+        write: bool,
+    ) -> Result<(), SyncCommandBufferBuilderError> {
+        struct Cmd {
+            _buffer: Arc<dyn BufferAccess>,
+        }
+
+        impl Command for Cmd {
+            fn name(&self) -> &'static str {
+                "reference_buffer"
+            }
+
+            unsafe fn send(&self, _out: &mut UnsafeCommandBufferBuilder) {}
+        }
+
+        let resources = [(
+            "buffer".into(),
+            Resource::Buffer {
+                buffer: buffer.clone(),
+                range: 0..buffer.size(),
+                memory: PipelineMemoryAccess {
+                    stages: PipelineStages {
+                        all_commands: true,
+                        ..PipelineStages::empty()
+                    },
+                    access: AccessFlags {
+                        shader_read: true,
+                        shader_write: write,
+                        ..AccessFlags::empty()
+                    },
+                    exclusive: write,
+                },
+            },
+        )];
+
+        for resource in &resources {
+            self.check_resource_conflicts(resource)?;
+        }
+
+        self.commands.push(Box::new(Cmd { _buffer: buffer }));
+
+        for resource in resources {
+            self.add_resource(resource);
+        }
+
+        Ok(())
+    }
+
     /// Calls `vkCmdPushDescriptorSetKHR` on the builder.
     pub unsafe fn push_descriptor_set(
         &mut self,
@@ -828,6 +1014,60 @@ impl SyncCommandBufferBuilder {
             descriptor_writes,
         }));
     }
+
+    /// Calls `vkCmdPushDescriptorSetWithTemplateKHR` on the builder.
+    pub unsafe fn push_descriptor_set_with_template(
+        &mut self,
+        data: Arc<DescriptorUpdateTemplateData>,
+    ) {
+        struct Cmd {
+            data: Arc<DescriptorUpdateTemplateData>,
+        }
+
+        impl Command for Cmd {
+            fn name(&self) -> &'static str {
+                "push_descriptor_set_with_template"
+            }
+
+            unsafe fn send(&self, out: &mut UnsafeCommandBufferBuilder) {
+                out.push_descriptor_set_with_template(&self.data);
+            }
+        }
+
+        let (pipeline_bind_point, pipeline_layout, set_num) = match data.template().template_type()
+        {
+            DescriptorUpdateTemplateType::PushDescriptors {
+                pipeline_bind_point,
+                pipeline_layout,
+                set_num,
+            } => (*pipeline_bind_point, pipeline_layout.clone(), *set_num),
+            DescriptorUpdateTemplateType::DescriptorSet => unreachable!(),
+        };
+
+        let state = self.current_state.invalidate_descriptor_sets(
+            pipeline_bind_point,
+            pipeline_layout,
+            set_num,
+            1,
+        );
+        let layout = state.pipeline_layout.set_layouts()[set_num as usize].as_ref();
+        debug_assert!(layout.push_descriptor());
+
+        let set_resources = match state
+            .descriptor_sets
+            .entry(set_num)
+            .or_insert_with(|| SetOrPush::Push(DescriptorSetResources::new(layout, 0)))
+        {
+            SetOrPush::Push(set_resources) => set_resources,
+            _ => unreachable!(),
+        };
+
+        for write in data.descriptor_writes() {
+            set_resources.update(write);
+        }
+
+        self.commands.push(Box::new(Cmd { data }));
+    }
 }
 
 pub struct SyncCommandBufferBuilderBindDescriptorSets<'b> {
@@ -1139,6 +1379,10 @@ impl UnsafeCommandBufferBuilder {
                     write.descriptor_count = info.len() as u32;
                     write.p_texel_buffer_view = info.as_ptr();
                 }
+                DescriptorWriteInfo::InlineUniformBlock(info) => {
+                    write.descriptor_count = info.data_size;
+                    write.p_next = info as *const _ as *const _;
+                }
             }
 
             debug_assert!(write.descriptor_count != 0);
@@ -1155,6 +1399,35 @@ impl UnsafeCommandBufferBuilder {
             writes.as_ptr(),
         );
     }
+
+    /// Calls `vkCmdPushDescriptorSetWithTemplateKHR` on the builder.
+    pub unsafe fn push_descriptor_set_with_template(
+        &mut self,
+        data: &DescriptorUpdateTemplateData,
+    ) {
+        debug_assert!(self.device.enabled_extensions().khr_push_descriptor);
+
+        let template = data.template();
+        let (pipeline_layout, set_num) = match template.template_type() {
+            DescriptorUpdateTemplateType::PushDescriptors {
+                pipeline_layout,
+                set_num,
+                ..
+            } => (pipeline_layout, *set_num),
+            DescriptorUpdateTemplateType::DescriptorSet => unreachable!(),
+        };
+
+        let fns = self.device.fns();
+
+        (fns.khr_push_descriptor
+            .cmd_push_descriptor_set_with_template_khr)(
+            self.handle,
+            template.internal_object(),
+            pipeline_layout.internal_object(),
+            set_num,
+            data.as_ptr(),
+        );
+    }
 }
 
 /// Prototype for a `vkCmdBindVertexBuffers`.
@@ -1214,6 +1487,12 @@ enum BindPushError {
         pipeline_layout_set_count: u32,
     },
 
+    /// The descriptor update template was not created for push descriptors.
+    DescriptorUpdateTemplateNotPush,
+
+    /// A buffer accessed through its device address is missing the `shader_device_address` usage.
+    DeviceAddressBufferMissingUsage,
+
     /// An index buffer is missing the `index_buffer` usage.
     IndexBufferMissingUsage,
 
@@ -1250,6 +1529,9 @@ enum BindPushError {
     /// The push constants size is not a multiple of 4.
     PushConstantsSizeNotAligned,
 
+    /// Transform feedback is active.
+    TransformFeedbackActive,
+
     /// A vertex buffer is missing the `vertex_buffer` usage.
     VertexBufferMissingUsage,
 }
@@ -1296,6 +1578,15 @@ impl Display for BindPushError {
                 sets in `pipeline_layout` ({})",
                 set_num, pipeline_layout_set_count,
             ),
+            Self::DescriptorUpdateTemplateNotPush => write!(
+                f,
+                "the descriptor update template was not created for push descriptors",
+            ),
+            Self::DeviceAddressBufferMissingUsage => write!(
+                f,
+                "a buffer accessed through its device address is missing the \
+                `shader_device_address` usage",
+            ),
             Self::IndexBufferMissingUsage => {
                 write!(f, "an index buffer is missing the `index_buffer` usage")
             }
@@ -1332,6 +1623,7 @@ impl Display for BindPushError {
             Self::PushConstantsSizeNotAligned => {
                 write!(f, "the push constants size is not a multiple of 4")
             }
+            Self::TransformFeedbackActive => write!(f, "transform feedback is active"),
             Self::VertexBufferMissingUsage => {
                 write!(f, "a vertex buffer is missing the `vertex_buffer` usage")
             }
@@ -0,0 +1,369 @@
+// Copyright (c) 2022 The vulkano developers
+// Licensed under the Apache License, Version 2.0
+// <LICENSE-APACHE or
+// https://www.apache.org/licenses/LICENSE-2.0> or the MIT
+// license <LICENSE-MIT or https://opensource.org/licenses/MIT>,
+// at your option. All files in the project carrying such
+// notice may not be copied, modified, or distributed except
+// according to those terms.
+
+use crate::{
+    buffer::{BufferAccess, TypedBufferAccess},
+    command_buffer::{
+        auto::ConditionalRenderingState,
+        synced::{Command, Resource, SyncCommandBufferBuilder, SyncCommandBufferBuilderError},
+        sys::UnsafeCommandBufferBuilder,
+        AutoCommandBufferBuilder,
+    },
+    device::DeviceOwned,
+    sync::{AccessFlags, PipelineMemoryAccess, PipelineStages},
+    DeviceSize, RequiresOneOf, VulkanObject,
+};
+use std::{
+    error::Error,
+    fmt::{Display, Error as FmtError, Formatter},
+    sync::Arc,
+};
+
+/// # Commands for conditional rendering.
+///
+/// These commands require the
+/// [`ext_conditional_rendering`](crate::device::DeviceExtensions::ext_conditional_rendering)
+/// extension to be enabled on the device.
+impl<L, P> AutoCommandBufferBuilder<L, P> {
+    /// Begins a conditional rendering block.
+    ///
+    /// Until [`end_conditional_rendering`](Self::end_conditional_rendering) is called, draw,
+    /// dispatch and attachment clear commands are discarded if the 32-bit value in `buffer` is
+    /// zero. If `inverted` is `true`, they are discarded if the value is nonzero instead.
+    ///
+    /// The value is read from the buffer when the commands are executed on the device, so it can
+    /// be written by earlier commands, such as an occlusion query result copy.
+    pub fn begin_conditional_rendering<B>(
+        &mut self,
+        buffer: Arc<B>,
+        inverted: bool,
+    ) -> Result<&mut Self, ConditionalRenderingError>
+    where
+        B: TypedBufferAccess<Content = u32> + 'static,
+    {
+        self.validate_begin_conditional_rendering(&buffer, inverted)?;
+
+        unsafe {
+            self.inner.begin_conditional_rendering(buffer, inverted)?;
+        }
+
+        self.conditional_rendering_state = Some(ConditionalRenderingState {
+            in_subpass: self.render_pass_state.is_some(),
+        });
+
+        Ok(self)
+    }
+
+    fn validate_begin_conditional_rendering(
+        &self,
+        buffer: &dyn BufferAccess,
+        _inverted: bool,
+    ) -> Result<(), ConditionalRenderingError> {
+        let device = self.device();
+
+        if !device.enabled_extensions().ext_conditional_rendering {
+            return Err(ConditionalRenderingError::RequirementNotMet {
+                required_for: "`begin_conditional_rendering`",
+                requires_one_of: RequiresOneOf {
+                    device_extensions: &["ext_conditional_rendering"],
+                    ..Default::default()
+                },
+            });
+        }
+
+        let queue_family_properties = self.queue_family_properties();
+
+        // VUID-vkCmdBeginConditionalRenderingEXT-commandBuffer-cmdpool
+        if !(queue_family_properties.queue_flags.graphics
+            || queue_family_properties.queue_flags.compute)
+        {
+            return Err(ConditionalRenderingError::NotSupportedByQueueFamily);
+        }
+
+        // VUID-vkCmdBeginConditionalRenderingEXT-None-01980
+        if self.conditional_rendering_state.is_some() {
+            return Err(ConditionalRenderingError::ConditionalRenderingActive);
+        }
+
+        // VUID-VkConditionalRenderingBeginInfoEXT-buffer-parameter
+        assert_eq!(device, buffer.device());
+
+        let inner = buffer.inner();
+
+        // VUID-VkConditionalRenderingBeginInfoEXT-buffer-01981
+        if !inner.buffer.usage().conditional_rendering {
+            return Err(ConditionalRenderingError::BufferMissingUsage);
+        }
+
+        // VUID-VkConditionalRenderingBeginInfoEXT-offset-01984
+        if inner.offset % 4 != 0 {
+            return Err(ConditionalRenderingError::BufferOffsetNotAligned {
+                offset: inner.offset,
+            });
+        }
+
+        // VUID-VkConditionalRenderingBeginInfoEXT-offset-01983
+        // Ensured by the `TypedBufferAccess` bound on the buffer.
+
+        Ok(())
+    }
+
+    /// Ends the conditional rendering block previously begun with
+    /// [`begin_conditional_rendering`](Self::begin_conditional_rendering).
+    ///
+    /// If conditional rendering was begun inside a render pass subpass, it must be ended in the
+    /// same subpass. If it was begun outside a render pass, it must be ended outside a render pass.
+    pub fn end_conditional_rendering(&mut self) -> Result<&mut Self, ConditionalRenderingError> {
+        self.validate_end_conditional_rendering()?;
+
+        unsafe {
+            self.inner.end_conditional_rendering();
+        }
+
+        self.conditional_rendering_state = None;
+
+        Ok(self)
+    }
+
+    fn validate_end_conditional_rendering(&self) -> Result<(), ConditionalRenderingError> {
+        let device = self.device();
+
+        if !device.enabled_extensions().ext_conditional_rendering {
+            return Err(ConditionalRenderingError::RequirementNotMet {
+                required_for: "`end_conditional_rendering`",
+                requires_one_of: RequiresOneOf {
+                    device_extensions: &["ext_conditional_rendering"],
+                    ..Default::default()
+                },
+            });
+        }
+
+        let queue_family_properties = self.queue_family_properties();
+
+        // VUID-vkCmdEndConditionalRenderingEXT-commandBuffer-cmdpool
+        if !(queue_family_properties.queue_flags.graphics
+            || queue_family_properties.queue_flags.compute)
+        {
+            return Err(ConditionalRenderingError::NotSupportedByQueueFamily);
+        }
+
+        // VUID-vkCmdEndConditionalRenderingEXT-None-01985
+        let state = self
+            .conditional_rendering_state
+            .as_ref()
+            .ok_or(ConditionalRenderingError::ConditionalRenderingNotActive)?;
+
+        // VUID-vkCmdEndConditionalRenderingEXT-None-01986
+        // VUID-vkCmdEndConditionalRenderingEXT-None-01987
+        // Changing the subpass or ending the render pass is not allowed while conditional
+        // rendering is active inside it, so checking this is enough.
+        if state.in_subpass != self.render_pass_state.is_some() {
+            return Err(ConditionalRenderingError::RenderPassScopeMismatch);
+        }
+
+        Ok(())
+    }
+}
+
+impl SyncCommandBufferBuilder {
+    /// Calls `vkCmdBeginConditionalRenderingEXT` on the builder.
+    #[inline]
+    pub unsafe fn begin_conditional_rendering(
+        &mut self,
+        buffer: Arc<dyn BufferAccess>,
+        inverted: bool,
+    ) -> Result<(), SyncCommandBufferBuilderError> {
+        struct Cmd {
+            buffer: Arc<dyn BufferAccess>,
+            inverted: bool,
+        }
+
+        impl Command for Cmd {
+            fn name(&self) -> &'static str {
+                "begin_conditional_rendering"
+            }
+
+            unsafe fn send(&self, out: &mut UnsafeCommandBufferBuilder) {
+                out.begin_conditional_rendering(self.buffer.as_ref(), self.inverted);
+            }
+        }
+
+        let resources = [(
+            "buffer".into(),
+            Resource::Buffer {
+                buffer: buffer.clone(),
+                range: 0..buffer.size(),
+                memory: PipelineMemoryAccess {
+                    stages: PipelineStages {
+                        conditional_rendering: true,
+                        ..PipelineStages::empty()
+                    },
+                    access: AccessFlags {
+                        conditional_rendering_read: true,
+                        ..AccessFlags::empty()
+                    },
+                    exclusive: false,
+                },
+            },
+        )];
+
+        for resource in &resources {
+            self.check_resource_conflicts(resource)?;
+        }
+
+        self.commands.push(Box::new(Cmd { buffer, inverted }));
+
+        for resource in resources {
+            self.add_resource(resource);
+        }
+
+        Ok(())
+    }
+
+    /// Calls `vkCmdEndConditionalRenderingEXT` on the builder.
+    #[inline]
+    pub unsafe fn end_conditional_rendering(&mut self) {
+        struct Cmd;
+
+        impl Command for Cmd {
+            fn name(&self) -> &'static str {
+                "end_conditional_rendering"
+            }
+
+            unsafe fn send(&self, out: &mut UnsafeCommandBufferBuilder) {
+                out.end_conditional_rendering();
+            }
+        }
+
+        self.commands.push(Box::new(Cmd));
+    }
+}
+
+impl UnsafeCommandBufferBuilder {
+    /// Calls `vkCmdBeginConditionalRenderingEXT` on the builder.
+    #[inline]
+    pub unsafe fn begin_conditional_rendering(
+        &mut self,
+        buffer: &dyn BufferAccess,
+        inverted: bool,
+    ) {
+        let inner = buffer.inner();
+        debug_assert!(inner.offset < inner.buffer.size());
+        debug_assert!(inner.buffer.usage().conditional_rendering);
+        debug_assert_eq!(inner.offset % 4, 0);
+
+        let mut flags = ash::vk::ConditionalRenderingFlagsEXT::empty();
+
+        if inverted {
+            flags |= ash::vk::ConditionalRenderingFlagsEXT::INVERTED;
+        }
+
+        let begin_info = ash::vk::ConditionalRenderingBeginInfoEXT {
+            buffer: inner.buffer.internal_object(),
+            offset: inner.offset,
+            flags,
+            ..Default::default()
+        };
+
+        let fns = self.device.fns();
+        (fns.ext_conditional_rendering
+            .cmd_begin_conditional_rendering_ext)(self.handle, &begin_info);
+    }
+
+    /// Calls `vkCmdEndConditionalRenderingEXT` on the builder.
+    #[inline]
+    pub unsafe fn end_conditional_rendering(&mut self) {
+        let fns = self.device.fns();
+        (fns.ext_conditional_rendering
+            .cmd_end_conditional_rendering_ext)(self.handle);
+    }
+}
+
+/// Error that can happen when recording a conditional rendering command.
+#[derive(Clone, Debug)]
+pub enum ConditionalRenderingError {
+    SyncCommandBufferBuilderError(SyncCommandBufferBuilderError),
+
+    RequirementNotMet {
+        required_for: &'static str,
+        requires_one_of: RequiresOneOf,
+    },
+
+    /// The queue family doesn't allow this operation.
+    NotSupportedByQueueFamily,
+
+    /// The buffer is missing the `conditional_rendering` usage.
+    BufferMissingUsage,
+
+    /// The offset of the buffer is not a multiple of 4.
+    BufferOffsetNotAligned {
+        offset: DeviceSize,
+    },
+
+    /// Conditional rendering is already active.
+    ConditionalRenderingActive,
+
+    /// Conditional rendering is not active.
+    ConditionalRenderingNotActive,
+
+    /// Conditional rendering was begun inside a render pass but is being ended outside of one, or
+    /// the other way around.
+    RenderPassScopeMismatch,
+}
+
+impl Error for ConditionalRenderingError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            Self::SyncCommandBufferBuilderError(err) => Some(err),
+            _ => None,
+        }
+    }
+}
+
+impl Display for ConditionalRenderingError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result<(), FmtError> {
+        match self {
+            Self::SyncCommandBufferBuilderError(_) => write!(f, "a SyncCommandBufferBuilderError"),
+            Self::RequirementNotMet {
+                required_for,
+                requires_one_of,
+            } => write!(
+                f,
+                "a requirement was not met for: {}; requires one of: {}",
+                required_for, requires_one_of,
+            ),
+            Self::NotSupportedByQueueFamily => {
+                write!(f, "the queue family doesn't allow this operation")
+            }
+            Self::BufferMissingUsage => {
+                write!(f, "the buffer is missing the `conditional_rendering` usage",)
+            }
+            Self::BufferOffsetNotAligned { offset } => write!(
+                f,
+                "the offset of the buffer ({}) is not a multiple of 4",
+                offset,
+            ),
+            Self::ConditionalRenderingActive => {
+                write!(f, "conditional rendering is already active")
+            }
+            Self::ConditionalRenderingNotActive => write!(f, "conditional rendering is not active"),
+            Self::RenderPassScopeMismatch => write!(
+                f,
+                "conditional rendering was begun inside a render pass but is being ended outside \
+                of one, or the other way around",
+            ),
+        }
+    }
+}
+
+impl From<SyncCommandBufferBuilderError> for ConditionalRenderingError {
+    fn from(err: SyncCommandBufferBuilderError) -> Self {
+        Self::SyncCommandBufferBuilderError(err)
+    }
+}
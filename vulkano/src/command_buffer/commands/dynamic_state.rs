@@ -18,6 +18,9 @@ use crate::{
         graphics::{
             color_blend::LogicOp,
             depth_stencil::{CompareOp, StencilFaces, StencilOp, StencilOps},
+            fragment_shading_rate::{
+                is_valid_fragment_size, FragmentShadingRate, FragmentShadingRateCombinerOp,
+            },
             input_assembly::PrimitiveTopology,
             rasterization::{CullMode, DepthBias, FrontFace, LineStipple},
             viewport::{Scissor, Viewport},
@@ -627,6 +630,144 @@ impl<L, P> AutoCommandBufferBuilder<L, P> {
         Ok(())
     }
 
+    /// Sets the dynamic fragment shading rate for future draw calls.
+    ///
+    /// `fragment_size` is the size, in pixels, of the area covered by a single fragment shader
+    /// invocation. `combiner_ops` are the operations used to combine the pipeline rate with the
+    /// primitive rate, and the result of that with the attachment rate.
+    ///
+    /// # Panics
+    ///
+    /// - Panics if the queue family of the command buffer does not support graphics operations.
+    /// - Panics if none of the
+    ///   [`pipeline_fragment_shading_rate`](crate::device::Features::pipeline_fragment_shading_rate),
+    ///   [`primitive_fragment_shading_rate`](crate::device::Features::primitive_fragment_shading_rate)
+    ///   or
+    ///   [`attachment_fragment_shading_rate`](crate::device::Features::attachment_fragment_shading_rate)
+    ///   features are enabled on the device.
+    /// - Panics if the currently bound graphics pipeline already contains this state internally.
+    /// - Panics if an element of `fragment_size` is not 1, 2 or 4.
+    /// - Panics if `fragment_size` is not `[1, 1]` and the `pipeline_fragment_shading_rate`
+    ///   feature is not enabled on the device.
+    /// - Panics if `combiner_ops[0]` is not [`FragmentShadingRateCombinerOp::Keep`] and the
+    ///   `primitive_fragment_shading_rate` feature is not enabled on the device.
+    /// - Panics if `combiner_ops[1]` is not `Keep` and the `attachment_fragment_shading_rate`
+    ///   feature is not enabled on the device.
+    /// - Panics if an element of `combiner_ops` is not `Keep` or `Replace`, and the
+    ///   [`fragment_shading_rate_non_trivial_combiner_ops`](crate::device::Properties::fragment_shading_rate_non_trivial_combiner_ops)
+    ///   device property is `false`.
+    pub fn set_fragment_shading_rate(
+        &mut self,
+        fragment_size: [u32; 2],
+        combiner_ops: [FragmentShadingRateCombinerOp; 2],
+    ) -> &mut Self {
+        self.validate_set_fragment_shading_rate(fragment_size, combiner_ops)
+            .unwrap();
+
+        unsafe {
+            self.inner
+                .set_fragment_shading_rate(fragment_size, combiner_ops);
+        }
+
+        self
+    }
+
+    fn validate_set_fragment_shading_rate(
+        &self,
+        fragment_size: [u32; 2],
+        combiner_ops: [FragmentShadingRateCombinerOp; 2],
+    ) -> Result<(), SetDynamicStateError> {
+        self.validate_pipeline_fixed_state(DynamicState::FragmentShadingRate)?;
+
+        for combiner_op in combiner_ops {
+            // VUID-vkCmdSetFragmentShadingRateKHR-combinerOps-parameter
+            combiner_op.validate_device(self.device())?;
+        }
+
+        let queue_family_properties = self.queue_family_properties();
+
+        // VUID-vkCmdSetFragmentShadingRateKHR-commandBuffer-cmdpool
+        if !queue_family_properties.queue_flags.graphics {
+            return Err(SetDynamicStateError::NotSupportedByQueueFamily);
+        }
+
+        let features = self.device().enabled_features();
+
+        // VUID?
+        if !(features.pipeline_fragment_shading_rate
+            || features.primitive_fragment_shading_rate
+            || features.attachment_fragment_shading_rate)
+        {
+            return Err(SetDynamicStateError::RequirementNotMet {
+                required_for: "`set_fragment_shading_rate`",
+                requires_one_of: RequiresOneOf {
+                    features: &[
+                        "pipeline_fragment_shading_rate",
+                        "primitive_fragment_shading_rate",
+                        "attachment_fragment_shading_rate",
+                    ],
+                    ..Default::default()
+                },
+            });
+        }
+
+        // VUID?
+        if !is_valid_fragment_size(fragment_size) {
+            return Err(SetDynamicStateError::FragmentSizeInvalid);
+        }
+
+        // VUID?
+        if fragment_size != [1, 1] && !features.pipeline_fragment_shading_rate {
+            return Err(SetDynamicStateError::RequirementNotMet {
+                required_for: "`fragment_size` is not `[1, 1]`",
+                requires_one_of: RequiresOneOf {
+                    features: &["pipeline_fragment_shading_rate"],
+                    ..Default::default()
+                },
+            });
+        }
+
+        // VUID?
+        if combiner_ops[0] != FragmentShadingRateCombinerOp::Keep
+            && !features.primitive_fragment_shading_rate
+        {
+            return Err(SetDynamicStateError::RequirementNotMet {
+                required_for: "`combiner_ops[0]` is not `FragmentShadingRateCombinerOp::Keep`",
+                requires_one_of: RequiresOneOf {
+                    features: &["primitive_fragment_shading_rate"],
+                    ..Default::default()
+                },
+            });
+        }
+
+        // VUID?
+        if combiner_ops[1] != FragmentShadingRateCombinerOp::Keep
+            && !features.attachment_fragment_shading_rate
+        {
+            return Err(SetDynamicStateError::RequirementNotMet {
+                required_for: "`combiner_ops[1]` is not `FragmentShadingRateCombinerOp::Keep`",
+                requires_one_of: RequiresOneOf {
+                    features: &["attachment_fragment_shading_rate"],
+                    ..Default::default()
+                },
+            });
+        }
+
+        // VUID?
+        if !self
+            .device()
+            .physical_device()
+            .properties()
+            .fragment_shading_rate_non_trivial_combiner_ops
+            .unwrap_or(false)
+            && !combiner_ops.into_iter().all(|op| op.is_trivial())
+        {
+            return Err(SetDynamicStateError::NonTrivialCombinerOpsNotSupported);
+        }
+
+        Ok(())
+    }
+
     /// Sets the dynamic front face for future draw calls.
     ///
     /// # Panics
@@ -1887,6 +2028,38 @@ impl SyncCommandBufferBuilder {
         }));
     }
 
+    /// Calls `vkCmdSetFragmentShadingRateKHR` on the builder.
+    #[inline]
+    pub unsafe fn set_fragment_shading_rate(
+        &mut self,
+        fragment_size: [u32; 2],
+        combiner_ops: [FragmentShadingRateCombinerOp; 2],
+    ) {
+        struct Cmd {
+            fragment_size: [u32; 2],
+            combiner_ops: [FragmentShadingRateCombinerOp; 2],
+        }
+
+        impl Command for Cmd {
+            fn name(&self) -> &'static str {
+                "set_fragment_shading_rate"
+            }
+
+            unsafe fn send(&self, out: &mut UnsafeCommandBufferBuilder) {
+                out.set_fragment_shading_rate(self.fragment_size, self.combiner_ops);
+            }
+        }
+
+        self.commands.push(Box::new(Cmd {
+            fragment_size,
+            combiner_ops,
+        }));
+        self.current_state.fragment_shading_rate = Some(FragmentShadingRate {
+            fragment_size,
+            combiner_ops,
+        });
+    }
+
     /// Calls `vkCmdSetFrontFaceEXT` on the builder.
     #[inline]
     pub unsafe fn set_front_face(&mut self, face: FrontFace) {
@@ -2530,6 +2703,27 @@ impl UnsafeCommandBufferBuilder {
         );
     }
 
+    /// Calls `vkCmdSetFragmentShadingRateKHR` on the builder.
+    #[inline]
+    pub unsafe fn set_fragment_shading_rate(
+        &mut self,
+        fragment_size: [u32; 2],
+        combiner_ops: [FragmentShadingRateCombinerOp; 2],
+    ) {
+        debug_assert!(self.device.enabled_extensions().khr_fragment_shading_rate);
+
+        let fragment_size = ash::vk::Extent2D {
+            width: fragment_size[0],
+            height: fragment_size[1],
+        };
+        let combiner_ops: [ash::vk::FragmentShadingRateCombinerOpKHR; 2] =
+            combiner_ops.map(Into::into);
+
+        let fns = self.device.fns();
+        (fns.khr_fragment_shading_rate
+            .cmd_set_fragment_shading_rate_khr)(self.handle, &fragment_size, &combiner_ops);
+    }
+
     /// Calls `vkCmdSetFrontFaceEXT` on the builder.
     #[inline]
     pub unsafe fn set_front_face(&mut self, face: FrontFace) {
@@ -2819,6 +3013,9 @@ enum SetDynamicStateError {
     /// The provided `factor` is not between 1 and 256 inclusive.
     FactorOutOfRange,
 
+    /// An element of the provided `fragment_size` is not 1, 2 or 4.
+    FragmentSizeInvalid,
+
     /// The [`max_discard_rectangles`](crate::device::Properties::max_discard_rectangles)
     /// limit has been exceeded.
     MaxDiscardRectanglesExceeded { provided: u32, max: u32 },
@@ -2831,6 +3028,11 @@ enum SetDynamicStateError {
     /// limit has been exceeded.
     MaxViewportsExceeded { provided: u32, max: u32 },
 
+    /// An element of the provided `combiner_ops` is not `Keep` or `Replace`, but the
+    /// [`fragment_shading_rate_non_trivial_combiner_ops`](crate::device::Properties::fragment_shading_rate_non_trivial_combiner_ops)
+    /// device property is `false`.
+    NonTrivialCombinerOpsNotSupported,
+
     /// The queue family doesn't allow this operation.
     NotSupportedByQueueFamily,
 
@@ -2863,6 +3065,10 @@ impl Display for SetDynamicStateError {
                 f,
                 "the provided `factor` is not between 1 and 256 inclusive",
             ),
+            Self::FragmentSizeInvalid => write!(
+                f,
+                "an element of the provided `fragment_size` is not 1, 2 or 4",
+            ),
             Self::MaxDiscardRectanglesExceeded { .. } => {
                 write!(f, "the `max_discard_rectangles` limit has been exceeded")
             }
@@ -2873,6 +3079,11 @@ impl Display for SetDynamicStateError {
             Self::MaxViewportsExceeded { .. } => {
                 write!(f, "the `max_viewports` limit has been exceeded")
             }
+            Self::NonTrivialCombinerOpsNotSupported => write!(
+                f,
+                "an element of the provided `combiner_ops` is not `Keep` or `Replace`, but the \
+                `fragment_shading_rate_non_trivial_combiner_ops` device property is `false`",
+            ),
             Self::NotSupportedByQueueFamily => {
                 write!(f, "the queue family doesn't allow this operation")
             }
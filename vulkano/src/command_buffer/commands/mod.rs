@@ -8,6 +8,7 @@
 // according to those terms.
 
 pub(super) mod bind_push;
+pub(super) mod conditional_rendering;
 pub(super) mod debug;
 pub(super) mod dynamic_state;
 pub(super) mod image;
@@ -17,6 +18,7 @@ pub(super) mod render_pass;
 pub(super) mod secondary;
 pub(super) mod sync;
 pub(super) mod transfer;
+pub(super) mod transform_feedback;
 
 use super::synced::SyncCommandBufferBuilderError;
 use crate::{
@@ -16,7 +16,10 @@ use crate::{
         AutoCommandBufferBuilder, DispatchIndirectCommand, DrawIndexedIndirectCommand,
         DrawIndirectCommand, SubpassContents,
     },
-    descriptor_set::{layout::DescriptorType, DescriptorBindingResources},
+    descriptor_set::{
+        layout::{DescriptorSetLayoutBinding, DescriptorType},
+        DescriptorBindingResources,
+    },
     device::DeviceOwned,
     format::Format,
     image::{
@@ -320,6 +323,132 @@ impl<L, P> AutoCommandBufferBuilder<L, P> {
         Ok(())
     }
 
+    /// Perform a single draw operation using a graphics pipeline, taking the number of vertices
+    /// from a counter buffer that was written by
+    /// [`end_transform_feedback`](Self::end_transform_feedback).
+    ///
+    /// The vertex count is calculated as the 32-bit value in `counter_buffer`, minus
+    /// `counter_offset`, divided by `vertex_stride`. For non-instanced drawing, specify
+    /// `instance_count` as 1 and `first_instance` as 0.
+    ///
+    /// The [`transform_feedback`](crate::device::Features::transform_feedback) feature must be
+    /// enabled on the device, and the
+    /// [`transform_feedback_draw`](crate::device::Properties::transform_feedback_draw) device
+    /// property must be `true`.
+    ///
+    /// A graphics pipeline must have been bound using
+    /// [`bind_pipeline_graphics`](Self::bind_pipeline_graphics). Any resources used by the graphics
+    /// pipeline, such as descriptor sets, vertex buffers and dynamic state, must have been set
+    /// beforehand. If the bound graphics pipeline uses vertex buffers, then the provided instance
+    /// range must be in range of the bound vertex buffers.
+    pub fn draw_indirect_byte_count<Cb>(
+        &mut self,
+        instance_count: u32,
+        first_instance: u32,
+        counter_buffer: Arc<Cb>,
+        counter_offset: u32,
+        vertex_stride: u32,
+    ) -> Result<&mut Self, PipelineExecutionError>
+    where
+        Cb: TypedBufferAccess<Content = u32> + 'static,
+    {
+        self.validate_draw_indirect_byte_count(
+            instance_count,
+            first_instance,
+            &counter_buffer,
+            counter_offset,
+            vertex_stride,
+        )?;
+
+        unsafe {
+            self.inner.draw_indirect_byte_count(
+                instance_count,
+                first_instance,
+                counter_buffer,
+                counter_offset,
+                vertex_stride,
+            )?;
+        }
+
+        if let RenderPassStateType::BeginRendering(state) =
+            &mut self.render_pass_state.as_mut().unwrap().render_pass
+        {
+            state.pipeline_used = true;
+        }
+
+        Ok(self)
+    }
+
+    fn validate_draw_indirect_byte_count(
+        &self,
+        instance_count: u32,
+        first_instance: u32,
+        counter_buffer: &dyn BufferAccess,
+        _counter_offset: u32,
+        vertex_stride: u32,
+    ) -> Result<(), PipelineExecutionError> {
+        let device = self.device();
+
+        // VUID-vkCmdDrawIndirectByteCountEXT-transformFeedback-02287
+        if !device.enabled_features().transform_feedback {
+            return Err(PipelineExecutionError::RequirementNotMet {
+                required_for: "`draw_indirect_byte_count`",
+                requires_one_of: RequiresOneOf {
+                    features: &["transform_feedback"],
+                    ..Default::default()
+                },
+            });
+        }
+
+        let properties = device.physical_device().properties();
+
+        // VUID-vkCmdDrawIndirectByteCountEXT-transformFeedbackDraw-02288
+        if !properties.transform_feedback_draw.unwrap_or(false) {
+            return Err(PipelineExecutionError::TransformFeedbackDrawNotSupported);
+        }
+
+        // VUID-vkCmdDrawIndirectByteCountEXT-renderpass
+        let render_pass_state = self
+            .render_pass_state
+            .as_ref()
+            .ok_or(PipelineExecutionError::ForbiddenOutsideRenderPass)?;
+
+        // VUID-vkCmdDrawIndirectByteCountEXT-None-02700
+        let pipeline = match self.state().pipeline_graphics() {
+            Some(x) => x.as_ref(),
+            None => return Err(PipelineExecutionError::PipelineNotBound),
+        };
+
+        self.validate_pipeline_descriptor_sets(pipeline, pipeline.descriptor_requirements())?;
+        self.validate_pipeline_push_constants(pipeline.layout())?;
+        self.validate_pipeline_graphics_dynamic_state(pipeline)?;
+        self.validate_pipeline_graphics_render_pass(pipeline, render_pass_state)?;
+        self.validate_pipeline_graphics_vertex_buffers(
+            pipeline,
+            None,
+            Some((first_instance, instance_count)),
+        )?;
+
+        // VUID-vkCmdDrawIndirectByteCountEXT-counterBuffer-02290
+        self.validate_indirect_buffer(counter_buffer)?;
+
+        let max = properties
+            .max_transform_feedback_buffer_data_stride
+            .unwrap_or(0);
+
+        // VUID-vkCmdDrawIndirectByteCountEXT-vertexStride-02289
+        if vertex_stride > max {
+            return Err(
+                PipelineExecutionError::MaxTransformFeedbackBufferDataStrideExceeded {
+                    provided: vertex_stride,
+                    max,
+                },
+            );
+        }
+
+        Ok(())
+    }
+
     /// Perform a single draw operation using a graphics pipeline, using an index buffer.
     ///
     /// The parameters specify the first index and the number of indices in the index buffer that
@@ -564,10 +693,21 @@ impl<L, P> AutoCommandBufferBuilder<L, P> {
             set_num: u32,
             binding_num: u32,
             reqs: &DescriptorRequirements,
+            layout_binding: &DescriptorSetLayoutBinding,
             elements: &[Option<T>],
             mut extra_check: impl FnMut(u32, &T) -> Result<(), DescriptorResourceInvalidError>,
         ) -> Result<(), PipelineExecutionError> {
-            let elements_to_check = if let Some(descriptor_count) = reqs.descriptor_count {
+            let elements_to_check = if layout_binding.partially_bound {
+                // Descriptors in a partially bound binding only need to be valid if they are
+                // dynamically used, which can't be known here. Only check the ones that were
+                // written.
+                match reqs.descriptor_count {
+                    Some(descriptor_count) => {
+                        &elements[..elements.len().min(descriptor_count as usize)]
+                    }
+                    None => elements,
+                }
+            } else if let Some(descriptor_count) = reqs.descriptor_count {
                 // The shader has a fixed-sized array, so it will never access more than
                 // the first `descriptor_count` elements.
                 elements.get(..descriptor_count as usize).ok_or({
@@ -591,6 +731,7 @@ impl<L, P> AutoCommandBufferBuilder<L, P> {
                 // VUID-vkCmdDispatch-None-02699
                 let element = match element {
                     Some(x) => x,
+                    None if layout_binding.partially_bound => continue,
                     None => {
                         return Err(PipelineExecutionError::DescriptorResourceInvalid {
                             set_num,
@@ -837,15 +978,16 @@ impl<L, P> AutoCommandBufferBuilder<L, P> {
                     // If the image view isn't actually present in the resources, then just skip it.
                     // It will be caught later by check_resources.
                     let iter = with_images.iter().filter_map(|id| {
-                        current_state
-                            .descriptor_set(pipeline.bind_point(), id.set)
-                            .and_then(|set| set.resources().binding(id.binding))
-                            .and_then(|res| match res {
-                                DescriptorBindingResources::ImageView(elements) => elements
-                                    .get(id.index as usize)
-                                    .and_then(|opt| opt.as_ref().map(|opt| (id, opt))),
-                                _ => None,
-                            })
+                        let set_resources = current_state
+                            .descriptor_set(pipeline.bind_point(), id.set)?
+                            .resources();
+
+                        match set_resources.binding(id.binding)? {
+                            DescriptorBindingResources::ImageView(elements) => elements
+                                .get(id.index as usize)
+                                .and_then(|opt| opt.clone().map(|opt| (id, opt))),
+                            _ => None,
+                        }
                     });
 
                     for (id, image_view) in iter {
@@ -882,28 +1024,64 @@ impl<L, P> AutoCommandBufferBuilder<L, P> {
 
             match binding_resources {
                 DescriptorBindingResources::None(elements) => {
-                    validate_resources(set_num, binding_num, reqs, elements, check_none)?;
+                    validate_resources(
+                        set_num,
+                        binding_num,
+                        reqs,
+                        layout_binding,
+                        elements,
+                        check_none,
+                    )?;
                 }
                 DescriptorBindingResources::Buffer(elements) => {
-                    validate_resources(set_num, binding_num, reqs, elements, check_buffer)?;
+                    validate_resources(
+                        set_num,
+                        binding_num,
+                        reqs,
+                        layout_binding,
+                        elements,
+                        check_buffer,
+                    )?;
                 }
                 DescriptorBindingResources::BufferView(elements) => {
-                    validate_resources(set_num, binding_num, reqs, elements, check_buffer_view)?;
+                    validate_resources(
+                        set_num,
+                        binding_num,
+                        reqs,
+                        layout_binding,
+                        elements,
+                        check_buffer_view,
+                    )?;
                 }
                 DescriptorBindingResources::ImageView(elements) => {
-                    validate_resources(set_num, binding_num, reqs, elements, check_image_view)?;
+                    validate_resources(
+                        set_num,
+                        binding_num,
+                        reqs,
+                        layout_binding,
+                        elements,
+                        check_image_view,
+                    )?;
                 }
                 DescriptorBindingResources::ImageViewSampler(elements) => {
                     validate_resources(
                         set_num,
                         binding_num,
                         reqs,
+                        layout_binding,
                         elements,
                         check_image_view_sampler,
                     )?;
                 }
                 DescriptorBindingResources::Sampler(elements) => {
-                    validate_resources(set_num, binding_num, reqs, elements, check_sampler)?;
+                    validate_resources(
+                        set_num,
+                        binding_num,
+                        reqs,
+                        layout_binding,
+                        elements,
+                        check_sampler,
+                    )?;
                 }
             }
         }
@@ -1058,7 +1236,12 @@ impl<L, P> AutoCommandBufferBuilder<L, P> {
                     }
                 }
                 DynamicState::ExclusiveScissor => todo!(),
-                DynamicState::FragmentShadingRate => todo!(),
+                DynamicState::FragmentShadingRate => {
+                    // VUID?
+                    if current_state.fragment_shading_rate().is_none() {
+                        return Err(PipelineExecutionError::DynamicStateNotSet { dynamic_state });
+                    }
+                }
                 DynamicState::FrontFace => {
                     // VUID?
                     if current_state.front_face().is_none() {
@@ -1836,6 +2019,87 @@ impl SyncCommandBufferBuilder {
         Ok(())
     }
 
+    /// Calls `vkCmdDrawIndirectByteCountEXT` on the builder.
+    #[inline]
+    pub unsafe fn draw_indirect_byte_count(
+        &mut self,
+        instance_count: u32,
+        first_instance: u32,
+        counter_buffer: Arc<dyn BufferAccess>,
+        counter_offset: u32,
+        vertex_stride: u32,
+    ) -> Result<(), SyncCommandBufferBuilderError> {
+        struct Cmd {
+            instance_count: u32,
+            first_instance: u32,
+            counter_buffer: Arc<dyn BufferAccess>,
+            counter_offset: u32,
+            vertex_stride: u32,
+        }
+
+        impl Command for Cmd {
+            fn name(&self) -> &'static str {
+                "draw_indirect_byte_count"
+            }
+
+            unsafe fn send(&self, out: &mut UnsafeCommandBufferBuilder) {
+                out.draw_indirect_byte_count(
+                    self.instance_count,
+                    self.first_instance,
+                    self.counter_buffer.as_ref(),
+                    self.counter_offset,
+                    self.vertex_stride,
+                );
+            }
+        }
+
+        let pipeline = self.current_state.pipeline_graphics.as_ref().unwrap();
+
+        let mut resources = Vec::new();
+        self.add_descriptor_set_resources(
+            &mut resources,
+            PipelineBindPoint::Graphics,
+            pipeline.descriptor_requirements(),
+        );
+        self.add_vertex_buffer_resources(&mut resources, pipeline.vertex_input_state());
+        resources.push((
+            "counter buffer".into(),
+            Resource::Buffer {
+                buffer: counter_buffer.clone(),
+                range: 0..counter_buffer.size(),
+                memory: PipelineMemoryAccess {
+                    stages: PipelineStages {
+                        draw_indirect: true,
+                        ..PipelineStages::empty()
+                    },
+                    access: AccessFlags {
+                        transform_feedback_counter_read: true,
+                        ..AccessFlags::empty()
+                    },
+                    exclusive: false,
+                },
+            },
+        ));
+
+        for resource in &resources {
+            self.check_resource_conflicts(resource)?;
+        }
+
+        self.commands.push(Box::new(Cmd {
+            instance_count,
+            first_instance,
+            counter_buffer,
+            counter_offset,
+            vertex_stride,
+        }));
+
+        for resource in resources {
+            self.add_resource(resource);
+        }
+
+        Ok(())
+    }
+
     /// Calls `vkCmdDrawIndexedIndirect` on the builder.
     #[inline]
     pub unsafe fn draw_indexed_indirect(
@@ -1923,7 +2187,7 @@ impl SyncCommandBufferBuilder {
             let access = PipelineMemoryAccess {
                 stages: reqs.stages.into(),
                 access: match descriptor_type {
-                    DescriptorType::Sampler => continue,
+                    DescriptorType::Sampler | DescriptorType::InlineUniformBlock => continue,
                     DescriptorType::CombinedImageSampler
                     | DescriptorType::SampledImage
                     | DescriptorType::StorageImage
@@ -2245,6 +2509,33 @@ impl UnsafeCommandBufferBuilder {
         );
     }
 
+    /// Calls `vkCmdDrawIndirectByteCountEXT` on the builder.
+    #[inline]
+    pub unsafe fn draw_indirect_byte_count(
+        &mut self,
+        instance_count: u32,
+        first_instance: u32,
+        counter_buffer: &dyn BufferAccess,
+        counter_offset: u32,
+        vertex_stride: u32,
+    ) {
+        let fns = self.device.fns();
+
+        let inner = counter_buffer.inner();
+        debug_assert!(inner.offset < inner.buffer.size());
+        debug_assert!(inner.buffer.usage().indirect_buffer);
+
+        (fns.ext_transform_feedback.cmd_draw_indirect_byte_count_ext)(
+            self.handle,
+            instance_count,
+            first_instance,
+            inner.buffer.internal_object(),
+            inner.offset,
+            counter_offset,
+            vertex_stride,
+        );
+    }
+
     /// Calls `vkCmdDrawIndexedIndirect` on the builder.
     #[inline]
     pub unsafe fn draw_indexed_indirect(
@@ -2367,6 +2658,12 @@ pub enum PipelineExecutionError {
         max: u32,
     },
 
+    /// The `max_transform_feedback_buffer_data_stride` limit has been exceeded.
+    MaxTransformFeedbackBufferDataStrideExceeded {
+        provided: u32,
+        max: u32,
+    },
+
     /// The queue family doesn't allow this operation.
     NotSupportedByQueueFamily,
 
@@ -2432,6 +2729,10 @@ pub enum PipelineExecutionError {
     /// Not all push constants used by the pipeline have been set.
     PushConstantsMissing,
 
+    /// The [`transform_feedback_draw`](crate::device::Properties::transform_feedback_draw) device
+    /// property is not `true`.
+    TransformFeedbackDrawNotSupported,
+
     /// The bound graphics pipeline requires a vertex buffer bound to a binding number, but none
     /// was bound.
     VertexBufferNotBound {
@@ -2570,6 +2871,10 @@ impl Display for PipelineExecutionError {
                 f,
                 "the `max_multiview_instance_index` limit has been exceeded",
             ),
+            Self::MaxTransformFeedbackBufferDataStrideExceeded { .. } => write!(
+                f,
+                "the `max_transform_feedback_buffer_data_stride` limit has been exceeded",
+            ),
             Self::NotSupportedByQueueFamily => {
                 write!(f, "the queue family doesn't allow this operation")
             }
@@ -2652,6 +2957,10 @@ impl Display for PipelineExecutionError {
                 f,
                 "not all push constants used by the pipeline have been set",
             ),
+            Self::TransformFeedbackDrawNotSupported => write!(
+                f,
+                "the `transform_feedback_draw` device property is not `true`",
+            ),
             Self::VertexBufferNotBound { binding_num } => write!(
                 f,
                 "the bound graphics pipeline requires a vertex buffer bound to binding number {}, \
@@ -47,14 +47,14 @@ impl<L, P> AutoCommandBufferBuilder<L, P> {
         query: u32,
         flags: QueryControlFlags,
     ) -> Result<&mut Self, QueryError> {
-        self.validate_begin_query(&query_pool, query, flags)?;
+        self.validate_begin_query(&query_pool, query, flags, 0)?;
 
         let ty = query_pool.query_type();
         let raw_query_pool = query_pool.internal_object();
 
         self.inner.begin_query(query_pool, query, flags);
         self.query_state.insert(
-            ty.into(),
+            (ty.into(), 0),
             QueryState {
                 query_pool: raw_query_pool,
                 query,
@@ -67,11 +67,108 @@ impl<L, P> AutoCommandBufferBuilder<L, P> {
         Ok(self)
     }
 
+    /// Begins a query on a specific vertex stream.
+    ///
+    /// This is the same as [`begin_query`](Self::begin_query), except that for
+    /// [`TransformFeedbackStream`](QueryType::TransformFeedbackStream) queries, `index` selects
+    /// the vertex stream whose primitives are counted. For other query types, `index` must be 0.
+    /// The query must be ended with [`end_query_indexed`](Self::end_query_indexed), using the
+    /// same `index`.
+    ///
+    /// The [`ext_transform_feedback`](crate::device::DeviceExtensions::ext_transform_feedback)
+    /// extension must be enabled on the device.
+    ///
+    /// # Safety
+    ///
+    /// The query must be unavailable, ensured by calling
+    /// [`reset_query_pool`](Self::reset_query_pool).
+    pub unsafe fn begin_query_indexed(
+        &mut self,
+        query_pool: Arc<QueryPool>,
+        query: u32,
+        flags: QueryControlFlags,
+        index: u32,
+    ) -> Result<&mut Self, QueryError> {
+        self.validate_begin_query_indexed(&query_pool, query, flags, index)?;
+
+        let ty = query_pool.query_type();
+        let raw_query_pool = query_pool.internal_object();
+
+        self.inner
+            .begin_query_indexed(query_pool, query, flags, index);
+        self.query_state.insert(
+            (ty.into(), index),
+            QueryState {
+                query_pool: raw_query_pool,
+                query,
+                ty,
+                flags,
+                in_subpass: self.render_pass_state.is_some(),
+            },
+        );
+
+        Ok(self)
+    }
+
+    fn validate_begin_query_indexed(
+        &self,
+        query_pool: &QueryPool,
+        query: u32,
+        flags: QueryControlFlags,
+        index: u32,
+    ) -> Result<(), QueryError> {
+        if !self.device().enabled_extensions().ext_transform_feedback {
+            return Err(QueryError::RequirementNotMet {
+                required_for: "`begin_query_indexed`",
+                requires_one_of: RequiresOneOf {
+                    device_extensions: &["ext_transform_feedback"],
+                    ..Default::default()
+                },
+            });
+        }
+
+        // VUID-vkCmdBeginQueryIndexedEXT-queryType-02339
+        // VUID-vkCmdBeginQueryIndexedEXT-queryType-02340
+        self.validate_query_index(query_pool, index)?;
+
+        // The remaining requirements are the same as for `vkCmdBeginQuery`.
+        // VUID-vkCmdBeginQueryIndexedEXT-queryPool-04753
+        self.validate_begin_query(query_pool, query, flags, index)
+    }
+
+    fn validate_query_index(&self, query_pool: &QueryPool, index: u32) -> Result<(), QueryError> {
+        match query_pool.query_type() {
+            QueryType::TransformFeedbackStream => {
+                let max_transform_feedback_streams = self
+                    .device()
+                    .physical_device()
+                    .properties()
+                    .max_transform_feedback_streams
+                    .unwrap_or(0);
+
+                if index >= max_transform_feedback_streams {
+                    return Err(QueryError::StreamIndexOutOfRange {
+                        index,
+                        max: max_transform_feedback_streams,
+                    });
+                }
+            }
+            _ => {
+                if index != 0 {
+                    return Err(QueryError::IndexNotZero);
+                }
+            }
+        }
+
+        Ok(())
+    }
+
     fn validate_begin_query(
         &self,
         query_pool: &QueryPool,
         query: u32,
         flags: QueryControlFlags,
+        index: u32,
     ) -> Result<(), QueryError> {
         let queue_family_properties = self.queue_family_properties();
 
@@ -130,12 +227,33 @@ impl<L, P> AutoCommandBufferBuilder<L, P> {
             }
             // VUID-vkCmdBeginQuery-queryType-02804
             QueryType::Timestamp => return Err(QueryError::NotPermitted),
+            QueryType::TransformFeedbackStream => {
+                // VUID-vkCmdBeginQuery-queryType-02327
+                if !queue_family_properties.queue_flags.graphics {
+                    return Err(QueryError::NotSupportedByQueueFamily);
+                }
+
+                // VUID-vkCmdBeginQuery-queryType-02328
+                if !device
+                    .physical_device()
+                    .properties()
+                    .transform_feedback_queries
+                    .unwrap_or(false)
+                {
+                    return Err(QueryError::TransformFeedbackQueriesNotSupported);
+                }
+
+                // VUID-vkCmdBeginQuery-queryType-00800
+                if flags.precise {
+                    return Err(QueryError::InvalidFlags);
+                }
+            }
         }
 
         // VUID-vkCmdBeginQuery-queryPool-01922
         if self
             .query_state
-            .contains_key(&query_pool.query_type().into())
+            .contains_key(&(ash::vk::QueryType::from(query_pool.query_type()), index))
         {
             return Err(QueryError::QueryIsActive);
         }
@@ -160,18 +278,70 @@ impl<L, P> AutoCommandBufferBuilder<L, P> {
         query_pool: Arc<QueryPool>,
         query: u32,
     ) -> Result<&mut Self, QueryError> {
-        self.validate_end_query(&query_pool, query)?;
+        self.validate_end_query(&query_pool, query, 0)?;
 
         unsafe {
-            let raw_ty = query_pool.query_type().into();
+            let raw_ty = ash::vk::QueryType::from(query_pool.query_type());
             self.inner.end_query(query_pool, query);
-            self.query_state.remove(&raw_ty);
+            self.query_state.remove(&(raw_ty, 0));
+        }
+
+        Ok(self)
+    }
+
+    /// Ends an active query that was begun with
+    /// [`begin_query_indexed`](Self::begin_query_indexed).
+    ///
+    /// The [`ext_transform_feedback`](crate::device::DeviceExtensions::ext_transform_feedback)
+    /// extension must be enabled on the device.
+    pub fn end_query_indexed(
+        &mut self,
+        query_pool: Arc<QueryPool>,
+        query: u32,
+        index: u32,
+    ) -> Result<&mut Self, QueryError> {
+        self.validate_end_query_indexed(&query_pool, query, index)?;
+
+        unsafe {
+            let raw_ty = ash::vk::QueryType::from(query_pool.query_type());
+            self.inner.end_query_indexed(query_pool, query, index);
+            self.query_state.remove(&(raw_ty, index));
         }
 
         Ok(self)
     }
 
-    fn validate_end_query(&self, query_pool: &QueryPool, query: u32) -> Result<(), QueryError> {
+    fn validate_end_query_indexed(
+        &self,
+        query_pool: &QueryPool,
+        query: u32,
+        index: u32,
+    ) -> Result<(), QueryError> {
+        if !self.device().enabled_extensions().ext_transform_feedback {
+            return Err(QueryError::RequirementNotMet {
+                required_for: "`end_query_indexed`",
+                requires_one_of: RequiresOneOf {
+                    device_extensions: &["ext_transform_feedback"],
+                    ..Default::default()
+                },
+            });
+        }
+
+        // VUID-vkCmdEndQueryIndexedEXT-queryType-02346
+        // VUID-vkCmdEndQueryIndexedEXT-queryType-02347
+        self.validate_query_index(query_pool, index)?;
+
+        // The remaining requirements are the same as for `vkCmdEndQuery`.
+        // VUID-vkCmdEndQueryIndexedEXT-queryType-06694
+        self.validate_end_query(query_pool, query, index)
+    }
+
+    fn validate_end_query(
+        &self,
+        query_pool: &QueryPool,
+        query: u32,
+        index: u32,
+    ) -> Result<(), QueryError> {
         let queue_family_properties = self.queue_family_properties();
 
         // VUID-vkCmdEndQuery-commandBuffer-cmdpool
@@ -189,7 +359,7 @@ impl<L, P> AutoCommandBufferBuilder<L, P> {
         // VUID-vkCmdEndQuery-None-01923
         if !self
             .query_state
-            .get(&query_pool.query_type().into())
+            .get(&(ash::vk::QueryType::from(query_pool.query_type()), index))
             .map_or(false, |state| {
                 state.query_pool == query_pool.internal_object() && state.query == query
             })
@@ -533,6 +703,70 @@ impl SyncCommandBufferBuilder {
         self.commands.push(Box::new(Cmd { query_pool, query }));
     }
 
+    /// Calls `vkCmdBeginQueryIndexedEXT` on the builder.
+    #[inline]
+    pub unsafe fn begin_query_indexed(
+        &mut self,
+        query_pool: Arc<QueryPool>,
+        query: u32,
+        flags: QueryControlFlags,
+        index: u32,
+    ) {
+        struct Cmd {
+            query_pool: Arc<QueryPool>,
+            query: u32,
+            flags: QueryControlFlags,
+            index: u32,
+        }
+
+        impl Command for Cmd {
+            fn name(&self) -> &'static str {
+                "begin_query_indexed"
+            }
+
+            unsafe fn send(&self, out: &mut UnsafeCommandBufferBuilder) {
+                out.begin_query_indexed(
+                    self.query_pool.query(self.query).unwrap(),
+                    self.flags,
+                    self.index,
+                );
+            }
+        }
+
+        self.commands.push(Box::new(Cmd {
+            query_pool,
+            query,
+            flags,
+            index,
+        }));
+    }
+
+    /// Calls `vkCmdEndQueryIndexedEXT` on the builder.
+    #[inline]
+    pub unsafe fn end_query_indexed(&mut self, query_pool: Arc<QueryPool>, query: u32, index: u32) {
+        struct Cmd {
+            query_pool: Arc<QueryPool>,
+            query: u32,
+            index: u32,
+        }
+
+        impl Command for Cmd {
+            fn name(&self) -> &'static str {
+                "end_query_indexed"
+            }
+
+            unsafe fn send(&self, out: &mut UnsafeCommandBufferBuilder) {
+                out.end_query_indexed(self.query_pool.query(self.query).unwrap(), self.index);
+            }
+        }
+
+        self.commands.push(Box::new(Cmd {
+            query_pool,
+            query,
+            index,
+        }));
+    }
+
     /// Calls `vkCmdWriteTimestamp` on the builder.
     #[inline]
     pub unsafe fn write_timestamp(
@@ -695,6 +929,41 @@ impl UnsafeCommandBufferBuilder {
         (fns.v1_0.cmd_end_query)(self.handle, query.pool().internal_object(), query.index());
     }
 
+    /// Calls `vkCmdBeginQueryIndexedEXT` on the builder.
+    #[inline]
+    pub unsafe fn begin_query_indexed(
+        &mut self,
+        query: Query<'_>,
+        flags: QueryControlFlags,
+        index: u32,
+    ) {
+        let fns = self.device.fns();
+        let flags = if flags.precise {
+            ash::vk::QueryControlFlags::PRECISE
+        } else {
+            ash::vk::QueryControlFlags::empty()
+        };
+        (fns.ext_transform_feedback.cmd_begin_query_indexed_ext)(
+            self.handle,
+            query.pool().internal_object(),
+            query.index(),
+            flags,
+            index,
+        );
+    }
+
+    /// Calls `vkCmdEndQueryIndexedEXT` on the builder.
+    #[inline]
+    pub unsafe fn end_query_indexed(&mut self, query: Query<'_>, index: u32) {
+        let fns = self.device.fns();
+        (fns.ext_transform_feedback.cmd_end_query_indexed_ext)(
+            self.handle,
+            query.pool().internal_object(),
+            query.index(),
+            index,
+        );
+    }
+
     /// Calls `vkCmdWriteTimestamp` on the builder.
     #[inline]
     pub unsafe fn write_timestamp(&mut self, query: Query<'_>, stage: PipelineStage) {
@@ -776,6 +1045,9 @@ pub enum QueryError {
     /// Operation forbidden inside of a render pass.
     ForbiddenInsideRenderPass,
 
+    /// A query index other than 0 was provided for a query type that only has a single stream.
+    IndexNotZero,
+
     /// The provided flags are not allowed for this type of query.
     InvalidFlags,
 
@@ -803,6 +1075,18 @@ pub enum QueryError {
 
     /// The provided stage is not supported by the queue family.
     StageNotSupported,
+
+    /// The provided vertex stream index is not less than the
+    /// [`max_transform_feedback_streams`](crate::device::Properties::max_transform_feedback_streams)
+    /// limit.
+    StreamIndexOutOfRange {
+        index: u32,
+        max: u32,
+    },
+
+    /// The [`transform_feedback_queries`](crate::device::Properties::transform_feedback_queries)
+    /// device property is not `true`.
+    TransformFeedbackQueriesNotSupported,
 }
 
 impl Error for QueryError {}
@@ -829,6 +1113,11 @@ impl Display for QueryError {
             Self::ForbiddenInsideRenderPass => {
                 write!(f, "operation forbidden inside of a render pass")
             }
+            Self::IndexNotZero => write!(
+                f,
+                "a query index other than 0 was provided for a query type that only has a single \
+                stream",
+            ),
             Self::InvalidFlags => write!(
                 f,
                 "the provided flags are not allowed for this type of query",
@@ -854,6 +1143,16 @@ impl Display for QueryError {
             Self::StageNotSupported => {
                 write!(f, "the provided stage is not supported by the queue family")
             }
+            Self::StreamIndexOutOfRange { index, max } => write!(
+                f,
+                "the provided vertex stream index ({}) is not less than the \
+                `max_transform_feedback_streams` limit ({})",
+                index, max,
+            ),
+            Self::TransformFeedbackQueriesNotSupported => write!(
+                f,
+                "the `transform_feedback_queries` device property is not `true`",
+            ),
         }
     }
 }
@@ -20,10 +20,11 @@ use crate::{
     },
     device::DeviceOwned,
     format::{ClearColorValue, ClearValue, Format, NumericType},
-    image::{ImageLayout, ImageViewAbstract, SampleCount},
+    image::{view::ImageViewType, ImageLayout, ImageViewAbstract, SampleCount},
+    pipeline::graphics::fragment_shading_rate::is_valid_attachment_texel_size,
     render_pass::{
-        AttachmentDescription, Framebuffer, LoadOp, RenderPass, ResolveMode, StoreOp,
-        SubpassDescription,
+        AttachmentDescription, Framebuffer, FramebufferAttachmentImageInfo, LoadOp, RenderPass,
+        ResolveMode, StoreOp, SubpassDescription,
     },
     sync::{AccessFlags, PipelineMemoryAccess, PipelineStages},
     RequirementNotMet, RequiresOneOf, Version, VulkanObject,
@@ -61,6 +62,7 @@ where
             let RenderPassBeginInfo {
                 ref render_pass,
                 ref framebuffer,
+                attachments: _,
                 render_area_offset,
                 render_area_extent,
                 clear_values: _,
@@ -77,6 +79,7 @@ where
                 render_pass: BeginRenderPassState {
                     subpass,
                     framebuffer: Some(framebuffer.clone()),
+                    attachments: Some(render_pass_begin_info.attachment_image_views().to_vec()),
                 }
                 .into(),
                 view_mask,
@@ -115,6 +118,7 @@ where
         let RenderPassBeginInfo {
             render_pass,
             framebuffer,
+            attachments,
             render_area_offset,
             render_area_extent,
             clear_values,
@@ -130,6 +134,93 @@ where
             return Err(RenderPassError::FramebufferNotCompatible);
         }
 
+        let image_views = if framebuffer.is_imageless() {
+            // VUID-VkRenderPassBeginInfo-framebuffer-03208
+            if attachments.len() != framebuffer.attachment_image_infos().len() {
+                return Err(RenderPassError::AttachmentCountMismatch {
+                    provided: attachments.len() as u32,
+                    required: framebuffer.attachment_image_infos().len() as u32,
+                });
+            }
+
+            for (attachment_index, ((image_view, image_info), attachment_desc)) in attachments
+                .iter()
+                .zip(framebuffer.attachment_image_infos())
+                .zip(render_pass.attachments())
+                .enumerate()
+            {
+                let attachment_index = attachment_index as u32;
+
+                // VUID-VkRenderPassAttachmentBeginInfo-pAttachments-parameter
+                assert_eq!(device, image_view.device());
+
+                let view_info = FramebufferAttachmentImageInfo::image_view(image_view.as_ref());
+
+                // VUID-VkRenderPassBeginInfo-framebuffer-03209
+                // VUID-VkRenderPassBeginInfo-framebuffer-03210
+                // VUID-VkRenderPassBeginInfo-framebuffer-03211
+                // VUID-VkRenderPassBeginInfo-framebuffer-03212
+                // VUID-VkRenderPassBeginInfo-framebuffer-03213
+                if view_info.flags != image_info.flags
+                    || view_info.usage != image_info.usage
+                    || view_info.extent != image_info.extent
+                    || view_info.array_layers != image_info.array_layers
+                {
+                    return Err(RenderPassError::AttachmentImageNotCompatible { attachment_index });
+                }
+
+                // VUID-VkRenderPassBeginInfo-framebuffer-03216
+                // VUID?
+                if image_view.format() != attachment_desc.format
+                    || image_view.image().samples() != attachment_desc.samples
+                {
+                    return Err(RenderPassError::AttachmentImageNotCompatible { attachment_index });
+                }
+
+                // VUID-VkRenderPassBeginInfo-framebuffer-03214
+                // VUID-VkRenderPassBeginInfo-framebuffer-03215
+                // Images are created without an explicit list of view formats, so the formats
+                // that views of the image can have follow from its format and `mutable_format`.
+                let image_inner = image_view.image().inner().image;
+                let image_format = image_inner.format().unwrap();
+
+                if !image_info.view_formats.iter().all(|&view_format| {
+                    view_format == image_format
+                        || (image_inner.mutable_format()
+                            && view_format.compatibility() == image_format.compatibility())
+                }) || !image_view
+                    .format()
+                    .map_or(false, |format| image_info.view_formats.contains(&format))
+                {
+                    return Err(RenderPassError::AttachmentImageNotCompatible { attachment_index });
+                }
+
+                let subresource_range = image_view.subresource_range();
+
+                // VUID-VkRenderPassAttachmentBeginInfo-pAttachments-03218
+                // VUID-VkRenderPassAttachmentBeginInfo-pAttachments-03219
+                // VUID-VkRenderPassAttachmentBeginInfo-pAttachments-04114
+                if subresource_range.mip_levels.end - subresource_range.mip_levels.start != 1
+                    || !image_view.component_mapping().is_identity()
+                    || image_view.view_type() == ImageViewType::Dim3d
+                {
+                    return Err(RenderPassError::AttachmentImageViewInvalid { attachment_index });
+                }
+            }
+
+            attachments.as_slice()
+        } else {
+            // VUID-VkRenderPassBeginInfo-framebuffer-03207
+            if !attachments.is_empty() {
+                return Err(RenderPassError::AttachmentCountMismatch {
+                    provided: attachments.len() as u32,
+                    required: 0,
+                });
+            }
+
+            framebuffer.attachments()
+        };
+
         for i in 0..2 {
             // VUID-VkRenderPassBeginInfo-pNext-02852
             // VUID-VkRenderPassBeginInfo-pNext-02853
@@ -141,7 +232,7 @@ where
         for (attachment_index, (attachment_desc, image_view)) in render_pass
             .attachments()
             .iter()
-            .zip(framebuffer.attachments())
+            .zip(image_views)
             .enumerate()
         {
             let attachment_index = attachment_index as u32;
@@ -201,6 +292,15 @@ where
                             });
                         }
                     }
+                    ImageLayout::FragmentShadingRateAttachmentOptimal => {
+                        // VUID?
+                        if !image_view.usage().fragment_shading_rate_attachment {
+                            return Err(RenderPassError::AttachmentImageMissingUsage {
+                                attachment_index,
+                                usage: "fragment_shading_rate_attachment",
+                            });
+                        }
+                    }
                     _ => (),
                 }
             }
@@ -213,6 +313,8 @@ where
                 color_attachments,
                 resolve_attachments,
                 depth_stencil_attachment,
+                fragment_shading_rate_attachment,
+                fragment_shading_rate_attachment_texel_size: _,
                 preserve_attachments: _,
                 _ne: _,
             } = subpass_desc;
@@ -221,9 +323,10 @@ where
                 .chain(color_attachments)
                 .chain(resolve_attachments)
                 .chain([depth_stencil_attachment])
+                .chain([fragment_shading_rate_attachment])
                 .flatten()
             {
-                let image_view = &framebuffer.attachments()[atch_ref.attachment as usize];
+                let image_view = &image_views[atch_ref.attachment as usize];
 
                 match atch_ref.layout {
                     ImageLayout::ColorAttachmentOptimal => {
@@ -274,6 +377,15 @@ where
                             });
                         }
                     }
+                    ImageLayout::FragmentShadingRateAttachmentOptimal => {
+                        // VUID?
+                        if !image_view.usage().fragment_shading_rate_attachment {
+                            return Err(RenderPassError::AttachmentImageMissingUsage {
+                                attachment_index: atch_ref.attachment,
+                                usage: "fragment_shading_rate_attachment",
+                            });
+                        }
+                    }
                     _ => (),
                 }
             }
@@ -455,6 +567,20 @@ where
             return Err(RenderPassError::QueryIsActive);
         }
 
+        // VUID-vkCmdNextSubpass2-None-02350
+        if self.transform_feedback_active {
+            return Err(RenderPassError::TransformFeedbackActive);
+        }
+
+        // VUID?
+        if self
+            .conditional_rendering_state
+            .as_ref()
+            .map_or(false, |state| state.in_subpass)
+        {
+            return Err(RenderPassError::ConditionalRenderingActive);
+        }
+
         // VUID-vkCmdNextSubpass2-commandBuffer-cmdpool
         debug_assert!({
             let queue_family_properties = self.queue_family_properties();
@@ -513,6 +639,20 @@ where
             return Err(RenderPassError::QueryIsActive);
         }
 
+        // VUID-vkCmdEndRenderPass2-None-02352
+        if self.transform_feedback_active {
+            return Err(RenderPassError::TransformFeedbackActive);
+        }
+
+        // VUID?
+        if self
+            .conditional_rendering_state
+            .as_ref()
+            .map_or(false, |state| state.in_subpass)
+        {
+            return Err(RenderPassError::ConditionalRenderingActive);
+        }
+
         // VUID-vkCmdEndRenderPass2-commandBuffer-cmdpool
         debug_assert!({
             let queue_family_properties = self.queue_family_properties();
@@ -543,6 +683,7 @@ impl<L, P> AutoCommandBufferBuilder<L, P> {
                 ref color_attachments,
                 ref depth_attachment,
                 ref stencil_attachment,
+                fragment_shading_rate_attachment: _,
                 contents: _,
                 _ne: _,
             } = rendering_info;
@@ -627,6 +768,7 @@ impl<L, P> AutoCommandBufferBuilder<L, P> {
                 ref color_attachments,
                 ref depth_attachment,
                 ref stencil_attachment,
+                fragment_shading_rate_attachment: _,
                 contents,
                 _ne: _,
             } = rendering_info;
@@ -703,6 +845,7 @@ impl<L, P> AutoCommandBufferBuilder<L, P> {
             ref color_attachments,
             ref depth_attachment,
             ref stencil_attachment,
+            ref fragment_shading_rate_attachment,
             contents,
             _ne: _,
         } = rendering_info;
@@ -1211,6 +1354,78 @@ impl<L, P> AutoCommandBufferBuilder<L, P> {
             }
         }
 
+        if let Some(attachment_info) = fragment_shading_rate_attachment {
+            let &RenderingFragmentShadingRateAttachmentInfo {
+                ref image_view,
+                image_layout,
+                texel_size,
+                _ne: _,
+            } = attachment_info;
+
+            // VUID-VkRenderingFragmentShadingRateAttachmentInfoKHR-imageLayout-parameter
+            image_layout.validate_device(device)?;
+
+            // VUID?
+            if !device.enabled_features().attachment_fragment_shading_rate {
+                return Err(RenderPassError::RequirementNotMet {
+                    required_for: "`rendering_info.fragment_shading_rate_attachment` is `Some`",
+                    requires_one_of: RequiresOneOf {
+                        features: &["attachment_fragment_shading_rate"],
+                        ..Default::default()
+                    },
+                });
+            }
+
+            // VUID-VkRenderingInfo-imageView-06148
+            if !image_view.usage().fragment_shading_rate_attachment {
+                return Err(RenderPassError::FragmentShadingRateAttachmentMissingUsage);
+            }
+
+            // VUID-VkRenderingFragmentShadingRateAttachmentInfoKHR-imageView-06147
+            if !matches!(
+                image_layout,
+                ImageLayout::General | ImageLayout::FragmentShadingRateAttachmentOptimal
+            ) {
+                return Err(RenderPassError::FragmentShadingRateAttachmentLayoutInvalid);
+            }
+
+            // VUID-VkRenderingFragmentShadingRateAttachmentInfoKHR-imageView-06149
+            // VUID-VkRenderingFragmentShadingRateAttachmentInfoKHR-imageView-06150
+            // VUID-VkRenderingFragmentShadingRateAttachmentInfoKHR-imageView-06151
+            // VUID-VkRenderingFragmentShadingRateAttachmentInfoKHR-imageView-06152
+            // VUID-VkRenderingFragmentShadingRateAttachmentInfoKHR-imageView-06153
+            // VUID-VkRenderingFragmentShadingRateAttachmentInfoKHR-imageView-06154
+            // VUID-VkRenderingFragmentShadingRateAttachmentInfoKHR-imageView-06155
+            // VUID-VkRenderingFragmentShadingRateAttachmentInfoKHR-imageView-06156
+            if !is_valid_attachment_texel_size(properties, texel_size) {
+                return Err(
+                    RenderPassError::FragmentShadingRateAttachmentTexelSizeNotSupported {
+                        texel_size,
+                    },
+                );
+            }
+
+            // The render area is compared with the extent of the mip level that the view
+            // accesses, not of the whole image.
+            let image_extent = image_view
+                .image()
+                .dimensions()
+                .mip_level_dimensions(image_view.subresource_range().mip_levels.start)
+                .unwrap()
+                .width_height();
+
+            for i in 0..2 {
+                // VUID-VkRenderingInfo-imageView-06117
+                // VUID-VkRenderingInfo-imageView-06118
+                if (render_area_offset[i] + render_area_extent[i] + texel_size[i] - 1)
+                    / texel_size[i]
+                    > image_extent[i]
+                {
+                    return Err(RenderPassError::RenderAreaOutOfBounds);
+                }
+            }
+        }
+
         Ok(())
     }
 
@@ -1247,6 +1462,20 @@ impl<L, P> AutoCommandBufferBuilder<L, P> {
             RenderPassStateType::BeginRendering(_) => (),
         }
 
+        // VUID?
+        if self.transform_feedback_active {
+            return Err(RenderPassError::TransformFeedbackActive);
+        }
+
+        // VUID?
+        if self
+            .conditional_rendering_state
+            .as_ref()
+            .map_or(false, |state| state.in_subpass)
+        {
+            return Err(RenderPassError::ConditionalRenderingActive);
+        }
+
         // VUID-vkCmdEndRendering-commandBuffer-cmdpool
         debug_assert!({
             let queue_family_properties = self.queue_family_properties();
@@ -1363,14 +1592,14 @@ impl<L, P> AutoCommandBufferBuilder<L, P> {
                     }
 
                     let image_view = match &render_pass_state.render_pass {
-                        RenderPassStateType::BeginRenderPass(state) => (state.framebuffer.as_ref())
+                        RenderPassStateType::BeginRenderPass(state) => (state.attachments.as_ref())
                             .zip(
                                 state.subpass.subpass_desc().color_attachments
                                     [color_attachment as usize]
                                     .as_ref(),
                             )
-                            .map(|(framebuffer, atch_ref)| {
-                                &framebuffer.attachments()[atch_ref.attachment as usize]
+                            .map(|(attachments, atch_ref)| {
+                                &attachments[atch_ref.attachment as usize]
                             }),
                         RenderPassStateType::BeginRendering(state) => state
                             .attachments
@@ -1434,7 +1663,7 @@ impl<L, P> AutoCommandBufferBuilder<L, P> {
                     }
 
                     let image_view = match &render_pass_state.render_pass {
-                        RenderPassStateType::BeginRenderPass(state) => (state.framebuffer.as_ref())
+                        RenderPassStateType::BeginRenderPass(state) => (state.attachments.as_ref())
                             .zip(
                                 state
                                     .subpass
@@ -1442,8 +1671,8 @@ impl<L, P> AutoCommandBufferBuilder<L, P> {
                                     .depth_stencil_attachment
                                     .as_ref(),
                             )
-                            .map(|(framebuffer, atch_ref)| {
-                                &framebuffer.attachments()[atch_ref.attachment as usize]
+                            .map(|(attachments, atch_ref)| {
+                                &attachments[atch_ref.attachment as usize]
                             }),
                         RenderPassStateType::BeginRendering(state) => state
                             .attachments
@@ -1534,21 +1763,25 @@ impl SyncCommandBufferBuilder {
             }
         }
 
-        let RenderPassBeginInfo {
-            render_pass,
-            framebuffer,
-            render_area_offset: _,
-            render_area_extent: _,
-            clear_values: _,
-            _ne: _,
-        } = &render_pass_begin_info;
+        let image_views = render_pass_begin_info.attachment_image_views();
+        let render_pass = &render_pass_begin_info.render_pass;
 
         let resources = render_pass
             .attachments()
             .iter()
             .enumerate()
             .map(|(num, desc)| {
-                let image_view = &framebuffer.attachments()[num];
+                let image_view = &image_views[num];
+
+                // Fragment shading rate attachments are read in their own pipeline stage, which
+                // must be made visible to writes from earlier commands.
+                let is_fragment_shading_rate_attachment =
+                    render_pass.subpasses().iter().any(|subpass| {
+                        subpass
+                            .fragment_shading_rate_attachment
+                            .as_ref()
+                            .map_or(false, |atch_ref| atch_ref.attachment == num as u32)
+                    });
 
                 (
                     format!("attachment {}", num).into(),
@@ -1558,6 +1791,8 @@ impl SyncCommandBufferBuilder {
                         memory: PipelineMemoryAccess {
                             stages: PipelineStages {
                                 all_commands: true,
+                                fragment_shading_rate_attachment:
+                                    is_fragment_shading_rate_attachment,
                                 ..PipelineStages::empty()
                             }, // TODO: wrong!
                             access: AccessFlags {
@@ -1566,6 +1801,8 @@ impl SyncCommandBufferBuilder {
                                 color_attachment_write: true,
                                 depth_stencil_attachment_read: true,
                                 depth_stencil_attachment_write: true,
+                                fragment_shading_rate_attachment_read:
+                                    is_fragment_shading_rate_attachment,
                                 ..AccessFlags::empty()
                             }, // TODO: suboptimal
                             exclusive: true, // TODO: suboptimal ; note: remember to always pass true if desc.initial_layout != desc.final_layout
@@ -1663,6 +1900,7 @@ impl SyncCommandBufferBuilder {
             color_attachments,
             depth_attachment,
             stencil_attachment,
+            fragment_shading_rate_attachment,
             contents: _,
             _ne,
         } = &rendering_info;
@@ -1873,6 +2111,39 @@ impl SyncCommandBufferBuilder {
             .into_iter()
             .flatten()
         }))
+        .chain(
+            fragment_shading_rate_attachment
+                .iter()
+                .map(|attachment_info| {
+                    let &RenderingFragmentShadingRateAttachmentInfo {
+                        ref image_view,
+                        image_layout,
+                        texel_size: _,
+                        _ne: _,
+                    } = attachment_info;
+
+                    (
+                        "fragment shading rate attachment".into(),
+                        Resource::Image {
+                            image: image_view.image(),
+                            subresource_range: image_view.subresource_range().clone(),
+                            memory: PipelineMemoryAccess {
+                                stages: PipelineStages {
+                                    fragment_shading_rate_attachment: true,
+                                    ..PipelineStages::empty()
+                                },
+                                access: AccessFlags {
+                                    fragment_shading_rate_attachment_read: true,
+                                    ..AccessFlags::empty()
+                                },
+                                exclusive: false,
+                            },
+                            start_layout: image_layout,
+                            end_layout: image_layout,
+                        },
+                    )
+                }),
+        )
         .collect::<Vec<_>>();
 
         for resource in &resources {
@@ -1951,6 +2222,7 @@ impl UnsafeCommandBufferBuilder {
         let &RenderPassBeginInfo {
             ref render_pass,
             ref framebuffer,
+            ref attachments,
             render_area_offset,
             render_area_extent,
             ref clear_values,
@@ -1963,7 +2235,7 @@ impl UnsafeCommandBufferBuilder {
             .map(|clear_value| clear_value.map(Into::into).unwrap_or_default())
             .collect();
 
-        let render_pass_begin_info = ash::vk::RenderPassBeginInfo {
+        let mut render_pass_begin_info = ash::vk::RenderPassBeginInfo {
             render_pass: render_pass.internal_object(),
             framebuffer: framebuffer.internal_object(),
             render_area: ash::vk::Rect2D {
@@ -1981,6 +2253,23 @@ impl UnsafeCommandBufferBuilder {
             ..Default::default()
         };
 
+        let attachments_vk: SmallVec<[_; 4]> = attachments
+            .iter()
+            .map(|image_view| image_view.internal_object())
+            .collect();
+        let render_pass_attachment_begin_info_vk;
+
+        if framebuffer.is_imageless() {
+            render_pass_attachment_begin_info_vk = ash::vk::RenderPassAttachmentBeginInfo {
+                attachment_count: attachments_vk.len() as u32,
+                p_attachments: attachments_vk.as_ptr(),
+                ..Default::default()
+            };
+
+            render_pass_begin_info.p_next =
+                &render_pass_attachment_begin_info_vk as *const _ as *const _;
+        }
+
         let subpass_begin_info = ash::vk::SubpassBeginInfo {
             contents: contents.into(),
             ..Default::default()
@@ -2083,6 +2372,7 @@ impl UnsafeCommandBufferBuilder {
             ref color_attachments,
             ref depth_attachment,
             ref stencil_attachment,
+            ref fragment_shading_rate_attachment,
             contents,
             _ne: _,
         } = rendering_info;
@@ -2144,7 +2434,29 @@ impl UnsafeCommandBufferBuilder {
         let depth_attachment = map_attachment_info(depth_attachment);
         let stencil_attachment = map_attachment_info(stencil_attachment);
 
-        let rendering_info = ash::vk::RenderingInfo {
+        let fragment_shading_rate_attachment_vk =
+            fragment_shading_rate_attachment
+                .as_ref()
+                .map(|attachment_info| {
+                    let &RenderingFragmentShadingRateAttachmentInfo {
+                        ref image_view,
+                        image_layout,
+                        texel_size,
+                        _ne: _,
+                    } = attachment_info;
+
+                    ash::vk::RenderingFragmentShadingRateAttachmentInfoKHR {
+                        image_view: image_view.internal_object(),
+                        image_layout: image_layout.into(),
+                        shading_rate_attachment_texel_size: ash::vk::Extent2D {
+                            width: texel_size[0],
+                            height: texel_size[1],
+                        },
+                        ..Default::default()
+                    }
+                });
+
+        let mut rendering_info = ash::vk::RenderingInfo {
             flags: contents.into(),
             render_area: ash::vk::Rect2D {
                 offset: ash::vk::Offset2D {
@@ -2165,6 +2477,10 @@ impl UnsafeCommandBufferBuilder {
             ..Default::default()
         };
 
+        if let Some(fragment_shading_rate_attachment_vk) = &fragment_shading_rate_attachment_vk {
+            rendering_info.p_next = fragment_shading_rate_attachment_vk as *const _ as *const _;
+        }
+
         let fns = self.device.fns();
 
         if self.device.api_version() >= Version::V1_3 {
@@ -2247,6 +2563,16 @@ pub struct RenderPassBeginInfo {
     /// There is no default value.
     pub framebuffer: Arc<Framebuffer>,
 
+    /// If `framebuffer` is imageless, the image views to use as the attachments of the render
+    /// pass.
+    ///
+    /// There must be exactly as many image views as there are attachment image infos in
+    /// `framebuffer`, and each one must match the corresponding
+    /// [`FramebufferAttachmentImageInfo`]. If `framebuffer` is not imageless, this must be empty.
+    ///
+    /// The default value is empty.
+    pub attachments: Vec<Arc<dyn ImageViewAbstract>>,
+
     /// The offset from the top left corner of the framebuffer that will be rendered to.
     ///
     /// The default value is `[0, 0]`.
@@ -2261,8 +2587,8 @@ pub struct RenderPassBeginInfo {
 
     /// Provides, for each attachment in `render_pass` that has a load operation of
     /// [`LoadOp::Clear`], the clear values that should be used for the attachments in the
-    /// framebuffer. There must be exactly [`framebuffer.attachments().len()`] elements provided,
-    /// and each one must match the attachment format.
+    /// framebuffer. There must be exactly one element for each attachment of `render_pass`, and
+    /// each one must match the attachment format.
     ///
     /// To skip over an attachment whose load operation is something else, provide `None`.
     ///
@@ -2280,12 +2606,24 @@ impl RenderPassBeginInfo {
         Self {
             render_pass: framebuffer.render_pass().clone(),
             framebuffer,
+            attachments: Vec::new(),
             render_area_offset: [0, 0],
             render_area_extent,
             clear_values: Vec::new(),
             _ne: crate::NonExhaustive(()),
         }
     }
+
+    /// Returns the image views that are used as the attachments of the render pass: either
+    /// `attachments` if the framebuffer is imageless, or the attachments of the framebuffer.
+    #[inline]
+    pub(crate) fn attachment_image_views(&self) -> &[Arc<dyn ImageViewAbstract>] {
+        if self.framebuffer.is_imageless() {
+            &self.attachments
+        } else {
+            self.framebuffer.attachments()
+        }
+    }
 }
 
 /// Parameters to begin rendering.
@@ -2356,6 +2694,15 @@ pub struct RenderingInfo {
     /// The default value is `None`.
     pub stencil_attachment: Option<RenderingAttachmentInfo>,
 
+    /// The fragment shading rate attachment to use for rendering.
+    ///
+    /// If set to `Some`, the
+    /// [`attachment_fragment_shading_rate`](crate::device::Features::attachment_fragment_shading_rate)
+    /// feature must be enabled on the device.
+    ///
+    /// The default value is `None`.
+    pub fragment_shading_rate_attachment: Option<RenderingFragmentShadingRateAttachmentInfo>,
+
     /// What kinds of commands will be recorded in the render pass: either inline draw commands, or
     /// executions of secondary command buffers.
     ///
@@ -2378,6 +2725,7 @@ impl Default for RenderingInfo {
             color_attachments: Vec::new(),
             depth_attachment: None,
             stencil_attachment: None,
+            fragment_shading_rate_attachment: None,
             contents: SubpassContents::Inline,
             _ne: crate::NonExhaustive(()),
         }
@@ -2488,6 +2836,65 @@ impl RenderingAttachmentResolveInfo {
     }
 }
 
+/// Parameters to specify the fragment shading rate attachment used during rendering.
+#[derive(Clone, Debug)]
+pub struct RenderingFragmentShadingRateAttachmentInfo {
+    /// The image view to use as the fragment shading rate attachment.
+    ///
+    /// The image view must have been created with the `fragment_shading_rate_attachment` usage,
+    /// and its format must support the `fragment_shading_rate_attachment` format feature.
+    ///
+    /// There is no default value.
+    pub image_view: Arc<dyn ImageViewAbstract>,
+
+    /// The image layout that `image_view` should be in during rendering.
+    ///
+    /// This must be either [`ImageLayout::General`] or
+    /// [`ImageLayout::FragmentShadingRateAttachmentOptimal`].
+    ///
+    /// The default value is [`ImageLayout::FragmentShadingRateAttachmentOptimal`].
+    pub image_layout: ImageLayout,
+
+    /// The size of the area in the framebuffer, in pixels, that corresponds to each texel of
+    /// `image_view`.
+    ///
+    /// Both values must be powers of two, and must be between the
+    /// [`min_fragment_shading_rate_attachment_texel_size`] and
+    /// [`max_fragment_shading_rate_attachment_texel_size`] device properties. The ratio of the
+    /// larger value to the smaller value must not exceed the
+    /// [`max_fragment_shading_rate_attachment_texel_size_aspect_ratio`] device property.
+    ///
+    /// The default value is the value of the
+    /// [`min_fragment_shading_rate_attachment_texel_size`] device property.
+    ///
+    /// [`min_fragment_shading_rate_attachment_texel_size`]: crate::device::Properties::min_fragment_shading_rate_attachment_texel_size
+    /// [`max_fragment_shading_rate_attachment_texel_size`]: crate::device::Properties::max_fragment_shading_rate_attachment_texel_size
+    /// [`max_fragment_shading_rate_attachment_texel_size_aspect_ratio`]: crate::device::Properties::max_fragment_shading_rate_attachment_texel_size_aspect_ratio
+    pub texel_size: [u32; 2],
+
+    pub _ne: crate::NonExhaustive,
+}
+
+impl RenderingFragmentShadingRateAttachmentInfo {
+    /// Returns a `RenderingFragmentShadingRateAttachmentInfo` with the specified `image_view`.
+    #[inline]
+    pub fn image_view(image_view: Arc<dyn ImageViewAbstract>) -> Self {
+        let texel_size = image_view
+            .device()
+            .physical_device()
+            .properties()
+            .min_fragment_shading_rate_attachment_texel_size
+            .unwrap_or([1, 1]);
+
+        Self {
+            image_view,
+            image_layout: ImageLayout::FragmentShadingRateAttachmentOptimal,
+            texel_size,
+            _ne: crate::NonExhaustive(()),
+        }
+    }
+}
+
 /// Clear attachment type, used in [`clear_attachments`] command.
 ///
 /// [`clear_attachments`]: crate::command_buffer::AutoCommandBufferBuilder::clear_attachments
@@ -2576,12 +2983,33 @@ pub enum RenderPassError {
         requires_one_of: RequiresOneOf,
     },
 
+    /// The number of image views in `attachments` does not match the number of attachment image
+    /// infos of an imageless framebuffer, or `attachments` is not empty for a framebuffer that is
+    /// not imageless.
+    AttachmentCountMismatch {
+        provided: u32,
+        required: u32,
+    },
+
     /// A framebuffer image did not have the required usage enabled.
     AttachmentImageMissingUsage {
         attachment_index: u32,
         usage: &'static str,
     },
 
+    /// An image view provided for an imageless framebuffer does not match the corresponding
+    /// attachment image info of the framebuffer, or the corresponding attachment of the render
+    /// pass.
+    AttachmentImageNotCompatible {
+        attachment_index: u32,
+    },
+
+    /// An image view provided for an imageless framebuffer has multiple mip levels, a
+    /// non-identity component mapping, or a view type of [`ImageViewType::Dim3d`].
+    AttachmentImageViewInvalid {
+        attachment_index: u32,
+    },
+
     /// One of the elements of `render_pass_extent` is zero, but no attachment images were given to
     /// calculate the extent from.
     AutoExtentAttachmentsEmpty,
@@ -2659,6 +3087,9 @@ pub enum RenderPassError {
         attachment_index: u32,
     },
 
+    /// Conditional rendering is active, and was begun in the current subpass.
+    ConditionalRenderingActive,
+
     /// The contents `SubpassContents::SecondaryCommandBuffers` is not allowed inside a secondary
     /// command buffer.
     ContentsForbiddenInSecondaryCommandBuffer,
@@ -2723,6 +3154,18 @@ pub enum RenderPassError {
         contents: SubpassContents,
     },
 
+    /// The fragment shading rate attachment has a layout that is not supported.
+    FragmentShadingRateAttachmentLayoutInvalid,
+
+    /// The fragment shading rate attachment is missing the `fragment_shading_rate_attachment`
+    /// usage.
+    FragmentShadingRateAttachmentMissingUsage,
+
+    /// The texel size of the fragment shading rate attachment is not supported by the device.
+    FragmentShadingRateAttachmentTexelSizeNotSupported {
+        texel_size: [u32; 2],
+    },
+
     /// The framebuffer is not compatible with the render pass.
     FramebufferNotCompatible,
 
@@ -2817,6 +3260,9 @@ pub enum RenderPassError {
         current_subpass: u32,
         remaining_subpasses: u32,
     },
+
+    /// Transform feedback is active.
+    TransformFeedbackActive,
 }
 
 impl Error for RenderPassError {
@@ -2840,6 +3286,12 @@ impl Display for RenderPassError {
                 "a requirement was not met for: {}; requires one of: {}",
                 required_for, requires_one_of,
             ),
+            Self::AttachmentCountMismatch { provided, required } => write!(
+                f,
+                "the number of image views in `attachments` ({}) does not match the number \
+                required by the framebuffer ({})",
+                provided, required,
+            ),
             Self::AttachmentImageMissingUsage {
                 attachment_index,
                 usage,
@@ -2849,6 +3301,19 @@ impl Display for RenderPassError {
                 usage {} enabled",
                 attachment_index, usage,
             ),
+            Self::AttachmentImageNotCompatible { attachment_index } => write!(
+                f,
+                "the image view provided for attachment index {} does not match the corresponding \
+                attachment image info of the framebuffer, or the corresponding attachment of the \
+                render pass",
+                attachment_index,
+            ),
+            Self::AttachmentImageViewInvalid { attachment_index } => write!(
+                f,
+                "the image view provided for attachment index {} has multiple mip levels, a \
+                non-identity component mapping, or a view type of `ImageViewType::Dim3d`",
+                attachment_index,
+            ),
             Self::AutoExtentAttachmentsEmpty => write!(
                 f,
                 "one of the elements of `render_pass_extent` is zero, but no attachment images \
@@ -2936,6 +3401,10 @@ impl Display for RenderPassError {
                 `SampleCount::Sample1`",
                 attachment_index,
             ),
+            Self::ConditionalRenderingActive => write!(
+                f,
+                "conditional rendering is active, and was begun in the current subpass",
+            ),
             Self::ContentsForbiddenInSecondaryCommandBuffer => write!(
                 f,
                 "the contents `SubpassContents::SecondaryCommandBuffers` is not allowed inside a \
@@ -3020,6 +3489,21 @@ impl Display for RenderPassError {
                 "operation forbidden inside a render subpass with contents {:?}",
                 subpass_contents,
             ),
+            Self::FragmentShadingRateAttachmentLayoutInvalid => write!(
+                f,
+                "the fragment shading rate attachment has a layout that is not supported",
+            ),
+            Self::FragmentShadingRateAttachmentMissingUsage => write!(
+                f,
+                "the fragment shading rate attachment is missing the \
+                `fragment_shading_rate_attachment` usage",
+            ),
+            Self::FragmentShadingRateAttachmentTexelSizeNotSupported { texel_size } => write!(
+                f,
+                "the texel size of the fragment shading rate attachment ({:?}) is not supported \
+                by the device",
+                texel_size,
+            ),
             Self::FramebufferNotCompatible => {
                 write!(f, "the framebuffer is not compatible with the render pass")
             }
@@ -3125,6 +3609,7 @@ impl Display for RenderPassError {
                 the render pass",
                 current_subpass, remaining_subpasses,
             ),
+            Self::TransformFeedbackActive => write!(f, "transform feedback is active"),
         }
     }
 }
@@ -3143,3 +3628,353 @@ impl From<RequirementNotMet> for RenderPassError {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        RenderPassBeginInfo, RenderPassError, RenderingFragmentShadingRateAttachmentInfo,
+        RenderingInfo,
+    };
+    use crate::{
+        command_buffer::{
+            pool::{CommandPool, CommandPoolBuilderAlloc},
+            synced::SyncCommandBufferBuilder,
+            sys::CommandBufferBeginInfo,
+            AutoCommandBufferBuilder, CommandBufferLevel, CommandBufferUsage, SubpassContents,
+        },
+        format::Format,
+        image::{
+            view::{ImageView, ImageViewCreateInfo},
+            AttachmentImage, ImageAccess, ImageCreateFlags, ImageDimensions, ImageLayout,
+            ImageSubresourceRange, ImageUsage, ImageViewAbstract, ImmutableImage,
+        },
+        render_pass::{
+            AttachmentDescription, AttachmentReference, Framebuffer,
+            FramebufferAttachmentImageInfo, FramebufferCreateInfo, RenderPass,
+            RenderPassCreateInfo, SubpassDescription,
+        },
+        VulkanObject,
+    };
+    use std::sync::Arc;
+
+    #[test]
+    fn begin_render_pass_fragment_shading_rate_attachment_access() {
+        let (device, queue) = gfx_dev_and_queue!(attachment_fragment_shading_rate);
+
+        let texel_size = device
+            .physical_device()
+            .properties()
+            .min_fragment_shading_rate_attachment_texel_size
+            .unwrap();
+
+        let render_pass = RenderPass::new(
+            device.clone(),
+            RenderPassCreateInfo {
+                attachments: vec![
+                    AttachmentDescription {
+                        format: Some(Format::R8G8B8A8_UNORM),
+                        initial_layout: ImageLayout::ColorAttachmentOptimal,
+                        final_layout: ImageLayout::ColorAttachmentOptimal,
+                        ..Default::default()
+                    },
+                    AttachmentDescription {
+                        format: Some(Format::R8_UINT),
+                        initial_layout: ImageLayout::FragmentShadingRateAttachmentOptimal,
+                        final_layout: ImageLayout::FragmentShadingRateAttachmentOptimal,
+                        ..Default::default()
+                    },
+                ],
+                subpasses: vec![SubpassDescription {
+                    color_attachments: vec![Some(AttachmentReference {
+                        attachment: 0,
+                        layout: ImageLayout::ColorAttachmentOptimal,
+                        ..Default::default()
+                    })],
+                    fragment_shading_rate_attachment: Some(AttachmentReference {
+                        attachment: 1,
+                        layout: ImageLayout::FragmentShadingRateAttachmentOptimal,
+                        ..Default::default()
+                    }),
+                    fragment_shading_rate_attachment_texel_size: texel_size,
+                    ..Default::default()
+                }],
+                ..Default::default()
+            },
+        )
+        .unwrap();
+
+        let color_image =
+            AttachmentImage::new(device.clone(), [64, 64], Format::R8G8B8A8_UNORM).unwrap();
+        let shading_rate_image = match AttachmentImage::with_usage(
+            device.clone(),
+            [64, 64],
+            Format::R8_UINT,
+            ImageUsage {
+                fragment_shading_rate_attachment: true,
+                ..ImageUsage::empty()
+            },
+        ) {
+            Ok(image) => image,
+            Err(_) => return, // test ignored
+        };
+
+        let framebuffer = Framebuffer::new(
+            render_pass,
+            FramebufferCreateInfo {
+                attachments: vec![
+                    ImageView::new_default(color_image.clone()).unwrap(),
+                    ImageView::new_default(shading_rate_image.clone()).unwrap(),
+                ],
+                ..Default::default()
+            },
+        )
+        .unwrap();
+
+        let command_buffer = unsafe {
+            let pool_builder_alloc = device
+                .with_standard_command_pool(queue.queue_family_index(), |pool| {
+                    pool.allocate(CommandBufferLevel::Primary, 1)
+                        .unwrap()
+                        .next()
+                        .unwrap()
+                })
+                .unwrap();
+            let mut builder = SyncCommandBufferBuilder::new(
+                pool_builder_alloc.inner(),
+                CommandBufferBeginInfo {
+                    usage: CommandBufferUsage::OneTimeSubmit,
+                    ..Default::default()
+                },
+            )
+            .unwrap();
+            builder
+                .begin_render_pass(
+                    RenderPassBeginInfo {
+                        clear_values: vec![None, None],
+                        ..RenderPassBeginInfo::framebuffer(framebuffer)
+                    },
+                    SubpassContents::Inline,
+                )
+                .unwrap();
+            builder.end_render_pass();
+            builder.build().unwrap()
+        };
+
+        // The shading rate image is read in the fragment shading rate attachment stage, so that
+        // earlier writes to it are made visible to that stage. The color image isn't.
+        for index in 0.. {
+            let (image, _, memory, _, _) = match command_buffer.image(index) {
+                Some(image) => image,
+                None => break,
+            };
+            let is_shading_rate_image = image.inner().image.internal_object()
+                == shading_rate_image.inner().image.internal_object();
+
+            assert_eq!(
+                memory.stages.fragment_shading_rate_attachment,
+                is_shading_rate_image
+            );
+            assert_eq!(
+                memory.access.fragment_shading_rate_attachment_read,
+                is_shading_rate_image
+            );
+        }
+    }
+
+    #[test]
+    fn begin_rendering_fragment_shading_rate_mip_level_extent() {
+        let (device, queue) =
+            gfx_dev_and_queue!(dynamic_rendering, attachment_fragment_shading_rate);
+
+        let texel_size = device
+            .physical_device()
+            .properties()
+            .min_fragment_shading_rate_attachment_texel_size
+            .unwrap();
+
+        // Level 0 covers a render area of 8 texels, level 1 only covers 4.
+        let image = match ImmutableImage::uninitialized(
+            device.clone(),
+            ImageDimensions::Dim2d {
+                width: 8,
+                height: 8,
+                array_layers: 1,
+            },
+            Format::R8_UINT,
+            2,
+            ImageUsage {
+                fragment_shading_rate_attachment: true,
+                ..ImageUsage::empty()
+            },
+            ImageCreateFlags::empty(),
+            ImageLayout::FragmentShadingRateAttachmentOptimal,
+            [queue.queue_family_index()],
+        ) {
+            Ok((image, _)) => image,
+            Err(_) => return, // test ignored
+        };
+        let mip_level_view = |level: u32| {
+            ImageView::new(
+                image.clone(),
+                ImageViewCreateInfo {
+                    subresource_range: ImageSubresourceRange {
+                        mip_levels: level..level + 1,
+                        ..image.subresource_range()
+                    },
+                    ..ImageViewCreateInfo::from_image(&image)
+                },
+            )
+            .unwrap()
+        };
+
+        let mut builder = AutoCommandBufferBuilder::primary(
+            device,
+            queue.queue_family_index(),
+            CommandBufferUsage::OneTimeSubmit,
+        )
+        .unwrap();
+        let rendering_info = |image_view: Arc<dyn ImageViewAbstract>| RenderingInfo {
+            render_area_extent: [8 * texel_size[0], 8 * texel_size[1]],
+            layer_count: 1,
+            fragment_shading_rate_attachment: Some(
+                RenderingFragmentShadingRateAttachmentInfo::image_view(image_view),
+            ),
+            ..Default::default()
+        };
+
+        assert!(matches!(
+            builder.begin_rendering(rendering_info(mip_level_view(1))),
+            Err(RenderPassError::RenderAreaOutOfBounds)
+        ));
+        builder
+            .begin_rendering(rendering_info(mip_level_view(0)))
+            .unwrap();
+    }
+
+    #[test]
+    fn begin_render_pass_imageless_mismatched_attachments() {
+        let (device, queue) = gfx_dev_and_queue!(imageless_framebuffer);
+
+        let render_pass = single_pass_renderpass!(device.clone(),
+            attachments: {
+                color: {
+                    load: DontCare,
+                    store: DontCare,
+                    format: Format::R8G8B8A8_UNORM,
+                    samples: 1,
+                }
+            },
+            pass: {
+                color: [color],
+                depth_stencil: {}
+            }
+        )
+        .unwrap();
+
+        let create_framebuffer = |view_formats| {
+            Framebuffer::new(
+                render_pass.clone(),
+                FramebufferCreateInfo {
+                    attachment_image_infos: vec![FramebufferAttachmentImageInfo {
+                        usage: ImageUsage {
+                            color_attachment: true,
+                            ..ImageUsage::empty()
+                        },
+                        extent: [64, 64],
+                        view_formats,
+                        ..Default::default()
+                    }],
+                    ..Default::default()
+                },
+            )
+            .unwrap()
+        };
+        let framebuffer = create_framebuffer(vec![Format::R8G8B8A8_UNORM]);
+        // Views of an image without `mutable_format` can't have another format.
+        let multi_format_framebuffer =
+            create_framebuffer(vec![Format::R8G8B8A8_UNORM, Format::R8G8B8A8_SRGB]);
+
+        let create_view = |extent, usage| -> Arc<dyn ImageViewAbstract> {
+            ImageView::new_default(
+                AttachmentImage::with_usage(device.clone(), extent, Format::R8G8B8A8_UNORM, usage)
+                    .unwrap(),
+            )
+            .unwrap()
+        };
+        let render_pass_begin_info =
+            |framebuffer: &Arc<Framebuffer>, attachments| RenderPassBeginInfo {
+                attachments,
+                ..RenderPassBeginInfo::framebuffer(framebuffer.clone())
+            };
+
+        let mut builder = AutoCommandBufferBuilder::primary(
+            device.clone(),
+            queue.queue_family_index(),
+            CommandBufferUsage::OneTimeSubmit,
+        )
+        .unwrap();
+
+        assert!(matches!(
+            builder.begin_render_pass(
+                render_pass_begin_info(&framebuffer, vec![]),
+                SubpassContents::Inline,
+            ),
+            Err(RenderPassError::AttachmentCountMismatch {
+                provided: 0,
+                required: 1,
+            })
+        ));
+        assert!(matches!(
+            builder.begin_render_pass(
+                render_pass_begin_info(
+                    &framebuffer,
+                    vec![create_view([32, 32], ImageUsage::empty())],
+                ),
+                SubpassContents::Inline,
+            ),
+            Err(RenderPassError::AttachmentImageNotCompatible {
+                attachment_index: 0
+            })
+        ));
+        assert!(matches!(
+            builder.begin_render_pass(
+                render_pass_begin_info(
+                    &framebuffer,
+                    vec![create_view(
+                        [64, 64],
+                        ImageUsage {
+                            transfer_src: true,
+                            ..ImageUsage::empty()
+                        },
+                    )],
+                ),
+                SubpassContents::Inline,
+            ),
+            Err(RenderPassError::AttachmentImageNotCompatible {
+                attachment_index: 0
+            })
+        ));
+        assert!(matches!(
+            builder.begin_render_pass(
+                render_pass_begin_info(
+                    &multi_format_framebuffer,
+                    vec![create_view([64, 64], ImageUsage::empty())],
+                ),
+                SubpassContents::Inline,
+            ),
+            Err(RenderPassError::AttachmentImageNotCompatible {
+                attachment_index: 0
+            })
+        ));
+
+        builder
+            .begin_render_pass(
+                render_pass_begin_info(
+                    &framebuffer,
+                    vec![create_view([64, 64], ImageUsage::empty())],
+                ),
+                SubpassContents::Inline,
+            )
+            .unwrap();
+    }
+}
@@ -69,6 +69,9 @@ where
 
     /// Executes multiple secondary command buffers in a vector.
     ///
+    /// To record the secondary command buffers on multiple threads, see
+    /// [`ParallelRecorder`](crate::command_buffer::ParallelRecorder).
+    ///
     /// This requires that the secondary command buffers do not have resource conflicts; an error
     /// will be returned if there are any. Use `execute_commands` if you want to ensure that
     /// resource conflicts are automatically resolved.
@@ -126,6 +129,22 @@ where
             return Err(ExecuteCommandsError::NotSupportedByQueueFamily);
         }
 
+        // VUID-vkCmdExecuteCommands-None-02286
+        if self.transform_feedback_active {
+            return Err(ExecuteCommandsError::TransformFeedbackActive);
+        }
+
+        // VUID-vkCmdExecuteCommands-commandBuffer-00101
+        if self.conditional_rendering_state.is_some()
+            && !command_buffer.inheritance_info().conditional_rendering
+        {
+            return Err(
+                ExecuteCommandsError::ConditionalRenderingInheritanceRequired {
+                    command_buffer_index,
+                },
+            );
+        }
+
         // TODO:
         // VUID-vkCmdExecuteCommands-pCommandBuffers-00094
 
@@ -372,7 +391,7 @@ where
                         );
                     }
                 }
-                QueryType::Timestamp => (),
+                QueryType::Timestamp | QueryType::TransformFeedbackStream => (),
             }
         }
 
@@ -564,6 +583,12 @@ pub enum ExecuteCommandsError {
         requires_one_of: RequiresOneOf,
     },
 
+    /// Conditional rendering is active, but a command buffer does not have conditional rendering
+    /// enabled in its inheritance info.
+    ConditionalRenderingInheritanceRequired {
+        command_buffer_index: u32,
+    },
+
     /// Operation forbidden inside a render subpass with the specified contents.
     ForbiddenWithSubpassContents {
         contents: SubpassContents,
@@ -691,6 +716,9 @@ pub enum ExecuteCommandsError {
         required_view_mask: u32,
         inherited_view_mask: u32,
     },
+
+    /// Transform feedback is active.
+    TransformFeedbackActive,
 }
 
 impl Error for ExecuteCommandsError {
@@ -714,6 +742,14 @@ impl Display for ExecuteCommandsError {
                 "a requirement was not met for: {}; requires one of: {}",
                 required_for, requires_one_of,
             ),
+            Self::ConditionalRenderingInheritanceRequired {
+                command_buffer_index,
+            } => write!(
+                f,
+                "conditional rendering is active, but command buffer {} does not have conditional \
+                rendering enabled in its inheritance info",
+                command_buffer_index,
+            ),
             Self::ForbiddenWithSubpassContents {
                 contents: subpass_contents,
             } => write!(
@@ -883,6 +919,7 @@ impl Display for ExecuteCommandsError {
                 mask ({})",
                 inherited_view_mask, command_buffer_index, required_view_mask,
             ),
+            Self::TransformFeedbackActive => write!(f, "transform feedback is active"),
         }
     }
 }
@@ -8,19 +8,137 @@
 // according to those terms.
 
 use crate::{
+    buffer::BufferAccess,
     command_buffer::{
-        synced::{Command, SyncCommandBufferBuilder},
+        synced::{Command, Resource, SyncCommandBufferBuilder, SyncCommandBufferBuilderError},
         sys::UnsafeCommandBufferBuilder,
+        AutoCommandBufferBuilder,
     },
-    image::ImageLayout,
+    device::DeviceOwned,
+    image::{ImageAccess, ImageLayout},
     sync::{
-        BufferMemoryBarrier, DependencyInfo, Event, ImageMemoryBarrier, MemoryBarrier,
-        PipelineStages,
+        AccessFlags, BufferMemoryBarrier, DependencyInfo, Event, ImageMemoryBarrier, MemoryBarrier,
+        PipelineMemoryAccess, PipelineStages, QueueFamilyTransfer, Sharing,
     },
     Version, VulkanObject,
 };
-use smallvec::SmallVec;
-use std::sync::Arc;
+use smallvec::{smallvec, SmallVec};
+use std::{
+    error::Error,
+    fmt::{Display, Error as FmtError, Formatter},
+    sync::Arc,
+};
+
+/// # Commands to transfer queue family ownership.
+///
+/// A resource created with [`Sharing::Exclusive`] can only be accessed by one queue family at a
+/// time. To use it on another queue family without discarding its contents, the owning queue
+/// family must release it, and the other queue family must then acquire it with the same
+/// [`QueueFamilyTransfer`].
+impl<L, P> AutoCommandBufferBuilder<L, P> {
+    /// Adds a queue family ownership transfer of `buffer` to the command buffer.
+    ///
+    /// If `transfer.source_index` is the queue family of the command buffer, this releases
+    /// ownership of `buffer`. If `transfer.destination_index` is the queue family of the command
+    /// buffer, this acquires it.
+    ///
+    /// # Safety
+    ///
+    /// - A release must be matched by an acquire with the same `transfer`, in a command buffer
+    ///   that is submitted to a queue of the destination queue family after the release has
+    ///   been submitted, and that waits for it with a semaphore.
+    /// - After it has been released, `buffer` must not be used in the rest of the command buffer.
+    pub unsafe fn transfer_buffer_ownership(
+        &mut self,
+        buffer: Arc<dyn BufferAccess>,
+        transfer: QueueFamilyTransfer,
+    ) -> Result<&mut Self, OwnershipTransferError> {
+        self.validate_transfer_ownership(buffer.inner().buffer.sharing(), transfer)?;
+
+        let release = transfer.source_index == self.queue_family_index;
+        self.inner
+            .transfer_buffer_ownership(buffer, transfer, release)?;
+
+        Ok(self)
+    }
+
+    /// Adds a queue family ownership transfer of `image` to the command buffer.
+    ///
+    /// If `transfer.source_index` is the queue family of the command buffer, this releases
+    /// ownership of `image`. If `transfer.destination_index` is the queue family of the command
+    /// buffer, this acquires it. The image is kept in its
+    /// [`final_layout_requirement`](ImageAccess::final_layout_requirement) layout.
+    ///
+    /// # Safety
+    ///
+    /// - A release must be matched by an acquire with the same `transfer`, in a command buffer
+    ///   that is submitted to a queue of the destination queue family after the release has
+    ///   been submitted, and that waits for it with a semaphore.
+    /// - After it has been released, `image` must not be used in the rest of the command buffer.
+    pub unsafe fn transfer_image_ownership(
+        &mut self,
+        image: Arc<dyn ImageAccess>,
+        transfer: QueueFamilyTransfer,
+    ) -> Result<&mut Self, OwnershipTransferError> {
+        self.validate_transfer_ownership(image.inner().image.sharing(), transfer)?;
+
+        let release = transfer.source_index == self.queue_family_index;
+        self.inner
+            .transfer_image_ownership(image, transfer, release)?;
+
+        Ok(self)
+    }
+
+    fn validate_transfer_ownership(
+        &self,
+        sharing: &Sharing<SmallVec<[u32; 4]>>,
+        transfer: QueueFamilyTransfer,
+    ) -> Result<(), OwnershipTransferError> {
+        // The barriers of a render pass can't transfer ownership.
+        if self.render_pass_state.is_some() {
+            return Err(OwnershipTransferError::ForbiddenInsideRenderPass);
+        }
+
+        let queue_family_count = self
+            .device()
+            .physical_device()
+            .queue_family_properties()
+            .len() as u32;
+
+        // VUID-VkBufferMemoryBarrier2-buffer-04089
+        // VUID-VkImageMemoryBarrier2-image-04072
+        if transfer.source_index >= queue_family_count
+            || transfer.destination_index >= queue_family_count
+        {
+            return Err(OwnershipTransferError::QueueFamilyIndexOutOfRange {
+                source_index: transfer.source_index,
+                destination_index: transfer.destination_index,
+                queue_family_count,
+            });
+        }
+
+        // VUID-vkCmdPipelineBarrier2-srcQueueFamilyIndex-02803
+        // VUID-vkCmdPipelineBarrier2-dstQueueFamilyIndex-02804
+        if transfer.source_index == transfer.destination_index
+            || (transfer.source_index != self.queue_family_index
+                && transfer.destination_index != self.queue_family_index)
+        {
+            return Err(OwnershipTransferError::QueueFamilyMismatch {
+                source_index: transfer.source_index,
+                destination_index: transfer.destination_index,
+                queue_family_index: self.queue_family_index,
+            });
+        }
+
+        // VUID-VkBufferMemoryBarrier2-buffer-04088
+        // VUID-VkImageMemoryBarrier2-image-04071
+        if !matches!(sharing, Sharing::Exclusive) {
+            return Err(OwnershipTransferError::NotExclusive);
+        }
+
+        Ok(())
+    }
+}
 
 impl SyncCommandBufferBuilder {
     /// Calls `vkCmdSetEvent` on the builder.
@@ -64,6 +182,189 @@ impl SyncCommandBufferBuilder {
 
         self.commands.push(Box::new(Cmd { event, stages }));
     }
+
+    /// Calls `vkCmdPipelineBarrier` on the builder, with a queue family ownership transfer of
+    /// `buffer`.
+    ///
+    /// If `release` is true, the barrier releases ownership, otherwise it acquires it.
+    pub unsafe fn transfer_buffer_ownership(
+        &mut self,
+        buffer: Arc<dyn BufferAccess>,
+        transfer: QueueFamilyTransfer,
+        release: bool,
+    ) -> Result<(), SyncCommandBufferBuilderError> {
+        struct Cmd {
+            buffer: Arc<dyn BufferAccess>,
+            transfer: QueueFamilyTransfer,
+            release: bool,
+        }
+
+        impl Command for Cmd {
+            fn name(&self) -> &'static str {
+                "transfer_buffer_ownership"
+            }
+
+            unsafe fn send(&self, out: &mut UnsafeCommandBufferBuilder) {
+                let (stages, access) = ownership_transfer_scope();
+                let inner = self.buffer.inner();
+                let mut barrier = BufferMemoryBarrier {
+                    queue_family_transfer: Some(self.transfer),
+                    range: inner.offset..inner.offset + self.buffer.size(),
+                    ..BufferMemoryBarrier::buffer(inner.buffer.clone())
+                };
+
+                // The destination scope of a release and the source scope of an acquire are
+                // ignored.
+                if self.release {
+                    barrier.source_stages = stages;
+                    barrier.source_access = access;
+                } else {
+                    barrier.destination_stages = stages;
+                    barrier.destination_access = access;
+                }
+
+                out.pipeline_barrier(&DependencyInfo {
+                    buffer_memory_barriers: smallvec![barrier],
+                    ..Default::default()
+                });
+            }
+        }
+
+        let (stages, access) = ownership_transfer_scope();
+        let resources = [(
+            "buffer".into(),
+            Resource::Buffer {
+                buffer: buffer.clone(),
+                range: 0..buffer.size(),
+                memory: PipelineMemoryAccess {
+                    stages,
+                    access,
+                    exclusive: true,
+                },
+            },
+        )];
+
+        for resource in &resources {
+            self.check_resource_conflicts(resource)?;
+        }
+
+        self.commands.push(Box::new(Cmd {
+            buffer,
+            transfer,
+            release,
+        }));
+
+        for resource in resources {
+            self.add_resource(resource);
+        }
+
+        Ok(())
+    }
+
+    /// Calls `vkCmdPipelineBarrier` on the builder, with a queue family ownership transfer of
+    /// `image`.
+    ///
+    /// If `release` is true, the barrier releases ownership, otherwise it acquires it.
+    pub unsafe fn transfer_image_ownership(
+        &mut self,
+        image: Arc<dyn ImageAccess>,
+        transfer: QueueFamilyTransfer,
+        release: bool,
+    ) -> Result<(), SyncCommandBufferBuilderError> {
+        struct Cmd {
+            image: Arc<dyn ImageAccess>,
+            transfer: QueueFamilyTransfer,
+            release: bool,
+        }
+
+        impl Command for Cmd {
+            fn name(&self) -> &'static str {
+                "transfer_image_ownership"
+            }
+
+            unsafe fn send(&self, out: &mut UnsafeCommandBufferBuilder) {
+                let (stages, access) = ownership_transfer_scope();
+                let inner = self.image.inner();
+                let layout = self.image.final_layout_requirement();
+                let mut subresource_range = self.image.subresource_range();
+                subresource_range.array_layers.start += inner.first_layer;
+                subresource_range.array_layers.end += inner.first_layer;
+                subresource_range.mip_levels.start += inner.first_mipmap_level;
+                subresource_range.mip_levels.end += inner.first_mipmap_level;
+
+                let mut barrier = ImageMemoryBarrier {
+                    old_layout: layout,
+                    new_layout: layout,
+                    queue_family_transfer: Some(self.transfer),
+                    subresource_range,
+                    ..ImageMemoryBarrier::image(inner.image.clone())
+                };
+
+                // The destination scope of a release and the source scope of an acquire are
+                // ignored.
+                if self.release {
+                    barrier.source_stages = stages;
+                    barrier.source_access = access;
+                } else {
+                    barrier.destination_stages = stages;
+                    barrier.destination_access = access;
+                }
+
+                out.pipeline_barrier(&DependencyInfo {
+                    image_memory_barriers: smallvec![barrier],
+                    ..Default::default()
+                });
+            }
+        }
+
+        let (stages, access) = ownership_transfer_scope();
+        let layout = image.final_layout_requirement();
+        let resources = [(
+            "image".into(),
+            Resource::Image {
+                image: image.clone(),
+                subresource_range: image.subresource_range(),
+                memory: PipelineMemoryAccess {
+                    stages,
+                    access,
+                    exclusive: true,
+                },
+                start_layout: layout,
+                end_layout: layout,
+            },
+        )];
+
+        for resource in &resources {
+            self.check_resource_conflicts(resource)?;
+        }
+
+        self.commands.push(Box::new(Cmd {
+            image,
+            transfer,
+            release,
+        }));
+
+        for resource in resources {
+            self.add_resource(resource);
+        }
+
+        Ok(())
+    }
+}
+
+/// Returns the stages and accesses that an ownership transfer barrier synchronizes with.
+fn ownership_transfer_scope() -> (PipelineStages, AccessFlags) {
+    (
+        PipelineStages {
+            all_commands: true,
+            ..PipelineStages::empty()
+        },
+        AccessFlags {
+            memory_read: true,
+            memory_write: true,
+            ..AccessFlags::empty()
+        },
+    )
 }
 
 impl UnsafeCommandBufferBuilder {
@@ -408,3 +709,78 @@ impl UnsafeCommandBufferBuilder {
         (fns.v1_0.cmd_reset_event)(self.handle, event.internal_object(), stages.into());
     }
 }
+
+/// Error that can happen when recording a queue family ownership transfer.
+#[derive(Clone, Debug)]
+pub enum OwnershipTransferError {
+    SyncCommandBufferBuilderError(SyncCommandBufferBuilderError),
+
+    /// Operation forbidden inside of a render pass.
+    ForbiddenInsideRenderPass,
+
+    /// The resource wasn't created with exclusive sharing, so it has no owning queue family.
+    NotExclusive,
+
+    /// The source and destination queue families of the transfer are the same, or neither of
+    /// them is the queue family of the command buffer.
+    QueueFamilyMismatch {
+        source_index: u32,
+        destination_index: u32,
+        queue_family_index: u32,
+    },
+
+    /// A queue family index of the transfer is not less than the number of queue families of the
+    /// physical device.
+    QueueFamilyIndexOutOfRange {
+        source_index: u32,
+        destination_index: u32,
+        queue_family_count: u32,
+    },
+}
+
+impl Error for OwnershipTransferError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            Self::SyncCommandBufferBuilderError(err) => Some(err),
+            _ => None,
+        }
+    }
+}
+
+impl Display for OwnershipTransferError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result<(), FmtError> {
+        match self {
+            Self::SyncCommandBufferBuilderError(_) => write!(f, "a SyncCommandBufferBuilderError"),
+            Self::ForbiddenInsideRenderPass => {
+                write!(f, "operation forbidden inside of a render pass")
+            }
+            Self::NotExclusive => write!(f, "the resource wasn't created with exclusive sharing",),
+            Self::QueueFamilyMismatch {
+                source_index,
+                destination_index,
+                queue_family_index,
+            } => write!(
+                f,
+                "the transfer from queue family {} to queue family {} can't be recorded in a \
+                command buffer of queue family {}",
+                source_index, destination_index, queue_family_index,
+            ),
+            Self::QueueFamilyIndexOutOfRange {
+                source_index,
+                destination_index,
+                queue_family_count,
+            } => write!(
+                f,
+                "the transfer from queue family {} to queue family {} is out of range of the {} \
+                queue families of the physical device",
+                source_index, destination_index, queue_family_count,
+            ),
+        }
+    }
+}
+
+impl From<SyncCommandBufferBuilderError> for OwnershipTransferError {
+    fn from(err: SyncCommandBufferBuilderError) -> Self {
+        Self::SyncCommandBufferBuilderError(err)
+    }
+}
@@ -0,0 +1,853 @@
+// Copyright (c) 2022 The vulkano developers
+// Licensed under the Apache License, Version 2.0
+// <LICENSE-APACHE or
+// https://www.apache.org/licenses/LICENSE-2.0> or the MIT
+// license <LICENSE-MIT or https://opensource.org/licenses/MIT>,
+// at your option. All files in the project carrying such
+// notice may not be copied, modified, or distributed except
+// according to those terms.
+
+use crate::{
+    buffer::BufferAccess,
+    command_buffer::{
+        synced::{Command, Resource, SyncCommandBufferBuilder, SyncCommandBufferBuilderError},
+        sys::UnsafeCommandBufferBuilder,
+        AutoCommandBufferBuilder,
+    },
+    device::DeviceOwned,
+    sync::{AccessFlags, PipelineMemoryAccess, PipelineStages},
+    DeviceSize, RequiresOneOf, VulkanObject,
+};
+use smallvec::SmallVec;
+use std::{
+    borrow::Cow,
+    error::Error,
+    fmt::{Display, Error as FmtError, Formatter},
+    ptr,
+    sync::Arc,
+};
+
+/// # Commands for transform feedback.
+///
+/// These commands require the
+/// [`transform_feedback`](crate::device::Features::transform_feedback) feature to be enabled on
+/// the device.
+impl<L, P> AutoCommandBufferBuilder<L, P> {
+    /// Binds buffers to the transform feedback bindings, starting at `first_binding`.
+    ///
+    /// While transform feedback is active, the vertices that are output by the last
+    /// pre-rasterization shader stage are written to these buffers.
+    ///
+    /// # Panics
+    ///
+    /// - Panics if `buffers` is empty.
+    pub fn bind_transform_feedback_buffers(
+        &mut self,
+        first_binding: u32,
+        buffers: impl IntoIterator<Item = Arc<dyn BufferAccess>>,
+    ) -> Result<&mut Self, TransformFeedbackError> {
+        let buffers: SmallVec<[_; 4]> = buffers.into_iter().collect();
+        self.validate_bind_transform_feedback_buffers(first_binding, &buffers)?;
+
+        unsafe {
+            self.inner
+                .bind_transform_feedback_buffers(first_binding, buffers);
+        }
+
+        Ok(self)
+    }
+
+    fn validate_bind_transform_feedback_buffers(
+        &self,
+        first_binding: u32,
+        buffers: &[Arc<dyn BufferAccess>],
+    ) -> Result<(), TransformFeedbackError> {
+        let device = self.device();
+
+        // VUID-vkCmdBindTransformFeedbackBuffersEXT-transformFeedback-02355
+        if !device.enabled_features().transform_feedback {
+            return Err(TransformFeedbackError::RequirementNotMet {
+                required_for: "`bind_transform_feedback_buffers`",
+                requires_one_of: RequiresOneOf {
+                    features: &["transform_feedback"],
+                    ..Default::default()
+                },
+            });
+        }
+
+        let queue_family_properties = self.queue_family_properties();
+
+        // VUID-vkCmdBindTransformFeedbackBuffersEXT-commandBuffer-cmdpool
+        if !queue_family_properties.queue_flags.graphics {
+            return Err(TransformFeedbackError::NotSupportedByQueueFamily);
+        }
+
+        // VUID-vkCmdBindTransformFeedbackBuffersEXT-None-02365
+        if self.transform_feedback_active {
+            return Err(TransformFeedbackError::TransformFeedbackActive);
+        }
+
+        // VUID-vkCmdBindTransformFeedbackBuffersEXT-bindingCount-arraylength
+        assert!(!buffers.is_empty());
+
+        let properties = device.physical_device().properties();
+        let max_transform_feedback_buffers = properties.max_transform_feedback_buffers.unwrap_or(0);
+
+        // VUID-vkCmdBindTransformFeedbackBuffersEXT-firstBinding-02356
+        // VUID-vkCmdBindTransformFeedbackBuffersEXT-firstBinding-02357
+        if first_binding + buffers.len() as u32 > max_transform_feedback_buffers {
+            return Err(
+                TransformFeedbackError::MaxTransformFeedbackBuffersExceeded {
+                    binding_count: first_binding + buffers.len() as u32,
+                    max: max_transform_feedback_buffers,
+                },
+            );
+        }
+
+        let max_transform_feedback_buffer_size =
+            properties.max_transform_feedback_buffer_size.unwrap_or(0);
+
+        for (buffer_index, buffer) in buffers.iter().enumerate() {
+            let buffer_index = buffer_index as u32;
+
+            // VUID-vkCmdBindTransformFeedbackBuffersEXT-commonparent
+            assert_eq!(device, buffer.device());
+
+            let inner = buffer.inner();
+
+            // VUID-vkCmdBindTransformFeedbackBuffersEXT-pBuffers-02360
+            if !inner.buffer.usage().transform_feedback_buffer {
+                return Err(TransformFeedbackError::BufferMissingUsage { buffer_index });
+            }
+
+            // VUID-vkCmdBindTransformFeedbackBuffersEXT-pOffsets-02359
+            if inner.offset % 4 != 0 {
+                return Err(TransformFeedbackError::BufferOffsetNotAligned {
+                    buffer_index,
+                    offset: inner.offset,
+                });
+            }
+
+            // VUID-vkCmdBindTransformFeedbackBuffersEXT-pSize-02362
+            if buffer.size() > max_transform_feedback_buffer_size {
+                return Err(TransformFeedbackError::BufferSizeExceedsLimit {
+                    buffer_index,
+                    size: buffer.size(),
+                    max: max_transform_feedback_buffer_size,
+                });
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Begins transform feedback, using the buffers that are currently bound with
+    /// [`bind_transform_feedback_buffers`](Self::bind_transform_feedback_buffers).
+    ///
+    /// Each element of `counter_buffers` corresponds to a transform feedback binding, starting at
+    /// `first_counter_buffer`. If a counter buffer is provided, the byte offset in the transform
+    /// feedback buffer to resume writing at is read from the first 4 bytes of the counter buffer.
+    /// If `None` is provided, writing starts at the beginning of the transform feedback buffer.
+    ///
+    /// This command must be recorded inside a render pass, and transform feedback must be ended
+    /// with [`end_transform_feedback`](Self::end_transform_feedback) before the subpass or render
+    /// pass is ended.
+    pub fn begin_transform_feedback(
+        &mut self,
+        first_counter_buffer: u32,
+        counter_buffers: impl IntoIterator<Item = Option<Arc<dyn BufferAccess>>>,
+    ) -> Result<&mut Self, TransformFeedbackError> {
+        let counter_buffers: SmallVec<[_; 4]> = counter_buffers.into_iter().collect();
+        self.validate_begin_transform_feedback(first_counter_buffer, &counter_buffers)?;
+
+        unsafe {
+            self.inner
+                .begin_transform_feedback(first_counter_buffer, counter_buffers)?;
+        }
+
+        self.transform_feedback_active = true;
+
+        Ok(self)
+    }
+
+    fn validate_begin_transform_feedback(
+        &self,
+        first_counter_buffer: u32,
+        counter_buffers: &[Option<Arc<dyn BufferAccess>>],
+    ) -> Result<(), TransformFeedbackError> {
+        let device = self.device();
+
+        // VUID-vkCmdBeginTransformFeedbackEXT-transformFeedback-02366
+        if !device.enabled_features().transform_feedback {
+            return Err(TransformFeedbackError::RequirementNotMet {
+                required_for: "`begin_transform_feedback`",
+                requires_one_of: RequiresOneOf {
+                    features: &["transform_feedback"],
+                    ..Default::default()
+                },
+            });
+        }
+
+        let queue_family_properties = self.queue_family_properties();
+
+        // VUID-vkCmdBeginTransformFeedbackEXT-commandBuffer-cmdpool
+        if !queue_family_properties.queue_flags.graphics {
+            return Err(TransformFeedbackError::NotSupportedByQueueFamily);
+        }
+
+        // VUID-vkCmdBeginTransformFeedbackEXT-renderpass
+        let render_pass_state = self
+            .render_pass_state
+            .as_ref()
+            .ok_or(TransformFeedbackError::ForbiddenOutsideRenderPass)?;
+
+        // VUID-vkCmdBeginTransformFeedbackEXT-None-02367
+        if self.transform_feedback_active {
+            return Err(TransformFeedbackError::TransformFeedbackActive);
+        }
+
+        // VUID-vkCmdBeginTransformFeedbackEXT-None-02373
+        if render_pass_state.view_mask != 0 {
+            return Err(TransformFeedbackError::MultiviewActive);
+        }
+
+        self.validate_counter_buffers(first_counter_buffer, counter_buffers)?;
+
+        // VUID-vkCmdBeginTransformFeedbackEXT-None-04128
+        let pipeline = self
+            .state()
+            .pipeline_graphics()
+            .ok_or(TransformFeedbackError::PipelineNotBound)?;
+
+        if !pipeline.transform_feedback() {
+            return Err(TransformFeedbackError::PipelineNoTransformFeedback);
+        }
+
+        // TODO:
+        // VUID-vkCmdBeginTransformFeedbackEXT-None-06233
+
+        Ok(())
+    }
+
+    /// Ends transform feedback that was previously begun with
+    /// [`begin_transform_feedback`](Self::begin_transform_feedback).
+    ///
+    /// Each element of `counter_buffers` corresponds to a transform feedback binding, starting at
+    /// `first_counter_buffer`. If a counter buffer is provided, the byte offset in the transform
+    /// feedback buffer that was written up to is stored in the first 4 bytes of the counter
+    /// buffer, so that it can be passed to a later `begin_transform_feedback` or
+    /// [`draw_indirect_byte_count`](Self::draw_indirect_byte_count) command.
+    pub fn end_transform_feedback(
+        &mut self,
+        first_counter_buffer: u32,
+        counter_buffers: impl IntoIterator<Item = Option<Arc<dyn BufferAccess>>>,
+    ) -> Result<&mut Self, TransformFeedbackError> {
+        let counter_buffers: SmallVec<[_; 4]> = counter_buffers.into_iter().collect();
+        self.validate_end_transform_feedback(first_counter_buffer, &counter_buffers)?;
+
+        unsafe {
+            self.inner
+                .end_transform_feedback(first_counter_buffer, counter_buffers)?;
+        }
+
+        self.transform_feedback_active = false;
+
+        Ok(self)
+    }
+
+    fn validate_end_transform_feedback(
+        &self,
+        first_counter_buffer: u32,
+        counter_buffers: &[Option<Arc<dyn BufferAccess>>],
+    ) -> Result<(), TransformFeedbackError> {
+        let device = self.device();
+
+        // VUID-vkCmdEndTransformFeedbackEXT-transformFeedback-02374
+        if !device.enabled_features().transform_feedback {
+            return Err(TransformFeedbackError::RequirementNotMet {
+                required_for: "`end_transform_feedback`",
+                requires_one_of: RequiresOneOf {
+                    features: &["transform_feedback"],
+                    ..Default::default()
+                },
+            });
+        }
+
+        let queue_family_properties = self.queue_family_properties();
+
+        // VUID-vkCmdEndTransformFeedbackEXT-commandBuffer-cmdpool
+        if !queue_family_properties.queue_flags.graphics {
+            return Err(TransformFeedbackError::NotSupportedByQueueFamily);
+        }
+
+        // VUID-vkCmdEndTransformFeedbackEXT-renderpass
+        if self.render_pass_state.is_none() {
+            return Err(TransformFeedbackError::ForbiddenOutsideRenderPass);
+        }
+
+        // VUID-vkCmdEndTransformFeedbackEXT-None-02375
+        if !self.transform_feedback_active {
+            return Err(TransformFeedbackError::TransformFeedbackNotActive);
+        }
+
+        self.validate_counter_buffers(first_counter_buffer, counter_buffers)?;
+
+        Ok(())
+    }
+
+    fn validate_counter_buffers(
+        &self,
+        first_counter_buffer: u32,
+        counter_buffers: &[Option<Arc<dyn BufferAccess>>],
+    ) -> Result<(), TransformFeedbackError> {
+        let device = self.device();
+        let max_transform_feedback_buffers = device
+            .physical_device()
+            .properties()
+            .max_transform_feedback_buffers
+            .unwrap_or(0);
+
+        // VUID-vkCmdBeginTransformFeedbackEXT-firstCounterBuffer-02368
+        // VUID-vkCmdBeginTransformFeedbackEXT-firstCounterBuffer-02369
+        // VUID-vkCmdEndTransformFeedbackEXT-firstCounterBuffer-02376
+        // VUID-vkCmdEndTransformFeedbackEXT-firstCounterBuffer-02377
+        if first_counter_buffer + counter_buffers.len() as u32 > max_transform_feedback_buffers {
+            return Err(
+                TransformFeedbackError::MaxTransformFeedbackBuffersExceeded {
+                    binding_count: first_counter_buffer + counter_buffers.len() as u32,
+                    max: max_transform_feedback_buffers,
+                },
+            );
+        }
+
+        for (counter_buffer_index, counter_buffer) in counter_buffers.iter().enumerate() {
+            let counter_buffer = match counter_buffer {
+                Some(x) => x,
+                None => continue,
+            };
+            let counter_buffer_index = counter_buffer_index as u32;
+
+            // VUID-vkCmdBeginTransformFeedbackEXT-commonparent
+            // VUID-vkCmdEndTransformFeedbackEXT-commonparent
+            assert_eq!(device, counter_buffer.device());
+
+            let inner = counter_buffer.inner();
+
+            // VUID-vkCmdBeginTransformFeedbackEXT-pCounterBuffers-02372
+            // VUID-vkCmdEndTransformFeedbackEXT-pCounterBuffers-02380
+            if !inner.buffer.usage().transform_feedback_counter_buffer {
+                return Err(TransformFeedbackError::CounterBufferMissingUsage {
+                    counter_buffer_index,
+                });
+            }
+
+            // VUID?
+            if inner.offset % 4 != 0 {
+                return Err(TransformFeedbackError::CounterBufferOffsetNotAligned {
+                    counter_buffer_index,
+                    offset: inner.offset,
+                });
+            }
+
+            // VUID-vkCmdBeginTransformFeedbackEXT-pCounterBufferOffsets-02370
+            // VUID-vkCmdEndTransformFeedbackEXT-pCounterBufferOffsets-02378
+            if counter_buffer.size() < 4 {
+                return Err(TransformFeedbackError::CounterBufferTooSmall {
+                    counter_buffer_index,
+                });
+            }
+        }
+
+        Ok(())
+    }
+}
+
+impl SyncCommandBufferBuilder {
+    /// Calls `vkCmdBindTransformFeedbackBuffersEXT` on the builder.
+    #[inline]
+    pub unsafe fn bind_transform_feedback_buffers(
+        &mut self,
+        first_binding: u32,
+        buffers: SmallVec<[Arc<dyn BufferAccess>; 4]>,
+    ) {
+        struct Cmd {
+            first_binding: u32,
+            buffers: SmallVec<[Arc<dyn BufferAccess>; 4]>,
+        }
+
+        impl Command for Cmd {
+            fn name(&self) -> &'static str {
+                "bind_transform_feedback_buffers"
+            }
+
+            unsafe fn send(&self, out: &mut UnsafeCommandBufferBuilder) {
+                out.bind_transform_feedback_buffers(
+                    self.first_binding,
+                    self.buffers.iter().map(|buffer| buffer.as_ref()),
+                );
+            }
+        }
+
+        for (i, buffer) in buffers.iter().enumerate() {
+            self.current_state
+                .transform_feedback_buffers
+                .insert(first_binding + i as u32, buffer.clone());
+        }
+
+        self.commands.push(Box::new(Cmd {
+            first_binding,
+            buffers,
+        }));
+    }
+
+    /// Calls `vkCmdBeginTransformFeedbackEXT` on the builder.
+    ///
+    /// The transform feedback buffers that are currently bound are added as resources that are
+    /// written by the command.
+    pub unsafe fn begin_transform_feedback(
+        &mut self,
+        first_counter_buffer: u32,
+        counter_buffers: SmallVec<[Option<Arc<dyn BufferAccess>>; 4]>,
+    ) -> Result<(), SyncCommandBufferBuilderError> {
+        struct Cmd {
+            first_counter_buffer: u32,
+            counter_buffers: SmallVec<[Option<Arc<dyn BufferAccess>>; 4]>,
+        }
+
+        impl Command for Cmd {
+            fn name(&self) -> &'static str {
+                "begin_transform_feedback"
+            }
+
+            unsafe fn send(&self, out: &mut UnsafeCommandBufferBuilder) {
+                out.begin_transform_feedback(
+                    self.first_counter_buffer,
+                    self.counter_buffers.iter().map(|buffer| buffer.as_deref()),
+                );
+            }
+        }
+
+        let mut resources: Vec<(Cow<'static, str>, Resource)> = Vec::new();
+
+        for (&binding, buffer) in &self.current_state.transform_feedback_buffers {
+            resources.push((
+                format!("transform feedback buffer {}", binding).into(),
+                Resource::Buffer {
+                    buffer: buffer.clone(),
+                    range: 0..buffer.size(),
+                    memory: PipelineMemoryAccess {
+                        stages: PipelineStages {
+                            transform_feedback: true,
+                            ..PipelineStages::empty()
+                        },
+                        access: AccessFlags {
+                            transform_feedback_write: true,
+                            ..AccessFlags::empty()
+                        },
+                        exclusive: true,
+                    },
+                },
+            ));
+        }
+
+        resources.extend(counter_buffers.iter().enumerate().filter_map(
+            |(index, counter_buffer)| {
+                counter_buffer.as_ref().map(|counter_buffer| {
+                    (
+                        format!("counter buffer {}", first_counter_buffer + index as u32).into(),
+                        Resource::Buffer {
+                            buffer: counter_buffer.clone(),
+                            range: 0..4,
+                            memory: PipelineMemoryAccess {
+                                stages: PipelineStages {
+                                    draw_indirect: true,
+                                    ..PipelineStages::empty()
+                                },
+                                access: AccessFlags {
+                                    transform_feedback_counter_read: true,
+                                    ..AccessFlags::empty()
+                                },
+                                exclusive: false,
+                            },
+                        },
+                    )
+                })
+            },
+        ));
+
+        for resource in &resources {
+            self.check_resource_conflicts(resource)?;
+        }
+
+        self.commands.push(Box::new(Cmd {
+            first_counter_buffer,
+            counter_buffers,
+        }));
+
+        for resource in resources {
+            self.add_resource(resource);
+        }
+
+        Ok(())
+    }
+
+    /// Calls `vkCmdEndTransformFeedbackEXT` on the builder.
+    pub unsafe fn end_transform_feedback(
+        &mut self,
+        first_counter_buffer: u32,
+        counter_buffers: SmallVec<[Option<Arc<dyn BufferAccess>>; 4]>,
+    ) -> Result<(), SyncCommandBufferBuilderError> {
+        struct Cmd {
+            first_counter_buffer: u32,
+            counter_buffers: SmallVec<[Option<Arc<dyn BufferAccess>>; 4]>,
+        }
+
+        impl Command for Cmd {
+            fn name(&self) -> &'static str {
+                "end_transform_feedback"
+            }
+
+            unsafe fn send(&self, out: &mut UnsafeCommandBufferBuilder) {
+                out.end_transform_feedback(
+                    self.first_counter_buffer,
+                    self.counter_buffers.iter().map(|buffer| buffer.as_deref()),
+                );
+            }
+        }
+
+        let resources: SmallVec<[_; 4]> = counter_buffers
+            .iter()
+            .enumerate()
+            .filter_map(|(index, counter_buffer)| {
+                counter_buffer.as_ref().map(|counter_buffer| {
+                    (
+                        Cow::from(format!(
+                            "counter buffer {}",
+                            first_counter_buffer + index as u32
+                        )),
+                        Resource::Buffer {
+                            buffer: counter_buffer.clone(),
+                            range: 0..4,
+                            memory: PipelineMemoryAccess {
+                                stages: PipelineStages {
+                                    transform_feedback: true,
+                                    ..PipelineStages::empty()
+                                },
+                                access: AccessFlags {
+                                    transform_feedback_counter_write: true,
+                                    ..AccessFlags::empty()
+                                },
+                                exclusive: true,
+                            },
+                        },
+                    )
+                })
+            })
+            .collect();
+
+        for resource in &resources {
+            self.check_resource_conflicts(resource)?;
+        }
+
+        self.commands.push(Box::new(Cmd {
+            first_counter_buffer,
+            counter_buffers,
+        }));
+
+        for resource in resources {
+            self.add_resource(resource);
+        }
+
+        Ok(())
+    }
+}
+
+impl UnsafeCommandBufferBuilder {
+    /// Calls `vkCmdBindTransformFeedbackBuffersEXT` on the builder.
+    #[inline]
+    pub unsafe fn bind_transform_feedback_buffers<'a>(
+        &mut self,
+        first_binding: u32,
+        buffers: impl IntoIterator<Item = &'a dyn BufferAccess>,
+    ) {
+        let mut buffers_vk: SmallVec<[_; 4]> = SmallVec::new();
+        let mut offsets_vk: SmallVec<[_; 4]> = SmallVec::new();
+        let mut sizes_vk: SmallVec<[_; 4]> = SmallVec::new();
+
+        for buffer in buffers {
+            let inner = buffer.inner();
+            debug_assert!(inner.buffer.usage().transform_feedback_buffer);
+
+            buffers_vk.push(inner.buffer.internal_object());
+            offsets_vk.push(inner.offset);
+            sizes_vk.push(buffer.size());
+        }
+
+        if buffers_vk.is_empty() {
+            return;
+        }
+
+        let fns = self.device.fns();
+        (fns.ext_transform_feedback
+            .cmd_bind_transform_feedback_buffers_ext)(
+            self.handle,
+            first_binding,
+            buffers_vk.len() as u32,
+            buffers_vk.as_ptr(),
+            offsets_vk.as_ptr(),
+            sizes_vk.as_ptr(),
+        );
+    }
+
+    /// Calls `vkCmdBeginTransformFeedbackEXT` on the builder.
+    #[inline]
+    pub unsafe fn begin_transform_feedback<'a>(
+        &mut self,
+        first_counter_buffer: u32,
+        counter_buffers: impl IntoIterator<Item = Option<&'a dyn BufferAccess>>,
+    ) {
+        let (counter_buffers_vk, counter_buffer_offsets_vk) =
+            Self::counter_buffers_vk(counter_buffers);
+
+        let fns = self.device.fns();
+        (fns.ext_transform_feedback.cmd_begin_transform_feedback_ext)(
+            self.handle,
+            first_counter_buffer,
+            counter_buffers_vk.len() as u32,
+            if counter_buffers_vk.is_empty() {
+                ptr::null()
+            } else {
+                counter_buffers_vk.as_ptr()
+            },
+            if counter_buffer_offsets_vk.is_empty() {
+                ptr::null()
+            } else {
+                counter_buffer_offsets_vk.as_ptr()
+            },
+        );
+    }
+
+    /// Calls `vkCmdEndTransformFeedbackEXT` on the builder.
+    #[inline]
+    pub unsafe fn end_transform_feedback<'a>(
+        &mut self,
+        first_counter_buffer: u32,
+        counter_buffers: impl IntoIterator<Item = Option<&'a dyn BufferAccess>>,
+    ) {
+        let (counter_buffers_vk, counter_buffer_offsets_vk) =
+            Self::counter_buffers_vk(counter_buffers);
+
+        let fns = self.device.fns();
+        (fns.ext_transform_feedback.cmd_end_transform_feedback_ext)(
+            self.handle,
+            first_counter_buffer,
+            counter_buffers_vk.len() as u32,
+            if counter_buffers_vk.is_empty() {
+                ptr::null()
+            } else {
+                counter_buffers_vk.as_ptr()
+            },
+            if counter_buffer_offsets_vk.is_empty() {
+                ptr::null()
+            } else {
+                counter_buffer_offsets_vk.as_ptr()
+            },
+        );
+    }
+
+    fn counter_buffers_vk<'a>(
+        counter_buffers: impl IntoIterator<Item = Option<&'a dyn BufferAccess>>,
+    ) -> (
+        SmallVec<[ash::vk::Buffer; 4]>,
+        SmallVec<[ash::vk::DeviceSize; 4]>,
+    ) {
+        counter_buffers
+            .into_iter()
+            .map(|counter_buffer| match counter_buffer {
+                Some(counter_buffer) => {
+                    let inner = counter_buffer.inner();
+                    debug_assert!(inner.buffer.usage().transform_feedback_counter_buffer);
+
+                    (inner.buffer.internal_object(), inner.offset)
+                }
+                None => (ash::vk::Buffer::null(), 0),
+            })
+            .unzip()
+    }
+}
+
+/// Error that can happen when recording a transform feedback command.
+#[derive(Clone, Debug)]
+pub enum TransformFeedbackError {
+    SyncCommandBufferBuilderError(SyncCommandBufferBuilderError),
+
+    RequirementNotMet {
+        required_for: &'static str,
+        requires_one_of: RequiresOneOf,
+    },
+
+    /// Operation forbidden outside of a render pass.
+    ForbiddenOutsideRenderPass,
+
+    /// The queue family doesn't allow this operation.
+    NotSupportedByQueueFamily,
+
+    /// A transform feedback buffer is missing the `transform_feedback_buffer` usage.
+    BufferMissingUsage {
+        buffer_index: u32,
+    },
+
+    /// The offset of a transform feedback buffer is not a multiple of 4.
+    BufferOffsetNotAligned {
+        buffer_index: u32,
+        offset: DeviceSize,
+    },
+
+    /// The size of a transform feedback buffer exceeds the
+    /// [`max_transform_feedback_buffer_size`](crate::device::Properties::max_transform_feedback_buffer_size)
+    /// limit.
+    BufferSizeExceedsLimit {
+        buffer_index: u32,
+        size: DeviceSize,
+        max: DeviceSize,
+    },
+
+    /// A counter buffer is missing the `transform_feedback_counter_buffer` usage.
+    CounterBufferMissingUsage {
+        counter_buffer_index: u32,
+    },
+
+    /// The offset of a counter buffer is not a multiple of 4.
+    CounterBufferOffsetNotAligned {
+        counter_buffer_index: u32,
+        offset: DeviceSize,
+    },
+
+    /// A counter buffer is smaller than 4 bytes.
+    CounterBufferTooSmall {
+        counter_buffer_index: u32,
+    },
+
+    /// The highest binding number exceeds the
+    /// [`max_transform_feedback_buffers`](crate::device::Properties::max_transform_feedback_buffers)
+    /// limit.
+    MaxTransformFeedbackBuffersExceeded {
+        binding_count: u32,
+        max: u32,
+    },
+
+    /// The current render pass instance has multiview enabled.
+    MultiviewActive,
+
+    /// No graphics pipeline is currently bound.
+    PipelineNotBound,
+
+    /// The last vertex processing stage of the bound graphics pipeline does not declare the `Xfb`
+    /// execution mode.
+    PipelineNoTransformFeedback,
+
+    /// Transform feedback is already active.
+    TransformFeedbackActive,
+
+    /// Transform feedback is not active.
+    TransformFeedbackNotActive,
+}
+
+impl Error for TransformFeedbackError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            Self::SyncCommandBufferBuilderError(err) => Some(err),
+            _ => None,
+        }
+    }
+}
+
+impl Display for TransformFeedbackError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result<(), FmtError> {
+        match self {
+            Self::SyncCommandBufferBuilderError(_) => write!(f, "a SyncCommandBufferBuilderError"),
+            Self::RequirementNotMet {
+                required_for,
+                requires_one_of,
+            } => write!(
+                f,
+                "a requirement was not met for: {}; requires one of: {}",
+                required_for, requires_one_of,
+            ),
+            Self::ForbiddenOutsideRenderPass => {
+                write!(f, "operation forbidden outside of a render pass")
+            }
+            Self::NotSupportedByQueueFamily => {
+                write!(f, "the queue family doesn't allow this operation")
+            }
+            Self::BufferMissingUsage { buffer_index } => write!(
+                f,
+                "transform feedback buffer {} is missing the `transform_feedback_buffer` usage",
+                buffer_index,
+            ),
+            Self::BufferOffsetNotAligned {
+                buffer_index,
+                offset,
+            } => write!(
+                f,
+                "the offset of transform feedback buffer {} ({}) is not a multiple of 4",
+                buffer_index, offset,
+            ),
+            Self::BufferSizeExceedsLimit {
+                buffer_index,
+                size,
+                max,
+            } => write!(
+                f,
+                "the size of transform feedback buffer {} ({}) exceeds the \
+                `max_transform_feedback_buffer_size` limit ({})",
+                buffer_index, size, max,
+            ),
+            Self::CounterBufferMissingUsage {
+                counter_buffer_index,
+            } => write!(
+                f,
+                "counter buffer {} is missing the `transform_feedback_counter_buffer` usage",
+                counter_buffer_index,
+            ),
+            Self::CounterBufferOffsetNotAligned {
+                counter_buffer_index,
+                offset,
+            } => write!(
+                f,
+                "the offset of counter buffer {} ({}) is not a multiple of 4",
+                counter_buffer_index, offset,
+            ),
+            Self::CounterBufferTooSmall {
+                counter_buffer_index,
+            } => write!(
+                f,
+                "counter buffer {} is smaller than 4 bytes",
+                counter_buffer_index,
+            ),
+            Self::MaxTransformFeedbackBuffersExceeded { binding_count, max } => write!(
+                f,
+                "the highest binding number ({}) exceeds the `max_transform_feedback_buffers` \
+                limit ({})",
+                binding_count, max,
+            ),
+            Self::MultiviewActive => {
+                write!(f, "the current render pass instance has multiview enabled",)
+            }
+            Self::PipelineNotBound => write!(f, "no graphics pipeline is currently bound"),
+            Self::PipelineNoTransformFeedback => write!(
+                f,
+                "the last vertex processing stage of the bound graphics pipeline does not declare \
+                the `Xfb` execution mode",
+            ),
+            Self::TransformFeedbackActive => write!(f, "transform feedback is already active"),
+            Self::TransformFeedbackNotActive => write!(f, "transform feedback is not active"),
+        }
+    }
+}
+
+impl From<SyncCommandBufferBuilderError> for TransformFeedbackError {
+    fn from(err: SyncCommandBufferBuilderError) -> Self {
+        Self::SyncCommandBufferBuilderError(err)
+    }
+}
@@ -95,6 +95,7 @@ pub use self::{
         SecondaryAutoCommandBuffer,
     },
     commands::{
+        conditional_rendering::ConditionalRenderingError,
         debug::DebugUtilsError,
         image::{
             BlitImageInfo, ClearColorImageInfo, ClearDepthStencilImageInfo, ImageBlit,
@@ -104,15 +105,19 @@ pub use self::{
         query::QueryError,
         render_pass::{
             ClearAttachment, ClearRect, RenderPassBeginInfo, RenderPassError,
-            RenderingAttachmentInfo, RenderingAttachmentResolveInfo, RenderingInfo,
+            RenderingAttachmentInfo, RenderingAttachmentResolveInfo,
+            RenderingFragmentShadingRateAttachmentInfo, RenderingInfo,
         },
         secondary::{ExecuteCommandsError, UnsafeCommandBufferBuilderExecuteCommands},
+        sync::OwnershipTransferError,
         transfer::{
             BufferCopy, BufferImageCopy, CopyBufferInfo, CopyBufferInfoTyped,
             CopyBufferToImageInfo, CopyImageInfo, CopyImageToBufferInfo, FillBufferInfo, ImageCopy,
         },
+        transform_feedback::TransformFeedbackError,
         CopyError, CopyErrorResource,
     },
+    parallel::{ParallelRecordError, ParallelRecorder},
     traits::{
         CommandBufferExecError, CommandBufferExecFuture, PrimaryCommandBuffer,
         SecondaryCommandBuffer,
@@ -131,6 +136,7 @@ use std::sync::Arc;
 
 mod auto;
 mod commands;
+mod parallel;
 pub mod pool;
 pub mod synced;
 pub mod sys;
@@ -230,6 +236,16 @@ pub struct CommandBufferInheritanceInfo {
     /// The default value is [`QueryPipelineStatisticFlags::empty()`].
     pub query_statistics_flags: QueryPipelineStatisticFlags,
 
+    /// Whether the secondary command buffer is allowed to be executed within a primary that has
+    /// conditional rendering active. If `false`, the primary command buffer cannot have
+    /// conditional rendering active when this secondary command buffer is executed.
+    ///
+    /// The [`inherited_conditional_rendering`](crate::device::Features::inherited_conditional_rendering)
+    /// feature must be enabled if this is `true`.
+    ///
+    /// The default value is `false`.
+    pub conditional_rendering: bool,
+
     pub _ne: crate::NonExhaustive,
 }
 
@@ -240,6 +256,7 @@ impl Default for CommandBufferInheritanceInfo {
             render_pass: None,
             occlusion_query: None,
             query_statistics_flags: QueryPipelineStatisticFlags::empty(),
+            conditional_rendering: false,
             _ne: crate::NonExhaustive(()),
         }
     }
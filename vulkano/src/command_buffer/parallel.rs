@@ -0,0 +1,384 @@
+// Copyright (c) 2022 The vulkano developers
+// Licensed under the Apache License, Version 2.0
+// <LICENSE-APACHE or
+// https://www.apache.org/licenses/LICENSE-2.0> or the MIT
+// license <LICENSE-MIT or https://opensource.org/licenses/MIT>,
+// at your option. All files in the project carrying such
+// notice may not be copied, modified, or distributed except
+// according to those terms.
+
+use super::{
+    AutoCommandBufferBuilder, BuildError, CommandBufferBeginError, CommandBufferInheritanceInfo,
+    CommandBufferUsage, SecondaryAutoCommandBuffer,
+};
+use crate::device::{Device, DeviceOwned};
+use std::{
+    error::Error,
+    fmt::{Display, Error as FmtError, Formatter},
+    sync::Arc,
+};
+
+/// Records secondary command buffers with the same inheritance on multiple threads.
+///
+/// A `ParallelRecorder` can be cloned and sent to other threads. Each call to
+/// [`record`](Self::record) allocates the command buffer from the standard command pool of the
+/// current thread, so that the threads don't need to synchronize with each other while
+/// recording. If the `rayon` feature is enabled, [`record_parallel`](Self::record_parallel)
+/// records multiple command buffers on the rayon thread pool in one call.
+///
+/// The recorded command buffers are then executed in the primary command buffer with
+/// [`execute_commands_from_vec`](AutoCommandBufferBuilder::execute_commands_from_vec), which
+/// checks that the inheritance is compatible with the current state of the primary command
+/// buffer, and that the secondary command buffers don't have resource conflicts with each other.
+///
+/// # Examples
+///
+/// ```no_run
+/// # use std::{convert::Infallible, sync::Arc, thread};
+/// # use vulkano::command_buffer::{
+/// #     AutoCommandBufferBuilder, CommandBufferInheritanceInfo, CommandBufferUsage,
+/// #     ParallelRecorder, PrimaryAutoCommandBuffer, RenderPassBeginInfo, SubpassContents,
+/// # };
+/// # use vulkano::device::{DeviceOwned, Queue};
+/// # use vulkano::render_pass::Subpass;
+/// # let queue: Arc<Queue> = return;
+/// # let subpass: Subpass = return;
+/// # let render_pass_begin_info: RenderPassBeginInfo = return;
+/// # let mut primary: AutoCommandBufferBuilder<PrimaryAutoCommandBuffer> = return;
+/// let recorder = ParallelRecorder::new(
+///     queue.device().clone(),
+///     queue.queue_family_index(),
+///     CommandBufferUsage::OneTimeSubmit,
+///     CommandBufferInheritanceInfo {
+///         render_pass: Some(subpass.into()),
+///         ..Default::default()
+///     },
+/// );
+///
+/// let threads: Vec<_> = (0..4)
+///     .map(|_| {
+///         let recorder = recorder.clone();
+///         thread::spawn(move || {
+///             recorder
+///                 .record(|_builder| {
+///                     // ... record the draw commands of this thread ...
+///                     Ok::<_, Infallible>(())
+///                 })
+///                 .unwrap()
+///         })
+///     })
+///     .collect();
+/// let secondaries: Vec<_> = threads
+///     .into_iter()
+///     .map(|thread| thread.join().unwrap())
+///     .collect();
+///
+/// primary
+///     .begin_render_pass(render_pass_begin_info, SubpassContents::SecondaryCommandBuffers)
+///     .unwrap()
+///     .execute_commands_from_vec(secondaries)
+///     .unwrap()
+///     .end_render_pass()
+///     .unwrap();
+/// ```
+#[derive(Clone, Debug)]
+pub struct ParallelRecorder {
+    device: Arc<Device>,
+    queue_family_index: u32,
+    usage: CommandBufferUsage,
+    inheritance_info: CommandBufferInheritanceInfo,
+}
+
+impl ParallelRecorder {
+    /// Creates a new `ParallelRecorder`.
+    ///
+    /// To record command buffers that are executed within a render pass instance, set
+    /// `inheritance_info.render_pass` to the subpass, or to the
+    /// [`CommandBufferInheritanceRenderingInfo`](super::CommandBufferInheritanceRenderingInfo) of
+    /// `begin_rendering`.
+    #[inline]
+    pub fn new(
+        device: Arc<Device>,
+        queue_family_index: u32,
+        usage: CommandBufferUsage,
+        inheritance_info: CommandBufferInheritanceInfo,
+    ) -> ParallelRecorder {
+        ParallelRecorder {
+            device,
+            queue_family_index,
+            usage,
+            inheritance_info,
+        }
+    }
+
+    /// Returns the index of the queue family that the command buffers are recorded for.
+    #[inline]
+    pub fn queue_family_index(&self) -> u32 {
+        self.queue_family_index
+    }
+
+    /// Returns the usage that the command buffers are recorded with.
+    #[inline]
+    pub fn usage(&self) -> CommandBufferUsage {
+        self.usage
+    }
+
+    /// Returns the inheritance info that the command buffers are recorded with.
+    #[inline]
+    pub fn inheritance_info(&self) -> &CommandBufferInheritanceInfo {
+        &self.inheritance_info
+    }
+
+    /// Records a secondary command buffer on the current thread.
+    ///
+    /// `record` is called with the builder, and the command buffer is built once it returns.
+    pub fn record<F, E>(
+        &self,
+        record: F,
+    ) -> Result<SecondaryAutoCommandBuffer, ParallelRecordError<E>>
+    where
+        F: FnOnce(&mut AutoCommandBufferBuilder<SecondaryAutoCommandBuffer>) -> Result<(), E>,
+    {
+        let mut builder = AutoCommandBufferBuilder::secondary(
+            self.device.clone(),
+            self.queue_family_index,
+            self.usage,
+            self.inheritance_info.clone(),
+        )?;
+        record(&mut builder).map_err(ParallelRecordError::RecordingFailed)?;
+
+        Ok(builder.build()?)
+    }
+
+    /// Records `command_buffer_count` secondary command buffers in parallel, on the rayon thread
+    /// pool.
+    ///
+    /// `record` is called once for each command buffer, with its index and the builder. The
+    /// command buffers are returned in order of their index. If any of the recordings fails, one
+    /// of the errors is returned.
+    #[cfg(feature = "rayon")]
+    pub fn record_parallel<F, E>(
+        &self,
+        command_buffer_count: usize,
+        record: F,
+    ) -> Result<Vec<SecondaryAutoCommandBuffer>, ParallelRecordError<E>>
+    where
+        F: Fn(usize, &mut AutoCommandBufferBuilder<SecondaryAutoCommandBuffer>) -> Result<(), E>
+            + Sync,
+        E: Send,
+    {
+        use rayon::iter::{IntoParallelIterator, ParallelIterator};
+
+        (0..command_buffer_count)
+            .into_par_iter()
+            .map(|index| self.record(|builder| record(index, builder)))
+            .collect()
+    }
+}
+
+unsafe impl DeviceOwned for ParallelRecorder {
+    #[inline]
+    fn device(&self) -> &Arc<Device> {
+        &self.device
+    }
+}
+
+/// Error that can happen when recording a command buffer with a `ParallelRecorder`.
+#[derive(Clone, Debug)]
+pub enum ParallelRecordError<E> {
+    CommandBufferBeginError(CommandBufferBeginError),
+    BuildError(BuildError),
+
+    /// The function that records the commands returned an error.
+    RecordingFailed(E),
+}
+
+impl<E> Error for ParallelRecordError<E>
+where
+    E: Error + 'static,
+{
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            Self::CommandBufferBeginError(err) => Some(err),
+            Self::BuildError(err) => Some(err),
+            Self::RecordingFailed(err) => Some(err),
+        }
+    }
+}
+
+impl<E> Display for ParallelRecordError<E> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result<(), FmtError> {
+        match self {
+            Self::CommandBufferBeginError(_) => write!(f, "beginning the command buffer failed"),
+            Self::BuildError(_) => write!(f, "building the command buffer failed"),
+            Self::RecordingFailed(_) => write!(
+                f,
+                "the function that records the commands returned an error",
+            ),
+        }
+    }
+}
+
+impl<E> From<CommandBufferBeginError> for ParallelRecordError<E> {
+    fn from(err: CommandBufferBeginError) -> Self {
+        Self::CommandBufferBeginError(err)
+    }
+}
+
+impl<E> From<BuildError> for ParallelRecordError<E> {
+    fn from(err: BuildError) -> Self {
+        Self::BuildError(err)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::ParallelRecorder;
+    use crate::{
+        command_buffer::{
+            AutoCommandBufferBuilder, CommandBufferInheritanceInfo, CommandBufferUsage,
+            RenderPassBeginInfo, SubpassContents,
+        },
+        format::Format,
+        image::{view::ImageView, AttachmentImage},
+        render_pass::{Framebuffer, FramebufferCreateInfo, Subpass},
+    };
+    use std::{convert::Infallible, thread};
+
+    #[test]
+    fn record_on_user_threads() {
+        let (device, queue) = gfx_dev_and_queue!();
+
+        let recorder = ParallelRecorder::new(
+            device.clone(),
+            queue.queue_family_index(),
+            CommandBufferUsage::OneTimeSubmit,
+            CommandBufferInheritanceInfo::default(),
+        );
+
+        let secondaries: Vec<_> = (0..2)
+            .map(|_| {
+                let recorder = recorder.clone();
+                thread::spawn(move || recorder.record(|_| Ok::<_, Infallible>(())).unwrap())
+            })
+            .collect::<Vec<_>>()
+            .into_iter()
+            .map(|handle| handle.join().unwrap())
+            .collect();
+
+        let mut builder = AutoCommandBufferBuilder::primary(
+            device,
+            queue.queue_family_index(),
+            CommandBufferUsage::OneTimeSubmit,
+        )
+        .unwrap();
+        builder.execute_commands_from_vec(secondaries).unwrap();
+        builder.build().unwrap();
+    }
+
+    #[test]
+    fn execute_inside_render_pass() {
+        let (device, queue) = gfx_dev_and_queue!();
+
+        let render_pass = single_pass_renderpass!(device.clone(),
+            attachments: {
+                color: {
+                    load: DontCare,
+                    store: DontCare,
+                    format: Format::R8G8B8A8_UNORM,
+                    samples: 1,
+                }
+            },
+            pass: {
+                color: [color],
+                depth_stencil: {}
+            }
+        )
+        .unwrap();
+        let framebuffer = Framebuffer::new(
+            render_pass.clone(),
+            FramebufferCreateInfo {
+                attachments: vec![ImageView::new_default(
+                    AttachmentImage::new(device.clone(), [64, 64], Format::R8G8B8A8_UNORM).unwrap(),
+                )
+                .unwrap()],
+                ..Default::default()
+            },
+        )
+        .unwrap();
+
+        let recorder = ParallelRecorder::new(
+            device.clone(),
+            queue.queue_family_index(),
+            CommandBufferUsage::OneTimeSubmit,
+            CommandBufferInheritanceInfo {
+                render_pass: Some(Subpass::from(render_pass, 0).unwrap().into()),
+                ..Default::default()
+            },
+        );
+
+        let secondaries: Vec<_> = (0..2)
+            .map(|_| {
+                let recorder = recorder.clone();
+                thread::spawn(move || recorder.record(|_| Ok::<_, Infallible>(())).unwrap())
+            })
+            .collect::<Vec<_>>()
+            .into_iter()
+            .map(|handle| handle.join().unwrap())
+            .collect();
+
+        let mut builder = AutoCommandBufferBuilder::primary(
+            device,
+            queue.queue_family_index(),
+            CommandBufferUsage::OneTimeSubmit,
+        )
+        .unwrap();
+        builder
+            .begin_render_pass(
+                RenderPassBeginInfo {
+                    clear_values: vec![None],
+                    ..RenderPassBeginInfo::framebuffer(framebuffer)
+                },
+                SubpassContents::SecondaryCommandBuffers,
+            )
+            .unwrap()
+            .execute_commands_from_vec(secondaries)
+            .unwrap()
+            .end_render_pass()
+            .unwrap();
+        builder.build().unwrap();
+    }
+
+    #[cfg(feature = "rayon")]
+    #[test]
+    fn record_parallel() {
+        let (device, queue) = gfx_dev_and_queue!();
+
+        let recorder = ParallelRecorder::new(
+            device.clone(),
+            queue.queue_family_index(),
+            CommandBufferUsage::OneTimeSubmit,
+            CommandBufferInheritanceInfo::default(),
+        );
+
+        let secondaries = recorder
+            .record_parallel(4, |_, _| Ok::<_, Infallible>(()))
+            .unwrap();
+        assert_eq!(secondaries.len(), 4);
+
+        // An error returned by one of the recordings is propagated.
+        assert!(matches!(
+            recorder.record_parallel(4, |index, _| if index == 2 { Err(index) } else { Ok(()) }),
+            Err(super::ParallelRecordError::RecordingFailed(2))
+        ));
+
+        let mut builder = AutoCommandBufferBuilder::primary(
+            device,
+            queue.queue_family_index(),
+            CommandBufferUsage::OneTimeSubmit,
+        )
+        .unwrap();
+        builder.execute_commands_from_vec(secondaries).unwrap();
+        builder.build().unwrap();
+    }
+}
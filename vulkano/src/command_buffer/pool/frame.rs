@@ -0,0 +1,432 @@
+// Copyright (c) 2022 The vulkano developers
+// Licensed under the Apache License, Version 2.0
+// <LICENSE-APACHE or
+// https://www.apache.org/licenses/LICENSE-2.0> or the MIT
+// license <LICENSE-MIT or https://opensource.org/licenses/MIT>,
+// at your option. All files in the project carrying such
+// notice may not be copied, modified, or distributed except
+// according to those terms.
+
+//! A command pool that is reset as a whole, one frame at a time.
+//!
+//! [`FrameCommandPool`] wraps a Vulkan command pool that is created without
+//! `reset_command_buffer`. Command buffers allocated from it are never reset individually;
+//! instead, once a command buffer is dropped it is kept aside, and the whole Vulkan pool is reset
+//! in one call with [`reset`](FrameCommandPool::reset) or
+//! [`wait_and_reset`](FrameCommandPool::wait_and_reset). The command buffers are then reused by
+//! the following allocations.
+//!
+//! The usual setup is to create one `FrameCommandPool` per thread for each frame in flight. At
+//! the start of a frame, wait for the fence of the submission that last used the pool of that
+//! frame, drop its command buffers (for example by cleaning up its `GpuFuture`), then reset the
+//! pool and record the command buffers of the new frame.
+
+use super::{
+    sys::{CommandBufferAllocateInfo, UnsafeCommandPoolCreateInfo, UnsafeCommandPoolCreationError},
+    CommandPool, CommandPoolAlloc, CommandPoolBuilderAlloc, UnsafeCommandPool,
+    UnsafeCommandPoolAlloc,
+};
+use crate::{
+    command_buffer::CommandBufferLevel,
+    device::{Device, DeviceOwned},
+    sync::{Fence, FenceError},
+    OomError,
+};
+use crossbeam_queue::SegQueue;
+use std::{
+    error::Error,
+    fmt::{Display, Error as FmtError, Formatter},
+    marker::PhantomData,
+    mem::ManuallyDrop,
+    ptr,
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        Arc,
+    },
+    thread::{self, ThreadId},
+    time::Duration,
+    vec::IntoIter as VecIntoIter,
+};
+
+/// A command pool that is reset as a whole once the frame that used it has completed.
+///
+/// Like `StandardCommandPool`, an `Arc<FrameCommandPool>` can't escape the thread it was created
+/// on. Finished command buffers can be moved between threads, but can only be recorded again on
+/// the thread that owns the pool.
+///
+/// See [the module-level documentation](self) for more.
+#[derive(Debug)]
+pub struct FrameCommandPool {
+    // The Vulkan pool specific to a device's queue family.
+    inner: UnsafeCommandPool,
+    // List of primary command buffers in the initial state, that are available for reuse.
+    available_primary_command_buffers: SegQueue<UnsafeCommandPoolAlloc>,
+    // List of secondary command buffers in the initial state, that are available for reuse.
+    available_secondary_command_buffers: SegQueue<UnsafeCommandPoolAlloc>,
+    // List of command buffers that were dropped since the last reset. They can't be recorded
+    // again until the pool is reset.
+    retired_command_buffers: SegQueue<UnsafeCommandPoolAlloc>,
+    // The number of command buffers that were allocated from the pool and are not dropped yet.
+    alive_command_buffers: AtomicUsize,
+    // The thread that the pool was created on, which is the only one that may use it.
+    owner_thread: ThreadId,
+}
+
+impl FrameCommandPool {
+    /// Builds a new pool.
+    ///
+    /// # Panics
+    ///
+    /// - Panics if the device and the queue family don't belong to the same physical device.
+    pub fn new(device: Arc<Device>, queue_family_index: u32) -> Result<FrameCommandPool, OomError> {
+        assert!(
+            queue_family_index < device.physical_device().queue_family_properties().len() as u32
+        );
+
+        let inner = UnsafeCommandPool::new(
+            device,
+            UnsafeCommandPoolCreateInfo {
+                queue_family_index,
+                transient: true,
+                reset_command_buffer: false,
+                ..Default::default()
+            },
+        )
+        .map_err(|err| match err {
+            UnsafeCommandPoolCreationError::OomError(err) => err,
+            _ => panic!("Unexpected error: {}", err),
+        })?;
+
+        Ok(FrameCommandPool {
+            inner,
+            available_primary_command_buffers: Default::default(),
+            available_secondary_command_buffers: Default::default(),
+            retired_command_buffers: Default::default(),
+            alive_command_buffers: AtomicUsize::new(0),
+            owner_thread: thread::current().id(),
+        })
+    }
+
+    /// Resets the pool, so that the command buffers that were previously allocated from it can be
+    /// recorded again.
+    ///
+    /// All command buffers that were allocated from the pool must have been dropped. Since a
+    /// command buffer is kept alive by the `GpuFuture` that it was submitted with, this means
+    /// that they have finished executing.
+    ///
+    /// If `release_resources` is true, it is a hint to the implementation that it should free all
+    /// the memory internally allocated for this pool.
+    pub fn reset(&self, release_resources: bool) -> Result<(), FrameCommandPoolResetError> {
+        let count = self.alive_command_buffers.load(Ordering::Acquire);
+
+        if count != 0 {
+            return Err(FrameCommandPoolResetError::CommandBuffersAlive { count });
+        }
+
+        // Safe because no command buffer allocated from the pool is alive, so none of them can be
+        // pending execution or be used afterwards.
+        unsafe {
+            self.inner.reset(release_resources)?;
+        }
+
+        while let Some(cmd) = self.retired_command_buffers.pop() {
+            match cmd.level() {
+                CommandBufferLevel::Primary => self.available_primary_command_buffers.push(cmd),
+                CommandBufferLevel::Secondary => self.available_secondary_command_buffers.push(cmd),
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Waits until `fence` is signaled, then resets the pool.
+    ///
+    /// `fence` should be the fence that was signaled by the last submission of command buffers
+    /// from this pool. See [`reset`](Self::reset) for more.
+    pub fn wait_and_reset(
+        &self,
+        fence: &Fence,
+        timeout: Option<Duration>,
+        release_resources: bool,
+    ) -> Result<(), FrameCommandPoolResetError> {
+        fence.wait(timeout)?;
+        self.reset(release_resources)
+    }
+}
+
+unsafe impl CommandPool for Arc<FrameCommandPool> {
+    type Iter = VecIntoIter<FrameCommandPoolBuilder>;
+    type Builder = FrameCommandPoolBuilder;
+    type Alloc = FrameCommandPoolAlloc;
+
+    #[inline]
+    fn allocate(
+        &self,
+        level: CommandBufferLevel,
+        mut command_buffer_count: u32,
+    ) -> Result<Self::Iter, OomError> {
+        let mut output = Vec::with_capacity(command_buffer_count as usize);
+
+        // First, pick from the command buffers that were reset.
+        {
+            let existing = match level {
+                CommandBufferLevel::Primary => &self.available_primary_command_buffers,
+                CommandBufferLevel::Secondary => &self.available_secondary_command_buffers,
+            };
+
+            for _ in 0..command_buffer_count as usize {
+                if let Some(cmd) = existing.pop() {
+                    output.push(cmd);
+                } else {
+                    break;
+                }
+            }
+        }
+
+        // Then allocate the rest.
+        if output.len() < command_buffer_count as usize {
+            command_buffer_count -= output.len() as u32;
+
+            output.extend(
+                self.inner
+                    .allocate_command_buffers(CommandBufferAllocateInfo {
+                        level,
+                        command_buffer_count,
+                        ..Default::default()
+                    })?,
+            );
+        }
+
+        self.alive_command_buffers
+            .fetch_add(output.len(), Ordering::Relaxed);
+
+        Ok(output
+            .into_iter()
+            .map(|cmd| FrameCommandPoolBuilder {
+                inner: FrameCommandPoolAlloc {
+                    cmd: ManuallyDrop::new(cmd),
+                    pool: self.clone(),
+                },
+                dummy_avoid_send_sync: PhantomData,
+            })
+            .collect::<Vec<_>>()
+            .into_iter())
+    }
+
+    #[inline]
+    fn queue_family_index(&self) -> u32 {
+        self.inner.queue_family_index()
+    }
+}
+
+unsafe impl DeviceOwned for FrameCommandPool {
+    #[inline]
+    fn device(&self) -> &Arc<Device> {
+        self.inner.device()
+    }
+}
+
+/// Command buffer allocated from a `FrameCommandPool` that is currently being built.
+pub struct FrameCommandPoolBuilder {
+    // Same as in `StandardCommandPoolBuilder`, the only difference with the alloc is that the
+    // builder must not implement `Send` and `Sync`.
+    inner: FrameCommandPoolAlloc,
+    // Unimplemented `Send` and `Sync` from the builder.
+    dummy_avoid_send_sync: PhantomData<*const u8>,
+}
+
+unsafe impl CommandPoolBuilderAlloc for FrameCommandPoolBuilder {
+    type Alloc = FrameCommandPoolAlloc;
+
+    #[inline]
+    fn inner(&self) -> &UnsafeCommandPoolAlloc {
+        self.inner.inner()
+    }
+
+    #[inline]
+    fn into_alloc(self) -> Self::Alloc {
+        self.inner
+    }
+
+    #[inline]
+    fn queue_family_index(&self) -> u32 {
+        self.inner.queue_family_index()
+    }
+}
+
+unsafe impl DeviceOwned for FrameCommandPoolBuilder {
+    #[inline]
+    fn device(&self) -> &Arc<Device> {
+        self.inner.device()
+    }
+}
+
+/// Command buffer allocated from a `FrameCommandPool`.
+pub struct FrameCommandPoolAlloc {
+    // The actual command buffer. Extracted in the `Drop` implementation.
+    cmd: ManuallyDrop<UnsafeCommandPoolAlloc>,
+    // We hold a reference to the command pool for our destructor.
+    pool: Arc<FrameCommandPool>,
+}
+
+unsafe impl Send for FrameCommandPoolAlloc {}
+unsafe impl Sync for FrameCommandPoolAlloc {}
+
+unsafe impl CommandPoolAlloc for FrameCommandPoolAlloc {
+    type Builder = FrameCommandPoolBuilder;
+
+    #[inline]
+    fn inner(&self) -> &UnsafeCommandPoolAlloc {
+        &*self.cmd
+    }
+
+    #[inline]
+    fn into_builder(self) -> Result<Self::Builder, Self> {
+        // The builder records into the pool, which must only be used by the thread that owns it.
+        if thread::current().id() != self.pool.owner_thread {
+            return Err(self);
+        }
+
+        Ok(FrameCommandPoolBuilder {
+            inner: self,
+            dummy_avoid_send_sync: PhantomData,
+        })
+    }
+
+    #[inline]
+    fn queue_family_index(&self) -> u32 {
+        self.pool.queue_family_index()
+    }
+}
+
+unsafe impl DeviceOwned for FrameCommandPoolAlloc {
+    #[inline]
+    fn device(&self) -> &Arc<Device> {
+        self.pool.device()
+    }
+}
+
+impl Drop for FrameCommandPoolAlloc {
+    #[inline]
+    fn drop(&mut self) {
+        // Safe because `self.cmd` is wrapped in a `ManuallyDrop`.
+        let cmd: UnsafeCommandPoolAlloc = unsafe { ptr::read(&*self.cmd) };
+
+        // The command buffer must be made available before the count is decreased, so that
+        // `reset` doesn't miss it.
+        self.pool.retired_command_buffers.push(cmd);
+        self.pool
+            .alive_command_buffers
+            .fetch_sub(1, Ordering::Release);
+    }
+}
+
+/// Error that can happen when resetting a `FrameCommandPool`.
+#[derive(Clone, Debug)]
+pub enum FrameCommandPoolResetError {
+    /// Not enough memory.
+    OomError(OomError),
+
+    /// Waiting for the fence failed.
+    FenceError(FenceError),
+
+    /// Some command buffers that were allocated from the pool are still alive.
+    CommandBuffersAlive { count: usize },
+}
+
+impl Error for FrameCommandPoolResetError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            Self::OomError(err) => Some(err),
+            Self::FenceError(err) => Some(err),
+            _ => None,
+        }
+    }
+}
+
+impl Display for FrameCommandPoolResetError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result<(), FmtError> {
+        match self {
+            Self::OomError(_) => write!(f, "not enough memory"),
+            Self::FenceError(_) => write!(f, "waiting for the fence failed"),
+            Self::CommandBuffersAlive { count } => write!(
+                f,
+                "{} command buffers that were allocated from the pool are still alive",
+                count,
+            ),
+        }
+    }
+}
+
+impl From<OomError> for FrameCommandPoolResetError {
+    fn from(err: OomError) -> Self {
+        Self::OomError(err)
+    }
+}
+
+impl From<FenceError> for FrameCommandPoolResetError {
+    fn from(err: FenceError) -> Self {
+        Self::FenceError(err)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{FrameCommandPool, FrameCommandPoolResetError};
+    use crate::{
+        command_buffer::{
+            pool::{CommandPool, CommandPoolBuilderAlloc},
+            CommandBufferLevel,
+        },
+        VulkanObject,
+    };
+    use std::sync::Arc;
+
+    #[test]
+    fn reuse_after_reset() {
+        let (device, queue) = gfx_dev_and_queue!();
+        let pool = Arc::new(FrameCommandPool::new(device, queue.queue_family_index()).unwrap());
+
+        let cb = pool
+            .allocate(CommandBufferLevel::Primary, 1)
+            .unwrap()
+            .next()
+            .unwrap();
+        let raw = cb.inner().internal_object();
+        drop(cb);
+
+        // Not reset yet, so the command buffer can't be reused.
+        let cb2 = pool
+            .allocate(CommandBufferLevel::Primary, 1)
+            .unwrap()
+            .next()
+            .unwrap();
+        assert_ne!(raw, cb2.inner().internal_object());
+        drop(cb2);
+
+        pool.reset(false).unwrap();
+
+        let cb3 = pool
+            .allocate(CommandBufferLevel::Primary, 1)
+            .unwrap()
+            .next()
+            .unwrap();
+        assert!(!cb3.inner().reset_command_buffer());
+    }
+
+    #[test]
+    fn reset_with_alive_command_buffers() {
+        let (device, queue) = gfx_dev_and_queue!();
+        let pool = Arc::new(FrameCommandPool::new(device, queue.queue_family_index()).unwrap());
+
+        let _cbs: Vec<_> = pool
+            .allocate(CommandBufferLevel::Secondary, 2)
+            .unwrap()
+            .collect();
+
+        match pool.reset(false) {
+            Err(FrameCommandPoolResetError::CommandBuffersAlive { count: 2 }) => (),
+            _ => panic!(),
+        }
+    }
+}
@@ -15,8 +15,13 @@
 //! In vulkano, creating a command buffer requires passing an implementation of the `CommandPool`
 //! trait. By default vulkano will use the `StandardCommandPool` struct, but you can implement
 //! this trait yourself by wrapping around the `UnsafeCommandPool` type.
+//!
+//! If you record the same kind of command buffers every frame, you can use a `FrameCommandPool`
+//! instead. It is reset as a whole once the frame that used it has completed, which is cheaper
+//! than resetting each command buffer individually.
 
 pub use self::{
+    frame::{FrameCommandPool, FrameCommandPoolResetError},
     standard::StandardCommandPool,
     sys::{
         CommandPoolTrimError, UnsafeCommandPool, UnsafeCommandPoolAlloc,
@@ -26,6 +31,7 @@ pub use self::{
 use super::CommandBufferLevel;
 use crate::{device::DeviceOwned, OomError};
 
+pub mod frame;
 pub mod standard;
 mod sys;
 
@@ -97,9 +103,20 @@ pub unsafe trait CommandPoolBuilderAlloc: DeviceOwned {
 ///
 /// See `CommandPool` for information about safety.
 pub unsafe trait CommandPoolAlloc: DeviceOwned + Send + Sync {
+    /// Return type of `into_builder`.
+    type Builder: CommandPoolBuilderAlloc<Alloc = Self>;
+
     /// Returns the internal object that contains the command buffer.
     fn inner(&self) -> &UnsafeCommandPoolAlloc;
 
+    /// Turns this command buffer back into a builder, so that it can be recorded again.
+    ///
+    /// Returns `self` back if the command buffer can't be recorded on the current thread, for
+    /// example because its pool belongs to another thread.
+    fn into_builder(self) -> Result<Self::Builder, Self>
+    where
+        Self: Sized;
+
     /// Returns the index of the queue family that the pool targets.
     fn queue_family_index(&self) -> u32;
 }
@@ -9,7 +9,14 @@ use crate::{
     OomError,
 };
 use crossbeam_queue::SegQueue;
-use std::{marker::PhantomData, mem::ManuallyDrop, ptr, sync::Arc, vec::IntoIter as VecIntoIter};
+use std::{
+    marker::PhantomData,
+    mem::ManuallyDrop,
+    ptr,
+    sync::Arc,
+    thread::{self, ThreadId},
+    vec::IntoIter as VecIntoIter,
+};
 
 // Copyright (c) 2016 The vulkano developers
 // Licensed under the Apache License, Version 2.0
@@ -25,8 +32,8 @@ use std::{marker::PhantomData, mem::ManuallyDrop, ptr, sync::Arc, vec::IntoIter
 /// A thread can have as many `Arc<StandardCommandPool>`s as needed, but none of them can escape the
 /// thread they were created on. This is done so that there are no locks involved when creating
 /// command buffers. Command buffers can't be moved between threads during the building process, but
-/// finished command buffers can. When a command buffer is dropped, it is returned back to the pool
-/// for reuse.
+/// finished command buffers can. They can only be reset and recorded again on the thread that owns
+/// the pool, however. When a command buffer is dropped, it is returned back to the pool for reuse.
 #[derive(Debug)]
 pub struct StandardCommandPool {
     // The Vulkan pool specific to a device's queue family.
@@ -35,6 +42,8 @@ pub struct StandardCommandPool {
     available_primary_command_buffers: SegQueue<UnsafeCommandPoolAlloc>,
     // List of existing secondary command buffers that are available for reuse.
     available_secondary_command_buffers: SegQueue<UnsafeCommandPoolAlloc>,
+    // The thread that the pool was created on, which is the only one that may use it.
+    owner_thread: ThreadId,
 }
 
 impl StandardCommandPool {
@@ -68,6 +77,7 @@ impl StandardCommandPool {
             inner,
             available_primary_command_buffers: Default::default(),
             available_secondary_command_buffers: Default::default(),
+            owner_thread: thread::current().id(),
         })
     }
 }
@@ -194,11 +204,26 @@ unsafe impl Send for StandardCommandPoolAlloc {}
 unsafe impl Sync for StandardCommandPoolAlloc {}
 
 unsafe impl CommandPoolAlloc for StandardCommandPoolAlloc {
+    type Builder = StandardCommandPoolBuilder;
+
     #[inline]
     fn inner(&self) -> &UnsafeCommandPoolAlloc {
         &*self.cmd
     }
 
+    #[inline]
+    fn into_builder(self) -> Result<Self::Builder, Self> {
+        // The builder records into the pool, which must only be used by the thread that owns it.
+        if thread::current().id() != self.pool.owner_thread {
+            return Err(self);
+        }
+
+        Ok(StandardCommandPoolBuilder {
+            inner: self,
+            dummy_avoid_send_sync: PhantomData,
+        })
+    }
+
     #[inline]
     fn queue_family_index(&self) -> u32 {
         self.pool.queue_family_index()
@@ -39,8 +39,8 @@ pub struct UnsafeCommandPool {
     dummy_avoid_sync: PhantomData<*const u8>,
 
     queue_family_index: u32,
-    _transient: bool,
-    _reset_command_buffer: bool,
+    transient: bool,
+    reset_command_buffer: bool,
 }
 
 unsafe impl Send for UnsafeCommandPool {}
@@ -67,8 +67,8 @@ impl UnsafeCommandPool {
             dummy_avoid_sync: PhantomData,
 
             queue_family_index,
-            _transient: transient,
-            _reset_command_buffer: reset_command_buffer,
+            transient,
+            reset_command_buffer,
         })
     }
 
@@ -97,8 +97,8 @@ impl UnsafeCommandPool {
             dummy_avoid_sync: PhantomData,
 
             queue_family_index,
-            _transient: transient,
-            _reset_command_buffer: reset_command_buffer,
+            transient,
+            reset_command_buffer,
         }
     }
 
@@ -231,6 +231,7 @@ impl UnsafeCommandPool {
         };
 
         let device = self.device.clone();
+        let reset_command_buffer = self.reset_command_buffer;
 
         Ok(out
             .into_iter()
@@ -239,6 +240,7 @@ impl UnsafeCommandPool {
                 device: device.clone(),
 
                 level,
+                reset_command_buffer,
             }))
     }
 
@@ -314,6 +316,18 @@ impl UnsafeCommandPool {
     pub fn queue_family_index(&self) -> u32 {
         self.queue_family_index
     }
+
+    /// Returns whether the pool was created with the `transient` hint.
+    #[inline]
+    pub fn transient(&self) -> bool {
+        self.transient
+    }
+
+    /// Returns whether command buffers allocated from this pool can be reset individually.
+    #[inline]
+    pub fn reset_command_buffer(&self) -> bool {
+        self.reset_command_buffer
+    }
 }
 
 impl Drop for UnsafeCommandPool {
@@ -479,6 +493,7 @@ pub struct UnsafeCommandPoolAlloc {
     handle: ash::vk::CommandBuffer,
     device: Arc<Device>,
     level: CommandBufferLevel,
+    reset_command_buffer: bool,
 }
 
 impl UnsafeCommandPoolAlloc {
@@ -487,6 +502,13 @@ impl UnsafeCommandPoolAlloc {
     pub fn level(&self) -> CommandBufferLevel {
         self.level
     }
+
+    /// Returns whether the command buffer can be reset individually, which is the case if the
+    /// pool it was allocated from was created with `reset_command_buffer` enabled.
+    #[inline]
+    pub fn reset_command_buffer(&self) -> bool {
+        self.reset_command_buffer
+    }
 }
 
 unsafe impl VulkanObject for UnsafeCommandPoolAlloc {
@@ -20,7 +20,7 @@ use crate::{
         pool::UnsafeCommandPoolAlloc,
         synced::{BufferFinalState, BufferUse, ImageFinalState, ImageUse},
         sys::{CommandBufferBeginInfo, UnsafeCommandBufferBuilder},
-        CommandBufferExecError, CommandBufferLevel,
+        CommandBufferExecError, CommandBufferLevel, CommandBufferUsage,
     },
     descriptor_set::{DescriptorSetResources, DescriptorSetWithOffsets},
     device::{Device, DeviceOwned},
@@ -29,6 +29,7 @@ use crate::{
         graphics::{
             color_blend::LogicOp,
             depth_stencil::{CompareOp, StencilOps},
+            fragment_shading_rate::FragmentShadingRate,
             input_assembly::{IndexType, PrimitiveTopology},
             rasterization::{CullMode, DepthBias, FrontFace, LineStipple},
             viewport::{Scissor, Viewport},
@@ -44,13 +45,14 @@ use crate::{
     DeviceSize, OomError, VulkanObject,
 };
 use ahash::HashMap;
+use parking_lot::RwLockReadGuard;
 use smallvec::SmallVec;
 use std::{
     borrow::Cow,
     collections::hash_map::Entry,
     error::Error,
     fmt::{Debug, Display, Error as FmtError, Formatter},
-    ops::{Range, RangeInclusive},
+    ops::{Deref, Range, RangeInclusive},
     sync::Arc,
 };
 
@@ -213,8 +215,26 @@ impl SyncCommandBufferBuilder {
                         previous_command_name: self.commands[conflicting_use.command_index].name(),
                         previous_command_offset: conflicting_use.command_index,
                         previous_command_param: conflicting_use.name.clone(),
+                        object_name: buffer.inner().buffer.name(),
                     });
                 }
+
+                for (resource_name, buffer, memory) in
+                    referenced_buffers(resource_name, buffer, memory)
+                {
+                    if let Some(conflicting_use) =
+                        self.find_buffer_conflict(&*buffer, 0..buffer.size(), &memory)
+                    {
+                        return Err(SyncCommandBufferBuilderError::Conflict {
+                            command_param: resource_name,
+                            previous_command_name: self.commands[conflicting_use.command_index]
+                                .name(),
+                            previous_command_offset: conflicting_use.command_index,
+                            previous_command_param: conflicting_use.name.clone(),
+                            object_name: buffer.inner().buffer.name(),
+                        });
+                    }
+                }
             }
             Resource::Image {
                 ref image,
@@ -240,6 +260,7 @@ impl SyncCommandBufferBuilder {
                         previous_command_name: self.commands[conflicting_use.command_index].name(),
                         previous_command_offset: conflicting_use.command_index,
                         previous_command_param: conflicting_use.name.clone(),
+                        object_name: image.inner().image.name(),
                     });
                 }
             }
@@ -370,7 +391,13 @@ impl SyncCommandBufferBuilder {
                 range,
                 memory,
             } => {
+                let referenced_buffers = referenced_buffers(&resource_name, &buffer, &memory);
                 self.add_buffer(resource_name, buffer, range, memory);
+
+                for (resource_name, buffer, memory) in referenced_buffers {
+                    let range = 0..buffer.size();
+                    self.add_buffer(resource_name, buffer, range, memory);
+                }
             }
             Resource::Image {
                 image,
@@ -532,6 +559,52 @@ impl SyncCommandBufferBuilder {
         subresource_range.mip_levels.end += inner.first_mipmap_level;
 
         let range_map = self.images2.entry(inner.image.clone()).or_insert_with(|| {
+            if self.level == CommandBufferLevel::Primary && image.is_layout_tracked() {
+                // The initial layout of each range is the layout that it is left in by the
+                // previously recorded command buffer that used it, even if that one hasn't been
+                // submitted yet. `lock_submit` checks that the image really is in that layout
+                // when this command buffer is submitted.
+                let return_to_initial_layout =
+                    self.inner.usage() != CommandBufferUsage::OneTimeSubmit;
+
+                return inner
+                    .image
+                    .state()
+                    .recorded_layouts()
+                    .map(|(range, layout)| {
+                        // If the command buffer can be submitted more than once, it must leave
+                        // the image in the layout that it expects at the start. If that layout
+                        // is `Undefined` or `Preinitialized` we can't transition back to it, so
+                        // leave it in the layout the image requires instead. Otherwise, leave
+                        // the image in whatever layout it was last used in. Ranges that aren't
+                        // used by the command buffer are never transitioned.
+                        let final_layout = if !return_to_initial_layout {
+                            ImageLayout::Undefined
+                        } else if matches!(
+                            layout,
+                            ImageLayout::Undefined | ImageLayout::Preinitialized
+                        ) {
+                            image.final_layout_requirement()
+                        } else {
+                            layout
+                        };
+
+                        (
+                            range,
+                            ImageState {
+                                resource_uses: Vec::new(),
+                                memory: PipelineMemoryAccess::default(),
+                                exclusive_any: false,
+                                initial_layout: layout,
+                                current_layout: layout,
+                                final_layout,
+                                layout_tracked: true,
+                            },
+                        )
+                    })
+                    .collect();
+            }
+
             [(
                 0..inner.image.range_size(),
                 match self.level {
@@ -555,6 +628,7 @@ impl SyncCommandBufferBuilder {
                             initial_layout,
                             current_layout: initial_layout,
                             final_layout: image.final_layout_requirement(),
+                            layout_tracked: false,
                         }
                     }
                     CommandBufferLevel::Secondary => {
@@ -567,6 +641,7 @@ impl SyncCommandBufferBuilder {
                             initial_layout: ImageLayout::Undefined,
                             current_layout: ImageLayout::Undefined,
                             final_layout: ImageLayout::Undefined,
+                            layout_tracked: false,
                         }
                     }
                 },
@@ -621,7 +696,12 @@ impl SyncCommandBufferBuilder {
                                         // A layout transition is a write, so if we perform one, we
                                         // need exclusive access.
                                         // state.memory.exclusive = true; // TODO: is this correct?
-                                        // state.exclusive_any = true;
+                                        //
+                                        // The command buffer must also lock the range for writing
+                                        // when it is submitted, because that is when the new
+                                        // layout is stored in the image. Otherwise the next
+                                        // command buffer would still see the old layout.
+                                        state.exclusive_any = true;
 
                                         // Note that we transition from `bottom_of_pipe`, which
                                         // means that we wait for all the previous commands to be
@@ -761,10 +841,11 @@ impl SyncCommandBufferBuilder {
         if self.level == CommandBufferLevel::Primary {
             unsafe {
                 for (image, range_map) in self.images2.iter_mut() {
-                    for (range, state) in range_map
-                        .iter_mut()
-                        .filter(|(_range, state)| state.final_layout != state.current_layout)
-                    {
+                    for (range, state) in range_map.iter_mut().filter(|(_range, state)| {
+                        state.final_layout != ImageLayout::Undefined
+                            && state.final_layout != state.current_layout
+                            && !(state.layout_tracked && state.resource_uses.is_empty())
+                    }) {
                         self.pending_barrier
                             .image_memory_barriers
                             .push(ImageMemoryBarrier {
@@ -822,20 +903,36 @@ impl SyncCommandBufferBuilder {
                     .filter(|(_range, state)| {
                         !state.resource_uses.is_empty()
                             || (self.level == CommandBufferLevel::Primary
+                                && !state.layout_tracked
+                                && state.final_layout != ImageLayout::Undefined
                                 && state.current_layout != state.final_layout)
                     })
                     .map(|(range, mut state)| {
-                        if self.level == CommandBufferLevel::Primary {
+                        if self.level == CommandBufferLevel::Primary
+                            && state.final_layout != ImageLayout::Undefined
+                        {
                             state.current_layout = state.final_layout;
                         }
 
+                        // Command buffers that are recorded after this one start from the layout
+                        // that this one leaves the range in.
+                        if state.layout_tracked {
+                            resource
+                                .state()
+                                .set_recorded_layout(range.clone(), state.current_layout);
+                        }
+
                         let state = ImageFinalState {
                             resource_uses: state.resource_uses,
                             final_stages: state.memory.stages,
                             final_access: state.memory.access,
-                            exclusive: state.exclusive_any,
+                            // If the range ends up in a different layout, the command buffer writes
+                            // it, even if it was only read by the commands themselves.
+                            exclusive: state.exclusive_any
+                                || state.initial_layout != state.current_layout,
                             initial_layout: state.initial_layout,
                             final_layout: state.current_layout,
+                            layout_tracked: state.layout_tracked,
                         };
 
                         (range, state)
@@ -871,6 +968,56 @@ impl Debug for SyncCommandBufferBuilder {
     }
 }
 
+/// Returns the buffers that are referenced by device address in the contents of `buffer`,
+/// directly or through other referenced buffers, if `memory` is an access by a shader.
+///
+/// The referenced buffers are accessed for reading by the same stages as `buffer`. Writes through
+/// a device address must be declared with `reference_buffer`.
+fn referenced_buffers(
+    resource_name: &str,
+    buffer: &Arc<dyn BufferAccess>,
+    memory: &PipelineMemoryAccess,
+) -> Vec<(
+    Cow<'static, str>,
+    Arc<dyn BufferAccess>,
+    PipelineMemoryAccess,
+)> {
+    let mut result = Vec::new();
+
+    if !(memory.access.shader_read || memory.access.uniform_read || memory.access.shader_write) {
+        return result;
+    }
+
+    let memory = PipelineMemoryAccess {
+        stages: memory.stages,
+        access: AccessFlags {
+            shader_read: true,
+            ..AccessFlags::empty()
+        },
+        exclusive: false,
+    };
+    let mut visited = vec![buffer.inner().buffer.internal_object()];
+    let mut pending = buffer.inner().buffer.referenced_buffers();
+
+    while let Some(buffer) = pending.pop() {
+        let inner = buffer.inner().buffer;
+
+        if visited.contains(&inner.internal_object()) {
+            continue;
+        }
+
+        visited.push(inner.internal_object());
+        pending.extend(inner.referenced_buffers());
+        result.push((
+            format!("{} (referenced by device address)", resource_name).into(),
+            buffer,
+            memory,
+        ));
+    }
+
+    result
+}
+
 /// Error returned if the builder detects that there's an unsolvable conflict.
 #[derive(Debug, Clone)]
 pub enum SyncCommandBufferBuilderError {
@@ -880,6 +1027,8 @@ pub enum SyncCommandBufferBuilderError {
         previous_command_name: &'static str,
         previous_command_offset: usize,
         previous_command_param: Cow<'static, str>,
+        /// The name of the buffer or image that the conflict is about, if it has one.
+        object_name: Option<String>,
     },
 
     ExecError(CommandBufferExecError),
@@ -890,7 +1039,28 @@ impl Error for SyncCommandBufferBuilderError {}
 impl Display for SyncCommandBufferBuilderError {
     fn fmt(&self, f: &mut Formatter<'_>) -> Result<(), FmtError> {
         match self {
-            SyncCommandBufferBuilderError::Conflict { .. } => write!(f, "unsolvable conflict"),
+            SyncCommandBufferBuilderError::Conflict {
+                command_param,
+                previous_command_name,
+                previous_command_offset,
+                previous_command_param,
+                object_name,
+            } => {
+                write!(f, "unsolvable conflict")?;
+
+                if let Some(object_name) = object_name {
+                    write!(f, " on '{}'", object_name)?;
+                }
+
+                write!(
+                    f,
+                    " between `{}` and `{}` of the previous `{}` command (index {})",
+                    command_param,
+                    previous_command_param,
+                    previous_command_name,
+                    previous_command_offset,
+                )
+            }
             SyncCommandBufferBuilderError::ExecError(err) => Display::fmt(err, f),
         }
     }
@@ -937,8 +1107,13 @@ struct ImageState {
     current_layout: ImageLayout,
 
     // The layout that the image range will have at the end of the command buffer.
-    // This is only used for primary command buffers.
+    // This is only used for primary command buffers. Can be `Undefined` if the image range is
+    // left in its current layout.
     final_layout: ImageLayout,
+
+    // True if `initial_layout` was taken from the layout that the image had when the command
+    // buffer was recorded.
+    layout_tracked: bool,
 }
 
 /// Holds the current binding and setting state.
@@ -949,6 +1124,7 @@ pub(in crate::command_buffer) struct CurrentState {
     pub(in crate::command_buffer) pipeline_compute: Option<Arc<ComputePipeline>>,
     pub(in crate::command_buffer) pipeline_graphics: Option<Arc<GraphicsPipeline>>,
     pub(in crate::command_buffer) vertex_buffers: HashMap<u32, Arc<dyn BufferAccess>>,
+    pub(in crate::command_buffer) transform_feedback_buffers: HashMap<u32, Arc<dyn BufferAccess>>,
 
     pub(in crate::command_buffer) push_constants: RangeSet<u32>,
     pub(in crate::command_buffer) push_constants_pipeline_layout: Option<Arc<PipelineLayout>>,
@@ -964,6 +1140,7 @@ pub(in crate::command_buffer) struct CurrentState {
     pub(in crate::command_buffer) depth_test_enable: Option<bool>,
     pub(in crate::command_buffer) depth_write_enable: Option<bool>,
     pub(in crate::command_buffer) discard_rectangle: HashMap<u32, Scissor>,
+    pub(in crate::command_buffer) fragment_shading_rate: Option<FragmentShadingRate>,
     pub(in crate::command_buffer) front_face: Option<FrontFace>,
     pub(in crate::command_buffer) line_stipple: Option<LineStipple>,
     pub(in crate::command_buffer) line_width: Option<f32>,
@@ -1002,7 +1179,7 @@ impl CurrentState {
                 DynamicState::DepthWriteEnable => self.depth_write_enable = None,
                 DynamicState::DiscardRectangle => self.discard_rectangle.clear(),
                 DynamicState::ExclusiveScissor => (), // TODO;
-                DynamicState::FragmentShadingRate => (), // TODO:
+                DynamicState::FragmentShadingRate => self.fragment_shading_rate = None,
                 DynamicState::FrontFace => self.front_face = None,
                 DynamicState::LineStipple => self.line_stipple = None,
                 DynamicState::LineWidth => self.line_width = None,
@@ -1103,9 +1280,27 @@ pub enum SetOrPush {
 
 impl SetOrPush {
     #[inline]
-    pub fn resources(&self) -> &DescriptorSetResources {
+    pub fn resources(&self) -> SetOrPushResources<'_> {
         match self {
-            Self::Set(set) => set.as_ref().0.resources(),
+            Self::Set(set) => SetOrPushResources::Set(set.as_ref().0.resources()),
+            Self::Push(resources) => SetOrPushResources::Push(resources),
+        }
+    }
+}
+
+/// The resources of a [`SetOrPush`], as returned by [`SetOrPush::resources`].
+pub enum SetOrPushResources<'a> {
+    Set(RwLockReadGuard<'a, DescriptorSetResources>),
+    Push(&'a DescriptorSetResources),
+}
+
+impl<'a> Deref for SetOrPushResources<'a> {
+    type Target = DescriptorSetResources;
+
+    #[inline]
+    fn deref(&self) -> &Self::Target {
+        match self {
+            Self::Set(resources) => resources,
             Self::Push(resources) => resources,
         }
     }
@@ -1175,6 +1370,15 @@ impl<'a> CommandBufferState<'a> {
         self.current_state.vertex_buffers.get(&binding_num)
     }
 
+    /// Returns the transform feedback buffer currently bound to a given binding slot number, or
+    /// `None` if nothing has been bound yet.
+    #[inline]
+    pub fn transform_feedback_buffer(&self, binding_num: u32) -> Option<&'a Arc<dyn BufferAccess>> {
+        self.current_state
+            .transform_feedback_buffers
+            .get(&binding_num)
+    }
+
     /// Returns a set containing push constant bytes that have been set.
     #[inline]
     pub fn push_constants(&self) -> &'a RangeSet<u32> {
@@ -1258,6 +1462,12 @@ impl<'a> CommandBufferState<'a> {
         self.current_state.discard_rectangle.get(&num)
     }
 
+    /// Returns the current fragment shading rate, or `None` if nothing has been set yet.
+    #[inline]
+    pub fn fragment_shading_rate(&self) -> Option<FragmentShadingRate> {
+        self.current_state.fragment_shading_rate
+    }
+
     /// Returns the current front face, or `None` if nothing has been set yet.
     #[inline]
     pub fn front_face(&self) -> Option<FrontFace> {
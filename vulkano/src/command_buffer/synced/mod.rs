@@ -65,7 +65,7 @@
 //! queue with a fresh new barrier prototype.
 
 pub use self::builder::{
-    CommandBufferState, SetOrPush, StencilOpStateDynamic, StencilStateDynamic,
+    CommandBufferState, SetOrPush, SetOrPushResources, StencilOpStateDynamic, StencilStateDynamic,
     SyncCommandBufferBuilder, SyncCommandBufferBuilderBindDescriptorSets,
     SyncCommandBufferBuilderBindVertexBuffer, SyncCommandBufferBuilderError,
     SyncCommandBufferBuilderExecuteCommands,
@@ -238,6 +238,28 @@ impl SyncCommandBuffer {
                         }
                         _ => (),
                     };
+
+                    // The initial layout of a layout-tracked image is the layout that the previously
+                    // recorded command buffer leaves it in, which is only correct if command
+                    // buffers are submitted in the order they were recorded in. The state of the
+                    // image includes every command buffer that was submitted before this one, so
+                    // check against it even if the future already knows about the image.
+                    if state.layout_tracked {
+                        if let Err(err) =
+                            image_state.check_layout(range.clone(), state.initial_layout)
+                        {
+                            let resource_use = &state.resource_uses[0];
+
+                            return Err(CommandBufferExecError::AccessError {
+                                error: err,
+                                command_name: self.commands[resource_use.command_index]
+                                    .name()
+                                    .into(),
+                                command_param: resource_use.name.clone(),
+                                command_offset: resource_use.command_index,
+                            });
+                        }
+                    }
                 }
 
                 Ok((image.as_ref(), image_state))
@@ -479,6 +501,10 @@ struct ImageFinalState {
 
     // Layout the image will be in at the end of the command buffer.
     final_layout: ImageLayout, // TODO: maybe wrap in an Option to mean that the layout doesn't change? because of buffers?
+
+    // True if the image is layout-tracked, and `initial_layout` was taken from its state when the
+    // command buffer was recorded. The image must be in exactly that layout when submitting.
+    layout_tracked: bool,
 }
 
 #[derive(Clone, PartialEq, Eq)]
@@ -671,6 +697,64 @@ mod tests {
         }
     }
 
+    #[test]
+    fn conflict_names_object() {
+        unsafe {
+            let (device, queue) = gfx_dev_and_queue!();
+
+            let (buf, future) = DeviceLocalBuffer::from_data_named(
+                0u32,
+                BufferUsage {
+                    transfer_dst: true,
+                    ..BufferUsage::empty()
+                },
+                queue.clone(),
+                "gbuffer_albedo",
+            )
+            .unwrap();
+            future
+                .then_signal_fence_and_flush()
+                .unwrap()
+                .wait(None)
+                .unwrap();
+            assert_eq!(buf.inner().buffer.name().as_deref(), Some("gbuffer_albedo"));
+
+            let pool_builder_alloc = device
+                .with_standard_command_pool(queue.queue_family_index(), |pool| {
+                    pool.allocate(CommandBufferLevel::Primary, 1)
+                        .unwrap()
+                        .next()
+                        .unwrap()
+                })
+                .unwrap();
+            let inner = UnsafeCommandBufferBuilder::new(
+                pool_builder_alloc.inner(),
+                CommandBufferBeginInfo {
+                    usage: CommandBufferUsage::OneTimeSubmit,
+                    ..Default::default()
+                },
+            )
+            .unwrap();
+
+            // Inside a render pass, no barrier can be inserted between the two writes.
+            let mut builder =
+                SyncCommandBufferBuilder::from_unsafe_cmd(inner, CommandBufferLevel::Primary, true);
+            builder
+                .fill_buffer(FillBufferInfo::dst_buffer(buf.clone()))
+                .unwrap();
+            let err = builder
+                .fill_buffer(FillBufferInfo::dst_buffer(buf))
+                .unwrap_err();
+
+            assert!(matches!(
+                err,
+                SyncCommandBufferBuilderError::Conflict { ref object_name, .. }
+                    if object_name.as_deref() == Some("gbuffer_albedo")
+            ));
+            assert!(err.to_string().contains("'gbuffer_albedo'"));
+        }
+    }
+
     #[test]
     fn vertex_buffer_binding() {
         unsafe {
@@ -81,6 +81,7 @@ impl UnsafeCommandBufferBuilder {
             let mut flags = ash::vk::CommandBufferUsageFlags::from(usage);
             let mut inheritance_info_vk = None;
             let mut inheritance_rendering_info_vk = None;
+            let mut inheritance_conditional_rendering_info_vk = None;
             let mut color_attachment_formats_vk: SmallVec<[_; 4]> = SmallVec::new();
 
             if let Some(inheritance_info) = &inheritance_info {
@@ -88,6 +89,7 @@ impl UnsafeCommandBufferBuilder {
                     ref render_pass,
                     occlusion_query,
                     query_statistics_flags,
+                    conditional_rendering,
                     _ne: _,
                 } = inheritance_info;
 
@@ -166,6 +168,20 @@ impl UnsafeCommandBufferBuilder {
                         }
                     }
                 }
+
+                if conditional_rendering {
+                    let inheritance_conditional_rendering_info_vk =
+                        inheritance_conditional_rendering_info_vk.insert(
+                            ash::vk::CommandBufferInheritanceConditionalRenderingInfoEXT {
+                                conditional_rendering_enable: ash::vk::TRUE,
+                                ..Default::default()
+                            },
+                        );
+
+                    inheritance_conditional_rendering_info_vk.p_next = inheritance_info_vk.p_next;
+                    inheritance_info_vk.p_next =
+                        inheritance_conditional_rendering_info_vk as *const _ as *const _;
+                }
             }
 
             let begin_info_vk = ash::vk::CommandBufferBeginInfo {
@@ -190,6 +206,12 @@ impl UnsafeCommandBufferBuilder {
         })
     }
 
+    /// Returns the usage that the command buffer is being recorded for.
+    #[inline]
+    pub fn usage(&self) -> CommandBufferUsage {
+        self.usage
+    }
+
     /// Turns the builder into an actual command buffer.
     #[inline]
     pub fn build(self) -> Result<UnsafeCommandBuffer, OomError> {
@@ -0,0 +1,889 @@
+// Copyright (c) 2022 The vulkano developers
+// Licensed under the Apache License, Version 2.0
+// <LICENSE-APACHE or
+// https://www.apache.org/licenses/LICENSE-2.0> or the MIT
+// license <LICENSE-MIT or https://opensource.org/licenses/MIT>,
+// at your option. All files in the project carrying such
+// notice may not be copied, modified, or distributed except
+// according to those terms.
+
+//! A descriptor set holding large arrays of resources that shaders index into.
+//!
+//! With "bindless" rendering, all the sampled images and buffers that shaders may need are put
+//! in one huge, long-lived descriptor set. Instead of binding different descriptor sets between
+//! draws, shaders receive plain integer indices (through push constants or buffers) and use them
+//! to index the arrays.
+//!
+//! [`BindlessTable`] manages such a descriptor set. Resources are added to the table and receive
+//! an index that stays valid until they are removed. Removed slots are only handed out again once
+//! the GPU is done with them, which the table learns about through *epochs*:
+//!
+//! - Call [`advance_epoch`](BindlessTable::advance_epoch) with the [`FenceSignalFuture`] of the
+//!   last submission that may use the table (typically once per frame). This ends the current
+//!   epoch, which is completed once the fence is signaled.
+//! - Call [`release_completed`](BindlessTable::release_completed) regularly, for example at the
+//!   start of every frame. Slots that were removed during an epoch that has completed become
+//!   available again, and the table drops its references to their resources.
+//!
+//! The table uses `update_after_bind`, `update_unused_while_pending` and `partially_bound`
+//! bindings, so the following features must be enabled on the device:
+//!
+//! - [`descriptor_binding_partially_bound`](crate::device::Features::descriptor_binding_partially_bound)
+//! - [`descriptor_binding_update_unused_while_pending`](crate::device::Features::descriptor_binding_update_unused_while_pending)
+//! - [`descriptor_binding_sampled_image_update_after_bind`](crate::device::Features::descriptor_binding_sampled_image_update_after_bind),
+//!   if `sampled_image_count` is not `0`.
+//! - [`descriptor_binding_storage_buffer_update_after_bind`](crate::device::Features::descriptor_binding_storage_buffer_update_after_bind),
+//!   if `storage_buffer_count` is not `0`.
+//!
+//! Shaders will additionally need the `runtime_descriptor_array` and
+//! `shader_sampled_image_array_non_uniform_indexing` features in most cases.
+//!
+//! The table does not record its resources in the command buffers it is bound in, so they are
+//! not synchronized automatically. Images must already be in a layout suitable for sampling, and
+//! any writes to the resources must have finished before the commands that read them execute.
+//!
+//! # Example
+//!
+//! ```no_run
+//! # use std::sync::Arc;
+//! # use vulkano::device::Device;
+//! # use vulkano::image::view::ImageViewAbstract;
+//! # use vulkano::pipeline::layout::{PipelineLayout, PipelineLayoutCreateInfo};
+//! # use vulkano::sync::{FenceSignalFuture, GpuFuture};
+//! use vulkano::descriptor_set::bindless::{BindlessTable, BindlessTableCreateInfo};
+//!
+//! # let device: Arc<Device> = return;
+//! # let texture: Arc<dyn ImageViewAbstract> = return;
+//! # let future: FenceSignalFuture<Box<dyn GpuFuture>> = return;
+//! let table = BindlessTable::new(
+//!     device.clone(),
+//!     BindlessTableCreateInfo {
+//!         sampled_image_count: 4096,
+//!         storage_buffer_count: 1024,
+//!         ..Default::default()
+//!     },
+//! )
+//! .unwrap();
+//!
+//! // The pipeline layout must use the table's layout for the set that it is bound to.
+//! let pipeline_layout = PipelineLayout::new(
+//!     device.clone(),
+//!     PipelineLayoutCreateInfo {
+//!         set_layouts: vec![table.layout().clone()],
+//!         ..Default::default()
+//!     },
+//! )
+//! .unwrap();
+//!
+//! // `texture` has been uploaded and transitioned to `ShaderReadOnlyOptimal` beforehand.
+//! let texture_index = table.add_image(texture).unwrap();
+//!
+//! // ... record and submit a command buffer that binds `table`, and signal a fence afterwards ...
+//!
+//! // Once the texture is no longer needed:
+//! table.remove_image(texture_index);
+//!
+//! // The submission may still use the texture, so its slot is only reused after `future` is
+//! // signaled.
+//! table.advance_epoch(&future);
+//!
+//! // ... typically at the start of the next frame ...
+//! table.release_completed().unwrap();
+//! ```
+
+use super::{
+    layout::{
+        DescriptorSetLayout, DescriptorSetLayoutBinding, DescriptorSetLayoutCreateInfo,
+        DescriptorSetLayoutCreationError, DescriptorType,
+    },
+    pool::{
+        DescriptorPoolAllocError, DescriptorSetAllocateInfo, UnsafeDescriptorPool,
+        UnsafeDescriptorPoolCreateInfo,
+    },
+    sys::UnsafeDescriptorSet,
+    update::check_descriptor_write,
+    DescriptorBindingResources, DescriptorSet, DescriptorSetResources, DescriptorSetUpdateError,
+    WriteDescriptorSet,
+};
+use crate::{
+    buffer::BufferAccess,
+    device::{Device, DeviceOwned},
+    image::view::ImageViewAbstract,
+    shader::ShaderStages,
+    sync::{Fence, FenceSignalFuture, GpuFuture},
+    OomError, VulkanObject,
+};
+use parking_lot::{Mutex, RwLock, RwLockReadGuard};
+use smallvec::SmallVec;
+use std::{
+    collections::VecDeque,
+    error::Error,
+    fmt::{Display, Error as FmtError, Formatter},
+    hash::{Hash, Hasher},
+    sync::Arc,
+};
+
+/// A long-lived descriptor set containing one large array of sampled images and one large array
+/// of storage buffers, which are indexed by shaders.
+///
+/// See the [module-level documentation](self) for more information.
+pub struct BindlessTable {
+    // Only kept alive to keep the descriptor set valid.
+    _pool: UnsafeDescriptorPool,
+    inner: UnsafeDescriptorSet,
+    layout: Arc<DescriptorSetLayout>,
+    // The resources are not tracked by command buffers, so this is always empty.
+    resources: RwLock<DescriptorSetResources>,
+    state: Mutex<BindlessTableState>,
+}
+
+struct BindlessTableState {
+    epoch: u64,
+    // Epochs that have ended but may not have completed yet, in order, along with the fence that
+    // is signaled once they complete. `None` if the epoch had already completed when it ended.
+    ended_epochs: VecDeque<(u64, Option<Arc<Fence>>)>,
+    images: Slots<Arc<dyn ImageViewAbstract>>,
+    buffers: Slots<Arc<dyn BufferAccess>>,
+}
+
+impl BindlessTable {
+    /// The binding number of the sampled image array.
+    pub const SAMPLED_IMAGE_BINDING: u32 = 0;
+
+    /// The binding number of the storage buffer array.
+    pub const STORAGE_BUFFER_BINDING: u32 = 1;
+
+    /// Creates a new `BindlessTable`.
+    ///
+    /// # Panics
+    ///
+    /// - Panics if both `create_info.sampled_image_count` and `create_info.storage_buffer_count`
+    ///   are `0`.
+    /// - Panics if `create_info.stages` is empty.
+    pub fn new(
+        device: Arc<Device>,
+        create_info: BindlessTableCreateInfo,
+    ) -> Result<Arc<BindlessTable>, BindlessTableError> {
+        let BindlessTableCreateInfo {
+            sampled_image_count,
+            storage_buffer_count,
+            stages,
+            _ne: _,
+        } = create_info;
+
+        assert!(sampled_image_count != 0 || storage_buffer_count != 0);
+        assert!(!stages.is_empty());
+
+        let properties = device.physical_device().properties();
+
+        let max_sampled_images = properties
+            .max_per_stage_descriptor_update_after_bind_sampled_images
+            .unwrap_or(0)
+            .min(
+                properties
+                    .max_descriptor_set_update_after_bind_sampled_images
+                    .unwrap_or(0),
+            );
+
+        if sampled_image_count > max_sampled_images {
+            return Err(BindlessTableError::MaxSampledImagesExceeded {
+                provided: sampled_image_count,
+                max_supported: max_sampled_images,
+            });
+        }
+
+        let max_storage_buffers = properties
+            .max_per_stage_descriptor_update_after_bind_storage_buffers
+            .unwrap_or(0)
+            .min(
+                properties
+                    .max_descriptor_set_update_after_bind_storage_buffers
+                    .unwrap_or(0),
+            );
+
+        if storage_buffer_count > max_storage_buffers {
+            return Err(BindlessTableError::MaxStorageBuffersExceeded {
+                provided: storage_buffer_count,
+                max_supported: max_storage_buffers,
+            });
+        }
+
+        let binding = |descriptor_type, descriptor_count| DescriptorSetLayoutBinding {
+            descriptor_count,
+            partially_bound: descriptor_count != 0,
+            update_after_bind: descriptor_count != 0,
+            update_unused_while_pending: descriptor_count != 0,
+            stages,
+            ..DescriptorSetLayoutBinding::descriptor_type(descriptor_type)
+        };
+
+        let layout = DescriptorSetLayout::new(
+            device.clone(),
+            DescriptorSetLayoutCreateInfo {
+                bindings: [
+                    (
+                        Self::SAMPLED_IMAGE_BINDING,
+                        binding(DescriptorType::SampledImage, sampled_image_count),
+                    ),
+                    (
+                        Self::STORAGE_BUFFER_BINDING,
+                        binding(DescriptorType::StorageBuffer, storage_buffer_count),
+                    ),
+                ]
+                .into(),
+                update_after_bind_pool: true,
+                ..Default::default()
+            },
+        )?;
+
+        let mut pool = UnsafeDescriptorPool::new(
+            device,
+            UnsafeDescriptorPoolCreateInfo {
+                max_sets: 1,
+                pool_sizes: layout.descriptor_counts().clone(),
+                update_after_bind: true,
+                max_inline_uniform_block_bindings: layout.inline_uniform_block_binding_count(),
+                ..Default::default()
+            },
+        )?;
+
+        let inner = match unsafe {
+            pool.allocate_descriptor_sets([DescriptorSetAllocateInfo {
+                layout: &layout,
+                variable_descriptor_count: 0,
+            }])
+        } {
+            Ok(mut sets) => sets.next().unwrap(),
+            Err(DescriptorPoolAllocError::OutOfHostMemory) => {
+                return Err(OomError::OutOfHostMemory.into());
+            }
+            Err(DescriptorPoolAllocError::OutOfDeviceMemory) => {
+                return Err(OomError::OutOfDeviceMemory.into());
+            }
+            Err(DescriptorPoolAllocError::FragmentedPool) => {
+                // This can't happen as we only allocate once from a new pool.
+                unreachable!();
+            }
+            Err(DescriptorPoolAllocError::OutOfPoolMemory) => {
+                // We created the pool with an exact size.
+                unreachable!();
+            }
+        };
+
+        // Shaders index the arrays with arbitrary indices, so there is nothing useful that
+        // command buffers could validate or synchronize. Leave the resources empty, which
+        // `partially_bound` allows.
+        let resources = DescriptorSetResources {
+            binding_resources: [
+                (
+                    Self::SAMPLED_IMAGE_BINDING,
+                    DescriptorBindingResources::ImageView(SmallVec::new()),
+                ),
+                (
+                    Self::STORAGE_BUFFER_BINDING,
+                    DescriptorBindingResources::Buffer(SmallVec::new()),
+                ),
+            ]
+            .into_iter()
+            .collect(),
+        };
+
+        Ok(Arc::new(BindlessTable {
+            _pool: pool,
+            inner,
+            layout,
+            resources: RwLock::new(resources),
+            state: Mutex::new(BindlessTableState {
+                epoch: 0,
+                ended_epochs: VecDeque::new(),
+                images: Slots::new(sampled_image_count),
+                buffers: Slots::new(storage_buffer_count),
+            }),
+        }))
+    }
+
+    /// Returns the number of sampled image slots in the table.
+    #[inline]
+    pub fn sampled_image_count(&self) -> u32 {
+        self.state.lock().images.capacity
+    }
+
+    /// Returns the number of storage buffer slots in the table.
+    #[inline]
+    pub fn storage_buffer_count(&self) -> u32 {
+        self.state.lock().buffers.capacity
+    }
+
+    /// Returns the identifier of the current epoch.
+    #[inline]
+    pub fn epoch(&self) -> u64 {
+        self.state.lock().epoch
+    }
+
+    /// Writes `image_view` to a free slot of the sampled image array, and returns its index.
+    ///
+    /// The table keeps `image_view` alive until the slot is removed and released.
+    ///
+    /// Command buffers that the table is bound in don't track `image_view`, so the image must
+    /// already be in the
+    /// [`ShaderReadOnlyOptimal`](crate::image::ImageLayout::ShaderReadOnlyOptimal) layout, or
+    /// [`General`](crate::image::ImageLayout::General) for images that are always in that layout,
+    /// whenever commands that may access the slot execute.
+    pub fn add_image(
+        &self,
+        image_view: Arc<dyn ImageViewAbstract>,
+    ) -> Result<u32, BindlessTableError> {
+        let mut state = self.state.lock();
+        let index = state
+            .images
+            .allocate()
+            .ok_or(BindlessTableError::SampledImagesFull)?;
+
+        let write = WriteDescriptorSet::image_view_array(
+            Self::SAMPLED_IMAGE_BINDING,
+            index,
+            [image_view.clone()],
+        );
+
+        if let Err(err) = unsafe { self.write(&write) } {
+            state.images.free.push(index);
+            return Err(err.into());
+        }
+
+        state.images.resources[index as usize] = Some(image_view);
+
+        Ok(index)
+    }
+
+    /// Writes `buffer` to a free slot of the storage buffer array, and returns its index.
+    ///
+    /// The table keeps `buffer` alive until the slot is removed and released.
+    pub fn add_buffer(&self, buffer: Arc<dyn BufferAccess>) -> Result<u32, BindlessTableError> {
+        let mut state = self.state.lock();
+        let index = state
+            .buffers
+            .allocate()
+            .ok_or(BindlessTableError::StorageBuffersFull)?;
+
+        let write =
+            WriteDescriptorSet::buffer_array(Self::STORAGE_BUFFER_BINDING, index, [buffer.clone()]);
+
+        if let Err(err) = unsafe { self.write(&write) } {
+            state.buffers.free.push(index);
+            return Err(err.into());
+        }
+
+        state.buffers.resources[index as usize] = Some(buffer);
+
+        Ok(index)
+    }
+
+    /// Removes the image at `index` from the table.
+    ///
+    /// The slot is not reused until the current epoch has completed.
+    ///
+    /// # Panics
+    ///
+    /// - Panics if there is no image at `index`.
+    #[inline]
+    pub fn remove_image(&self, index: u32) {
+        let mut state = self.state.lock();
+        let epoch = state.epoch;
+        state.images.retire(index, epoch);
+    }
+
+    /// Removes the buffer at `index` from the table.
+    ///
+    /// The slot is not reused until the current epoch has completed.
+    ///
+    /// # Panics
+    ///
+    /// - Panics if there is no buffer at `index`.
+    #[inline]
+    pub fn remove_buffer(&self, index: u32) {
+        let mut state = self.state.lock();
+        let epoch = state.epoch;
+        state.buffers.retire(index, epoch);
+    }
+
+    /// Ends the current epoch, and returns its identifier.
+    ///
+    /// The epoch completes once the fence of `future` is signaled. `future` should be the future
+    /// of the last submission that may use the table, so that the fence is signaled after all
+    /// the work that was submitted to its queue before it. If the table is used on multiple
+    /// queues, `future` must come after the work on the other queues, for example by joining
+    /// their futures before signaling the fence.
+    pub fn advance_epoch<F>(&self, future: &FenceSignalFuture<F>) -> u64
+    where
+        F: GpuFuture,
+    {
+        let mut state = self.state.lock();
+        let epoch = state.epoch;
+        state.epoch += 1;
+        state.ended_epochs.push_back((epoch, future.fence()));
+
+        epoch
+    }
+
+    /// Checks which of the epochs that have ended have also completed. Slots that were removed
+    /// during those epochs are made available again, and the table drops its references to
+    /// their resources.
+    ///
+    /// Epochs are checked in the order they ended in, so this stops at the first epoch whose
+    /// fence isn't signaled yet.
+    pub fn release_completed(&self) -> Result<(), OomError> {
+        let mut state = self.state.lock();
+        let mut completed_epoch = None;
+
+        while let Some((epoch, fence)) = state.ended_epochs.front() {
+            if let Some(fence) = fence {
+                if !fence.is_signaled()? {
+                    break;
+                }
+            }
+
+            completed_epoch = Some(*epoch);
+            state.ended_epochs.pop_front();
+        }
+
+        if let Some(epoch) = completed_epoch {
+            state.images.release(epoch);
+            state.buffers.release(epoch);
+        }
+
+        Ok(())
+    }
+
+    // The state lock must be held by the caller, as updates to the same descriptor set must be
+    // externally synchronized.
+    unsafe fn write(&self, write: &WriteDescriptorSet) -> Result<(), DescriptorSetUpdateError> {
+        check_descriptor_write(write, &self.layout, 0)?;
+
+        // The bindings are `update_after_bind` and `update_unused_while_pending`, so the set can
+        // be written to while it is bound or in use, as long as the written slot is not. Freed
+        // slots are only reused once the fence of their epoch is signaled, which ensures this.
+        UnsafeDescriptorSet::new(self.inner.internal_object()).write(&self.layout, [write]);
+
+        Ok(())
+    }
+}
+
+unsafe impl DescriptorSet for BindlessTable {
+    #[inline]
+    fn inner(&self) -> &UnsafeDescriptorSet {
+        &self.inner
+    }
+
+    #[inline]
+    fn layout(&self) -> &Arc<DescriptorSetLayout> {
+        &self.layout
+    }
+
+    #[inline]
+    fn resources(&self) -> RwLockReadGuard<'_, DescriptorSetResources> {
+        self.resources.read()
+    }
+}
+
+unsafe impl DeviceOwned for BindlessTable {
+    #[inline]
+    fn device(&self) -> &Arc<Device> {
+        self.layout.device()
+    }
+}
+
+impl PartialEq for BindlessTable {
+    #[inline]
+    fn eq(&self, other: &Self) -> bool {
+        self.inner.internal_object() == other.inner.internal_object()
+            && self.device() == other.device()
+    }
+}
+
+impl Eq for BindlessTable {}
+
+impl Hash for BindlessTable {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.inner.internal_object().hash(state);
+        self.device().hash(state);
+    }
+}
+
+// Hands out indices into one of the arrays of a `BindlessTable`.
+#[derive(Debug)]
+struct Slots<T> {
+    capacity: u32,
+    // The resource in each slot that has been handed out so far.
+    resources: Vec<Option<T>>,
+    // Slots that can be reused immediately.
+    free: Vec<u32>,
+    // Slots that were removed, along with the epoch they were removed in.
+    retired: VecDeque<(u64, u32)>,
+}
+
+impl<T> Slots<T> {
+    fn new(capacity: u32) -> Self {
+        Slots {
+            capacity,
+            resources: Vec::new(),
+            free: Vec::new(),
+            retired: VecDeque::new(),
+        }
+    }
+
+    fn allocate(&mut self) -> Option<u32> {
+        if let Some(index) = self.free.pop() {
+            Some(index)
+        } else if (self.resources.len() as u32) < self.capacity {
+            self.resources.push(None);
+            Some(self.resources.len() as u32 - 1)
+        } else {
+            None
+        }
+    }
+
+    fn retire(&mut self, index: u32, epoch: u64) {
+        assert!(
+            matches!(self.resources.get(index as usize), Some(Some(_))),
+            "no resource at index {}",
+            index,
+        );
+        assert!(
+            !self.retired.iter().any(|&(_, retired)| retired == index),
+            "the resource at index {} was already removed",
+            index,
+        );
+
+        self.retired.push_back((epoch, index));
+    }
+
+    fn release(&mut self, epoch: u64) {
+        while let Some(&(retired_epoch, index)) = self.retired.front() {
+            if retired_epoch > epoch {
+                break;
+            }
+
+            self.retired.pop_front();
+            self.resources[index as usize] = None;
+            self.free.push(index);
+        }
+    }
+}
+
+/// Parameters to create a new `BindlessTable`.
+#[derive(Clone, Debug)]
+pub struct BindlessTableCreateInfo {
+    /// The number of slots in the sampled image array, at binding
+    /// [`SAMPLED_IMAGE_BINDING`](BindlessTable::SAMPLED_IMAGE_BINDING).
+    ///
+    /// This must not be greater than the
+    /// [`max_per_stage_descriptor_update_after_bind_sampled_images`](crate::device::Properties::max_per_stage_descriptor_update_after_bind_sampled_images)
+    /// and
+    /// [`max_descriptor_set_update_after_bind_sampled_images`](crate::device::Properties::max_descriptor_set_update_after_bind_sampled_images)
+    /// limits.
+    ///
+    /// The default value is `0`.
+    pub sampled_image_count: u32,
+
+    /// The number of slots in the storage buffer array, at binding
+    /// [`STORAGE_BUFFER_BINDING`](BindlessTable::STORAGE_BUFFER_BINDING).
+    ///
+    /// This must not be greater than the
+    /// [`max_per_stage_descriptor_update_after_bind_storage_buffers`](crate::device::Properties::max_per_stage_descriptor_update_after_bind_storage_buffers)
+    /// and
+    /// [`max_descriptor_set_update_after_bind_storage_buffers`](crate::device::Properties::max_descriptor_set_update_after_bind_storage_buffers)
+    /// limits.
+    ///
+    /// The default value is `0`.
+    pub storage_buffer_count: u32,
+
+    /// The shader stages that can access the table.
+    ///
+    /// The default value is [`ShaderStages::all()`].
+    pub stages: ShaderStages,
+
+    pub _ne: crate::NonExhaustive,
+}
+
+impl Default for BindlessTableCreateInfo {
+    #[inline]
+    fn default() -> Self {
+        Self {
+            sampled_image_count: 0,
+            storage_buffer_count: 0,
+            stages: ShaderStages::all(),
+            _ne: crate::NonExhaustive(()),
+        }
+    }
+}
+
+/// Error that can happen when creating or using a `BindlessTable`.
+#[derive(Clone, Debug)]
+pub enum BindlessTableError {
+    /// Not enough memory.
+    OomError(OomError),
+
+    /// Creating the descriptor set layout failed.
+    DescriptorSetLayoutCreationError(DescriptorSetLayoutCreationError),
+
+    /// Writing a resource to the descriptor set failed.
+    DescriptorSetUpdateError(DescriptorSetUpdateError),
+
+    /// `sampled_image_count` is greater than the update-after-bind sampled image limits of the
+    /// device.
+    MaxSampledImagesExceeded { provided: u32, max_supported: u32 },
+
+    /// `storage_buffer_count` is greater than the update-after-bind storage buffer limits of the
+    /// device.
+    MaxStorageBuffersExceeded { provided: u32, max_supported: u32 },
+
+    /// All the slots of the sampled image array are in use.
+    SampledImagesFull,
+
+    /// All the slots of the storage buffer array are in use.
+    StorageBuffersFull,
+}
+
+impl Error for BindlessTableError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            Self::OomError(err) => Some(err),
+            Self::DescriptorSetLayoutCreationError(err) => Some(err),
+            Self::DescriptorSetUpdateError(err) => Some(err),
+            _ => None,
+        }
+    }
+}
+
+impl Display for BindlessTableError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result<(), FmtError> {
+        match self {
+            Self::OomError(_) => write!(f, "not enough memory available"),
+            Self::DescriptorSetLayoutCreationError(_) => {
+                write!(f, "creating the descriptor set layout failed")
+            }
+            Self::DescriptorSetUpdateError(_) => {
+                write!(f, "writing a resource to the descriptor set failed")
+            }
+            Self::MaxSampledImagesExceeded {
+                provided,
+                max_supported,
+            } => write!(
+                f,
+                "`sampled_image_count` ({}) is greater than the update-after-bind sampled image \
+                limits of the device ({})",
+                provided, max_supported,
+            ),
+            Self::MaxStorageBuffersExceeded {
+                provided,
+                max_supported,
+            } => write!(
+                f,
+                "`storage_buffer_count` ({}) is greater than the update-after-bind storage buffer \
+                limits of the device ({})",
+                provided, max_supported,
+            ),
+            Self::SampledImagesFull => {
+                write!(f, "all the slots of the sampled image array are in use")
+            }
+            Self::StorageBuffersFull => {
+                write!(f, "all the slots of the storage buffer array are in use")
+            }
+        }
+    }
+}
+
+impl From<OomError> for BindlessTableError {
+    #[inline]
+    fn from(err: OomError) -> Self {
+        Self::OomError(err)
+    }
+}
+
+impl From<DescriptorSetLayoutCreationError> for BindlessTableError {
+    #[inline]
+    fn from(err: DescriptorSetLayoutCreationError) -> Self {
+        Self::DescriptorSetLayoutCreationError(err)
+    }
+}
+
+impl From<DescriptorSetUpdateError> for BindlessTableError {
+    #[inline]
+    fn from(err: DescriptorSetUpdateError) -> Self {
+        Self::DescriptorSetUpdateError(err)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{BindlessTable, BindlessTableCreateInfo, BindlessTableError, Slots};
+    use crate::{
+        buffer::{BufferUsage, DeviceLocalBuffer},
+        command_buffer::{AutoCommandBufferBuilder, CommandBufferUsage, PrimaryCommandBuffer},
+        format::Format,
+        image::{view::ImageView, AttachmentImage},
+        sync::GpuFuture,
+    };
+
+    #[test]
+    fn slots_recycled_after_release() {
+        let mut slots: Slots<()> = Slots::new(2);
+
+        let a = slots.allocate().unwrap();
+        slots.resources[a as usize] = Some(());
+        let b = slots.allocate().unwrap();
+        slots.resources[b as usize] = Some(());
+        assert_eq!(slots.allocate(), None);
+
+        slots.retire(a, 3);
+
+        // Not yet completed.
+        slots.release(2);
+        assert_eq!(slots.allocate(), None);
+
+        slots.release(3);
+        assert_eq!(slots.allocate(), Some(a));
+    }
+
+    #[test]
+    fn slots_retire_unused() {
+        let mut slots: Slots<()> = Slots::new(2);
+
+        assert_should_panic!({
+            slots.retire(0, 0);
+        });
+    }
+
+    #[test]
+    fn table_limits_exceeded() {
+        let (device, _queue) = gfx_dev_and_queue!(
+            descriptor_binding_partially_bound,
+            descriptor_binding_update_unused_while_pending,
+            descriptor_binding_sampled_image_update_after_bind
+        );
+
+        assert!(matches!(
+            BindlessTable::new(
+                device,
+                BindlessTableCreateInfo {
+                    sampled_image_count: u32::MAX,
+                    ..Default::default()
+                },
+            ),
+            Err(BindlessTableError::MaxSampledImagesExceeded { .. })
+        ));
+    }
+
+    #[test]
+    fn table_slots_recycled_after_release() {
+        let (device, queue) = gfx_dev_and_queue!(
+            descriptor_binding_partially_bound,
+            descriptor_binding_update_unused_while_pending,
+            descriptor_binding_sampled_image_update_after_bind,
+            descriptor_binding_storage_buffer_update_after_bind
+        );
+
+        let table = BindlessTable::new(
+            device.clone(),
+            BindlessTableCreateInfo {
+                sampled_image_count: 1,
+                storage_buffer_count: 1,
+                ..Default::default()
+            },
+        )
+        .unwrap();
+        assert_eq!(table.sampled_image_count(), 1);
+        assert_eq!(table.storage_buffer_count(), 1);
+
+        let view = ImageView::new_default(
+            AttachmentImage::sampled(device.clone(), [1, 1], Format::R8G8B8A8_UNORM).unwrap(),
+        )
+        .unwrap();
+        let buffer = DeviceLocalBuffer::<u32>::new(
+            device,
+            BufferUsage {
+                storage_buffer: true,
+                ..BufferUsage::empty()
+            },
+            [queue.queue_family_index()],
+        )
+        .unwrap();
+
+        let submit = || {
+            AutoCommandBufferBuilder::primary(
+                device.clone(),
+                queue.queue_family_index(),
+                CommandBufferUsage::OneTimeSubmit,
+            )
+            .unwrap()
+            .build()
+            .unwrap()
+            .execute(queue.clone())
+            .unwrap()
+        };
+
+        assert_eq!(table.add_image(view.clone()).unwrap(), 0);
+        assert!(matches!(
+            table.add_image(view.clone()),
+            Err(BindlessTableError::SampledImagesFull)
+        ));
+        assert_eq!(table.add_buffer(buffer.clone()).unwrap(), 0);
+        assert!(matches!(
+            table.add_buffer(buffer.clone()),
+            Err(BindlessTableError::StorageBuffersFull)
+        ));
+
+        let first_future = submit().then_signal_fence_and_flush().unwrap();
+        let first = table.advance_epoch(&first_future);
+        assert_eq!(table.epoch(), first + 1);
+
+        table.remove_image(0);
+        table.remove_buffer(0);
+
+        // The fence of the second epoch isn't signaled until the future is flushed.
+        let second_future = submit().then_signal_fence();
+        table.advance_epoch(&second_future);
+
+        // The slots were removed after the first epoch ended, so they may still be in use.
+        first_future.wait(None).unwrap();
+        table.release_completed().unwrap();
+        assert!(matches!(
+            table.add_image(view.clone()),
+            Err(BindlessTableError::SampledImagesFull)
+        ));
+
+        second_future.wait(None).unwrap();
+        table.release_completed().unwrap();
+        assert_eq!(table.add_image(view).unwrap(), 0);
+        assert_eq!(table.add_buffer(buffer).unwrap(), 0);
+    }
+
+    #[test]
+    fn table_remove_twice() {
+        let (device, _queue) = gfx_dev_and_queue!(
+            descriptor_binding_partially_bound,
+            descriptor_binding_update_unused_while_pending,
+            descriptor_binding_sampled_image_update_after_bind
+        );
+
+        let table = BindlessTable::new(
+            device.clone(),
+            BindlessTableCreateInfo {
+                sampled_image_count: 1,
+                ..Default::default()
+            },
+        )
+        .unwrap();
+        let view = ImageView::new_default(
+            AttachmentImage::sampled(device, [1, 1], Format::R8G8B8A8_UNORM).unwrap(),
+        )
+        .unwrap();
+
+        let index = table.add_image(view).unwrap();
+        table.remove_image(index);
+
+        assert_should_panic!({
+            table.remove_image(index);
+        });
+    }
+}
@@ -37,6 +37,7 @@ pub struct DescriptorSetLayout {
 
     bindings: BTreeMap<u32, DescriptorSetLayoutBinding>,
     push_descriptor: bool,
+    update_after_bind_pool: bool,
 
     descriptor_counts: HashMap<DescriptorType, u32>,
 }
@@ -54,6 +55,7 @@ impl DescriptorSetLayout {
         let DescriptorSetLayoutCreateInfo {
             bindings,
             push_descriptor,
+            update_after_bind_pool,
             _ne: _,
         } = create_info;
 
@@ -63,6 +65,7 @@ impl DescriptorSetLayout {
 
             bindings,
             push_descriptor,
+            update_after_bind_pool,
 
             descriptor_counts,
         }))
@@ -83,6 +86,7 @@ impl DescriptorSetLayout {
         let DescriptorSetLayoutCreateInfo {
             bindings,
             push_descriptor,
+            update_after_bind_pool,
             _ne: _,
         } = create_info;
 
@@ -101,6 +105,7 @@ impl DescriptorSetLayout {
 
             bindings,
             push_descriptor,
+            update_after_bind_pool,
 
             descriptor_counts,
         })
@@ -113,6 +118,7 @@ impl DescriptorSetLayout {
         let &mut DescriptorSetLayoutCreateInfo {
             ref bindings,
             push_descriptor,
+            update_after_bind_pool,
             _ne: _,
         } = create_info;
 
@@ -130,6 +136,21 @@ impl DescriptorSetLayout {
             }
         }
 
+        if update_after_bind_pool {
+            if !(device.api_version() >= Version::V1_2
+                || device.enabled_extensions().ext_descriptor_indexing)
+            {
+                return Err(DescriptorSetLayoutCreationError::RequirementNotMet {
+                    required_for: "`create_info.update_after_bind_pool` is set",
+                    requires_one_of: RequiresOneOf {
+                        api_version: Some(Version::V1_2),
+                        device_extensions: &["ext_descriptor_indexing"],
+                        ..Default::default()
+                    },
+                });
+            }
+        }
+
         let highest_binding_num = bindings.keys().copied().next_back();
 
         for (&binding_num, binding) in bindings.iter() {
@@ -137,6 +158,9 @@ impl DescriptorSetLayout {
                 descriptor_type,
                 descriptor_count,
                 variable_descriptor_count,
+                partially_bound,
+                update_after_bind,
+                update_unused_while_pending,
                 stages,
                 ref immutable_samplers,
                 _ne: _,
@@ -152,6 +176,46 @@ impl DescriptorSetLayout {
                 *descriptor_counts.entry(descriptor_type).or_default() += descriptor_count;
             }
 
+            if descriptor_type == DescriptorType::InlineUniformBlock {
+                // VUID-VkDescriptorSetLayoutBinding-descriptorType-04604
+                if !device.enabled_features().inline_uniform_block {
+                    return Err(DescriptorSetLayoutCreationError::RequirementNotMet {
+                        required_for: "`create_info.bindings` has an element where \
+                            `descriptor_type` is `DescriptorType::InlineUniformBlock`",
+                        requires_one_of: RequiresOneOf {
+                            features: &["inline_uniform_block"],
+                            ..Default::default()
+                        },
+                    });
+                }
+
+                // VUID-VkDescriptorSetLayoutBinding-descriptorType-02209
+                if descriptor_count % 4 != 0 {
+                    return Err(
+                        DescriptorSetLayoutCreationError::InlineUniformBlockSizeNotMultipleOf4 {
+                            binding_num,
+                        },
+                    );
+                }
+
+                let max_inline_uniform_block_size = device
+                    .physical_device()
+                    .properties()
+                    .max_inline_uniform_block_size
+                    .unwrap_or(0);
+
+                // VUID-VkDescriptorSetLayoutBinding-descriptorType-02210
+                if descriptor_count > max_inline_uniform_block_size {
+                    return Err(
+                        DescriptorSetLayoutCreationError::MaxInlineUniformBlockSizeExceeded {
+                            binding_num,
+                            provided: descriptor_count,
+                            max_supported: max_inline_uniform_block_size,
+                        },
+                    );
+                }
+            }
+
             if push_descriptor {
                 // VUID-VkDescriptorSetLayoutCreateInfo-flags-00280
                 if matches!(
@@ -173,6 +237,15 @@ impl DescriptorSetLayout {
                         },
                     );
                 }
+
+                // VUID-VkDescriptorSetLayoutBindingFlagsCreateInfo-flags-03003
+                if update_after_bind || update_unused_while_pending {
+                    return Err(
+                        DescriptorSetLayoutCreationError::PushDescriptorUpdateAfterBind {
+                            binding_num,
+                        },
+                    );
+                }
             }
 
             if !immutable_samplers.is_empty() {
@@ -252,6 +325,110 @@ impl DescriptorSetLayout {
                     );
                 }
             }
+
+            if partially_bound {
+                // VUID-VkDescriptorSetLayoutBindingFlagsCreateInfo-descriptorBindingPartiallyBound-03013
+                if !device.enabled_features().descriptor_binding_partially_bound {
+                    return Err(DescriptorSetLayoutCreationError::RequirementNotMet {
+                        required_for:
+                            "`create_info.bindings` has an element where `partially_bound` is set",
+                        requires_one_of: RequiresOneOf {
+                            features: &["descriptor_binding_partially_bound"],
+                            ..Default::default()
+                        },
+                    });
+                }
+            }
+
+            if update_unused_while_pending {
+                // VUID-VkDescriptorSetLayoutBindingFlagsCreateInfo-descriptorBindingUpdateUnusedWhilePending-03012
+                if !device
+                    .enabled_features()
+                    .descriptor_binding_update_unused_while_pending
+                {
+                    return Err(DescriptorSetLayoutCreationError::RequirementNotMet {
+                        required_for: "`create_info.bindings` has an element where `update_unused_while_pending` is set",
+                        requires_one_of: RequiresOneOf {
+                            features: &["descriptor_binding_update_unused_while_pending"],
+                            ..Default::default()
+                        },
+                    });
+                }
+            }
+
+            if update_after_bind {
+                // VUID-VkDescriptorSetLayoutCreateInfo-flags-03000
+                if !update_after_bind_pool {
+                    return Err(
+                        DescriptorSetLayoutCreationError::UpdateAfterBindWithoutUpdateAfterBindPool {
+                            binding_num,
+                        },
+                    );
+                }
+
+                let features = device.enabled_features();
+
+                let (feature_enabled, feature_name) = match descriptor_type {
+                    // VUID-VkDescriptorSetLayoutBindingFlagsCreateInfo-descriptorBindingUniformBufferUpdateAfterBind-03005
+                    DescriptorType::UniformBuffer => (
+                        features.descriptor_binding_uniform_buffer_update_after_bind,
+                        &["descriptor_binding_uniform_buffer_update_after_bind"],
+                    ),
+                    // VUID-VkDescriptorSetLayoutBindingFlagsCreateInfo-descriptorBindingSampledImageUpdateAfterBind-03006
+                    DescriptorType::Sampler
+                    | DescriptorType::CombinedImageSampler
+                    | DescriptorType::SampledImage => (
+                        features.descriptor_binding_sampled_image_update_after_bind,
+                        &["descriptor_binding_sampled_image_update_after_bind"],
+                    ),
+                    // VUID-VkDescriptorSetLayoutBindingFlagsCreateInfo-descriptorBindingStorageImageUpdateAfterBind-03007
+                    DescriptorType::StorageImage => (
+                        features.descriptor_binding_storage_image_update_after_bind,
+                        &["descriptor_binding_storage_image_update_after_bind"],
+                    ),
+                    // VUID-VkDescriptorSetLayoutBindingFlagsCreateInfo-descriptorBindingStorageBufferUpdateAfterBind-03008
+                    DescriptorType::StorageBuffer => (
+                        features.descriptor_binding_storage_buffer_update_after_bind,
+                        &["descriptor_binding_storage_buffer_update_after_bind"],
+                    ),
+                    // VUID-VkDescriptorSetLayoutBindingFlagsCreateInfo-descriptorBindingUniformTexelBufferUpdateAfterBind-03009
+                    DescriptorType::UniformTexelBuffer => (
+                        features.descriptor_binding_uniform_texel_buffer_update_after_bind,
+                        &["descriptor_binding_uniform_texel_buffer_update_after_bind"],
+                    ),
+                    // VUID-VkDescriptorSetLayoutBindingFlagsCreateInfo-descriptorBindingStorageTexelBufferUpdateAfterBind-03010
+                    DescriptorType::StorageTexelBuffer => (
+                        features.descriptor_binding_storage_texel_buffer_update_after_bind,
+                        &["descriptor_binding_storage_texel_buffer_update_after_bind"],
+                    ),
+                    // VUID-VkDescriptorSetLayoutBindingFlagsCreateInfo-descriptorBindingInlineUniformBlockUpdateAfterBind-02211
+                    DescriptorType::InlineUniformBlock => (
+                        features.descriptor_binding_inline_uniform_block_update_after_bind,
+                        &["descriptor_binding_inline_uniform_block_update_after_bind"],
+                    ),
+                    // VUID-VkDescriptorSetLayoutBindingFlagsCreateInfo-None-03011
+                    DescriptorType::UniformBufferDynamic
+                    | DescriptorType::StorageBufferDynamic
+                    | DescriptorType::InputAttachment => {
+                        return Err(
+                            DescriptorSetLayoutCreationError::UpdateAfterBindDescriptorTypeIncompatible {
+                                binding_num,
+                            },
+                        );
+                    }
+                };
+
+                if !feature_enabled {
+                    return Err(DescriptorSetLayoutCreationError::RequirementNotMet {
+                        required_for:
+                            "`create_info.bindings` has an element where `update_after_bind` is set",
+                        requires_one_of: RequiresOneOf {
+                            features: feature_name,
+                            ..Default::default()
+                        },
+                    });
+                }
+            }
         }
 
         // VUID-VkDescriptorSetLayoutCreateInfo-flags-00281
@@ -285,6 +462,7 @@ impl DescriptorSetLayout {
         let &DescriptorSetLayoutCreateInfo {
             ref bindings,
             push_descriptor,
+            update_after_bind_pool,
             _ne: _,
         } = create_info;
 
@@ -297,6 +475,10 @@ impl DescriptorSetLayout {
             flags |= ash::vk::DescriptorSetLayoutCreateFlags::PUSH_DESCRIPTOR_KHR;
         }
 
+        if update_after_bind_pool {
+            flags |= ash::vk::DescriptorSetLayoutCreateFlags::UPDATE_AFTER_BIND_POOL;
+        }
+
         for (&binding_num, binding) in bindings.iter() {
             let mut binding_flags = ash::vk::DescriptorBindingFlags::empty();
 
@@ -319,6 +501,18 @@ impl DescriptorSetLayout {
                 binding_flags |= ash::vk::DescriptorBindingFlags::VARIABLE_DESCRIPTOR_COUNT;
             }
 
+            if binding.partially_bound {
+                binding_flags |= ash::vk::DescriptorBindingFlags::PARTIALLY_BOUND;
+            }
+
+            if binding.update_after_bind {
+                binding_flags |= ash::vk::DescriptorBindingFlags::UPDATE_AFTER_BIND;
+            }
+
+            if binding.update_unused_while_pending {
+                binding_flags |= ash::vk::DescriptorBindingFlags::UPDATE_UNUSED_WHILE_PENDING;
+            }
+
             // VUID-VkDescriptorSetLayoutCreateInfo-binding-00279
             // Guaranteed by BTreeMap
             bindings_vk.push(ash::vk::DescriptorSetLayoutBinding {
@@ -386,6 +580,13 @@ impl DescriptorSetLayout {
         self.push_descriptor
     }
 
+    /// Returns whether the descriptor set layout was created for use with update-after-bind
+    /// descriptor pools.
+    #[inline]
+    pub fn update_after_bind_pool(&self) -> bool {
+        self.update_after_bind_pool
+    }
+
     /// Returns the number of descriptors of each type.
     ///
     /// The map is guaranteed to not contain any elements with a count of `0`.
@@ -394,6 +595,21 @@ impl DescriptorSetLayout {
         &self.descriptor_counts
     }
 
+    /// Returns the number of bindings with a type of [`DescriptorType::InlineUniformBlock`].
+    ///
+    /// Descriptor pools that allocate sets with this layout must reserve this many inline uniform
+    /// block bindings per set, in addition to the bytes counted in `descriptor_counts`.
+    #[inline]
+    pub fn inline_uniform_block_binding_count(&self) -> u32 {
+        self.bindings
+            .values()
+            .filter(|binding| {
+                binding.descriptor_type == DescriptorType::InlineUniformBlock
+                    && binding.descriptor_count != 0
+            })
+            .count() as u32
+    }
+
     /// If the highest-numbered binding has a variable count, returns its `descriptor_count`.
     /// Otherwise returns `0`.
     #[inline]
@@ -411,6 +627,30 @@ impl DescriptorSetLayout {
             .unwrap_or(0)
     }
 
+    // Returns the number of descriptors of each type that are allocated for a set with this
+    // layout and the given variable descriptor count. Types with a count of `0` are left out.
+    pub(crate) fn allocated_descriptor_counts(
+        &self,
+        variable_descriptor_count: u32,
+    ) -> HashMap<DescriptorType, u32> {
+        let mut descriptor_counts = self.descriptor_counts.clone();
+
+        // Only the requested number of descriptors is allocated for a variable-count binding.
+        if let Some(binding) = self
+            .bindings
+            .values()
+            .next_back()
+            .filter(|binding| binding.variable_descriptor_count)
+        {
+            if let Some(count) = descriptor_counts.get_mut(&binding.descriptor_type) {
+                *count -= binding.descriptor_count - variable_descriptor_count;
+            }
+        }
+
+        descriptor_counts.retain(|_, &mut count| count != 0);
+        descriptor_counts
+    }
+
     /// Returns whether `self` is compatible with `other`.
     ///
     /// "Compatible" in this sense is defined by the Vulkan specification under the section
@@ -419,7 +659,9 @@ impl DescriptorSetLayout {
     #[inline]
     pub fn is_compatible_with(&self, other: &DescriptorSetLayout) -> bool {
         self == other
-            || (self.bindings == other.bindings && self.push_descriptor == other.push_descriptor)
+            || (self.bindings == other.bindings
+                && self.push_descriptor == other.push_descriptor
+                && self.update_after_bind_pool == other.update_after_bind_pool)
     }
 }
 
@@ -490,6 +732,20 @@ pub enum DescriptorSetLayoutCreationError {
     /// A binding includes immutable samplers but it has an incompatible `descriptor_type`.
     ImmutableSamplersDescriptorTypeIncompatible { binding_num: u32 },
 
+    /// A binding has a `descriptor_type` of [`DescriptorType::InlineUniformBlock`], but its
+    /// `descriptor_count` is not a multiple of 4.
+    InlineUniformBlockSizeNotMultipleOf4 { binding_num: u32 },
+
+    /// A binding has a `descriptor_type` of [`DescriptorType::InlineUniformBlock`], but its
+    /// `descriptor_count` is greater than the
+    /// [`max_inline_uniform_block_size`](crate::device::Properties::max_inline_uniform_block_size)
+    /// limit.
+    MaxInlineUniformBlockSizeExceeded {
+        binding_num: u32,
+        provided: u32,
+        max_supported: u32,
+    },
+
     /// More descriptors were provided in all bindings than the
     /// [`max_push_descriptors`](crate::device::Properties::max_push_descriptors) limit.
     MaxPushDescriptorsExceeded { provided: u32, max_supported: u32 },
@@ -497,9 +753,19 @@ pub enum DescriptorSetLayoutCreationError {
     /// `push_descriptor` is enabled, but a binding has an incompatible `descriptor_type`.
     PushDescriptorDescriptorTypeIncompatible { binding_num: u32 },
 
+    /// `push_descriptor` is enabled, but a binding has `update_after_bind` or
+    /// `update_unused_while_pending` enabled.
+    PushDescriptorUpdateAfterBind { binding_num: u32 },
+
     /// `push_descriptor` is enabled, but a binding has `variable_descriptor_count` enabled.
     PushDescriptorVariableDescriptorCount { binding_num: u32 },
 
+    /// A binding has `update_after_bind` enabled, but it has an incompatible `descriptor_type`.
+    UpdateAfterBindDescriptorTypeIncompatible { binding_num: u32 },
+
+    /// A binding has `update_after_bind` enabled, but `update_after_bind_pool` is not enabled.
+    UpdateAfterBindWithoutUpdateAfterBindPool { binding_num: u32 },
+
     /// A binding has `variable_descriptor_count` enabled, but it is not the highest-numbered
     /// binding.
     VariableDescriptorCountBindingNotHighest {
@@ -550,6 +816,22 @@ impl Display for DescriptorSetLayoutCreationError {
                 `descriptor_type`",
                 binding_num,
             ),
+            Self::InlineUniformBlockSizeNotMultipleOf4 { binding_num } => write!(
+                f,
+                "binding {} is an inline uniform block, but its `descriptor_count` is not a \
+                multiple of 4",
+                binding_num,
+            ),
+            Self::MaxInlineUniformBlockSizeExceeded {
+                binding_num,
+                provided,
+                max_supported,
+            } => write!(
+                f,
+                "binding {} is an inline uniform block, but its `descriptor_count` ({}) is \
+                greater than the `max_inline_uniform_block_size` limit ({})",
+                binding_num, provided, max_supported,
+            ),
             Self::MaxPushDescriptorsExceeded {
                 provided,
                 max_supported,
@@ -565,12 +847,30 @@ impl Display for DescriptorSetLayoutCreationError {
                 `descriptor_type`",
                 binding_num,
             ),
+            Self::PushDescriptorUpdateAfterBind { binding_num } => write!(
+                f,
+                "`push_descriptor` is enabled, but binding {} has `update_after_bind` or \
+                `update_unused_while_pending` enabled",
+                binding_num,
+            ),
             Self::PushDescriptorVariableDescriptorCount { binding_num } => write!(
                 f,
                 "`push_descriptor` is enabled, but binding {} has `variable_descriptor_count` \
                 enabled",
                 binding_num,
             ),
+            Self::UpdateAfterBindDescriptorTypeIncompatible { binding_num } => write!(
+                f,
+                "binding {} has `update_after_bind` enabled, but it has an incompatible \
+                `descriptor_type`",
+                binding_num,
+            ),
+            Self::UpdateAfterBindWithoutUpdateAfterBindPool { binding_num } => write!(
+                f,
+                "binding {} has `update_after_bind` enabled, but `update_after_bind_pool` is not \
+                enabled",
+                binding_num,
+            ),
             Self::VariableDescriptorCountBindingNotHighest {
                 binding_num,
                 highest_binding_num,
@@ -627,6 +927,23 @@ pub struct DescriptorSetLayoutCreateInfo {
     /// The default value is `false`.
     pub push_descriptor: bool,
 
+    /// Whether descriptor sets with this layout must be allocated from a descriptor pool that was
+    /// created with [`update_after_bind`] enabled.
+    ///
+    /// This must be set to `true` if any of the bindings has `update_after_bind` enabled. If set
+    /// to `true`, the device API version must be at least 1.2, or the
+    /// [`ext_descriptor_indexing`](crate::device::DeviceExtensions::ext_descriptor_indexing)
+    /// extension must be enabled on the device.
+    ///
+    /// Pipeline layouts that include an update-after-bind set layout are checked against the
+    /// `max_per_stage_descriptor_update_after_bind_*` and
+    /// `max_descriptor_set_update_after_bind_*` limits instead of the regular limits.
+    ///
+    /// The default value is `false`.
+    ///
+    /// [`update_after_bind`]: crate::descriptor_set::pool::UnsafeDescriptorPoolCreateInfo::update_after_bind
+    pub update_after_bind_pool: bool,
+
     pub _ne: crate::NonExhaustive,
 }
 
@@ -636,6 +953,7 @@ impl Default for DescriptorSetLayoutCreateInfo {
         Self {
             bindings: BTreeMap::new(),
             push_descriptor: false,
+            update_after_bind_pool: false,
             _ne: crate::NonExhaustive(()),
         }
     }
@@ -676,6 +994,11 @@ pub struct DescriptorSetLayoutBinding {
     ///
     /// If the binding is a single element rather than an array, then you must specify `1`.
     ///
+    /// If `descriptor_type` is [`DescriptorType::InlineUniformBlock`], this is instead the size
+    /// of the block in bytes, which must be a multiple of 4 and no greater than the
+    /// [`max_inline_uniform_block_size`](crate::device::Properties::max_inline_uniform_block_size)
+    /// limit.
+    ///
     /// The default value is `1`.
     pub descriptor_count: u32,
 
@@ -694,6 +1017,40 @@ pub struct DescriptorSetLayoutBinding {
     /// [`descriptor_binding_variable_descriptor_count`]: crate::device::Features::descriptor_binding_variable_descriptor_count
     pub variable_descriptor_count: bool,
 
+    /// Whether descriptors in the binding may be left unwritten, as long as they are not
+    /// dynamically accessed by a shader.
+    ///
+    /// If set to `true`, the [`descriptor_binding_partially_bound`] feature must be enabled.
+    /// Vulkano will then no longer report an error for array elements that have not been written
+    /// to when executing a pipeline; it is up to the shader not to access them.
+    ///
+    /// The default value is `false`.
+    ///
+    /// [`descriptor_binding_partially_bound`]: crate::device::Features::descriptor_binding_partially_bound
+    pub partially_bound: bool,
+
+    /// Whether descriptors in the binding can be updated after the descriptor set has been bound
+    /// in a command buffer, without invalidating the command buffer.
+    ///
+    /// If set to `true`, `update_after_bind_pool` must be enabled on the layout, and the
+    /// `descriptor_binding_*_update_after_bind` feature corresponding to `descriptor_type` must be
+    /// enabled. The `descriptor_type` must not be [`DescriptorType::UniformBufferDynamic`],
+    /// [`DescriptorType::StorageBufferDynamic`] or [`DescriptorType::InputAttachment`].
+    ///
+    /// The default value is `false`.
+    pub update_after_bind: bool,
+
+    /// Whether descriptors in the binding that are not used by a pending command buffer can be
+    /// updated while the command buffer is executing.
+    ///
+    /// If set to `true`, the [`descriptor_binding_update_unused_while_pending`] feature must be
+    /// enabled.
+    ///
+    /// The default value is `false`.
+    ///
+    /// [`descriptor_binding_update_unused_while_pending`]: crate::device::Features::descriptor_binding_update_unused_while_pending
+    pub update_unused_while_pending: bool,
+
     /// Which shader stages are going to access the descriptors in this binding.
     ///
     /// The default value is [`ShaderStages::empty()`], which must be overridden.
@@ -721,6 +1078,9 @@ impl DescriptorSetLayoutBinding {
             descriptor_type,
             descriptor_count: 1,
             variable_descriptor_count: false,
+            partially_bound: false,
+            update_after_bind: false,
+            update_unused_while_pending: false,
             stages: ShaderStages::empty(),
             immutable_samplers: Vec::new(),
             _ne: crate::NonExhaustive(()),
@@ -785,6 +1145,9 @@ impl From<&DescriptorRequirements> for DescriptorSetLayoutBinding {
             descriptor_type: reqs.descriptor_types[0],
             descriptor_count: reqs.descriptor_count.unwrap_or(0),
             variable_descriptor_count: false,
+            partially_bound: false,
+            update_after_bind: false,
+            update_unused_while_pending: false,
             stages: reqs.stages,
             immutable_samplers: Vec::new(),
             _ne: crate::NonExhaustive(()),
@@ -879,13 +1242,18 @@ vulkan_enum! {
     /// pixel that is currently being processed by the fragment shader.
     InputAttachment = INPUT_ATTACHMENT,
 
-    /*
-    // TODO: document
+    /// Gives read-only access to data stored directly in the descriptor set, interpreted as a
+    /// structure. No buffer is needed; the data is written with
+    /// [`WriteDescriptorSet::inline_uniform_block`](crate::descriptor_set::WriteDescriptorSet::inline_uniform_block).
+    ///
+    /// For bindings of this type, `descriptor_count` is the size of the block in bytes rather
+    /// than the number of array elements.
     InlineUniformBlock = INLINE_UNIFORM_BLOCK {
         api_version: V1_3,
         device_extensions: [ext_inline_uniform_block],
     },
 
+    /*
     // TODO: document
     AccelerationStructure = ACCELERATION_STRUCTURE_KHR {
         device_extensions: [khr_acceleration_structure],
@@ -908,7 +1276,7 @@ mod tests {
     use crate::{
         descriptor_set::layout::{
             DescriptorSetLayout, DescriptorSetLayoutBinding, DescriptorSetLayoutCreateInfo,
-            DescriptorType,
+            DescriptorSetLayoutCreationError, DescriptorType,
         },
         shader::ShaderStages,
     };
@@ -947,4 +1315,62 @@ mod tests {
                 .collect::<HashMap<_, _>>(),
         );
     }
+
+    #[test]
+    fn update_after_bind_without_pool() {
+        let (device, _) = gfx_dev_and_queue!();
+
+        let result = DescriptorSetLayout::new(
+            device,
+            DescriptorSetLayoutCreateInfo {
+                bindings: [(
+                    0,
+                    DescriptorSetLayoutBinding {
+                        update_after_bind: true,
+                        stages: ShaderStages::all_graphics(),
+                        ..DescriptorSetLayoutBinding::descriptor_type(DescriptorType::SampledImage)
+                    },
+                )]
+                .into(),
+                ..Default::default()
+            },
+        );
+
+        assert!(matches!(
+            result,
+            Err(
+                DescriptorSetLayoutCreationError::UpdateAfterBindWithoutUpdateAfterBindPool {
+                    binding_num: 0
+                }
+            )
+        ));
+    }
+
+    #[test]
+    fn inline_uniform_block_without_feature() {
+        let (device, _) = gfx_dev_and_queue!();
+
+        let result = DescriptorSetLayout::new(
+            device,
+            DescriptorSetLayoutCreateInfo {
+                bindings: [(
+                    0,
+                    DescriptorSetLayoutBinding {
+                        descriptor_count: 16,
+                        stages: ShaderStages::all_graphics(),
+                        ..DescriptorSetLayoutBinding::descriptor_type(
+                            DescriptorType::InlineUniformBlock,
+                        )
+                    },
+                )]
+                .into(),
+                ..Default::default()
+            },
+        );
+
+        assert!(matches!(
+            result,
+            Err(DescriptorSetLayoutCreationError::RequirementNotMet { .. })
+        ));
+    }
 }
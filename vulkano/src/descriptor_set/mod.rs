@@ -66,6 +66,8 @@
 //!   descriptor sets. However it is different from Vulkan descriptor pools in the sense that an
 //!   implementation of the `DescriptorPool` trait can manage multiple Vulkan descriptor pools.
 //! - The `StandardDescriptorPool` type is a default implementation of the `DescriptorPool` trait.
+//! - The `FrameDescriptorPool` type is an implementation of the `DescriptorPool` trait that
+//!   allocates sets linearly and recycles whole Vulkan pools from one frame to the next.
 //! - The `DescriptorSet` trait is implemented on types that wrap around Vulkan descriptor sets in
 //!   a safe way. A Vulkan descriptor set is inherently unsafe, so we need safe wrappers around
 //!   them.
@@ -73,39 +75,52 @@
 //! - The `DescriptorSetsCollection` trait is implemented on collections of types that implement
 //!   `DescriptorSet`. It is what you pass to the draw functions.
 
-pub(crate) use self::update::{check_descriptor_write, DescriptorWriteInfo};
+pub(crate) use self::update::{check_descriptor_copy, check_descriptor_write, DescriptorWriteInfo};
 pub use self::{
     collection::DescriptorSetsCollection,
     persistent::PersistentDescriptorSet,
     single_layout_pool::SingleLayoutDescSetPool,
-    update::{DescriptorSetUpdateError, WriteDescriptorSet, WriteDescriptorSetElements},
+    updatable::UpdatableDescriptorSet,
+    update::{
+        CopyDescriptorSet, DescriptorSetUpdateError, WriteDescriptorSet, WriteDescriptorSetElements,
+    },
+};
+use self::{
+    layout::DescriptorSetLayout,
+    sys::UnsafeDescriptorSet,
+    update_template::{DescriptorUpdateTemplateData, DescriptorUpdateTemplateType},
 };
-use self::{layout::DescriptorSetLayout, sys::UnsafeDescriptorSet};
 use crate::{
     buffer::{view::BufferViewAbstract, BufferAccess},
     descriptor_set::layout::DescriptorType,
     device::DeviceOwned,
     image::view::ImageViewAbstract,
     sampler::Sampler,
-    OomError, VulkanObject,
+    DeviceSize, OomError, Version, VulkanObject,
 };
 use ahash::HashMap;
+use ash::vk::Handle;
+use parking_lot::{RwLock, RwLockReadGuard};
 use smallvec::{smallvec, SmallVec};
 use std::{
     error::Error,
     fmt::{Display, Error as FmtError, Formatter},
     hash::{Hash, Hasher},
+    ops::Range,
     ptr,
     sync::Arc,
 };
 
+pub mod bindless;
 mod collection;
 pub mod layout;
 pub mod persistent;
 pub mod pool;
 pub mod single_layout_pool;
 pub mod sys;
+pub mod updatable;
 mod update;
+pub mod update_template;
 
 /// Trait for objects that contain a collection of resources that will be accessible by shaders.
 ///
@@ -129,7 +144,37 @@ pub unsafe trait DescriptorSet: DeviceOwned + Send + Sync {
     }
 
     /// Returns the resources bound to this descriptor set.
-    fn resources(&self) -> &DescriptorSetResources;
+    ///
+    /// The returned guard prevents the descriptor set from being updated while it is held.
+    fn resources(&self) -> RwLockReadGuard<'_, DescriptorSetResources>;
+
+    /// Returns the name of the descriptor set, if it has one.
+    #[inline]
+    fn name(&self) -> Option<String> {
+        self.inner().name()
+    }
+
+    /// Sets or removes the name of the descriptor set.
+    ///
+    /// If the `ext_debug_utils` extension is enabled on the instance, the name is also assigned to
+    /// the descriptor set with [`Device::set_debug_utils_object_name`], so that debugging tools
+    /// show it.
+    ///
+    /// [`Device::set_debug_utils_object_name`]: crate::device::Device::set_debug_utils_object_name
+    #[inline]
+    fn set_name(&self, name: Option<&str>) -> Result<(), OomError> {
+        unsafe {
+            self.device().set_debug_utils_object_name_if_enabled(
+                ash::vk::ObjectType::DESCRIPTOR_SET,
+                self.inner().internal_object().as_raw(),
+                name,
+            )?;
+        }
+
+        self.inner().store_name(name);
+
+        Ok(())
+    }
 }
 
 impl PartialEq for dyn DescriptorSet {
@@ -151,7 +196,8 @@ impl Hash for dyn DescriptorSet {
 
 pub(crate) struct DescriptorSetInner {
     layout: Arc<DescriptorSetLayout>,
-    resources: DescriptorSetResources,
+    variable_descriptor_count: u32,
+    resources: RwLock<DescriptorSetResources>,
 }
 
 impl DescriptorSetInner {
@@ -179,16 +225,18 @@ impl DescriptorSetInner {
 
         let mut resources = DescriptorSetResources::new(&layout, variable_descriptor_count);
 
-        let descriptor_writes = descriptor_writes.into_iter();
-        let (lower_size_bound, _) = descriptor_writes.size_hint();
-        let mut descriptor_write_info: SmallVec<[_; 8]> = SmallVec::with_capacity(lower_size_bound);
-        let mut write_descriptor_set: SmallVec<[_; 8]> = SmallVec::with_capacity(lower_size_bound);
+        // The writes are kept alive until the update, because the infos of inline uniform blocks
+        // point into them.
+        let descriptor_writes: SmallVec<[_; 8]> = descriptor_writes.into_iter().collect();
+        let mut descriptor_write_info: SmallVec<[_; 8]> =
+            SmallVec::with_capacity(descriptor_writes.len());
+        let mut write_descriptor_set: SmallVec<[_; 8]> =
+            SmallVec::with_capacity(descriptor_writes.len());
 
-        for write in descriptor_writes {
-            let layout_binding =
-                check_descriptor_write(&write, &layout, variable_descriptor_count)?;
+        for write in &descriptor_writes {
+            let layout_binding = check_descriptor_write(write, &layout, variable_descriptor_count)?;
 
-            resources.update(&write);
+            resources.update(write);
             descriptor_write_info.push(write.to_vulkan_info(layout_binding.descriptor_type));
             write_descriptor_set.push(write.to_vulkan(handle, layout_binding.descriptor_type));
         }
@@ -211,6 +259,10 @@ impl DescriptorSetInner {
                         write.descriptor_count = info.len() as u32;
                         write.p_texel_buffer_view = info.as_ptr();
                     }
+                    DescriptorWriteInfo::InlineUniformBlock(info) => {
+                        write.descriptor_count = info.data_size;
+                        write.p_next = info as *const _ as *const _;
+                    }
                 }
             }
         }
@@ -227,15 +279,184 @@ impl DescriptorSetInner {
             );
         }
 
-        Ok(DescriptorSetInner { layout, resources })
+        Ok(DescriptorSetInner {
+            layout,
+            variable_descriptor_count,
+            resources: RwLock::new(resources),
+        })
+    }
+
+    /// Validates and performs descriptor writes and copies on the descriptor set `handle`, which
+    /// must have been created together with `self`.
+    ///
+    /// # Safety
+    ///
+    /// - The descriptor set must not be bound in a command buffer that has not yet been submitted,
+    ///   unless all the updated bindings were created with `update_after_bind`.
+    pub(crate) unsafe fn update(
+        &self,
+        handle: ash::vk::DescriptorSet,
+        descriptor_writes: impl IntoIterator<Item = WriteDescriptorSet>,
+        descriptor_copies: impl IntoIterator<Item = CopyDescriptorSet>,
+    ) -> Result<(), DescriptorSetUpdateError> {
+        let descriptor_writes: SmallVec<[_; 8]> = descriptor_writes.into_iter().collect();
+        let descriptor_copies: SmallVec<[_; 8]> = descriptor_copies.into_iter().collect();
+
+        for write in &descriptor_writes {
+            check_descriptor_write(write, &self.layout, self.variable_descriptor_count)?;
+        }
+
+        for copy in &descriptor_copies {
+            check_descriptor_copy(copy, handle, &self.layout, self.variable_descriptor_count)?;
+        }
+
+        // Take the source resources before locking our own, since a copy can read from the same
+        // descriptor set that it writes to.
+        let copy_sources: SmallVec<[_; 8]> = descriptor_copies
+            .iter()
+            .map(|copy| {
+                copy.src_set
+                    .resources()
+                    .binding(copy.src_binding)
+                    .unwrap()
+                    .clone()
+            })
+            .collect();
+
+        let mut resources = self.resources.write();
+
+        // VUID-vkUpdateDescriptorSets-None-03047
+        self.check_in_use(
+            &resources,
+            descriptor_writes
+                .iter()
+                .map(|write| write.binding())
+                .chain(descriptor_copies.iter().map(|copy| copy.dst_binding)),
+        )?;
+
+        UnsafeDescriptorSet::new(handle).update(
+            &self.layout,
+            descriptor_writes.iter(),
+            descriptor_copies.iter(),
+        );
+
+        for write in &descriptor_writes {
+            resources.update(write);
+        }
+
+        for (copy, src_resources) in descriptor_copies.iter().zip(&copy_sources) {
+            resources.copy(copy, src_resources);
+        }
+
+        Ok(())
+    }
+
+    /// Performs the update described by `data` on the descriptor set `handle`, which must have
+    /// been created together with `self`.
+    ///
+    /// # Safety
+    ///
+    /// - Same as [`update`](Self::update).
+    pub(crate) unsafe fn update_with_template(
+        &self,
+        handle: ash::vk::DescriptorSet,
+        data: &DescriptorUpdateTemplateData,
+    ) -> Result<(), DescriptorSetUpdateError> {
+        let template = data.template();
+
+        assert!(
+            matches!(
+                template.template_type(),
+                DescriptorUpdateTemplateType::DescriptorSet
+            ),
+            "the provided descriptor update template is for push descriptors, and cannot be used \
+            to update a descriptor set",
+        );
+        assert!(
+            self.layout.is_compatible_with(template.layout()),
+            "the provided descriptor update template was created with a layout that is not \
+            compatible with the layout of the descriptor set",
+        );
+
+        // The writes were validated against the largest variable descriptor count that the layout
+        // allows, so only the range needs to be checked again.
+        for write in data.descriptor_writes() {
+            let layout_binding = &self.layout.bindings()[&write.binding()];
+
+            if layout_binding.variable_descriptor_count {
+                let written_count = write.first_array_element() + write.elements().len();
+
+                if written_count > self.variable_descriptor_count {
+                    return Err(DescriptorSetUpdateError::ArrayIndexOutOfBounds {
+                        binding: write.binding(),
+                        available_count: self.variable_descriptor_count,
+                        written_count,
+                    });
+                }
+            }
+        }
+
+        let mut resources = self.resources.write();
+
+        self.check_in_use(
+            &resources,
+            data.descriptor_writes().iter().map(|write| write.binding()),
+        )?;
+
+        let device = self.layout.device();
+        let fns = device.fns();
+        let update_descriptor_set_with_template = if device.api_version() >= Version::V1_1 {
+            fns.v1_1.update_descriptor_set_with_template
+        } else {
+            fns.khr_descriptor_update_template
+                .update_descriptor_set_with_template_khr
+        };
+
+        update_descriptor_set_with_template(
+            device.internal_object(),
+            handle,
+            template.internal_object(),
+            data.as_ptr(),
+        );
+
+        for write in data.descriptor_writes() {
+            resources.update(write);
+        }
+
+        Ok(())
+    }
+
+    // A command buffer that is pending execution keeps the resources that it uses from this set
+    // locked, so if any of the resources in an updated binding are locked by the GPU, the binding
+    // may be in use.
+    fn check_in_use(
+        &self,
+        resources: &DescriptorSetResources,
+        bindings: impl IntoIterator<Item = u32>,
+    ) -> Result<(), DescriptorSetUpdateError> {
+        for binding_num in bindings {
+            let layout_binding = &self.layout.bindings()[&binding_num];
+
+            if layout_binding.update_after_bind || layout_binding.update_unused_while_pending {
+                continue;
+            }
+
+            if resources.binding(binding_num).unwrap().is_gpu_locked() {
+                return Err(DescriptorSetUpdateError::InUse {
+                    binding: binding_num,
+                });
+            }
+        }
+
+        Ok(())
     }
 
     pub(crate) fn layout(&self) -> &Arc<DescriptorSetLayout> {
         &self.layout
     }
 
-    pub(crate) fn resources(&self) -> &DescriptorSetResources {
-        &self.resources
+    pub(crate) fn resources(&self) -> RwLockReadGuard<'_, DescriptorSetResources> {
+        self.resources.read()
     }
 }
 
@@ -297,6 +518,11 @@ impl DescriptorSetResources {
                             DescriptorBindingResources::None(smallvec![Some(()); count])
                         }
                     }
+                    DescriptorType::InlineUniformBlock => {
+                        // The data is stored in the descriptor set itself, so there are no
+                        // resources to keep. Each byte is tracked to know whether it was written.
+                        DescriptorBindingResources::None(smallvec![None; count])
+                    }
                 };
                 (binding_num, binding_resources)
             })
@@ -319,6 +545,21 @@ impl DescriptorSetResources {
             .update(write)
     }
 
+    /// Applies a descriptor copy to the resources, taking the copied elements from
+    /// `src_resources`, which must be the resources of the source binding of the copy.
+    ///
+    /// # Panics
+    ///
+    /// - Panics if the destination binding number of the copy does not exist in the resources.
+    /// - See also [`DescriptorBindingResources::copy`].
+    #[inline]
+    pub fn copy(&mut self, copy: &CopyDescriptorSet, src_resources: &DescriptorBindingResources) {
+        self.binding_resources
+            .get_mut(&copy.dst_binding)
+            .expect("descriptor copy has invalid destination binding number")
+            .copy(copy, src_resources)
+    }
+
     /// Returns a reference to the bound resources for `binding`. Returns `None` if the binding
     /// doesn't exist.
     #[inline]
@@ -365,10 +606,11 @@ impl DescriptorBindingResources {
         match (self, write.elements()) {
             (
                 DescriptorBindingResources::None(resources),
-                WriteDescriptorSetElements::None(num_elements),
+                elements @ (WriteDescriptorSetElements::None(_)
+                | WriteDescriptorSetElements::InlineUniformBlock(_)),
             ) => {
                 resources
-                    .get_mut(first..first + *num_elements as usize)
+                    .get_mut(first..first + elements.len() as usize)
                     .expect("descriptor write for binding out of bounds")
                     .iter_mut()
                     .for_each(|resource| {
@@ -401,6 +643,124 @@ impl DescriptorBindingResources {
             ),
         }
     }
+
+    /// Applies a descriptor copy to the resources, taking the copied elements from
+    /// `src_resources`, which must be the resources of the source binding of the copy.
+    ///
+    /// # Panics
+    ///
+    /// - Panics if the resource types do not match.
+    /// - Panics if the copy goes out of bounds.
+    #[inline]
+    pub fn copy(&mut self, copy: &CopyDescriptorSet, src_resources: &DescriptorBindingResources) {
+        fn copy_resources<T: Clone>(
+            copy: &CopyDescriptorSet,
+            resources: &mut [Option<T>],
+            src_resources: &[Option<T>],
+        ) {
+            let src_first = copy.src_first_array_element as usize;
+            let dst_first = copy.dst_first_array_element as usize;
+            let count = copy.descriptor_count as usize;
+
+            resources
+                .get_mut(dst_first..dst_first + count)
+                .expect("descriptor copy for destination binding out of bounds")
+                .clone_from_slice(
+                    src_resources
+                        .get(src_first..src_first + count)
+                        .expect("descriptor copy for source binding out of bounds"),
+                );
+        }
+
+        match (self, src_resources) {
+            (
+                DescriptorBindingResources::None(resources),
+                DescriptorBindingResources::None(src_resources),
+            ) => copy_resources(copy, resources, src_resources),
+            (
+                DescriptorBindingResources::Buffer(resources),
+                DescriptorBindingResources::Buffer(src_resources),
+            ) => copy_resources(copy, resources, src_resources),
+            (
+                DescriptorBindingResources::BufferView(resources),
+                DescriptorBindingResources::BufferView(src_resources),
+            ) => copy_resources(copy, resources, src_resources),
+            (
+                DescriptorBindingResources::ImageView(resources),
+                DescriptorBindingResources::ImageView(src_resources),
+            ) => copy_resources(copy, resources, src_resources),
+            (
+                DescriptorBindingResources::ImageViewSampler(resources),
+                DescriptorBindingResources::ImageViewSampler(src_resources),
+            ) => copy_resources(copy, resources, src_resources),
+            (
+                DescriptorBindingResources::Sampler(resources),
+                DescriptorBindingResources::Sampler(src_resources),
+            ) => copy_resources(copy, resources, src_resources),
+            _ => panic!(
+                "descriptor copy from binding {} to binding {} has mismatched resource types",
+                copy.src_binding, copy.dst_binding,
+            ),
+        }
+    }
+
+    /// Returns the number of descriptors in the binding.
+    pub(crate) fn len(&self) -> u32 {
+        (match self {
+            Self::None(elements) => elements.len(),
+            Self::Buffer(elements) => elements.len(),
+            Self::BufferView(elements) => elements.len(),
+            Self::ImageView(elements) => elements.len(),
+            Self::ImageViewSampler(elements) => elements.len(),
+            Self::Sampler(elements) => elements.len(),
+        }) as u32
+    }
+
+    /// Returns whether any of the buffers or images in the binding are currently locked by a
+    /// queue operation.
+    pub(crate) fn is_gpu_locked(&self) -> bool {
+        fn buffer_gpu_locked(buffer: &dyn BufferAccess, range: Range<DeviceSize>) -> bool {
+            let inner = buffer.inner();
+            let range = range.start + inner.offset..range.end + inner.offset;
+            let state = inner.buffer.state();
+            state.is_gpu_locked(range)
+        }
+
+        fn image_view_gpu_locked(image_view: &dyn ImageViewAbstract) -> bool {
+            let image = image_view.image();
+            let inner = image.inner();
+            let mut subresource_range = image_view.subresource_range().clone();
+            subresource_range.array_layers.start += inner.first_layer;
+            subresource_range.array_layers.end += inner.first_layer;
+            subresource_range.mip_levels.start += inner.first_mipmap_level;
+            subresource_range.mip_levels.end += inner.first_mipmap_level;
+
+            let state = inner.image.state();
+            inner
+                .image
+                .iter_ranges(subresource_range)
+                .any(|range| state.is_gpu_locked(range))
+        }
+
+        match self {
+            Self::None(_) | Self::Sampler(_) => false,
+            Self::Buffer(elements) => elements
+                .iter()
+                .flatten()
+                .any(|buffer| buffer_gpu_locked(buffer.as_ref(), 0..buffer.size())),
+            Self::BufferView(elements) => elements.iter().flatten().any(|buffer_view| {
+                buffer_gpu_locked(buffer_view.buffer().as_ref(), buffer_view.range())
+            }),
+            Self::ImageView(elements) => elements
+                .iter()
+                .flatten()
+                .any(|image_view| image_view_gpu_locked(image_view.as_ref())),
+            Self::ImageViewSampler(elements) => elements
+                .iter()
+                .flatten()
+                .any(|(image_view, _)| image_view_gpu_locked(image_view.as_ref())),
+        }
+    }
 }
 
 #[derive(Clone)]
@@ -31,6 +31,7 @@ use crate::{
     device::{Device, DeviceOwned},
     VulkanObject,
 };
+use parking_lot::RwLockReadGuard;
 use std::{
     hash::{Hash, Hasher},
     sync::Arc,
@@ -59,6 +60,21 @@ impl PersistentDescriptorSet {
             })
     }
 
+    /// Same as `new`, but also gives the descriptor set a name.
+    ///
+    /// See [`DescriptorSet::set_name`] for how the name is used.
+    #[inline]
+    pub fn new_named(
+        layout: Arc<DescriptorSetLayout>,
+        descriptor_writes: impl IntoIterator<Item = WriteDescriptorSet>,
+        name: &str,
+    ) -> Result<Arc<PersistentDescriptorSet>, DescriptorSetCreationError> {
+        let set = Self::new(layout, descriptor_writes)?;
+        set.set_name(Some(name))?;
+
+        Ok(set)
+    }
+
     /// Creates and returns a new descriptor set with the requested variable descriptor count.
     ///
     /// See `new_with_pool` for more.
@@ -132,7 +148,7 @@ where
         self.inner.layout()
     }
 
-    fn resources(&self) -> &DescriptorSetResources {
+    fn resources(&self) -> RwLockReadGuard<'_, DescriptorSetResources> {
         self.inner.resources()
     }
 }
@@ -0,0 +1,663 @@
+// Copyright (c) 2022 The vulkano developers
+// Licensed under the Apache License, Version 2.0
+// <LICENSE-APACHE or
+// https://www.apache.org/licenses/LICENSE-2.0> or the MIT
+// license <LICENSE-MIT or https://opensource.org/licenses/MIT>,
+// at your option. All files in the project carrying such
+// notice may not be copied, modified, or distributed except
+// according to those terms.
+
+//! A descriptor pool that allocates descriptor sets linearly, one frame at a time.
+//!
+//! [`FrameDescriptorPool`] allocates sets one after the other from Vulkan descriptor pools that
+//! are created without `can_free_descriptor_sets`. Sets are never freed individually, and the
+//! allocations don't keep a reference to their Vulkan pool. Instead, the Vulkan pools that were
+//! used during a frame are reset as a whole once the GPU has finished executing that frame, and
+//! are then reused.
+//!
+//! Call [`next_frame`](FrameDescriptorPool::next_frame) once the command buffers of a frame have
+//! been submitted, with the [`FenceSignalFuture`] of that submission. The Vulkan pools of the
+//! frame are reset the next time the allocator needs a pool after the fence has been signaled.
+//!
+//! Because nothing keeps a Vulkan pool from being reset while a set that was allocated from it
+//! still exists, the sets of a frame must not be used once that frame has completed, or once the
+//! allocator has been dropped. This is why creating the allocator and ending a frame are unsafe.
+
+use super::{
+    DescriptorCountStatistics, DescriptorPool, DescriptorPoolAlloc, DescriptorPoolAllocError,
+    DescriptorSetAllocateInfo, UnsafeDescriptorPool, UnsafeDescriptorPoolCreateInfo,
+};
+use crate::{
+    descriptor_set::{
+        layout::{DescriptorSetLayout, DescriptorType},
+        sys::UnsafeDescriptorSet,
+    },
+    device::{Device, DeviceOwned},
+    sync::{FenceSignalFuture, FlushError, GpuFuture},
+    OomError,
+};
+use ahash::HashMap;
+use std::{
+    collections::VecDeque,
+    fmt::{Debug, Error as FmtError, Formatter},
+    sync::Arc,
+};
+
+// The maximum number of reset Vulkan pools that are kept for reuse.
+const MAX_POOLS: usize = 32;
+
+/// A descriptor pool that allocates descriptor sets linearly, and resets its Vulkan pools as a
+/// whole once the frame that used them has completed.
+///
+/// See [the module-level documentation](self) for more.
+#[derive(Debug)]
+pub struct FrameDescriptorPool {
+    device: Arc<Device>,
+    create_info: FrameDescriptorPoolCreateInfo,
+
+    // The Vulkan pools that sets were allocated from during the current frame, together with how
+    // much of each pool has been used. Sets are allocated from the last one.
+    frame_pools: Vec<(UnsafeDescriptorPool, PoolUsage)>,
+    // The Vulkan pools of frames that have ended, in the order of the frames, waiting for the GPU
+    // to finish executing them.
+    pending_frames: VecDeque<PendingFrame>,
+    // Vulkan pools that have been reset and can be reused.
+    reserve: Vec<UnsafeDescriptorPool>,
+}
+
+impl FrameDescriptorPool {
+    /// Creates a new `FrameDescriptorPool`.
+    ///
+    /// No Vulkan pool is created until the first descriptor set is allocated.
+    ///
+    /// # Panics
+    ///
+    /// - Panics if `create_info.max_sets` is `0`.
+    /// - Panics if `create_info.pool_sizes` is empty, or contains a descriptor type with a count
+    ///   of `0`.
+    ///
+    /// # Safety
+    ///
+    /// - The descriptor sets that are allocated from the allocator must not be used after the
+    ///   allocator has been dropped.
+    #[inline]
+    pub unsafe fn new(
+        device: Arc<Device>,
+        create_info: FrameDescriptorPoolCreateInfo,
+    ) -> FrameDescriptorPool {
+        assert!(create_info.max_sets != 0);
+        assert!(!create_info.pool_sizes.is_empty());
+        assert!(create_info.pool_sizes.values().all(|&count| count != 0));
+
+        FrameDescriptorPool {
+            device,
+            create_info,
+            frame_pools: Vec::new(),
+            pending_frames: VecDeque::new(),
+            reserve: Vec::new(),
+        }
+    }
+
+    /// Returns the parameters that the Vulkan pools of the allocator are created with.
+    #[inline]
+    pub fn create_info(&self) -> &FrameDescriptorPoolCreateInfo {
+        &self.create_info
+    }
+
+    /// Ends the current frame, and starts a new one.
+    ///
+    /// `future` must be the future that signals a fence once all the command buffers that use the
+    /// sets allocated during the current frame have finished executing, usually the one returned
+    /// by `then_signal_fence_and_flush` for the frame. The Vulkan pools that were used during the
+    /// current frame are reset once the fence is signaled. Sets allocated afterwards come from a
+    /// different Vulkan pool.
+    ///
+    /// # Safety
+    ///
+    /// - The descriptor sets that were allocated during the current frame must not be used by
+    ///   any command buffer that is not waited on by `future`, and must not be used at all once
+    ///   `future` has been signaled.
+    pub unsafe fn next_frame<F>(&mut self, future: Arc<FenceSignalFuture<F>>)
+    where
+        F: GpuFuture + Send + 'static,
+    {
+        if !self.frame_pools.is_empty() {
+            self.pending_frames.push_back(PendingFrame {
+                pools: self.frame_pools.drain(..).map(|(pool, _)| pool).collect(),
+                future,
+            });
+        }
+
+        self.reset_completed_frames();
+    }
+
+    /// Returns statistics about the descriptor sets that were allocated during the current frame.
+    pub fn statistics(&self) -> FrameDescriptorPoolStatistics {
+        let pool_count = self.frame_pools.len() as u32;
+        let mut descriptor_counts: HashMap<_, _> = self
+            .create_info
+            .pool_sizes
+            .iter()
+            .map(|(&ty, &count)| {
+                (
+                    ty,
+                    DescriptorCountStatistics {
+                        allocated: 0,
+                        capacity: count * pool_count,
+                    },
+                )
+            })
+            .collect();
+
+        for (_, usage) in &self.frame_pools {
+            for (&ty, &count) in &usage.descriptor_counts {
+                descriptor_counts
+                    .entry(ty)
+                    .or_insert_with(DescriptorCountStatistics::default)
+                    .allocated += count;
+            }
+        }
+
+        FrameDescriptorPoolStatistics {
+            pool_count,
+            allocated_sets: self
+                .frame_pools
+                .iter()
+                .map(|(_, usage)| usage.allocated_sets)
+                .sum(),
+            max_sets: self.create_info.max_sets * pool_count,
+            descriptor_counts,
+            pending_count: self
+                .pending_frames
+                .iter()
+                .map(|frame| frame.pools.len() as u32)
+                .sum(),
+            reserve_count: self.reserve.len() as u32,
+        }
+    }
+
+    // Resets the Vulkan pools of the frames whose fence has been signaled, and puts them in the
+    // reserve. Frames complete in order, so this stops at the first frame that hasn't completed.
+    fn reset_completed_frames(&mut self) {
+        while let Some(frame) = self.pending_frames.front() {
+            if !frame.future.is_signaled().unwrap_or(false) {
+                break;
+            }
+
+            let frame = self.pending_frames.pop_front().unwrap();
+
+            for mut pool in frame.pools {
+                // TODO: This should not return `Result`, resetting a pool can't fail.
+                unsafe { pool.reset() }.unwrap();
+
+                // If there is not enough space in the reserve, we destroy the pool.
+                if self.reserve.len() < MAX_POOLS {
+                    self.reserve.push(pool);
+                }
+            }
+        }
+    }
+
+    // Adds a Vulkan pool to the current frame, taken from the reserve or newly created. Sets are
+    // then allocated from this pool.
+    fn push_pool(&mut self) -> Result<(), OomError> {
+        if self.reserve.is_empty() {
+            self.reset_completed_frames();
+        }
+
+        let unsafe_pool = match self.reserve.pop() {
+            Some(unsafe_pool) => unsafe_pool,
+            None => UnsafeDescriptorPool::new(
+                self.device.clone(),
+                UnsafeDescriptorPoolCreateInfo {
+                    max_sets: self.create_info.max_sets,
+                    pool_sizes: self.create_info.pool_sizes.clone(),
+                    can_free_descriptor_sets: false,
+                    update_after_bind: self.create_info.update_after_bind,
+                    max_inline_uniform_block_bindings: self
+                        .create_info
+                        .max_inline_uniform_block_bindings,
+                    ..Default::default()
+                },
+            )?,
+        };
+
+        self.frame_pools.push((unsafe_pool, PoolUsage::default()));
+
+        Ok(())
+    }
+}
+
+impl Drop for FrameDescriptorPool {
+    fn drop(&mut self) {
+        // The GPU may still be using the sets of the frames that have ended, so we must wait
+        // before the Vulkan pools are destroyed.
+        for frame in &self.pending_frames {
+            let _ = frame.future.wait();
+        }
+    }
+}
+
+unsafe impl DescriptorPool for FrameDescriptorPool {
+    type Alloc = FrameDescriptorPoolAlloc;
+
+    /// Allocates a descriptor set from the current Vulkan pool, or from a new one if the current
+    /// pool does not have enough space left.
+    ///
+    /// # Panics
+    ///
+    /// - Panics if `layout` was created for push descriptors.
+    /// - Panics if `variable_descriptor_count` is too large for the given `layout`.
+    /// - Panics if `layout` has `update_after_bind_pool` enabled, but
+    ///   `create_info.update_after_bind` was not enabled.
+    /// - Panics if a set with the given `layout` requires more descriptors than a single Vulkan
+    ///   pool of the allocator can hold.
+    fn allocate(
+        &mut self,
+        layout: &Arc<DescriptorSetLayout>,
+        variable_descriptor_count: u32,
+    ) -> Result<FrameDescriptorPoolAlloc, OomError> {
+        assert!(
+            !layout.push_descriptor(),
+            "the provided descriptor set layout is for push descriptors, and cannot be used to \
+            build a descriptor set object",
+        );
+
+        let max_count = layout.variable_descriptor_count();
+
+        assert!(
+            variable_descriptor_count <= max_count,
+            "the provided variable_descriptor_count ({}) is greater than the maximum number of \
+            variable count descriptors in the set ({})",
+            variable_descriptor_count,
+            max_count,
+        );
+
+        assert!(
+            !layout.update_after_bind_pool() || self.create_info.update_after_bind,
+            "the provided descriptor set layout has `update_after_bind_pool` enabled, but the \
+            pool was not created with `update_after_bind` enabled",
+        );
+
+        let required = PoolUsage::for_set(layout, variable_descriptor_count);
+
+        assert!(
+            PoolUsage::default().can_fit(&required, &self.create_info),
+            "a descriptor set with the provided layout requires more descriptors than a single \
+            pool can hold",
+        );
+
+        if !self.frame_pools.last().map_or(false, |(_, usage)| {
+            usage.can_fit(&required, &self.create_info)
+        }) {
+            self.push_pool()?;
+        }
+
+        let (unsafe_pool, usage) = self.frame_pools.last_mut().unwrap();
+
+        let allocate_info = DescriptorSetAllocateInfo {
+            layout,
+            variable_descriptor_count,
+        };
+
+        let inner = match unsafe { unsafe_pool.allocate_descriptor_sets([allocate_info]) } {
+            Ok(mut sets) => sets.next().unwrap(),
+            Err(DescriptorPoolAllocError::OutOfHostMemory) => {
+                return Err(OomError::OutOfHostMemory);
+            }
+            Err(DescriptorPoolAllocError::OutOfDeviceMemory) => {
+                return Err(OomError::OutOfDeviceMemory);
+            }
+            Err(DescriptorPoolAllocError::FragmentedPool) => {
+                // This can't happen as we don't free individual sets.
+                unreachable!();
+            }
+            Err(DescriptorPoolAllocError::OutOfPoolMemory) => {
+                // We checked above that the set fits in the remaining space of the pool.
+                unreachable!();
+            }
+        };
+
+        usage.add(&required);
+
+        Ok(FrameDescriptorPoolAlloc { inner })
+    }
+}
+
+unsafe impl DeviceOwned for FrameDescriptorPool {
+    #[inline]
+    fn device(&self) -> &Arc<Device> {
+        &self.device
+    }
+}
+
+/// Parameters to create a new `FrameDescriptorPool`.
+///
+/// These parameters apply to each Vulkan pool that the allocator creates.
+#[derive(Clone, Debug)]
+pub struct FrameDescriptorPoolCreateInfo {
+    /// The maximum number of descriptor sets that can be allocated from each Vulkan pool.
+    ///
+    /// The default value is `256`.
+    pub max_sets: u32,
+
+    /// The number of descriptors of each type that can be allocated from each Vulkan pool.
+    ///
+    /// The default value contains `1024` descriptors of each type that is available in Vulkan
+    /// 1.0.
+    pub pool_sizes: HashMap<DescriptorType, u32>,
+
+    /// Whether descriptor sets whose layout was created with `update_after_bind_pool` enabled
+    /// can be allocated.
+    ///
+    /// See [`UnsafeDescriptorPoolCreateInfo::update_after_bind`] for the requirements.
+    ///
+    /// The default value is `false`.
+    pub update_after_bind: bool,
+
+    /// The maximum number of inline uniform block bindings that can be allocated from each
+    /// Vulkan pool.
+    ///
+    /// See [`UnsafeDescriptorPoolCreateInfo::max_inline_uniform_block_bindings`] for the
+    /// requirements.
+    ///
+    /// The default value is `0`.
+    pub max_inline_uniform_block_bindings: u32,
+
+    pub _ne: crate::NonExhaustive,
+}
+
+impl Default for FrameDescriptorPoolCreateInfo {
+    #[inline]
+    fn default() -> Self {
+        Self {
+            max_sets: 256,
+            pool_sizes: [
+                DescriptorType::Sampler,
+                DescriptorType::CombinedImageSampler,
+                DescriptorType::SampledImage,
+                DescriptorType::StorageImage,
+                DescriptorType::UniformTexelBuffer,
+                DescriptorType::StorageTexelBuffer,
+                DescriptorType::UniformBuffer,
+                DescriptorType::StorageBuffer,
+                DescriptorType::UniformBufferDynamic,
+                DescriptorType::StorageBufferDynamic,
+                DescriptorType::InputAttachment,
+            ]
+            .into_iter()
+            .map(|ty| (ty, 1024))
+            .collect(),
+            update_after_bind: false,
+            max_inline_uniform_block_bindings: 0,
+            _ne: crate::NonExhaustive(()),
+        }
+    }
+}
+
+/// Statistics about the descriptor sets that a `FrameDescriptorPool` allocated during the
+/// current frame.
+#[derive(Clone, Debug, Default)]
+pub struct FrameDescriptorPoolStatistics {
+    /// The number of Vulkan pools that were used.
+    pub pool_count: u32,
+
+    /// The number of descriptor sets that were allocated.
+    pub allocated_sets: u32,
+
+    /// The maximum number of descriptor sets that the used Vulkan pools can hold together.
+    pub max_sets: u32,
+
+    /// The occupancy of the used Vulkan pools, for each descriptor type.
+    pub descriptor_counts: HashMap<DescriptorType, DescriptorCountStatistics>,
+
+    /// The number of Vulkan pools of earlier frames that are waiting for their frame to complete
+    /// before they are reset.
+    pub pending_count: u32,
+
+    /// The number of reset Vulkan pools that are ready to be reused.
+    pub reserve_count: u32,
+}
+
+/// A descriptor set allocated from a `FrameDescriptorPool`.
+#[derive(Debug)]
+pub struct FrameDescriptorPoolAlloc {
+    // The actual descriptor set. It is freed when its Vulkan pool is reset.
+    inner: UnsafeDescriptorSet,
+}
+
+impl DescriptorPoolAlloc for FrameDescriptorPoolAlloc {
+    #[inline]
+    fn inner(&self) -> &UnsafeDescriptorSet {
+        &self.inner
+    }
+
+    #[inline]
+    fn inner_mut(&mut self) -> &mut UnsafeDescriptorSet {
+        &mut self.inner
+    }
+}
+
+// The Vulkan pools of a frame that has ended.
+struct PendingFrame {
+    pools: Vec<UnsafeDescriptorPool>,
+    // Signals the fence once the GPU has finished executing the frame.
+    future: Arc<dyn FrameFuture>,
+}
+
+impl Debug for PendingFrame {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result<(), FmtError> {
+        f.debug_struct("PendingFrame")
+            .field("pools", &self.pools)
+            .finish()
+    }
+}
+
+// A `FenceSignalFuture`, with the type of the future that it follows erased.
+trait FrameFuture: Send + Sync {
+    fn is_signaled(&self) -> Result<bool, OomError>;
+
+    fn wait(&self) -> Result<(), FlushError>;
+}
+
+impl<F> FrameFuture for FenceSignalFuture<F>
+where
+    F: GpuFuture + Send,
+{
+    fn is_signaled(&self) -> Result<bool, OomError> {
+        FenceSignalFuture::is_signaled(self)
+    }
+
+    fn wait(&self) -> Result<(), FlushError> {
+        FenceSignalFuture::wait(self, None)
+    }
+}
+
+// How much of a Vulkan pool is used, or how much a single descriptor set needs.
+#[derive(Clone, Debug, Default)]
+struct PoolUsage {
+    allocated_sets: u32,
+    descriptor_counts: HashMap<DescriptorType, u32>,
+    inline_uniform_block_bindings: u32,
+}
+
+impl PoolUsage {
+    fn for_set(layout: &DescriptorSetLayout, variable_descriptor_count: u32) -> Self {
+        PoolUsage {
+            allocated_sets: 1,
+            descriptor_counts: layout.allocated_descriptor_counts(variable_descriptor_count),
+            inline_uniform_block_bindings: layout.inline_uniform_block_binding_count(),
+        }
+    }
+
+    fn can_fit(&self, required: &PoolUsage, create_info: &FrameDescriptorPoolCreateInfo) -> bool {
+        self.allocated_sets + required.allocated_sets <= create_info.max_sets
+            && self.inline_uniform_block_bindings + required.inline_uniform_block_bindings
+                <= create_info.max_inline_uniform_block_bindings
+            && required.descriptor_counts.iter().all(|(ty, &count)| {
+                let allocated = self.descriptor_counts.get(ty).copied().unwrap_or(0);
+                let capacity = create_info.pool_sizes.get(ty).copied().unwrap_or(0);
+                allocated + count <= capacity
+            })
+    }
+
+    fn add(&mut self, other: &PoolUsage) {
+        self.allocated_sets += other.allocated_sets;
+        self.inline_uniform_block_bindings += other.inline_uniform_block_bindings;
+
+        for (&ty, &count) in &other.descriptor_counts {
+            *self.descriptor_counts.entry(ty).or_default() += count;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{FrameDescriptorPool, FrameDescriptorPoolCreateInfo};
+    use crate::{
+        command_buffer::{AutoCommandBufferBuilder, CommandBufferUsage, PrimaryCommandBuffer},
+        descriptor_set::{
+            layout::{
+                DescriptorSetLayout, DescriptorSetLayoutBinding, DescriptorSetLayoutCreateInfo,
+                DescriptorType,
+            },
+            pool::DescriptorPool,
+        },
+        shader::ShaderStages,
+        sync::GpuFuture,
+    };
+    use std::sync::Arc;
+
+    #[test]
+    fn reset_after_frame() {
+        let (device, queue) = gfx_dev_and_queue!();
+
+        let layout = DescriptorSetLayout::new(
+            device.clone(),
+            DescriptorSetLayoutCreateInfo {
+                bindings: [(
+                    0,
+                    DescriptorSetLayoutBinding {
+                        stages: ShaderStages::all_graphics(),
+                        ..DescriptorSetLayoutBinding::descriptor_type(DescriptorType::UniformBuffer)
+                    },
+                )]
+                .into(),
+                ..Default::default()
+            },
+        )
+        .unwrap();
+
+        let mut pool = unsafe {
+            FrameDescriptorPool::new(
+                device.clone(),
+                FrameDescriptorPoolCreateInfo {
+                    max_sets: 2,
+                    pool_sizes: [(DescriptorType::UniformBuffer, 2)].into_iter().collect(),
+                    ..Default::default()
+                },
+            )
+        };
+
+        let allocs: Vec<_> = (0..3).map(|_| pool.allocate(&layout, 0).unwrap()).collect();
+
+        let statistics = pool.statistics();
+        assert_eq!(statistics.pool_count, 2);
+        assert_eq!(statistics.allocated_sets, 3);
+        assert_eq!(statistics.max_sets, 4);
+        assert_eq!(
+            statistics.descriptor_counts[&DescriptorType::UniformBuffer].allocated,
+            3
+        );
+
+        // Dropping the sets doesn't reset anything, only the end of the frame does.
+        drop(allocs);
+        assert_eq!(pool.statistics().pool_count, 2);
+        assert_eq!(pool.statistics().reserve_count, 0);
+
+        let future = Arc::new(
+            AutoCommandBufferBuilder::primary(
+                device,
+                queue.queue_family_index(),
+                CommandBufferUsage::OneTimeSubmit,
+            )
+            .unwrap()
+            .build()
+            .unwrap()
+            .execute(queue)
+            .unwrap()
+            .then_signal_fence_and_flush()
+            .unwrap(),
+        );
+        unsafe { pool.next_frame(future.clone()) };
+        assert_eq!(pool.statistics().pool_count, 0);
+
+        future.wait(None).unwrap();
+        unsafe { pool.next_frame(future) };
+        let statistics = pool.statistics();
+        assert_eq!(statistics.pending_count, 0);
+        assert_eq!(statistics.reserve_count, 2);
+
+        pool.allocate(&layout, 0).unwrap();
+        assert_eq!(pool.statistics().reserve_count, 1);
+    }
+
+    #[test]
+    fn statistics_variable_count_absent_type() {
+        let (device, _) = gfx_dev_and_queue!(descriptor_binding_variable_descriptor_count);
+
+        let layout = DescriptorSetLayout::new(
+            device.clone(),
+            DescriptorSetLayoutCreateInfo {
+                bindings: [
+                    (
+                        0,
+                        DescriptorSetLayoutBinding {
+                            stages: ShaderStages::all_graphics(),
+                            ..DescriptorSetLayoutBinding::descriptor_type(
+                                DescriptorType::UniformBuffer,
+                            )
+                        },
+                    ),
+                    (
+                        1,
+                        DescriptorSetLayoutBinding {
+                            descriptor_count: 4,
+                            variable_descriptor_count: true,
+                            stages: ShaderStages::all_graphics(),
+                            ..DescriptorSetLayoutBinding::descriptor_type(
+                                DescriptorType::SampledImage,
+                            )
+                        },
+                    ),
+                ]
+                .into(),
+                ..Default::default()
+            },
+        )
+        .unwrap();
+
+        // The pool has no room for sampled images, which is fine as none are allocated.
+        let mut pool = unsafe {
+            FrameDescriptorPool::new(
+                device,
+                FrameDescriptorPoolCreateInfo {
+                    max_sets: 2,
+                    pool_sizes: [(DescriptorType::UniformBuffer, 2)].into_iter().collect(),
+                    ..Default::default()
+                },
+            )
+        };
+        let _alloc = pool.allocate(&layout, 0).unwrap();
+
+        let statistics = pool.statistics();
+        assert_eq!(statistics.allocated_sets, 1);
+        assert_eq!(
+            statistics.descriptor_counts[&DescriptorType::UniformBuffer].allocated,
+            1
+        );
+        assert!(!statistics
+            .descriptor_counts
+            .contains_key(&DescriptorType::SampledImage));
+    }
+}
@@ -10,7 +10,8 @@
 //! A pool from which descriptor sets can be allocated.
 
 pub use self::{
-    standard::StandardDescriptorPool,
+    frame::{FrameDescriptorPool, FrameDescriptorPoolCreateInfo, FrameDescriptorPoolStatistics},
+    standard::{StandardDescriptorPool, StandardDescriptorPoolStatistics},
     sys::{
         DescriptorPoolAllocError, DescriptorSetAllocateInfo, UnsafeDescriptorPool,
         UnsafeDescriptorPoolCreateInfo,
@@ -20,6 +21,7 @@ use super::{layout::DescriptorSetLayout, sys::UnsafeDescriptorSet};
 use crate::{device::DeviceOwned, OomError};
 use std::sync::Arc;
 
+pub mod frame;
 pub mod standard;
 mod sys;
 
@@ -46,3 +48,13 @@ pub trait DescriptorPoolAlloc: Send + Sync {
     /// Returns the inner unsafe descriptor set object.
     fn inner_mut(&mut self) -> &mut UnsafeDescriptorSet;
 }
+
+/// The occupancy of a descriptor type, in the statistics of a descriptor pool.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct DescriptorCountStatistics {
+    /// The number of descriptors that were allocated.
+    pub allocated: u32,
+
+    /// The maximum number of descriptors that the Vulkan pools can hold together.
+    pub capacity: u32,
+}
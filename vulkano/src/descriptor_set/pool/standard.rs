@@ -7,10 +7,10 @@
 // notice may not be copied, modified, or distributed except
 // according to those terms.
 
-use super::{DescriptorPool, DescriptorPoolAlloc};
+use super::{DescriptorCountStatistics, DescriptorPool, DescriptorPoolAlloc};
 use crate::{
     descriptor_set::{
-        layout::DescriptorSetLayout,
+        layout::{DescriptorSetLayout, DescriptorType},
         single_layout_pool::{
             SingleLayoutPoolAlloc, SingleLayoutVariableDescSetPool, SingleLayoutVariablePoolAlloc,
         },
@@ -48,6 +48,44 @@ impl StandardDescriptorPool {
             pools: HashMap::default(),
         }
     }
+
+    /// Returns statistics about the Vulkan pools that the allocator currently allocates from.
+    ///
+    /// There is one such pool for each descriptor set layout that sets have been allocated for.
+    /// Earlier Vulkan pools are kept alive by the sets that were allocated from them, and are not
+    /// included.
+    pub fn statistics(&self) -> StandardDescriptorPoolStatistics {
+        let mut statistics = StandardDescriptorPoolStatistics {
+            pool_count: self.pools.len() as u32,
+            ..Default::default()
+        };
+
+        for (layout, pool) in &self.pools {
+            let (allocated_sets, max_sets) = match pool {
+                Pool::Fixed(pool) => pool.set_counts(),
+                Pool::Variable(pool) => pool.set_counts(),
+            };
+            statistics.allocated_sets += allocated_sets;
+            statistics.max_sets += max_sets;
+
+            for (&ty, &count) in layout.descriptor_counts() {
+                let allocated = match pool {
+                    Pool::Fixed(_) => count * allocated_sets,
+                    Pool::Variable(pool) => pool
+                        .allocated_descriptor_counts()
+                        .get(&ty)
+                        .copied()
+                        .unwrap_or(0),
+                };
+
+                let descriptor_count = statistics.descriptor_counts.entry(ty).or_default();
+                descriptor_count.allocated += allocated;
+                descriptor_count.capacity += count * max_sets;
+            }
+        }
+
+        statistics
+    }
 }
 
 unsafe impl DescriptorPool for StandardDescriptorPool {
@@ -107,6 +145,22 @@ unsafe impl DeviceOwned for StandardDescriptorPool {
     }
 }
 
+/// Statistics about the Vulkan pools that a `StandardDescriptorPool` currently allocates from.
+#[derive(Clone, Debug, Default)]
+pub struct StandardDescriptorPoolStatistics {
+    /// The number of Vulkan pools, which is also the number of descriptor set layouts.
+    pub pool_count: u32,
+
+    /// The number of descriptor sets that are allocated from the Vulkan pools.
+    pub allocated_sets: u32,
+
+    /// The maximum number of descriptor sets that the Vulkan pools can hold together.
+    pub max_sets: u32,
+
+    /// The occupancy of the Vulkan pools, for each descriptor type.
+    pub descriptor_counts: HashMap<DescriptorType, DescriptorCountStatistics>,
+}
+
 /// A descriptor set allocated from a `StandardDescriptorPool`.
 #[derive(Debug)]
 pub struct StandardDescriptorPoolAlloc {
@@ -137,3 +191,57 @@ impl DescriptorPoolAlloc for StandardDescriptorPoolAlloc {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::StandardDescriptorPool;
+    use crate::{
+        descriptor_set::{
+            layout::{
+                DescriptorSetLayout, DescriptorSetLayoutBinding, DescriptorSetLayoutCreateInfo,
+                DescriptorType,
+            },
+            pool::DescriptorPool,
+        },
+        shader::ShaderStages,
+    };
+
+    #[test]
+    fn statistics() {
+        let (device, _) = gfx_dev_and_queue!();
+
+        let layout = DescriptorSetLayout::new(
+            device.clone(),
+            DescriptorSetLayoutCreateInfo {
+                bindings: [(
+                    0,
+                    DescriptorSetLayoutBinding {
+                        descriptor_count: 2,
+                        stages: ShaderStages::all_graphics(),
+                        ..DescriptorSetLayoutBinding::descriptor_type(DescriptorType::UniformBuffer)
+                    },
+                )]
+                .into(),
+                ..Default::default()
+            },
+        )
+        .unwrap();
+
+        let mut pool = StandardDescriptorPool::new(device);
+        assert_eq!(pool.statistics().pool_count, 0);
+
+        let alloc1 = pool.allocate(&layout, 0).unwrap();
+        let _alloc2 = pool.allocate(&layout, 0).unwrap();
+
+        let statistics = pool.statistics();
+        assert_eq!(statistics.pool_count, 1);
+        assert_eq!(statistics.allocated_sets, 2);
+        let uniform_buffers = statistics.descriptor_counts[&DescriptorType::UniformBuffer];
+        assert_eq!(uniform_buffers.allocated, 4);
+        assert_eq!(uniform_buffers.capacity, 2 * statistics.max_sets);
+
+        // Sets of a fixed-size layout are returned to the pool when they are dropped.
+        drop(alloc1);
+        assert_eq!(pool.statistics().allocated_sets, 1);
+    }
+}
@@ -38,6 +38,8 @@ pub struct UnsafeDescriptorPool {
     max_sets: u32,
     pool_sizes: HashMap<DescriptorType, u32>,
     can_free_descriptor_sets: bool,
+    update_after_bind: bool,
+    max_inline_uniform_block_bindings: u32,
 }
 
 impl UnsafeDescriptorPool {
@@ -48,6 +50,16 @@ impl UnsafeDescriptorPool {
     /// - Panics if `create_info.max_sets` is `0`.
     /// - Panics if `create_info.pool_sizes` is empty.
     /// - Panics if `create_info.pool_sizes` contains a descriptor type with a count of `0`.
+    /// - Panics if `create_info.update_after_bind` is set, but the device API version is less
+    ///   than 1.2 and the
+    ///   [`ext_descriptor_indexing`](crate::device::DeviceExtensions::ext_descriptor_indexing)
+    ///   extension is not enabled.
+    /// - Panics if `create_info.pool_sizes` contains [`DescriptorType::InlineUniformBlock`] with
+    ///   a count that is not a multiple of 4.
+    /// - Panics if `create_info.max_inline_uniform_block_bindings` is not `0`, but the device API
+    ///   version is less than 1.3 and the
+    ///   [`ext_inline_uniform_block`](crate::device::DeviceExtensions::ext_inline_uniform_block)
+    ///   extension is not enabled.
     pub fn new(
         device: Arc<Device>,
         create_info: UnsafeDescriptorPoolCreateInfo,
@@ -56,6 +68,8 @@ impl UnsafeDescriptorPool {
             max_sets,
             pool_sizes,
             can_free_descriptor_sets,
+            update_after_bind,
+            max_inline_uniform_block_bindings,
             _ne: _,
         } = create_info;
 
@@ -65,6 +79,19 @@ impl UnsafeDescriptorPool {
         // VUID-VkDescriptorPoolCreateInfo-poolSizeCount-arraylength
         assert!(!pool_sizes.is_empty());
 
+        // VUID-VkDescriptorPoolCreateInfo-flags-parameter
+        assert!(
+            !update_after_bind
+                || device.api_version() >= Version::V1_2
+                || device.enabled_extensions().ext_descriptor_indexing
+        );
+
+        assert!(
+            max_inline_uniform_block_bindings == 0
+                || device.api_version() >= Version::V1_3
+                || device.enabled_extensions().ext_inline_uniform_block
+        );
+
         let handle = {
             let pool_sizes: SmallVec<[_; 8]> = pool_sizes
                 .iter()
@@ -72,6 +99,9 @@ impl UnsafeDescriptorPool {
                     // VUID-VkDescriptorPoolSize-descriptorCount-00302
                     assert!(descriptor_count != 0);
 
+                    // VUID-VkDescriptorPoolSize-type-02218
+                    assert!(ty != DescriptorType::InlineUniformBlock || descriptor_count % 4 == 0);
+
                     ash::vk::DescriptorPoolSize {
                         ty: ty.into(),
                         descriptor_count,
@@ -85,7 +115,11 @@ impl UnsafeDescriptorPool {
                 flags |= ash::vk::DescriptorPoolCreateFlags::FREE_DESCRIPTOR_SET;
             }
 
-            let create_info = ash::vk::DescriptorPoolCreateInfo {
+            if update_after_bind {
+                flags |= ash::vk::DescriptorPoolCreateFlags::UPDATE_AFTER_BIND;
+            }
+
+            let mut create_info = ash::vk::DescriptorPoolCreateInfo {
                 flags,
                 max_sets,
                 pool_size_count: pool_sizes.len() as u32,
@@ -93,6 +127,18 @@ impl UnsafeDescriptorPool {
                 ..Default::default()
             };
 
+            let inline_uniform_block_create_info;
+
+            if max_inline_uniform_block_bindings != 0 {
+                inline_uniform_block_create_info =
+                    ash::vk::DescriptorPoolInlineUniformBlockCreateInfo {
+                        max_inline_uniform_block_bindings,
+                        ..Default::default()
+                    };
+
+                create_info.p_next = &inline_uniform_block_create_info as *const _ as *const _;
+            }
+
             unsafe {
                 let fns = device.fns();
                 let mut output = MaybeUninit::uninit();
@@ -115,6 +161,8 @@ impl UnsafeDescriptorPool {
             max_sets,
             pool_sizes,
             can_free_descriptor_sets,
+            update_after_bind,
+            max_inline_uniform_block_bindings,
         })
     }
 
@@ -134,6 +182,8 @@ impl UnsafeDescriptorPool {
             max_sets,
             pool_sizes,
             can_free_descriptor_sets,
+            update_after_bind,
+            max_inline_uniform_block_bindings,
             _ne: _,
         } = create_info;
 
@@ -144,6 +194,8 @@ impl UnsafeDescriptorPool {
             max_sets,
             pool_sizes,
             can_free_descriptor_sets,
+            update_after_bind,
+            max_inline_uniform_block_bindings,
         }
     }
 
@@ -165,6 +217,20 @@ impl UnsafeDescriptorPool {
         self.can_free_descriptor_sets
     }
 
+    /// Returns whether the pool can allocate descriptor sets whose layout was created with
+    /// `update_after_bind_pool` enabled.
+    #[inline]
+    pub fn update_after_bind(&self) -> bool {
+        self.update_after_bind
+    }
+
+    /// Returns the maximum number of inline uniform block bindings that the pool can allocate
+    /// across all of its descriptor sets.
+    #[inline]
+    pub fn max_inline_uniform_block_bindings(&self) -> u32 {
+        self.max_inline_uniform_block_bindings
+    }
+
     /// Allocates descriptor sets from the pool, one for each element in `create_info`.
     /// Returns an iterator to the allocated sets, or an error.
     ///
@@ -174,6 +240,8 @@ impl UnsafeDescriptorPool {
     /// # Panics
     ///
     /// - Panics if one of the layouts wasn't created with the same device as the pool.
+    /// - Panics if one of the layouts has `update_after_bind_pool` enabled, but the pool was not
+    ///   created with `update_after_bind` enabled.
     ///
     /// # Safety
     ///
@@ -195,6 +263,8 @@ impl UnsafeDescriptorPool {
                         self.device.internal_object(),
                         info.layout.device().internal_object(),
                     );
+                    // VUID-VkDescriptorSetAllocateInfo-pSetLayouts-03044
+                    assert!(!info.layout.update_after_bind_pool() || self.update_after_bind);
                     debug_assert!(!info.layout.push_descriptor());
                     debug_assert!(
                         info.variable_descriptor_count <= info.layout.variable_descriptor_count()
@@ -387,6 +457,29 @@ pub struct UnsafeDescriptorPoolCreateInfo {
     /// The default value is `false`.
     pub can_free_descriptor_sets: bool,
 
+    /// Whether the pool can allocate descriptor sets whose layout was created with
+    /// [`update_after_bind_pool`] enabled.
+    ///
+    /// If set to `true`, the device API version must be at least 1.2, or the
+    /// [`ext_descriptor_indexing`](crate::device::DeviceExtensions::ext_descriptor_indexing)
+    /// extension must be enabled on the device.
+    ///
+    /// The default value is `false`.
+    ///
+    /// [`update_after_bind_pool`]: crate::descriptor_set::layout::DescriptorSetLayoutCreateInfo::update_after_bind_pool
+    pub update_after_bind: bool,
+
+    /// The maximum number of bindings of type [`DescriptorType::InlineUniformBlock`] that can be
+    /// allocated from the pool, across all descriptor sets. The total size of the blocks is
+    /// specified separately, in bytes, as the `pool_sizes` entry for that type.
+    ///
+    /// If not `0`, the device API version must be at least 1.3, or the
+    /// [`ext_inline_uniform_block`](crate::device::DeviceExtensions::ext_inline_uniform_block)
+    /// extension must be enabled on the device.
+    ///
+    /// The default value is `0`.
+    pub max_inline_uniform_block_bindings: u32,
+
     pub _ne: crate::NonExhaustive,
 }
 
@@ -397,6 +490,8 @@ impl Default for UnsafeDescriptorPoolCreateInfo {
             max_sets: 0,
             pool_sizes: HashMap::default(),
             can_free_descriptor_sets: false,
+            update_after_bind: false,
+            max_inline_uniform_block_bindings: 0,
             _ne: crate::NonExhaustive(()),
         }
     }
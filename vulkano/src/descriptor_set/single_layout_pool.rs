@@ -8,7 +8,7 @@
 // according to those terms.
 
 use super::{
-    layout::DescriptorSetLayout,
+    layout::{DescriptorSetLayout, DescriptorType},
     pool::{
         DescriptorPoolAlloc, DescriptorPoolAllocError, DescriptorSetAllocateInfo,
         UnsafeDescriptorPool, UnsafeDescriptorPoolCreateInfo,
@@ -21,7 +21,9 @@ use crate::{
     device::{Device, DeviceOwned},
     OomError, VulkanObject,
 };
+use ahash::HashMap;
 use crossbeam_queue::ArrayQueue;
+use parking_lot::RwLockReadGuard;
 use std::{
     cell::UnsafeCell,
     hash::{Hash, Hasher},
@@ -97,6 +99,14 @@ impl SingleLayoutDescSetPool {
         Ok(Arc::new(SingleLayoutDescSet { alloc, inner }))
     }
 
+    // Returns the number of sets that are currently allocated from the current Vulkan pool, and
+    // the number of sets that it can hold.
+    pub(crate) fn set_counts(&self) -> (u32, u32) {
+        let set_count = self.set_count as u32;
+
+        (set_count - self.inner.reserve.len() as u32, set_count)
+    }
+
     pub(crate) fn next_alloc(&mut self) -> Result<SingleLayoutPoolAlloc, OomError> {
         loop {
             if let Some(existing) = self.inner.reserve.pop() {
@@ -134,6 +144,9 @@ impl SingleLayoutPool {
                     .iter()
                     .map(|(&ty, &count)| (ty, count * set_count as u32))
                     .collect(),
+                update_after_bind: layout.update_after_bind_pool(),
+                max_inline_uniform_block_bindings: layout.inline_uniform_block_binding_count()
+                    * set_count as u32,
                 ..Default::default()
             },
         )?;
@@ -198,6 +211,8 @@ impl DescriptorPoolAlloc for SingleLayoutPoolAlloc {
 impl Drop for SingleLayoutPoolAlloc {
     fn drop(&mut self) {
         let inner = unsafe { ManuallyDrop::take(&mut self.inner) };
+        // The set is handed out again, so it mustn't keep the name of its previous user.
+        inner.store_name(None);
         self.pool.reserve.push(inner).unwrap();
     }
 }
@@ -220,7 +235,7 @@ unsafe impl DescriptorSet for SingleLayoutDescSet {
     }
 
     #[inline]
-    fn resources(&self) -> &DescriptorSetResources {
+    fn resources(&self) -> RwLockReadGuard<'_, DescriptorSetResources> {
         self.inner.resources()
     }
 }
@@ -266,6 +281,8 @@ pub struct SingleLayoutVariableDescSetPool {
     layout: Arc<DescriptorSetLayout>,
     // The number of sets currently allocated from the Vulkan pool.
     allocated_sets: usize,
+    // The number of descriptors of each type currently allocated from the Vulkan pool.
+    allocated_descriptor_counts: HashMap<DescriptorType, u32>,
 }
 
 impl SingleLayoutVariableDescSetPool {
@@ -291,6 +308,7 @@ impl SingleLayoutVariableDescSetPool {
             reserve,
             layout,
             allocated_sets: 0,
+            allocated_descriptor_counts: HashMap::default(),
         })
     }
 
@@ -325,6 +343,18 @@ impl SingleLayoutVariableDescSetPool {
         Ok(SingleLayoutVariableDescSet { inner, alloc })
     }
 
+    // Returns the number of sets that are currently allocated from the current Vulkan pool, and
+    // the number of sets that it can hold.
+    pub(crate) fn set_counts(&self) -> (u32, u32) {
+        (self.allocated_sets as u32, MAX_SETS as u32)
+    }
+
+    // Returns the number of descriptors of each type that are currently allocated from the
+    // current Vulkan pool.
+    pub(crate) fn allocated_descriptor_counts(&self) -> &HashMap<DescriptorType, u32> {
+        &self.allocated_descriptor_counts
+    }
+
     pub(crate) fn next_alloc(
         &mut self,
         variable_descriptor_count: u32,
@@ -339,6 +369,7 @@ impl SingleLayoutVariableDescSetPool {
                 SingleLayoutVariablePool::new(&self.layout, self.reserve.clone())?
             };
             self.allocated_sets = 0;
+            self.allocated_descriptor_counts.clear();
         }
 
         let inner = {
@@ -370,6 +401,13 @@ impl SingleLayoutVariableDescSetPool {
 
         self.allocated_sets += 1;
 
+        for (ty, count) in self
+            .layout
+            .allocated_descriptor_counts(variable_descriptor_count)
+        {
+            *self.allocated_descriptor_counts.entry(ty).or_default() += count;
+        }
+
         Ok(SingleLayoutVariablePoolAlloc {
             inner,
             _pool: self.inner.clone(),
@@ -399,6 +437,9 @@ impl SingleLayoutVariablePool {
                     .iter()
                     .map(|(&ty, &count)| (ty, count * MAX_SETS as u32))
                     .collect(),
+                update_after_bind: layout.update_after_bind_pool(),
+                max_inline_uniform_block_bindings: layout.inline_uniform_block_binding_count()
+                    * MAX_SETS as u32,
                 ..Default::default()
             },
         )?;
@@ -464,7 +505,7 @@ unsafe impl DescriptorSet for SingleLayoutVariableDescSet {
     }
 
     #[inline]
-    fn resources(&self) -> &DescriptorSetResources {
+    fn resources(&self) -> RwLockReadGuard<'_, DescriptorSetResources> {
         self.inner.resources()
     }
 }
@@ -492,3 +533,77 @@ impl Hash for SingleLayoutVariableDescSet {
         self.device().hash(state);
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::{SingleLayoutDescSetPool, MAX_SETS};
+    use crate::{
+        descriptor_set::{
+            layout::{
+                DescriptorSetLayout, DescriptorSetLayoutBinding, DescriptorSetLayoutCreateInfo,
+                DescriptorType,
+            },
+            WriteDescriptorSet,
+        },
+        shader::ShaderStages,
+    };
+
+    #[test]
+    fn inline_uniform_block_pool_sizing() {
+        let (device, _) = gfx_dev_and_queue!(inline_uniform_block);
+
+        let inline_binding = |descriptor_count| DescriptorSetLayoutBinding {
+            descriptor_count,
+            stages: ShaderStages::all_graphics(),
+            ..DescriptorSetLayoutBinding::descriptor_type(DescriptorType::InlineUniformBlock)
+        };
+        let layout = DescriptorSetLayout::new(
+            device,
+            DescriptorSetLayoutCreateInfo {
+                bindings: [
+                    (0, inline_binding(16)),
+                    (1, inline_binding(32)),
+                    (
+                        2,
+                        DescriptorSetLayoutBinding {
+                            stages: ShaderStages::all_graphics(),
+                            ..DescriptorSetLayoutBinding::descriptor_type(
+                                DescriptorType::UniformBuffer,
+                            )
+                        },
+                    ),
+                ]
+                .into(),
+                ..Default::default()
+            },
+        )
+        .unwrap();
+        assert_eq!(layout.inline_uniform_block_binding_count(), 2);
+
+        let mut pool = SingleLayoutDescSetPool::new(layout).unwrap();
+        assert_eq!(
+            pool.inner._inner.max_inline_uniform_block_bindings(),
+            2 * MAX_SETS as u32,
+        );
+        assert_eq!(
+            pool.inner._inner.pool_sizes()[&DescriptorType::InlineUniformBlock],
+            48 * MAX_SETS as u32,
+        );
+
+        // Allocating more sets than the first pool holds creates a bigger pool, which must also
+        // reserve enough inline uniform block bindings.
+        let _sets: Vec<_> = (0..MAX_SETS + 1)
+            .map(|_| {
+                pool.next([
+                    WriteDescriptorSet::inline_uniform_block(0, 0, [0; 16]),
+                    WriteDescriptorSet::inline_uniform_block(1, 0, [0; 32]),
+                ])
+                .unwrap()
+            })
+            .collect();
+        assert_eq!(
+            pool.inner._inner.max_inline_uniform_block_bindings(),
+            2 * 2 * MAX_SETS as u32,
+        );
+    }
+}
@@ -12,16 +12,14 @@
 use crate::{
     descriptor_set::{
         layout::DescriptorSetLayout,
-        update::{DescriptorWriteInfo, WriteDescriptorSet},
+        update::{CopyDescriptorSet, DescriptorWriteInfo, WriteDescriptorSet},
     },
     device::DeviceOwned,
     VulkanObject,
 };
+use parking_lot::Mutex;
 use smallvec::SmallVec;
-use std::{
-    fmt::{Debug, Error as FmtError, Formatter},
-    ptr,
-};
+use std::fmt::{Debug, Error as FmtError, Formatter};
 
 /// Low-level descriptor set.
 ///
@@ -30,28 +28,80 @@ use std::{
 /// Instead it is an object meant to be used with the `UnsafeDescriptorPool`.
 pub struct UnsafeDescriptorSet {
     handle: ash::vk::DescriptorSet,
+    name: Mutex<Option<String>>,
 }
 
 impl UnsafeDescriptorSet {
     pub(crate) fn new(handle: ash::vk::DescriptorSet) -> Self {
-        Self { handle }
+        Self {
+            handle,
+            name: Mutex::new(None),
+        }
+    }
+
+    /// Returns the name of the descriptor set, if it has one.
+    ///
+    /// The name is set with [`DescriptorSet::set_name`].
+    ///
+    /// [`DescriptorSet::set_name`]: crate::descriptor_set::DescriptorSet::set_name
+    #[inline]
+    pub fn name(&self) -> Option<String> {
+        self.name.lock().clone()
+    }
+
+    // Stores the name without assigning it to the Vulkan object, which requires the device.
+    pub(crate) fn store_name(&self, name: Option<&str>) {
+        *self.name.lock() = name.map(ToOwned::to_owned);
     }
 
-    /// Modifies a descriptor set. Doesn't check that the writes or copies are correct, and
-    /// doesn't check whether the descriptor set is in use.
+    /// Modifies a descriptor set. Doesn't check that the writes are correct, and doesn't check
+    /// whether the descriptor set is in use.
+    ///
+    /// # Safety
+    ///
+    /// - See [`update`](Self::update).
+    #[inline]
+    pub unsafe fn write<'a>(
+        &mut self,
+        layout: &DescriptorSetLayout,
+        writes: impl IntoIterator<Item = &'a WriteDescriptorSet>,
+    ) {
+        self.update(layout, writes, std::iter::empty())
+    }
+
+    /// Copies descriptors from other descriptor sets into this one. Doesn't check that the copies
+    /// are correct, and doesn't check whether the descriptor set is in use.
+    ///
+    /// # Safety
+    ///
+    /// - See [`update`](Self::update).
+    #[inline]
+    pub unsafe fn copy<'a>(
+        &mut self,
+        layout: &DescriptorSetLayout,
+        copies: impl IntoIterator<Item = &'a CopyDescriptorSet>,
+    ) {
+        self.update(layout, std::iter::empty(), copies)
+    }
+
+    /// Modifies a descriptor set, first performing the writes and then the copies. Doesn't check
+    /// that the writes or copies are correct, and doesn't check whether the descriptor set is in
+    /// use.
     ///
     /// # Safety
     ///
     /// - The `Device` must be the device the pool of this set was created with.
     /// - Doesn't verify that the things you write in the descriptor set match its layout.
+    /// - Doesn't verify that the copies are between bindings of the same type, and within range.
     /// - Doesn't keep the resources alive. You have to do that yourself.
     /// - Updating a descriptor set obeys synchronization rules that aren't checked here. Once a
     ///   command buffer contains a pointer/reference to a descriptor set, it is illegal to write
     ///   to it.
-    pub unsafe fn write<'a>(
+    pub unsafe fn update<'a>(
         &mut self,
         layout: &DescriptorSetLayout,
         writes: impl IntoIterator<Item = &'a WriteDescriptorSet>,
+        copies: impl IntoIterator<Item = &'a CopyDescriptorSet>,
     ) {
         let (infos, mut writes): (SmallVec<[_; 8]>, SmallVec<[_; 8]>) = writes
             .into_iter()
@@ -64,10 +114,14 @@ impl UnsafeDescriptorSet {
                 )
             })
             .unzip();
+        let copies: SmallVec<[_; 8]> = copies
+            .into_iter()
+            .map(|copy| copy.to_vulkan(self.handle))
+            .collect();
 
-        // It is forbidden to call `vkUpdateDescriptorSets` with 0 writes, so we need to perform
-        // this emptiness check.
-        if writes.is_empty() {
+        // It is forbidden to call `vkUpdateDescriptorSets` with 0 writes and 0 copies, so we need
+        // to perform this emptiness check.
+        if writes.is_empty() && copies.is_empty() {
             return;
         }
 
@@ -86,6 +140,10 @@ impl UnsafeDescriptorSet {
                     write.descriptor_count = info.len() as u32;
                     write.p_texel_buffer_view = info.as_ptr();
                 }
+                DescriptorWriteInfo::InlineUniformBlock(info) => {
+                    write.descriptor_count = info.data_size;
+                    write.p_next = info as *const _ as *const _;
+                }
             }
 
             debug_assert!(write.descriptor_count != 0);
@@ -97,14 +155,10 @@ impl UnsafeDescriptorSet {
             layout.device().internal_object(),
             writes.len() as u32,
             writes.as_ptr(),
-            0,
-            ptr::null(),
+            copies.len() as u32,
+            copies.as_ptr(),
         );
     }
-
-    // TODO: add copying from other descriptor sets
-    //       add a `copy` method that just takes a copy, and an `update` method that takes both
-    //       writes and copies and that actually performs the operation
 }
 
 unsafe impl VulkanObject for UnsafeDescriptorSet {
@@ -0,0 +1,328 @@
+// Copyright (c) 2022 The vulkano developers
+// Licensed under the Apache License, Version 2.0
+// <LICENSE-APACHE or
+// https://www.apache.org/licenses/LICENSE-2.0> or the MIT
+// license <LICENSE-MIT or https://opensource.org/licenses/MIT>,
+// at your option. All files in the project carrying such
+// notice may not be copied, modified, or distributed except
+// according to those terms.
+
+//! A long-lived descriptor set whose descriptors can be updated after it has been created.
+//!
+//! An `UpdatableDescriptorSet` is created the same way as a `PersistentDescriptorSet`, but
+//! afterwards it also accepts new descriptor writes, and copies from other descriptor sets,
+//! through its [`update`](UpdatableDescriptorSet::update) method.
+//!
+//! Vulkan forbids updating a binding of a descriptor set while a command buffer that uses it is
+//! pending execution, unless the binding was created with `update_after_bind` or
+//! `update_unused_while_pending`. Vulkano detects this by checking whether the resources that are
+//! currently in the updated bindings are locked by a queue operation, and returns an error if they
+//! are.
+
+use crate::{
+    descriptor_set::{
+        pool::{standard::StandardDescriptorPoolAlloc, DescriptorPool, DescriptorPoolAlloc},
+        update::{CopyDescriptorSet, WriteDescriptorSet},
+        update_template::DescriptorUpdateTemplateData,
+        DescriptorSet, DescriptorSetCreationError, DescriptorSetInner, DescriptorSetLayout,
+        DescriptorSetResources, DescriptorSetUpdateError, UnsafeDescriptorSet,
+    },
+    device::{Device, DeviceOwned},
+    VulkanObject,
+};
+use parking_lot::RwLockReadGuard;
+use std::{
+    hash::{Hash, Hasher},
+    sync::Arc,
+};
+
+/// A long-lived descriptor set that can be updated after it has been created.
+pub struct UpdatableDescriptorSet<P = StandardDescriptorPoolAlloc> {
+    alloc: P,
+    inner: DescriptorSetInner,
+}
+
+impl UpdatableDescriptorSet {
+    /// Creates and returns a new descriptor set with a variable descriptor count of 0.
+    ///
+    /// See `new_with_pool` for more.
+    #[inline]
+    pub fn new(
+        layout: Arc<DescriptorSetLayout>,
+        descriptor_writes: impl IntoIterator<Item = WriteDescriptorSet>,
+    ) -> Result<Arc<UpdatableDescriptorSet>, DescriptorSetCreationError> {
+        layout
+            .device()
+            .clone()
+            .with_standard_descriptor_pool(|pool| {
+                Self::new_with_pool(layout, 0, pool, descriptor_writes)
+            })
+    }
+
+    /// Creates and returns a new descriptor set with the requested variable descriptor count.
+    ///
+    /// See `new_with_pool` for more.
+    #[inline]
+    pub fn new_variable(
+        layout: Arc<DescriptorSetLayout>,
+        variable_descriptor_count: u32,
+        descriptor_writes: impl IntoIterator<Item = WriteDescriptorSet>,
+    ) -> Result<Arc<UpdatableDescriptorSet>, DescriptorSetCreationError> {
+        layout
+            .device()
+            .clone()
+            .with_standard_descriptor_pool(|pool| {
+                Self::new_with_pool(layout, variable_descriptor_count, pool, descriptor_writes)
+            })
+    }
+
+    /// Creates and returns a new descriptor set with the requested variable descriptor count,
+    /// allocating it from the provided pool.
+    ///
+    /// # Panics
+    ///
+    /// - Panics if `layout` was created for push descriptors rather than descriptor sets.
+    /// - Panics if `variable_descriptor_count` is too large for the given `layout`.
+    pub fn new_with_pool<P>(
+        layout: Arc<DescriptorSetLayout>,
+        variable_descriptor_count: u32,
+        pool: &mut P,
+        descriptor_writes: impl IntoIterator<Item = WriteDescriptorSet>,
+    ) -> Result<Arc<UpdatableDescriptorSet<P::Alloc>>, DescriptorSetCreationError>
+    where
+        P: ?Sized + DescriptorPool,
+    {
+        assert!(
+            !layout.push_descriptor(),
+            "the provided descriptor set layout is for push descriptors, and cannot be used to \
+            build a descriptor set object",
+        );
+
+        let max_count = layout.variable_descriptor_count();
+
+        assert!(
+            variable_descriptor_count <= max_count,
+            "the provided variable_descriptor_count ({}) is greater than the maximum number of \
+            variable count descriptors in the set ({})",
+            variable_descriptor_count,
+            max_count,
+        );
+
+        let alloc = pool.allocate(&layout, variable_descriptor_count)?;
+        let inner = DescriptorSetInner::new(
+            alloc.inner().internal_object(),
+            layout,
+            variable_descriptor_count,
+            descriptor_writes,
+        )?;
+
+        Ok(Arc::new(UpdatableDescriptorSet { alloc, inner }))
+    }
+}
+
+impl<P> UpdatableDescriptorSet<P>
+where
+    P: DescriptorPoolAlloc,
+{
+    /// Performs the given descriptor writes, followed by the given descriptor copies, on the
+    /// descriptor set.
+    ///
+    /// Returns [`DescriptorSetUpdateError::InUse`] if one of the resources currently in an updated
+    /// binding is in use by a command buffer that is pending execution, unless the binding was
+    /// created with `update_after_bind` or `update_unused_while_pending`. Bindings that do not
+    /// contain any buffers or images can't be checked.
+    ///
+    /// # Safety
+    ///
+    /// - The descriptor set must not be bound in a command buffer that is still being recorded, or
+    ///   that has been built but not yet submitted, unless all the updated bindings were created
+    ///   with `update_after_bind`. The command buffer would otherwise keep using the old
+    ///   resources for synchronization.
+    /// - The descriptor set must not be in use by a pending command buffer that uses one of the
+    ///   updated bindings, if that binding contains no buffers or images.
+    pub unsafe fn update(
+        &self,
+        descriptor_writes: impl IntoIterator<Item = WriteDescriptorSet>,
+        descriptor_copies: impl IntoIterator<Item = CopyDescriptorSet>,
+    ) -> Result<(), DescriptorSetUpdateError> {
+        self.inner.update(
+            self.alloc.inner().internal_object(),
+            descriptor_writes,
+            descriptor_copies,
+        )
+    }
+
+    /// Performs the update described by `data` on the descriptor set, with a single call to
+    /// Vulkan.
+    ///
+    /// Returns [`DescriptorSetUpdateError::InUse`] under the same conditions as
+    /// [`update`](Self::update).
+    ///
+    /// # Panics
+    ///
+    /// - Panics if the template of `data` was created for push descriptors.
+    /// - Panics if the template of `data` was created with a layout that is not compatible with
+    ///   the layout of the descriptor set.
+    ///
+    /// # Safety
+    ///
+    /// - Same as [`update`](Self::update).
+    pub unsafe fn update_with_template(
+        &self,
+        data: &DescriptorUpdateTemplateData,
+    ) -> Result<(), DescriptorSetUpdateError> {
+        self.inner
+            .update_with_template(self.alloc.inner().internal_object(), data)
+    }
+}
+
+unsafe impl<P> DescriptorSet for UpdatableDescriptorSet<P>
+where
+    P: DescriptorPoolAlloc,
+{
+    fn inner(&self) -> &UnsafeDescriptorSet {
+        self.alloc.inner()
+    }
+
+    fn layout(&self) -> &Arc<DescriptorSetLayout> {
+        self.inner.layout()
+    }
+
+    fn resources(&self) -> RwLockReadGuard<'_, DescriptorSetResources> {
+        self.inner.resources()
+    }
+}
+
+unsafe impl<P> DeviceOwned for UpdatableDescriptorSet<P>
+where
+    P: DescriptorPoolAlloc,
+{
+    fn device(&self) -> &Arc<Device> {
+        self.inner.layout().device()
+    }
+}
+
+impl<P> PartialEq for UpdatableDescriptorSet<P>
+where
+    P: DescriptorPoolAlloc,
+{
+    fn eq(&self, other: &Self) -> bool {
+        self.inner().internal_object() == other.inner().internal_object()
+            && self.device() == other.device()
+    }
+}
+
+impl<P> Eq for UpdatableDescriptorSet<P> where P: DescriptorPoolAlloc {}
+
+impl<P> Hash for UpdatableDescriptorSet<P>
+where
+    P: DescriptorPoolAlloc,
+{
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.inner().internal_object().hash(state);
+        self.device().hash(state);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::UpdatableDescriptorSet;
+    use crate::{
+        descriptor_set::{
+            layout::{
+                DescriptorSetLayout, DescriptorSetLayoutBinding, DescriptorSetLayoutCreateInfo,
+                DescriptorType,
+            },
+            CopyDescriptorSet, DescriptorBindingResources, DescriptorSet, DescriptorSetUpdateError,
+            WriteDescriptorSet,
+        },
+        sampler::{Sampler, SamplerCreateInfo},
+        shader::ShaderStages,
+    };
+
+    #[test]
+    fn update_and_copy() {
+        let (device, _) = gfx_dev_and_queue!();
+
+        let layout = DescriptorSetLayout::new(
+            device.clone(),
+            DescriptorSetLayoutCreateInfo {
+                bindings: [
+                    (
+                        0,
+                        DescriptorSetLayoutBinding {
+                            descriptor_count: 4,
+                            stages: ShaderStages::all_graphics(),
+                            ..DescriptorSetLayoutBinding::descriptor_type(DescriptorType::Sampler)
+                        },
+                    ),
+                    (
+                        1,
+                        DescriptorSetLayoutBinding {
+                            stages: ShaderStages::all_graphics(),
+                            ..DescriptorSetLayoutBinding::descriptor_type(
+                                DescriptorType::SampledImage,
+                            )
+                        },
+                    ),
+                ]
+                .into(),
+                ..Default::default()
+            },
+        )
+        .unwrap();
+
+        let sampler = Sampler::new(device, SamplerCreateInfo::simple_repeat_linear()).unwrap();
+        let set =
+            UpdatableDescriptorSet::new(layout, [WriteDescriptorSet::sampler(0, sampler.clone())])
+                .unwrap();
+
+        unsafe {
+            set.update([WriteDescriptorSet::sampler_array(0, 1, [sampler])], [])
+                .unwrap();
+
+            assert!(matches!(
+                set.update(
+                    [],
+                    [CopyDescriptorSet {
+                        dst_first_array_element: 1,
+                        descriptor_count: 2,
+                        ..CopyDescriptorSet::new(set.clone())
+                    }],
+                ),
+                Err(DescriptorSetUpdateError::CopyOverlapping { binding: 0 })
+            ));
+
+            assert!(matches!(
+                set.update(
+                    [],
+                    [CopyDescriptorSet {
+                        dst_binding: 1,
+                        ..CopyDescriptorSet::new(set.clone())
+                    }],
+                ),
+                Err(DescriptorSetUpdateError::CopyDescriptorTypeMismatch {
+                    src_binding: 0,
+                    dst_binding: 1,
+                })
+            ));
+
+            set.update(
+                [],
+                [CopyDescriptorSet {
+                    dst_first_array_element: 2,
+                    descriptor_count: 2,
+                    ..CopyDescriptorSet::new(set.clone())
+                }],
+            )
+            .unwrap();
+        }
+
+        match set.resources().binding(0).unwrap() {
+            DescriptorBindingResources::Sampler(elements) => {
+                assert!(elements.iter().all(Option::is_some))
+            }
+            _ => unreachable!(),
+        }
+    }
+}
@@ -7,7 +7,10 @@
 // notice may not be copied, modified, or distributed except
 // according to those terms.
 
-use super::layout::{DescriptorSetLayout, DescriptorSetLayoutBinding, DescriptorType};
+use super::{
+    layout::{DescriptorSetLayout, DescriptorSetLayoutBinding, DescriptorType},
+    DescriptorSet,
+};
 use crate::{
     buffer::{view::BufferViewAbstract, BufferAccess, BufferInner},
     device::DeviceOwned,
@@ -174,6 +177,21 @@ impl WriteDescriptorSet {
         }
     }
 
+    /// Write data to an inline uniform block, starting at byte `offset`.
+    ///
+    /// For bindings of type [`DescriptorType::InlineUniformBlock`], the binding's
+    /// `descriptor_count` is its size in bytes, so `offset` and the length of `data` are also
+    /// counted in bytes. Both must be a multiple of 4. `data` must not be empty.
+    pub fn inline_uniform_block(binding: u32, offset: u32, data: impl Into<Vec<u8>>) -> Self {
+        let data = data.into();
+        assert!(!data.is_empty());
+        Self {
+            binding,
+            first_array_element: offset,
+            elements: WriteDescriptorSetElements::InlineUniformBlock(data),
+        }
+    }
+
     /// Returns the binding number that is updated by this descriptor write.
     #[inline]
     pub fn binding(&self) -> u32 {
@@ -181,6 +199,8 @@ impl WriteDescriptorSet {
     }
 
     /// Returns the first array element in the binding that is updated by this descriptor write.
+    ///
+    /// For inline uniform blocks, this is the byte offset of the written data.
     #[inline]
     pub fn first_array_element(&self) -> u32 {
         self.first_array_element
@@ -318,6 +338,19 @@ impl WriteDescriptorSet {
                         .collect(),
                 )
             }
+            WriteDescriptorSetElements::InlineUniformBlock(data) => {
+                debug_assert!(matches!(
+                    descriptor_type,
+                    DescriptorType::InlineUniformBlock
+                ));
+                DescriptorWriteInfo::InlineUniformBlock(
+                    ash::vk::WriteDescriptorSetInlineUniformBlock {
+                        data_size: data.len() as u32,
+                        p_data: data.as_ptr() as *const _,
+                        ..Default::default()
+                    },
+                )
+            }
         }
     }
 
@@ -348,10 +381,13 @@ pub enum WriteDescriptorSetElements {
     ImageView(SmallVec<[Arc<dyn ImageViewAbstract>; 1]>),
     ImageViewSampler(SmallVec<[(Arc<dyn ImageViewAbstract>, Arc<Sampler>); 1]>),
     Sampler(SmallVec<[Arc<Sampler>; 1]>),
+    InlineUniformBlock(Vec<u8>),
 }
 
 impl WriteDescriptorSetElements {
     /// Returns the number of elements.
+    ///
+    /// For inline uniform blocks, this is the number of bytes.
     #[inline]
     pub fn len(&self) -> u32 {
         match self {
@@ -361,6 +397,76 @@ impl WriteDescriptorSetElements {
             Self::ImageView(elements) => elements.len() as u32,
             Self::ImageViewSampler(elements) => elements.len() as u32,
             Self::Sampler(elements) => elements.len() as u32,
+            Self::InlineUniformBlock(data) => data.len() as u32,
+        }
+    }
+}
+
+/// Represents a single copy operation from the binding of one descriptor set to the binding of
+/// another, or the same, descriptor set.
+///
+/// The descriptors are copied as they currently are in the source set. The source and destination
+/// bindings must have the same descriptor type.
+#[derive(Clone)]
+pub struct CopyDescriptorSet {
+    /// The descriptor set to copy from.
+    ///
+    /// There is no default value.
+    pub src_set: Arc<dyn DescriptorSet>,
+
+    /// The binding number in the source descriptor set to copy from.
+    ///
+    /// The default value is 0.
+    pub src_binding: u32,
+
+    /// The first array element in the source binding to copy from.
+    ///
+    /// The default value is 0.
+    pub src_first_array_element: u32,
+
+    /// The binding number in the destination descriptor set to copy into.
+    ///
+    /// The default value is 0.
+    pub dst_binding: u32,
+
+    /// The first array element in the destination binding to copy into.
+    ///
+    /// The default value is 0.
+    pub dst_first_array_element: u32,
+
+    /// The number of descriptors to copy.
+    ///
+    /// The default value is 1.
+    pub descriptor_count: u32,
+
+    pub _ne: crate::NonExhaustive,
+}
+
+impl CopyDescriptorSet {
+    /// Returns a `CopyDescriptorSet` with the specified `src_set`.
+    #[inline]
+    pub fn new(src_set: Arc<dyn DescriptorSet>) -> Self {
+        Self {
+            src_set,
+            src_binding: 0,
+            src_first_array_element: 0,
+            dst_binding: 0,
+            dst_first_array_element: 0,
+            descriptor_count: 1,
+            _ne: crate::NonExhaustive(()),
+        }
+    }
+
+    pub(crate) fn to_vulkan(&self, dst_set: ash::vk::DescriptorSet) -> ash::vk::CopyDescriptorSet {
+        ash::vk::CopyDescriptorSet {
+            src_set: self.src_set.inner().internal_object(),
+            src_binding: self.src_binding,
+            src_array_element: self.src_first_array_element,
+            dst_set,
+            dst_binding: self.dst_binding,
+            dst_array_element: self.dst_first_array_element,
+            descriptor_count: self.descriptor_count,
+            ..Default::default()
         }
     }
 }
@@ -370,6 +476,9 @@ pub(crate) enum DescriptorWriteInfo {
     Image(SmallVec<[ash::vk::DescriptorImageInfo; 1]>),
     Buffer(SmallVec<[ash::vk::DescriptorBufferInfo; 1]>),
     BufferView(SmallVec<[ash::vk::BufferView; 1]>),
+    // Chained into the `p_next` of the write. `p_data` points into the `WriteDescriptorSet` that
+    // the info was created from.
+    InlineUniformBlock(ash::vk::WriteDescriptorSetInlineUniformBlock),
 }
 
 pub(crate) fn check_descriptor_write<'a>(
@@ -870,11 +979,153 @@ pub(crate) fn check_descriptor_write<'a>(
                 })
             }
         },
+        WriteDescriptorSetElements::InlineUniformBlock(_) => {
+            match layout_binding.descriptor_type {
+                DescriptorType::InlineUniformBlock => {}
+                _ => {
+                    return Err(DescriptorSetUpdateError::IncompatibleDescriptorType {
+                        binding: write.binding(),
+                    })
+                }
+            }
+
+            // VUID-VkWriteDescriptorSet-descriptorType-02219
+            // VUID-VkWriteDescriptorSet-descriptorType-02220
+            if descriptor_range_start % 4 != 0 || num_elements % 4 != 0 {
+                return Err(DescriptorSetUpdateError::InlineUniformBlockNotAligned {
+                    binding: write.binding(),
+                });
+            }
+        }
     }
 
     Ok(layout_binding)
 }
 
+pub(crate) fn check_descriptor_copy<'a>(
+    copy: &CopyDescriptorSet,
+    dst_set: ash::vk::DescriptorSet,
+    dst_layout: &'a DescriptorSetLayout,
+    dst_variable_descriptor_count: u32,
+) -> Result<&'a DescriptorSetLayoutBinding, DescriptorSetUpdateError> {
+    let src_layout = copy.src_set.layout();
+
+    assert_eq!(
+        src_layout.device().internal_object(),
+        dst_layout.device().internal_object(),
+    );
+
+    // VUID-VkCopyDescriptorSet-srcBinding-00345
+    let src_layout_binding = match src_layout.bindings().get(&copy.src_binding) {
+        Some(binding) => binding,
+        None => {
+            return Err(DescriptorSetUpdateError::CopySrcInvalidBinding {
+                binding: copy.src_binding,
+            })
+        }
+    };
+
+    // VUID-VkCopyDescriptorSet-dstBinding-00347
+    let dst_layout_binding = match dst_layout.bindings().get(&copy.dst_binding) {
+        Some(binding) => binding,
+        None => {
+            return Err(DescriptorSetUpdateError::InvalidBinding {
+                binding: copy.dst_binding,
+            })
+        }
+    };
+
+    // VUID-VkCopyDescriptorSet-dstBinding-02632
+    // The immutable samplers must also match, otherwise the resources that are tracked for the
+    // two bindings have a different shape.
+    if src_layout_binding.descriptor_type != dst_layout_binding.descriptor_type
+        || src_layout_binding.immutable_samplers.is_empty()
+            != dst_layout_binding.immutable_samplers.is_empty()
+    {
+        return Err(DescriptorSetUpdateError::CopyDescriptorTypeMismatch {
+            src_binding: copy.src_binding,
+            dst_binding: copy.dst_binding,
+        });
+    }
+
+    // VUID-VkCopyDescriptorSet-dstBinding-02753
+    if dst_layout_binding.descriptor_type == DescriptorType::Sampler
+        && !dst_layout_binding.immutable_samplers.is_empty()
+    {
+        return Err(DescriptorSetUpdateError::SamplerIsImmutable {
+            binding: copy.dst_binding,
+        });
+    }
+
+    // VUID-VkCopyDescriptorSet-srcSet-01918
+    if src_layout.update_after_bind_pool() && !dst_layout.update_after_bind_pool() {
+        return Err(DescriptorSetUpdateError::CopyUpdateAfterBindPoolMismatch);
+    }
+
+    assert!(copy.descriptor_count != 0);
+
+    // The variable descriptor count of the source set is not known here, but the resources that
+    // are tracked for the binding have the same length.
+    let src_descriptor_count = copy
+        .src_set
+        .resources()
+        .binding(copy.src_binding)
+        .map_or(0, |resources| resources.len());
+    let src_range_end = copy.src_first_array_element + copy.descriptor_count;
+
+    // VUID-VkCopyDescriptorSet-srcArrayElement-00346
+    if src_range_end > src_descriptor_count {
+        return Err(DescriptorSetUpdateError::CopySrcArrayIndexOutOfBounds {
+            binding: copy.src_binding,
+            available_count: src_descriptor_count,
+            copied_count: src_range_end,
+        });
+    }
+
+    let dst_descriptor_count = if dst_layout_binding.variable_descriptor_count {
+        dst_variable_descriptor_count
+    } else {
+        dst_layout_binding.descriptor_count
+    };
+    let dst_range_end = copy.dst_first_array_element + copy.descriptor_count;
+
+    // VUID-VkCopyDescriptorSet-dstArrayElement-00348
+    if dst_range_end > dst_descriptor_count {
+        return Err(DescriptorSetUpdateError::ArrayIndexOutOfBounds {
+            binding: copy.dst_binding,
+            available_count: dst_descriptor_count,
+            written_count: dst_range_end,
+        });
+    }
+
+    // VUID-VkCopyDescriptorSet-srcSet-00349
+    if copy.src_set.inner().internal_object() == dst_set
+        && copy.src_binding == copy.dst_binding
+        && copy.src_first_array_element < dst_range_end
+        && copy.dst_first_array_element < src_range_end
+    {
+        return Err(DescriptorSetUpdateError::CopyOverlapping {
+            binding: copy.dst_binding,
+        });
+    }
+
+    if dst_layout_binding.descriptor_type == DescriptorType::InlineUniformBlock {
+        // VUID-VkCopyDescriptorSet-srcBinding-02223
+        // VUID-VkCopyDescriptorSet-dstBinding-02224
+        // VUID-VkCopyDescriptorSet-srcBinding-02225
+        if copy.src_first_array_element % 4 != 0
+            || copy.dst_first_array_element % 4 != 0
+            || copy.descriptor_count % 4 != 0
+        {
+            return Err(DescriptorSetUpdateError::InlineUniformBlockNotAligned {
+                binding: copy.dst_binding,
+            });
+        }
+    }
+
+    Ok(dst_layout_binding)
+}
+
 #[derive(Clone, Copy, Debug)]
 pub enum DescriptorSetUpdateError {
     /// Tried to write more elements than were available in a binding.
@@ -887,6 +1138,31 @@ pub enum DescriptorSetUpdateError {
         written_count: u32,
     },
 
+    /// The source and destination bindings of a copy did not have the same descriptor type, or
+    /// only one of them had immutable samplers.
+    CopyDescriptorTypeMismatch { src_binding: u32, dst_binding: u32 },
+
+    /// Tried to copy descriptors within the same binding of a descriptor set, and the source and
+    /// destination ranges overlapped.
+    CopyOverlapping { binding: u32 },
+
+    /// Tried to copy more elements than were available in the source binding.
+    CopySrcArrayIndexOutOfBounds {
+        /// Source binding that is affected.
+        binding: u32,
+        /// Number of available descriptors in the source binding.
+        available_count: u32,
+        /// The number of descriptors that were in the copy.
+        copied_count: u32,
+    },
+
+    /// Tried to copy from a nonexistent binding.
+    CopySrcInvalidBinding { binding: u32 },
+
+    /// Tried to copy from a descriptor set whose layout was created with `update_after_bind_pool`
+    /// into a descriptor set whose layout was not.
+    CopyUpdateAfterBindPoolMismatch,
+
     /// Tried to write an image view with a 2D type and a 3D underlying image.
     ImageView2dFrom3d { binding: u32, index: u32 },
 
@@ -913,6 +1189,14 @@ pub enum DescriptorSetUpdateError {
     /// but it was not.
     ImageViewNotIdentitySwizzled { binding: u32, index: u32 },
 
+    /// Tried to write or copy to an inline uniform block with an offset or size that is not a
+    /// multiple of 4.
+    InlineUniformBlockNotAligned { binding: u32 },
+
+    /// Tried to update a binding whose current resources are still in use by a command buffer
+    /// that is pending execution, and the binding does not allow this.
+    InUse { binding: u32 },
+
     /// Tried to write an element type that was not compatible with the descriptor type in the
     /// layout.
     IncompatibleDescriptorType { binding: u32 },
@@ -932,6 +1216,14 @@ pub enum DescriptorSetUpdateError {
 
     /// Tried to write a sampler to a binding with immutable samplers.
     SamplerIsImmutable { binding: u32 },
+
+    /// A write for a descriptor update template did not have the same binding, first array
+    /// element or number of elements as the corresponding entry of the template.
+    TemplateEntryMismatch { entry_index: u32 },
+
+    /// The number of writes for a descriptor update template did not match the number of entries
+    /// of the template.
+    TemplateWriteCountMismatch { entry_count: u32, write_count: u32 },
 }
 
 impl Error for DescriptorSetUpdateError {
@@ -956,6 +1248,39 @@ impl Display for DescriptorSetUpdateError {
                 available",
                 written_count, binding, available_count,
             ),
+            Self::CopyDescriptorTypeMismatch {
+                src_binding,
+                dst_binding,
+            } => write!(
+                f,
+                "tried to copy from binding {} to binding {}, but the bindings do not have the \
+                same descriptor type and immutable samplers",
+                src_binding, dst_binding,
+            ),
+            Self::CopyOverlapping { binding } => write!(
+                f,
+                "tried to copy descriptors within binding {} of the same descriptor set, but the \
+                source and destination ranges overlap",
+                binding,
+            ),
+            Self::CopySrcArrayIndexOutOfBounds {
+                binding,
+                available_count,
+                copied_count,
+            } => write!(
+                f,
+                "tried to copy up to element {} from binding {}, but only {} descriptors are \
+                available",
+                copied_count, binding, available_count,
+            ),
+            Self::CopySrcInvalidBinding { binding } => {
+                write!(f, "tried to copy from a nonexistent binding {}", binding)
+            }
+            Self::CopyUpdateAfterBindPoolMismatch => write!(
+                f,
+                "tried to copy from a descriptor set whose layout was created with \
+                `update_after_bind_pool` into a descriptor set whose layout was not",
+            ),
             Self::ImageView2dFrom3d { binding, index } => write!(
                 f,
                 "tried to write an image view to binding {} index {} with a 2D type and a 3D \
@@ -992,6 +1317,18 @@ impl Display for DescriptorSetUpdateError {
                 but this binding has a descriptor type that requires it to be identity swizzled",
                 binding, index,
             ),
+            Self::InlineUniformBlockNotAligned { binding } => write!(
+                f,
+                "tried to update inline uniform block binding {} with an offset or size that is \
+                not a multiple of 4",
+                binding,
+            ),
+            Self::InUse { binding } => write!(
+                f,
+                "tried to update binding {}, but its current resources are in use by a command \
+                buffer that is pending execution",
+                binding,
+            ),
             Self::IncompatibleDescriptorType { binding } => write!(
                 f,
                 "tried to write a resource to binding {} whose type was not compatible with the \
@@ -1023,6 +1360,124 @@ impl Display for DescriptorSetUpdateError {
                 in the descriptor set layout",
                 binding,
             ),
+            Self::TemplateEntryMismatch { entry_index } => write!(
+                f,
+                "the write for entry {} of the descriptor update template did not have the same \
+                binding, first array element or number of elements as the entry",
+                entry_index,
+            ),
+            Self::TemplateWriteCountMismatch {
+                entry_count,
+                write_count,
+            } => write!(
+                f,
+                "{} writes were provided for a descriptor update template with {} entries",
+                write_count, entry_count,
+            ),
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::{DescriptorSetUpdateError, WriteDescriptorSet};
+    use crate::{
+        descriptor_set::{
+            layout::{
+                DescriptorSetLayout, DescriptorSetLayoutBinding, DescriptorSetLayoutCreateInfo,
+                DescriptorType,
+            },
+            DescriptorSetCreationError, PersistentDescriptorSet,
+        },
+        shader::ShaderStages,
+    };
+
+    #[test]
+    fn inline_uniform_block_write() {
+        let (device, _) = gfx_dev_and_queue!(inline_uniform_block);
+
+        let layout = DescriptorSetLayout::new(
+            device,
+            DescriptorSetLayoutCreateInfo {
+                bindings: [(
+                    0,
+                    DescriptorSetLayoutBinding {
+                        descriptor_count: 16,
+                        stages: ShaderStages::all_graphics(),
+                        ..DescriptorSetLayoutBinding::descriptor_type(
+                            DescriptorType::InlineUniformBlock,
+                        )
+                    },
+                )]
+                .into(),
+                ..Default::default()
+            },
+        )
+        .unwrap();
+
+        PersistentDescriptorSet::new(
+            layout.clone(),
+            [
+                WriteDescriptorSet::inline_uniform_block(0, 0, [1; 8]),
+                WriteDescriptorSet::inline_uniform_block(0, 8, [2; 8]),
+            ],
+        )
+        .unwrap();
+
+        assert!(matches!(
+            PersistentDescriptorSet::new(
+                layout.clone(),
+                [WriteDescriptorSet::inline_uniform_block(0, 2, [1; 4])],
+            ),
+            Err(DescriptorSetCreationError::DescriptorSetUpdateError(
+                DescriptorSetUpdateError::InlineUniformBlockNotAligned { binding: 0 }
+            ))
+        ));
+
+        assert!(matches!(
+            PersistentDescriptorSet::new(
+                layout,
+                [WriteDescriptorSet::inline_uniform_block(0, 8, [1; 12])],
+            ),
+            Err(DescriptorSetCreationError::DescriptorSetUpdateError(
+                DescriptorSetUpdateError::ArrayIndexOutOfBounds {
+                    binding: 0,
+                    available_count: 16,
+                    written_count: 20,
+                }
+            ))
+        ));
+    }
+
+    #[test]
+    fn inline_uniform_block_write_wrong_type() {
+        let (device, _) = gfx_dev_and_queue!();
+
+        let layout = DescriptorSetLayout::new(
+            device,
+            DescriptorSetLayoutCreateInfo {
+                bindings: [(
+                    0,
+                    DescriptorSetLayoutBinding {
+                        descriptor_count: 4,
+                        stages: ShaderStages::all_graphics(),
+                        ..DescriptorSetLayoutBinding::descriptor_type(DescriptorType::UniformBuffer)
+                    },
+                )]
+                .into(),
+                ..Default::default()
+            },
+        )
+        .unwrap();
+
+        assert!(matches!(
+            PersistentDescriptorSet::new(
+                layout,
+                [WriteDescriptorSet::inline_uniform_block(0, 0, [1; 4])],
+            ),
+            Err(DescriptorSetCreationError::DescriptorSetUpdateError(
+                DescriptorSetUpdateError::IncompatibleDescriptorType { binding: 0 }
+            ))
+        ));
+    }
+}
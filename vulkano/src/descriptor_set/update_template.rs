@@ -0,0 +1,764 @@
+// Copyright (c) 2022 The vulkano developers
+// Licensed under the Apache License, Version 2.0
+// <LICENSE-APACHE or
+// https://www.apache.org/licenses/LICENSE-2.0> or the MIT
+// license <LICENSE-MIT or https://opensource.org/licenses/MIT>,
+// at your option. All files in the project carrying such
+// notice may not be copied, modified, or distributed except
+// according to those terms.
+
+//! Templates that describe a fixed set of descriptors to update.
+//!
+//! Updating descriptors with `WriteDescriptorSet` requires vulkano to validate every write and
+//! to convert it to Vulkan structures each time. A [`DescriptorUpdateTemplate`] describes which
+//! descriptors of a layout are updated, and is validated once when it is created. The resources
+//! themselves are then provided in a [`DescriptorUpdateTemplateData`], which is also validated
+//! once and can be reused for any number of updates. Each update is then performed with a single
+//! call to Vulkan.
+//!
+//! A template is created for either descriptor sets or push descriptors. Templates for descriptor
+//! sets are used with [`UpdatableDescriptorSet::update_with_template`], templates for push
+//! descriptors are used with
+//! [`AutoCommandBufferBuilder::push_descriptor_set_with_template`].
+//!
+//! [`UpdatableDescriptorSet::update_with_template`]: crate::descriptor_set::UpdatableDescriptorSet::update_with_template
+//! [`AutoCommandBufferBuilder::push_descriptor_set_with_template`]: crate::command_buffer::AutoCommandBufferBuilder::push_descriptor_set_with_template
+
+use super::{
+    layout::{DescriptorSetLayout, DescriptorType},
+    update::{check_descriptor_write, DescriptorWriteInfo, WriteDescriptorSet},
+    DescriptorSetUpdateError,
+};
+use crate::{
+    device::{Device, DeviceOwned},
+    pipeline::{layout::PipelineLayout, PipelineBindPoint},
+    OomError, RequirementNotMet, RequiresOneOf, Version, VulkanError, VulkanObject,
+};
+use smallvec::SmallVec;
+use std::{
+    error::Error,
+    fmt::{Display, Error as FmtError, Formatter},
+    hash::{Hash, Hasher},
+    mem::{size_of, MaybeUninit},
+    ptr,
+    sync::Arc,
+};
+
+/// Describes a fixed set of descriptors of a descriptor set layout, that are updated together.
+#[derive(Debug)]
+pub struct DescriptorUpdateTemplate {
+    handle: ash::vk::DescriptorUpdateTemplate,
+    device: Arc<Device>,
+
+    layout: Arc<DescriptorSetLayout>,
+    entries: Vec<DescriptorUpdateTemplateEntry>,
+    template_type: DescriptorUpdateTemplateType,
+
+    // The descriptor type and the byte offset in the template data of each entry.
+    entry_data: Vec<(DescriptorType, usize)>,
+    data_size: usize,
+}
+
+impl DescriptorUpdateTemplate {
+    /// Creates a new `DescriptorUpdateTemplate` that updates descriptors of `layout`.
+    ///
+    /// The device API version must be at least 1.1, or the
+    /// [`khr_descriptor_update_template`](crate::device::DeviceExtensions::khr_descriptor_update_template)
+    /// extension must be enabled on the device.
+    ///
+    /// # Panics
+    ///
+    /// - Panics if `create_info.entries` is empty.
+    /// - Panics if the `descriptor_count` of an entry is 0.
+    pub fn new(
+        layout: Arc<DescriptorSetLayout>,
+        create_info: DescriptorUpdateTemplateCreateInfo,
+    ) -> Result<Arc<DescriptorUpdateTemplate>, DescriptorUpdateTemplateCreationError> {
+        let DescriptorUpdateTemplateCreateInfo {
+            entries,
+            template_type,
+            _ne: _,
+        } = create_info;
+
+        let device = layout.device().clone();
+
+        if !(device.api_version() >= Version::V1_1
+            || device.enabled_extensions().khr_descriptor_update_template)
+        {
+            return Err(DescriptorUpdateTemplateCreationError::RequirementNotMet {
+                required_for: "`DescriptorUpdateTemplate`",
+                requires_one_of: RequiresOneOf {
+                    api_version: Some(Version::V1_1),
+                    device_extensions: &["khr_descriptor_update_template"],
+                    ..Default::default()
+                },
+            });
+        }
+
+        match &template_type {
+            DescriptorUpdateTemplateType::DescriptorSet => {
+                if layout.push_descriptor() {
+                    return Err(
+                        DescriptorUpdateTemplateCreationError::DescriptorSetLayoutIsPushDescriptor,
+                    );
+                }
+            }
+            DescriptorUpdateTemplateType::PushDescriptors {
+                pipeline_bind_point,
+                pipeline_layout,
+                set_num,
+            } => {
+                if !device.enabled_extensions().khr_push_descriptor {
+                    return Err(DescriptorUpdateTemplateCreationError::RequirementNotMet {
+                        required_for: "`create_info.template_type` is \
+                            `DescriptorUpdateTemplateType::PushDescriptors`",
+                        requires_one_of: RequiresOneOf {
+                            device_extensions: &["khr_push_descriptor"],
+                            ..Default::default()
+                        },
+                    });
+                }
+
+                // VUID-VkDescriptorUpdateTemplateCreateInfo-templateType-00351
+                pipeline_bind_point.validate_device(&device)?;
+
+                // VUID-VkDescriptorUpdateTemplateCreateInfo-templateType-00352
+                assert_eq!(&device, pipeline_layout.device());
+
+                // VUID-VkDescriptorUpdateTemplateCreateInfo-templateType-00353
+                match pipeline_layout.set_layouts().get(*set_num as usize) {
+                    Some(set_layout) => {
+                        if !set_layout.push_descriptor() {
+                            return Err(
+                                DescriptorUpdateTemplateCreationError::DescriptorSetNotPush {
+                                    set_num: *set_num,
+                                },
+                            );
+                        }
+
+                        if !set_layout.is_compatible_with(&layout) {
+                            return Err(
+                                DescriptorUpdateTemplateCreationError::DescriptorSetNotCompatible {
+                                    set_num: *set_num,
+                                },
+                            );
+                        }
+                    }
+                    None => {
+                        return Err(
+                            DescriptorUpdateTemplateCreationError::DescriptorSetOutOfRange {
+                                set_num: *set_num,
+                                pipeline_layout_set_count: pipeline_layout.set_layouts().len()
+                                    as u32,
+                            },
+                        )
+                    }
+                }
+            }
+        }
+
+        // VUID-VkDescriptorUpdateTemplateCreateInfo-descriptorUpdateEntryCount-arraylength
+        assert!(!entries.is_empty());
+
+        let mut entry_data = Vec::with_capacity(entries.len());
+        let mut data_size = 0;
+
+        for entry in &entries {
+            let &DescriptorUpdateTemplateEntry {
+                binding,
+                first_array_element,
+                descriptor_count,
+                _ne: _,
+            } = entry;
+
+            let layout_binding = match layout.bindings().get(&binding) {
+                Some(layout_binding) => layout_binding,
+                None => {
+                    return Err(DescriptorUpdateTemplateCreationError::InvalidBinding { binding })
+                }
+            };
+
+            assert!(descriptor_count != 0);
+
+            let max_descriptor_count = if layout_binding.variable_descriptor_count {
+                layout.variable_descriptor_count()
+            } else {
+                layout_binding.descriptor_count
+            };
+
+            // VUID-VkDescriptorUpdateTemplateEntry-dstBinding-00354
+            if first_array_element + descriptor_count > max_descriptor_count {
+                return Err(
+                    DescriptorUpdateTemplateCreationError::ArrayIndexOutOfBounds {
+                        binding,
+                        available_count: max_descriptor_count,
+                        written_count: first_array_element + descriptor_count,
+                    },
+                );
+            }
+
+            let descriptor_type = layout_binding.descriptor_type;
+
+            if descriptor_type == DescriptorType::InlineUniformBlock {
+                if first_array_element % 4 != 0 || descriptor_count % 4 != 0 {
+                    return Err(
+                        DescriptorUpdateTemplateCreationError::InlineUniformBlockNotAligned {
+                            binding,
+                        },
+                    );
+                }
+            }
+
+            entry_data.push((descriptor_type, data_size));
+            data_size += descriptor_count as usize * info_size(descriptor_type);
+
+            // Inline uniform block data is not necessarily a multiple of 8 bytes, so realign for
+            // the infos that follow.
+            data_size = (data_size + 7) & !7;
+        }
+
+        let entries_vk: SmallVec<[_; 8]> = entries
+            .iter()
+            .zip(&entry_data)
+            .map(
+                |(entry, &(descriptor_type, offset))| ash::vk::DescriptorUpdateTemplateEntry {
+                    dst_binding: entry.binding,
+                    dst_array_element: entry.first_array_element,
+                    descriptor_count: entry.descriptor_count,
+                    descriptor_type: descriptor_type.into(),
+                    offset,
+                    stride: info_size(descriptor_type),
+                },
+            )
+            .collect();
+
+        let mut create_info_vk = ash::vk::DescriptorUpdateTemplateCreateInfo {
+            flags: ash::vk::DescriptorUpdateTemplateCreateFlags::empty(),
+            descriptor_update_entry_count: entries_vk.len() as u32,
+            p_descriptor_update_entries: entries_vk.as_ptr(),
+            template_type: ash::vk::DescriptorUpdateTemplateType::DESCRIPTOR_SET,
+            descriptor_set_layout: layout.internal_object(),
+            ..Default::default()
+        };
+
+        if let DescriptorUpdateTemplateType::PushDescriptors {
+            pipeline_bind_point,
+            pipeline_layout,
+            set_num,
+        } = &template_type
+        {
+            create_info_vk.template_type =
+                ash::vk::DescriptorUpdateTemplateType::PUSH_DESCRIPTORS_KHR;
+            create_info_vk.pipeline_bind_point = (*pipeline_bind_point).into();
+            create_info_vk.pipeline_layout = pipeline_layout.internal_object();
+            create_info_vk.set = *set_num;
+        }
+
+        let handle = unsafe {
+            let fns = device.fns();
+            let create_descriptor_update_template = if device.api_version() >= Version::V1_1 {
+                fns.v1_1.create_descriptor_update_template
+            } else {
+                fns.khr_descriptor_update_template
+                    .create_descriptor_update_template_khr
+            };
+
+            let mut output = MaybeUninit::uninit();
+            create_descriptor_update_template(
+                device.internal_object(),
+                &create_info_vk,
+                ptr::null(),
+                output.as_mut_ptr(),
+            )
+            .result()
+            .map_err(VulkanError::from)?;
+            output.assume_init()
+        };
+
+        Ok(Arc::new(DescriptorUpdateTemplate {
+            handle,
+            device,
+            layout,
+            entries,
+            template_type,
+            entry_data,
+            data_size,
+        }))
+    }
+
+    /// Returns the descriptor set layout that the template updates descriptors of.
+    #[inline]
+    pub fn layout(&self) -> &Arc<DescriptorSetLayout> {
+        &self.layout
+    }
+
+    /// Returns the entries of the template.
+    #[inline]
+    pub fn entries(&self) -> &[DescriptorUpdateTemplateEntry] {
+        &self.entries
+    }
+
+    /// Returns the type of the template.
+    #[inline]
+    pub fn template_type(&self) -> &DescriptorUpdateTemplateType {
+        &self.template_type
+    }
+}
+
+impl Drop for DescriptorUpdateTemplate {
+    #[inline]
+    fn drop(&mut self) {
+        unsafe {
+            let fns = self.device.fns();
+            let destroy_descriptor_update_template = if self.device.api_version() >= Version::V1_1 {
+                fns.v1_1.destroy_descriptor_update_template
+            } else {
+                fns.khr_descriptor_update_template
+                    .destroy_descriptor_update_template_khr
+            };
+
+            destroy_descriptor_update_template(
+                self.device.internal_object(),
+                self.handle,
+                ptr::null(),
+            );
+        }
+    }
+}
+
+unsafe impl VulkanObject for DescriptorUpdateTemplate {
+    type Object = ash::vk::DescriptorUpdateTemplate;
+
+    #[inline]
+    fn internal_object(&self) -> ash::vk::DescriptorUpdateTemplate {
+        self.handle
+    }
+}
+
+unsafe impl DeviceOwned for DescriptorUpdateTemplate {
+    #[inline]
+    fn device(&self) -> &Arc<Device> {
+        &self.device
+    }
+}
+
+impl PartialEq for DescriptorUpdateTemplate {
+    #[inline]
+    fn eq(&self, other: &Self) -> bool {
+        self.handle == other.handle && self.device() == other.device()
+    }
+}
+
+impl Eq for DescriptorUpdateTemplate {}
+
+impl Hash for DescriptorUpdateTemplate {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.handle.hash(state);
+        self.device().hash(state);
+    }
+}
+
+/// Parameters to create a new `DescriptorUpdateTemplate`.
+#[derive(Clone, Debug)]
+pub struct DescriptorUpdateTemplateCreateInfo {
+    /// The descriptors that are updated by the template.
+    ///
+    /// The list must not be empty.
+    ///
+    /// The default value is empty, which must be overridden.
+    pub entries: Vec<DescriptorUpdateTemplateEntry>,
+
+    /// Whether the template updates descriptor sets or push descriptors.
+    ///
+    /// The default value is [`DescriptorUpdateTemplateType::DescriptorSet`].
+    pub template_type: DescriptorUpdateTemplateType,
+
+    pub _ne: crate::NonExhaustive,
+}
+
+impl Default for DescriptorUpdateTemplateCreateInfo {
+    #[inline]
+    fn default() -> Self {
+        Self {
+            entries: Vec::new(),
+            template_type: DescriptorUpdateTemplateType::DescriptorSet,
+            _ne: crate::NonExhaustive(()),
+        }
+    }
+}
+
+/// A range of descriptors in a single binding that is updated by a template.
+#[derive(Clone, Debug)]
+pub struct DescriptorUpdateTemplateEntry {
+    /// The binding number to update.
+    ///
+    /// The default value is 0.
+    pub binding: u32,
+
+    /// The first array element in the binding to update.
+    ///
+    /// The default value is 0.
+    pub first_array_element: u32,
+
+    /// The number of descriptors to update.
+    ///
+    /// For inline uniform blocks, `first_array_element` and `descriptor_count` are a byte offset
+    /// and size instead, and must both be a multiple of 4.
+    ///
+    /// The default value is 1.
+    pub descriptor_count: u32,
+
+    pub _ne: crate::NonExhaustive,
+}
+
+impl Default for DescriptorUpdateTemplateEntry {
+    #[inline]
+    fn default() -> Self {
+        Self {
+            binding: 0,
+            first_array_element: 0,
+            descriptor_count: 1,
+            _ne: crate::NonExhaustive(()),
+        }
+    }
+}
+
+/// What a `DescriptorUpdateTemplate` updates.
+#[derive(Clone, Debug)]
+pub enum DescriptorUpdateTemplateType {
+    /// The template updates descriptor sets that were created with the layout of the template.
+    DescriptorSet,
+
+    /// The template pushes descriptors to `set_num` of `pipeline_layout`, which must be a push
+    /// descriptor set layout that is compatible with the layout of the template.
+    PushDescriptors {
+        pipeline_bind_point: PipelineBindPoint,
+        pipeline_layout: Arc<PipelineLayout>,
+        set_num: u32,
+    },
+}
+
+/// The resources to update with a `DescriptorUpdateTemplate`, validated and laid out in the form
+/// that the template expects.
+///
+/// The data can be reused for any number of updates.
+pub struct DescriptorUpdateTemplateData {
+    template: Arc<DescriptorUpdateTemplate>,
+    // Kept as `u64` so that the descriptor infos, whose size is a multiple of 8, are aligned.
+    data: Vec<u64>,
+    descriptor_writes: SmallVec<[WriteDescriptorSet; 8]>,
+}
+
+impl DescriptorUpdateTemplateData {
+    /// Validates `descriptor_writes` and lays them out for use with `template`.
+    ///
+    /// `descriptor_writes` must contain one write for every entry of the template, in the same
+    /// order. Each write must have the same binding and first array element as its entry, and
+    /// the same number of elements as its `descriptor_count`.
+    pub fn new(
+        template: Arc<DescriptorUpdateTemplate>,
+        descriptor_writes: impl IntoIterator<Item = WriteDescriptorSet>,
+    ) -> Result<DescriptorUpdateTemplateData, DescriptorSetUpdateError> {
+        let descriptor_writes: SmallVec<[_; 8]> = descriptor_writes.into_iter().collect();
+
+        if descriptor_writes.len() != template.entries.len() {
+            return Err(DescriptorSetUpdateError::TemplateWriteCountMismatch {
+                entry_count: template.entries.len() as u32,
+                write_count: descriptor_writes.len() as u32,
+            });
+        }
+
+        let mut data = vec![0u64; template.data_size / size_of::<u64>()];
+
+        for (entry_index, ((entry, &(descriptor_type, offset)), write)) in template
+            .entries
+            .iter()
+            .zip(&template.entry_data)
+            .zip(&descriptor_writes)
+            .enumerate()
+        {
+            if write.binding() != entry.binding
+                || write.first_array_element() != entry.first_array_element
+                || write.elements().len() != entry.descriptor_count
+            {
+                return Err(DescriptorSetUpdateError::TemplateEntryMismatch {
+                    entry_index: entry_index as u32,
+                });
+            }
+
+            check_descriptor_write(
+                write,
+                &template.layout,
+                template.layout.variable_descriptor_count(),
+            )?;
+
+            unsafe {
+                let dst = (data.as_mut_ptr() as *mut u8).add(offset);
+
+                match write.to_vulkan_info(descriptor_type) {
+                    DescriptorWriteInfo::Image(info) => {
+                        ptr::copy_nonoverlapping(info.as_ptr(), dst as *mut _, info.len())
+                    }
+                    DescriptorWriteInfo::Buffer(info) => {
+                        ptr::copy_nonoverlapping(info.as_ptr(), dst as *mut _, info.len())
+                    }
+                    DescriptorWriteInfo::BufferView(info) => {
+                        ptr::copy_nonoverlapping(info.as_ptr(), dst as *mut _, info.len())
+                    }
+                    DescriptorWriteInfo::InlineUniformBlock(info) => ptr::copy_nonoverlapping(
+                        info.p_data as *const u8,
+                        dst,
+                        info.data_size as usize,
+                    ),
+                }
+            }
+        }
+
+        Ok(DescriptorUpdateTemplateData {
+            template,
+            data,
+            descriptor_writes,
+        })
+    }
+
+    /// Returns the template that the data was created for.
+    #[inline]
+    pub fn template(&self) -> &Arc<DescriptorUpdateTemplate> {
+        &self.template
+    }
+
+    /// Returns the descriptor writes that the data was created from.
+    #[inline]
+    pub fn descriptor_writes(&self) -> &[WriteDescriptorSet] {
+        &self.descriptor_writes
+    }
+
+    /// Returns a pointer to the data, in the form expected by Vulkan.
+    #[inline]
+    pub(crate) fn as_ptr(&self) -> *const std::ffi::c_void {
+        self.data.as_ptr() as *const _
+    }
+}
+
+// The size of the Vulkan structure that holds one descriptor of the given type.
+fn info_size(descriptor_type: DescriptorType) -> usize {
+    match descriptor_type {
+        DescriptorType::Sampler
+        | DescriptorType::CombinedImageSampler
+        | DescriptorType::SampledImage
+        | DescriptorType::StorageImage
+        | DescriptorType::InputAttachment => size_of::<ash::vk::DescriptorImageInfo>(),
+        DescriptorType::UniformBuffer
+        | DescriptorType::StorageBuffer
+        | DescriptorType::UniformBufferDynamic
+        | DescriptorType::StorageBufferDynamic => size_of::<ash::vk::DescriptorBufferInfo>(),
+        DescriptorType::UniformTexelBuffer | DescriptorType::StorageTexelBuffer => {
+            size_of::<ash::vk::BufferView>()
+        }
+        // The descriptor count is a number of bytes.
+        DescriptorType::InlineUniformBlock => 1,
+    }
+}
+
+/// Error that can happen when creating a `DescriptorUpdateTemplate`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum DescriptorUpdateTemplateCreationError {
+    /// Not enough memory.
+    OomError(OomError),
+
+    RequirementNotMet {
+        required_for: &'static str,
+        requires_one_of: RequiresOneOf,
+    },
+
+    /// An entry updates more descriptors than are available in its binding.
+    ArrayIndexOutOfBounds {
+        /// Binding that is affected.
+        binding: u32,
+        /// Number of available descriptors in the binding.
+        available_count: u32,
+        /// The number of descriptors that were in the entry.
+        written_count: u32,
+    },
+
+    /// The template is for descriptor sets, but the layout was created for push descriptors.
+    DescriptorSetLayoutIsPushDescriptor,
+
+    /// The template is for push descriptors, but the layout of `set_num` in the pipeline layout
+    /// is not compatible with the layout of the template.
+    DescriptorSetNotCompatible { set_num: u32 },
+
+    /// The template is for push descriptors, but the layout of `set_num` in the pipeline layout
+    /// was not created for push descriptors.
+    DescriptorSetNotPush { set_num: u32 },
+
+    /// The template is for push descriptors, but `set_num` is not less than the number of sets
+    /// in the pipeline layout.
+    DescriptorSetOutOfRange {
+        set_num: u32,
+        pipeline_layout_set_count: u32,
+    },
+
+    /// An entry for an inline uniform block has a first array element or descriptor count that
+    /// is not a multiple of 4.
+    InlineUniformBlockNotAligned { binding: u32 },
+
+    /// An entry refers to a binding that does not exist in the layout.
+    InvalidBinding { binding: u32 },
+}
+
+impl Error for DescriptorUpdateTemplateCreationError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            Self::OomError(err) => Some(err),
+            _ => None,
+        }
+    }
+}
+
+impl Display for DescriptorUpdateTemplateCreationError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result<(), FmtError> {
+        match self {
+            Self::OomError(_) => write!(f, "not enough memory available"),
+            Self::RequirementNotMet {
+                required_for,
+                requires_one_of,
+            } => write!(
+                f,
+                "a requirement was not met for: {}; requires one of: {}",
+                required_for, requires_one_of,
+            ),
+            Self::ArrayIndexOutOfBounds {
+                binding,
+                available_count,
+                written_count,
+            } => write!(
+                f,
+                "an entry updates up to element {} of binding {}, but only {} descriptors are \
+                available",
+                written_count, binding, available_count,
+            ),
+            Self::DescriptorSetLayoutIsPushDescriptor => write!(
+                f,
+                "the template is for descriptor sets, but the layout was created for push \
+                descriptors",
+            ),
+            Self::DescriptorSetNotCompatible { set_num } => write!(
+                f,
+                "the layout of set {} in the pipeline layout is not compatible with the layout of \
+                the template",
+                set_num,
+            ),
+            Self::DescriptorSetNotPush { set_num } => write!(
+                f,
+                "the layout of set {} in the pipeline layout was not created for push descriptors",
+                set_num,
+            ),
+            Self::DescriptorSetOutOfRange {
+                set_num,
+                pipeline_layout_set_count,
+            } => write!(
+                f,
+                "set {} is not less than the number of sets in the pipeline layout ({})",
+                set_num, pipeline_layout_set_count,
+            ),
+            Self::InlineUniformBlockNotAligned { binding } => write!(
+                f,
+                "an entry for inline uniform block binding {} has a first array element or \
+                descriptor count that is not a multiple of 4",
+                binding,
+            ),
+            Self::InvalidBinding { binding } => write!(
+                f,
+                "an entry refers to binding {}, which does not exist in the layout",
+                binding,
+            ),
+        }
+    }
+}
+
+impl From<OomError> for DescriptorUpdateTemplateCreationError {
+    fn from(err: OomError) -> DescriptorUpdateTemplateCreationError {
+        DescriptorUpdateTemplateCreationError::OomError(err)
+    }
+}
+
+impl From<VulkanError> for DescriptorUpdateTemplateCreationError {
+    fn from(err: VulkanError) -> DescriptorUpdateTemplateCreationError {
+        match err {
+            err @ VulkanError::OutOfHostMemory => {
+                DescriptorUpdateTemplateCreationError::OomError(OomError::from(err))
+            }
+            err @ VulkanError::OutOfDeviceMemory => {
+                DescriptorUpdateTemplateCreationError::OomError(OomError::from(err))
+            }
+            _ => panic!("unexpected error: {:?}", err),
+        }
+    }
+}
+
+impl From<RequirementNotMet> for DescriptorUpdateTemplateCreationError {
+    fn from(err: RequirementNotMet) -> Self {
+        Self::RequirementNotMet {
+            required_for: err.required_for,
+            requires_one_of: err.requires_one_of,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        DescriptorUpdateTemplate, DescriptorUpdateTemplateCreateInfo,
+        DescriptorUpdateTemplateCreationError, DescriptorUpdateTemplateEntry,
+    };
+    use crate::{
+        descriptor_set::layout::{
+            DescriptorSetLayout, DescriptorSetLayoutBinding, DescriptorSetLayoutCreateInfo,
+            DescriptorType,
+        },
+        shader::ShaderStages,
+    };
+
+    #[test]
+    fn entry_out_of_bounds() {
+        let (device, _) = gfx_dev_and_queue!();
+
+        let layout = DescriptorSetLayout::new(
+            device,
+            DescriptorSetLayoutCreateInfo {
+                bindings: [(
+                    0,
+                    DescriptorSetLayoutBinding {
+                        descriptor_count: 2,
+                        stages: ShaderStages::all_graphics(),
+                        ..DescriptorSetLayoutBinding::descriptor_type(DescriptorType::UniformBuffer)
+                    },
+                )]
+                .into(),
+                ..Default::default()
+            },
+        )
+        .unwrap();
+
+        match DescriptorUpdateTemplate::new(
+            layout,
+            DescriptorUpdateTemplateCreateInfo {
+                entries: vec![DescriptorUpdateTemplateEntry {
+                    first_array_element: 1,
+                    descriptor_count: 2,
+                    ..Default::default()
+                }],
+                ..Default::default()
+            },
+        ) {
+            Err(DescriptorUpdateTemplateCreationError::RequirementNotMet { .. }) => (),
+            Err(DescriptorUpdateTemplateCreationError::ArrayIndexOutOfBounds {
+                binding: 0,
+                available_count: 2,
+                written_count: 3,
+            }) => (),
+            _ => panic!(),
+        }
+    }
+}
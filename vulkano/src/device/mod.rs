@@ -109,10 +109,13 @@ pub use self::{
     queue::{Queue, QueueError, QueueFamilyProperties, QueueFlags, QueueGuard},
 };
 use crate::{
+    buffer::BufferAccess,
     command_buffer::pool::StandardCommandPool,
     descriptor_set::pool::StandardDescriptorPool,
     instance::Instance,
     memory::{pool::StandardMemoryPool, ExternalMemoryHandleType},
+    swapchain::display::{self, DisplayError, DisplayEventType, DisplayPowerState},
+    sync::{Fence, FenceCreateInfo},
     OomError, RequirementNotMet, RequiresOneOf, Version, VulkanError, VulkanObject,
 };
 pub use crate::{
@@ -126,7 +129,7 @@ use parking_lot::Mutex;
 use smallvec::SmallVec;
 use std::{
     cell::RefCell,
-    collections::hash_map::Entry,
+    collections::{hash_map::Entry, BTreeMap},
     error::Error,
     ffi::CString,
     fmt::{Display, Error as FmtError, Formatter},
@@ -163,6 +166,9 @@ pub struct Device {
     fence_pool: Mutex<Vec<ash::vk::Fence>>,
     semaphore_pool: Mutex<Vec<ash::vk::Semaphore>>,
     event_pool: Mutex<Vec<ash::vk::Event>>,
+    // The buffers whose device address was queried, by address. Used to find the buffers that
+    // are referenced in push constants or in the contents of other buffers.
+    buffer_device_addresses: Mutex<BTreeMap<u64, (u64, Weak<dyn BufferAccess>)>>,
 }
 
 // The `StandardCommandPool` type doesn't implement Send/Sync, so we have to manually reimplement
@@ -423,6 +429,7 @@ impl Device {
             fence_pool: Mutex::new(Vec::new()),
             semaphore_pool: Mutex::new(Vec::new()),
             event_pool: Mutex::new(Vec::new()),
+            buffer_device_addresses: Mutex::new(BTreeMap::new()),
         });
 
         // Iterator to return the queues
@@ -600,6 +607,49 @@ impl Device {
         &self.event_pool
     }
 
+    /// Registers `buffer` as being located at `address`, so that it can be found by
+    /// `buffers_at_device_addresses`.
+    pub(crate) fn register_buffer_device_address(
+        &self,
+        address: u64,
+        buffer: &Arc<dyn BufferAccess>,
+    ) {
+        let mut addresses = self.buffer_device_addresses.lock();
+        addresses.retain(|_, (_, buffer)| buffer.strong_count() != 0);
+        addresses.insert(address, (address + buffer.size(), Arc::downgrade(buffer)));
+    }
+
+    /// Returns the registered buffers that contain one of the addresses found in `data`.
+    ///
+    /// Every 4-byte aligned 64-bit value of `data` is treated as a potential address. Buffers
+    /// that are no longer alive are ignored.
+    pub(crate) fn buffers_at_device_addresses(&self, data: &[u8]) -> Vec<Arc<dyn BufferAccess>> {
+        let addresses = self.buffer_device_addresses.lock();
+        let mut buffers: Vec<Arc<dyn BufferAccess>> = Vec::new();
+
+        if addresses.is_empty() {
+            return buffers;
+        }
+
+        for offset in (0..data.len().saturating_sub(7)).step_by(4) {
+            let value = u64::from_ne_bytes(data[offset..offset + 8].try_into().unwrap());
+
+            if let Some((_, (end, buffer))) = addresses.range(..=value).next_back() {
+                if value >= *end {
+                    continue;
+                }
+
+                if let Some(buffer) = buffer.upgrade() {
+                    if !buffers.iter().any(|b| **b == *buffer) {
+                        buffers.push(buffer);
+                    }
+                }
+            }
+        }
+
+        buffers
+    }
+
     /// Retrieves the properties of an external file descriptor when imported as a given external
     /// handle type.
     ///
@@ -626,7 +676,7 @@ impl Device {
 
         #[cfg(unix)]
         {
-            use std::os::unix::io::IntoRawFd;
+            use std::os::unix::io::AsRawFd;
 
             // VUID-vkGetMemoryFdPropertiesKHR-handleType-parameter
             handle_type.validate_device(self)?;
@@ -642,7 +692,7 @@ impl Device {
             (fns.khr_external_memory_fd.get_memory_fd_properties_khr)(
                 self.handle,
                 handle_type.into(),
-                file.into_raw_fd(),
+                file.as_raw_fd(),
                 &mut memory_fd_properties,
             )
             .result()
@@ -654,6 +704,147 @@ impl Device {
         }
     }
 
+    /// Sets the power state of `display`.
+    ///
+    /// The [`ext_display_control`](DeviceExtensions::ext_display_control) extension must be
+    /// enabled on the device.
+    pub fn display_power_control(
+        &self,
+        display: &display::Display,
+        power_state: DisplayPowerState,
+    ) -> Result<(), DisplayError> {
+        self.validate_display_power_control(display, power_state)?;
+
+        unsafe {
+            self.display_power_control_unchecked(display, power_state)?;
+        }
+
+        Ok(())
+    }
+
+    fn validate_display_power_control(
+        &self,
+        display: &display::Display,
+        power_state: DisplayPowerState,
+    ) -> Result<(), DisplayError> {
+        if !self.enabled_extensions().ext_display_control {
+            return Err(DisplayError::RequirementNotMet {
+                required_for: "`display_power_control`",
+                requires_one_of: RequiresOneOf {
+                    device_extensions: &["ext_display_control"],
+                    ..Default::default()
+                },
+            });
+        }
+
+        // VUID-vkDisplayPowerControlEXT-commonparent
+        if display.physical_device() != self.physical_device() {
+            return Err(DisplayError::DisplayPhysicalDeviceMismatch);
+        }
+
+        // VUID-VkDisplayPowerInfoEXT-powerState-parameter
+        power_state.validate_device(self)?;
+
+        Ok(())
+    }
+
+    #[cfg_attr(not(feature = "document_unchecked"), doc(hidden))]
+    pub unsafe fn display_power_control_unchecked(
+        &self,
+        display: &display::Display,
+        power_state: DisplayPowerState,
+    ) -> Result<(), VulkanError> {
+        let info = ash::vk::DisplayPowerInfoEXT {
+            power_state: power_state.into(),
+            ..Default::default()
+        };
+
+        let fns = self.fns();
+        (fns.ext_display_control.display_power_control_ext)(
+            self.handle,
+            display.internal_object(),
+            &info,
+        )
+        .result()
+        .map_err(VulkanError::from)?;
+
+        Ok(())
+    }
+
+    /// Returns a fence that will be signaled when `display_event` happens on `display`.
+    ///
+    /// The fence is signaled once; call this function again to wait for the next event. The
+    /// [`ext_display_control`](DeviceExtensions::ext_display_control) extension must be enabled
+    /// on the device.
+    pub fn register_display_event(
+        self: &Arc<Self>,
+        display: &display::Display,
+        display_event: DisplayEventType,
+    ) -> Result<Fence, DisplayError> {
+        self.validate_register_display_event(display, display_event)?;
+
+        unsafe { Ok(self.register_display_event_unchecked(display, display_event)?) }
+    }
+
+    fn validate_register_display_event(
+        &self,
+        display: &display::Display,
+        display_event: DisplayEventType,
+    ) -> Result<(), DisplayError> {
+        if !self.enabled_extensions().ext_display_control {
+            return Err(DisplayError::RequirementNotMet {
+                required_for: "`register_display_event`",
+                requires_one_of: RequiresOneOf {
+                    device_extensions: &["ext_display_control"],
+                    ..Default::default()
+                },
+            });
+        }
+
+        // VUID-vkRegisterDisplayEventEXT-commonparent
+        if display.physical_device() != self.physical_device() {
+            return Err(DisplayError::DisplayPhysicalDeviceMismatch);
+        }
+
+        // VUID-VkDisplayEventInfoEXT-displayEvent-parameter
+        display_event.validate_device(self)?;
+
+        Ok(())
+    }
+
+    #[cfg_attr(not(feature = "document_unchecked"), doc(hidden))]
+    pub unsafe fn register_display_event_unchecked(
+        self: &Arc<Self>,
+        display: &display::Display,
+        display_event: DisplayEventType,
+    ) -> Result<Fence, VulkanError> {
+        let info = ash::vk::DisplayEventInfoEXT {
+            display_event: display_event.into(),
+            ..Default::default()
+        };
+
+        let handle = {
+            let fns = self.fns();
+            let mut output = MaybeUninit::uninit();
+            (fns.ext_display_control.register_display_event_ext)(
+                self.handle,
+                display.internal_object(),
+                &info,
+                ptr::null(),
+                output.as_mut_ptr(),
+            )
+            .result()
+            .map_err(VulkanError::from)?;
+            output.assume_init()
+        };
+
+        Ok(Fence::from_handle(
+            self.clone(),
+            handle,
+            FenceCreateInfo::default(),
+        ))
+    }
+
     /// Assigns a human-readable name to `object` for debugging purposes.
     ///
     /// If `object_name` is `None`, a previously set object name is removed.
@@ -667,20 +858,55 @@ impl Device {
     ) -> Result<(), OomError> {
         assert!(object.device().internal_object() == self.internal_object());
 
+        unsafe {
+            self.set_debug_utils_object_name_unchecked(
+                T::Object::TYPE,
+                object.internal_object().as_raw(),
+                object_name,
+            )?;
+        }
+
+        Ok(())
+    }
+
+    /// Assigns `object_name` to an object if the `ext_debug_utils` extension is enabled on the
+    /// instance, and does nothing otherwise.
+    ///
+    /// This is used to apply the `name` of create infos, which is optional and therefore
+    /// doesn't require the extension.
+    pub(crate) unsafe fn set_debug_utils_object_name_if_enabled(
+        &self,
+        object_type: ash::vk::ObjectType,
+        object_handle: u64,
+        object_name: Option<&str>,
+    ) -> Result<(), VulkanError> {
+        if !self.instance().enabled_extensions().ext_debug_utils {
+            return Ok(());
+        }
+
+        self.set_debug_utils_object_name_unchecked(object_type, object_handle, object_name)
+    }
+
+    unsafe fn set_debug_utils_object_name_unchecked(
+        &self,
+        object_type: ash::vk::ObjectType,
+        object_handle: u64,
+        object_name: Option<&str>,
+    ) -> Result<(), VulkanError> {
         let object_name_vk = object_name.map(|object_name| CString::new(object_name).unwrap());
         let info = ash::vk::DebugUtilsObjectNameInfoEXT {
-            object_type: T::Object::TYPE,
-            object_handle: object.internal_object().as_raw(),
-            p_object_name: object_name_vk.map_or(ptr::null(), |object_name| object_name.as_ptr()),
+            object_type,
+            object_handle,
+            p_object_name: object_name_vk
+                .as_ref()
+                .map_or(ptr::null(), |object_name| object_name.as_ptr()),
             ..Default::default()
         };
 
-        unsafe {
-            let fns = self.instance().fns();
-            (fns.ext_debug_utils.set_debug_utils_object_name_ext)(self.handle, &info)
-                .result()
-                .map_err(VulkanError::from)?;
-        }
+        let fns = self.instance().fns();
+        (fns.ext_debug_utils.set_debug_utils_object_name_ext)(self.handle, &info)
+            .result()
+            .map_err(VulkanError::from)?;
 
         Ok(())
     }
@@ -1010,8 +1236,8 @@ impl From<RequirementNotMet> for MemoryFdPropertiesError {
 #[cfg(test)]
 mod tests {
     use crate::device::{
-        Device, DeviceCreateInfo, DeviceCreationError, FeatureRestriction, FeatureRestrictionError,
-        Features, QueueCreateInfo,
+        Device, DeviceCreateInfo, DeviceCreationError, DeviceExtensions, FeatureRestriction,
+        FeatureRestrictionError, Features, QueueCreateInfo,
     };
     use std::sync::Arc;
 
@@ -1085,6 +1311,94 @@ mod tests {
         };
     }
 
+    #[test]
+    fn extensions_with_dependencies() {
+        let instance = instance!();
+        let physical_device = match instance.enumerate_physical_devices().unwrap().next() {
+            Some(p) => p,
+            None => return,
+        };
+
+        let extensions = DeviceExtensions {
+            khr_swapchain: true,
+            ..DeviceExtensions::empty()
+        }
+        .intersection(physical_device.supported_extensions());
+        let features = Features {
+            dynamic_rendering: true,
+            ..Features::empty()
+        }
+        .intersection(physical_device.supported_features());
+        let enabled_extensions =
+            physical_device.extensions_with_dependencies(&extensions, &features);
+
+        assert!(enabled_extensions.contains(&extensions));
+        assert!(physical_device
+            .supported_extensions()
+            .contains(&enabled_extensions));
+
+        Device::new(
+            physical_device,
+            DeviceCreateInfo {
+                enabled_extensions,
+                enabled_features: features,
+                queue_create_infos: vec![QueueCreateInfo {
+                    queue_family_index: 0,
+                    ..Default::default()
+                }],
+                ..Default::default()
+            },
+        )
+        .unwrap();
+    }
+
+    #[test]
+    fn features_with_dependencies() {
+        let instance = instance!();
+        let physical_device = match instance.enumerate_physical_devices().unwrap().next() {
+            Some(p) => p,
+            None => return,
+        };
+
+        let usable_features = physical_device.usable_features();
+        assert!(physical_device
+            .supported_features()
+            .contains(&usable_features));
+        assert!(physical_device
+            .supported_extensions()
+            .contains(&physical_device.usable_extensions()));
+
+        let features = Features {
+            sparse_image_int64_atomics: true,
+            ..Features::empty()
+        }
+        .intersection(&usable_features);
+        let enabled_features =
+            physical_device.features_with_dependencies(&features, &DeviceExtensions::empty());
+        let enabled_extensions =
+            physical_device.extensions_with_dependencies(&DeviceExtensions::empty(), &features);
+
+        assert!(enabled_features.contains(&features));
+        assert_eq!(
+            enabled_features.shader_image_int64_atomics,
+            features.sparse_image_int64_atomics,
+        );
+
+        Device::new(
+            physical_device,
+            DeviceCreateInfo {
+                enabled_extensions,
+                enabled_features,
+                queue_create_infos: vec![QueueCreateInfo {
+                    queue_family_index: 0,
+                    ..Default::default()
+                }],
+                ..Default::default()
+            },
+        )
+        .unwrap();
+    }
+
     #[test]
     fn priority_out_of_range() {
         let instance = instance!();
@@ -12,10 +12,10 @@ use crate::{
     buffer::{ExternalBufferInfo, ExternalBufferProperties},
     cache::OnceCache,
     device::{DeviceExtensions, Features, FeaturesFfi, Properties, PropertiesFfi},
-    format::{Format, FormatProperties},
+    format::{DrmFormatModifierProperties, Format, FormatProperties},
     image::{
-        ImageCreateFlags, ImageFormatInfo, ImageFormatProperties, ImageUsage,
-        SparseImageFormatInfo, SparseImageFormatProperties,
+        ImageCreateFlags, ImageFormatInfo, ImageFormatProperties, ImageTiling, ImageUsage,
+        SampleCounts, SparseImageFormatInfo, SparseImageFormatProperties,
     },
     instance::Instance,
     macros::{vulkan_bitflags, vulkan_enum},
@@ -406,6 +406,100 @@ impl PhysicalDevice {
         &self.supported_features
     }
 
+    /// Returns the supported extensions whose dependencies can all be met on this physical
+    /// device, by its API version, by the enabled instance extensions, or by other supported
+    /// extensions.
+    ///
+    /// An extension that is supported but missing from this list can't be enabled on a device.
+    #[inline]
+    pub fn usable_extensions(&self) -> DeviceExtensions {
+        DeviceExtensions::usable(
+            &self.supported_extensions,
+            self.api_version,
+            self.instance.enabled_extensions(),
+        )
+    }
+
+    /// Returns the supported features whose dependencies are also supported by this physical
+    /// device.
+    ///
+    /// A feature that is supported but missing from this list can't be enabled on a device.
+    #[inline]
+    pub fn usable_features(&self) -> Features {
+        Features::usable(&self.supported_features)
+    }
+
+    /// Returns `extensions`, with the supported extensions added that are needed to enable
+    /// `extensions` and `features` on a device created from this physical device.
+    ///
+    /// This includes the extensions needed by the features that
+    /// [`features_with_dependencies`](Self::features_with_dependencies) adds, and the
+    /// dependencies of the added extensions themselves. Dependencies that are already provided by
+    /// the API version of the physical device or by the enabled instance extensions are not
+    /// added. For example, enabling the `dynamic_rendering` feature adds the
+    /// `khr_dynamic_rendering` extension, but only if the device doesn't support Vulkan 1.3.
+    ///
+    /// Dependencies that are not supported are skipped; creating the device will then return an
+    /// error. Only requesting extensions and features from
+    /// [`usable_extensions`](Self::usable_extensions) and
+    /// [`usable_features`](Self::usable_features) avoids this.
+    #[inline]
+    pub fn extensions_with_dependencies(
+        &self,
+        extensions: &DeviceExtensions,
+        features: &Features,
+    ) -> DeviceExtensions {
+        self.resolve_dependencies(extensions, features).0
+    }
+
+    /// Returns `features`, with the supported features added that are needed to enable
+    /// `features` and the extensions returned by
+    /// [`extensions_with_dependencies`](Self::extensions_with_dependencies) on a device created
+    /// from this physical device.
+    ///
+    /// For example, enabling the `sparse_image_int64_atomics` feature adds the
+    /// `shader_image_int64_atomics` feature. Dependencies that are not supported are skipped;
+    /// creating the device will then return an error.
+    #[inline]
+    pub fn features_with_dependencies(
+        &self,
+        features: &Features,
+        extensions: &DeviceExtensions,
+    ) -> Features {
+        self.resolve_dependencies(extensions, features).1
+    }
+
+    fn resolve_dependencies(
+        &self,
+        extensions: &DeviceExtensions,
+        features: &Features,
+    ) -> (DeviceExtensions, Features) {
+        let instance_extensions = self.instance.enabled_extensions();
+        let mut extensions = *extensions;
+        let mut features = *features;
+
+        // Enabling an extension or a feature can add dependencies of its own, so repeat until
+        // nothing changes anymore.
+        loop {
+            let previous = (extensions, features);
+            features.add_dependencies(&self.supported_features, self.api_version, &extensions);
+            features.add_required_extensions(
+                &mut extensions,
+                &self.supported_extensions,
+                self.api_version,
+            );
+            extensions.add_dependencies(
+                &self.supported_extensions,
+                self.api_version,
+                instance_extensions,
+            );
+
+            if (extensions, features) == previous {
+                return (extensions, features);
+            }
+        }
+    }
+
     /// Returns the memory properties reported by the physical device.
     #[inline]
     pub fn memory_properties(&self) -> &MemoryProperties {
@@ -901,6 +995,193 @@ impl PhysicalDevice {
         })
     }
 
+    /// Returns the fragment shading rates that are supported by the physical device, and the
+    /// sample counts that each rate can be used with.
+    ///
+    /// The returned list is ordered from the largest fragment size to the smallest, and always
+    /// contains a fragment size of `[1, 1]` that supports all sample counts.
+    ///
+    /// The [`khr_fragment_shading_rate`](crate::device::DeviceExtensions::khr_fragment_shading_rate)
+    /// extension must be supported by the physical device.
+    #[inline]
+    pub fn fragment_shading_rates(
+        &self,
+    ) -> Result<Vec<PhysicalDeviceFragmentShadingRate>, PhysicalDeviceError> {
+        self.validate_fragment_shading_rates()?;
+
+        unsafe { Ok(self.fragment_shading_rates_unchecked()?) }
+    }
+
+    fn validate_fragment_shading_rates(&self) -> Result<(), PhysicalDeviceError> {
+        if !self.supported_extensions().khr_fragment_shading_rate {
+            return Err(PhysicalDeviceError::RequirementNotMet {
+                required_for: "`fragment_shading_rates`",
+                requires_one_of: RequiresOneOf {
+                    device_extensions: &["khr_fragment_shading_rate"],
+                    ..Default::default()
+                },
+            });
+        }
+
+        Ok(())
+    }
+
+    #[cfg_attr(not(feature = "document_unchecked"), doc(hidden))]
+    #[inline]
+    pub unsafe fn fragment_shading_rates_unchecked(
+        &self,
+    ) -> Result<Vec<PhysicalDeviceFragmentShadingRate>, VulkanError> {
+        let fns = self.instance.fns();
+
+        loop {
+            let mut count = 0;
+            (fns.khr_fragment_shading_rate
+                .get_physical_device_fragment_shading_rates_khr)(
+                self.internal_object(),
+                &mut count,
+                ptr::null_mut(),
+            )
+            .result()
+            .map_err(VulkanError::from)?;
+
+            let mut fragment_shading_rates =
+                vec![ash::vk::PhysicalDeviceFragmentShadingRateKHR::default(); count as usize];
+            let result = (fns
+                .khr_fragment_shading_rate
+                .get_physical_device_fragment_shading_rates_khr)(
+                self.internal_object(),
+                &mut count,
+                fragment_shading_rates.as_mut_ptr(),
+            );
+
+            match result {
+                ash::vk::Result::INCOMPLETE => (),
+                ash::vk::Result::SUCCESS => {
+                    fragment_shading_rates.truncate(count as usize);
+
+                    return Ok(fragment_shading_rates
+                        .into_iter()
+                        .map(|fragment_shading_rate| PhysicalDeviceFragmentShadingRate {
+                            sample_counts: fragment_shading_rate.sample_counts.into(),
+                            fragment_size: [
+                                fragment_shading_rate.fragment_size.width,
+                                fragment_shading_rate.fragment_size.height,
+                            ],
+                        })
+                        .collect());
+                }
+                err => return Err(VulkanError::from(err)),
+            }
+        }
+    }
+
+    /// Returns the DRM format modifiers that are supported for a given format, and the properties
+    /// of the format when used with each of them.
+    ///
+    /// The returned modifiers can be used to create an image with
+    /// [`ImageTiling::DrmFormatModifier`](crate::image::ImageTiling::DrmFormatModifier).
+    #[inline]
+    pub fn drm_format_modifier_properties(
+        &self,
+        format: Format,
+    ) -> Result<Vec<DrmFormatModifierProperties>, PhysicalDeviceError> {
+        self.validate_drm_format_modifier_properties(format)?;
+
+        unsafe { Ok(self.drm_format_modifier_properties_unchecked(format)) }
+    }
+
+    fn validate_drm_format_modifier_properties(
+        &self,
+        format: Format,
+    ) -> Result<(), PhysicalDeviceError> {
+        if !(self.api_version() >= Version::V1_1
+            || self
+                .instance
+                .enabled_extensions()
+                .khr_get_physical_device_properties2)
+        {
+            return Err(PhysicalDeviceError::RequirementNotMet {
+                required_for: "`drm_format_modifier_properties`",
+                requires_one_of: RequiresOneOf {
+                    api_version: Some(Version::V1_1),
+                    instance_extensions: &["khr_get_physical_device_properties2"],
+                    ..Default::default()
+                },
+            });
+        }
+
+        if !self.supported_extensions().ext_image_drm_format_modifier {
+            return Err(PhysicalDeviceError::RequirementNotMet {
+                required_for: "`drm_format_modifier_properties`",
+                requires_one_of: RequiresOneOf {
+                    device_extensions: &["ext_image_drm_format_modifier"],
+                    ..Default::default()
+                },
+            });
+        }
+
+        // VUID-vkGetPhysicalDeviceFormatProperties2-format-parameter
+        format.validate_physical_device(self)?;
+
+        Ok(())
+    }
+
+    #[cfg_attr(not(feature = "document_unchecked"), doc(hidden))]
+    pub unsafe fn drm_format_modifier_properties_unchecked(
+        &self,
+        format: Format,
+    ) -> Vec<DrmFormatModifierProperties> {
+        let get_format_properties2 = |format_properties2: &mut ash::vk::FormatProperties2| {
+            let fns = self.instance.fns();
+
+            if self.api_version() >= Version::V1_1 {
+                (fns.v1_1.get_physical_device_format_properties2)(
+                    self.handle,
+                    format.into(),
+                    format_properties2,
+                );
+            } else {
+                (fns.khr_get_physical_device_properties2
+                    .get_physical_device_format_properties2_khr)(
+                    self.handle,
+                    format.into(),
+                    format_properties2,
+                );
+            }
+        };
+
+        let mut modifier_properties_list = ash::vk::DrmFormatModifierPropertiesListEXT::default();
+        let mut format_properties2 = ash::vk::FormatProperties2 {
+            p_next: &mut modifier_properties_list as *mut _ as *mut _,
+            ..Default::default()
+        };
+        get_format_properties2(&mut format_properties2);
+
+        let mut modifier_properties_vk =
+            Vec::with_capacity(modifier_properties_list.drm_format_modifier_count as usize);
+        modifier_properties_list.p_drm_format_modifier_properties =
+            modifier_properties_vk.as_mut_ptr();
+        let mut format_properties2 = ash::vk::FormatProperties2 {
+            p_next: &mut modifier_properties_list as *mut _ as *mut _,
+            ..Default::default()
+        };
+        get_format_properties2(&mut format_properties2);
+        modifier_properties_vk.set_len(modifier_properties_list.drm_format_modifier_count as usize);
+
+        modifier_properties_vk
+            .into_iter()
+            .map(|properties: ash::vk::DrmFormatModifierPropertiesEXT| {
+                DrmFormatModifierProperties {
+                    drm_format_modifier: properties.drm_format_modifier,
+                    drm_format_modifier_plane_count: properties.drm_format_modifier_plane_count,
+                    drm_format_modifier_tiling_features: properties
+                        .drm_format_modifier_tiling_features
+                        .into(),
+                }
+            })
+            .collect()
+    }
+
     /// Returns the properties supported for images with a given image configuration.
     ///
     /// `Some` is returned if the configuration is supported, `None` if it is not.
@@ -934,6 +1215,7 @@ impl PhysicalDevice {
             mut stencil_usage,
             external_memory_handle_type,
             image_view_type,
+            drm_format_modifier,
             mutable_format: _,
             cube_compatible: _,
             array_2d_compatible: _,
@@ -961,6 +1243,13 @@ impl PhysicalDevice {
         // VUID-VkPhysicalDeviceImageFormatInfo2-tiling-parameter
         tiling.validate_physical_device(self)?;
 
+        // VUID-VkPhysicalDeviceImageFormatInfo2-tiling-02249
+        // VUID-VkPhysicalDeviceImageFormatInfo2-tiling-02313
+        assert_eq!(
+            tiling == ImageTiling::DrmFormatModifier,
+            drm_format_modifier.is_some()
+        );
+
         // VUID-VkPhysicalDeviceImageFormatInfo2-usage-parameter
         usage.validate_physical_device(self)?;
 
@@ -1059,6 +1348,7 @@ impl PhysicalDevice {
                     stencil_usage,
                     external_memory_handle_type,
                     image_view_type,
+                    drm_format_modifier,
                     mutable_format,
                     cube_compatible,
                     array_2d_compatible,
@@ -1087,6 +1377,7 @@ impl PhysicalDevice {
                 let mut external_info_vk = None;
                 let mut image_view_info_vk = None;
                 let mut stencil_usage_info_vk = None;
+                let mut drm_format_modifier_info_vk = None;
 
                 if let Some(handle_type) = external_memory_handle_type {
                     let next =
@@ -1121,6 +1412,19 @@ impl PhysicalDevice {
                     info2_vk.p_next = next as *const _ as *const _;
                 }
 
+                if let Some(drm_format_modifier) = drm_format_modifier {
+                    let next = drm_format_modifier_info_vk.insert(
+                        ash::vk::PhysicalDeviceImageDrmFormatModifierInfoEXT {
+                            drm_format_modifier,
+                            sharing_mode: ash::vk::SharingMode::EXCLUSIVE,
+                            ..Default::default()
+                        },
+                    );
+
+                    next.p_next = info2_vk.p_next as *mut _;
+                    info2_vk.p_next = next as *const _ as *const _;
+                }
+
                 /* Output */
 
                 let mut properties2_vk = ash::vk::ImageFormatProperties2::default();
@@ -2558,6 +2862,17 @@ vulkan_enum! {
     MoltenVK = MOLTENVK,
 }
 
+/// A fragment shading rate that is supported by a physical device.
+#[derive(Clone, Debug)]
+#[non_exhaustive]
+pub struct PhysicalDeviceFragmentShadingRate {
+    /// The sample counts of the framebuffer that the fragment size can be used with.
+    pub sample_counts: SampleCounts,
+
+    /// The size, in pixels, of the area covered by a single fragment shader invocation.
+    pub fragment_size: [u32; 2],
+}
+
 /// Information provided about an active tool.
 #[derive(Clone, Debug)]
 #[non_exhaustive]
@@ -2767,3 +3082,47 @@ impl From<RequirementNotMet> for PhysicalDeviceError {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::PhysicalDeviceError;
+    use crate::{
+        format::Format,
+        instance::{Instance, InstanceCreateInfo},
+        RequiresOneOf, Version, VulkanLibrary,
+    };
+
+    #[test]
+    fn drm_format_modifier_properties_missing_extension() {
+        let library = match VulkanLibrary::new() {
+            Ok(x) => x,
+            Err(_) => return,
+        };
+        let instance = match Instance::new(
+            library,
+            InstanceCreateInfo {
+                max_api_version: Some(Version::V1_0),
+                ..Default::default()
+            },
+        ) {
+            Ok(x) => x,
+            Err(_) => return,
+        };
+        let physical_device = match instance.enumerate_physical_devices().unwrap().next() {
+            Some(x) => x,
+            None => return,
+        };
+
+        match physical_device.drm_format_modifier_properties(Format::R8G8B8A8_UNORM) {
+            Err(PhysicalDeviceError::RequirementNotMet {
+                requires_one_of:
+                    RequiresOneOf {
+                        instance_extensions,
+                        ..
+                    },
+                ..
+            }) if instance_extensions.contains(&"khr_get_physical_device_properties2") => (),
+            _ => panic!(),
+        }
+    }
+}
@@ -742,6 +742,24 @@ impl FormatProperties {
     }
 }
 
+/// The properties of a format when used with a particular DRM format modifier.
+///
+/// Returned by
+/// [`PhysicalDevice::drm_format_modifier_properties`](crate::device::physical::PhysicalDevice::drm_format_modifier_properties).
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub struct DrmFormatModifierProperties {
+    /// The Linux DRM format modifier.
+    pub drm_format_modifier: u64,
+
+    /// The number of memory planes that an image must have when it is created with this
+    /// modifier. This can differ from the number of planes of the format itself.
+    pub drm_format_modifier_plane_count: u32,
+
+    /// Features available for images created with this modifier.
+    pub drm_format_modifier_tiling_features: FormatFeatures,
+}
+
 vulkan_bitflags! {
     /// The features supported by a device for an image or buffer with a particular format.
     #[non_exhaustive]
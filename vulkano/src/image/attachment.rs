@@ -102,6 +102,22 @@ impl AttachmentImage {
         )
     }
 
+    /// Same as `new`, but also gives the image a name.
+    ///
+    /// See [`UnsafeImage::set_name`] for how the name is used.
+    #[inline]
+    pub fn named(
+        device: Arc<Device>,
+        dimensions: [u32; 2],
+        format: Format,
+        name: &str,
+    ) -> Result<Arc<AttachmentImage>, ImageCreationError> {
+        let image = AttachmentImage::new(device, dimensions, format)?;
+        image.image.set_name(Some(name))?;
+
+        Ok(image)
+    }
+
     /// Same as `new`, but creates an image that can be used as an input attachment.
     ///
     /// > **Note**: This function is just a convenient shortcut for `with_usage`.
@@ -529,6 +545,10 @@ impl AttachmentImage {
                     AllocFromRequirementsFilter::Allowed
                 }
             },
+            ExternalMemoryHandleTypes {
+                opaque_fd: true,
+                ..ExternalMemoryHandleTypes::empty()
+            },
         )?;
 
         debug_assert!((memory.offset() % mem_reqs.alignment) == 0);
@@ -330,6 +330,10 @@ where
         true
     }
 
+    fn is_layout_tracked(&self) -> bool {
+        true
+    }
+
     fn initial_layout_requirement(&self) -> ImageLayout {
         self.layout
     }
@@ -51,6 +51,7 @@ pub use self::{
     attachment::AttachmentImage,
     immutable::ImmutableImage,
     layout::{ImageDescriptorLayouts, ImageLayout},
+    sparse::{SparseImage, SparseImageRegion},
     storage::{StorageImage, SubresourceData},
     swapchain::SwapchainImage,
     sys::ImageCreationError,
@@ -70,6 +71,7 @@ mod aspect;
 pub mod attachment; // TODO: make private
 pub mod immutable; // TODO: make private
 mod layout;
+mod sparse;
 mod storage;
 pub mod swapchain; // TODO: make private
 pub mod sys;
@@ -662,6 +664,13 @@ pub struct ImageFormatInfo {
     /// The default value is `None`.
     pub image_view_type: Option<ImageViewType>,
 
+    /// The Linux DRM format modifier that the image will have.
+    ///
+    /// Must be `Some` if `tiling` is [`ImageTiling::DrmFormatModifier`], and `None` otherwise.
+    ///
+    /// The default value is `None`.
+    pub drm_format_modifier: Option<u64>,
+
     /// The `mutable_format` that the image will have.
     ///
     /// The default value is `false`.
@@ -696,6 +705,7 @@ impl Default for ImageFormatInfo {
             stencil_usage: ImageUsage::empty(),
             external_memory_handle_type: None,
             image_view_type: None,
+            drm_format_modifier: None,
             mutable_format: false,
             cube_compatible: false,
             array_2d_compatible: false,
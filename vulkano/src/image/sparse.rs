@@ -0,0 +1,863 @@
+// Copyright (c) 2022 The vulkano developers
+// Licensed under the Apache License, Version 2.0
+// <LICENSE-APACHE or
+// https://www.apache.org/licenses/LICENSE-2.0> or the MIT
+// license <LICENSE-MIT or https://opensource.org/licenses/MIT>,
+// at your option. All files in the project carrying such
+// notice may not be copied, modified, or distributed except
+// according to those terms.
+
+use super::{
+    sys::{SparseLevel, UnsafeImage, UnsafeImageCreateInfo},
+    ImageAccess, ImageAspects, ImageDescriptorLayouts, ImageDimensions, ImageInner, ImageLayout,
+    ImageTiling, ImageUsage, MipmapsCount, SampleCount, SparseImageFormatInfo,
+    SparseImageMemoryRequirements,
+};
+use crate::{
+    device::{Device, DeviceOwned, Queue},
+    format::Format,
+    memory::{
+        sparse::check_image_unused, BindSparseInfo, SparseBindFuture, SparseError,
+        SparseImageMemoryBind, SparseImageOpaqueMemoryBind, SparsePage, SparsePagePool,
+    },
+    sync::{now, GpuFuture, NowFuture, Sharing},
+    DeviceSize,
+};
+use ahash::HashMap;
+use parking_lot::Mutex;
+use smallvec::SmallVec;
+use std::{
+    hash::{Hash, Hasher},
+    mem,
+    ops::Range,
+    sync::Arc,
+};
+
+/// Image that is only partially resident in memory, with residency tracked per sparse block.
+///
+/// A `SparseImage` is created without any memory. Memory is bound to individual sparse blocks
+/// with [`bind_blocks`](SparseImage::bind_blocks) and released with
+/// [`unbind_blocks`](SparseImage::unbind_blocks). Each block takes one page from the image's
+/// [`SparsePagePool`]. Sampling a block that is not resident returns undefined values, but is
+/// otherwise safe.
+///
+/// The smallest mip levels, whose dimensions are smaller than a sparse block, are packed together
+/// in the mip tail. The mip tail can not be bound per block, and must be bound as a whole with
+/// [`bind_mip_tail`](SparseImage::bind_mip_tail). If the image has a metadata aspect, it is
+/// bound together with the mip tail, and must be resident before the image is used.
+///
+/// Creating a `SparseImage` requires the
+/// [`sparse_binding`](crate::device::Features::sparse_binding) feature to be enabled on the
+/// device, as well as
+/// [`sparse_residency_image2_d`](crate::device::Features::sparse_residency_image2_d) or
+/// [`sparse_residency_image3_d`](crate::device::Features::sparse_residency_image3_d) depending on
+/// the dimensions. Binding memory requires a queue whose queue family supports sparse binding.
+#[derive(Debug)]
+pub struct SparseImage {
+    image: Arc<UnsafeImage>,
+    page_pool: Arc<SparsePagePool>,
+    sparse_requirements: Vec<SparseImageMemoryRequirements>,
+    state: Mutex<SparseImageState>,
+}
+
+#[derive(Debug, Default)]
+struct SparseImageState {
+    // Keyed by index into `sparse_requirements`, mip level, array layer and block coordinates.
+    blocks: HashMap<(usize, u32, u32, [u32; 3]), SparsePage>,
+    mip_tail: Vec<SparsePage>,
+}
+
+impl SparseImage {
+    /// Creates a new `SparseImage`, with no memory resident.
+    ///
+    /// A new page pool is created for the image.
+    ///
+    /// # Panics
+    ///
+    /// - Panics if one of the values in `dimensions` is zero.
+    /// - Panics if `usage` is empty.
+    pub fn new(
+        device: Arc<Device>,
+        dimensions: ImageDimensions,
+        format: Format,
+        mip_levels: impl Into<MipmapsCount>,
+        usage: ImageUsage,
+        queue_family_indices: impl IntoIterator<Item = u32>,
+    ) -> Result<Arc<SparseImage>, SparseError> {
+        let image = Self::build_image(
+            &device,
+            dimensions,
+            format,
+            mip_levels.into(),
+            usage,
+            queue_family_indices,
+        )?;
+        let page_pool = SparsePagePool::new(device, &image.memory_requirements());
+
+        Ok(Self::from_parts(image, page_pool))
+    }
+
+    /// Same as `new`, but takes pages from an existing page pool, which can be shared with other
+    /// sparse resources.
+    ///
+    /// # Panics
+    ///
+    /// - Panics if one of the values in `dimensions` is zero.
+    /// - Panics if `usage` is empty.
+    /// - Panics if `page_pool` was not created from `device`.
+    pub fn with_page_pool(
+        device: Arc<Device>,
+        dimensions: ImageDimensions,
+        format: Format,
+        mip_levels: impl Into<MipmapsCount>,
+        usage: ImageUsage,
+        queue_family_indices: impl IntoIterator<Item = u32>,
+        page_pool: Arc<SparsePagePool>,
+    ) -> Result<Arc<SparseImage>, SparseError> {
+        assert_eq!(&device, page_pool.device());
+
+        let image = Self::build_image(
+            &device,
+            dimensions,
+            format,
+            mip_levels.into(),
+            usage,
+            queue_family_indices,
+        )?;
+
+        if !page_pool.is_compatible(&image.memory_requirements()) {
+            return Err(SparseError::PagePoolIncompatible);
+        }
+
+        Ok(Self::from_parts(image, page_pool))
+    }
+
+    fn build_image(
+        device: &Arc<Device>,
+        dimensions: ImageDimensions,
+        format: Format,
+        mip_levels: MipmapsCount,
+        usage: ImageUsage,
+        queue_family_indices: impl IntoIterator<Item = u32>,
+    ) -> Result<Arc<UnsafeImage>, SparseError> {
+        let queue_family_indices: SmallVec<[_; 4]> = queue_family_indices.into_iter().collect();
+
+        format.validate_device(device)?;
+        usage.validate_device(device)?;
+        assert!(!usage.is_empty());
+
+        // Use unchecked, because all validation has been done above.
+        let format_properties = unsafe {
+            device
+                .physical_device()
+                .sparse_image_format_properties_unchecked(SparseImageFormatInfo {
+                    format: Some(format),
+                    image_type: dimensions.image_type(),
+                    samples: SampleCount::Sample1,
+                    usage,
+                    tiling: ImageTiling::Optimal,
+                    ..Default::default()
+                })
+        };
+
+        if format_properties.is_empty() {
+            return Err(SparseError::FormatNotSupported);
+        }
+
+        Ok(UnsafeImage::new(
+            device.clone(),
+            UnsafeImageCreateInfo {
+                dimensions,
+                format: Some(format),
+                mip_levels: match mip_levels {
+                    MipmapsCount::Specific(num) => num,
+                    MipmapsCount::Log2 => dimensions.max_mip_levels(),
+                    MipmapsCount::One => 1,
+                },
+                usage,
+                sharing: if queue_family_indices.len() >= 2 {
+                    Sharing::Concurrent(queue_family_indices)
+                } else {
+                    Sharing::Exclusive
+                },
+                sparse: Some(SparseLevel {
+                    sparse_residency: true,
+                    ..SparseLevel::empty()
+                }),
+                ..Default::default()
+            },
+        )?)
+    }
+
+    fn from_parts(image: Arc<UnsafeImage>, page_pool: Arc<SparsePagePool>) -> Arc<SparseImage> {
+        let sparse_requirements = image.sparse_memory_requirements();
+
+        Arc::new(SparseImage {
+            image,
+            page_pool,
+            sparse_requirements,
+            state: Mutex::new(Default::default()),
+        })
+    }
+
+    /// Returns the sparse memory requirements of the image, one for each group of aspects that
+    /// is bound separately.
+    #[inline]
+    pub fn sparse_memory_requirements(&self) -> &[SparseImageMemoryRequirements] {
+        &self.sparse_requirements
+    }
+
+    /// Returns the pool that pages of the image are taken from.
+    #[inline]
+    pub fn page_pool(&self) -> &Arc<SparsePagePool> {
+        &self.page_pool
+    }
+
+    /// Returns the number of sparse blocks in each dimension of `mip_level`, for the given
+    /// `aspects`.
+    ///
+    /// Returns `None` if `aspects` does not match the aspects of one of the sparse memory
+    /// requirements, or if `mip_level` is part of the mip tail or out of range.
+    pub fn block_count(&self, aspects: ImageAspects, mip_level: u32) -> Option<[u32; 3]> {
+        let requirements_index = self.requirements_index(aspects).ok()?;
+        self.mip_level_blocks(requirements_index, mip_level)
+            .map(|(block_count, _)| block_count)
+    }
+
+    /// Returns whether memory has been bound to a sparse block.
+    ///
+    /// The residency is updated when the bind operation returned by `bind_blocks` or
+    /// `unbind_blocks` is submitted to the queue, not when it is executed.
+    pub fn is_block_resident(
+        &self,
+        aspects: ImageAspects,
+        mip_level: u32,
+        array_layer: u32,
+        block: [u32; 3],
+    ) -> bool {
+        self.requirements_index(aspects)
+            .map_or(false, |requirements_index| {
+                self.state.lock().blocks.contains_key(&(
+                    requirements_index,
+                    mip_level,
+                    array_layer,
+                    block,
+                ))
+            })
+    }
+
+    /// Returns whether the image has a mip tail or a metadata aspect, that must be bound with
+    /// `bind_mip_tail`.
+    #[inline]
+    pub fn has_mip_tail(&self) -> bool {
+        self.mip_tail_regions().next().is_some()
+    }
+
+    /// Returns whether memory has been bound to the mip tail.
+    #[inline]
+    pub fn is_mip_tail_resident(&self) -> bool {
+        !self.state.lock().mip_tail.is_empty()
+    }
+
+    /// Binds memory from the page pool to the blocks in `region`, on `queue`.
+    ///
+    /// Blocks that are already resident are left untouched.
+    #[inline]
+    pub fn bind_blocks(
+        self: &Arc<Self>,
+        queue: Arc<Queue>,
+        region: SparseImageRegion,
+    ) -> Result<SparseBindFuture<NowFuture>, SparseError> {
+        let device = queue.device().clone();
+        self.bind_blocks_after(now(device), queue, region)
+    }
+
+    /// Same as `bind_blocks`, but the bind operation is executed after an existing future.
+    ///
+    /// # Panics
+    ///
+    /// - Panics if the device of the image is not the same as the device of the future.
+    pub fn bind_blocks_after<F>(
+        self: &Arc<Self>,
+        future: F,
+        queue: Arc<Queue>,
+        region: SparseImageRegion,
+    ) -> Result<SparseBindFuture<F>, SparseError>
+    where
+        F: GpuFuture,
+    {
+        assert_eq!(self.device(), future.device());
+
+        let requirements_index = self.requirements_index(region.aspects)?;
+        let (_, mip_extent) = self.validate_region(requirements_index, &region)?;
+        let granularity = self.sparse_requirements[requirements_index]
+            .format_properties
+            .image_granularity;
+
+        let state = self.state.lock();
+        let mut new_blocks = Vec::new();
+
+        for block in region.blocks() {
+            let key = (
+                requirements_index,
+                region.mip_level,
+                region.array_layer,
+                block,
+            );
+
+            if !state.blocks.contains_key(&key) {
+                new_blocks.push((key, self.page_pool.allocate()?));
+            }
+        }
+
+        drop(state);
+
+        let memory_binds = new_blocks
+            .iter()
+            .map(|&((_, _, _, block), ref page)| {
+                let (offset, extent) = block_bounds(block, granularity, mip_extent);
+
+                SparseImageMemoryBind {
+                    aspects: region.aspects,
+                    mip_level: region.mip_level,
+                    array_layer: region.array_layer,
+                    offset,
+                    extent,
+                    memory: page.memory_bind(),
+                }
+            })
+            .collect();
+        let future = SparseBindFuture::new(
+            future,
+            queue,
+            BindSparseInfo {
+                image_binds: vec![(self.clone() as Arc<dyn ImageAccess>, memory_binds)],
+                ..Default::default()
+            },
+        )?;
+        let image = self.clone();
+
+        Ok(future.with_residency_update(move || {
+            let mut state = image.state.lock();
+
+            // If another bind operation was submitted for the same blocks in the meantime, its
+            // memory has now been replaced.
+            new_blocks
+                .into_iter()
+                .filter_map(|(key, page)| state.blocks.insert(key, page))
+                .collect()
+        }))
+    }
+
+    /// Unbinds the memory of the blocks in `region`, on `queue`.
+    ///
+    /// The unbound memory is returned to the page pool once the bind operation has finished
+    /// executing. Blocks that are not resident are left untouched.
+    ///
+    /// Returns an error if the image may still be in use by a command buffer that was submitted
+    /// before, and that isn't part of the future that the bind operation is executed after.
+    #[inline]
+    pub fn unbind_blocks(
+        self: &Arc<Self>,
+        queue: Arc<Queue>,
+        region: SparseImageRegion,
+    ) -> Result<SparseBindFuture<NowFuture>, SparseError> {
+        let device = queue.device().clone();
+        self.unbind_blocks_after(now(device), queue, region)
+    }
+
+    /// Same as `unbind_blocks`, but the bind operation is executed after an existing future.
+    ///
+    /// # Panics
+    ///
+    /// - Panics if the device of the image is not the same as the device of the future.
+    pub fn unbind_blocks_after<F>(
+        self: &Arc<Self>,
+        future: F,
+        queue: Arc<Queue>,
+        region: SparseImageRegion,
+    ) -> Result<SparseBindFuture<F>, SparseError>
+    where
+        F: GpuFuture,
+    {
+        assert_eq!(self.device(), future.device());
+
+        let requirements_index = self.requirements_index(region.aspects)?;
+        let (_, mip_extent) = self.validate_region(requirements_index, &region)?;
+        let granularity = self.sparse_requirements[requirements_index]
+            .format_properties
+            .image_granularity;
+
+        check_image_unused(&future, &self.image, &queue)?;
+
+        let state = self.state.lock();
+        let keys: Vec<_> = region
+            .blocks()
+            .map(|block| {
+                (
+                    requirements_index,
+                    region.mip_level,
+                    region.array_layer,
+                    block,
+                )
+            })
+            .filter(|key| state.blocks.contains_key(key))
+            .collect();
+        drop(state);
+
+        let memory_binds = keys
+            .iter()
+            .map(|&(_, _, _, block)| {
+                let (offset, extent) = block_bounds(block, granularity, mip_extent);
+
+                SparseImageMemoryBind {
+                    aspects: region.aspects,
+                    mip_level: region.mip_level,
+                    array_layer: region.array_layer,
+                    offset,
+                    extent,
+                    memory: None,
+                }
+            })
+            .collect();
+        let future = SparseBindFuture::new(
+            future,
+            queue,
+            BindSparseInfo {
+                image_binds: vec![(self.clone() as Arc<dyn ImageAccess>, memory_binds)],
+                ..Default::default()
+            },
+        )?;
+        let image = self.clone();
+
+        Ok(future.with_residency_update(move || {
+            let mut state = image.state.lock();
+
+            keys.iter()
+                .filter_map(|key| state.blocks.remove(key))
+                .collect()
+        }))
+    }
+
+    /// Binds memory from the page pool to the whole mip tail and the metadata aspect, on `queue`.
+    ///
+    /// If the mip tail is already resident, the bind operation does nothing.
+    #[inline]
+    pub fn bind_mip_tail(
+        self: &Arc<Self>,
+        queue: Arc<Queue>,
+    ) -> Result<SparseBindFuture<NowFuture>, SparseError> {
+        let device = queue.device().clone();
+        self.bind_mip_tail_after(now(device), queue)
+    }
+
+    /// Same as `bind_mip_tail`, but the bind operation is executed after an existing future.
+    ///
+    /// # Panics
+    ///
+    /// - Panics if the device of the image is not the same as the device of the future.
+    pub fn bind_mip_tail_after<F>(
+        self: &Arc<Self>,
+        future: F,
+        queue: Arc<Queue>,
+    ) -> Result<SparseBindFuture<F>, SparseError>
+    where
+        F: GpuFuture,
+    {
+        assert_eq!(self.device(), future.device());
+
+        let mut new_pages = Vec::new();
+        let mut memory_binds = Vec::new();
+
+        if !self.is_mip_tail_resident() {
+            let page_size = self.page_pool.page_size();
+
+            for (range, metadata) in self.mip_tail_regions() {
+                for resource_offset in (range.start..range.end).step_by(page_size as usize) {
+                    let page = self.page_pool.allocate()?;
+                    memory_binds.push(SparseImageOpaqueMemoryBind {
+                        resource_offset,
+                        size: page_size,
+                        memory: page.memory_bind(),
+                        metadata,
+                    });
+                    new_pages.push(page);
+                }
+            }
+        }
+
+        let future = SparseBindFuture::new(
+            future,
+            queue,
+            BindSparseInfo {
+                image_opaque_binds: vec![(self.clone() as Arc<dyn ImageAccess>, memory_binds)],
+                ..Default::default()
+            },
+        )?;
+        let image = self.clone();
+
+        Ok(future.with_residency_update(move || {
+            if new_pages.is_empty() {
+                return Vec::new();
+            }
+
+            // If another bind operation was submitted for the mip tail in the meantime, its
+            // memory has now been replaced.
+            mem::replace(&mut image.state.lock().mip_tail, new_pages)
+        }))
+    }
+
+    /// Unbinds the memory of the mip tail and the metadata aspect, on `queue`.
+    ///
+    /// The unbound memory is returned to the page pool once the bind operation has finished
+    /// executing.
+    ///
+    /// Returns an error if the image may still be in use by a command buffer that was submitted
+    /// before, and that isn't part of the future that the bind operation is executed after.
+    #[inline]
+    pub fn unbind_mip_tail(
+        self: &Arc<Self>,
+        queue: Arc<Queue>,
+    ) -> Result<SparseBindFuture<NowFuture>, SparseError> {
+        let device = queue.device().clone();
+        self.unbind_mip_tail_after(now(device), queue)
+    }
+
+    /// Same as `unbind_mip_tail`, but the bind operation is executed after an existing future.
+    ///
+    /// # Panics
+    ///
+    /// - Panics if the device of the image is not the same as the device of the future.
+    pub fn unbind_mip_tail_after<F>(
+        self: &Arc<Self>,
+        future: F,
+        queue: Arc<Queue>,
+    ) -> Result<SparseBindFuture<F>, SparseError>
+    where
+        F: GpuFuture,
+    {
+        assert_eq!(self.device(), future.device());
+
+        check_image_unused(&future, &self.image, &queue)?;
+
+        let unbind = self.is_mip_tail_resident();
+        let memory_binds = if !unbind {
+            Vec::new()
+        } else {
+            self.mip_tail_regions()
+                .map(|(range, metadata)| SparseImageOpaqueMemoryBind {
+                    resource_offset: range.start,
+                    size: range.end - range.start,
+                    memory: None,
+                    metadata,
+                })
+                .collect()
+        };
+
+        let future = SparseBindFuture::new(
+            future,
+            queue,
+            BindSparseInfo {
+                image_opaque_binds: vec![(self.clone() as Arc<dyn ImageAccess>, memory_binds)],
+                ..Default::default()
+            },
+        )?;
+        let image = self.clone();
+
+        Ok(future.with_residency_update(move || {
+            if unbind {
+                image.state.lock().mip_tail.drain(..).collect()
+            } else {
+                Vec::new()
+            }
+        }))
+    }
+
+    // Returns the index of the sparse memory requirements that apply to `aspects`.
+    fn requirements_index(&self, aspects: ImageAspects) -> Result<usize, SparseError> {
+        self.sparse_requirements
+            .iter()
+            .position(|requirements| {
+                !requirements.format_properties.aspects.metadata
+                    && requirements.format_properties.aspects == aspects
+            })
+            .ok_or(SparseError::AspectsNotSupported)
+    }
+
+    // Returns the number of blocks and the extent of a mip level, or `None` if the mip level is
+    // out of range or part of the mip tail. The extent is in texel blocks for compressed formats.
+    fn mip_level_blocks(
+        &self,
+        requirements_index: usize,
+        mip_level: u32,
+    ) -> Option<([u32; 3], [u32; 3])> {
+        let requirements = &self.sparse_requirements[requirements_index];
+
+        if mip_level >= self.image.mip_levels()
+            || mip_level >= requirements.image_mip_tail_first_lod
+        {
+            return None;
+        }
+
+        let texel_extent = self
+            .image
+            .dimensions()
+            .mip_level_dimensions(mip_level)?
+            .width_height_depth();
+        let block_extent = self.image.format().unwrap().block_extent();
+        let granularity = requirements.format_properties.image_granularity;
+
+        let mut mip_extent = [0; 3];
+        let mut block_count = [0; 3];
+
+        for i in 0..3 {
+            mip_extent[i] = (texel_extent[i] + block_extent[i] - 1) / block_extent[i];
+            block_count[i] = (mip_extent[i] + granularity[i] - 1) / granularity[i];
+        }
+
+        Some((block_count, mip_extent))
+    }
+
+    fn validate_region(
+        &self,
+        requirements_index: usize,
+        region: &SparseImageRegion,
+    ) -> Result<([u32; 3], [u32; 3]), SparseError> {
+        let (block_count, mip_extent) = self
+            .mip_level_blocks(requirements_index, region.mip_level)
+            .ok_or(SparseError::BlockOutOfRange)?;
+
+        if region.array_layer >= self.image.dimensions().array_layers()
+            || (0..3).any(|i| region.offset[i] + region.extent[i] > block_count[i])
+        {
+            return Err(SparseError::BlockOutOfRange);
+        }
+
+        Ok((block_count, mip_extent))
+    }
+
+    // Returns the byte ranges of the mip tail regions that must be bound with opaque binds, and
+    // whether each one belongs to the metadata aspect.
+    fn mip_tail_regions(&self) -> impl Iterator<Item = (Range<DeviceSize>, bool)> + '_ {
+        let mip_levels = self.image.mip_levels();
+        let array_layers = self.image.dimensions().array_layers();
+
+        self.sparse_requirements
+            .iter()
+            .filter(move |requirements| {
+                requirements.image_mip_tail_size != 0
+                    && (requirements.format_properties.aspects.metadata
+                        || requirements.image_mip_tail_first_lod < mip_levels)
+            })
+            .flat_map(move |requirements| {
+                let metadata = requirements.format_properties.aspects.metadata;
+                let size = requirements.image_mip_tail_size;
+                let offset = requirements.image_mip_tail_offset;
+                let (count, stride) = match requirements.image_mip_tail_stride {
+                    Some(stride) => (array_layers as DeviceSize, stride),
+                    None => (1, 0),
+                };
+
+                (0..count).map(move |layer| {
+                    let start = offset + layer * stride;
+                    (start..start + size, metadata)
+                })
+            })
+    }
+}
+
+// Returns the offset and extent of a block, clamped to the extent of the mip level.
+fn block_bounds(
+    block: [u32; 3],
+    granularity: [u32; 3],
+    mip_extent: [u32; 3],
+) -> ([u32; 3], [u32; 3]) {
+    let mut offset = [0; 3];
+    let mut extent = [0; 3];
+
+    for i in 0..3 {
+        offset[i] = block[i] * granularity[i];
+        extent[i] = granularity[i].min(mip_extent[i] - offset[i]);
+    }
+
+    (offset, extent)
+}
+
+/// A region of a mip level and array layer of a [`SparseImage`], measured in sparse blocks.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct SparseImageRegion {
+    /// The aspects of the image that the region applies to. This must be equal to the aspects
+    /// of one of the image's sparse memory requirements, and must not contain `metadata`.
+    pub aspects: ImageAspects,
+
+    /// The mip level of the region. It must not be part of the mip tail.
+    pub mip_level: u32,
+
+    /// The array layer of the region.
+    pub array_layer: u32,
+
+    /// The offset of the region, in sparse blocks.
+    pub offset: [u32; 3],
+
+    /// The extent of the region, in sparse blocks.
+    pub extent: [u32; 3],
+}
+
+impl SparseImageRegion {
+    fn blocks(&self) -> impl Iterator<Item = [u32; 3]> {
+        let Self { offset, extent, .. } = *self;
+
+        (offset[2]..offset[2] + extent[2]).flat_map(move |z| {
+            (offset[1]..offset[1] + extent[1])
+                .flat_map(move |y| (offset[0]..offset[0] + extent[0]).map(move |x| [x, y, z]))
+        })
+    }
+}
+
+unsafe impl DeviceOwned for SparseImage {
+    fn device(&self) -> &Arc<Device> {
+        self.image.device()
+    }
+}
+
+unsafe impl ImageAccess for SparseImage {
+    fn inner(&self) -> ImageInner<'_> {
+        ImageInner {
+            image: &self.image,
+            first_layer: 0,
+            num_layers: self.image.dimensions().array_layers(),
+            first_mipmap_level: 0,
+            num_mipmap_levels: self.image.mip_levels(),
+        }
+    }
+
+    fn is_layout_tracked(&self) -> bool {
+        true
+    }
+
+    fn initial_layout_requirement(&self) -> ImageLayout {
+        ImageLayout::General
+    }
+
+    fn final_layout_requirement(&self) -> ImageLayout {
+        ImageLayout::General
+    }
+
+    fn descriptor_layouts(&self) -> Option<ImageDescriptorLayouts> {
+        Some(ImageDescriptorLayouts {
+            storage_image: ImageLayout::General,
+            combined_image_sampler: ImageLayout::ShaderReadOnlyOptimal,
+            sampled_image: ImageLayout::ShaderReadOnlyOptimal,
+            input_attachment: ImageLayout::ShaderReadOnlyOptimal,
+        })
+    }
+}
+
+impl PartialEq for SparseImage {
+    fn eq(&self, other: &Self) -> bool {
+        self.inner() == other.inner()
+    }
+}
+
+impl Eq for SparseImage {}
+
+impl Hash for SparseImage {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.inner().hash(state);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{SparseImage, SparseImageRegion};
+    use crate::{
+        format::Format,
+        image::{ImageAspects, ImageDimensions, ImageUsage, MipmapsCount},
+        memory::SparseError,
+        sync::GpuFuture,
+    };
+
+    #[test]
+    fn bind_block_and_mip_tail() {
+        let (device, queue) = gfx_dev_and_queue!(sparse_binding, sparse_residency_image2_d);
+
+        if !device.physical_device().queue_family_properties()[queue.queue_family_index() as usize]
+            .queue_flags
+            .sparse_binding
+        {
+            return;
+        }
+
+        let image = match SparseImage::new(
+            device,
+            ImageDimensions::Dim2d {
+                width: 1024,
+                height: 1024,
+                array_layers: 1,
+            },
+            Format::R8G8B8A8_UNORM,
+            MipmapsCount::Log2,
+            ImageUsage {
+                sampled: true,
+                transfer_dst: true,
+                ..ImageUsage::empty()
+            },
+            [queue.queue_family_index()],
+        ) {
+            Ok(image) => image,
+            Err(SparseError::FormatNotSupported) => return,
+            Err(err) => panic!("{:?}", err),
+        };
+
+        let aspects = ImageAspects {
+            color: true,
+            ..ImageAspects::empty()
+        };
+        let block_count = image.block_count(aspects, 0).unwrap();
+        assert!(block_count.iter().all(|&count| count >= 1));
+        assert!(!image.is_block_resident(aspects, 0, 0, [0, 0, 0]));
+
+        image
+            .bind_blocks(
+                queue.clone(),
+                SparseImageRegion {
+                    aspects,
+                    mip_level: 0,
+                    array_layer: 0,
+                    offset: [0, 0, 0],
+                    extent: [1, 1, 1],
+                },
+            )
+            .unwrap()
+            .then_signal_fence_and_flush()
+            .unwrap()
+            .wait(None)
+            .unwrap();
+        assert!(image.is_block_resident(aspects, 0, 0, [0, 0, 0]));
+
+        if image.has_mip_tail() {
+            image
+                .bind_mip_tail(queue.clone())
+                .unwrap()
+                .then_signal_fence_and_flush()
+                .unwrap()
+                .wait(None)
+                .unwrap();
+            assert!(image.is_mip_tail_resident());
+        }
+
+        assert!(matches!(
+            image.bind_blocks(
+                queue,
+                SparseImageRegion {
+                    aspects,
+                    mip_level: 0,
+                    array_layer: 0,
+                    offset: block_count,
+                    extent: [1, 1, 1],
+                },
+            ),
+            Err(SparseError::BlockOutOfRange)
+        ));
+    }
+}
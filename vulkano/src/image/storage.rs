@@ -10,6 +10,7 @@
 use super::{
     sys::UnsafeImage, traits::ImageContent, ImageAccess, ImageCreateFlags, ImageCreationError,
     ImageDescriptorLayouts, ImageDimensions, ImageInner, ImageLayout, ImageUsage,
+    SubresourceLayout,
 };
 use crate::{
     device::{Device, DeviceOwned, Queue},
@@ -27,12 +28,12 @@ use crate::{
     sync::Sharing,
     DeviceSize,
 };
-use ash::vk::{ImageDrmFormatModifierExplicitCreateInfoEXT, SubresourceLayout};
 use smallvec::SmallVec;
 use std::{
     fs::File,
     hash::{Hash, Hasher},
-    os::unix::prelude::RawFd,
+    mem::ManuallyDrop,
+    os::unix::prelude::{FromRawFd, RawFd},
     sync::Arc,
 };
 
@@ -149,7 +150,7 @@ impl StorageImage {
 
     /// Creates a new image from a set of dma_buf file descriptors. The memory will be imported from the file desciptors, and will be bound to the image.
     /// # Arguments
-    /// * `fds` - The list of file descriptors to import from. Single planar images should only use one, and multiplanar images can use multiple, for example, for each color.
+    /// * `fds` - The list of file descriptors to import from, one for each memory plane. All planes must use the same file descriptor, at different offsets; importing each plane from its own file descriptor is not supported, and returns `ImageCreationError::DmaBufFdsNotSupported`.
     /// * `offset` - The byte offset from the start of the image of the plane where the image subresource begins.
     /// * `pitch` - Describes the number of bytes between each row of texels in an image.
     pub fn new_from_dma_buf_fd(
@@ -159,78 +160,99 @@ impl StorageImage {
         usage: ImageUsage,
         flags: ImageCreateFlags,
         queue_family_indices: impl IntoIterator<Item = u32>,
-        mut subresource_data: Vec<SubresourceData>,
+        subresource_data: Vec<SubresourceData>,
         drm_format_modifier: u64,
     ) -> Result<Arc<StorageImage>, ImageCreationError> {
-        let queue_family_indices: SmallVec<[_; 4]> = queue_family_indices.into_iter().collect();
-
         // Create a vector of the layout of each image plane.
-        let layout: Vec<SubresourceLayout> = subresource_data
-            .iter_mut()
-            .map(
-                |SubresourceData {
-                     fd: _,
-                     offset,
-                     row_pitch,
-                 }| {
-                    SubresourceLayout {
-                        offset: offset.clone(),
-                        size: 0,
-                        row_pitch: row_pitch.clone(),
-                        array_pitch: 0,
-                        depth_pitch: 0,
-                    }
-                },
-            )
-            .collect();
-
-        let fds: Vec<RawFd> = subresource_data
-            .iter_mut()
-            .map(
-                |SubresourceData {
-                     fd,
-                     offset: _,
-                     row_pitch: _,
-                 }| { *fd },
-            )
-            .collect();
+        let plane_layouts = subresource_data.iter().map(
+            |&SubresourceData {
+                 fd: _,
+                 offset,
+                 row_pitch,
+             }| SubresourceLayout {
+                offset,
+                size: 0,
+                row_pitch,
+                array_pitch: 0,
+                depth_pitch: 0,
+            },
+        );
 
-        let drm_mod = ImageDrmFormatModifierExplicitCreateInfoEXT::builder()
-            .drm_format_modifier(drm_format_modifier)
-            .plane_layouts(layout.as_ref())
-            .build();
+        // Planes in different file descriptors would have to be bound separately, with a
+        // disjoint image.
+        let fd = match subresource_data.first() {
+            Some(first) if subresource_data.iter().all(|data| data.fd == first.fd) => first.fd,
+            _ => return Err(ImageCreationError::DmaBufFdsNotSupported),
+        };
 
-        let image = UnsafeImage::new(
-            device.clone(),
-            UnsafeImageCreateInfo {
+        let file = unsafe {
+            // Try cloning underlying fd, and turn the original file descriptor back into a raw fd
+            // to avoid ownership problems.
+            let file = ManuallyDrop::new(File::from_raw_fd(fd));
+            file.try_clone()
+                .map_err(|err| DeviceMemoryError::ImportFdDuplicationFailed { kind: err.kind() })?
+        };
+
+        unsafe {
+            StorageImage::new_from_fd(
+                device,
                 dimensions,
-                format: Some(format),
+                format,
                 usage,
-                sharing: if queue_family_indices.len() >= 2 {
-                    Sharing::Concurrent(queue_family_indices)
-                } else {
-                    Sharing::Exclusive
-                },
-                external_memory_handle_types: ExternalMemoryHandleTypes {
-                    dma_buf: true,
-                    ..ExternalMemoryHandleTypes::empty()
-                },
-                mutable_format: flags.mutable_format,
-                cube_compatible: flags.cube_compatible,
-                array_2d_compatible: flags.array_2d_compatible,
-                block_texel_view_compatible: flags.block_texel_view_compatible,
-                tiling: ImageTiling::DrmFormatModifier,
-                image_drm_format_modifier_create_info: Some(drm_mod),
-                ..Default::default()
-            },
+                flags,
+                queue_family_indices,
+                ExternalMemoryHandleType::DmaBuf,
+                file,
+                Some(drm_format_modifier),
+                plane_layouts,
+            )
+        }
+    }
+
+    /// Creates a new image whose memory is imported from `file`, instead of being allocated.
+    ///
+    /// `handle_type` must be either [`ExternalMemoryHandleType::OpaqueFd`] or
+    /// [`ExternalMemoryHandleType::DmaBuf`].
+    ///
+    /// If `drm_format_modifier` is `Some`, the image is created with
+    /// [`ImageTiling::DrmFormatModifier`], and `drm_format_modifier_plane_layouts` gives the
+    /// offset and pitches of each memory plane within `file`. Otherwise, the image is created
+    /// with optimal tiling and `drm_format_modifier_plane_layouts` must be empty.
+    ///
+    /// # Safety
+    ///
+    /// - `file` must satisfy the safety requirements of
+    ///   [`MemoryImportInfo::Fd`](crate::memory::MemoryImportInfo::Fd). If `handle_type` is
+    ///   `OpaqueFd`, the original image must have been created with the same parameters.
+    pub unsafe fn new_from_fd(
+        device: Arc<Device>,
+        dimensions: ImageDimensions,
+        format: Format,
+        usage: ImageUsage,
+        flags: ImageCreateFlags,
+        queue_family_indices: impl IntoIterator<Item = u32>,
+        handle_type: ExternalMemoryHandleType,
+        file: File,
+        drm_format_modifier: Option<u64>,
+        drm_format_modifier_plane_layouts: impl IntoIterator<Item = SubresourceLayout>,
+    ) -> Result<Arc<StorageImage>, ImageCreationError> {
+        let image = Self::build_external_image(
+            &device,
+            dimensions,
+            format,
+            usage,
+            flags,
+            queue_family_indices,
+            handle_type.into(),
+            drm_format_modifier,
+            drm_format_modifier_plane_layouts,
         )?;
 
         let mem_reqs = image.memory_requirements();
-
         let memory = alloc_import_from_fd(
-            device.clone(),
+            device,
             &mem_reqs,
-            AllocLayout::Linear,
+            Self::external_alloc_layout(&image),
             MappingRequirement::DoNotMap,
             DedicatedAllocation::Image(&image),
             |t| {
@@ -240,13 +262,12 @@ impl StorageImage {
                     AllocFromRequirementsFilter::Allowed
                 }
             },
-            fds,
+            handle_type,
+            file,
         )?;
 
         debug_assert!((memory.offset() % mem_reqs.alignment) == 0);
-        unsafe {
-            image.bind_memory(memory.memory(), memory.offset())?;
-        }
+        image.bind_memory(memory.memory(), memory.offset())?;
 
         Ok(Arc::new(StorageImage {
             image,
@@ -263,36 +284,57 @@ impl StorageImage {
         flags: ImageCreateFlags,
         queue_family_indices: impl IntoIterator<Item = u32>,
     ) -> Result<Arc<StorageImage>, ImageCreationError> {
-        let queue_family_indices: SmallVec<[_; 4]> = queue_family_indices.into_iter().collect();
-
-        let image = UnsafeImage::new(
-            device.clone(),
-            UnsafeImageCreateInfo {
-                dimensions,
-                format: Some(format),
-                usage,
-                sharing: if queue_family_indices.len() >= 2 {
-                    Sharing::Concurrent(queue_family_indices)
-                } else {
-                    Sharing::Exclusive
-                },
-                external_memory_handle_types: ExternalMemoryHandleTypes {
-                    opaque_fd: true,
-                    ..ExternalMemoryHandleTypes::empty()
-                },
-                mutable_format: flags.mutable_format,
-                cube_compatible: flags.cube_compatible,
-                array_2d_compatible: flags.array_2d_compatible,
-                block_texel_view_compatible: flags.block_texel_view_compatible,
-                ..Default::default()
+        StorageImage::new_with_exportable_handle_types(
+            device,
+            dimensions,
+            format,
+            usage,
+            flags,
+            queue_family_indices,
+            ExternalMemoryHandleTypes {
+                opaque_fd: true,
+                ..ExternalMemoryHandleTypes::empty()
             },
+            None,
+            [],
+        )
+    }
+
+    /// Same as `with_usage`, but the memory is allocated so that it can be exported as any of
+    /// `export_handle_types` with [`export_fd`](Self::export_fd).
+    ///
+    /// `drm_format_modifier` and `drm_format_modifier_plane_layouts` have the same meaning as
+    /// for [`new_from_fd`](Self::new_from_fd). Other APIs that import a DMA-BUF usually need to
+    /// know its modifier and plane layouts, so they should be provided when exporting with
+    /// `dma_buf`.
+    pub fn new_with_exportable_handle_types(
+        device: Arc<Device>,
+        dimensions: ImageDimensions,
+        format: Format,
+        usage: ImageUsage,
+        flags: ImageCreateFlags,
+        queue_family_indices: impl IntoIterator<Item = u32>,
+        export_handle_types: ExternalMemoryHandleTypes,
+        drm_format_modifier: Option<u64>,
+        drm_format_modifier_plane_layouts: impl IntoIterator<Item = SubresourceLayout>,
+    ) -> Result<Arc<StorageImage>, ImageCreationError> {
+        let image = Self::build_external_image(
+            &device,
+            dimensions,
+            format,
+            usage,
+            flags,
+            queue_family_indices,
+            export_handle_types,
+            drm_format_modifier,
+            drm_format_modifier_plane_layouts,
         )?;
 
         let mem_reqs = image.memory_requirements();
         let memory = alloc_dedicated_with_exportable_fd(
             device,
             &mem_reqs,
-            AllocLayout::Optimal,
+            Self::external_alloc_layout(&image),
             MappingRequirement::DoNotMap,
             DedicatedAllocation::Image(&image),
             |t| {
@@ -302,6 +344,7 @@ impl StorageImage {
                     AllocFromRequirementsFilter::Allowed
                 }
             },
+            export_handle_types,
         )?;
         debug_assert!((memory.offset() % mem_reqs.alignment) == 0);
         unsafe {
@@ -315,6 +358,57 @@ impl StorageImage {
         }))
     }
 
+    fn build_external_image(
+        device: &Arc<Device>,
+        dimensions: ImageDimensions,
+        format: Format,
+        usage: ImageUsage,
+        flags: ImageCreateFlags,
+        queue_family_indices: impl IntoIterator<Item = u32>,
+        external_memory_handle_types: ExternalMemoryHandleTypes,
+        drm_format_modifier: Option<u64>,
+        drm_format_modifier_plane_layouts: impl IntoIterator<Item = SubresourceLayout>,
+    ) -> Result<Arc<UnsafeImage>, ImageCreationError> {
+        let queue_family_indices: SmallVec<[_; 4]> = queue_family_indices.into_iter().collect();
+
+        UnsafeImage::new(
+            device.clone(),
+            UnsafeImageCreateInfo {
+                dimensions,
+                format: Some(format),
+                usage,
+                sharing: if queue_family_indices.len() >= 2 {
+                    Sharing::Concurrent(queue_family_indices)
+                } else {
+                    Sharing::Exclusive
+                },
+                external_memory_handle_types,
+                mutable_format: flags.mutable_format,
+                cube_compatible: flags.cube_compatible,
+                array_2d_compatible: flags.array_2d_compatible,
+                block_texel_view_compatible: flags.block_texel_view_compatible,
+                tiling: if drm_format_modifier.is_some() {
+                    ImageTiling::DrmFormatModifier
+                } else {
+                    ImageTiling::Optimal
+                },
+                drm_format_modifier,
+                drm_format_modifier_plane_layouts: drm_format_modifier_plane_layouts
+                    .into_iter()
+                    .collect(),
+                ..Default::default()
+            },
+        )
+    }
+
+    fn external_alloc_layout(image: &UnsafeImage) -> AllocLayout {
+        if image.tiling() == ImageTiling::Optimal {
+            AllocLayout::Optimal
+        } else {
+            AllocLayout::Linear
+        }
+    }
+
     /// Allows the creation of a simple 2D general purpose image view from `StorageImage`.
     #[inline]
     pub fn general_purpose_image_view(
@@ -354,9 +448,20 @@ impl StorageImage {
     /// Requires `khr_external_memory_fd` and `khr_external_memory` extensions to be loaded.
     #[inline]
     pub fn export_posix_fd(&self) -> Result<File, DeviceMemoryError> {
-        self.memory
-            .memory()
-            .export_fd(ExternalMemoryHandleType::OpaqueFd)
+        self.export_fd(ExternalMemoryHandleType::OpaqueFd)
+    }
+
+    /// Exports the memory of the image as a file descriptor of the given handle type.
+    ///
+    /// The image must have been created with
+    /// [`new_with_exportable_handle_types`](Self::new_with_exportable_handle_types), and
+    /// `handle_type` must be one of the handle types it was created with.
+    #[inline]
+    pub fn export_fd(
+        &self,
+        handle_type: ExternalMemoryHandleType,
+    ) -> Result<File, DeviceMemoryError> {
+        self.memory.memory().export_fd(handle_type)
     }
 
     /// Return the size of the allocated memory (used e.g. with cuda).
@@ -401,6 +506,10 @@ where
         }
     }
 
+    fn is_layout_tracked(&self) -> bool {
+        true
+    }
+
     fn initial_layout_requirement(&self) -> ImageLayout {
         ImageLayout::General
     }
@@ -412,9 +521,9 @@ where
     fn descriptor_layouts(&self) -> Option<ImageDescriptorLayouts> {
         Some(ImageDescriptorLayouts {
             storage_image: ImageLayout::General,
-            combined_image_sampler: ImageLayout::General,
-            sampled_image: ImageLayout::General,
-            input_attachment: ImageLayout::General,
+            combined_image_sampler: ImageLayout::ShaderReadOnlyOptimal,
+            sampled_image: ImageLayout::ShaderReadOnlyOptimal,
+            input_attachment: ImageLayout::ShaderReadOnlyOptimal,
         })
     }
 }
@@ -452,12 +561,34 @@ where
 mod tests {
     use super::StorageImage;
     use crate::{
+        buffer::{BufferUsage, CpuAccessibleBuffer},
+        command_buffer::{
+            AutoCommandBufferBuilder, ClearColorImageInfo, CommandBufferExecError,
+            CommandBufferUsage, CopyImageToBufferInfo, PrimaryCommandBuffer,
+        },
+        device::Queue,
         format::Format,
         image::{
-            view::ImageViewCreationError, ImageAccess, ImageCreationError, ImageDimensions,
-            ImageUsage,
+            view::ImageViewCreationError, ImageAccess, ImageCreateFlags, ImageCreationError,
+            ImageDimensions, ImageLayout, ImageUsage,
         },
+        sync::{AccessError, GpuFuture},
     };
+    use std::sync::Arc;
+
+    fn submit(
+        command_buffer: impl PrimaryCommandBuffer + 'static,
+        queue: Arc<Queue>,
+    ) -> Result<(), CommandBufferExecError> {
+        command_buffer
+            .execute(queue)?
+            .then_signal_fence_and_flush()
+            .unwrap()
+            .wait(None)
+            .unwrap();
+
+        Ok(())
+    }
 
     #[test]
     fn create() {
@@ -515,4 +646,254 @@ mod tests {
             ))
         );
     }
+
+    #[test]
+    fn dma_buf_fds_not_supported() {
+        let (device, queue) = gfx_dev_and_queue!();
+
+        assert_eq!(
+            StorageImage::new_from_dma_buf_fd(
+                device,
+                ImageDimensions::Dim2d {
+                    width: 32,
+                    height: 32,
+                    array_layers: 1,
+                },
+                Format::R8G8B8A8_UNORM,
+                ImageUsage {
+                    sampled: true,
+                    ..ImageUsage::empty()
+                },
+                ImageCreateFlags::empty(),
+                [queue.queue_family_index()],
+                Vec::new(),
+                0,
+            )
+            .err(),
+            Some(ImageCreationError::DmaBufFdsNotSupported)
+        );
+    }
+
+    #[test]
+    fn layout_tracked_across_submissions() {
+        let (device, queue) = gfx_dev_and_queue!();
+        let image = StorageImage::new(
+            device.clone(),
+            ImageDimensions::Dim2d {
+                width: 32,
+                height: 32,
+                array_layers: 1,
+            },
+            Format::R8G8B8A8_UNORM,
+            Some(queue.queue_family_index()),
+        )
+        .unwrap();
+        assert_eq!(
+            image
+                .inner()
+                .image
+                .current_layout(image.subresource_range()),
+            Some(ImageLayout::Undefined)
+        );
+
+        let mut cbb = AutoCommandBufferBuilder::primary(
+            device,
+            queue.queue_family_index(),
+            CommandBufferUsage::OneTimeSubmit,
+        )
+        .unwrap();
+        cbb.clear_color_image(ClearColorImageInfo::image(image.clone()))
+            .unwrap();
+        cbb.build()
+            .unwrap()
+            .execute(queue)
+            .unwrap()
+            .then_signal_fence_and_flush()
+            .unwrap()
+            .wait(None)
+            .unwrap();
+
+        // The image is left in the layout of its last use, instead of being transitioned to
+        // `General`.
+        assert_eq!(
+            image
+                .inner()
+                .image
+                .current_layout(image.subresource_range()),
+            Some(ImageLayout::TransferDstOptimal)
+        );
+    }
+
+    #[test]
+    fn layout_tracked_recorded_before_submit() {
+        let (device, queue) = gfx_dev_and_queue!();
+        let image = StorageImage::new(
+            device.clone(),
+            ImageDimensions::Dim2d {
+                width: 32,
+                height: 32,
+                array_layers: 1,
+            },
+            Format::R8G8B8A8_UNORM,
+            Some(queue.queue_family_index()),
+        )
+        .unwrap();
+
+        let buffer = CpuAccessibleBuffer::from_iter(
+            device.clone(),
+            BufferUsage {
+                transfer_dst: true,
+                ..BufferUsage::empty()
+            },
+            false,
+            (0..32 * 32 * 4).map(|_| 0u8),
+        )
+        .unwrap();
+
+        // Both command buffers are recorded before either is submitted. The second one starts
+        // from the layout that the first one leaves the image in.
+        let mut cbb = AutoCommandBufferBuilder::primary(
+            device.clone(),
+            queue.queue_family_index(),
+            CommandBufferUsage::OneTimeSubmit,
+        )
+        .unwrap();
+        cbb.clear_color_image(ClearColorImageInfo::image(image.clone()))
+            .unwrap();
+        let clear = cbb.build().unwrap();
+
+        let mut cbb = AutoCommandBufferBuilder::primary(
+            device,
+            queue.queue_family_index(),
+            CommandBufferUsage::OneTimeSubmit,
+        )
+        .unwrap();
+        cbb.copy_image_to_buffer(CopyImageToBufferInfo::image_buffer(image.clone(), buffer))
+            .unwrap();
+        let copy = cbb.build().unwrap();
+
+        submit(clear, queue.clone()).unwrap();
+        submit(copy, queue).unwrap();
+        assert_eq!(
+            image
+                .inner()
+                .image
+                .current_layout(image.subresource_range()),
+            Some(ImageLayout::TransferSrcOptimal)
+        );
+    }
+
+    #[test]
+    fn layout_tracked_submitted_out_of_order() {
+        let (device, queue) = gfx_dev_and_queue!();
+        let image = StorageImage::new(
+            device.clone(),
+            ImageDimensions::Dim2d {
+                width: 32,
+                height: 32,
+                array_layers: 1,
+            },
+            Format::R8G8B8A8_UNORM,
+            Some(queue.queue_family_index()),
+        )
+        .unwrap();
+
+        let record = || {
+            let mut cbb = AutoCommandBufferBuilder::primary(
+                device.clone(),
+                queue.queue_family_index(),
+                CommandBufferUsage::OneTimeSubmit,
+            )
+            .unwrap();
+            cbb.clear_color_image(ClearColorImageInfo::image(image.clone()))
+                .unwrap();
+            cbb.build().unwrap()
+        };
+        let first = record();
+        let second = record();
+
+        // `second` expects the image to be in the layout that `first` leaves it in.
+        match submit(second, queue.clone()) {
+            Err(CommandBufferExecError::AccessError {
+                error:
+                    AccessError::UnexpectedImageLayout {
+                        allowed: ImageLayout::Undefined,
+                        requested: ImageLayout::TransferDstOptimal,
+                    },
+                ..
+            }) => (),
+            _ => panic!(),
+        }
+        submit(first, queue).unwrap();
+    }
+
+    #[test]
+    fn layout_tracked_multiple_submit() {
+        let (device, queue) = gfx_dev_and_queue!();
+        let image = StorageImage::new(
+            device.clone(),
+            ImageDimensions::Dim2d {
+                width: 32,
+                height: 32,
+                array_layers: 1,
+            },
+            Format::R8G8B8A8_UNORM,
+            Some(queue.queue_family_index()),
+        )
+        .unwrap();
+        let buffer = CpuAccessibleBuffer::from_iter(
+            device.clone(),
+            BufferUsage {
+                transfer_dst: true,
+                ..BufferUsage::empty()
+            },
+            false,
+            (0..32 * 32 * 4).map(|_| 0u8),
+        )
+        .unwrap();
+
+        // Recorded while the image is `Undefined`, so it can't return the image to that layout.
+        let mut cbb = AutoCommandBufferBuilder::primary(
+            device.clone(),
+            queue.queue_family_index(),
+            CommandBufferUsage::MultipleSubmit,
+        )
+        .unwrap();
+        cbb.clear_color_image(ClearColorImageInfo::image(image.clone()))
+            .unwrap();
+        let clear = Arc::new(cbb.build().unwrap());
+
+        submit(clear.clone(), queue.clone()).unwrap();
+        assert_eq!(
+            image
+                .inner()
+                .image
+                .current_layout(image.subresource_range()),
+            Some(ImageLayout::General)
+        );
+        assert!(submit(clear, queue.clone()).is_err());
+
+        // Recorded while the image is `General`, so it returns the image to `General` and can be
+        // submitted any number of times.
+        let mut cbb = AutoCommandBufferBuilder::primary(
+            device,
+            queue.queue_family_index(),
+            CommandBufferUsage::MultipleSubmit,
+        )
+        .unwrap();
+        cbb.copy_image_to_buffer(CopyImageToBufferInfo::image_buffer(image.clone(), buffer))
+            .unwrap();
+        let copy = Arc::new(cbb.build().unwrap());
+
+        for _ in 0..2 {
+            submit(copy.clone(), queue.clone()).unwrap();
+            assert_eq!(
+                image
+                    .inner()
+                    .image
+                    .current_layout(image.subresource_range()),
+                Some(ImageLayout::General)
+            );
+        }
+    }
 }
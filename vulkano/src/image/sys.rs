@@ -16,7 +16,7 @@
 use super::{
     ImageAspect, ImageAspects, ImageCreateFlags, ImageDimensions, ImageLayout,
     ImageSubresourceLayers, ImageSubresourceRange, ImageTiling, ImageUsage, SampleCount,
-    SampleCounts, SparseImageMemoryRequirements,
+    SampleCounts, SparseImageMemoryRequirements, SubresourceLayout,
 };
 use crate::{
     buffer::cpu_access::{ReadLockError, WriteLockError},
@@ -26,6 +26,7 @@ use crate::{
         view::ImageViewCreationError, ImageFormatInfo, ImageFormatProperties, ImageType,
         SparseImageFormatProperties,
     },
+    macros::vulkan_bitflags,
     memory::{
         DeviceMemory, DeviceMemoryError, ExternalMemoryHandleType, ExternalMemoryHandleTypes,
         MemoryRequirements,
@@ -34,7 +35,7 @@ use crate::{
     sync::{AccessError, CurrentAccess, Sharing},
     DeviceSize, OomError, RequirementNotMet, RequiresOneOf, Version, VulkanError, VulkanObject,
 };
-use ash::vk::{Handle, ImageDrmFormatModifierExplicitCreateInfoEXT};
+use ash::vk::Handle;
 use parking_lot::{Mutex, MutexGuard};
 use smallvec::{smallvec, SmallVec};
 use std::{
@@ -74,11 +75,12 @@ pub struct UnsafeImage {
     tiling: ImageTiling,
     usage: ImageUsage,
     stencil_usage: ImageUsage,
+    sharing: Sharing<SmallVec<[u32; 4]>>,
     external_memory_handle_types: ExternalMemoryHandleTypes,
-    mutable_format: bool,
-    cube_compatible: bool,
-    array_2d_compatible: bool,
-    block_texel_view_compatible: bool,
+    flags: ImageCreateFlags,
+    sparse: Option<SparseLevel>,
+    drm_format_modifier: Option<u64>,
+    name: Mutex<Option<String>>,
 
     aspect_list: SmallVec<[ImageAspect; 4]>,
     aspect_size: DeviceSize,
@@ -143,7 +145,10 @@ impl UnsafeImage {
             cube_compatible,
             array_2d_compatible,
             block_texel_view_compatible,
-            image_drm_format_modifier_create_info: _,
+            sparse,
+            drm_format_modifier,
+            ref drm_format_modifier_plane_layouts,
+            name: _,
             _ne: _,
         } = create_info;
 
@@ -170,6 +175,12 @@ impl UnsafeImage {
         // VUID-VkImageCreateInfo-tiling-parameter
         tiling.validate_device(device)?;
 
+        // VUID-VkImageCreateInfo-tiling-02261
+        // VUID-VkImageCreateInfo-pNext-02262
+        if (tiling == ImageTiling::DrmFormatModifier) != drm_format_modifier.is_some() {
+            return Err(ImageCreationError::DrmFormatModifierTilingMismatch);
+        }
+
         // VUID-VkImageCreateInfo-usage-parameter
         usage.validate_device(device)?;
 
@@ -216,7 +227,32 @@ impl UnsafeImage {
             match tiling {
                 ImageTiling::Linear => format_properties.linear_tiling_features,
                 ImageTiling::Optimal => format_properties.optimal_tiling_features,
-                ImageTiling::DrmFormatModifier => format_properties.linear_tiling_features, // TODO: Fix
+                ImageTiling::DrmFormatModifier => {
+                    let drm_format_modifier = drm_format_modifier.unwrap();
+
+                    // VUID-VkImageDrmFormatModifierExplicitCreateInfoEXT-drmFormatModifier-02264
+                    let modifier_properties =
+                        unsafe { physical_device.drm_format_modifier_properties_unchecked(format) }
+                            .into_iter()
+                            .find(|properties| {
+                                properties.drm_format_modifier == drm_format_modifier
+                            })
+                            .ok_or(ImageCreationError::DrmFormatModifierNotSupported {
+                                drm_format_modifier,
+                            })?;
+
+                    // VUID-VkImageDrmFormatModifierExplicitCreateInfoEXT-drmFormatModifierPlaneCount-02265
+                    if drm_format_modifier_plane_layouts.len()
+                        != modifier_properties.drm_format_modifier_plane_count as usize
+                    {
+                        return Err(ImageCreationError::DrmFormatModifierPlaneCountMismatch {
+                            provided: drm_format_modifier_plane_layouts.len() as u32,
+                            required: modifier_properties.drm_format_modifier_plane_count,
+                        });
+                    }
+
+                    modifier_properties.drm_format_modifier_tiling_features
+                }
             }
         };
 
@@ -258,6 +294,23 @@ impl UnsafeImage {
         // VUID-VkImageCreateInfo-mipLevels-00947
         assert!(mip_levels != 0);
 
+        if tiling == ImageTiling::DrmFormatModifier {
+            for (plane_index, plane_layout) in drm_format_modifier_plane_layouts.iter().enumerate()
+            {
+                // VUID-VkImageDrmFormatModifierExplicitCreateInfoEXT-size-02267
+                // VUID-VkImageDrmFormatModifierExplicitCreateInfoEXT-arrayPitch-02268
+                // VUID-VkImageDrmFormatModifierExplicitCreateInfoEXT-depthPitch-02269
+                if plane_layout.size != 0
+                    || array_layers == 1 && plane_layout.array_pitch != 0
+                    || image_type != ImageType::Dim3d && plane_layout.depth_pitch != 0
+                {
+                    return Err(ImageCreationError::DrmFormatModifierPlaneLayoutInvalid {
+                        plane_index,
+                    });
+                }
+            }
+        }
+
         // Check mip levels
 
         let max_mip_levels = dimensions.max_mip_levels();
@@ -502,6 +555,100 @@ impl UnsafeImage {
             }
         }
 
+        if let Some(sparse_level) = sparse {
+            // VUID-VkImageCreateInfo-flags-00969
+            if !device.enabled_features().sparse_binding {
+                return Err(ImageCreationError::RequirementNotMet {
+                    required_for: "`create_info.sparse` is `Some`",
+                    requires_one_of: RequiresOneOf {
+                        features: &["sparse_binding"],
+                        ..Default::default()
+                    },
+                });
+            }
+
+            if sparse_level.sparse_residency {
+                // VUID-VkImageCreateInfo-imageType-00970
+                // VUID-VkImageCreateInfo-tiling-04121
+                if image_type == ImageType::Dim1d || tiling != ImageTiling::Optimal {
+                    return Err(ImageCreationError::SparseResidencyInvalidImage);
+                }
+
+                // VUID-VkImageCreateInfo-imageType-00971
+                if image_type == ImageType::Dim2d
+                    && samples == SampleCount::Sample1
+                    && !device.enabled_features().sparse_residency_image2_d
+                {
+                    return Err(ImageCreationError::RequirementNotMet {
+                        required_for: "`create_info.sparse` is `Some(sparse_level)`, where `sparse_level.sparse_residency` is set, and `create_info.dimensions` is `ImageDimensions::Dim2d`",
+                        requires_one_of: RequiresOneOf {
+                            features: &["sparse_residency_image2_d"],
+                            ..Default::default()
+                        },
+                    });
+                }
+
+                // VUID-VkImageCreateInfo-imageType-00972
+                if image_type == ImageType::Dim3d
+                    && !device.enabled_features().sparse_residency_image3_d
+                {
+                    return Err(ImageCreationError::RequirementNotMet {
+                        required_for: "`create_info.sparse` is `Some(sparse_level)`, where `sparse_level.sparse_residency` is set, and `create_info.dimensions` is `ImageDimensions::Dim3d`",
+                        requires_one_of: RequiresOneOf {
+                            features: &["sparse_residency_image3_d"],
+                            ..Default::default()
+                        },
+                    });
+                }
+
+                // VUID-VkImageCreateInfo-imageType-00973
+                // VUID-VkImageCreateInfo-imageType-00974
+                // VUID-VkImageCreateInfo-imageType-00975
+                // VUID-VkImageCreateInfo-imageType-00976
+                let (samples_feature_enabled, samples_features): (bool, &'static [&'static str]) =
+                    match samples {
+                        SampleCount::Sample2 => (
+                            device.enabled_features().sparse_residency2_samples,
+                            &["sparse_residency2_samples"],
+                        ),
+                        SampleCount::Sample4 => (
+                            device.enabled_features().sparse_residency4_samples,
+                            &["sparse_residency4_samples"],
+                        ),
+                        SampleCount::Sample8 => (
+                            device.enabled_features().sparse_residency8_samples,
+                            &["sparse_residency8_samples"],
+                        ),
+                        SampleCount::Sample16 => (
+                            device.enabled_features().sparse_residency16_samples,
+                            &["sparse_residency16_samples"],
+                        ),
+                        _ => (true, &[]),
+                    };
+
+                if !samples_feature_enabled {
+                    return Err(ImageCreationError::RequirementNotMet {
+                        required_for: "`create_info.sparse` is `Some(sparse_level)`, where `sparse_level.sparse_residency` is set, and `create_info.samples` is not `SampleCount::Sample1`",
+                        requires_one_of: RequiresOneOf {
+                            features: samples_features,
+                            ..Default::default()
+                        },
+                    });
+                }
+            }
+
+            // VUID-VkImageCreateInfo-flags-01924
+            if sparse_level.sparse_aliased && !device.enabled_features().sparse_residency_aliased {
+                return Err(ImageCreationError::RequirementNotMet {
+                    required_for: "`create_info.sparse` is `Some(sparse_level)`, where `sparse_level.sparse_aliased` is set",
+                    requires_one_of: RequiresOneOf {
+                        features: &["sparse_residency_aliased"],
+                        ..Default::default()
+                    },
+                });
+            }
+        }
+
         /* Check sharing mode and queue families */
 
         match sharing {
@@ -743,6 +890,7 @@ impl UnsafeImage {
                             array_2d_compatible,
                             block_texel_view_compatible,
                             external_memory_handle_type,
+                            drm_format_modifier,
                             ..Default::default()
                         })?
                 };
@@ -824,7 +972,10 @@ impl UnsafeImage {
             cube_compatible,
             array_2d_compatible,
             block_texel_view_compatible,
-            mut image_drm_format_modifier_create_info,
+            sparse,
+            drm_format_modifier,
+            ref drm_format_modifier_plane_layouts,
+            name: _,
             _ne: _,
         } = &create_info;
 
@@ -839,6 +990,9 @@ impl UnsafeImage {
             };
 
         let flags = ImageCreateFlags {
+            sparse_binding: sparse.is_some(),
+            sparse_residency: sparse.map_or(false, |sparse_level| sparse_level.sparse_residency),
+            sparse_aliased: sparse.map_or(false, |sparse_level| sparse_level.sparse_aliased),
             mutable_format,
             cube_compatible,
             array_2d_compatible,
@@ -892,6 +1046,8 @@ impl UnsafeImage {
         };
         let mut external_memory_info_vk = None;
         let mut stencil_usage_info_vk = None;
+        let drm_format_modifier_plane_layouts_vk: SmallVec<[_; 4]>;
+        let mut drm_format_modifier_explicit_info_vk = None;
 
         if !external_memory_handle_types.is_empty() {
             let next = external_memory_info_vk.insert(ash::vk::ExternalMemoryImageCreateInfo {
@@ -913,8 +1069,27 @@ impl UnsafeImage {
             info_vk.p_next = next as *const _ as *const _;
         }
 
-        if external_memory_handle_types.dma_buf {
-            let next = image_drm_format_modifier_create_info.as_mut().unwrap();
+        if let Some(drm_format_modifier) = drm_format_modifier {
+            drm_format_modifier_plane_layouts_vk = drm_format_modifier_plane_layouts
+                .iter()
+                .map(|plane_layout| ash::vk::SubresourceLayout {
+                    offset: plane_layout.offset,
+                    size: plane_layout.size,
+                    row_pitch: plane_layout.row_pitch,
+                    array_pitch: plane_layout.array_pitch,
+                    depth_pitch: plane_layout.depth_pitch,
+                })
+                .collect();
+
+            let next = drm_format_modifier_explicit_info_vk.insert(
+                ash::vk::ImageDrmFormatModifierExplicitCreateInfoEXT {
+                    drm_format_modifier,
+                    drm_format_modifier_plane_count: drm_format_modifier_plane_layouts_vk.len()
+                        as u32,
+                    p_plane_layouts: drm_format_modifier_plane_layouts_vk.as_ptr(),
+                    ..Default::default()
+                },
+            );
 
             next.p_next = info_vk.p_next;
             info_vk.p_next = next as *const _ as *const _;
@@ -934,7 +1109,17 @@ impl UnsafeImage {
             output.assume_init()
         };
 
-        Ok(Self::from_handle(device, handle, create_info))
+        let image = Self::from_handle(device, handle, create_info);
+
+        if let Some(name) = image.name.lock().as_deref() {
+            image.device.set_debug_utils_object_name_if_enabled(
+                ash::vk::ObjectType::IMAGE,
+                handle.as_raw(),
+                Some(name),
+            )?;
+        }
+
+        Ok(image)
     }
 
     /// Creates a new `UnsafeImage` from a raw object handle.
@@ -957,14 +1142,17 @@ impl UnsafeImage {
             tiling,
             usage,
             mut stencil_usage,
-            sharing: _,
+            sharing,
             initial_layout,
             external_memory_handle_types,
             mutable_format,
             cube_compatible,
             array_2d_compatible,
             block_texel_view_compatible,
-            image_drm_format_modifier_create_info: _,
+            sparse,
+            drm_format_modifier,
+            drm_format_modifier_plane_layouts: _,
+            name,
             _ne: _,
         } = create_info;
 
@@ -984,9 +1172,28 @@ impl UnsafeImage {
             match tiling {
                 ImageTiling::Linear => format_properties.linear_tiling_features,
                 ImageTiling::Optimal => format_properties.optimal_tiling_features,
-                ImageTiling::DrmFormatModifier => format_properties.linear_tiling_features,
+                ImageTiling::DrmFormatModifier => device
+                    .physical_device()
+                    .drm_format_modifier_properties_unchecked(format.unwrap())
+                    .into_iter()
+                    .find(|properties| Some(properties.drm_format_modifier) == drm_format_modifier)
+                    .map_or_else(Default::default, |properties| {
+                        properties.drm_format_modifier_tiling_features
+                    }),
             }
         };
+
+        let flags = ImageCreateFlags {
+            sparse_binding: sparse.is_some(),
+            sparse_residency: sparse.map_or(false, |sparse_level| sparse_level.sparse_residency),
+            sparse_aliased: sparse.map_or(false, |sparse_level| sparse_level.sparse_aliased),
+            mutable_format,
+            cube_compatible,
+            array_2d_compatible,
+            block_texel_view_compatible,
+            ..ImageCreateFlags::empty()
+        };
+
         let aspects = format.unwrap().aspects();
         let aspect_list: SmallVec<[ImageAspect; 4]> = aspects.iter().collect();
         let mip_level_size = dimensions.array_layers() as DeviceSize;
@@ -1006,11 +1213,12 @@ impl UnsafeImage {
             tiling,
             usage,
             stencil_usage,
+            sharing,
             external_memory_handle_types,
-            mutable_format,
-            cube_compatible,
-            array_2d_compatible,
-            block_texel_view_compatible,
+            flags,
+            sparse,
+            drm_format_modifier,
+            name: Mutex::new(name),
 
             aspect_list,
             aspect_size,
@@ -1028,6 +1236,7 @@ impl UnsafeImage {
         device: Arc<Device>,
         handle: ash::vk::Image,
         usage: ImageUsage,
+        sharing: Sharing<SmallVec<[u32; 4]>>,
         format: Format,
         flags: ImageCreateFlags,
         dimensions: ImageDimensions,
@@ -1075,11 +1284,12 @@ impl UnsafeImage {
             tiling,
             usage,
             stencil_usage: usage,
+            sharing,
             external_memory_handle_types: ExternalMemoryHandleTypes::empty(),
-            mutable_format: flags.mutable_format,
-            cube_compatible: flags.cube_compatible,
-            array_2d_compatible: flags.array_2d_compatible,
-            block_texel_view_compatible: flags.block_texel_view_compatible,
+            flags,
+            sparse: None,
+            drm_format_modifier: None,
+            name: Mutex::new(None),
 
             aspect_list,
             aspect_size,
@@ -1469,6 +1679,32 @@ impl UnsafeImage {
         self.state.lock()
     }
 
+    /// Returns the layout that the given subresources were left in by the most recently
+    /// submitted command buffer that used them, or the initial layout of the image if they have
+    /// not been used yet.
+    ///
+    /// Returns `None` if the subresources are not all in the same layout.
+    ///
+    /// # Panics
+    ///
+    /// - Panics if `subresource_range` is not within the image.
+    pub fn current_layout(&self, subresource_range: ImageSubresourceRange) -> Option<ImageLayout> {
+        let state = self.state.lock();
+        let mut current_layout = None;
+
+        for range in self.iter_ranges(subresource_range) {
+            for (_range, range_state) in state.ranges.range(&range) {
+                match current_layout {
+                    None => current_layout = Some(range_state.layout),
+                    Some(layout) if layout != range_state.layout => return None,
+                    Some(_) => (),
+                }
+            }
+        }
+
+        current_layout
+    }
+
     /// Returns the dimensions of the image.
     #[inline]
     pub fn dimensions(&self) -> ImageDimensions {
@@ -1523,34 +1759,58 @@ impl UnsafeImage {
         &self.stencil_usage
     }
 
+    /// Returns the sharing mode the image was created with.
+    #[inline]
+    pub fn sharing(&self) -> &Sharing<SmallVec<[u32; 4]>> {
+        &self.sharing
+    }
+
     /// Returns the external memory handle types that are supported with this image.
     #[inline]
     pub fn external_memory_handle_types(&self) -> ExternalMemoryHandleTypes {
         self.external_memory_handle_types
     }
 
+    /// Returns the flags the image was created with.
+    #[inline]
+    pub fn flags(&self) -> ImageCreateFlags {
+        self.flags
+    }
+
     /// Returns whether `mutable_format` is enabled on the image.
     #[inline]
     pub fn mutable_format(&self) -> bool {
-        self.mutable_format
+        self.flags.mutable_format
     }
 
     /// Returns whether `cube_compatible` is enabled on the image.
     #[inline]
     pub fn cube_compatible(&self) -> bool {
-        self.cube_compatible
+        self.flags.cube_compatible
     }
 
     /// Returns whether `array_2d_compatible` is enabled on the image.
     #[inline]
     pub fn array_2d_compatible(&self) -> bool {
-        self.array_2d_compatible
+        self.flags.array_2d_compatible
     }
 
     /// Returns whether `block_texel_view_compatible` is enabled on the image.
     #[inline]
     pub fn block_texel_view_compatible(&self) -> bool {
-        self.block_texel_view_compatible
+        self.flags.block_texel_view_compatible
+    }
+
+    /// Returns the level of sparse binding that the image was created with, if any.
+    #[inline]
+    pub fn sparse(&self) -> Option<SparseLevel> {
+        self.sparse
+    }
+
+    /// Returns the Linux DRM format modifier that the image was created with, if any.
+    #[inline]
+    pub fn drm_format_modifier(&self) -> Option<u64> {
+        self.drm_format_modifier
     }
 
     /// Returns an `ImageSubresourceLayers` covering the first mip level of the image. All aspects
@@ -1591,6 +1851,31 @@ impl UnsafeImage {
         }
     }
 
+    /// Returns the name of the image, if it has one.
+    #[inline]
+    pub fn name(&self) -> Option<String> {
+        self.name.lock().clone()
+    }
+
+    /// Sets or removes the name of the image.
+    ///
+    /// The name is used in the error messages of vulkano. If the `ext_debug_utils` extension is
+    /// enabled on the instance, it is also assigned to the image with
+    /// [`Device::set_debug_utils_object_name`], so that debugging tools show it.
+    pub fn set_name(&self, name: Option<&str>) -> Result<(), OomError> {
+        unsafe {
+            self.device.set_debug_utils_object_name_if_enabled(
+                ash::vk::ObjectType::IMAGE,
+                self.handle.as_raw(),
+                name,
+            )?;
+        }
+
+        *self.name.lock() = name.map(ToOwned::to_owned);
+
+        Ok(())
+    }
+
     /// Returns a key unique to each `UnsafeImage`. Can be used for the `conflicts_key` method.
     #[inline]
     pub fn key(&self) -> u64 {
@@ -1861,7 +2146,42 @@ pub struct UnsafeImageCreateInfo {
     /// The default value is `false`.
     pub block_texel_view_compatible: bool,
 
-    pub image_drm_format_modifier_create_info: Option<ImageDrmFormatModifierExplicitCreateInfoEXT>,
+    /// Create an image with sparsely bound memory.
+    ///
+    /// If `Some`, memory is not bound to the image with `bind_memory`, but with sparse bind
+    /// operations on a queue.
+    ///
+    /// The default value is `None`.
+    pub sparse: Option<SparseLevel>,
+
+    /// The Linux DRM format modifier to create the image with.
+    ///
+    /// Must be `Some` if `tiling` is [`ImageTiling::DrmFormatModifier`], and `None` otherwise. The
+    /// modifier must be one of those returned by
+    /// [`PhysicalDevice::drm_format_modifier_properties`](crate::device::physical::PhysicalDevice::drm_format_modifier_properties)
+    /// for `format`.
+    ///
+    /// The default value is `None`.
+    pub drm_format_modifier: Option<u64>,
+
+    /// The layout of each memory plane of the image, if `drm_format_modifier` is `Some`.
+    ///
+    /// There must be as many elements as the
+    /// [`drm_format_modifier_plane_count`](crate::format::DrmFormatModifierProperties::drm_format_modifier_plane_count)
+    /// of the modifier. For each element, `size` must be 0, `array_pitch` must be 0 if the image
+    /// has only one array layer, and `depth_pitch` must be 0 if the image is not three-dimensional.
+    ///
+    /// The default value is empty.
+    pub drm_format_modifier_plane_layouts: SmallVec<[SubresourceLayout; 4]>,
+
+    /// A name for the image, that is used in the error messages of vulkano.
+    ///
+    /// If the `ext_debug_utils` extension is enabled on the instance, the name is also assigned
+    /// to the image with [`Device::set_debug_utils_object_name`], so that debugging tools show
+    /// it. Otherwise, this doesn't require the extension.
+    ///
+    /// The default value is `None`.
+    pub name: Option<String>,
 
     pub _ne: crate::NonExhaustive,
 }
@@ -1888,7 +2208,10 @@ impl Default for UnsafeImageCreateInfo {
             cube_compatible: false,
             array_2d_compatible: false,
             block_texel_view_compatible: false,
-            image_drm_format_modifier_create_info: None,
+            sparse: None,
+            drm_format_modifier: None,
+            drm_format_modifier_plane_layouts: SmallVec::new(),
+            name: None,
             _ne: crate::NonExhaustive(()),
         }
     }
@@ -1923,6 +2246,32 @@ pub enum ImageCreationError {
     /// The cube_compatible flag was enabled together with multisampling.
     CubeCompatibleMultisampling,
 
+    /// No DMA-BUF file descriptor was provided, or the planes were given different file
+    /// descriptors. Importing each plane from its own file descriptor is not supported.
+    DmaBufFdsNotSupported,
+
+    /// The given DRM format modifier is not supported for the format.
+    DrmFormatModifierNotSupported {
+        drm_format_modifier: u64,
+    },
+
+    /// The number of DRM format modifier plane layouts does not equal the number of memory planes
+    /// required by the modifier.
+    DrmFormatModifierPlaneCountMismatch {
+        provided: u32,
+        required: u32,
+    },
+
+    /// A DRM format modifier plane layout has a nonzero `size`, or a nonzero `array_pitch` or
+    /// `depth_pitch` that is not allowed for the image dimensions.
+    DrmFormatModifierPlaneLayoutInvalid {
+        plane_index: usize,
+    },
+
+    /// A DRM format modifier was provided but the tiling was not `DrmFormatModifier`, or the
+    /// tiling was `DrmFormatModifier` but no modifier was provided.
+    DrmFormatModifierTilingMismatch,
+
     /// One or more external memory handle types were provided, but the initial layout was not
     /// `Undefined`.
     ExternalMemoryInvalidInitialLayout,
@@ -1978,6 +2327,10 @@ pub enum ImageCreationError {
     /// Multisampling was enabled, but the image type was not 2D.
     MultisampleNot2d,
 
+    /// The `sparse_residency` sparse level was requested, but the image type was 1D or the tiling
+    /// was not `Optimal`.
+    SparseResidencyInvalidImage,
+
     /// The sample count is not supported by the device for this image configuration.
     SampleCountNotSupported {
         samples: SampleCount,
@@ -2061,6 +2414,33 @@ impl Display for ImageCreationError {
                 f,
                 "the cube_compatible flag was enabled together with multisampling",
             ),
+            Self::DmaBufFdsNotSupported => write!(
+                f,
+                "no DMA-BUF file descriptor was provided, or the planes were given different file \
+                descriptors",
+            ),
+            Self::DrmFormatModifierNotSupported {
+                drm_format_modifier,
+            } => write!(
+                f,
+                "the DRM format modifier {:#x} is not supported for the format",
+                drm_format_modifier,
+            ),
+            Self::DrmFormatModifierPlaneCountMismatch { provided, required } => write!(
+                f,
+                "{} DRM format modifier plane layouts were provided, but the modifier requires {}",
+                provided, required,
+            ),
+            Self::DrmFormatModifierPlaneLayoutInvalid { plane_index } => write!(
+                f,
+                "the DRM format modifier plane layout at index {} is not valid for the image",
+                plane_index,
+            ),
+            Self::DrmFormatModifierTilingMismatch => write!(
+                f,
+                "a DRM format modifier was provided without `DrmFormatModifier` tiling, or vice \
+                versa",
+            ),
             Self::ExternalMemoryInvalidInitialLayout => write!(
                 f,
                 "one or more external memory handle types were provided, but the initial layout \
@@ -2112,6 +2492,11 @@ impl Display for ImageCreationError {
                 f,
                 "multisampling was enabled, but the image type was not 2D",
             ),
+            Self::SparseResidencyInvalidImage => write!(
+                f,
+                "the `sparse_residency` sparse level was requested, but the image type was 1D or \
+                the tiling was not `Optimal`",
+            ),
             Self::SampleCountNotSupported { .. } => write!(
                 f,
                 "the sample count is not supported by the device for this image configuration",
@@ -2204,6 +2589,20 @@ pub struct LinearLayout {
     pub depth_pitch: DeviceSize,
 }
 
+vulkan_bitflags! {
+    /// The level of sparse binding that an image should be created with.
+    #[non_exhaustive]
+    SparseLevel = ImageCreateFlags(u32);
+
+    /// The image can be partially resident: not every part of it needs to have memory bound to
+    /// it before it is used.
+    sparse_residency = SPARSE_RESIDENCY,
+
+    /// The memory that is bound to the image can also be bound to other parts of the same image,
+    /// or to other resources, at the same time.
+    sparse_aliased = SPARSE_ALIASED,
+}
+
 /// The current state of an image.
 #[derive(Debug)]
 pub(crate) struct ImageState {
@@ -2221,6 +2620,7 @@ impl ImageState {
                         gpu_reads: 0,
                     },
                     layout: initial_layout,
+                    recorded_layout: initial_layout,
                 },
             )]
             .into_iter()
@@ -2228,6 +2628,57 @@ impl ImageState {
         }
     }
 
+    /// Returns an iterator over the ranges of the image and the layout that each range will be in
+    /// once all the command buffers that have been recorded so far have executed.
+    pub(crate) fn recorded_layouts(
+        &self,
+    ) -> impl Iterator<Item = (Range<DeviceSize>, ImageLayout)> + '_ {
+        self.ranges
+            .iter()
+            .map(|(range, state)| (range.clone(), state.recorded_layout))
+    }
+
+    /// Sets the layout that the given range of the image is left in by the command buffer that
+    /// was just recorded.
+    pub(crate) fn set_recorded_layout(&mut self, range: Range<DeviceSize>, layout: ImageLayout) {
+        self.ranges.split_at(&range.start);
+        self.ranges.split_at(&range.end);
+
+        for (_range, state) in self.ranges.range_mut(&range) {
+            state.recorded_layout = layout;
+        }
+    }
+
+    /// Checks that the given range of the image is exactly in `expected_layout`. Unlike
+    /// `check_gpu_read` and `check_gpu_write`, `Undefined` is not treated as "don't care".
+    pub(crate) fn check_layout(
+        &self,
+        range: Range<DeviceSize>,
+        expected_layout: ImageLayout,
+    ) -> Result<(), AccessError> {
+        for (_range, state) in self.ranges.range(&range) {
+            if state.layout != expected_layout {
+                return Err(AccessError::UnexpectedImageLayout {
+                    allowed: state.layout,
+                    requested: expected_layout,
+                });
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Returns whether any part of `range` is currently locked by a queue operation.
+    pub(crate) fn is_gpu_locked(&self, range: Range<DeviceSize>) -> bool {
+        self.ranges
+            .range(&range)
+            .any(|(_range, state)| match state.current_access {
+                CurrentAccess::CpuExclusive => false,
+                CurrentAccess::GpuExclusive { .. } => true,
+                CurrentAccess::Shared { gpu_reads, .. } => gpu_reads > 0,
+            })
+    }
+
     #[allow(dead_code)]
     pub(crate) fn check_cpu_read(&mut self, range: Range<DeviceSize>) -> Result<(), ReadLockError> {
         for (_range, state) in self.ranges.range(&range) {
@@ -2452,6 +2903,11 @@ impl ImageState {
 struct ImageRangeState {
     current_access: CurrentAccess,
     layout: ImageLayout,
+
+    // The layout that the range will be in after all the command buffers that have been recorded
+    // so far, including those that haven't been submitted yet. Only used for images that are
+    // layout-tracked.
+    recorded_layout: ImageLayout,
 }
 
 #[derive(Clone)]
@@ -2617,6 +3073,34 @@ mod tests {
         .unwrap();
     }
 
+    #[test]
+    fn drm_format_modifier_without_tiling() {
+        let (device, _) = gfx_dev_and_queue!();
+
+        let res = UnsafeImage::new(
+            device,
+            UnsafeImageCreateInfo {
+                dimensions: ImageDimensions::Dim2d {
+                    width: 32,
+                    height: 32,
+                    array_layers: 1,
+                },
+                format: Some(Format::R8G8B8A8_UNORM),
+                usage: ImageUsage {
+                    sampled: true,
+                    ..ImageUsage::empty()
+                },
+                drm_format_modifier: Some(0),
+                ..Default::default()
+            },
+        );
+
+        assert!(matches!(
+            res,
+            Err(ImageCreationError::DrmFormatModifierTilingMismatch)
+        ));
+    }
+
     #[test]
     fn create_transient() {
         let (device, _) = gfx_dev_and_queue!();
@@ -14,7 +14,7 @@ use super::{
 use crate::{
     device::{Device, DeviceOwned},
     format::{Format, FormatFeatures},
-    SafeDeref,
+    OomError, SafeDeref,
 };
 use std::{
     fmt::{Debug, Error as FmtError, Formatter},
@@ -27,6 +27,14 @@ pub unsafe trait ImageAccess: DeviceOwned + Send + Sync {
     /// Returns the inner unsafe image object used by this image.
     fn inner(&self) -> ImageInner<'_>;
 
+    /// Sets or removes the name of the image, for use in error messages and debugging tools.
+    ///
+    /// This names the whole underlying [`UnsafeImage`]. See [`UnsafeImage::set_name`].
+    #[inline]
+    fn set_name(&self, name: Option<&str>) -> Result<(), OomError> {
+        self.inner().image.set_name(name)
+    }
+
     /// Returns the dimensions of the image.
     #[inline]
     fn dimensions(&self) -> ImageDimensions {
@@ -167,13 +175,45 @@ pub unsafe trait ImageAccess: DeviceOwned + Send + Sync {
         self.inner().image.initial_layout()
     }
 
+    /// Returns whether vulkano keeps track of the layout of each subresource of the image, instead
+    /// of using `initial_layout_requirement` and `final_layout_requirement`.
+    ///
+    /// If this returns `true`, the first time you use a subresource of the image in a primary
+    /// command buffer, vulkano will transition it from the layout it is left in by the last
+    /// recorded command buffer that used it, even if that one hasn't been submitted yet. Once
+    /// all recorded command buffers have executed, this is the layout returned by
+    /// [`UnsafeImage::current_layout`].
+    /// At the end of the command buffer, the subresource is left in the layout of its last use,
+    /// so that the next command buffer does not need to transition it back.
+    ///
+    /// For command buffers that are not created with `CommandBufferUsage::OneTimeSubmit`, the
+    /// subresources are transitioned back to the layout they had when the command buffer was
+    /// recorded, so that it can be submitted again. If they were still `Undefined` or
+    /// `Preinitialized`, they are transitioned to `final_layout_requirement` instead, and
+    /// submitting the command buffer a second time returns an error because they no longer have
+    /// the layout it expects. To reuse such a command buffer, record it after the image has been
+    /// initialized.
+    ///
+    /// Command buffers that use the image must therefore be submitted in the same order that
+    /// they were recorded in. If the subresources are not in the layout that a command buffer
+    /// expects when it is submitted, for example because it was submitted before a command buffer
+    /// that was recorded earlier, or because a command buffer that was recorded earlier was
+    /// dropped without being submitted, submitting it returns a
+    /// `CommandBufferExecError::AccessError` containing `AccessError::UnexpectedImageLayout`,
+    /// instead of discarding the contents of the image.
+    #[inline]
+    fn is_layout_tracked(&self) -> bool {
+        false
+    }
+
     /// Returns the layout that the image has when it is first used in a primary command buffer.
     ///
     /// The first time you use an image in an `AutoCommandBufferBuilder`, vulkano will suppose that
     /// the image is in the layout returned by this function. Later when the command buffer is
     /// submitted vulkano will check whether the image is actually in this layout, and if it is not
     /// the case then an error will be returned.
-    /// TODO: ^ that check is not yet implemented
+    ///
+    /// This is ignored if `is_layout_tracked` returns `true`.
     fn initial_layout_requirement(&self) -> ImageLayout;
 
     /// Returns the layout that the image must be returned to before the end of the command buffer.
@@ -185,6 +225,8 @@ pub unsafe trait ImageAccess: DeviceOwned + Send + Sync {
     /// Except for special cases, this value should likely be the same as the one returned by
     /// `initial_layout_requirement` so that the user can submit multiple command buffers that use
     /// this image one after the other.
+    ///
+    /// This is ignored if `is_layout_tracked` returns `true`.
     fn final_layout_requirement(&self) -> ImageLayout;
 
     /// Wraps around this `ImageAccess` and returns an identical `ImageAccess` but whose initial
@@ -347,4 +389,8 @@ where
     fn is_layout_initialized(&self) -> bool {
         (**self).is_layout_initialized()
     }
+
+    fn is_layout_tracked(&self) -> bool {
+        (**self).is_layout_tracked()
+    }
 }
@@ -25,6 +25,7 @@ use crate::{
     sampler::{ycbcr::SamplerYcbcrConversion, ComponentMapping},
     OomError, RequirementNotMet, RequiresOneOf, Version, VulkanError, VulkanObject,
 };
+use ash::vk::Handle;
 use std::{
     error::Error,
     fmt::{Debug, Display, Error as FmtError, Formatter},
@@ -50,6 +51,7 @@ where
     subresource_range: ImageSubresourceRange,
     usage: ImageUsage,
     view_type: ImageViewType,
+    name: Option<String>,
 
     filter_cubic: bool,
     filter_cubic_minmax: bool,
@@ -95,6 +97,7 @@ where
             ref subresource_range,
             mut usage,
             ref sampler_ycbcr_conversion,
+            name: _,
             _ne: _,
         } = create_info;
 
@@ -488,6 +491,7 @@ where
             ref subresource_range,
             mut usage,
             ref sampler_ycbcr_conversion,
+            name: _,
             _ne: _,
         } = &create_info;
 
@@ -550,7 +554,18 @@ where
             output.assume_init()
         };
 
-        Self::from_handle_with_format_features(image, handle, create_info, format_features)
+        let image_view =
+            Self::from_handle_with_format_features(image, handle, create_info, format_features)?;
+
+        if let Some(name) = image_view.name.as_deref() {
+            image_view.device().set_debug_utils_object_name_if_enabled(
+                ash::vk::ObjectType::IMAGE_VIEW,
+                handle.as_raw(),
+                Some(name),
+            )?;
+        }
+
+        Ok(image_view)
     }
 
     /// Creates a default `ImageView`. Equivalent to
@@ -589,6 +604,7 @@ where
             subresource_range,
             mut usage,
             sampler_ycbcr_conversion,
+            name,
             _ne: _,
         } = create_info;
 
@@ -618,6 +634,7 @@ where
                         tiling: image_inner.tiling(),
                         usage: *image_inner.usage(),
                         image_view_type: Some(view_type),
+                        drm_format_modifier: image_inner.drm_format_modifier(),
                         mutable_format: image_inner.mutable_format(),
                         cube_compatible: image_inner.cube_compatible(),
                         array_2d_compatible: image_inner.array_2d_compatible(),
@@ -642,6 +659,7 @@ where
             subresource_range,
             usage,
             sampler_ycbcr_conversion,
+            name,
 
             filter_cubic,
             filter_cubic_minmax,
@@ -679,7 +697,16 @@ where
             match image.tiling() {
                 ImageTiling::Optimal => format_properties.optimal_tiling_features,
                 ImageTiling::Linear => format_properties.linear_tiling_features,
-                ImageTiling::DrmFormatModifier => format_properties.linear_tiling_features,
+                ImageTiling::DrmFormatModifier => device
+                    .physical_device()
+                    .drm_format_modifier_properties_unchecked(format)
+                    .into_iter()
+                    .find(|properties| {
+                        Some(properties.drm_format_modifier) == image.drm_format_modifier()
+                    })
+                    .map_or_else(Default::default, |properties| {
+                        properties.drm_format_modifier_tiling_features
+                    }),
             }
         } else {
             *image.format_features()
@@ -710,6 +737,12 @@ where
     pub fn image(&self) -> &Arc<I> {
         &self.image
     }
+
+    /// Returns the name that the image view was created with, if any.
+    #[inline]
+    pub fn name(&self) -> Option<&str> {
+        self.name.as_deref()
+    }
 }
 
 impl<I> Drop for ImageView<I>
@@ -822,6 +855,15 @@ pub struct ImageViewCreateInfo {
     /// The default value is `None`.
     pub sampler_ycbcr_conversion: Option<Arc<SamplerYcbcrConversion>>,
 
+    /// A name for the image view.
+    ///
+    /// If the `ext_debug_utils` extension is enabled on the instance, the name is assigned to the
+    /// image view with [`Device::set_debug_utils_object_name`], so that debugging tools show it.
+    /// Otherwise, this doesn't require the extension.
+    ///
+    /// The default value is `None`.
+    pub name: Option<String>,
+
     pub _ne: crate::NonExhaustive,
 }
 
@@ -839,6 +881,7 @@ impl Default for ImageViewCreateInfo {
             },
             usage: ImageUsage::empty(),
             sampler_ycbcr_conversion: None,
+            name: None,
             _ne: crate::NonExhaustive(()),
         }
     }
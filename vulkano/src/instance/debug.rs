@@ -40,16 +40,49 @@
 //! Note that you must keep the `_callback` object alive for as long as you want your callback to
 //! be callable. If you don't store the return value of `DebugUtilsMessenger`'s constructor in a
 //! variable, it will be immediately destroyed and your callback will not work.
+//!
+//! # Ready-made callbacks
+//!
+//! Instead of writing a callback yourself, you can use one of the [`DebugCallback`]s that vulkano
+//! provides, and enable it for the whole lifetime of the instance with
+//! [`InstanceCreateInfo::debug_callbacks`](crate::instance::InstanceCreateInfo::debug_callbacks):
+//!
+//! ```no_run
+//! # use vulkano::VulkanLibrary;
+//! use vulkano::instance::{debug::DebugCallback, Instance, InstanceCreateInfo};
+//!
+//! # let library = VulkanLibrary::new().unwrap();
+//! let instance = Instance::new(
+//!     library,
+//!     InstanceCreateInfo {
+//!         debug_callbacks: vec![DebugCallback::stderr()],
+//!         ..Default::default()
+//!     },
+//! );
+//! ```
+//!
+//! - [`DebugCallback::stderr`] prints messages to the standard error output.
+//! - [`DebugCallback::log`] forwards messages to the [`log`](https://docs.rs/log) crate, if the
+//!   `log` feature is enabled.
+//! - [`DebugMessageRecorder::callback`] records messages, so that tests can assert that no
+//!   validation errors happened.
+//!
+//! Messages that are known to be harmless can be ignored by setting
+//! [`DebugCallback::ignored_message_ids`].
 
 use super::Instance;
-use crate::{macros::vulkan_bitflags, RequirementNotMet, RequiresOneOf, VulkanError, VulkanObject};
+use crate::{
+    macros::{vulkan_bitflags, vulkan_enum},
+    RequirementNotMet, RequiresOneOf, VulkanError, VulkanObject,
+};
+use parking_lot::Mutex;
 use std::{
     error::Error,
     ffi::{c_void, CStr},
     fmt::{Debug, Display, Error as FmtError, Formatter},
     mem::MaybeUninit,
     panic::{catch_unwind, AssertUnwindSafe, RefUnwindSafe},
-    ptr,
+    ptr, slice,
     sync::Arc,
 };
 
@@ -228,11 +261,58 @@ pub(super) unsafe extern "system" fn trampoline(
             .to_str()
             .expect("debug callback message not utf-8");
 
+        let labels = |labels: *const ash::vk::DebugUtilsLabelEXT, count: u32| {
+            if count == 0 || labels.is_null() {
+                return Vec::new();
+            }
+
+            slice::from_raw_parts(labels, count as usize)
+                .iter()
+                .map(|label| MessageLabel {
+                    label_name: CStr::from_ptr(label.p_label_name)
+                        .to_str()
+                        .expect("debug callback label not utf-8"),
+                    color: label.color,
+                })
+                .collect()
+        };
+
+        let objects = if (*callback_data).object_count == 0 || (*callback_data).p_objects.is_null()
+        {
+            Vec::new()
+        } else {
+            slice::from_raw_parts(
+                (*callback_data).p_objects,
+                (*callback_data).object_count as usize,
+            )
+            .iter()
+            .map(|object| MessageObject {
+                object_type: object.object_type.try_into().ok(),
+                object_handle: object.object_handle,
+                object_name: object.p_object_name.as_ref().map(|object_name| {
+                    CStr::from_ptr(object_name)
+                        .to_str()
+                        .expect("debug callback object name not utf-8")
+                }),
+            })
+            .collect()
+        };
+
         let message = Message {
             severity: message_severity.into(),
             ty: message_types.into(),
             layer_prefix,
+            message_id_number: (*callback_data).message_id_number,
             description,
+            queue_labels: labels(
+                (*callback_data).p_queue_labels,
+                (*callback_data).queue_label_count,
+            ),
+            cmd_buf_labels: labels(
+                (*callback_data).p_cmd_buf_labels,
+                (*callback_data).cmd_buf_label_count,
+            ),
+            objects,
         };
 
         user_callback(&message);
@@ -348,15 +428,449 @@ impl Debug for DebugUtilsMessengerCreateInfo {
 }
 
 /// A message received by the callback.
+///
+/// The `Display` implementation formats the message on a single line, along with its ID, the
+/// objects it relates to and the active labels.
 pub struct Message<'a> {
     /// Severity of message.
     pub severity: DebugUtilsMessageSeverity,
     /// Type of message,
     pub ty: DebugUtilsMessageType,
-    /// Prefix of the layer that reported this message or `None` if unknown.
+    /// Prefix of the layer that reported this message or `None` if unknown. For validation
+    /// messages, this is the name of the message ID, such as a VUID.
     pub layer_prefix: Option<&'a str>,
+    /// The number of the message ID. This is zero if the message has no ID.
+    pub message_id_number: i32,
     /// Description of the message.
     pub description: &'a str,
+    /// The labels of the queue that were active when the message was emitted, innermost first.
+    pub queue_labels: Vec<MessageLabel<'a>>,
+    /// The labels of the command buffer that were active when the message was emitted,
+    /// innermost first.
+    pub cmd_buf_labels: Vec<MessageLabel<'a>>,
+    /// The objects that the message relates to.
+    pub objects: Vec<MessageObject<'a>>,
+}
+
+impl<'a> Message<'a> {
+    /// Returns whether the message is the output of a `debugPrintfEXT` call in a shader.
+    ///
+    /// These messages are emitted by the validation layers if
+    /// [`ValidationFeatureEnable::DebugPrintf`](crate::instance::ValidationFeatureEnable::DebugPrintf)
+    /// is enabled. They have the `information` severity, and the formatted output of the shader as
+    /// their description.
+    #[inline]
+    pub fn is_debug_printf(&self) -> bool {
+        self.layer_prefix.map_or(false, |message_id_name| {
+            message_id_name.contains("DEBUG-PRINTF")
+        })
+    }
+
+    fn is_ignored_by(&self, callback: &DebugCallback) -> bool {
+        self.layer_prefix.map_or(false, |message_id_name| {
+            callback
+                .ignored_message_ids
+                .iter()
+                .any(|ignored| ignored == message_id_name)
+        }) || (self.message_id_number != 0
+            && callback
+                .ignored_message_id_numbers
+                .contains(&self.message_id_number))
+    }
+}
+
+impl<'a> Display for Message<'a> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result<(), FmtError> {
+        if let Some(message_id_name) = self.layer_prefix {
+            write!(
+                f,
+                "[{} ({:#x})] ",
+                message_id_name, self.message_id_number as u32,
+            )?;
+        }
+
+        write!(f, "{}", self.description)?;
+
+        for (index, object) in self.objects.iter().enumerate() {
+            let separator = if index == 0 { " | objects: " } else { ", " };
+            write!(f, "{}{}", separator, object)?;
+        }
+
+        for (index, label) in self.queue_labels.iter().enumerate() {
+            let separator = if index == 0 {
+                " | queue labels: "
+            } else {
+                ", "
+            };
+            write!(f, "{}\"{}\"", separator, label.label_name)?;
+        }
+
+        for (index, label) in self.cmd_buf_labels.iter().enumerate() {
+            let separator = if index == 0 {
+                " | command buffer labels: "
+            } else {
+                ", "
+            };
+            write!(f, "{}\"{}\"", separator, label.label_name)?;
+        }
+
+        Ok(())
+    }
+}
+
+/// A label that was active when a [`Message`] was emitted.
+#[derive(Clone, Copy, Debug)]
+pub struct MessageLabel<'a> {
+    /// The name of the label.
+    pub label_name: &'a str,
+    /// The color of the label.
+    pub color: [f32; 4],
+}
+
+/// An object that a [`Message`] relates to.
+#[derive(Clone, Copy, Debug)]
+pub struct MessageObject<'a> {
+    /// The type of the object, or `None` if vulkano doesn't know the type.
+    pub object_type: Option<ObjectType>,
+    /// The raw Vulkan handle of the object.
+    pub object_handle: u64,
+    /// The name that was given to the object, if any.
+    pub object_name: Option<&'a str>,
+}
+
+impl<'a> Display for MessageObject<'a> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result<(), FmtError> {
+        match self.object_type {
+            Some(object_type) => write!(f, "{:?}", object_type)?,
+            None => write!(f, "Object")?,
+        }
+
+        write!(f, " {:#x}", self.object_handle)?;
+
+        if let Some(object_name) = self.object_name {
+            write!(f, " \"{}\"", object_name)?;
+        }
+
+        Ok(())
+    }
+}
+
+/// A ready-made callback for debug messages.
+///
+/// The callback can be enabled for the whole lifetime of an instance with
+/// [`InstanceCreateInfo::debug_callbacks`](crate::instance::InstanceCreateInfo::debug_callbacks),
+/// or used to create a [`DebugUtilsMessenger`] with [`to_create_info`](Self::to_create_info).
+#[derive(Clone)]
+pub struct DebugCallback {
+    /// The message severity types that the callback should be called for.
+    ///
+    /// The value must not be empty.
+    ///
+    /// The default value depends on the constructor.
+    pub message_severity: DebugUtilsMessageSeverity,
+
+    /// The message types that the callback should be called for.
+    ///
+    /// The value must not be empty.
+    ///
+    /// The default value is all message types.
+    pub message_type: DebugUtilsMessageType,
+
+    /// Names of message IDs that are not passed to the callback, such as
+    /// `"UNASSIGNED-BestPractices-vkCreateInstance-specialuse-extension-debugging"`.
+    ///
+    /// The default value is empty.
+    pub ignored_message_ids: Vec<String>,
+
+    /// Numbers of message IDs that are not passed to the callback.
+    ///
+    /// The default value is empty.
+    pub ignored_message_id_numbers: Vec<i32>,
+
+    user_callback: UserCallback,
+}
+
+impl DebugCallback {
+    /// Returns a `DebugCallback` that prints messages to the standard error output.
+    ///
+    /// All message severities except `verbose` are printed. Debug printf output is printed with
+    /// a `[vulkan printf]` prefix.
+    pub fn stderr() -> Self {
+        Self::with_severity(
+            DebugUtilsMessageSeverity {
+                error: true,
+                warning: true,
+                information: true,
+                ..DebugUtilsMessageSeverity::empty()
+            },
+            Arc::new(|message| {
+                if message.is_debug_printf() {
+                    eprintln!("[vulkan printf] {}", message.description);
+                } else {
+                    eprintln!(
+                        "[vulkan {} {}] {}",
+                        severity_name(message.severity),
+                        type_name(message.ty),
+                        message,
+                    );
+                }
+            }),
+        )
+    }
+
+    /// Returns a `DebugCallback` that forwards messages to the `log` crate, with the
+    /// `vulkano::debug` target.
+    ///
+    /// Errors are logged as [`Level::Error`](log::Level::Error), warnings as
+    /// [`Level::Warn`](log::Level::Warn), information as [`Level::Info`](log::Level::Info) and
+    /// verbose messages as [`Level::Trace`](log::Level::Trace). All message severities are
+    /// forwarded, use the filtering of your logger to choose which are shown.
+    ///
+    /// Debug printf output is logged as [`Level::Info`](log::Level::Info) with the
+    /// `vulkano::debug_printf` target instead.
+    #[cfg(feature = "log")]
+    pub fn log() -> Self {
+        Self::with_severity(
+            DebugUtilsMessageSeverity {
+                error: true,
+                warning: true,
+                information: true,
+                verbose: true,
+                ..DebugUtilsMessageSeverity::empty()
+            },
+            Arc::new(|message| {
+                if message.is_debug_printf() {
+                    log::info!(target: "vulkano::debug_printf", "{}", message.description);
+                    return;
+                }
+
+                let level = if message.severity.error {
+                    log::Level::Error
+                } else if message.severity.warning {
+                    log::Level::Warn
+                } else if message.severity.information {
+                    log::Level::Info
+                } else {
+                    log::Level::Trace
+                };
+
+                log::log!(
+                    target: "vulkano::debug",
+                    level,
+                    "[{}] {}",
+                    type_name(message.ty),
+                    message,
+                );
+            }),
+        )
+    }
+
+    /// Returns a `DebugCallback` that calls `user_callback`.
+    ///
+    /// # Safety
+    ///
+    /// - `user_callback` must not make any calls to the Vulkan API.
+    pub unsafe fn user_callback(
+        message_severity: DebugUtilsMessageSeverity,
+        user_callback: UserCallback,
+    ) -> Self {
+        Self::with_severity(message_severity, user_callback)
+    }
+
+    fn with_severity(
+        message_severity: DebugUtilsMessageSeverity,
+        user_callback: UserCallback,
+    ) -> Self {
+        Self {
+            message_severity,
+            message_type: DebugUtilsMessageType {
+                general: true,
+                validation: true,
+                performance: true,
+                ..DebugUtilsMessageType::empty()
+            },
+            ignored_message_ids: Vec::new(),
+            ignored_message_id_numbers: Vec::new(),
+            user_callback,
+        }
+    }
+
+    /// Returns a `DebugUtilsMessengerCreateInfo` that calls this callback, with the ignored
+    /// message IDs filtered out.
+    pub fn to_create_info(&self) -> DebugUtilsMessengerCreateInfo {
+        let user_callback =
+            if self.ignored_message_ids.is_empty() && self.ignored_message_id_numbers.is_empty() {
+                self.user_callback.clone()
+            } else {
+                let callback = self.clone();
+                Arc::new(move |message: &Message<'_>| {
+                    if !message.is_ignored_by(&callback) {
+                        (callback.user_callback)(message);
+                    }
+                })
+            };
+
+        DebugUtilsMessengerCreateInfo {
+            message_severity: self.message_severity,
+            message_type: self.message_type,
+            ..DebugUtilsMessengerCreateInfo::user_callback(user_callback)
+        }
+    }
+}
+
+impl Debug for DebugCallback {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result<(), FmtError> {
+        let Self {
+            message_severity,
+            message_type,
+            ignored_message_ids,
+            ignored_message_id_numbers,
+            user_callback: _,
+        } = self;
+
+        f.debug_struct("DebugCallback")
+            .field("message_severity", message_severity)
+            .field("message_type", message_type)
+            .field("ignored_message_ids", ignored_message_ids)
+            .field("ignored_message_id_numbers", ignored_message_id_numbers)
+            .finish_non_exhaustive()
+    }
+}
+
+fn severity_name(severity: DebugUtilsMessageSeverity) -> &'static str {
+    if severity.error {
+        "error"
+    } else if severity.warning {
+        "warning"
+    } else if severity.information {
+        "information"
+    } else {
+        "verbose"
+    }
+}
+
+fn type_name(ty: DebugUtilsMessageType) -> &'static str {
+    if ty.validation {
+        "validation"
+    } else if ty.performance {
+        "performance"
+    } else {
+        "general"
+    }
+}
+
+/// Records debug messages, so that they can be inspected later.
+///
+/// This is mainly useful in tests, to assert that no validation errors happened:
+///
+/// ```no_run
+/// # use vulkano::VulkanLibrary;
+/// use vulkano::instance::{debug::DebugMessageRecorder, Instance, InstanceCreateInfo};
+///
+/// let recorder = DebugMessageRecorder::new();
+/// # let library = VulkanLibrary::new().unwrap();
+/// let instance = Instance::new(
+///     library,
+///     InstanceCreateInfo {
+///         debug_callbacks: vec![recorder.callback()],
+///         ..Default::default()
+///     },
+/// );
+///
+/// // Use the instance...
+///
+/// recorder.assert_no_errors();
+/// ```
+///
+/// Panics inside debug callbacks are caught, so a callback can't abort the operation that caused
+/// a message. Instead, the messages are recorded, and the test fails as soon as it checks them.
+#[derive(Clone, Debug, Default)]
+pub struct DebugMessageRecorder {
+    messages: Arc<Mutex<Vec<RecordedMessage>>>,
+}
+
+impl DebugMessageRecorder {
+    /// Creates a new `DebugMessageRecorder` with no messages.
+    #[inline]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Returns a `DebugCallback` that records error and warning messages into this recorder.
+    pub fn callback(&self) -> DebugCallback {
+        let messages = self.messages.clone();
+
+        DebugCallback::with_severity(
+            DebugUtilsMessageSeverity {
+                error: true,
+                warning: true,
+                ..DebugUtilsMessageSeverity::empty()
+            },
+            Arc::new(move |message| {
+                messages.lock().push(RecordedMessage {
+                    severity: message.severity,
+                    ty: message.ty,
+                    message_id_name: message.layer_prefix.map(ToOwned::to_owned),
+                    message_id_number: message.message_id_number,
+                    text: message.to_string(),
+                });
+            }),
+        )
+    }
+
+    /// Returns all the recorded messages.
+    #[inline]
+    pub fn messages(&self) -> Vec<RecordedMessage> {
+        self.messages.lock().clone()
+    }
+
+    /// Returns the recorded messages with the `error` severity.
+    #[inline]
+    pub fn errors(&self) -> Vec<RecordedMessage> {
+        self.messages
+            .lock()
+            .iter()
+            .filter(|message| message.severity.error)
+            .cloned()
+            .collect()
+    }
+
+    /// Removes all the recorded messages.
+    #[inline]
+    pub fn clear(&self) {
+        self.messages.lock().clear();
+    }
+
+    /// Panics if messages with the `error` severity were recorded, listing them in the panic
+    /// message.
+    #[track_caller]
+    pub fn assert_no_errors(&self) {
+        let errors = self.errors();
+
+        if !errors.is_empty() {
+            let list: Vec<_> = errors.iter().map(|error| error.text.as_str()).collect();
+            panic!(
+                "{} Vulkan error message(s) were emitted:\n{}",
+                errors.len(),
+                list.join("\n"),
+            );
+        }
+    }
+}
+
+/// A message recorded by a [`DebugMessageRecorder`].
+#[derive(Clone, Debug)]
+pub struct RecordedMessage {
+    /// Severity of message.
+    pub severity: DebugUtilsMessageSeverity,
+    /// Type of message.
+    pub ty: DebugUtilsMessageType,
+    /// The name of the message ID, if any.
+    pub message_id_name: Option<String>,
+    /// The number of the message ID. This is zero if the message has no ID.
+    pub message_id_number: i32,
+    /// The message, formatted the same as the `Display` implementation of [`Message`].
+    pub text: String,
 }
 
 vulkan_bitflags! {
@@ -392,6 +906,138 @@ vulkan_bitflags! {
     performance = PERFORMANCE,
 }
 
+vulkan_enum! {
+    /// The type of a Vulkan object.
+    #[non_exhaustive]
+    ObjectType = ObjectType(i32);
+
+    // TODO: document
+    Unknown = UNKNOWN,
+
+    // TODO: document
+    Instance = INSTANCE,
+
+    // TODO: document
+    PhysicalDevice = PHYSICAL_DEVICE,
+
+    // TODO: document
+    Device = DEVICE,
+
+    // TODO: document
+    Queue = QUEUE,
+
+    // TODO: document
+    Semaphore = SEMAPHORE,
+
+    // TODO: document
+    CommandBuffer = COMMAND_BUFFER,
+
+    // TODO: document
+    Fence = FENCE,
+
+    // TODO: document
+    DeviceMemory = DEVICE_MEMORY,
+
+    // TODO: document
+    Buffer = BUFFER,
+
+    // TODO: document
+    Image = IMAGE,
+
+    // TODO: document
+    Event = EVENT,
+
+    // TODO: document
+    QueryPool = QUERY_POOL,
+
+    // TODO: document
+    BufferView = BUFFER_VIEW,
+
+    // TODO: document
+    ImageView = IMAGE_VIEW,
+
+    // TODO: document
+    ShaderModule = SHADER_MODULE,
+
+    // TODO: document
+    PipelineCache = PIPELINE_CACHE,
+
+    // TODO: document
+    PipelineLayout = PIPELINE_LAYOUT,
+
+    // TODO: document
+    RenderPass = RENDER_PASS,
+
+    // TODO: document
+    Pipeline = PIPELINE,
+
+    // TODO: document
+    DescriptorSetLayout = DESCRIPTOR_SET_LAYOUT,
+
+    // TODO: document
+    Sampler = SAMPLER,
+
+    // TODO: document
+    DescriptorPool = DESCRIPTOR_POOL,
+
+    // TODO: document
+    DescriptorSet = DESCRIPTOR_SET,
+
+    // TODO: document
+    Framebuffer = FRAMEBUFFER,
+
+    // TODO: document
+    CommandPool = COMMAND_POOL,
+
+    // TODO: document
+    SamplerYcbcrConversion = SAMPLER_YCBCR_CONVERSION {
+        api_version: V1_1,
+        device_extensions: [khr_sampler_ycbcr_conversion],
+    },
+
+    // TODO: document
+    DescriptorUpdateTemplate = DESCRIPTOR_UPDATE_TEMPLATE {
+        api_version: V1_1,
+        device_extensions: [khr_descriptor_update_template],
+    },
+
+    // TODO: document
+    PrivateDataSlot = PRIVATE_DATA_SLOT {
+        api_version: V1_3,
+        device_extensions: [ext_private_data],
+    },
+
+    // TODO: document
+    Surface = SURFACE_KHR {
+        instance_extensions: [khr_surface],
+    },
+
+    // TODO: document
+    Swapchain = SWAPCHAIN_KHR {
+        device_extensions: [khr_swapchain],
+    },
+
+    // TODO: document
+    Display = DISPLAY_KHR {
+        instance_extensions: [khr_display],
+    },
+
+    // TODO: document
+    DisplayMode = DISPLAY_MODE_KHR {
+        instance_extensions: [khr_display],
+    },
+
+    // TODO: document
+    DebugUtilsMessenger = DEBUG_UTILS_MESSENGER_EXT {
+        instance_extensions: [ext_debug_utils],
+    },
+
+    // TODO: document
+    AccelerationStructure = ACCELERATION_STRUCTURE_KHR {
+        device_extensions: [khr_acceleration_structure],
+    },
+}
+
 /// A label to associate with a span of work in a queue.
 ///
 /// When debugging, labels can be useful to identify which queue, or where in a specific queue,
@@ -481,4 +1127,70 @@ mod tests {
             drop(callback);
         });
     }
+
+    fn error_message(message_id_name: &str) -> Message<'_> {
+        Message {
+            severity: DebugUtilsMessageSeverity {
+                error: true,
+                ..DebugUtilsMessageSeverity::empty()
+            },
+            ty: DebugUtilsMessageType {
+                validation: true,
+                ..DebugUtilsMessageType::empty()
+            },
+            layer_prefix: Some(message_id_name),
+            message_id_number: 0x1234,
+            description: "description",
+            queue_labels: Vec::new(),
+            cmd_buf_labels: vec![MessageLabel {
+                label_name: "label",
+                color: [0.0; 4],
+            }],
+            objects: vec![MessageObject {
+                object_type: Some(ObjectType::Image),
+                object_handle: 0xab,
+                object_name: Some("albedo"),
+            }],
+        }
+    }
+
+    #[test]
+    fn message_display() {
+        assert_eq!(
+            error_message("VUID-test").to_string(),
+            "[VUID-test (0x1234)] description | objects: Image 0xab \"albedo\" \
+            | command buffer labels: \"label\"",
+        );
+    }
+
+    #[test]
+    fn recorder_ignored_message_ids() {
+        let recorder = DebugMessageRecorder::new();
+        let create_info = DebugCallback {
+            ignored_message_ids: vec!["VUID-ignored".to_owned()],
+            ..recorder.callback()
+        }
+        .to_create_info();
+
+        (create_info.user_callback)(&error_message("VUID-ignored"));
+        recorder.assert_no_errors();
+
+        (create_info.user_callback)(&error_message("VUID-reported"));
+        let errors = recorder.errors();
+        assert_eq!(errors.len(), 1);
+        assert_eq!(errors[0].message_id_name.as_deref(), Some("VUID-reported"));
+
+        recorder.clear();
+        recorder.assert_no_errors();
+    }
+
+    #[test]
+    #[should_panic]
+    fn recorder_assert_no_errors() {
+        let recorder = DebugMessageRecorder::new();
+        let create_info = recorder.callback().to_create_info();
+
+        (create_info.user_callback)(&error_message("VUID-reported"));
+        recorder.assert_no_errors();
+    }
 }
@@ -54,11 +54,11 @@
 //! Once you have chosen a physical device, you can create a `Device` object from it. See the
 //! `device` module for more info.
 
-use self::debug::{DebugUtilsMessengerCreateInfo, UserCallback};
+use self::debug::{DebugCallback, DebugUtilsMessengerCreateInfo, UserCallback};
 pub use self::{extensions::InstanceExtensions, layers::LayerProperties};
 use crate::{
-    device::physical::PhysicalDevice, instance::debug::trampoline, OomError, RequiresOneOf,
-    VulkanError, VulkanLibrary, VulkanObject,
+    device::physical::PhysicalDevice, instance::debug::trampoline, macros::vulkan_enum, OomError,
+    RequiresOneOf, VulkanError, VulkanLibrary, VulkanObject,
 };
 pub use crate::{
     extensions::{ExtensionRestriction, ExtensionRestrictionError},
@@ -239,6 +239,7 @@ pub struct Instance {
     enabled_layers: Vec<String>,
     library: Arc<VulkanLibrary>,
     max_api_version: Version,
+    debug_utils_messengers: Vec<ash::vk::DebugUtilsMessengerEXT>,
     _user_callbacks: Vec<Box<UserCallback>>,
 }
 
@@ -294,6 +295,9 @@ impl Instance {
             engine_version,
             max_api_version,
             enumerate_portability,
+            debug_callbacks,
+            enabled_validation_features,
+            disabled_validation_features,
             _ne: _,
         } = create_info;
 
@@ -321,9 +325,43 @@ impl Instance {
             flags |= ash::vk::InstanceCreateFlags::ENUMERATE_PORTABILITY_KHR;
         }
 
+        if !debug_callbacks.is_empty() {
+            enabled_extensions.ext_debug_utils = true;
+        }
+
         // Check if the extensions are correct
         enabled_extensions.check_requirements(&supported_extensions, api_version)?;
 
+        if !enabled_validation_features.is_empty() || !disabled_validation_features.is_empty() {
+            if !enabled_extensions.ext_validation_features {
+                return Err(InstanceCreationError::RequirementNotMet {
+                    required_for: "`create_info.enabled_validation_features` or \
+                        `create_info.disabled_validation_features` are not empty",
+                    requires_one_of: RequiresOneOf {
+                        instance_extensions: &["ext_validation_features"],
+                        ..Default::default()
+                    },
+                });
+            }
+
+            // VUID-VkValidationFeaturesEXT-pEnabledValidationFeatures-02967
+            if enabled_validation_features
+                .contains(&ValidationFeatureEnable::GpuAssistedReserveBindingSlot)
+                && !enabled_validation_features.contains(&ValidationFeatureEnable::GpuAssisted)
+            {
+                return Err(
+                    InstanceCreationError::ValidationFeatureReserveBindingSlotWithoutGpuAssisted,
+                );
+            }
+
+            // VUID-VkValidationFeaturesEXT-pEnabledValidationFeatures-02968
+            if enabled_validation_features.contains(&ValidationFeatureEnable::DebugPrintf)
+                && enabled_validation_features.contains(&ValidationFeatureEnable::GpuAssisted)
+            {
+                return Err(InstanceCreationError::ValidationFeatureGpuAssistedWithDebugPrintf);
+            }
+        }
+
         // FIXME: check whether each layer is supported
         let enabled_layers_cstr: Vec<CString> = enabled_layers
             .iter()
@@ -369,8 +407,12 @@ impl Instance {
             ..Default::default()
         };
 
-        // Handle debug messengers
-        let debug_utils_messengers = debug_utils_messengers.into_iter();
+        // Handle debug messengers. The debug callbacks come first, so that they can be used again
+        // to create the messengers that live as long as the instance.
+        let debug_utils_messengers = debug_callbacks
+            .iter()
+            .map(DebugCallback::to_create_info)
+            .chain(debug_utils_messengers);
         let mut debug_utils_messenger_create_infos =
             Vec::with_capacity(debug_utils_messengers.size_hint().0);
         let mut user_callbacks = Vec::with_capacity(debug_utils_messengers.size_hint().0);
@@ -432,6 +474,31 @@ impl Instance {
             create_info.p_next = info as *const _ as *const _;
         }
 
+        let enabled_validation_features_vk: SmallVec<[_; 5]> = enabled_validation_features
+            .iter()
+            .copied()
+            .map(ash::vk::ValidationFeatureEnableEXT::from)
+            .collect();
+        let disabled_validation_features_vk: SmallVec<[_; 8]> = disabled_validation_features
+            .iter()
+            .copied()
+            .map(ash::vk::ValidationFeatureDisableEXT::from)
+            .collect();
+        let mut validation_features_vk = None;
+
+        if !enabled_validation_features.is_empty() || !disabled_validation_features.is_empty() {
+            let next = validation_features_vk.insert(ash::vk::ValidationFeaturesEXT {
+                enabled_validation_feature_count: enabled_validation_features_vk.len() as u32,
+                p_enabled_validation_features: enabled_validation_features_vk.as_ptr(),
+                disabled_validation_feature_count: disabled_validation_features_vk.len() as u32,
+                p_disabled_validation_features: disabled_validation_features_vk.as_ptr(),
+                ..Default::default()
+            });
+
+            next.p_next = create_info.p_next;
+            create_info.p_next = next as *const _ as *const _;
+        }
+
         // Creating the Vulkan instance.
         let handle = {
             let mut output = MaybeUninit::uninit();
@@ -451,6 +518,39 @@ impl Instance {
             })
         };
 
+        // Creating the messengers of the debug callbacks.
+        let mut debug_utils_messengers = Vec::with_capacity(debug_callbacks.len());
+
+        for create_info in &debug_utils_messenger_create_infos[..debug_callbacks.len()] {
+            let create_info = ash::vk::DebugUtilsMessengerCreateInfoEXT {
+                p_next: ptr::null(),
+                ..*create_info
+            };
+            let mut output = MaybeUninit::uninit();
+            let result = (fns.ext_debug_utils.create_debug_utils_messenger_ext)(
+                handle,
+                &create_info,
+                ptr::null(),
+                output.as_mut_ptr(),
+            )
+            .result();
+
+            if let Err(err) = result {
+                for messenger in debug_utils_messengers {
+                    (fns.ext_debug_utils.destroy_debug_utils_messenger_ext)(
+                        handle,
+                        messenger,
+                        ptr::null(),
+                    );
+                }
+                (fns.v1_0.destroy_instance)(handle, ptr::null());
+
+                return Err(VulkanError::from(err).into());
+            }
+
+            debug_utils_messengers.push(output.assume_init());
+        }
+
         Ok(Arc::new(Instance {
             handle,
             fns,
@@ -460,6 +560,7 @@ impl Instance {
             enabled_layers,
             library,
             max_api_version,
+            debug_utils_messengers,
             _user_callbacks: user_callbacks,
         }))
     }
@@ -565,6 +666,14 @@ impl Drop for Instance {
         let fns = self.fns();
 
         unsafe {
+            for &messenger in &self.debug_utils_messengers {
+                (fns.ext_debug_utils.destroy_debug_utils_messenger_ext)(
+                    self.handle,
+                    messenger,
+                    ptr::null(),
+                );
+            }
+
             (fns.v1_0.destroy_instance)(self.handle, ptr::null());
         }
     }
@@ -604,6 +713,7 @@ impl Debug for Instance {
             enabled_layers,
             library: function_pointers,
             max_api_version,
+            debug_utils_messengers: _,
             _user_callbacks: _,
         } = self;
 
@@ -674,6 +784,28 @@ pub struct InstanceCreateInfo {
     ///   extension will automatically be enabled.
     pub enumerate_portability: bool,
 
+    /// Ready-made callbacks that are called with debug messages, from the creation to the
+    /// destruction of the instance.
+    ///
+    /// If not empty, the `ext_debug_utils` extension is enabled automatically.
+    ///
+    /// The default value is empty.
+    pub debug_callbacks: Vec<DebugCallback>,
+
+    /// Features of the validation layers to enable, in addition to the default ones.
+    ///
+    /// If not empty, the `ext_validation_features` extension must be enabled on the instance.
+    ///
+    /// The default value is empty.
+    pub enabled_validation_features: Vec<ValidationFeatureEnable>,
+
+    /// Features of the validation layers to disable.
+    ///
+    /// If not empty, the `ext_validation_features` extension must be enabled on the instance.
+    ///
+    /// The default value is empty.
+    pub disabled_validation_features: Vec<ValidationFeatureDisable>,
+
     pub _ne: crate::NonExhaustive,
 }
 
@@ -689,6 +821,9 @@ impl Default for InstanceCreateInfo {
             engine_version: Version::major_minor(0, 0),
             max_api_version: None,
             enumerate_portability: false,
+            debug_callbacks: Vec::new(),
+            enabled_validation_features: Vec::new(),
+            disabled_validation_features: Vec::new(),
             _ne: crate::NonExhaustive(()),
         }
     }
@@ -716,6 +851,68 @@ impl InstanceCreateInfo {
     }
 }
 
+vulkan_enum! {
+    /// A feature of the validation layers that can be enabled.
+    #[non_exhaustive]
+    ValidationFeatureEnable = ValidationFeatureEnableEXT(i32);
+
+    /// The validation layers instrument shaders to validate their memory accesses and descriptor
+    /// indexing on the GPU.
+    ///
+    /// This feature can't be enabled together with `DebugPrintf`.
+    GpuAssisted = GPU_ASSISTED,
+
+    /// The validation layers reserve a descriptor set binding slot for their own use with
+    /// `GpuAssisted`, and report a lower `max_bound_descriptor_sets` limit.
+    ///
+    /// This feature requires `GpuAssisted` to be enabled too.
+    GpuAssistedReserveBindingSlot = GPU_ASSISTED_RESERVE_BINDING_SLOT,
+
+    /// The validation layers warn about API usage that is valid but may perform poorly.
+    BestPractices = BEST_PRACTICES,
+
+    /// The validation layers forward the output of `debugPrintfEXT` calls in shaders to the debug
+    /// messengers, as messages with the `information` severity. See
+    /// [`Message::is_debug_printf`](crate::instance::debug::Message::is_debug_printf).
+    ///
+    /// This feature can't be enabled together with `GpuAssisted`.
+    DebugPrintf = DEBUG_PRINTF,
+
+    /// The validation layers report missing or incorrect synchronization between commands.
+    SynchronizationValidation = SYNCHRONIZATION_VALIDATION,
+}
+
+vulkan_enum! {
+    /// A feature of the validation layers that can be disabled.
+    #[non_exhaustive]
+    ValidationFeatureDisable = ValidationFeatureDisableEXT(i32);
+
+    /// All validation is disabled.
+    All = ALL,
+
+    /// Shader validation is disabled.
+    Shaders = SHADERS,
+
+    /// Thread safety validation is disabled.
+    ThreadSafety = THREAD_SAFETY,
+
+    /// Validation of the parameters of the API calls is disabled.
+    ApiParameters = API_PARAMETERS,
+
+    /// Validation of the lifetimes of objects is disabled.
+    ObjectLifetimes = OBJECT_LIFETIMES,
+
+    /// Core validation is disabled.
+    CoreChecks = CORE_CHECKS,
+
+    /// Protection against reuse of Vulkan handles is disabled.
+    UniqueHandles = UNIQUE_HANDLES,
+
+    /// The cache of validated shaders is disabled, so that shaders are validated again every time
+    /// the application runs.
+    ShaderValidationCache = SHADER_VALIDATION_CACHE,
+}
+
 /// Error that can happen when creating an instance.
 #[derive(Clone, Debug)]
 pub enum InstanceCreationError {
@@ -741,6 +938,14 @@ pub enum InstanceCreationError {
         required_for: &'static str,
         requires_one_of: RequiresOneOf,
     },
+
+    /// `ValidationFeatureEnable::GpuAssistedReserveBindingSlot` was enabled without
+    /// `ValidationFeatureEnable::GpuAssisted`.
+    ValidationFeatureReserveBindingSlotWithoutGpuAssisted,
+
+    /// `ValidationFeatureEnable::GpuAssisted` and `ValidationFeatureEnable::DebugPrintf` were
+    /// both enabled.
+    ValidationFeatureGpuAssistedWithDebugPrintf,
 }
 
 impl Error for InstanceCreationError {
@@ -769,6 +974,15 @@ impl Display for InstanceCreationError {
                 "a requirement was not met for: {}; requires one of: {}",
                 required_for, requires_one_of,
             ),
+            Self::ValidationFeatureReserveBindingSlotWithoutGpuAssisted => write!(
+                f,
+                "the `GpuAssistedReserveBindingSlot` validation feature was enabled without the \
+                `GpuAssisted` validation feature",
+            ),
+            Self::ValidationFeatureGpuAssistedWithDebugPrintf => write!(
+                f,
+                "the `GpuAssisted` and `DebugPrintf` validation features were both enabled",
+            ),
         }
     }
 }
@@ -801,9 +1015,40 @@ impl From<VulkanError> for InstanceCreationError {
 
 #[cfg(test)]
 mod tests {
+    use super::*;
 
     #[test]
     fn create_instance() {
         let _ = instance!();
     }
+
+    #[test]
+    fn validation_features_gpu_assisted_with_debug_printf() {
+        let library = match VulkanLibrary::new() {
+            Ok(x) => x,
+            Err(_) => return,
+        };
+
+        if !library.supported_extensions().ext_validation_features {
+            return;
+        }
+
+        match Instance::new(
+            library,
+            InstanceCreateInfo {
+                enabled_extensions: InstanceExtensions {
+                    ext_validation_features: true,
+                    ..InstanceExtensions::empty()
+                },
+                enabled_validation_features: vec![
+                    ValidationFeatureEnable::GpuAssisted,
+                    ValidationFeatureEnable::DebugPrintf,
+                ],
+                ..Default::default()
+            },
+        ) {
+            Err(InstanceCreationError::ValidationFeatureGpuAssistedWithDebugPrintf) => (),
+            _ => panic!(),
+        }
+    }
 }
@@ -13,6 +13,7 @@ use crate::{
     macros::{vulkan_bitflags, vulkan_enum},
     DeviceSize, OomError, RequirementNotMet, RequiresOneOf, Version, VulkanError, VulkanObject,
 };
+use ash::vk::Handle;
 use std::{
     error::Error,
     ffi::c_void,
@@ -55,6 +56,7 @@ pub struct DeviceMemory {
     allocation_size: DeviceSize,
     memory_type_index: u32,
     export_handle_types: ExternalMemoryHandleTypes,
+    name: Option<String>,
 }
 
 impl DeviceMemory {
@@ -81,17 +83,32 @@ impl DeviceMemory {
             dedicated_allocation: _,
             export_handle_types,
             import_handle_types: _,
+            flags: _,
+            name,
             _ne: _,
         } = allocate_info;
 
-        Ok(DeviceMemory {
+        let memory = DeviceMemory {
             handle,
             device,
 
             allocation_size,
             memory_type_index,
             export_handle_types,
-        })
+            name,
+        };
+
+        if let Some(name) = memory.name.as_deref() {
+            unsafe {
+                memory.device.set_debug_utils_object_name_if_enabled(
+                    ash::vk::ObjectType::DEVICE_MEMORY,
+                    memory.handle.as_raw(),
+                    Some(name),
+                )?;
+            }
+        }
+
+        Ok(memory)
     }
 
     /// Creates a new `DeviceMemory` from a raw object handle.
@@ -110,6 +127,7 @@ impl DeviceMemory {
             memory_type_index,
             dedicated_allocation: _,
             export_handle_types,
+            name,
             _ne: _,
             ..
         } = allocate_info;
@@ -121,6 +139,7 @@ impl DeviceMemory {
             allocation_size,
             memory_type_index,
             export_handle_types,
+            name,
         }
     }
 
@@ -149,17 +168,30 @@ impl DeviceMemory {
             dedicated_allocation: _,
             export_handle_types,
             import_handle_types: _,
+            flags: _,
+            name,
             _ne: _,
         } = allocate_info;
 
-        Ok(DeviceMemory {
+        let memory = DeviceMemory {
             handle,
             device,
 
             allocation_size,
             memory_type_index,
             export_handle_types,
-        })
+            name,
+        };
+
+        if let Some(name) = memory.name.as_deref() {
+            memory.device.set_debug_utils_object_name_if_enabled(
+                ash::vk::ObjectType::DEVICE_MEMORY,
+                memory.handle.as_raw(),
+                Some(name),
+            )?;
+        }
+
+        Ok(memory)
     }
 
     fn validate(
@@ -173,6 +205,8 @@ impl DeviceMemory {
             ref mut dedicated_allocation,
             export_handle_types,
             import_handle_types: _,
+            flags,
+            name: _,
             _ne: _,
         } = allocate_info;
 
@@ -274,6 +308,53 @@ impl DeviceMemory {
             // allocation to only images or buffers of that type?
         }
 
+        if !flags.is_empty() {
+            if !(device.api_version() >= Version::V1_1
+                || device.enabled_extensions().khr_device_group)
+            {
+                return Err(DeviceMemoryError::RequirementNotMet {
+                    required_for: "`allocate_info.flags` is not empty",
+                    requires_one_of: RequiresOneOf {
+                        api_version: Some(Version::V1_1),
+                        device_extensions: &["khr_device_group"],
+                        ..Default::default()
+                    },
+                });
+            }
+
+            // VUID-VkMemoryAllocateFlagsInfo-flags-parameter
+            flags.validate_device(device)?;
+
+            if flags.device_address {
+                // VUID-VkMemoryAllocateInfo-flags-03331
+                if !device.enabled_features().buffer_device_address {
+                    return Err(DeviceMemoryError::RequirementNotMet {
+                        required_for: "`allocate_info.flags.device_address` is set",
+                        requires_one_of: RequiresOneOf {
+                            features: &["buffer_device_address"],
+                            ..Default::default()
+                        },
+                    });
+                }
+            }
+
+            if flags.device_address_capture_replay {
+                // VUID-VkMemoryAllocateInfo-flags-03330
+                if !device
+                    .enabled_features()
+                    .buffer_device_address_capture_replay
+                {
+                    return Err(DeviceMemoryError::RequirementNotMet {
+                        required_for: "`allocate_info.flags.device_address_capture_replay` is set",
+                        requires_one_of: RequiresOneOf {
+                            features: &["buffer_device_address_capture_replay"],
+                            ..Default::default()
+                        },
+                    });
+                }
+            }
+        }
+
         if let Some(import_info) = import_info {
             match *import_info {
                 MemoryImportInfo::Fd {
@@ -397,6 +478,8 @@ impl DeviceMemory {
             dedicated_allocation,
             export_handle_types,
             import_handle_types: _,
+            flags,
+            name: _,
             _ne: _,
         } = allocate_info;
 
@@ -434,6 +517,19 @@ impl DeviceMemory {
             allocate_info = allocate_info.push_next(info);
         }
 
+        let mut flags_info = if !flags.is_empty() {
+            Some(ash::vk::MemoryAllocateFlagsInfo {
+                flags: flags.into(),
+                ..Default::default()
+            })
+        } else {
+            None
+        };
+
+        if let Some(info) = flags_info.as_mut() {
+            allocate_info = allocate_info.push_next(info);
+        }
+
         #[cfg(unix)]
         let mut import_fd_info = match import_info {
             Some(MemoryImportInfo::Fd { handle_type, file }) => {
@@ -521,6 +617,12 @@ impl DeviceMemory {
         self.export_handle_types
     }
 
+    /// Returns the name that the memory was allocated with, if any.
+    #[inline]
+    pub fn name(&self) -> Option<&str> {
+        self.name.as_deref()
+    }
+
     /// Retrieves the amount of lazily-allocated memory that is currently commited to this
     /// memory object.
     ///
@@ -700,6 +802,24 @@ pub struct MemoryAllocateInfo<'d> {
     /// Handle for importing
     pub import_handle_types: ExternalMemoryHandleTypes,
 
+    /// Additional flags for the allocation.
+    ///
+    /// If not empty, the device API version must be at least 1.1, or the
+    /// [`khr_device_group`](crate::device::DeviceExtensions::khr_device_group) extension must be
+    /// enabled on the device.
+    ///
+    /// The default value is [`MemoryAllocateFlags::empty()`].
+    pub flags: MemoryAllocateFlags,
+
+    /// A name for the allocation.
+    ///
+    /// If the `ext_debug_utils` extension is enabled on the instance, the name is assigned to the
+    /// allocation with [`Device::set_debug_utils_object_name`], so that debugging tools show it.
+    /// Otherwise, this doesn't require the extension.
+    ///
+    /// The default value is `None`.
+    pub name: Option<String>,
+
     pub _ne: crate::NonExhaustive,
 }
 
@@ -712,6 +832,8 @@ impl Default for MemoryAllocateInfo<'static> {
             dedicated_allocation: None,
             export_handle_types: ExternalMemoryHandleTypes::empty(),
             import_handle_types: ExternalMemoryHandleTypes::empty(),
+            flags: MemoryAllocateFlags::empty(),
+            name: None,
             _ne: crate::NonExhaustive(()),
         }
     }
@@ -727,11 +849,55 @@ impl<'d> MemoryAllocateInfo<'d> {
             dedicated_allocation: Some(dedicated_allocation),
             export_handle_types: ExternalMemoryHandleTypes::empty(),
             import_handle_types: ExternalMemoryHandleTypes::empty(),
+            flags: MemoryAllocateFlags::empty(),
+            name: None,
             _ne: crate::NonExhaustive(()),
         }
     }
 }
 
+vulkan_bitflags! {
+    /// Flags specifying additional properties of a device memory allocation.
+    #[non_exhaustive]
+    MemoryAllocateFlags = MemoryAllocateFlags(u32);
+
+    /*
+    /// The allocation is for the devices of a device group whose mask is provided.
+    device_mask = DEVICE_MASK,
+    */
+
+    /// The memory can be bound to buffers created with the
+    /// [`shader_device_address`](crate::buffer::BufferUsage::shader_device_address) usage, so that
+    /// their device address can be queried.
+    device_address = DEVICE_ADDRESS,
+
+    /// The device address of the memory can be saved and reused on a subsequent run, for example
+    /// for trace capture and replay.
+    device_address_capture_replay = DEVICE_ADDRESS_CAPTURE_REPLAY,
+}
+
+impl MemoryAllocateFlags {
+    /// Returns the flags that should be set on allocations that may back buffers with the
+    /// `shader_device_address` usage on `device`.
+    ///
+    /// With the `ext_buffer_device_address` extension, no flag is needed. Otherwise,
+    /// `device_address` is set if the `buffer_device_address` feature is enabled.
+    #[inline]
+    pub(crate) fn for_device_address(device: &Device) -> Self {
+        if device.enabled_features().buffer_device_address
+            && (device.api_version() >= Version::V1_2
+                || device.enabled_extensions().khr_buffer_device_address)
+        {
+            MemoryAllocateFlags {
+                device_address: true,
+                ..MemoryAllocateFlags::empty()
+            }
+        } else {
+            MemoryAllocateFlags::empty()
+        }
+    }
+}
+
 /// Parameters to import memory from an external source.
 #[derive(Debug)]
 #[non_exhaustive]
@@ -946,6 +1112,13 @@ impl ExternalMemoryHandleTypes {
     }
 }
 
+impl From<ExternalMemoryHandleType> for ExternalMemoryHandleTypes {
+    #[inline]
+    fn from(val: ExternalMemoryHandleType) -> Self {
+        ash::vk::ExternalMemoryHandleTypeFlags::from(val).into()
+    }
+}
+
 /// Error type returned by functions related to `DeviceMemory`.
 #[derive(Clone, Debug, PartialEq, Eq)]
 pub enum DeviceMemoryError {
@@ -981,6 +1154,9 @@ pub enum DeviceMemoryError {
         handle_type: ExternalMemoryHandleType,
     },
 
+    /// Duplicating the file descriptor to import from failed.
+    ImportFdDuplicationFailed { kind: std::io::ErrorKind },
+
     /// The provided `MemoryImportInfo::Win32::handle_type` is not supported.
     ImportWin32HandleTypeNotSupported {
         handle_type: ExternalMemoryHandleType,
@@ -1059,6 +1235,11 @@ impl Display for DeviceMemoryError {
                 descriptors",
                 handle_type,
             ),
+            Self::ImportFdDuplicationFailed { kind } => write!(
+                f,
+                "duplicating the file descriptor to import from failed: {:?}",
+                kind,
+            ),
             Self::ImportWin32HandleTypeNotSupported { handle_type } => write!(
                 f,
                 "the provided `MemoryImportInfo::Win32::handle_type` ({:?}) is not supported",
@@ -1569,7 +1750,7 @@ impl From<OomError> for MemoryMapError {
 
 #[cfg(test)]
 mod tests {
-    use super::MemoryAllocateInfo;
+    use super::{MemoryAllocateFlags, MemoryAllocateInfo};
     use crate::{
         memory::{DeviceMemory, DeviceMemoryError},
         OomError,
@@ -1589,6 +1770,31 @@ mod tests {
         .unwrap();
     }
 
+    #[test]
+    fn device_address_missing_feature() {
+        let (device, _) = gfx_dev_and_queue!();
+
+        if device.api_version() < crate::Version::V1_1 {
+            return;
+        }
+
+        match DeviceMemory::allocate(
+            device,
+            MemoryAllocateInfo {
+                allocation_size: 256,
+                memory_type_index: 0,
+                flags: MemoryAllocateFlags {
+                    device_address: true,
+                    ..MemoryAllocateFlags::empty()
+                },
+                ..Default::default()
+            },
+        ) {
+            Err(DeviceMemoryError::RequirementNotMet { .. }) => (),
+            _ => panic!(),
+        }
+    }
+
     #[test]
     fn zero_size() {
         let (device, _) = gfx_dev_and_queue!();
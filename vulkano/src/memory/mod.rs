@@ -95,9 +95,11 @@
 pub use self::{
     device_memory::{
         DeviceMemory, DeviceMemoryError, ExternalMemoryHandleType, ExternalMemoryHandleTypes,
-        MappedDeviceMemory, MemoryAllocateInfo, MemoryImportInfo, MemoryMapError,
+        MappedDeviceMemory, MemoryAllocateFlags, MemoryAllocateInfo, MemoryImportInfo,
+        MemoryMapError,
     },
     pool::MemoryPool,
+    sparse::{SparseBindFuture, SparseError, SparsePage, SparsePagePool},
 };
 use crate::{
     buffer::{sys::UnsafeBuffer, BufferAccess},
@@ -110,6 +112,7 @@ use std::sync::Arc;
 
 mod device_memory;
 pub mod pool;
+pub mod sparse;
 
 /// Properties of the memory in a physical device.
 #[derive(Clone, Debug)]
@@ -11,6 +11,7 @@ use crate::{
     device::Device,
     memory::{
         device_memory::MemoryAllocateInfo, DeviceMemory, DeviceMemoryError, MappedDeviceMemory,
+        MemoryAllocateFlags,
     },
     DeviceSize,
 };
@@ -113,6 +114,7 @@ impl StandardHostVisibleMemoryTypePool {
                 MemoryAllocateInfo {
                     allocation_size,
                     memory_type_index: self.memory_type_index,
+                    flags: MemoryAllocateFlags::for_device_address(&self.device),
                     ..Default::default()
                 },
             )?;
@@ -16,18 +16,15 @@ pub use self::{
 };
 use super::MemoryType;
 use crate::{
-    device::{Device, DeviceOwned},
+    device::{Device, DeviceOwned, MemoryFdPropertiesError},
     memory::{
         device_memory::MemoryAllocateInfo, DedicatedAllocation, DeviceMemory, DeviceMemoryError,
-        ExternalMemoryHandleTypes, MappedDeviceMemory, MemoryRequirements,
+        ExternalMemoryHandleType, ExternalMemoryHandleTypes, MappedDeviceMemory,
+        MemoryAllocateFlags, MemoryImportInfo, MemoryRequirements,
     },
-    DeviceSize,
-};
-use std::{
-    fs::File,
-    os::unix::prelude::{FromRawFd, IntoRawFd, RawFd},
-    sync::Arc,
+    DeviceSize, OomError, RequiresOneOf,
 };
+use std::{fs::File, sync::Arc};
 
 mod host_visible;
 mod non_host_visible;
@@ -37,7 +34,7 @@ mod pool;
 // the pool. This prevents the pool from overallocating a significant amount of memory.
 const MAX_POOL_ALLOC: DeviceSize = 256 * 1024 * 1024;
 
-fn choose_allocation_memory_type<F>(
+pub(crate) fn choose_allocation_memory_type<F>(
     device: &Arc<Device>,
     requirements: &MemoryRequirements,
     mut filter: F,
@@ -86,23 +83,19 @@ pub(crate) fn alloc_dedicated_with_exportable_fd<F>(
     map: MappingRequirement,
     dedicated_allocation: DedicatedAllocation<'_>,
     filter: F,
+    export_handle_types: ExternalMemoryHandleTypes,
 ) -> Result<PotentialDedicatedAllocation<StandardMemoryPoolAlloc>, DeviceMemoryError>
 where
     F: FnMut(&MemoryType) -> AllocFromRequirementsFilter,
 {
-    assert!(device.enabled_extensions().khr_external_memory_fd);
-    assert!(device.enabled_extensions().khr_external_memory);
-
     let memory_type_index = choose_allocation_memory_type(&device, requirements, filter, map);
     let memory = DeviceMemory::allocate(
         device,
         MemoryAllocateInfo {
             allocation_size: requirements.size,
             memory_type_index,
-            export_handle_types: ExternalMemoryHandleTypes {
-                opaque_fd: true,
-                ..ExternalMemoryHandleTypes::empty()
-            },
+            export_handle_types,
+            flags: MemoryAllocateFlags::for_device_address(&device),
             ..MemoryAllocateInfo::dedicated_allocation(dedicated_allocation)
         },
     )?;
@@ -116,52 +109,78 @@ where
     }
 }
 
-/// Import memory from a Vec of file descriptors.
-pub(crate) fn alloc_import_from_fd<F>(
+/// Import dedicated memory from a file descriptor.
+///
+/// If `handle_type` is not `OpaqueFd`, the memory type is chosen among those that the file
+/// descriptor can be imported as, according to `Device::memory_fd_properties`.
+///
+/// # Safety
+///
+/// - `file` must satisfy the safety requirements of `MemoryImportInfo::Fd`.
+pub(crate) unsafe fn alloc_import_from_fd<F>(
     device: Arc<Device>,
     requirements: &MemoryRequirements,
     _layout: AllocLayout,
     map: MappingRequirement,
     dedicated_allocation: DedicatedAllocation<'_>,
     filter: F,
-    fd: Vec<RawFd>,
+    handle_type: ExternalMemoryHandleType,
+    file: File,
 ) -> Result<PotentialDedicatedAllocation<StandardMemoryPoolAlloc>, DeviceMemoryError>
 where
     F: FnMut(&MemoryType) -> AllocFromRequirementsFilter,
 {
-    assert!(device.enabled_extensions().khr_external_memory_fd);
-    assert!(device.enabled_extensions().khr_external_memory);
-    assert!(device.enabled_extensions().ext_external_memory_dma_buf);
+    let mut requirements = *requirements;
 
-    let memory_type_index = choose_allocation_memory_type(&device, requirements, filter, map);
+    // VUID-vkGetMemoryFdPropertiesKHR-handleType-00674
+    if handle_type != ExternalMemoryHandleType::OpaqueFd {
+        let file_clone = file
+            .try_clone()
+            .map_err(|err| DeviceMemoryError::ImportFdDuplicationFailed { kind: err.kind() })?;
 
-    let memory = unsafe {
-        // Try cloning underlying fd
-        // @TODO: For completeness, importing memory from muliple file descriptors should be added (In order to support importing multiplanar images). As of now, only single planar image importing will work.
-        let file = File::from_raw_fd(*fd.get(0).expect("File descriptor Vec is empty"));
-        let new_file = file.try_clone().expect("Error cloning file descriptor");
+        match device.memory_fd_properties(handle_type, file_clone) {
+            Ok(properties) => requirements.memory_type_bits &= properties.memory_type_bits,
+            Err(MemoryFdPropertiesError::OutOfHostMemory) => {
+                return Err(OomError::OutOfHostMemory.into())
+            }
+            Err(MemoryFdPropertiesError::RequirementNotMet {
+                required_for,
+                requires_one_of,
+            }) => {
+                return Err(DeviceMemoryError::RequirementNotMet {
+                    required_for,
+                    requires_one_of,
+                })
+            }
+            Err(MemoryFdPropertiesError::NotSupported) => {
+                return Err(DeviceMemoryError::RequirementNotMet {
+                    required_for: "importing memory from a file descriptor",
+                    requires_one_of: RequiresOneOf {
+                        device_extensions: &["khr_external_memory_fd"],
+                        ..Default::default()
+                    },
+                })
+            }
+            Err(MemoryFdPropertiesError::InvalidExternalHandle)
+            | Err(MemoryFdPropertiesError::InvalidExternalHandleType) => {
+                return Err(DeviceMemoryError::ImportFdHandleTypeNotSupported { handle_type })
+            }
+        }
+    }
 
-        // Turn the original file descriptor back into a raw fd to avoid ownership problems
-        file.into_raw_fd();
+    let memory_type_index = choose_allocation_memory_type(&device, &requirements, filter, map);
 
-        DeviceMemory::import(
-            device.clone(),
-            MemoryAllocateInfo {
-                allocation_size: requirements.size,
-                memory_type_index,
-                export_handle_types: ExternalMemoryHandleTypes::empty(),
-                import_handle_types: ExternalMemoryHandleTypes {
-                    dma_buf: true,
-                    ..ExternalMemoryHandleTypes::empty()
-                },
-                ..MemoryAllocateInfo::dedicated_allocation(dedicated_allocation)
-            },
-            crate::memory::MemoryImportInfo::Fd {
-                handle_type: crate::memory::ExternalMemoryHandleType::DmaBuf,
-                file: new_file,
-            },
-        )
-    }?;
+    let memory = DeviceMemory::import(
+        device,
+        MemoryAllocateInfo {
+            allocation_size: requirements.size,
+            memory_type_index,
+            import_handle_types: handle_type.into(),
+            flags: MemoryAllocateFlags::for_device_address(&device),
+            ..MemoryAllocateInfo::dedicated_allocation(dedicated_allocation)
+        },
+        MemoryImportInfo::Fd { handle_type, file },
+    )?;
 
     match map {
         MappingRequirement::Map => {
@@ -280,6 +299,7 @@ pub unsafe trait MemoryPool: DeviceOwned {
                 allocation_size: requirements.size,
                 memory_type_index,
                 dedicated_allocation,
+                flags: MemoryAllocateFlags::for_device_address(self.device()),
                 ..Default::default()
             },
         )?;
@@ -9,7 +9,9 @@
 
 use crate::{
     device::Device,
-    memory::{device_memory::MemoryAllocateInfo, DeviceMemory, DeviceMemoryError},
+    memory::{
+        device_memory::MemoryAllocateInfo, DeviceMemory, DeviceMemoryError, MemoryAllocateFlags,
+    },
     DeviceSize,
 };
 use parking_lot::Mutex;
@@ -108,6 +110,7 @@ impl StandardNonHostVisibleMemoryTypePool {
                 MemoryAllocateInfo {
                     allocation_size,
                     memory_type_index: self.memory_type_index,
+                    flags: MemoryAllocateFlags::for_device_address(&self.device),
                     ..Default::default()
                 },
             )?;
@@ -0,0 +1,731 @@
+// Copyright (c) 2022 The vulkano developers
+// Licensed under the Apache License, Version 2.0
+// <LICENSE-APACHE or
+// https://www.apache.org/licenses/LICENSE-2.0> or the MIT
+// license <LICENSE-MIT or https://opensource.org/licenses/MIT>,
+// at your option. All files in the project carrying such
+// notice may not be copied, modified, or distributed except
+// according to those terms.
+
+//! Memory management for sparse resources.
+//!
+//! Sparse buffers and images are not backed by a single allocation. Instead, their memory is
+//! divided into pages, and each page can individually be bound to a region of device memory, or
+//! be left unbound, using sparse bind operations executed on a queue.
+//!
+//! This module contains the [`SparsePagePool`], which hands out pages of device memory that can
+//! be bound to sparse resources, and the [`SparseBindFuture`], which represents the execution of
+//! a sparse bind operation on a queue. The high-level sparse resource types are
+//! [`SparseBuffer`](crate::buffer::SparseBuffer) and [`SparseImage`](crate::image::SparseImage).
+
+use super::{
+    pool::{choose_allocation_memory_type, AllocFromRequirementsFilter, MappingRequirement},
+    BindSparseInfo, DeviceMemory, DeviceMemoryError, MemoryAllocateFlags, MemoryAllocateInfo,
+    MemoryRequirements,
+};
+use crate::{
+    buffer::{sys::UnsafeBuffer, BufferCreationError},
+    command_buffer::{SemaphoreSubmitInfo, SubmitInfo},
+    device::{Device, DeviceOwned, Queue},
+    image::{sys::UnsafeImage, ImageCreationError, ImageLayout},
+    sync::{
+        AccessCheckError, AccessError, AccessFlags, FlushError, GpuFuture, PipelineStages,
+        Semaphore, SemaphoreError, SubmitAnyBuilder,
+    },
+    DeviceSize, OomError, RequirementNotMet, RequiresOneOf, VulkanObject,
+};
+use parking_lot::Mutex;
+use smallvec::smallvec;
+use std::{
+    error::Error,
+    fmt::{Display, Error as FmtError, Formatter},
+    ops::Range,
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc,
+    },
+};
+
+// Size in bytes of the blocks of device memory that pages are taken from, if the page size is
+// smaller than this.
+const BLOCK_SIZE: DeviceSize = 16 * 1024 * 1024;
+
+/// A pool of fixed-size pages of device memory, that can be bound to sparse resources.
+///
+/// Device memory is allocated in blocks that hold multiple pages each. Pages that are dropped are
+/// returned to the pool and reused before a new block is allocated. Blocks are only freed when
+/// the pool and all of its pages have been dropped.
+///
+/// A single pool can be shared between multiple sparse resources, as long as their memory
+/// requirements are compatible with the pool.
+#[derive(Debug)]
+pub struct SparsePagePool {
+    device: Arc<Device>,
+    page_size: DeviceSize,
+    pages_per_block: DeviceSize,
+    memory_type_index: u32,
+    state: Mutex<SparsePagePoolState>,
+}
+
+#[derive(Debug, Default)]
+struct SparsePagePoolState {
+    blocks: Vec<Arc<DeviceMemory>>,
+    // Block index and page index within the block, of each page that is currently free.
+    free_pages: Vec<(usize, DeviceSize)>,
+}
+
+impl SparsePagePool {
+    /// Creates a new `SparsePagePool` for pages that satisfy `requirements`.
+    ///
+    /// The page size is equal to `requirements.alignment`, which for sparse resources is the size
+    /// of a sparse block in bytes. Device-local memory types are preferred.
+    ///
+    /// # Panics
+    ///
+    /// - Panics if `requirements.alignment` is `0`.
+    /// - Panics if none of the memory types in `requirements.memory_type_bits` can be used.
+    pub fn new(device: Arc<Device>, requirements: &MemoryRequirements) -> Arc<Self> {
+        assert!(requirements.alignment != 0);
+
+        let memory_type_index = choose_allocation_memory_type(
+            &device,
+            requirements,
+            |t| {
+                if t.property_flags.device_local {
+                    AllocFromRequirementsFilter::Preferred
+                } else {
+                    AllocFromRequirementsFilter::Allowed
+                }
+            },
+            MappingRequirement::DoNotMap,
+        );
+
+        Arc::new(SparsePagePool {
+            device,
+            page_size: requirements.alignment,
+            pages_per_block: (BLOCK_SIZE / requirements.alignment).max(1),
+            memory_type_index,
+            state: Mutex::new(Default::default()),
+        })
+    }
+
+    /// Returns the size in bytes of each page.
+    #[inline]
+    pub fn page_size(&self) -> DeviceSize {
+        self.page_size
+    }
+
+    /// Returns the index of the memory type that pages are allocated from.
+    #[inline]
+    pub fn memory_type_index(&self) -> u32 {
+        self.memory_type_index
+    }
+
+    /// Returns whether pages from this pool can be bound to a resource with the given memory
+    /// requirements.
+    #[inline]
+    pub fn is_compatible(&self, requirements: &MemoryRequirements) -> bool {
+        self.page_size == requirements.alignment
+            && requirements.memory_type_bits & (1 << self.memory_type_index) != 0
+    }
+
+    /// Takes a page from the pool, allocating a new block of device memory if no page is free.
+    pub fn allocate(self: &Arc<Self>) -> Result<SparsePage, DeviceMemoryError> {
+        let mut state = self.state.lock();
+
+        if state.free_pages.is_empty() {
+            let memory = DeviceMemory::allocate(
+                self.device.clone(),
+                MemoryAllocateInfo {
+                    allocation_size: self.page_size * self.pages_per_block,
+                    memory_type_index: self.memory_type_index,
+                    flags: MemoryAllocateFlags::for_device_address(&self.device),
+                    ..Default::default()
+                },
+            )?;
+            let block = state.blocks.len();
+            state.blocks.push(Arc::new(memory));
+
+            // Pushed in reverse, so that pages are handed out in increasing order.
+            state
+                .free_pages
+                .extend((0..self.pages_per_block).rev().map(|index| (block, index)));
+        }
+
+        let (block, index) = state.free_pages.pop().unwrap();
+
+        Ok(SparsePage {
+            pool: self.clone(),
+            memory: state.blocks[block].clone(),
+            block,
+            index,
+        })
+    }
+}
+
+unsafe impl DeviceOwned for SparsePagePool {
+    #[inline]
+    fn device(&self) -> &Arc<Device> {
+        &self.device
+    }
+}
+
+/// A page of device memory taken from a [`SparsePagePool`].
+///
+/// The page is returned to the pool when it is dropped.
+#[derive(Debug)]
+pub struct SparsePage {
+    pool: Arc<SparsePagePool>,
+    memory: Arc<DeviceMemory>,
+    block: usize,
+    index: DeviceSize,
+}
+
+impl SparsePage {
+    /// Returns the device memory that the page is part of.
+    #[inline]
+    pub fn memory(&self) -> &Arc<DeviceMemory> {
+        &self.memory
+    }
+
+    /// Returns the offset in bytes of the page within `memory`.
+    #[inline]
+    pub fn offset(&self) -> DeviceSize {
+        self.index * self.pool.page_size
+    }
+
+    /// Returns the size in bytes of the page.
+    #[inline]
+    pub fn size(&self) -> DeviceSize {
+        self.pool.page_size
+    }
+
+    /// Returns the pool that the page was taken from.
+    #[inline]
+    pub fn pool(&self) -> &Arc<SparsePagePool> {
+        &self.pool
+    }
+
+    #[inline]
+    pub(crate) fn memory_bind(&self) -> Option<(Arc<DeviceMemory>, DeviceSize)> {
+        Some((self.memory.clone(), self.offset()))
+    }
+}
+
+impl Drop for SparsePage {
+    #[inline]
+    fn drop(&mut self) {
+        self.pool
+            .state
+            .lock()
+            .free_pages
+            .push((self.block, self.index));
+    }
+}
+
+// Applies a bind operation to the residency state of a sparse resource, once the operation has
+// been submitted. Returns the pages that are no longer bound to the resource.
+type ResidencyUpdate = Box<dyn FnOnce() -> Vec<SparsePage> + Send>;
+
+/// Represents a sparse bind operation being executed on a queue after a previous event.
+///
+/// The residency state of the resource is only updated once the operation has been successfully
+/// submitted to the queue. Pages that were unbound by the operation are kept alive until the
+/// operation has finished executing, and are then returned to their pool.
+///
+/// Unlike other futures, the bind operation is submitted as soon as another future is chained
+/// after it, so that it knows whether the submission succeeded.
+#[must_use = "Dropping this object will immediately block the thread until the GPU has finished \
+              processing the submission"]
+pub struct SparseBindFuture<F>
+where
+    F: GpuFuture,
+{
+    previous: F,
+    queue: Arc<Queue>,
+    bind_info: BindSparseInfo,
+    // Signaled by a previous submission that must finish before the bind operation starts.
+    semaphore: Arc<Semaphore>,
+    // Signaled by the bind operation, for a following submission to wait on.
+    signal_semaphore: Arc<Semaphore>,
+    residency_update: Mutex<Option<ResidencyUpdate>>,
+    released_pages: Mutex<Vec<SparsePage>>,
+    // True if the bind operation has already been submitted.
+    // If flush is called multiple times, we want to block so that only one flushing is executed.
+    // Therefore we use a `Mutex<bool>` and not an `AtomicBool`.
+    submitted: Mutex<bool>,
+    finished: AtomicBool,
+}
+
+impl<F> SparseBindFuture<F>
+where
+    F: GpuFuture,
+{
+    /// Checks that `queue` can execute sparse bind operations after `previous`, and builds a new
+    /// future for `bind_info`.
+    pub(crate) fn new(
+        previous: F,
+        queue: Arc<Queue>,
+        mut bind_info: BindSparseInfo,
+    ) -> Result<Self, SparseError> {
+        assert_eq!(
+            queue.device().internal_object(),
+            previous.device().internal_object()
+        );
+
+        if !previous.queue_change_allowed() {
+            assert!(previous.queue().unwrap() == queue);
+        }
+
+        let queue_family_index = queue.queue_family_index();
+
+        if !queue.device().physical_device().queue_family_properties()[queue_family_index as usize]
+            .queue_flags
+            .sparse_binding
+        {
+            return Err(SparseError::QueueFamilyNotSupported { queue_family_index });
+        }
+
+        // Resources with nothing to (un)bind can't be passed to Vulkan.
+        bind_info
+            .buffer_binds
+            .retain(|(_, memory_binds)| !memory_binds.is_empty());
+        bind_info
+            .image_opaque_binds
+            .retain(|(_, memory_binds)| !memory_binds.is_empty());
+        bind_info
+            .image_binds
+            .retain(|(_, memory_binds)| !memory_binds.is_empty());
+
+        Ok(SparseBindFuture {
+            previous,
+            semaphore: Arc::new(Semaphore::from_pool(queue.device().clone())?),
+            signal_semaphore: Arc::new(Semaphore::from_pool(queue.device().clone())?),
+            queue,
+            bind_info,
+            residency_update: Mutex::new(None),
+            released_pages: Mutex::new(Vec::new()),
+            submitted: Mutex::new(false),
+            finished: AtomicBool::new(false),
+        })
+    }
+
+    /// Sets the function that updates the residency state of the resource once the bind operation
+    /// has been submitted. The pages that it returns are kept alive until the bind operation has
+    /// finished executing.
+    pub(crate) fn with_residency_update(
+        mut self,
+        update: impl FnOnce() -> Vec<SparsePage> + Send + 'static,
+    ) -> Self {
+        *self.residency_update.get_mut() = Some(Box::new(update));
+        self
+    }
+
+    // Submits the bind operation, and updates the residency state if that succeeded. Doesn't check
+    // whether the future was already flushed. You must make sure to not submit the same bind
+    // operation multiple times.
+    unsafe fn submit(&self, signal_semaphore: bool) -> Result<(), FlushError> {
+        match self.build_submission_impl()? {
+            SubmitAnyBuilder::BindSparse(mut bind_infos, fence) => {
+                if signal_semaphore {
+                    bind_infos
+                        .last_mut()
+                        .unwrap()
+                        .signal_semaphores
+                        .push(self.signal_semaphore.clone());
+                }
+
+                self.queue
+                    .with(|mut q| q.bind_sparse_unchecked(bind_infos, fence))?;
+            }
+            _ => unreachable!(),
+        };
+
+        if let Some(update) = self.residency_update.lock().take() {
+            self.released_pages.lock().extend(update());
+        }
+
+        Ok(())
+    }
+
+    // Returns the bind operation, after the previous submission. Doesn't check whether the future
+    // was already flushed.
+    unsafe fn build_submission_impl(&self) -> Result<SubmitAnyBuilder, FlushError> {
+        Ok(match self.previous.build_submission()? {
+            SubmitAnyBuilder::Empty => {
+                SubmitAnyBuilder::BindSparse(smallvec![self.bind_info.clone()], None)
+            }
+            SubmitAnyBuilder::SemaphoresWait(semaphores) => SubmitAnyBuilder::BindSparse(
+                smallvec![BindSparseInfo {
+                    wait_semaphores: semaphores.into_iter().collect(),
+                    ..self.bind_info.clone()
+                }],
+                None,
+            ),
+            // Batches in a single submission are not ordered relative to each other, so the
+            // previous submission is submitted first, and signals a semaphore that the bind
+            // operation waits on.
+            SubmitAnyBuilder::CommandBuffer(mut submit_info, fence) => {
+                submit_info
+                    .signal_semaphores
+                    .push(SemaphoreSubmitInfo::semaphore(self.semaphore.clone()));
+                self.previous
+                    .queue()
+                    .unwrap()
+                    .with(|mut q| q.submit_unchecked([submit_info], fence))?;
+
+                self.wait_on_semaphore()
+            }
+            SubmitAnyBuilder::BindSparse(mut bind_infos, fence) => {
+                bind_infos
+                    .last_mut()
+                    .unwrap()
+                    .signal_semaphores
+                    .push(self.semaphore.clone());
+                self.previous
+                    .queue()
+                    .unwrap()
+                    .with(|mut q| q.bind_sparse_unchecked(bind_infos, fence))?;
+
+                self.wait_on_semaphore()
+            }
+            // A present operation can't signal a semaphore, so it is flushed first, followed by an
+            // empty submission that signals the semaphore that the bind operation waits on.
+            SubmitAnyBuilder::QueuePresent(_) => {
+                self.previous.flush()?;
+                self.previous.queue().unwrap().with(|mut q| {
+                    q.submit_unchecked(
+                        [SubmitInfo {
+                            signal_semaphores: vec![SemaphoreSubmitInfo::semaphore(
+                                self.semaphore.clone(),
+                            )],
+                            ..Default::default()
+                        }],
+                        None,
+                    )
+                })?;
+
+                self.wait_on_semaphore()
+            }
+        })
+    }
+
+    fn wait_on_semaphore(&self) -> SubmitAnyBuilder {
+        SubmitAnyBuilder::BindSparse(
+            smallvec![BindSparseInfo {
+                wait_semaphores: vec![self.semaphore.clone()],
+                ..self.bind_info.clone()
+            }],
+            None,
+        )
+    }
+}
+
+unsafe impl<F> GpuFuture for SparseBindFuture<F>
+where
+    F: GpuFuture,
+{
+    fn cleanup_finished(&mut self) {
+        self.previous.cleanup_finished();
+    }
+
+    unsafe fn build_submission(&self) -> Result<SubmitAnyBuilder, FlushError> {
+        let mut submitted = self.submitted.lock();
+        if *submitted {
+            return Ok(SubmitAnyBuilder::Empty);
+        }
+
+        // If the bind operation was passed on to be submitted by the next future, we couldn't
+        // tell whether the submission succeeded. So submit it now, and let the next submission
+        // wait on it instead.
+        self.submit(true)?;
+        *submitted = true;
+
+        Ok(SubmitAnyBuilder::SemaphoresWait(smallvec![self
+            .signal_semaphore
+            .clone()]))
+    }
+
+    fn flush(&self) -> Result<(), FlushError> {
+        unsafe {
+            let mut submitted = self.submitted.lock();
+            if *submitted {
+                return Ok(());
+            }
+
+            self.submit(false)?;
+
+            // Only write `true` here in order to try again next time if we failed to submit.
+            *submitted = true;
+            Ok(())
+        }
+    }
+
+    unsafe fn signal_finished(&self) {
+        self.finished.store(true, Ordering::SeqCst);
+        self.released_pages.lock().clear();
+        self.previous.signal_finished();
+    }
+
+    fn queue_change_allowed(&self) -> bool {
+        false
+    }
+
+    fn queue(&self) -> Option<Arc<Queue>> {
+        Some(self.queue.clone())
+    }
+
+    fn check_buffer_access(
+        &self,
+        buffer: &UnsafeBuffer,
+        range: Range<DeviceSize>,
+        exclusive: bool,
+        queue: &Queue,
+    ) -> Result<Option<(PipelineStages, AccessFlags)>, AccessCheckError> {
+        self.previous
+            .check_buffer_access(buffer, range, exclusive, queue)
+    }
+
+    fn check_image_access(
+        &self,
+        image: &UnsafeImage,
+        range: Range<DeviceSize>,
+        exclusive: bool,
+        expected_layout: ImageLayout,
+        queue: &Queue,
+    ) -> Result<Option<(PipelineStages, AccessFlags)>, AccessCheckError> {
+        self.previous
+            .check_image_access(image, range, exclusive, expected_layout, queue)
+    }
+
+    #[inline]
+    fn check_swapchain_image_acquired(
+        &self,
+        image: &UnsafeImage,
+        _before: bool,
+    ) -> Result<(), AccessCheckError> {
+        self.previous.check_swapchain_image_acquired(image, false)
+    }
+}
+
+unsafe impl<F> DeviceOwned for SparseBindFuture<F>
+where
+    F: GpuFuture,
+{
+    fn device(&self) -> &Arc<Device> {
+        self.queue.device()
+    }
+}
+
+impl<F> Drop for SparseBindFuture<F>
+where
+    F: GpuFuture,
+{
+    fn drop(&mut self) {
+        unsafe {
+            if !*self.finished.get_mut() {
+                // TODO: handle errors?
+                self.flush().unwrap();
+                // Block until the queue finished.
+                self.queue.with(|mut q| q.wait_idle()).unwrap();
+                self.previous.signal_finished();
+            }
+        }
+    }
+}
+
+// Checks that the GPU isn't using `range` of `buffer`, other than in `future`, which the bind
+// operation waits for.
+pub(crate) fn check_buffer_unused(
+    future: &dyn GpuFuture,
+    buffer: &UnsafeBuffer,
+    range: Range<DeviceSize>,
+    queue: &Queue,
+) -> Result<(), SparseError> {
+    match future.check_buffer_access(buffer, range.clone(), true, queue) {
+        Ok(_) => Ok(()),
+        Err(AccessCheckError::Denied(err)) => Err(err.into()),
+        Err(AccessCheckError::Unknown) => Ok(buffer.state().check_gpu_write(range)?),
+    }
+}
+
+// Checks that the GPU isn't using `image`, other than in `future`, which the bind operation waits
+// for.
+pub(crate) fn check_image_unused(
+    future: &dyn GpuFuture,
+    image: &UnsafeImage,
+    queue: &Queue,
+) -> Result<(), SparseError> {
+    let range = 0..image.range_size();
+
+    match future.check_image_access(image, range.clone(), true, ImageLayout::Undefined, queue) {
+        Ok(_) => Ok(()),
+        Err(AccessCheckError::Denied(err)) => Err(err.into()),
+        Err(AccessCheckError::Unknown) => Ok(image
+            .state()
+            .check_gpu_write(range, ImageLayout::Undefined)?),
+    }
+}
+
+/// Error that can happen when creating a sparse resource or binding memory to it.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum SparseError {
+    /// Allocating memory failed.
+    AllocError(DeviceMemoryError),
+
+    /// The memory to unbind may still be in use by the GPU.
+    AccessError(AccessError),
+
+    RequirementNotMet {
+        required_for: &'static str,
+        requires_one_of: RequiresOneOf,
+    },
+
+    /// Creating the sparse buffer failed.
+    BufferCreationError(BufferCreationError),
+
+    /// Creating the sparse image failed.
+    ImageCreationError(ImageCreationError),
+
+    /// The requested aspects do not match the aspects of any of the sparse memory requirements
+    /// of the image.
+    AspectsNotSupported,
+
+    /// The requested region of a sparse image is outside the image, or lies in the mip tail.
+    ///
+    /// The mip tail must be bound with `bind_mip_tail` instead.
+    BlockOutOfRange,
+
+    /// The format and usage of the image do not support partial residency on this device.
+    FormatNotSupported,
+
+    /// The requested pages are outside the resource.
+    PageOutOfRange {
+        pages_end: DeviceSize,
+        num_pages: DeviceSize,
+    },
+
+    /// The page pool does not provide memory that matches the memory requirements of the
+    /// resource.
+    PagePoolIncompatible,
+
+    /// The queue family of the queue does not support sparse binding.
+    QueueFamilyNotSupported { queue_family_index: u32 },
+
+    /// Creating a semaphore for the bind operation failed.
+    SemaphoreError(SemaphoreError),
+}
+
+impl Error for SparseError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            Self::AllocError(err) => Some(err),
+            Self::AccessError(err) => Some(err),
+            Self::BufferCreationError(err) => Some(err),
+            Self::ImageCreationError(err) => Some(err),
+            Self::SemaphoreError(err) => Some(err),
+            _ => None,
+        }
+    }
+}
+
+impl Display for SparseError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result<(), FmtError> {
+        match self {
+            Self::AllocError(_) => write!(f, "allocating memory failed"),
+            Self::AccessError(_) => {
+                write!(f, "the memory to unbind may still be in use by the GPU")
+            }
+            Self::RequirementNotMet {
+                required_for,
+                requires_one_of,
+            } => write!(
+                f,
+                "a requirement was not met for: {}; requires one of: {}",
+                required_for, requires_one_of,
+            ),
+            Self::BufferCreationError(_) => write!(f, "creating the sparse buffer failed"),
+            Self::ImageCreationError(_) => write!(f, "creating the sparse image failed"),
+            Self::AspectsNotSupported => write!(
+                f,
+                "the requested aspects do not match the aspects of any of the sparse memory \
+                requirements of the image",
+            ),
+            Self::BlockOutOfRange => write!(
+                f,
+                "the requested region of a sparse image is outside the image, or lies in the mip \
+                tail",
+            ),
+            Self::FormatNotSupported => write!(
+                f,
+                "the format and usage of the image do not support partial residency on this device",
+            ),
+            Self::PageOutOfRange {
+                pages_end,
+                num_pages,
+            } => write!(
+                f,
+                "the requested pages end at {}, but the resource only has {} pages",
+                pages_end, num_pages,
+            ),
+            Self::PagePoolIncompatible => write!(
+                f,
+                "the page pool does not provide memory that matches the memory requirements of \
+                the resource",
+            ),
+            Self::QueueFamilyNotSupported { queue_family_index } => write!(
+                f,
+                "the queue family {} does not support sparse binding",
+                queue_family_index,
+            ),
+            Self::SemaphoreError(_) => {
+                write!(f, "creating a semaphore for the bind operation failed")
+            }
+        }
+    }
+}
+
+impl From<DeviceMemoryError> for SparseError {
+    fn from(err: DeviceMemoryError) -> Self {
+        Self::AllocError(err)
+    }
+}
+
+impl From<AccessError> for SparseError {
+    fn from(err: AccessError) -> Self {
+        Self::AccessError(err)
+    }
+}
+
+impl From<OomError> for SparseError {
+    fn from(err: OomError) -> Self {
+        Self::AllocError(DeviceMemoryError::OomError(err))
+    }
+}
+
+impl From<RequirementNotMet> for SparseError {
+    fn from(err: RequirementNotMet) -> Self {
+        Self::RequirementNotMet {
+            required_for: err.required_for,
+            requires_one_of: err.requires_one_of,
+        }
+    }
+}
+
+impl From<BufferCreationError> for SparseError {
+    fn from(err: BufferCreationError) -> Self {
+        Self::BufferCreationError(err)
+    }
+}
+
+impl From<ImageCreationError> for SparseError {
+    fn from(err: ImageCreationError) -> Self {
+        Self::ImageCreationError(err)
+    }
+}
+
+impl From<SemaphoreError> for SparseError {
+    fn from(err: SemaphoreError) -> Self {
+        Self::SemaphoreError(err)
+    }
+}
@@ -37,6 +37,8 @@ use crate::{
     DeviceSize, OomError, VulkanError, VulkanObject,
 };
 use ahash::HashMap;
+use ash::vk::Handle;
+use parking_lot::Mutex;
 use std::{
     error::Error,
     fmt::{Debug, Display, Error as FmtError, Formatter},
@@ -60,6 +62,7 @@ pub struct ComputePipeline {
     layout: Arc<PipelineLayout>,
     descriptor_requirements: HashMap<(u32, u32), DescriptorRequirements>,
     num_used_descriptor_sets: u32,
+    name: Mutex<Option<String>>,
 }
 
 impl ComputePipeline {
@@ -235,6 +238,7 @@ impl ComputePipeline {
             layout,
             descriptor_requirements,
             num_used_descriptor_sets,
+            name: Mutex::new(None),
         }))
     }
 
@@ -253,6 +257,30 @@ impl ComputePipeline {
             .iter()
             .map(|(loc, reqs)| (*loc, reqs))
     }
+
+    /// Returns the name of the pipeline, if it has one.
+    #[inline]
+    pub fn name(&self) -> Option<String> {
+        self.name.lock().clone()
+    }
+
+    /// Sets or removes the name of the pipeline.
+    ///
+    /// If the `ext_debug_utils` extension is enabled on the instance, the name is assigned to the
+    /// pipeline with [`Device::set_debug_utils_object_name`], so that debugging tools show it.
+    pub fn set_name(&self, name: Option<&str>) -> Result<(), OomError> {
+        unsafe {
+            self.device.set_debug_utils_object_name_if_enabled(
+                ash::vk::ObjectType::PIPELINE,
+                self.handle.as_raw(),
+                name,
+            )?;
+        }
+
+        *self.name.lock() = name.map(ToOwned::to_owned);
+
+        Ok(())
+    }
 }
 
 impl Pipeline for ComputePipeline {
@@ -17,6 +17,10 @@ use super::{
     },
     depth_stencil::{DepthStencilState, StencilOps},
     discard_rectangle::DiscardRectangleState,
+    fragment_shading_rate::{
+        is_valid_fragment_size, FragmentShadingRate, FragmentShadingRateCombinerOp,
+        FragmentShadingRateState,
+    },
     input_assembly::{InputAssemblyState, PrimitiveTopology, PrimitiveTopologyClass},
     multisample::MultisampleState,
     rasterization::{
@@ -52,6 +56,7 @@ use crate::{
     DeviceSize, RequiresOneOf, Version, VulkanError, VulkanObject,
 };
 use ahash::HashMap;
+use ash::vk::Handle;
 use smallvec::SmallVec;
 use std::{
     collections::hash_map::Entry,
@@ -65,6 +70,7 @@ use std::{
 pub struct GraphicsPipelineBuilder<'vs, 'tcs, 'tes, 'gs, 'fs, Vdef, Vss, Tcss, Tess, Gss, Fss> {
     render_pass: Option<PipelineRenderPassType>,
     cache: Option<Arc<PipelineCache>>,
+    name: Option<String>,
 
     vertex_shader: Option<(EntryPoint<'vs>, Vss)>,
     tessellation_shaders: Option<TessellationShaders<'tcs, 'tes, Tcss, Tess>>,
@@ -80,6 +86,7 @@ pub struct GraphicsPipelineBuilder<'vs, 'tcs, 'tes, 'gs, 'fs, Vdef, Vss, Tcss, T
     multisample_state: MultisampleState,
     depth_stencil_state: DepthStencilState,
     color_blend_state: ColorBlendState,
+    fragment_shading_rate_state: FragmentShadingRateState,
 }
 
 // Additional parameters if tessellation is used.
@@ -109,6 +116,7 @@ impl
         GraphicsPipelineBuilder {
             render_pass: None,
             cache: None,
+            name: None,
 
             vertex_shader: None,
             tessellation_shaders: None,
@@ -124,6 +132,7 @@ impl
             multisample_state: Default::default(),
             depth_stencil_state: Default::default(),
             color_blend_state: Default::default(),
+            fragment_shading_rate_state: Default::default(),
         }
     }
 }
@@ -313,6 +322,7 @@ where
                 multisample_state: _,
                 depth_stencil_state: _,
                 color_blend_state: _,
+                fragment_shading_rate_state: _,
             } = &self;
 
             let render_pass = render_pass.as_ref().expect("Missing render pass");
@@ -366,9 +376,21 @@ where
         let (handle, descriptor_requirements, dynamic_state, shaders) =
             unsafe { self.record_create(&device, &pipeline_layout, &vertex_input_state, has)? };
 
+        // The last vertex processing stage is the one whose outputs are captured.
+        let transform_feedback = if let Some((entry_point, _)) = &self.geometry_shader {
+            entry_point.transform_feedback()
+        } else if let Some(tessellation_shaders) = &self.tessellation_shaders {
+            tessellation_shaders.evaluation.0.transform_feedback()
+        } else if let Some((entry_point, _)) = &self.vertex_shader {
+            entry_point.transform_feedback()
+        } else {
+            false
+        };
+
         let Self {
             mut render_pass,
             cache: _,
+            name,
             vertex_shader: _,
             tessellation_shaders: _,
             geometry_shader: _,
@@ -382,6 +404,7 @@ where
             multisample_state,
             depth_stencil_state,
             color_blend_state,
+            fragment_shading_rate_state,
         } = self;
 
         let num_used_descriptor_sets = descriptor_requirements
@@ -391,7 +414,7 @@ where
             .map(|x| x + 1)
             .unwrap_or(0);
 
-        Ok(Arc::new(GraphicsPipeline {
+        let pipeline = Arc::new(GraphicsPipeline {
             handle,
             device,
             layout: pipeline_layout,
@@ -411,8 +434,25 @@ where
             multisample_state: has.fragment_output_state.then_some(multisample_state),
             depth_stencil_state: has.depth_stencil_state.then_some(depth_stencil_state),
             color_blend_state: has.color_blend_state.then_some(color_blend_state),
+            fragment_shading_rate_state: has
+                .fragment_shader_state
+                .then_some(fragment_shading_rate_state),
             dynamic_state,
-        }))
+            transform_feedback,
+            name,
+        });
+
+        if let Some(name) = pipeline.name.as_deref() {
+            unsafe {
+                pipeline.device.set_debug_utils_object_name_if_enabled(
+                    ash::vk::ObjectType::PIPELINE,
+                    handle.as_raw(),
+                    Some(name),
+                )?;
+            }
+        }
+
+        Ok(pipeline)
     }
 
     fn validate_create(
@@ -443,6 +483,7 @@ where
             multisample_state,
             depth_stencil_state,
             color_blend_state,
+            fragment_shading_rate_state,
         } = self;
 
         let render_pass = render_pass.as_ref().expect("Missing render pass");
@@ -1756,6 +1797,92 @@ where
 
             // TODO:
             // VUID-VkGraphicsPipelineCreateInfo-renderPass-06038
+
+            // Fragment shading rate state
+            if device.enabled_extensions().khr_fragment_shading_rate {
+                if let StateMode::Fixed(shading_rate) = fragment_shading_rate_state.shading_rate {
+                    let FragmentShadingRate {
+                        fragment_size,
+                        combiner_ops,
+                    } = shading_rate;
+
+                    // VUID-VkGraphicsPipelineCreateInfo-pDynamicState-04494
+                    // VUID-VkGraphicsPipelineCreateInfo-pDynamicState-04495
+                    // VUID-VkGraphicsPipelineCreateInfo-pDynamicState-04496
+                    // VUID-VkGraphicsPipelineCreateInfo-pDynamicState-04497
+                    // VUID-VkGraphicsPipelineCreateInfo-pDynamicState-04498
+                    // VUID-VkGraphicsPipelineCreateInfo-pDynamicState-04499
+                    if !is_valid_fragment_size(fragment_size) {
+                        return Err(
+                            GraphicsPipelineCreationError::FragmentShadingRateFragmentSizeInvalid,
+                        );
+                    }
+
+                    // VUID-VkGraphicsPipelineCreateInfo-pDynamicState-04500
+                    if fragment_size != [1, 1]
+                        && !device.enabled_features().pipeline_fragment_shading_rate
+                    {
+                        return Err(GraphicsPipelineCreationError::RequirementNotMet {
+                            required_for: "`fragment_shading_rate_state.shading_rate` is `StateMode::Fixed(shading_rate)`, where `shading_rate.fragment_size` is not `[1, 1]`",
+                            requires_one_of: RequiresOneOf {
+                                features: &["pipeline_fragment_shading_rate"],
+                                ..Default::default()
+                            },
+                        });
+                    }
+
+                    for combiner_op in combiner_ops {
+                        // VUID-VkPipelineFragmentShadingRateStateCreateInfoKHR-combinerOps-parameter
+                        combiner_op.validate_device(device)?;
+                    }
+
+                    // VUID-VkGraphicsPipelineCreateInfo-pDynamicState-04501
+                    if combiner_ops[0] != FragmentShadingRateCombinerOp::Keep
+                        && !device.enabled_features().primitive_fragment_shading_rate
+                    {
+                        return Err(GraphicsPipelineCreationError::RequirementNotMet {
+                            required_for: "`fragment_shading_rate_state.shading_rate` is `StateMode::Fixed(shading_rate)`, where `shading_rate.combiner_ops[0]` is not `FragmentShadingRateCombinerOp::Keep`",
+                            requires_one_of: RequiresOneOf {
+                                features: &["primitive_fragment_shading_rate"],
+                                ..Default::default()
+                            },
+                        });
+                    }
+
+                    // VUID-VkGraphicsPipelineCreateInfo-pDynamicState-04502
+                    if combiner_ops[1] != FragmentShadingRateCombinerOp::Keep
+                        && !device.enabled_features().attachment_fragment_shading_rate
+                    {
+                        return Err(GraphicsPipelineCreationError::RequirementNotMet {
+                            required_for: "`fragment_shading_rate_state.shading_rate` is `StateMode::Fixed(shading_rate)`, where `shading_rate.combiner_ops[1]` is not `FragmentShadingRateCombinerOp::Keep`",
+                            requires_one_of: RequiresOneOf {
+                                features: &["attachment_fragment_shading_rate"],
+                                ..Default::default()
+                            },
+                        });
+                    }
+
+                    // VUID?
+                    if !properties
+                        .fragment_shading_rate_non_trivial_combiner_ops
+                        .unwrap_or(false)
+                        && !combiner_ops.into_iter().all(|op| op.is_trivial())
+                    {
+                        return Err(
+                            GraphicsPipelineCreationError::FragmentShadingRateNonTrivialCombinerOpsNotSupported,
+                        );
+                    }
+                }
+            } else if !fragment_shading_rate_state.is_default() {
+                return Err(GraphicsPipelineCreationError::RequirementNotMet {
+                    required_for:
+                        "`fragment_shading_rate_state` is not `FragmentShadingRateState::default()`",
+                    requires_one_of: RequiresOneOf {
+                        device_extensions: &["khr_fragment_shading_rate"],
+                        ..Default::default()
+                    },
+                });
+            }
         }
 
         // Depth/stencil state
@@ -2339,7 +2466,6 @@ where
         // - ViewportShadingRatePalette (VkPipelineViewportShadingRateImageStateCreateInfoNV)
         // - ViewportCoarseSampleOrder (VkPipelineViewportCoarseSampleOrderStateCreateInfoNV)
         // - ExclusiveScissor (VkPipelineViewportExclusiveScissorStateCreateInfoNV)
-        // - FragmentShadingRate (VkPipelineFragmentShadingRateStateCreateInfoKHR)
 
         Ok(())
     }
@@ -2377,6 +2503,7 @@ where
             multisample_state,
             depth_stencil_state,
             color_blend_state,
+            fragment_shading_rate_state,
         } = self;
 
         let render_pass = render_pass.as_ref().unwrap();
@@ -3028,6 +3155,7 @@ where
         */
 
         let mut fragment_shader_specialization_vk = None;
+        let mut fragment_shading_rate_state_vk = None;
         let mut depth_stencil_state_vk = None;
 
         if has.fragment_shader_state {
@@ -3069,6 +3197,36 @@ where
                     ..Default::default()
                 });
             }
+
+            // Fragment shading rate state
+            if device.enabled_extensions().khr_fragment_shading_rate {
+                let FragmentShadingRateState { shading_rate } = fragment_shading_rate_state;
+
+                let FragmentShadingRate {
+                    fragment_size,
+                    combiner_ops,
+                } = match shading_rate {
+                    StateMode::Fixed(shading_rate) => {
+                        dynamic_state.insert(DynamicState::FragmentShadingRate, false);
+                        *shading_rate
+                    }
+                    StateMode::Dynamic => {
+                        dynamic_state.insert(DynamicState::FragmentShadingRate, true);
+                        Default::default()
+                    }
+                };
+
+                let _ = fragment_shading_rate_state_vk.insert(
+                    ash::vk::PipelineFragmentShadingRateStateCreateInfoKHR {
+                        fragment_size: ash::vk::Extent2D {
+                            width: fragment_size[0],
+                            height: fragment_size[1],
+                        },
+                        combiner_ops: combiner_ops.map(Into::into),
+                        ..Default::default()
+                    },
+                );
+            }
         }
 
         // Depth/stencil state
@@ -3480,6 +3638,11 @@ where
             create_info.p_next = info as *const _ as *const _;
         }
 
+        if let Some(info) = fragment_shading_rate_state_vk.as_mut() {
+            info.p_next = create_info.p_next;
+            create_info.p_next = info as *const _ as *const _;
+        }
+
         if let Some(info) = rendering_create_info_vk.as_mut() {
             info.p_next = create_info.p_next;
             create_info.p_next = info as *const _ as *const _;
@@ -3544,6 +3707,7 @@ impl<'vs, 'tcs, 'tes, 'gs, 'fs, Vdef, Vss, Tcss, Tess, Gss, Fss>
         GraphicsPipelineBuilder {
             render_pass: self.render_pass,
             cache: self.cache,
+            name: self.name,
 
             vertex_shader: Some((shader, specialization_constants)),
             tessellation_shaders: self.tessellation_shaders,
@@ -3559,6 +3723,7 @@ impl<'vs, 'tcs, 'tes, 'gs, 'fs, Vdef, Vss, Tcss, Tess, Gss, Fss>
             multisample_state: self.multisample_state,
             depth_stencil_state: self.depth_stencil_state,
             color_blend_state: self.color_blend_state,
+            fragment_shading_rate_state: self.fragment_shading_rate_state,
         }
     }
 
@@ -3578,6 +3743,7 @@ impl<'vs, 'tcs, 'tes, 'gs, 'fs, Vdef, Vss, Tcss, Tess, Gss, Fss>
         GraphicsPipelineBuilder {
             render_pass: self.render_pass,
             cache: self.cache,
+            name: self.name,
 
             vertex_shader: self.vertex_shader,
             tessellation_shaders: Some(TessellationShaders {
@@ -3596,6 +3762,7 @@ impl<'vs, 'tcs, 'tes, 'gs, 'fs, Vdef, Vss, Tcss, Tess, Gss, Fss>
             multisample_state: self.multisample_state,
             depth_stencil_state: self.depth_stencil_state,
             color_blend_state: self.color_blend_state,
+            fragment_shading_rate_state: self.fragment_shading_rate_state,
         }
     }
 
@@ -3612,6 +3779,7 @@ impl<'vs, 'tcs, 'tes, 'gs, 'fs, Vdef, Vss, Tcss, Tess, Gss, Fss>
         GraphicsPipelineBuilder {
             render_pass: self.render_pass,
             cache: self.cache,
+            name: self.name,
 
             vertex_shader: self.vertex_shader,
             tessellation_shaders: self.tessellation_shaders,
@@ -3627,6 +3795,7 @@ impl<'vs, 'tcs, 'tes, 'gs, 'fs, Vdef, Vss, Tcss, Tess, Gss, Fss>
             multisample_state: self.multisample_state,
             depth_stencil_state: self.depth_stencil_state,
             color_blend_state: self.color_blend_state,
+            fragment_shading_rate_state: self.fragment_shading_rate_state,
         }
     }
 
@@ -3645,6 +3814,7 @@ impl<'vs, 'tcs, 'tes, 'gs, 'fs, Vdef, Vss, Tcss, Tess, Gss, Fss>
         GraphicsPipelineBuilder {
             render_pass: self.render_pass,
             cache: self.cache,
+            name: self.name,
 
             vertex_shader: self.vertex_shader,
             tessellation_shaders: self.tessellation_shaders,
@@ -3660,6 +3830,7 @@ impl<'vs, 'tcs, 'tes, 'gs, 'fs, Vdef, Vss, Tcss, Tess, Gss, Fss>
             multisample_state: self.multisample_state,
             depth_stencil_state: self.depth_stencil_state,
             color_blend_state: self.color_blend_state,
+            fragment_shading_rate_state: self.fragment_shading_rate_state,
         }
     }
 
@@ -3676,6 +3847,7 @@ impl<'vs, 'tcs, 'tes, 'gs, 'fs, Vdef, Vss, Tcss, Tess, Gss, Fss>
         GraphicsPipelineBuilder {
             render_pass: self.render_pass,
             cache: self.cache,
+            name: self.name,
 
             vertex_shader: self.vertex_shader,
             tessellation_shaders: self.tessellation_shaders,
@@ -3691,6 +3863,7 @@ impl<'vs, 'tcs, 'tes, 'gs, 'fs, Vdef, Vss, Tcss, Tess, Gss, Fss>
             multisample_state: self.multisample_state,
             depth_stencil_state: self.depth_stencil_state,
             color_blend_state: self.color_blend_state,
+            fragment_shading_rate_state: self.fragment_shading_rate_state,
         }
     }
 
@@ -3770,6 +3943,18 @@ impl<'vs, 'tcs, 'tes, 'gs, 'fs, Vdef, Vss, Tcss, Tess, Gss, Fss>
         self
     }
 
+    /// Sets the fragment shading rate state.
+    ///
+    /// The default value is [`FragmentShadingRateState::default()`].
+    #[inline]
+    pub fn fragment_shading_rate_state(
+        mut self,
+        fragment_shading_rate_state: FragmentShadingRateState,
+    ) -> Self {
+        self.fragment_shading_rate_state = fragment_shading_rate_state;
+        self
+    }
+
     /// Sets the tessellation shaders stage as disabled. This is the default.
     #[deprecated(since = "0.27.0")]
     #[inline]
@@ -4326,11 +4511,23 @@ impl<'vs, 'tcs, 'tes, 'gs, 'fs, Vdef, Vss, Tcss, Tess, Gss, Fss>
         self
     }
 
+    /// Sets a name for the pipeline.
+    ///
+    /// If the `ext_debug_utils` extension is enabled on the instance, the name is assigned to the
+    /// pipeline with [`Device::set_debug_utils_object_name`], so that debugging tools show it.
+    /// Otherwise, this doesn't require the extension.
+    #[inline]
+    pub fn name(mut self, name: impl Into<String>) -> Self {
+        self.name = Some(name.into());
+        self
+    }
+
     /// Sets the render pass subpass to use.
     pub fn render_pass(self, render_pass: impl Into<PipelineRenderPassType>) -> Self {
         GraphicsPipelineBuilder {
             render_pass: Some(render_pass.into()),
             cache: self.cache,
+            name: self.name,
 
             vertex_shader: self.vertex_shader,
             tessellation_shaders: self.tessellation_shaders,
@@ -4345,6 +4542,7 @@ impl<'vs, 'tcs, 'tes, 'gs, 'fs, Vdef, Vss, Tcss, Tess, Gss, Fss>
             multisample_state: self.multisample_state,
             depth_stencil_state: self.depth_stencil_state,
             color_blend_state: self.color_blend_state,
+            fragment_shading_rate_state: self.fragment_shading_rate_state,
 
             discard_rectangle_state: self.discard_rectangle_state,
         }
@@ -4376,6 +4574,7 @@ where
         GraphicsPipelineBuilder {
             render_pass: self.render_pass.clone(),
             cache: self.cache.clone(),
+            name: self.name.clone(),
 
             vertex_shader: self.vertex_shader.clone(),
             tessellation_shaders: self.tessellation_shaders.clone(),
@@ -4390,6 +4589,7 @@ where
             multisample_state: self.multisample_state,
             depth_stencil_state: self.depth_stencil_state.clone(),
             color_blend_state: self.color_blend_state.clone(),
+            fragment_shading_rate_state: self.fragment_shading_rate_state,
 
             discard_rectangle_state: self.discard_rectangle_state.clone(),
         }
@@ -44,6 +44,15 @@ pub enum GraphicsPipelineCreationError {
     /// expects.
     FragmentShaderRenderPassIncompatible,
 
+    /// The fixed fragment shading rate has a fragment size that is not 1, 2 or 4 in each
+    /// dimension.
+    FragmentShadingRateFragmentSizeInvalid,
+
+    /// A fixed fragment shading rate combiner operation is not `Keep` or `Replace`, but the
+    /// [`fragment_shading_rate_non_trivial_combiner_ops`](crate::device::Properties::fragment_shading_rate_non_trivial_combiner_ops)
+    /// device property is `false`.
+    FragmentShadingRateNonTrivialCombinerOpsNotSupported,
+
     /// The pipeline layout is not compatible with what the shaders expect.
     IncompatiblePipelineLayout(PipelineLayoutSupersetError),
 
@@ -243,6 +252,16 @@ impl Display for GraphicsPipelineCreationError {
                 "the output of the fragment shader is not compatible with what the render pass \
                 subpass expects",
             ),
+            Self::FragmentShadingRateFragmentSizeInvalid => write!(
+                f,
+                "the fixed fragment shading rate has a fragment size that is not 1, 2 or 4 in each \
+                dimension",
+            ),
+            Self::FragmentShadingRateNonTrivialCombinerOpsNotSupported => write!(
+                f,
+                "a fixed fragment shading rate combiner operation is not `Keep` or `Replace`, but \
+                the `fragment_shading_rate_non_trivial_combiner_ops` device property is `false`",
+            ),
             Self::IncompatiblePipelineLayout(_) => write!(
                 f,
                 "the pipeline layout is not compatible with what the shaders expect",
@@ -0,0 +1,170 @@
+// Copyright (c) 2022 The vulkano developers
+// Licensed under the Apache License, Version 2.0
+// <LICENSE-APACHE or
+// https://www.apache.org/licenses/LICENSE-2.0> or the MIT
+// license <LICENSE-MIT or https://opensource.org/licenses/MIT>,
+// at your option. All files in the project carrying such
+// notice may not be copied, modified, or distributed except
+// according to those terms.
+
+//! Invoking the fragment shader once for a group of pixels, rather than for every pixel.
+//!
+//! The fragment shading rate determines the size of the area in the framebuffer, in pixels, that
+//! is covered by a single fragment shader invocation. A larger fragment size reduces the shading
+//! cost in areas where full detail is not needed, such as the periphery of the view in foveated
+//! rendering.
+//!
+//! The final shading rate can come from three sources:
+//! - The pipeline rate, which is specified in [`FragmentShadingRateState`] or set dynamically.
+//! - The primitive rate, which is written by the last pre-rasterization shader stage in the
+//!   `PrimitiveShadingRateKHR` built-in.
+//! - The attachment rate, which is read from the fragment shading rate attachment of the
+//!   subpass or of [`begin_rendering`].
+//!
+//! The pipeline rate is first combined with the primitive rate, and the result is then combined
+//! with the attachment rate, each time using the corresponding
+//! [`FragmentShadingRateCombinerOp`].
+//!
+//! [`begin_rendering`]: crate::command_buffer::AutoCommandBufferBuilder::begin_rendering
+
+use crate::{device::Properties, macros::vulkan_enum, pipeline::StateMode};
+
+/// The state in a graphics pipeline describing the fragment shading rate.
+#[derive(Clone, Copy, Debug)]
+pub struct FragmentShadingRateState {
+    /// The pipeline fragment shading rate and the combiner operations.
+    ///
+    /// If set to anything other than the default value, the
+    /// [`khr_fragment_shading_rate`](crate::device::DeviceExtensions::khr_fragment_shading_rate)
+    /// extension must be enabled on the device.
+    pub shading_rate: StateMode<FragmentShadingRate>,
+}
+
+impl FragmentShadingRateState {
+    /// Creates a `FragmentShadingRateState` with a fixed fragment size of `[1, 1]` and
+    /// [`FragmentShadingRateCombinerOp::Keep`] for both combiner operations. This effectively
+    /// disables variable rate shading.
+    #[inline]
+    pub fn new() -> Self {
+        Self {
+            shading_rate: StateMode::Fixed(Default::default()),
+        }
+    }
+
+    /// Returns whether the state is equal to the state returned by
+    /// [`FragmentShadingRateState::new`].
+    #[inline]
+    pub(crate) fn is_default(&self) -> bool {
+        self.shading_rate == StateMode::Fixed(Default::default())
+    }
+}
+
+impl Default for FragmentShadingRateState {
+    /// Returns [`FragmentShadingRateState::new`].
+    #[inline]
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// The values to use for the pipeline fragment shading rate.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct FragmentShadingRate {
+    /// The size, in pixels, of the area covered by a single fragment shader invocation.
+    ///
+    /// Both values must be 1, 2 or 4. Setting this to a value other than `[1, 1]` requires the
+    /// [`pipeline_fragment_shading_rate`](crate::device::Features::pipeline_fragment_shading_rate)
+    /// feature to be enabled on the device.
+    ///
+    /// The default value is `[1, 1]`.
+    pub fragment_size: [u32; 2],
+
+    /// The operations used to combine the pipeline rate with the primitive rate (first element),
+    /// and the result of that with the attachment rate (second element).
+    ///
+    /// Setting the first element to a value other than [`FragmentShadingRateCombinerOp::Keep`]
+    /// requires the
+    /// [`primitive_fragment_shading_rate`](crate::device::Features::primitive_fragment_shading_rate)
+    /// feature to be enabled on the device, and setting the second element to a value other
+    /// than `Keep` requires the
+    /// [`attachment_fragment_shading_rate`](crate::device::Features::attachment_fragment_shading_rate)
+    /// feature. Operations other than `Keep` and `Replace` additionally require the
+    /// [`fragment_shading_rate_non_trivial_combiner_ops`](crate::device::Properties::fragment_shading_rate_non_trivial_combiner_ops)
+    /// device property to be `true`.
+    ///
+    /// The default value is `[FragmentShadingRateCombinerOp::Keep; 2]`.
+    pub combiner_ops: [FragmentShadingRateCombinerOp; 2],
+}
+
+impl Default for FragmentShadingRate {
+    #[inline]
+    fn default() -> Self {
+        Self {
+            fragment_size: [1, 1],
+            combiner_ops: [FragmentShadingRateCombinerOp::Keep; 2],
+        }
+    }
+}
+
+vulkan_enum! {
+    /// How two fragment shading rates are combined into one.
+    ///
+    /// In the descriptions below, `A` is the rate that results from the previous stage of
+    /// combining, and `B` is the rate that is being combined with it.
+    #[non_exhaustive]
+    FragmentShadingRateCombinerOp = FragmentShadingRateCombinerOpKHR(i32);
+
+    /// The result is `A`; `B` is ignored.
+    Keep = KEEP,
+
+    /// The result is `B`; `A` is ignored.
+    Replace = REPLACE,
+
+    /// The result is the component-wise minimum of `A` and `B`.
+    Min = MIN,
+
+    /// The result is the component-wise maximum of `A` and `B`.
+    Max = MAX,
+
+    /// The result is the component-wise product of `A` and `B`.
+    Mul = MUL,
+}
+
+impl FragmentShadingRateCombinerOp {
+    /// Returns whether the operation is one of the trivial operations, `Keep` or `Replace`.
+    #[inline]
+    pub(crate) fn is_trivial(self) -> bool {
+        matches!(self, Self::Keep | Self::Replace)
+    }
+}
+
+/// Returns whether `fragment_size` is a valid fragment size for the pipeline or dynamic fragment
+/// shading rate.
+#[inline]
+pub(crate) fn is_valid_fragment_size(fragment_size: [u32; 2]) -> bool {
+    fragment_size
+        .into_iter()
+        .all(|size| matches!(size, 1 | 2 | 4))
+}
+
+/// Returns whether `texel_size` is a valid texel size for a fragment shading rate attachment, on
+/// a device with the given `properties`.
+pub(crate) fn is_valid_attachment_texel_size(
+    properties: &Properties,
+    texel_size: [u32; 2],
+) -> bool {
+    let min_texel_size = properties
+        .min_fragment_shading_rate_attachment_texel_size
+        .unwrap_or_default();
+    let max_texel_size = properties
+        .max_fragment_shading_rate_attachment_texel_size
+        .unwrap_or_default();
+    let max_aspect_ratio = properties
+        .max_fragment_shading_rate_attachment_texel_size_aspect_ratio
+        .unwrap_or(0);
+
+    (0..2).all(|i| {
+        texel_size[i].is_power_of_two()
+            && (min_texel_size[i]..=max_texel_size[i]).contains(&texel_size[i])
+    }) && texel_size[0].max(texel_size[1]) / texel_size[0].min(texel_size[1]) <= max_aspect_ratio
+}
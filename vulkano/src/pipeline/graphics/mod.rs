@@ -59,10 +59,10 @@
 pub use self::{builder::GraphicsPipelineBuilder, creation_error::GraphicsPipelineCreationError};
 use self::{
     color_blend::ColorBlendState, depth_stencil::DepthStencilState,
-    discard_rectangle::DiscardRectangleState, input_assembly::InputAssemblyState,
-    multisample::MultisampleState, rasterization::RasterizationState,
-    render_pass::PipelineRenderPassType, tessellation::TessellationState,
-    vertex_input::VertexInputState, viewport::ViewportState,
+    discard_rectangle::DiscardRectangleState, fragment_shading_rate::FragmentShadingRateState,
+    input_assembly::InputAssemblyState, multisample::MultisampleState,
+    rasterization::RasterizationState, render_pass::PipelineRenderPassType,
+    tessellation::TessellationState, vertex_input::VertexInputState, viewport::ViewportState,
 };
 use super::{DynamicState, Pipeline, PipelineBindPoint, PipelineLayout};
 use crate::{
@@ -83,6 +83,7 @@ pub mod color_blend;
 mod creation_error;
 pub mod depth_stencil;
 pub mod discard_rectangle;
+pub mod fragment_shading_rate;
 pub mod input_assembly;
 pub mod multisample;
 pub mod rasterization;
@@ -117,7 +118,10 @@ pub struct GraphicsPipeline {
     multisample_state: Option<MultisampleState>,
     depth_stencil_state: Option<DepthStencilState>,
     color_blend_state: Option<ColorBlendState>,
+    fragment_shading_rate_state: Option<FragmentShadingRateState>,
     dynamic_state: HashMap<DynamicState, bool>,
+    transform_feedback: bool,
+    name: Option<String>,
 }
 
 impl GraphicsPipeline {
@@ -152,6 +156,12 @@ impl GraphicsPipeline {
         &self.render_pass
     }
 
+    /// Returns the name that the pipeline was created with, if any.
+    #[inline]
+    pub fn name(&self) -> Option<&str> {
+        self.name.as_deref()
+    }
+
     /// Returns information about a particular shader.
     ///
     /// `None` is returned if the pipeline does not contain this shader.
@@ -228,6 +238,12 @@ impl GraphicsPipeline {
         self.color_blend_state.as_ref()
     }
 
+    /// Returns the fragment shading rate state used to create this pipeline.
+    #[inline]
+    pub fn fragment_shading_rate_state(&self) -> Option<&FragmentShadingRateState> {
+        self.fragment_shading_rate_state.as_ref()
+    }
+
     /// Returns whether a particular state is must be dynamically set.
     ///
     /// `None` is returned if the pipeline does not contain this state. Previously set dynamic
@@ -242,6 +258,13 @@ impl GraphicsPipeline {
     pub fn dynamic_states(&self) -> impl ExactSizeIterator<Item = (DynamicState, bool)> + '_ {
         self.dynamic_state.iter().map(|(k, v)| (*k, *v))
     }
+
+    /// Returns whether the last vertex processing stage of the pipeline declares the `Xfb`
+    /// execution mode, so that its outputs can be captured with transform feedback.
+    #[inline]
+    pub fn transform_feedback(&self) -> bool {
+        self.transform_feedback
+    }
 }
 
 impl Pipeline for GraphicsPipeline {
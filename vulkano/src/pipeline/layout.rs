@@ -201,15 +201,9 @@ impl PipelineLayout {
         }
 
         {
-            let mut num_resources = Counter::default();
-            let mut num_samplers = Counter::default();
-            let mut num_uniform_buffers = Counter::default();
-            let mut num_uniform_buffers_dynamic = 0;
-            let mut num_storage_buffers = Counter::default();
-            let mut num_storage_buffers_dynamic = 0;
-            let mut num_sampled_images = Counter::default();
-            let mut num_storage_images = Counter::default();
-            let mut num_input_attachments = Counter::default();
+            let mut counts = DescriptorCounts::default();
+            let mut counts_update_after_bind = DescriptorCounts::default();
+            let mut has_update_after_bind_pool = false;
             let mut push_descriptor_set = None;
 
             for (set_num, set_layout) in set_layouts.iter().enumerate() {
@@ -224,55 +218,30 @@ impl PipelineLayout {
                     }
                 }
 
-                for layout_binding in set_layout.bindings().values() {
-                    num_resources
-                        .increment(layout_binding.descriptor_count, &layout_binding.stages);
-
-                    match layout_binding.descriptor_type {
-                        DescriptorType::Sampler => {
-                            num_samplers
-                                .increment(layout_binding.descriptor_count, &layout_binding.stages);
-                        }
-                        DescriptorType::CombinedImageSampler => {
-                            num_samplers
-                                .increment(layout_binding.descriptor_count, &layout_binding.stages);
-                            num_sampled_images
-                                .increment(layout_binding.descriptor_count, &layout_binding.stages);
-                        }
-                        DescriptorType::SampledImage | DescriptorType::UniformTexelBuffer => {
-                            num_sampled_images
-                                .increment(layout_binding.descriptor_count, &layout_binding.stages);
-                        }
-                        DescriptorType::StorageImage | DescriptorType::StorageTexelBuffer => {
-                            num_storage_images
-                                .increment(layout_binding.descriptor_count, &layout_binding.stages);
-                        }
-                        DescriptorType::UniformBuffer => {
-                            num_uniform_buffers
-                                .increment(layout_binding.descriptor_count, &layout_binding.stages);
-                        }
-                        DescriptorType::UniformBufferDynamic => {
-                            num_uniform_buffers
-                                .increment(layout_binding.descriptor_count, &layout_binding.stages);
-                            num_uniform_buffers_dynamic += 1;
-                        }
-                        DescriptorType::StorageBuffer => {
-                            num_storage_buffers
-                                .increment(layout_binding.descriptor_count, &layout_binding.stages);
-                        }
-                        DescriptorType::StorageBufferDynamic => {
-                            num_storage_buffers
-                                .increment(layout_binding.descriptor_count, &layout_binding.stages);
-                            num_storage_buffers_dynamic += 1;
-                        }
-                        DescriptorType::InputAttachment => {
-                            num_input_attachments
-                                .increment(layout_binding.descriptor_count, &layout_binding.stages);
-                        }
-                    }
+                // The regular limits only apply to set layouts that are not update-after-bind,
+                // while the update-after-bind limits apply to all set layouts.
+                if set_layout.update_after_bind_pool() {
+                    has_update_after_bind_pool = true;
+                } else {
+                    counts.add_set_layout(set_layout);
                 }
+
+                counts_update_after_bind.add_set_layout(set_layout);
             }
 
+            let DescriptorCounts {
+                resources: num_resources,
+                samplers: num_samplers,
+                uniform_buffers: num_uniform_buffers,
+                uniform_buffers_dynamic: num_uniform_buffers_dynamic,
+                storage_buffers: num_storage_buffers,
+                storage_buffers_dynamic: num_storage_buffers_dynamic,
+                sampled_images: num_sampled_images,
+                storage_images: num_storage_images,
+                input_attachments: num_input_attachments,
+                inline_uniform_blocks: num_inline_uniform_blocks,
+            } = counts;
+
             if num_resources.max_per_stage() > properties.max_per_stage_resources {
                 return Err(PipelineLayoutCreationError::MaxPerStageResourcesExceeded {
                     provided: num_resources.max_per_stage(),
@@ -350,6 +319,20 @@ impl PipelineLayout {
                 );
             }
 
+            // VUID-VkPipelineLayoutCreateInfo-descriptorType-02214
+            let max_supported = properties
+                .max_per_stage_descriptor_inline_uniform_blocks
+                .unwrap_or(0);
+
+            if num_inline_uniform_blocks.max_per_stage() > max_supported {
+                return Err(
+                    PipelineLayoutCreationError::MaxPerStageDescriptorInlineUniformBlocksExceeded {
+                        provided: num_inline_uniform_blocks.max_per_stage(),
+                        max_supported,
+                    },
+                );
+            }
+
             // VUID-VkPipelineLayoutCreateInfo-descriptorType-03028
             if num_samplers.total > properties.max_descriptor_set_samplers {
                 return Err(
@@ -429,6 +412,231 @@ impl PipelineLayout {
                     },
                 );
             }
+
+            // VUID-VkPipelineLayoutCreateInfo-descriptorType-02216
+            let max_supported = properties
+                .max_descriptor_set_inline_uniform_blocks
+                .unwrap_or(0);
+
+            if num_inline_uniform_blocks.total > max_supported {
+                return Err(
+                    PipelineLayoutCreationError::MaxDescriptorSetInlineUniformBlocksExceeded {
+                        provided: num_inline_uniform_blocks.total,
+                        max_supported,
+                    },
+                );
+            }
+
+            if has_update_after_bind_pool {
+                let max_supported = properties
+                    .max_per_stage_update_after_bind_resources
+                    .unwrap_or(0);
+
+                if counts_update_after_bind.resources.max_per_stage() > max_supported {
+                    return Err(
+                        PipelineLayoutCreationError::MaxPerStageUpdateAfterBindResourcesExceeded {
+                            provided: counts_update_after_bind.resources.max_per_stage(),
+                            max_supported,
+                        },
+                    );
+                }
+
+                // VUID-VkPipelineLayoutCreateInfo-descriptorType-03022
+                let max_supported = properties
+                    .max_per_stage_descriptor_update_after_bind_samplers
+                    .unwrap_or(0);
+
+                if counts_update_after_bind.samplers.max_per_stage() > max_supported {
+                    return Err(PipelineLayoutCreationError::MaxPerStageDescriptorUpdateAfterBindSamplersExceeded {
+                        provided: counts_update_after_bind.samplers.max_per_stage(),
+                        max_supported,
+                    });
+                }
+
+                // VUID-VkPipelineLayoutCreateInfo-descriptorType-03023
+                let max_supported = properties
+                    .max_per_stage_descriptor_update_after_bind_uniform_buffers
+                    .unwrap_or(0);
+
+                if counts_update_after_bind.uniform_buffers.max_per_stage() > max_supported {
+                    return Err(PipelineLayoutCreationError::MaxPerStageDescriptorUpdateAfterBindUniformBuffersExceeded {
+                        provided: counts_update_after_bind.uniform_buffers.max_per_stage(),
+                        max_supported,
+                    });
+                }
+
+                // VUID-VkPipelineLayoutCreateInfo-descriptorType-03024
+                let max_supported = properties
+                    .max_per_stage_descriptor_update_after_bind_storage_buffers
+                    .unwrap_or(0);
+
+                if counts_update_after_bind.storage_buffers.max_per_stage() > max_supported {
+                    return Err(PipelineLayoutCreationError::MaxPerStageDescriptorUpdateAfterBindStorageBuffersExceeded {
+                        provided: counts_update_after_bind.storage_buffers.max_per_stage(),
+                        max_supported,
+                    });
+                }
+
+                // VUID-VkPipelineLayoutCreateInfo-descriptorType-03025
+                let max_supported = properties
+                    .max_per_stage_descriptor_update_after_bind_sampled_images
+                    .unwrap_or(0);
+
+                if counts_update_after_bind.sampled_images.max_per_stage() > max_supported {
+                    return Err(PipelineLayoutCreationError::MaxPerStageDescriptorUpdateAfterBindSampledImagesExceeded {
+                        provided: counts_update_after_bind.sampled_images.max_per_stage(),
+                        max_supported,
+                    });
+                }
+
+                // VUID-VkPipelineLayoutCreateInfo-descriptorType-03026
+                let max_supported = properties
+                    .max_per_stage_descriptor_update_after_bind_storage_images
+                    .unwrap_or(0);
+
+                if counts_update_after_bind.storage_images.max_per_stage() > max_supported {
+                    return Err(PipelineLayoutCreationError::MaxPerStageDescriptorUpdateAfterBindStorageImagesExceeded {
+                        provided: counts_update_after_bind.storage_images.max_per_stage(),
+                        max_supported,
+                    });
+                }
+
+                // VUID-VkPipelineLayoutCreateInfo-descriptorType-03027
+                let max_supported = properties
+                    .max_per_stage_descriptor_update_after_bind_input_attachments
+                    .unwrap_or(0);
+
+                if counts_update_after_bind.input_attachments.max_per_stage() > max_supported {
+                    return Err(PipelineLayoutCreationError::MaxPerStageDescriptorUpdateAfterBindInputAttachmentsExceeded {
+                        provided: counts_update_after_bind.input_attachments.max_per_stage(),
+                        max_supported,
+                    });
+                }
+
+                // VUID-VkPipelineLayoutCreateInfo-descriptorType-02215
+                let max_supported = properties
+                    .max_per_stage_descriptor_update_after_bind_inline_uniform_blocks
+                    .unwrap_or(0);
+
+                if counts_update_after_bind
+                    .inline_uniform_blocks
+                    .max_per_stage()
+                    > max_supported
+                {
+                    return Err(PipelineLayoutCreationError::MaxPerStageDescriptorUpdateAfterBindInlineUniformBlocksExceeded {
+                        provided: counts_update_after_bind.inline_uniform_blocks.max_per_stage(),
+                        max_supported,
+                    });
+                }
+
+                // VUID-VkPipelineLayoutCreateInfo-descriptorType-03036
+                let max_supported = properties
+                    .max_descriptor_set_update_after_bind_samplers
+                    .unwrap_or(0);
+
+                if counts_update_after_bind.samplers.total > max_supported {
+                    return Err(PipelineLayoutCreationError::MaxDescriptorSetUpdateAfterBindSamplersExceeded {
+                        provided: counts_update_after_bind.samplers.total,
+                        max_supported,
+                    });
+                }
+
+                // VUID-VkPipelineLayoutCreateInfo-descriptorType-03037
+                let max_supported = properties
+                    .max_descriptor_set_update_after_bind_uniform_buffers
+                    .unwrap_or(0);
+
+                if counts_update_after_bind.uniform_buffers.total > max_supported {
+                    return Err(PipelineLayoutCreationError::MaxDescriptorSetUpdateAfterBindUniformBuffersExceeded {
+                        provided: counts_update_after_bind.uniform_buffers.total,
+                        max_supported,
+                    });
+                }
+
+                // VUID-VkPipelineLayoutCreateInfo-descriptorType-03038
+                let max_supported = properties
+                    .max_descriptor_set_update_after_bind_uniform_buffers_dynamic
+                    .unwrap_or(0);
+
+                if counts_update_after_bind.uniform_buffers_dynamic > max_supported {
+                    return Err(PipelineLayoutCreationError::MaxDescriptorSetUpdateAfterBindUniformBuffersDynamicExceeded {
+                        provided: counts_update_after_bind.uniform_buffers_dynamic,
+                        max_supported,
+                    });
+                }
+
+                // VUID-VkPipelineLayoutCreateInfo-descriptorType-03039
+                let max_supported = properties
+                    .max_descriptor_set_update_after_bind_storage_buffers
+                    .unwrap_or(0);
+
+                if counts_update_after_bind.storage_buffers.total > max_supported {
+                    return Err(PipelineLayoutCreationError::MaxDescriptorSetUpdateAfterBindStorageBuffersExceeded {
+                        provided: counts_update_after_bind.storage_buffers.total,
+                        max_supported,
+                    });
+                }
+
+                // VUID-VkPipelineLayoutCreateInfo-descriptorType-03040
+                let max_supported = properties
+                    .max_descriptor_set_update_after_bind_storage_buffers_dynamic
+                    .unwrap_or(0);
+
+                if counts_update_after_bind.storage_buffers_dynamic > max_supported {
+                    return Err(PipelineLayoutCreationError::MaxDescriptorSetUpdateAfterBindStorageBuffersDynamicExceeded {
+                        provided: counts_update_after_bind.storage_buffers_dynamic,
+                        max_supported,
+                    });
+                }
+
+                // VUID-VkPipelineLayoutCreateInfo-descriptorType-03041
+                let max_supported = properties
+                    .max_descriptor_set_update_after_bind_sampled_images
+                    .unwrap_or(0);
+
+                if counts_update_after_bind.sampled_images.total > max_supported {
+                    return Err(PipelineLayoutCreationError::MaxDescriptorSetUpdateAfterBindSampledImagesExceeded {
+                        provided: counts_update_after_bind.sampled_images.total,
+                        max_supported,
+                    });
+                }
+
+                // VUID-VkPipelineLayoutCreateInfo-descriptorType-03042
+                let max_supported = properties
+                    .max_descriptor_set_update_after_bind_storage_images
+                    .unwrap_or(0);
+
+                if counts_update_after_bind.storage_images.total > max_supported {
+                    return Err(PipelineLayoutCreationError::MaxDescriptorSetUpdateAfterBindStorageImagesExceeded {
+                        provided: counts_update_after_bind.storage_images.total,
+                        max_supported,
+                    });
+                }
+
+                // VUID-VkPipelineLayoutCreateInfo-descriptorType-03043
+                let max_supported = properties
+                    .max_descriptor_set_update_after_bind_input_attachments
+                    .unwrap_or(0);
+
+                if counts_update_after_bind.input_attachments.total > max_supported {
+                    return Err(PipelineLayoutCreationError::MaxDescriptorSetUpdateAfterBindInputAttachmentsExceeded {
+                        provided: counts_update_after_bind.input_attachments.total,
+                        max_supported,
+                    });
+                }
+
+                // VUID-VkPipelineLayoutCreateInfo-descriptorType-02217
+                let max_supported = properties
+                    .max_descriptor_set_update_after_bind_inline_uniform_blocks
+                    .unwrap_or(0);
+
+                if counts_update_after_bind.inline_uniform_blocks.total > max_supported {
+                    return Err(PipelineLayoutCreationError::MaxDescriptorSetUpdateAfterBindInlineUniformBlocksExceeded {
+                        provided: counts_update_after_bind.inline_uniform_blocks.total,
+                        max_supported,
+                    });
+                }
+            }
         }
 
         /* Check push constant ranges */
@@ -770,6 +978,11 @@ pub enum PipelineLayoutCreationError {
     /// limit.
     MaxDescriptorSetInputAttachmentsExceeded { provided: u32, max_supported: u32 },
 
+    /// The `set_layouts` contain more [`DescriptorType::InlineUniformBlock`] bindings than the
+    /// [`max_descriptor_set_inline_uniform_blocks`](crate::device::Properties::max_descriptor_set_inline_uniform_blocks)
+    /// limit.
+    MaxDescriptorSetInlineUniformBlocksExceeded { provided: u32, max_supported: u32 },
+
     /// The `set_layouts` contain more bound resources in a single stage than the
     /// [`max_per_stage_resources`](crate::device::Properties::max_per_stage_resources)
     /// limit.
@@ -812,6 +1025,120 @@ pub enum PipelineLayoutCreationError {
     /// limit.
     MaxPerStageDescriptorInputAttachmentsExceeded { provided: u32, max_supported: u32 },
 
+    /// The `set_layouts` contain more [`DescriptorType::InlineUniformBlock`] bindings in a single
+    /// stage than the
+    /// [`max_per_stage_descriptor_inline_uniform_blocks`](crate::device::Properties::max_per_stage_descriptor_inline_uniform_blocks)
+    /// limit.
+    MaxPerStageDescriptorInlineUniformBlocksExceeded { provided: u32, max_supported: u32 },
+
+    /// The `set_layouts` contain more [`DescriptorType::Sampler`] and
+    /// [`DescriptorType::CombinedImageSampler`] descriptors than the
+    /// [`max_descriptor_set_update_after_bind_samplers`](crate::device::Properties::max_descriptor_set_update_after_bind_samplers)
+    /// limit.
+    MaxDescriptorSetUpdateAfterBindSamplersExceeded { provided: u32, max_supported: u32 },
+
+    /// The `set_layouts` contain more [`DescriptorType::UniformBuffer`] descriptors than the
+    /// [`max_descriptor_set_update_after_bind_uniform_buffers`](crate::device::Properties::max_descriptor_set_update_after_bind_uniform_buffers)
+    /// limit.
+    MaxDescriptorSetUpdateAfterBindUniformBuffersExceeded { provided: u32, max_supported: u32 },
+
+    /// The `set_layouts` contain more [`DescriptorType::UniformBufferDynamic`] descriptors than the
+    /// [`max_descriptor_set_update_after_bind_uniform_buffers_dynamic`](crate::device::Properties::max_descriptor_set_update_after_bind_uniform_buffers_dynamic)
+    /// limit.
+    MaxDescriptorSetUpdateAfterBindUniformBuffersDynamicExceeded {
+        provided: u32,
+        max_supported: u32,
+    },
+
+    /// The `set_layouts` contain more [`DescriptorType::StorageBuffer`] descriptors than the
+    /// [`max_descriptor_set_update_after_bind_storage_buffers`](crate::device::Properties::max_descriptor_set_update_after_bind_storage_buffers)
+    /// limit.
+    MaxDescriptorSetUpdateAfterBindStorageBuffersExceeded { provided: u32, max_supported: u32 },
+
+    /// The `set_layouts` contain more [`DescriptorType::StorageBufferDynamic`] descriptors than the
+    /// [`max_descriptor_set_update_after_bind_storage_buffers_dynamic`](crate::device::Properties::max_descriptor_set_update_after_bind_storage_buffers_dynamic)
+    /// limit.
+    MaxDescriptorSetUpdateAfterBindStorageBuffersDynamicExceeded {
+        provided: u32,
+        max_supported: u32,
+    },
+
+    /// The `set_layouts` contain more [`DescriptorType::SampledImage`],
+    /// [`DescriptorType::CombinedImageSampler`] and [`DescriptorType::UniformTexelBuffer`]
+    /// descriptors than the
+    /// [`max_descriptor_set_update_after_bind_sampled_images`](crate::device::Properties::max_descriptor_set_update_after_bind_sampled_images)
+    /// limit.
+    MaxDescriptorSetUpdateAfterBindSampledImagesExceeded { provided: u32, max_supported: u32 },
+
+    /// The `set_layouts` contain more [`DescriptorType::StorageImage`] and
+    /// [`DescriptorType::StorageTexelBuffer`] descriptors than the
+    /// [`max_descriptor_set_update_after_bind_storage_images`](crate::device::Properties::max_descriptor_set_update_after_bind_storage_images)
+    /// limit.
+    MaxDescriptorSetUpdateAfterBindStorageImagesExceeded { provided: u32, max_supported: u32 },
+
+    /// The `set_layouts` contain more [`DescriptorType::InputAttachment`] descriptors than the
+    /// [`max_descriptor_set_update_after_bind_input_attachments`](crate::device::Properties::max_descriptor_set_update_after_bind_input_attachments)
+    /// limit.
+    MaxDescriptorSetUpdateAfterBindInputAttachmentsExceeded { provided: u32, max_supported: u32 },
+
+    /// The `set_layouts` contain more [`DescriptorType::InlineUniformBlock`] bindings than the
+    /// [`max_descriptor_set_update_after_bind_inline_uniform_blocks`](crate::device::Properties::max_descriptor_set_update_after_bind_inline_uniform_blocks)
+    /// limit.
+    MaxDescriptorSetUpdateAfterBindInlineUniformBlocksExceeded { provided: u32, max_supported: u32 },
+
+    /// The `set_layouts` contain more bound resources in a single stage than the
+    /// [`max_per_stage_update_after_bind_resources`](crate::device::Properties::max_per_stage_update_after_bind_resources)
+    /// limit.
+    MaxPerStageUpdateAfterBindResourcesExceeded { provided: u32, max_supported: u32 },
+
+    /// The `set_layouts` contain more [`DescriptorType::Sampler`] and
+    /// [`DescriptorType::CombinedImageSampler`] descriptors in a single stage than the
+    /// [`max_per_stage_descriptor_update_after_bind_samplers`](crate::device::Properties::max_per_stage_descriptor_update_after_bind_samplers)
+    /// limit.
+    MaxPerStageDescriptorUpdateAfterBindSamplersExceeded { provided: u32, max_supported: u32 },
+
+    /// The `set_layouts` contain more [`DescriptorType::UniformBuffer`] and
+    /// [`DescriptorType::UniformBufferDynamic`] descriptors in a single stage than the
+    /// [`max_per_stage_descriptor_update_after_bind_uniform_buffers`](crate::device::Properties::max_per_stage_descriptor_update_after_bind_uniform_buffers)
+    /// limit.
+    MaxPerStageDescriptorUpdateAfterBindUniformBuffersExceeded { provided: u32, max_supported: u32 },
+
+    /// The `set_layouts` contain more [`DescriptorType::StorageBuffer`] and
+    /// [`DescriptorType::StorageBufferDynamic`] descriptors in a single stage than the
+    /// [`max_per_stage_descriptor_update_after_bind_storage_buffers`](crate::device::Properties::max_per_stage_descriptor_update_after_bind_storage_buffers)
+    /// limit.
+    MaxPerStageDescriptorUpdateAfterBindStorageBuffersExceeded { provided: u32, max_supported: u32 },
+
+    /// The `set_layouts` contain more [`DescriptorType::SampledImage`],
+    /// [`DescriptorType::CombinedImageSampler`] and [`DescriptorType::UniformTexelBuffer`]
+    /// descriptors in a single stage than the
+    /// [`max_per_stage_descriptor_update_after_bind_sampled_images`](crate::device::Properties::max_per_stage_descriptor_update_after_bind_sampled_images)
+    /// limit.
+    MaxPerStageDescriptorUpdateAfterBindSampledImagesExceeded { provided: u32, max_supported: u32 },
+
+    /// The `set_layouts` contain more [`DescriptorType::StorageImage`] and
+    /// [`DescriptorType::StorageTexelBuffer`] descriptors in a single stage than the
+    /// [`max_per_stage_descriptor_update_after_bind_storage_images`](crate::device::Properties::max_per_stage_descriptor_update_after_bind_storage_images)
+    /// limit.
+    MaxPerStageDescriptorUpdateAfterBindStorageImagesExceeded { provided: u32, max_supported: u32 },
+
+    /// The `set_layouts` contain more [`DescriptorType::InputAttachment`] descriptors in a single stage than the
+    /// [`max_per_stage_descriptor_update_after_bind_input_attachments`](crate::device::Properties::max_per_stage_descriptor_update_after_bind_input_attachments)
+    /// limit.
+    MaxPerStageDescriptorUpdateAfterBindInputAttachmentsExceeded {
+        provided: u32,
+        max_supported: u32,
+    },
+
+    /// The `set_layouts` contain more [`DescriptorType::InlineUniformBlock`] bindings in a single
+    /// stage than the
+    /// [`max_per_stage_descriptor_update_after_bind_inline_uniform_blocks`](crate::device::Properties::max_per_stage_descriptor_update_after_bind_inline_uniform_blocks)
+    /// limit.
+    MaxPerStageDescriptorUpdateAfterBindInlineUniformBlocksExceeded {
+        provided: u32,
+        max_supported: u32,
+    },
+
     /// An element in `push_constant_ranges` has an `offset + size` greater than the
     /// [`max_push_constants_size`](crate::device::Properties::max_push_constants_size) limit.
     MaxPushConstantsSizeExceeded { provided: u32, max_supported: u32 },
@@ -928,6 +1255,15 @@ impl Display for PipelineLayoutCreationError {
                 than the `max_descriptor_set_input_attachments` limit ({})",
                 provided, max_supported,
             ),
+            Self::MaxDescriptorSetInlineUniformBlocksExceeded {
+                provided,
+                max_supported,
+            } => write!(
+                f,
+                "the `set_layouts` contain more `DescriptorType::InlineUniformBlock` bindings ({}) \
+                than the `max_descriptor_set_inline_uniform_blocks` limit ({})",
+                provided, max_supported,
+            ),
             Self::MaxPerStageResourcesExceeded {
                 provided,
                 max_supported,
@@ -998,6 +1334,183 @@ impl Display for PipelineLayoutCreationError {
                 ({})",
                 provided, max_supported,
             ),
+            Self::MaxPerStageDescriptorInlineUniformBlocksExceeded {
+                provided,
+                max_supported,
+            } => write!(
+                f,
+                "the `set_layouts` contain more `DescriptorType::InlineUniformBlock` bindings ({}) \
+                in a single stage than the `max_per_stage_descriptor_inline_uniform_blocks` limit \
+                ({})",
+                provided, max_supported,
+            ),
+            Self::MaxDescriptorSetUpdateAfterBindSamplersExceeded {
+                provided,
+                max_supported,
+            } => write!(
+                f,
+                "the `set_layouts` contain more `DescriptorType::Sampler` and \
+                `DescriptorType::CombinedImageSampler` descriptors ({}) than the \
+                `max_descriptor_set_update_after_bind_samplers` limit ({})",
+                provided, max_supported,
+            ),
+            Self::MaxDescriptorSetUpdateAfterBindUniformBuffersExceeded {
+                provided,
+                max_supported,
+            } => write!(
+                f,
+                "the `set_layouts` contain more `DescriptorType::UniformBuffer` descriptors ({}) \
+                than the `max_descriptor_set_update_after_bind_uniform_buffers` limit ({})",
+                provided, max_supported,
+            ),
+            Self::MaxDescriptorSetUpdateAfterBindUniformBuffersDynamicExceeded {
+                provided,
+                max_supported,
+            } => write!(
+                f,
+                "the `set_layouts` contain more `DescriptorType::UniformBufferDynamic` descriptors \
+                ({}) than the `max_descriptor_set_update_after_bind_uniform_buffers_dynamic` limit \
+                ({})",
+                provided, max_supported,
+            ),
+            Self::MaxDescriptorSetUpdateAfterBindStorageBuffersExceeded {
+                provided,
+                max_supported,
+            } => write!(
+                f,
+                "the `set_layouts` contain more `DescriptorType::StorageBuffer` descriptors ({}) \
+                than the `max_descriptor_set_update_after_bind_storage_buffers` limit ({})",
+                provided, max_supported,
+            ),
+            Self::MaxDescriptorSetUpdateAfterBindStorageBuffersDynamicExceeded {
+                provided,
+                max_supported,
+            } => write!(
+                f,
+                "the `set_layouts` contain more `DescriptorType::StorageBufferDynamic` descriptors \
+                ({}) than the `max_descriptor_set_update_after_bind_storage_buffers_dynamic` limit \
+                ({})",
+                provided, max_supported,
+            ),
+            Self::MaxDescriptorSetUpdateAfterBindSampledImagesExceeded {
+                provided,
+                max_supported,
+            } => write!(
+                f,
+                "the `set_layouts` contain more `DescriptorType::SampledImage`, \
+                `DescriptorType::CombinedImageSampler` and `DescriptorType::UniformTexelBuffer` \
+                descriptors ({}) than the `max_descriptor_set_update_after_bind_sampled_images` \
+                limit ({})",
+                provided, max_supported,
+            ),
+            Self::MaxDescriptorSetUpdateAfterBindStorageImagesExceeded {
+                provided,
+                max_supported,
+            } => write!(
+                f,
+                "the `set_layouts` contain more `DescriptorType::StorageImage` and \
+                `DescriptorType::StorageTexelBuffer` descriptors ({}) than the \
+                `max_descriptor_set_update_after_bind_storage_images` limit ({})",
+                provided, max_supported,
+            ),
+            Self::MaxDescriptorSetUpdateAfterBindInputAttachmentsExceeded {
+                provided,
+                max_supported,
+            } => write!(
+                f,
+                "the `set_layouts` contain more `DescriptorType::InputAttachment` descriptors ({}) \
+                than the `max_descriptor_set_update_after_bind_input_attachments` limit ({})",
+                provided, max_supported,
+            ),
+            Self::MaxDescriptorSetUpdateAfterBindInlineUniformBlocksExceeded {
+                provided,
+                max_supported,
+            } => write!(
+                f,
+                "the `set_layouts` contain more `DescriptorType::InlineUniformBlock` bindings ({}) \
+                than the `max_descriptor_set_update_after_bind_inline_uniform_blocks` limit ({})",
+                provided, max_supported,
+            ),
+            Self::MaxPerStageUpdateAfterBindResourcesExceeded {
+                provided,
+                max_supported,
+            } => write!(
+                f,
+                "the `set_layouts` contain more bound resources ({}) in a single stage than the \
+                `max_per_stage_update_after_bind_resources` limit ({})",
+                provided, max_supported,
+            ),
+            Self::MaxPerStageDescriptorUpdateAfterBindSamplersExceeded {
+                provided,
+                max_supported,
+            } => write!(
+                f,
+                "the `set_layouts` contain more `DescriptorType::Sampler` and \
+                `DescriptorType::CombinedImageSampler` descriptors ({}) in a single stage than the \
+                `max_per_stage_descriptor_update_after_bind_samplers` limit ({})",
+                provided, max_supported,
+            ),
+            Self::MaxPerStageDescriptorUpdateAfterBindUniformBuffersExceeded {
+                provided,
+                max_supported,
+            } => write!(
+                f,
+                "the `set_layouts` contain more `DescriptorType::UniformBuffer` and \
+                `DescriptorType::UniformBufferDynamic` descriptors ({}) in a single stage than the \
+                `max_per_stage_descriptor_update_after_bind_uniform_buffers` limit ({})",
+                provided, max_supported,
+            ),
+            Self::MaxPerStageDescriptorUpdateAfterBindStorageBuffersExceeded {
+                provided,
+                max_supported,
+            } => write!(
+                f,
+                "the `set_layouts` contain more `DescriptorType::StorageBuffer` and \
+                `DescriptorType::StorageBufferDynamic` descriptors ({}) in a single stage than the \
+                `max_per_stage_descriptor_update_after_bind_storage_buffers` limit ({})",
+                provided, max_supported,
+            ),
+            Self::MaxPerStageDescriptorUpdateAfterBindSampledImagesExceeded {
+                provided,
+                max_supported,
+            } => write!(
+                f,
+                "the `set_layouts` contain more `DescriptorType::SampledImage`, \
+                `DescriptorType::CombinedImageSampler` and `DescriptorType::UniformTexelBuffer` \
+                descriptors ({}) in a single stage than the \
+                `max_per_stage_descriptor_update_after_bind_sampled_images` limit ({})",
+                provided, max_supported,
+            ),
+            Self::MaxPerStageDescriptorUpdateAfterBindStorageImagesExceeded {
+                provided,
+                max_supported,
+            } => write!(
+                f,
+                "the `set_layouts` contain more `DescriptorType::StorageImage` and \
+                `DescriptorType::StorageTexelBuffer` descriptors ({}) in a single stage than the \
+                `max_per_stage_descriptor_update_after_bind_storage_images` limit ({})",
+                provided, max_supported,
+            ),
+            Self::MaxPerStageDescriptorUpdateAfterBindInputAttachmentsExceeded {
+                provided,
+                max_supported,
+            } => write!(
+                f,
+                "the `set_layouts` contain more `DescriptorType::InputAttachment` descriptors ({}) \
+                in a single stage than the \
+                `max_per_stage_descriptor_update_after_bind_input_attachments` limit ({})",
+                provided, max_supported,
+            ),
+            Self::MaxPerStageDescriptorUpdateAfterBindInlineUniformBlocksExceeded {
+                provided,
+                max_supported,
+            } => write!(
+                f,
+                "the `set_layouts` contain more `DescriptorType::InlineUniformBlock` bindings ({}) \
+                in a single stage than the \
+                `max_per_stage_descriptor_update_after_bind_inline_uniform_blocks` limit ({})",
+                provided, max_supported,
+            ),
             Self::MaxPushConstantsSizeExceeded {
                 provided,
                 max_supported,
@@ -1236,6 +1749,76 @@ impl Counter {
     }
 }
 
+// Helper struct for the main function.
+#[derive(Default)]
+struct DescriptorCounts {
+    resources: Counter,
+    samplers: Counter,
+    uniform_buffers: Counter,
+    uniform_buffers_dynamic: u32,
+    storage_buffers: Counter,
+    storage_buffers_dynamic: u32,
+    sampled_images: Counter,
+    storage_images: Counter,
+    input_attachments: Counter,
+    inline_uniform_blocks: Counter,
+}
+
+impl DescriptorCounts {
+    fn add_set_layout(&mut self, set_layout: &DescriptorSetLayout) {
+        for layout_binding in set_layout.bindings().values() {
+            let count = layout_binding.descriptor_count;
+            let stages = &layout_binding.stages;
+
+            // The descriptor count of an inline uniform block is its size in bytes, while the
+            // limits count whole blocks.
+            if layout_binding.descriptor_type == DescriptorType::InlineUniformBlock {
+                if count != 0 {
+                    self.inline_uniform_blocks.increment(1, stages);
+                }
+
+                continue;
+            }
+
+            self.resources.increment(count, stages);
+
+            match layout_binding.descriptor_type {
+                DescriptorType::Sampler => {
+                    self.samplers.increment(count, stages);
+                }
+                DescriptorType::CombinedImageSampler => {
+                    self.samplers.increment(count, stages);
+                    self.sampled_images.increment(count, stages);
+                }
+                DescriptorType::SampledImage | DescriptorType::UniformTexelBuffer => {
+                    self.sampled_images.increment(count, stages);
+                }
+                DescriptorType::StorageImage | DescriptorType::StorageTexelBuffer => {
+                    self.storage_images.increment(count, stages);
+                }
+                DescriptorType::UniformBuffer => {
+                    self.uniform_buffers.increment(count, stages);
+                }
+                DescriptorType::UniformBufferDynamic => {
+                    self.uniform_buffers.increment(count, stages);
+                    self.uniform_buffers_dynamic += 1;
+                }
+                DescriptorType::StorageBuffer => {
+                    self.storage_buffers.increment(count, stages);
+                }
+                DescriptorType::StorageBufferDynamic => {
+                    self.storage_buffers.increment(count, stages);
+                    self.storage_buffers_dynamic += 1;
+                }
+                DescriptorType::InputAttachment => {
+                    self.input_attachments.increment(count, stages);
+                }
+                DescriptorType::InlineUniformBlock => unreachable!(),
+            }
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
 
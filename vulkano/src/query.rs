@@ -71,6 +71,14 @@ impl QueryPool {
             QueryType::Occlusion | QueryType::Timestamp => {
                 ash::vk::QueryPipelineStatisticFlags::empty()
             }
+            QueryType::TransformFeedbackStream => {
+                // VUID-VkQueryPoolCreateInfo-queryType-parameter
+                if !device.enabled_extensions().ext_transform_feedback {
+                    return Err(QueryPoolCreationError::TransformFeedbackExtensionNotEnabled);
+                }
+
+                ash::vk::QueryPipelineStatisticFlags::empty()
+            }
         };
 
         let create_info = ash::vk::QueryPoolCreateInfo {
@@ -246,6 +254,9 @@ pub enum QueryPoolCreationError {
     OomError(OomError),
     /// A pipeline statistics pool was requested but the corresponding feature wasn't enabled.
     PipelineStatisticsQueryFeatureNotEnabled,
+    /// A transform feedback stream pool was requested but the corresponding extension wasn't
+    /// enabled.
+    TransformFeedbackExtensionNotEnabled,
 }
 
 impl Error for QueryPoolCreationError {
@@ -268,6 +279,10 @@ impl Display for QueryPoolCreationError {
                     "a pipeline statistics pool was requested but the corresponding feature \
                     wasn't enabled"
                 }
+                QueryPoolCreationError::TransformFeedbackExtensionNotEnabled => {
+                    "a transform feedback stream pool was requested but the corresponding \
+                    extension wasn't enabled"
+                }
             }
         )
     }
@@ -420,6 +435,7 @@ impl<'a> QueriesRange<'a> {
         match self.pool.query_type {
             QueryType::Occlusion => (),
             QueryType::PipelineStatistics(_) => (),
+            QueryType::TransformFeedbackStream => (),
             QueryType::Timestamp => {
                 // VUID-vkGetQueryPoolResults-queryType-00818
                 if flags.partial {
@@ -535,6 +551,7 @@ unsafe impl QueryResultElement for u64 {
 
 /// The type of query that a query pool should perform.
 #[derive(Debug, Copy, Clone)]
+#[non_exhaustive]
 pub enum QueryType {
     /// Tracks the number of samples that pass per-fragment tests (e.g. the depth test).
     Occlusion,
@@ -542,6 +559,12 @@ pub enum QueryType {
     PipelineStatistics(QueryPipelineStatisticFlags),
     /// Writes timestamps at chosen points in a command buffer.
     Timestamp,
+    /// Tracks the number of primitives that were written to the transform feedback buffers, and
+    /// the number of primitives that would have been written if the buffers were large enough.
+    ///
+    /// The [`ext_transform_feedback`](crate::device::DeviceExtensions::ext_transform_feedback)
+    /// extension must be enabled on the device.
+    TransformFeedbackStream,
 }
 
 impl QueryType {
@@ -550,6 +573,7 @@ impl QueryType {
     ///
     /// - For `Occlusion` and `Timestamp` queries, this returns 1.
     /// - For `PipelineStatistics` queries, this returns the number of statistics flags enabled.
+    /// - For `TransformFeedbackStream` queries, this returns 2.
     ///
     /// If the results are retrieved with [`QueryResultFlags::with_availability`] enabled, then
     /// an additional element is required per query.
@@ -558,6 +582,7 @@ impl QueryType {
         match self {
             Self::Occlusion | Self::Timestamp => 1,
             Self::PipelineStatistics(flags) => flags.count(),
+            Self::TransformFeedbackStream => 2,
         }
     }
 }
@@ -569,6 +594,7 @@ impl From<QueryType> for ash::vk::QueryType {
             QueryType::Occlusion => ash::vk::QueryType::OCCLUSION,
             QueryType::PipelineStatistics(_) => ash::vk::QueryType::PIPELINE_STATISTICS,
             QueryType::Timestamp => ash::vk::QueryType::TIMESTAMP,
+            QueryType::TransformFeedbackStream => ash::vk::QueryType::TRANSFORM_FEEDBACK_STREAM_EXT,
         }
     }
 }
@@ -14,6 +14,7 @@ use super::{
 use crate::{
     device::Device,
     image::{ImageLayout, SampleCount},
+    pipeline::graphics::fragment_shading_rate::is_valid_attachment_texel_size,
     sync::PipelineStages,
     OomError, RequirementNotMet, RequiresOneOf, Version, VulkanError, VulkanObject,
 };
@@ -37,6 +38,7 @@ impl RenderPass {
             subpasses,
             dependencies,
             correlated_view_masks,
+            name: _,
             _ne: _,
         } = create_info;
 
@@ -163,6 +165,8 @@ impl RenderPass {
                 ref color_attachments,
                 ref resolve_attachments,
                 ref depth_stencil_attachment,
+                ref fragment_shading_rate_attachment,
+                fragment_shading_rate_attachment_texel_size,
                 ref preserve_attachments,
                 _ne: _,
             } = subpass;
@@ -590,6 +594,76 @@ impl RenderPass {
                 }
             }
 
+            /*
+                Check fragment shading rate attachment
+            */
+
+            if let Some(atch_ref) = fragment_shading_rate_attachment.as_ref() {
+                // VUID?
+                if !device.enabled_features().attachment_fragment_shading_rate {
+                    return Err(RenderPassCreationError::RequirementNotMet {
+                        required_for: "`create_info.subpasses` has an element where `fragment_shading_rate_attachment` is `Some`",
+                        requires_one_of: RequiresOneOf {
+                            features: &["attachment_fragment_shading_rate"],
+                            ..Default::default()
+                        },
+                    });
+                }
+
+                let (_atch, features, _first_use) = check_attachment(atch_ref)?;
+
+                // VUID?
+                if !features.fragment_shading_rate_attachment {
+                    return Err(
+                        RenderPassCreationError::SubpassAttachmentFormatUsageNotSupported {
+                            subpass: subpass_num,
+                            attachment: atch_ref.attachment,
+                            usage: "fragment shading rate",
+                        },
+                    );
+                }
+
+                // VUID-VkFragmentShadingRateAttachmentInfoKHR-pFragmentShadingRateAttachment-04524
+                if !matches!(
+                    atch_ref.layout,
+                    ImageLayout::General | ImageLayout::FragmentShadingRateAttachmentOptimal
+                ) {
+                    return Err(RenderPassCreationError::SubpassAttachmentLayoutInvalid {
+                        subpass: subpass_num,
+                        attachment: atch_ref.attachment,
+                        usage: "fragment shading rate",
+                    });
+                }
+
+                // Not required by spec, but enforced by Vulkano for sanity.
+                if !atch_ref.aspects.is_empty() {
+                    return Err(RenderPassCreationError::SubpassAttachmentAspectsNotEmpty {
+                        subpass: subpass_num,
+                        attachment: atch_ref.attachment,
+                    });
+                }
+
+                // VUID-VkFragmentShadingRateAttachmentInfoKHR-pFragmentShadingRateAttachment-04525
+                // VUID-VkFragmentShadingRateAttachmentInfoKHR-pFragmentShadingRateAttachment-04526
+                // VUID-VkFragmentShadingRateAttachmentInfoKHR-pFragmentShadingRateAttachment-04527
+                // VUID-VkFragmentShadingRateAttachmentInfoKHR-pFragmentShadingRateAttachment-04528
+                // VUID-VkFragmentShadingRateAttachmentInfoKHR-pFragmentShadingRateAttachment-04529
+                // VUID-VkFragmentShadingRateAttachmentInfoKHR-pFragmentShadingRateAttachment-04530
+                // VUID-VkFragmentShadingRateAttachmentInfoKHR-pFragmentShadingRateAttachment-04531
+                // VUID-VkFragmentShadingRateAttachmentInfoKHR-pFragmentShadingRateAttachment-04532
+                if !is_valid_attachment_texel_size(
+                    properties,
+                    fragment_shading_rate_attachment_texel_size,
+                ) {
+                    return Err(
+                        RenderPassCreationError::SubpassFragmentShadingRateAttachmentTexelSizeNotSupported {
+                            subpass: subpass_num,
+                            texel_size: fragment_shading_rate_attachment_texel_size,
+                        },
+                    );
+                }
+            }
+
             /*
                 Check preserve attachments
             */
@@ -963,6 +1037,7 @@ impl RenderPass {
             subpasses,
             dependencies,
             correlated_view_masks,
+            name: _,
             _ne: _,
         } = create_info;
 
@@ -1010,13 +1085,46 @@ impl RenderPass {
             })
             .collect::<SmallVec<[_; 8]>>();
 
+        let fragment_shading_rate_attachment_references_vk = subpasses
+            .iter()
+            .map(|subpass| {
+                subpass
+                    .fragment_shading_rate_attachment
+                    .as_ref()
+                    .map(|atch_ref| ash::vk::AttachmentReference2 {
+                        attachment: atch_ref.attachment,
+                        layout: atch_ref.layout.into(),
+                        aspect_mask: atch_ref.aspects.into(),
+                        ..Default::default()
+                    })
+            })
+            .collect::<SmallVec<[_; 4]>>();
+
+        let fragment_shading_rate_attachment_infos_vk = subpasses
+            .iter()
+            .zip(&fragment_shading_rate_attachment_references_vk)
+            .map(|(subpass, atch_ref_vk)| {
+                atch_ref_vk.as_ref().map(|atch_ref_vk| {
+                    ash::vk::FragmentShadingRateAttachmentInfoKHR {
+                        p_fragment_shading_rate_attachment: atch_ref_vk,
+                        shading_rate_attachment_texel_size: ash::vk::Extent2D {
+                            width: subpass.fragment_shading_rate_attachment_texel_size[0],
+                            height: subpass.fragment_shading_rate_attachment_texel_size[1],
+                        },
+                        ..Default::default()
+                    }
+                })
+            })
+            .collect::<SmallVec<[_; 4]>>();
+
         let subpasses_vk = {
             // `ref_index` is increased during the loop and points to the next element to use
             // in `attachment_references_vk`.
             let mut ref_index = 0usize;
             let out: SmallVec<[_; 4]> = subpasses
                 .iter()
-                .map(|subpass| {
+                .zip(&fragment_shading_rate_attachment_infos_vk)
+                .map(|(subpass, fragment_shading_rate_attachment_info_vk)| {
                     let input_attachments = attachment_references_vk.as_ptr().add(ref_index);
                     ref_index += subpass.input_attachments.len();
                     let color_attachments = attachment_references_vk.as_ptr().add(ref_index);
@@ -1032,6 +1140,9 @@ impl RenderPass {
                     };
 
                     ash::vk::SubpassDescription2 {
+                        p_next: fragment_shading_rate_attachment_info_vk
+                            .as_ref()
+                            .map_or(ptr::null(), |info| info as *const _ as *const _),
                         flags: ash::vk::SubpassDescriptionFlags::empty(),
                         pipeline_bind_point: ash::vk::PipelineBindPoint::GRAPHICS, // TODO: any need to make this user-specifiable?
                         view_mask: subpass.view_mask,
@@ -1162,6 +1273,7 @@ impl RenderPass {
             subpasses,
             dependencies,
             correlated_view_masks,
+            name: _,
             _ne: _,
         } = create_info;
 
@@ -1525,6 +1637,10 @@ pub enum RenderPassCreationError {
         first_samples: SampleCount,
     },
 
+    /// The `fragment_shading_rate_attachment_texel_size` of a subpass with a fragment shading
+    /// rate attachment is not supported by the device.
+    SubpassFragmentShadingRateAttachmentTexelSizeNotSupported { subpass: u32, texel_size: [u32; 2] },
+
     /// A reference to an attachment used as an input attachment in a subpass selects aspects that
     /// are not present in the format of the attachment.
     SubpassInputAttachmentAspectsNotCompatible { subpass: u32, attachment: u32 },
@@ -1777,6 +1893,15 @@ impl Display for RenderPassCreationError {
                 `samples` value {:?} that is different from the first color attachment ({:?})",
                 attachment, subpass, samples, first_samples,
             ),
+            Self::SubpassFragmentShadingRateAttachmentTexelSizeNotSupported {
+                subpass,
+                texel_size,
+            } => write!(
+                f,
+                "the fragment shading rate attachment texel size {:?} of subpass {} is not \
+                supported by the device",
+                texel_size, subpass,
+            ),
             Self::SubpassInputAttachmentAspectsNotCompatible {
                 subpass,
                 attachment,
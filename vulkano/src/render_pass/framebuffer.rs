@@ -11,9 +11,13 @@ use super::RenderPass;
 use crate::{
     device::{Device, DeviceOwned},
     format::Format,
-    image::{view::ImageViewType, ImageDimensions, ImageViewAbstract, SampleCount},
-    OomError, VulkanError, VulkanObject,
+    image::{
+        view::ImageViewType, ImageCreateFlags, ImageDimensions, ImageUsage, ImageViewAbstract,
+        SampleCount,
+    },
+    OomError, RequirementNotMet, RequiresOneOf, VulkanError, VulkanObject,
 };
+use ash::vk::Handle;
 use smallvec::SmallVec;
 use std::{
     error::Error,
@@ -55,8 +59,10 @@ pub struct Framebuffer {
     render_pass: Arc<RenderPass>,
 
     attachments: Vec<Arc<dyn ImageViewAbstract>>,
+    attachment_image_infos: Vec<FramebufferAttachmentImageInfo>,
     extent: [u32; 2],
     layers: u32,
+    name: Option<String>,
 }
 
 impl Framebuffer {
@@ -67,17 +73,57 @@ impl Framebuffer {
     ) -> Result<Arc<Framebuffer>, FramebufferCreationError> {
         let FramebufferCreateInfo {
             attachments,
+            attachment_image_infos,
             mut extent,
             mut layers,
+            name,
             _ne: _,
         } = create_info;
 
         let device = render_pass.device();
+        let imageless = !attachment_image_infos.is_empty();
+
+        if imageless {
+            // VUID-VkFramebufferCreateInfo-flags-03189
+            if !device.enabled_features().imageless_framebuffer {
+                return Err(FramebufferCreationError::RequirementNotMet {
+                    required_for: "`create_info.attachment_image_infos` is not empty",
+                    requires_one_of: RequiresOneOf {
+                        features: &["imageless_framebuffer"],
+                        ..Default::default()
+                    },
+                });
+            }
+
+            if !attachments.is_empty() {
+                return Err(FramebufferCreationError::ImagelessAttachmentsNotEmpty);
+            }
+
+            for image_info in &attachment_image_infos {
+                // VUID-VkFramebufferAttachmentImageInfo-flags-parameter
+                image_info.flags.validate_device(device)?;
+
+                // VUID-VkFramebufferAttachmentImageInfo-usage-parameter
+                image_info.usage.validate_device(device)?;
+
+                // VUID-VkFramebufferAttachmentImageInfo-pViewFormats-parameter
+                for format in &image_info.view_formats {
+                    format.validate_device(device)?;
+                }
+            }
+        }
+
+        let attachment_count = if imageless {
+            attachment_image_infos.len()
+        } else {
+            attachments.len()
+        };
 
         // VUID-VkFramebufferCreateInfo-attachmentCount-00876
-        if attachments.len() != render_pass.attachments().len() {
+        // VUID-VkFramebufferCreateInfo-flags-03191
+        if attachment_count != render_pass.attachments().len() {
             return Err(FramebufferCreationError::AttachmentCountMismatch {
-                provided: attachments.len() as u32,
+                provided: attachment_count as u32,
                 required: render_pass.attachments().len() as u32,
             });
         }
@@ -88,7 +134,7 @@ impl Framebuffer {
         // VUID-VkFramebufferCreateInfo-width-00885
         // VUID-VkFramebufferCreateInfo-height-00887
         if auto_extent {
-            if attachments.is_empty() {
+            if attachment_count == 0 {
                 return Err(FramebufferCreationError::AutoExtentAttachmentsEmpty);
             }
 
@@ -97,7 +143,7 @@ impl Framebuffer {
 
         // VUID-VkFramebufferCreateInfo-layers-00889
         if auto_layers {
-            if attachments.is_empty() {
+            if attachment_count == 0 {
                 return Err(FramebufferCreationError::AutoLayersAttachmentsEmpty);
             }
 
@@ -114,160 +160,228 @@ impl Framebuffer {
             }
         }
 
-        let attachments_vk = attachments
-            .iter()
-            .zip(render_pass.attachments())
-            .enumerate()
-            .map(|(attachment_num, (image_view, attachment_desc))| {
-                let attachment_num = attachment_num as u32;
-                assert_eq!(device, image_view.device());
-
-                for subpass in render_pass.subpasses() {
-                    // VUID-VkFramebufferCreateInfo-pAttachments-00877
-                    if subpass
-                        .color_attachments
-                        .iter()
-                        .flatten()
-                        .any(|atch_ref| atch_ref.attachment == attachment_num)
-                    {
-                        if !image_view.usage().color_attachment {
+        let mut attachments_vk: SmallVec<[_; 4]> = SmallVec::new();
+
+        for (attachment_num, attachment_desc) in render_pass.attachments().iter().enumerate() {
+            let image_view = attachments.get(attachment_num);
+            let image_info = attachment_image_infos.get(attachment_num);
+            let attachment_num = attachment_num as u32;
+
+            let (usage, mut attachment_extent, attachment_array_layers) =
+                match (image_view, image_info) {
+                    (Some(image_view), _) => {
+                        assert_eq!(device, image_view.device());
+                        let array_layers = &image_view.subresource_range().array_layers;
+
+                        (
+                            *image_view.usage(),
+                            image_view.image().dimensions().width_height(),
+                            array_layers.end - array_layers.start,
+                        )
+                    }
+                    (None, Some(image_info)) => {
+                        (image_info.usage, image_info.extent, image_info.array_layers)
+                    }
+                    (None, None) => unreachable!(),
+                };
+
+            for subpass in render_pass.subpasses() {
+                // VUID-VkFramebufferCreateInfo-pAttachments-00877
+                // VUID-VkFramebufferCreateInfo-flags-03201
+                if subpass
+                    .color_attachments
+                    .iter()
+                    .flatten()
+                    .any(|atch_ref| atch_ref.attachment == attachment_num)
+                {
+                    if !usage.color_attachment {
+                        return Err(FramebufferCreationError::AttachmentMissingUsage {
+                            attachment: attachment_num,
+                            usage: "color_attachment",
+                        });
+                    }
+                }
+
+                // VUID-VkFramebufferCreateInfo-pAttachments-02633
+                // VUID-VkFramebufferCreateInfo-flags-03202
+                if let Some(atch_ref) = &subpass.depth_stencil_attachment {
+                    if atch_ref.attachment == attachment_num {
+                        if !usage.depth_stencil_attachment {
                             return Err(FramebufferCreationError::AttachmentMissingUsage {
                                 attachment: attachment_num,
-                                usage: "color_attachment",
+                                usage: "depth_stencil",
                             });
                         }
                     }
+                }
 
-                    // VUID-VkFramebufferCreateInfo-pAttachments-02633
-                    if let Some(atch_ref) = &subpass.depth_stencil_attachment {
-                        if atch_ref.attachment == attachment_num {
-                            if !image_view.usage().depth_stencil_attachment {
-                                return Err(FramebufferCreationError::AttachmentMissingUsage {
-                                    attachment: attachment_num,
-                                    usage: "depth_stencil",
-                                });
-                            }
-                        }
+                // VUID-VkFramebufferCreateInfo-pAttachments-00879
+                // VUID-VkFramebufferCreateInfo-flags-03204
+                if subpass
+                    .input_attachments
+                    .iter()
+                    .flatten()
+                    .any(|atch_ref| atch_ref.attachment == attachment_num)
+                {
+                    if !usage.input_attachment {
+                        return Err(FramebufferCreationError::AttachmentMissingUsage {
+                            attachment: attachment_num,
+                            usage: "input_attachment",
+                        });
                     }
+                }
 
-                    // VUID-VkFramebufferCreateInfo-pAttachments-00879
-                    if subpass
-                        .input_attachments
-                        .iter()
-                        .flatten()
-                        .any(|atch_ref| atch_ref.attachment == attachment_num)
-                    {
-                        if !image_view.usage().input_attachment {
+                // VUID-VkFramebufferCreateInfo-flags-04548
+                // VUID-VkFramebufferCreateInfo-flags-04549
+                if let Some(atch_ref) = &subpass.fragment_shading_rate_attachment {
+                    if atch_ref.attachment == attachment_num {
+                        if !usage.fragment_shading_rate_attachment {
                             return Err(FramebufferCreationError::AttachmentMissingUsage {
                                 attachment: attachment_num,
-                                usage: "input_attachment",
+                                usage: "fragment_shading_rate_attachment",
                             });
                         }
                     }
                 }
+            }
 
-                // VUID-VkFramebufferCreateInfo-pAttachments-00880
-                if image_view.format() != attachment_desc.format {
-                    return Err(FramebufferCreationError::AttachmentFormatMismatch {
-                        attachment: attachment_num,
-                        provided: image_view.format(),
-                        required: attachment_desc.format,
-                    });
-                }
+            // A fragment shading rate attachment covers an area of the framebuffer that is
+            // larger by a factor of the texel size.
+            if let Some(texel_size) = render_pass.subpasses().iter().find_map(|subpass| {
+                subpass
+                    .fragment_shading_rate_attachment
+                    .as_ref()
+                    .filter(|atch_ref| atch_ref.attachment == attachment_num)
+                    .map(|_| subpass.fragment_shading_rate_attachment_texel_size)
+            }) {
+                attachment_extent[0] = attachment_extent[0].saturating_mul(texel_size[0]);
+                attachment_extent[1] = attachment_extent[1].saturating_mul(texel_size[1]);
+            }
 
-                // VUID-VkFramebufferCreateInfo-pAttachments-00881
-                if image_view.image().samples() != attachment_desc.samples {
-                    return Err(FramebufferCreationError::AttachmentSamplesMismatch {
+            // VUID-VkFramebufferCreateInfo-renderPass-04536
+            // VUID-VkFramebufferCreateInfo-renderPass-03198
+            if attachment_array_layers < render_pass.views_used() {
+                return Err(
+                    FramebufferCreationError::MultiviewAttachmentNotEnoughLayers {
                         attachment: attachment_num,
-                        provided: image_view.image().samples(),
-                        required: attachment_desc.samples,
-                    });
-                }
+                        provided: attachment_array_layers,
+                        min: render_pass.views_used(),
+                    },
+                );
+            }
 
-                let image_view_extent = image_view.image().dimensions().width_height();
-                let image_view_array_layers = image_view.subresource_range().array_layers.end
-                    - image_view.subresource_range().array_layers.start;
+            // VUID-VkFramebufferCreateInfo-flags-04533
+            // VUID-VkFramebufferCreateInfo-flags-04534
+            // VUID-VkFramebufferCreateInfo-flags-03192
+            // VUID-VkFramebufferCreateInfo-flags-03193
+            if auto_extent {
+                extent[0] = extent[0].min(attachment_extent[0]);
+                extent[1] = extent[1].min(attachment_extent[1]);
+            } else if attachment_extent[0] < extent[0] || attachment_extent[1] < extent[1] {
+                return Err(FramebufferCreationError::AttachmentExtentTooSmall {
+                    attachment: attachment_num,
+                    provided: attachment_extent,
+                    min: extent,
+                });
+            }
 
-                // VUID-VkFramebufferCreateInfo-renderPass-04536
-                if image_view_array_layers < render_pass.views_used() {
-                    return Err(
-                        FramebufferCreationError::MultiviewAttachmentNotEnoughLayers {
-                            attachment: attachment_num,
-                            provided: image_view_array_layers,
-                            min: render_pass.views_used(),
-                        },
-                    );
-                }
+            // VUID-VkFramebufferCreateInfo-flags-04535
+            // VUID-VkFramebufferCreateInfo-flags-03194
+            if auto_layers {
+                layers = layers.min(attachment_array_layers);
+            } else if attachment_array_layers < layers {
+                return Err(FramebufferCreationError::AttachmentNotEnoughLayers {
+                    attachment: attachment_num,
+                    provided: attachment_array_layers,
+                    min: layers,
+                });
+            }
 
-                // VUID-VkFramebufferCreateInfo-flags-04533
-                // VUID-VkFramebufferCreateInfo-flags-04534
-                if auto_extent {
-                    extent[0] = extent[0].min(image_view_extent[0]);
-                    extent[1] = extent[1].min(image_view_extent[1]);
-                } else if image_view_extent[0] < extent[0] || image_view_extent[1] < extent[1] {
-                    return Err(FramebufferCreationError::AttachmentExtentTooSmall {
-                        attachment: attachment_num,
-                        provided: image_view_extent,
-                        min: extent,
-                    });
-                }
+            let image_view = match image_view {
+                Some(x) => x,
+                None => {
+                    let image_info = image_info.unwrap();
 
-                // VUID-VkFramebufferCreateInfo-flags-04535
-                if auto_layers {
-                    layers = layers.min(image_view_array_layers);
-                } else if image_view_array_layers < layers {
-                    return Err(FramebufferCreationError::AttachmentNotEnoughLayers {
-                        attachment: attachment_num,
-                        provided: image_view_array_layers,
-                        min: layers,
-                    });
-                }
+                    // VUID-VkFramebufferCreateInfo-flags-03205
+                    if !image_info
+                        .view_formats
+                        .iter()
+                        .any(|&format| Some(format) == attachment_desc.format)
+                    {
+                        return Err(
+                            FramebufferCreationError::AttachmentViewFormatsMissingFormat {
+                                attachment: attachment_num,
+                                required: attachment_desc.format,
+                            },
+                        );
+                    }
 
-                // VUID-VkFramebufferCreateInfo-pAttachments-00883
-                if image_view.subresource_range().mip_levels.end
-                    - image_view.subresource_range().mip_levels.start
-                    != 1
-                {
-                    return Err(FramebufferCreationError::AttachmentMultipleMipLevels {
-                        attachment: attachment_num,
-                    });
+                    continue;
                 }
+            };
+
+            // VUID-VkFramebufferCreateInfo-pAttachments-00880
+            if image_view.format() != attachment_desc.format {
+                return Err(FramebufferCreationError::AttachmentFormatMismatch {
+                    attachment: attachment_num,
+                    provided: image_view.format(),
+                    required: attachment_desc.format,
+                });
+            }
 
-                // VUID-VkFramebufferCreateInfo-pAttachments-00884
-                if !image_view.component_mapping().is_identity() {
-                    return Err(
-                        FramebufferCreationError::AttachmentComponentMappingNotIdentity {
-                            attachment: attachment_num,
-                        },
-                    );
-                }
+            // VUID-VkFramebufferCreateInfo-pAttachments-00881
+            if image_view.image().samples() != attachment_desc.samples {
+                return Err(FramebufferCreationError::AttachmentSamplesMismatch {
+                    attachment: attachment_num,
+                    provided: image_view.image().samples(),
+                    required: attachment_desc.samples,
+                });
+            }
 
-                // VUID-VkFramebufferCreateInfo-pAttachments-00891
-                if matches!(
-                    image_view.view_type(),
-                    ImageViewType::Dim2d | ImageViewType::Dim2dArray
-                ) && matches!(
-                    image_view.image().dimensions(),
-                    ImageDimensions::Dim3d { .. }
-                ) && image_view.format().unwrap().type_color().is_none()
-                {
-                    return Err(
-                        FramebufferCreationError::Attachment2dArrayCompatibleDepthStencil {
-                            attachment: attachment_num,
-                        },
-                    );
-                }
+            // VUID-VkFramebufferCreateInfo-pAttachments-00883
+            if image_view.subresource_range().mip_levels.end
+                - image_view.subresource_range().mip_levels.start
+                != 1
+            {
+                return Err(FramebufferCreationError::AttachmentMultipleMipLevels {
+                    attachment: attachment_num,
+                });
+            }
 
-                // VUID-VkFramebufferCreateInfo-flags-04113
-                if image_view.view_type() == ImageViewType::Dim3d {
-                    return Err(FramebufferCreationError::AttachmentViewType3d {
+            // VUID-VkFramebufferCreateInfo-pAttachments-00884
+            if !image_view.component_mapping().is_identity() {
+                return Err(
+                    FramebufferCreationError::AttachmentComponentMappingNotIdentity {
                         attachment: attachment_num,
-                    });
-                }
+                    },
+                );
+            }
 
-                Ok(image_view.internal_object())
-            })
-            .collect::<Result<SmallVec<[_; 4]>, _>>()?;
+            // VUID-VkFramebufferCreateInfo-pAttachments-00891
+            if matches!(
+                image_view.view_type(),
+                ImageViewType::Dim2d | ImageViewType::Dim2dArray
+            ) && matches!(
+                image_view.image().dimensions(),
+                ImageDimensions::Dim3d { .. }
+            ) && image_view.format().unwrap().type_color().is_none()
+            {
+                return Err(
+                    FramebufferCreationError::Attachment2dArrayCompatibleDepthStencil {
+                        attachment: attachment_num,
+                    },
+                );
+            }
+
+            // VUID-VkFramebufferCreateInfo-flags-04113
+            if image_view.view_type() == ImageViewType::Dim3d {
+                return Err(FramebufferCreationError::AttachmentViewType3d {
+                    attachment: attachment_num,
+                });
+            }
+
+            attachments_vk.push(image_view.internal_object());
+        }
 
         {
             let properties = device.physical_device().properties();
@@ -295,7 +409,7 @@ impl Framebuffer {
             }
         }
 
-        let create_info = ash::vk::FramebufferCreateInfo {
+        let mut create_info = ash::vk::FramebufferCreateInfo {
             flags: ash::vk::FramebufferCreateFlags::empty(),
             render_pass: render_pass.internal_object(),
             attachment_count: attachments_vk.len() as u32,
@@ -306,6 +420,57 @@ impl Framebuffer {
             ..Default::default()
         };
 
+        let view_formats_vk: SmallVec<[SmallVec<[_; 4]>; 4]> = attachment_image_infos
+            .iter()
+            .map(|image_info| {
+                image_info
+                    .view_formats
+                    .iter()
+                    .copied()
+                    .map(ash::vk::Format::from)
+                    .collect()
+            })
+            .collect();
+        let attachment_image_infos_vk: SmallVec<[_; 4]> = attachment_image_infos
+            .iter()
+            .zip(&view_formats_vk)
+            .map(|(image_info, view_formats_vk)| {
+                let &FramebufferAttachmentImageInfo {
+                    flags,
+                    usage,
+                    extent,
+                    array_layers,
+                    view_formats: _,
+                    _ne: _,
+                } = image_info;
+
+                ash::vk::FramebufferAttachmentImageInfo {
+                    flags: flags.into(),
+                    usage: usage.into(),
+                    width: extent[0],
+                    height: extent[1],
+                    layer_count: array_layers,
+                    view_format_count: view_formats_vk.len() as u32,
+                    p_view_formats: view_formats_vk.as_ptr(),
+                    ..Default::default()
+                }
+            })
+            .collect();
+        let attachments_create_info_vk;
+
+        if imageless {
+            attachments_create_info_vk = ash::vk::FramebufferAttachmentsCreateInfo {
+                attachment_image_info_count: attachment_image_infos_vk.len() as u32,
+                p_attachment_image_infos: attachment_image_infos_vk.as_ptr(),
+                ..Default::default()
+            };
+
+            create_info.flags |= ash::vk::FramebufferCreateFlags::IMAGELESS;
+            create_info.attachment_count = attachment_image_infos_vk.len() as u32;
+            create_info.p_attachments = ptr::null();
+            create_info.p_next = &attachments_create_info_vk as *const _ as *const _;
+        }
+
         let handle = unsafe {
             let fns = device.fns();
             let mut output = MaybeUninit::uninit();
@@ -320,14 +485,30 @@ impl Framebuffer {
             output.assume_init()
         };
 
-        Ok(Arc::new(Framebuffer {
+        let framebuffer = Arc::new(Framebuffer {
             handle,
             render_pass,
 
             attachments,
+            attachment_image_infos,
             extent,
             layers,
-        }))
+            name,
+        });
+
+        if let Some(name) = framebuffer.name.as_deref() {
+            unsafe {
+                framebuffer
+                    .device()
+                    .set_debug_utils_object_name_if_enabled(
+                        ash::vk::ObjectType::FRAMEBUFFER,
+                        framebuffer.handle.as_raw(),
+                        Some(name),
+                    )?;
+            }
+        }
+
+        Ok(framebuffer)
     }
 
     /// Creates a new `Framebuffer` from a raw object handle.
@@ -344,8 +525,10 @@ impl Framebuffer {
     ) -> Arc<Framebuffer> {
         let FramebufferCreateInfo {
             attachments,
+            attachment_image_infos,
             extent,
             layers,
+            name,
             _ne: _,
         } = create_info;
 
@@ -354,8 +537,10 @@ impl Framebuffer {
             render_pass,
 
             attachments,
+            attachment_image_infos,
             extent,
             layers,
+            name,
         })
     }
     /// Returns the renderpass that was used to create this framebuffer.
@@ -365,11 +550,32 @@ impl Framebuffer {
     }
 
     /// Returns the attachments of the framebuffer.
+    ///
+    /// If the framebuffer is [imageless](Self::is_imageless), it has no attachments of its own,
+    /// and this returns an empty slice. The attachments are instead provided each time a render
+    /// pass is begun, in
+    /// [`RenderPassBeginInfo::attachments`](crate::command_buffer::RenderPassBeginInfo::attachments),
+    /// and [`attachment_image_infos`](Self::attachment_image_infos) describes them.
     #[inline]
     pub fn attachments(&self) -> &[Arc<dyn ImageViewAbstract>] {
         &self.attachments
     }
 
+    /// Returns the attachment image infos of the framebuffer.
+    ///
+    /// This is empty if the framebuffer is not imageless.
+    #[inline]
+    pub fn attachment_image_infos(&self) -> &[FramebufferAttachmentImageInfo] {
+        &self.attachment_image_infos
+    }
+
+    /// Returns whether the framebuffer is imageless, meaning that the attachment image views are
+    /// only provided when beginning a render pass.
+    #[inline]
+    pub fn is_imageless(&self) -> bool {
+        !self.attachment_image_infos.is_empty()
+    }
+
     /// Returns the extent (width and height) of the framebuffer.
     #[inline]
     pub fn extent(&self) -> [u32; 2] {
@@ -383,6 +589,8 @@ impl Framebuffer {
     }
 
     /// Returns the layer ranges for all attachments.
+    ///
+    /// Like [`attachments`](Self::attachments), this is empty if the framebuffer is imageless.
     #[inline]
     pub fn attached_layers_ranges(&self) -> SmallVec<[Range<u32>; 4]> {
         self.attachments
@@ -390,6 +598,12 @@ impl Framebuffer {
             .map(|img| img.subresource_range().array_layers.clone())
             .collect()
     }
+
+    /// Returns the name that the framebuffer was created with, if any.
+    #[inline]
+    pub fn name(&self) -> Option<&str> {
+        self.name.as_deref()
+    }
 }
 
 impl Drop for Framebuffer {
@@ -455,9 +669,28 @@ pub struct FramebufferCreateInfo {
     /// If the render pass has multiview enabled (`views_used` does not return 0), then each
     /// image must have at least `views_used` array layers.
     ///
+    /// If `attachment_image_infos` is not empty, then this must be empty.
+    ///
     /// The default value is empty.
     pub attachments: Vec<Arc<dyn ImageViewAbstract>>,
 
+    /// Descriptions of the attachment images that will be used with the framebuffer, for an
+    /// imageless framebuffer.
+    ///
+    /// If this is not empty, the framebuffer is created without any image views, and the image
+    /// views are instead provided in [`RenderPassBeginInfo::attachments`] each time a render pass
+    /// is begun. The same rules apply to these descriptions as to the image views in
+    /// `attachments`, and the image views that are provided later must match them.
+    ///
+    /// If this is not empty, then `attachments` must be empty, and the
+    /// [`imageless_framebuffer`](crate::device::Features::imageless_framebuffer) feature must be
+    /// enabled on the device.
+    ///
+    /// The default value is empty.
+    ///
+    /// [`RenderPassBeginInfo::attachments`]: crate::command_buffer::RenderPassBeginInfo::attachments
+    pub attachment_image_infos: Vec<FramebufferAttachmentImageInfo>,
+
     /// The extent (width and height) of the framebuffer.
     ///
     /// This must be no larger than the smallest width and height of the images in `attachments`.
@@ -489,6 +722,15 @@ pub struct FramebufferCreateInfo {
     /// The default value is `0`.
     pub layers: u32,
 
+    /// A name for the framebuffer.
+    ///
+    /// If the `ext_debug_utils` extension is enabled on the instance, the name is assigned to the
+    /// framebuffer with [`Device::set_debug_utils_object_name`], so that debugging tools show it.
+    /// Otherwise, this doesn't require the extension.
+    ///
+    /// The default value is `None`.
+    pub name: Option<String>,
+
     pub _ne: crate::NonExhaustive,
 }
 
@@ -497,8 +739,75 @@ impl Default for FramebufferCreateInfo {
     fn default() -> Self {
         Self {
             attachments: Vec::new(),
+            attachment_image_infos: Vec::new(),
             extent: [0, 0],
             layers: 0,
+            name: None,
+            _ne: crate::NonExhaustive(()),
+        }
+    }
+}
+
+/// Describes an attachment image of an imageless framebuffer.
+#[derive(Clone, Debug)]
+pub struct FramebufferAttachmentImageInfo {
+    /// The flags that the attachment images will be created with.
+    ///
+    /// The default value is empty.
+    pub flags: ImageCreateFlags,
+
+    /// The usage that the attachment images will be created with.
+    ///
+    /// The default value is [`ImageUsage::empty()`].
+    pub usage: ImageUsage,
+
+    /// The extent (width and height) of the attachment images.
+    ///
+    /// The default value is `[0, 0]`, which must be overridden.
+    pub extent: [u32; 2],
+
+    /// The number of array layers of the attachment image views.
+    ///
+    /// The default value is `1`.
+    pub array_layers: u32,
+
+    /// The formats that image views of the attachment images can have. This must include the
+    /// format of the attachment in the render pass.
+    ///
+    /// The default value is empty, which must be overridden.
+    pub view_formats: Vec<Format>,
+
+    pub _ne: crate::NonExhaustive,
+}
+
+impl Default for FramebufferAttachmentImageInfo {
+    #[inline]
+    fn default() -> Self {
+        Self {
+            flags: ImageCreateFlags::empty(),
+            usage: ImageUsage::empty(),
+            extent: [0, 0],
+            array_layers: 1,
+            view_formats: Vec::new(),
+            _ne: crate::NonExhaustive(()),
+        }
+    }
+}
+
+impl FramebufferAttachmentImageInfo {
+    /// Returns a `FramebufferAttachmentImageInfo` that matches `image_view`.
+    #[inline]
+    pub fn image_view(image_view: &dyn ImageViewAbstract) -> Self {
+        let image = image_view.image();
+        let image_inner = image.inner().image;
+        let array_layers = &image_view.subresource_range().array_layers;
+
+        Self {
+            flags: image_inner.flags(),
+            usage: *image_inner.usage(),
+            extent: image.dimensions().width_height(),
+            array_layers: array_layers.end - array_layers.start,
+            view_formats: image_view.format().into_iter().collect(),
             _ne: crate::NonExhaustive(()),
         }
     }
@@ -510,6 +819,11 @@ pub enum FramebufferCreationError {
     /// Out of memory.
     OomError(OomError),
 
+    RequirementNotMet {
+        required_for: &'static str,
+        requires_one_of: RequiresOneOf,
+    },
+
     /// An attachment image is a 2D image view created from a 3D image, and has a depth/stencil
     /// format.
     Attachment2dArrayCompatibleDepthStencil { attachment: u32 },
@@ -560,6 +874,13 @@ pub enum FramebufferCreationError {
     /// An attachment image has a `ty` of [`ImageViewType::Dim3d`].
     AttachmentViewType3d { attachment: u32 },
 
+    /// The `view_formats` of an attachment image info do not include the format that the render
+    /// pass requires.
+    AttachmentViewFormatsMissingFormat {
+        attachment: u32,
+        required: Option<Format>,
+    },
+
     /// One of the elements of `extent` is zero, but no attachment images were given to calculate
     /// the extent from.
     AutoExtentAttachmentsEmpty,
@@ -568,6 +889,9 @@ pub enum FramebufferCreationError {
     /// from.
     AutoLayersAttachmentsEmpty,
 
+    /// Both `attachments` and `attachment_image_infos` are not empty.
+    ImagelessAttachmentsNotEmpty,
+
     /// The provided `extent` exceeds the `max_framebuffer_width` or `max_framebuffer_height`
     /// limits.
     MaxFramebufferExtentExceeded { provided: [u32; 2], max: [u32; 2] },
@@ -606,6 +930,14 @@ impl Display for FramebufferCreationError {
     fn fmt(&self, f: &mut Formatter<'_>) -> Result<(), FmtError> {
         match self {
             Self::OomError(_) => write!(f, "no memory available",),
+            Self::RequirementNotMet {
+                required_for,
+                requires_one_of,
+            } => write!(
+                f,
+                "a requirement was not met for: {}; requires one of: {}",
+                required_for, requires_one_of,
+            ),
             Self::Attachment2dArrayCompatibleDepthStencil { attachment } => write!(
                 f,
                 "attachment image {} is a 2D image view created from a 3D image, and has a \
@@ -674,6 +1006,15 @@ impl Display for FramebufferCreationError {
                 "attachment image {} has a `ty` of `ImageViewType::Dim3d`",
                 attachment,
             ),
+            Self::AttachmentViewFormatsMissingFormat {
+                attachment,
+                required,
+            } => write!(
+                f,
+                "the `view_formats` of attachment image info {} do not include the format that \
+                the render pass requires ({:?})",
+                attachment, required,
+            ),
             Self::AutoExtentAttachmentsEmpty => write!(
                 f,
                 "one of the elements of `extent` is zero, but no attachment images were given to \
@@ -684,6 +1025,10 @@ impl Display for FramebufferCreationError {
                 "`layers` is zero, but no attachment images were given to calculate the number of \
                 layers from",
             ),
+            Self::ImagelessAttachmentsNotEmpty => write!(
+                f,
+                "both `attachments` and `attachment_image_infos` are not empty",
+            ),
             Self::MaxFramebufferExtentExceeded { provided, max } => write!(
                 f,
                 "the provided `extent` ({:?}) exceeds the `max_framebuffer_width` or \
@@ -719,12 +1064,24 @@ impl From<VulkanError> for FramebufferCreationError {
     }
 }
 
+impl From<RequirementNotMet> for FramebufferCreationError {
+    fn from(err: RequirementNotMet) -> Self {
+        Self::RequirementNotMet {
+            required_for: err.required_for,
+            requires_one_of: err.requires_one_of,
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use crate::{
         format::Format,
-        image::{attachment::AttachmentImage, view::ImageView},
-        render_pass::{Framebuffer, FramebufferCreateInfo, FramebufferCreationError, RenderPass},
+        image::{attachment::AttachmentImage, view::ImageView, ImageUsage},
+        render_pass::{
+            Framebuffer, FramebufferAttachmentImageInfo, FramebufferCreateInfo,
+            FramebufferCreationError, RenderPass,
+        },
     };
 
     #[test]
@@ -1080,4 +1437,46 @@ mod tests {
             _ => panic!(),
         }
     }
+
+    #[test]
+    fn imageless_feature_missing() {
+        let (device, _) = gfx_dev_and_queue!();
+
+        let render_pass = single_pass_renderpass!(device.clone(),
+            attachments: {
+                color: {
+                    load: Clear,
+                    store: DontCare,
+                    format: Format::R8G8B8A8_UNORM,
+                    samples: 1,
+                }
+            },
+            pass: {
+                color: [color],
+                depth_stencil: {}
+            }
+        )
+        .unwrap();
+
+        let res = Framebuffer::new(
+            render_pass,
+            FramebufferCreateInfo {
+                attachment_image_infos: vec![FramebufferAttachmentImageInfo {
+                    usage: ImageUsage {
+                        color_attachment: true,
+                        ..ImageUsage::empty()
+                    },
+                    extent: [512, 512],
+                    view_formats: vec![Format::R8G8B8A8_UNORM],
+                    ..Default::default()
+                }],
+                ..Default::default()
+            },
+        );
+
+        match res {
+            Err(FramebufferCreationError::RequirementNotMet { .. }) => (),
+            _ => panic!(),
+        }
+    }
 }
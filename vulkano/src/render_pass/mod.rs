@@ -27,7 +27,10 @@
 
 pub use self::{
     create::RenderPassCreationError,
-    framebuffer::{Framebuffer, FramebufferCreateInfo, FramebufferCreationError},
+    framebuffer::{
+        Framebuffer, FramebufferAttachmentImageInfo, FramebufferCreateInfo,
+        FramebufferCreationError,
+    },
 };
 use crate::{
     device::{Device, DeviceOwned},
@@ -38,6 +41,7 @@ use crate::{
     sync::{AccessFlags, PipelineStages},
     Version, VulkanObject,
 };
+use ash::vk::Handle;
 use std::{
     cmp::max,
     hash::{Hash, Hasher},
@@ -116,6 +120,7 @@ pub struct RenderPass {
 
     granularity: [u32; 2],
     views_used: u32,
+    name: Option<String>,
 }
 
 impl RenderPass {
@@ -146,12 +151,13 @@ impl RenderPass {
             subpasses,
             dependencies,
             correlated_view_masks,
+            name,
             _ne: _,
         } = create_info;
 
         let granularity = unsafe { Self::get_granularity(&device, handle) };
 
-        Ok(Arc::new(RenderPass {
+        let render_pass = Arc::new(RenderPass {
             handle,
             device,
 
@@ -162,7 +168,22 @@ impl RenderPass {
 
             granularity,
             views_used,
-        }))
+            name,
+        });
+
+        if let Some(name) = render_pass.name.as_deref() {
+            unsafe {
+                render_pass
+                    .device()
+                    .set_debug_utils_object_name_if_enabled(
+                        ash::vk::ObjectType::RENDER_PASS,
+                        render_pass.handle.as_raw(),
+                        Some(name),
+                    )?;
+            }
+        }
+
+        Ok(render_pass)
     }
 
     unsafe fn get_granularity(device: &Arc<Device>, handle: ash::vk::RenderPass) -> [u32; 2] {
@@ -217,6 +238,7 @@ impl RenderPass {
             subpasses,
             dependencies,
             correlated_view_masks,
+            name,
             _ne: _,
         } = create_info;
 
@@ -231,6 +253,7 @@ impl RenderPass {
 
             granularity,
             views_used,
+            name,
         }))
     }
 
@@ -252,6 +275,12 @@ impl RenderPass {
         &self.dependencies
     }
 
+    /// Returns the name that the render pass was created with, if any.
+    #[inline]
+    pub fn name(&self) -> Option<&str> {
+        self.name.as_deref()
+    }
+
     /// Returns the correlated view masks of the render pass.
     #[inline]
     pub fn correlated_view_masks(&self) -> &[u32] {
@@ -406,6 +435,8 @@ impl RenderPass {
                     color_attachments: color_attachments1,
                     resolve_attachments: resolve_attachments1,
                     depth_stencil_attachment: depth_stencil_attachment1,
+                    fragment_shading_rate_attachment: _,
+                    fragment_shading_rate_attachment_texel_size: _,
                     preserve_attachments: _,
                     _ne: _,
                 } = subpass1;
@@ -415,6 +446,8 @@ impl RenderPass {
                     color_attachments: color_attachments2,
                     resolve_attachments: resolve_attachments2,
                     depth_stencil_attachment: depth_stencil_attachment2,
+                    fragment_shading_rate_attachment: _,
+                    fragment_shading_rate_attachment_texel_size: _,
                     preserve_attachments: _,
                     _ne: _,
                 } = subpass2;
@@ -775,6 +808,15 @@ pub struct RenderPassCreateInfo {
     /// The default value is empty.
     pub correlated_view_masks: Vec<u32>,
 
+    /// A name for the render pass.
+    ///
+    /// If the `ext_debug_utils` extension is enabled on the instance, the name is assigned to the
+    /// render pass with [`Device::set_debug_utils_object_name`], so that debugging tools show it.
+    /// Otherwise, this doesn't require the extension.
+    ///
+    /// The default value is `None`.
+    pub name: Option<String>,
+
     pub _ne: crate::NonExhaustive,
 }
 
@@ -786,6 +828,7 @@ impl Default for RenderPassCreateInfo {
             subpasses: Vec::new(),
             dependencies: Vec::new(),
             correlated_view_masks: Vec::new(),
+            name: None,
             _ne: crate::NonExhaustive(()),
         }
     }
@@ -924,6 +967,36 @@ pub struct SubpassDescription {
     /// The default value is `None`.
     pub depth_stencil_attachment: Option<AttachmentReference>,
 
+    /// The single attachment of the render pass that is to be used as fragment shading rate
+    /// attachment in this subpass. Each texel of the attachment specifies the fragment shading
+    /// rate for an area of the framebuffer, whose size is given by
+    /// `fragment_shading_rate_attachment_texel_size`.
+    ///
+    /// If set to `Some`, the
+    /// [`attachment_fragment_shading_rate`](crate::device::Features::attachment_fragment_shading_rate)
+    /// feature must be enabled on the device. The referenced attachment must have a format that
+    /// supports the `fragment_shading_rate_attachment` format feature, and `layout` must be
+    /// [`ImageLayout::General`] or [`ImageLayout::FragmentShadingRateAttachmentOptimal`].
+    ///
+    /// The default value is `None`.
+    pub fragment_shading_rate_attachment: Option<AttachmentReference>,
+
+    /// The size, in framebuffer pixels, of the area that each texel of
+    /// `fragment_shading_rate_attachment` applies to. This value is ignored if
+    /// `fragment_shading_rate_attachment` is `None`.
+    ///
+    /// Both values must be powers of two, and must be between the
+    /// [`min_fragment_shading_rate_attachment_texel_size`](crate::device::Properties::min_fragment_shading_rate_attachment_texel_size)
+    /// and
+    /// [`max_fragment_shading_rate_attachment_texel_size`](crate::device::Properties::max_fragment_shading_rate_attachment_texel_size)
+    /// device properties. The ratio of the larger value to the smaller must not exceed the
+    /// [`max_fragment_shading_rate_attachment_texel_size_aspect_ratio`](crate::device::Properties::max_fragment_shading_rate_attachment_texel_size_aspect_ratio)
+    /// device property.
+    ///
+    /// The default value is `[0, 0]`, which must be overridden if
+    /// `fragment_shading_rate_attachment` is `Some`.
+    pub fragment_shading_rate_attachment_texel_size: [u32; 2],
+
     /// The indices of attachments of the render pass that will be preserved during this subpass.
     ///
     /// The referenced attachments must not be used as any other attachment type in the subpass.
@@ -941,6 +1014,8 @@ impl Default for SubpassDescription {
             view_mask: 0,
             color_attachments: Vec::new(),
             depth_stencil_attachment: None,
+            fragment_shading_rate_attachment: None,
+            fragment_shading_rate_attachment_texel_size: [0, 0],
             input_attachments: Vec::new(),
             resolve_attachments: Vec::new(),
             preserve_attachments: Vec::new(),
@@ -1233,7 +1308,11 @@ impl ResolveModes {
 mod tests {
     use crate::{
         format::Format,
-        render_pass::{RenderPass, RenderPassCreationError},
+        image::ImageLayout,
+        render_pass::{
+            AttachmentDescription, AttachmentReference, RenderPass, RenderPassCreateInfo,
+            RenderPassCreationError, SubpassDescription,
+        },
     };
 
     #[test]
@@ -1296,4 +1375,40 @@ mod tests {
         assert_ne!(granularity[0], 0);
         assert_ne!(granularity[1], 0);
     }
+
+    #[test]
+    fn fragment_shading_rate_attachment_feature_missing() {
+        let (device, _) = gfx_dev_and_queue!();
+
+        if device.enabled_features().attachment_fragment_shading_rate {
+            return; // test ignored
+        }
+
+        let rp = RenderPass::new(
+            device,
+            RenderPassCreateInfo {
+                attachments: vec![AttachmentDescription {
+                    format: Some(Format::R8_UINT),
+                    initial_layout: ImageLayout::FragmentShadingRateAttachmentOptimal,
+                    final_layout: ImageLayout::FragmentShadingRateAttachmentOptimal,
+                    ..Default::default()
+                }],
+                subpasses: vec![SubpassDescription {
+                    fragment_shading_rate_attachment: Some(AttachmentReference {
+                        attachment: 0,
+                        layout: ImageLayout::FragmentShadingRateAttachmentOptimal,
+                        ..Default::default()
+                    }),
+                    fragment_shading_rate_attachment_texel_size: [16, 16],
+                    ..Default::default()
+                }],
+                ..Default::default()
+            },
+        );
+
+        match rp {
+            Err(RenderPassCreationError::RequirementNotMet { .. }) => (),
+            _ => panic!(),
+        }
+    }
 }
@@ -55,6 +55,7 @@ use crate::{
     shader::ShaderScalarType,
     OomError, RequirementNotMet, RequiresOneOf, VulkanError, VulkanObject,
 };
+use ash::vk::Handle;
 use std::{
     error::Error,
     fmt::{Display, Error as FmtError, Formatter},
@@ -111,6 +112,7 @@ pub struct Sampler {
     reduction_mode: SamplerReductionMode,
     sampler_ycbcr_conversion: Option<Arc<SamplerYcbcrConversion>>,
     unnormalized_coordinates: bool,
+    name: Option<String>,
 }
 
 impl Sampler {
@@ -137,6 +139,7 @@ impl Sampler {
             unnormalized_coordinates,
             reduction_mode,
             sampler_ycbcr_conversion,
+            name,
             _ne: _,
         } = create_info;
 
@@ -420,7 +423,7 @@ impl Sampler {
             output.assume_init()
         };
 
-        Ok(Arc::new(Sampler {
+        let sampler = Arc::new(Sampler {
             handle,
             device,
 
@@ -439,7 +442,20 @@ impl Sampler {
             reduction_mode,
             sampler_ycbcr_conversion,
             unnormalized_coordinates,
-        }))
+            name,
+        });
+
+        if let Some(name) = sampler.name.as_deref() {
+            unsafe {
+                sampler.device().set_debug_utils_object_name_if_enabled(
+                    ash::vk::ObjectType::SAMPLER,
+                    sampler.handle.as_raw(),
+                    Some(name),
+                )?;
+            }
+        }
+
+        Ok(sampler)
     }
 
     /// Creates a new `Sampler` from a raw object handle.
@@ -467,6 +483,7 @@ impl Sampler {
             unnormalized_coordinates,
             reduction_mode,
             sampler_ycbcr_conversion,
+            name,
             _ne: _,
         } = create_info;
 
@@ -489,6 +506,7 @@ impl Sampler {
             reduction_mode,
             sampler_ycbcr_conversion,
             unnormalized_coordinates,
+            name,
         })
     }
 
@@ -715,6 +733,12 @@ impl Sampler {
     pub fn unnormalized_coordinates(&self) -> bool {
         self.unnormalized_coordinates
     }
+
+    /// Returns the name that the sampler was created with, if any.
+    #[inline]
+    pub fn name(&self) -> Option<&str> {
+        self.name.as_deref()
+    }
 }
 
 impl Drop for Sampler {
@@ -1092,6 +1116,15 @@ pub struct SamplerCreateInfo {
     /// The default value is `None`.
     pub sampler_ycbcr_conversion: Option<Arc<SamplerYcbcrConversion>>,
 
+    /// A name for the sampler.
+    ///
+    /// If the `ext_debug_utils` extension is enabled on the instance, the name is assigned to the
+    /// sampler with [`Device::set_debug_utils_object_name`], so that debugging tools show it.
+    /// Otherwise, this doesn't require the extension.
+    ///
+    /// The default value is `None`.
+    pub name: Option<String>,
+
     pub _ne: crate::NonExhaustive,
 }
 
@@ -1111,6 +1144,7 @@ impl Default for SamplerCreateInfo {
             unnormalized_coordinates: false,
             reduction_mode: SamplerReductionMode::WeightedAverage,
             sampler_ycbcr_conversion: None,
+            name: None,
             _ne: crate::NonExhaustive(()),
         }
     }
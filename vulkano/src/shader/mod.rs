@@ -378,6 +378,9 @@ pub struct EntryPointInfo {
     pub specialization_constant_requirements: HashMap<u32, SpecializationConstantRequirements>,
     pub input_interface: ShaderInterface,
     pub output_interface: ShaderInterface,
+    /// Whether the entry point declares the `Xfb` execution mode, meaning that its outputs can be
+    /// captured with transform feedback.
+    pub transform_feedback: bool,
 }
 
 /// Represents a shader entry point in a shader module.
@@ -448,6 +451,13 @@ impl<'a> EntryPoint<'a> {
     pub fn output_interface(&self) -> &ShaderInterface {
         &self.info.output_interface
     }
+
+    /// Returns whether the shader stage declares the `Xfb` execution mode, meaning that its
+    /// outputs can be captured with transform feedback.
+    #[inline]
+    pub fn transform_feedback(&self) -> bool {
+        self.info.transform_feedback
+    }
 }
 
 /// The mode in which a shader executes. This includes both information about the shader type/stage,
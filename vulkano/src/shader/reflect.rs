@@ -98,6 +98,16 @@ pub fn entry_points(
             StorageClass::Output,
             matches!(execution_model, ExecutionModel::TessellationControl),
         );
+        let transform_feedback = spirv.iter_execution_mode().any(|instruction| {
+            matches!(
+                instruction,
+                Instruction::ExecutionMode {
+                    entry_point,
+                    mode: ExecutionMode::Xfb,
+                    ..
+                } if *entry_point == function_id
+            )
+        });
 
         Some((
             entry_point_name.clone(),
@@ -109,6 +119,7 @@ pub fn entry_points(
                 specialization_constant_requirements,
                 input_interface,
                 output_interface,
+                transform_feedback,
             },
         ))
     })
@@ -144,7 +155,7 @@ fn shader_execution(
                         ExecutionMode::InputTrianglesAdjacency => {
                             Some(GeometryShaderInput::TrianglesWithAdjacency)
                         }
-                        _ => todo!(),
+                        _ => None,
                     },
                     _ => None,
                 })
@@ -771,6 +782,12 @@ fn descriptor_requirements_of(spirv: &Spirv, variable_id: Id) -> DescriptorVaria
                         DescriptorType::UniformBuffer,
                         DescriptorType::UniformBufferDynamic,
                     ];
+
+                    // Inline uniform blocks can't be arrayed.
+                    if reqs.descriptor_count == Some(1) {
+                        reqs.descriptor_types
+                            .push(DescriptorType::InlineUniformBlock);
+                    }
                 };
 
                 None
@@ -1210,6 +1227,10 @@ fn size_of_type(spirv: &Spirv, id: Id) -> Option<DeviceSize> {
 
             Some(end_of_struct)
         }
+        Instruction::TypePointer {
+            storage_class: StorageClass::PhysicalStorageBuffer,
+            ..
+        } => Some(8),
         _ => panic!("Type {} not found", id),
     }
 }
@@ -1382,3 +1403,67 @@ fn is_builtin(spirv: &Spirv, id: Id) -> bool {
         _ => false,
     }
 }
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use super::entry_points;
+    use crate::shader::spirv::Spirv;
+
+    /// Returns the words of a vertex shader that does nothing, optionally declaring the `Xfb`
+    /// execution mode.
+    pub(crate) fn empty_vertex_shader(transform_feedback: bool) -> Vec<u32> {
+        let mut words = vec![0x07230203, 0x00010000, 0, 5, 0];
+        // OpCapability Shader
+        words.extend([(2 << 16) | 17, 1]);
+
+        if transform_feedback {
+            // OpCapability TransformFeedback
+            words.extend([(2 << 16) | 17, 53]);
+        }
+
+        // OpMemoryModel Logical GLSL450
+        words.extend([(3 << 16) | 14, 0, 1]);
+        // OpEntryPoint Vertex %1 "main"
+        words.extend([(5 << 16) | 15, 0, 1, u32::from_le_bytes(*b"main"), 0]);
+
+        if transform_feedback {
+            // OpExecutionMode %1 Xfb
+            words.extend([(3 << 16) | 16, 1, 11]);
+        }
+
+        words.extend([
+            // %2 = OpTypeVoid
+            (2 << 16) | 19,
+            2,
+            // %3 = OpTypeFunction %2
+            (3 << 16) | 33,
+            3,
+            2,
+            // %1 = OpFunction %2 None %3
+            (5 << 16) | 54,
+            2,
+            1,
+            0,
+            3,
+            // %4 = OpLabel
+            (2 << 16) | 248,
+            4,
+            // OpReturn
+            (1 << 16) | 253,
+            // OpFunctionEnd
+            (1 << 16) | 56,
+        ]);
+
+        words
+    }
+
+    #[test]
+    fn transform_feedback_execution_mode() {
+        for transform_feedback in [false, true] {
+            let spirv = Spirv::new(&empty_vertex_shader(transform_feedback)).unwrap();
+            let (name, _, info) = entry_points(&spirv).next().unwrap();
+            assert_eq!(name, "main");
+            assert_eq!(info.transform_feedback, transform_feedback);
+        }
+    }
+}
@@ -20,7 +20,7 @@
 //!   `Display::enumerate`.
 //! - Choose a `DisplayMode`, which is the combination of a display, a resolution and a refresh
 //!   rate. You can enumerate the modes available on a display with `Display::display_modes`, or
-//!   attempt to create your own mode with `TODO`.
+//!   attempt to create your own mode with `DisplayMode::new`.
 //! - Choose a `DisplayPlane`. A display can show multiple planes in a stacking fashion.
 //! - Create a `Surface` object with `Surface::from_display_plane` and pass the chosen `DisplayMode`
 //!   and `DisplayPlane`.
@@ -29,12 +29,17 @@
 #![allow(unused_variables)] // TODO: this module isn't finished
 
 use crate::{
-    device::physical::PhysicalDevice, swapchain::SupportedSurfaceTransforms, OomError, VulkanError,
-    VulkanObject,
+    device::physical::PhysicalDevice,
+    macros::{vulkan_bitflags, vulkan_enum},
+    swapchain::SupportedSurfaceTransforms,
+    OomError, RequirementNotMet, RequiresOneOf, VulkanError, VulkanObject,
 };
 use std::{
+    error::Error,
     ffi::CStr,
     fmt::{Display as FmtDisplay, Error as FmtError, Formatter},
+    fs::File,
+    mem::MaybeUninit,
     ptr,
     sync::Arc,
     vec::IntoIter,
@@ -350,6 +355,106 @@ impl Display {
     pub fn display_modes(&self) -> IntoIter<DisplayMode> {
         self.display_modes_raw().unwrap()
     }
+
+    /// Returns the display that corresponds to a DRM connector.
+    ///
+    /// `drm_fd` must be an open file descriptor of the DRM primary node of `physical_device`.
+    /// The [`ext_acquire_drm_display`] extension must be enabled on the instance.
+    ///
+    /// [`ext_acquire_drm_display`]: crate::instance::InstanceExtensions::ext_acquire_drm_display
+    pub fn from_drm_connector(
+        physical_device: Arc<PhysicalDevice>,
+        drm_fd: &File,
+        connector_id: u32,
+    ) -> Result<Display, DisplayError> {
+        if !physical_device
+            .instance()
+            .enabled_extensions()
+            .ext_acquire_drm_display
+        {
+            return Err(DisplayError::RequirementNotMet {
+                required_for: "`Display::from_drm_connector`",
+                requires_one_of: RequiresOneOf {
+                    instance_extensions: &["ext_acquire_drm_display"],
+                    ..Default::default()
+                },
+            });
+        }
+
+        #[cfg(not(unix))]
+        unreachable!("`ext_acquire_drm_display` was somehow enabled on a non-Unix system");
+
+        #[cfg(unix)]
+        {
+            use std::os::unix::io::AsRawFd;
+
+            let handle = unsafe {
+                let fns = physical_device.instance().fns();
+                let mut output = MaybeUninit::uninit();
+                (fns.ext_acquire_drm_display.get_drm_display_ext)(
+                    physical_device.internal_object(),
+                    drm_fd.as_raw_fd(),
+                    connector_id,
+                    output.as_mut_ptr(),
+                )
+                .result()
+                .map_err(VulkanError::from)?;
+                output.assume_init()
+            };
+
+            if handle == ash::vk::DisplayKHR::null() {
+                return Err(DisplayError::NoDisplayForConnector);
+            }
+
+            Display::enumerate_raw(physical_device)?
+                .find(|display| display.internal_object() == handle)
+                .ok_or(DisplayError::NoDisplayForConnector)
+        }
+    }
+
+    /// Acquires exclusive control of the display through DRM, so that it can be presented to
+    /// without a compositor.
+    ///
+    /// `drm_fd` must be an open file descriptor of the DRM primary node of the physical device of
+    /// the display, with DRM master permissions. The control is released when the instance is
+    /// destroyed. The [`ext_acquire_drm_display`] extension must be enabled on the instance.
+    ///
+    /// [`ext_acquire_drm_display`]: crate::instance::InstanceExtensions::ext_acquire_drm_display
+    pub fn acquire_drm(&self, drm_fd: &File) -> Result<(), DisplayError> {
+        if !self
+            .physical_device
+            .instance()
+            .enabled_extensions()
+            .ext_acquire_drm_display
+        {
+            return Err(DisplayError::RequirementNotMet {
+                required_for: "`Display::acquire_drm`",
+                requires_one_of: RequiresOneOf {
+                    instance_extensions: &["ext_acquire_drm_display"],
+                    ..Default::default()
+                },
+            });
+        }
+
+        #[cfg(not(unix))]
+        unreachable!("`ext_acquire_drm_display` was somehow enabled on a non-Unix system");
+
+        #[cfg(unix)]
+        unsafe {
+            use std::os::unix::io::AsRawFd;
+
+            let fns = self.physical_device.instance().fns();
+            (fns.ext_acquire_drm_display.acquire_drm_display_ext)(
+                self.physical_device.internal_object(),
+                drm_fd.as_raw_fd(),
+                self.properties.display,
+            )
+            .result()
+            .map_err(VulkanError::from)?;
+
+            Ok(())
+        }
+    }
 }
 
 unsafe impl VulkanObject for Display {
@@ -371,35 +476,91 @@ pub struct DisplayMode {
 }
 
 impl DisplayMode {
-    /*pub fn new(display: &Display) -> Result<Arc<DisplayMode>, OomError> {
-        let fns = instance.fns();
-        assert!(device.instance().enabled_extensions().khr_display);     // TODO: return error instead
+    /// Creates a custom display mode on `display`.
+    ///
+    /// The implementation may reject parameters that the display doesn't support, in which case
+    /// [`DisplayError::InitializationFailed`] is returned.
+    pub fn new(
+        display: &Display,
+        parameters: DisplayModeParameters,
+    ) -> Result<DisplayMode, DisplayError> {
+        Self::validate_new(display, &parameters)?;
+
+        unsafe { Ok(Self::new_unchecked(display, parameters)?) }
+    }
+
+    fn validate_new(
+        display: &Display,
+        parameters: &DisplayModeParameters,
+    ) -> Result<(), DisplayError> {
+        if !display
+            .physical_device()
+            .instance()
+            .enabled_extensions()
+            .khr_display
+        {
+            return Err(DisplayError::RequirementNotMet {
+                required_for: "`DisplayMode::new`",
+                requires_one_of: RequiresOneOf {
+                    instance_extensions: &["khr_display"],
+                    ..Default::default()
+                },
+            });
+        }
+
+        let &DisplayModeParameters {
+            visible_region,
+            refresh_rate,
+        } = parameters;
+
+        // VUID-VkDisplayModeParametersKHR-width-01990
+        // VUID-VkDisplayModeParametersKHR-height-01991
+        if visible_region[0] == 0 || visible_region[1] == 0 {
+            return Err(DisplayError::VisibleRegionZero);
+        }
+
+        // VUID-VkDisplayModeParametersKHR-refreshRate-01992
+        if refresh_rate == 0 {
+            return Err(DisplayError::RefreshRateZero);
+        }
+
+        Ok(())
+    }
 
-        let parameters = ash::vk::DisplayModeParametersKHR {
-            visibleRegion: ash::vk::Extent2D { width: , height:  },
-            refreshRate: ,
+    #[cfg_attr(not(feature = "document_unchecked"), doc(hidden))]
+    pub unsafe fn new_unchecked(
+        display: &Display,
+        parameters: DisplayModeParameters,
+    ) -> Result<DisplayMode, VulkanError> {
+        let parameters = ash::vk::DisplayModeParametersKHR::from(parameters);
+
+        let create_info = ash::vk::DisplayModeCreateInfoKHR {
+            flags: ash::vk::DisplayModeCreateFlagsKHR::empty(),
+            parameters,
+            ..Default::default()
         };
 
         let display_mode = {
-            let infos = ash::vk::DisplayModeCreateInfoKHR {
-                flags: ash::vk::DisplayModeCreateFlags::empty(),
-                parameters: parameters,
-                ..Default::default()
-            };
-
-            let mut output = mem::uninitialized();
-            (fns.v1_0.CreateDisplayModeKHR)(display.device.internal_object(),
-                                                      display.display, &infos, ptr::null(),
-                                                      &mut output).result().map_err(VulkanError::from)?;
-            output
+            let fns = display.physical_device().instance().fns();
+            let mut output = MaybeUninit::uninit();
+            (fns.khr_display.create_display_mode_khr)(
+                display.physical_device().internal_object(),
+                display.internal_object(),
+                &create_info,
+                ptr::null(),
+                output.as_mut_ptr(),
+            )
+            .result()
+            .map_err(VulkanError::from)?;
+            output.assume_init()
         };
 
-        Ok(Arc::new(DisplayMode {
-            instance: display.device.instance().clone(),
-            display_mode: display_mode,
-            parameters: ,
-        }))
-    }*/
+        Ok(DisplayMode {
+            display: display.clone(),
+            display_mode,
+            parameters,
+        })
+    }
 
     /// Returns the display corresponding to this mode.
     #[inline]
@@ -422,6 +583,53 @@ impl DisplayMode {
     pub fn refresh_rate(&self) -> u32 {
         self.parameters.refresh_rate
     }
+
+    /// Returns the parameters of this mode.
+    #[inline]
+    pub fn parameters(&self) -> DisplayModeParameters {
+        self.parameters.into()
+    }
+
+    /// Queries the capabilities of `plane` when it is used with this mode.
+    ///
+    /// # Panics
+    ///
+    /// - Panics if `plane` doesn't belong to the same physical device as this mode.
+    pub fn plane_capabilities(
+        &self,
+        plane: &DisplayPlane,
+    ) -> Result<DisplayPlaneCapabilities, DisplayError> {
+        assert_eq!(self.display.physical_device(), plane.physical_device());
+
+        let capabilities = unsafe {
+            let fns = self.display.physical_device().instance().fns();
+            let mut output = MaybeUninit::uninit();
+            (fns.khr_display.get_display_plane_capabilities_khr)(
+                self.display.physical_device().internal_object(),
+                self.display_mode,
+                plane.index(),
+                output.as_mut_ptr(),
+            )
+            .result()
+            .map_err(VulkanError::from)?;
+            output.assume_init()
+        };
+
+        let offset = |o: ash::vk::Offset2D| [o.x, o.y];
+        let extent = |e: ash::vk::Extent2D| [e.width, e.height];
+
+        Ok(DisplayPlaneCapabilities {
+            supported_alpha: capabilities.supported_alpha.into(),
+            min_src_position: offset(capabilities.min_src_position),
+            max_src_position: offset(capabilities.max_src_position),
+            min_src_extent: extent(capabilities.min_src_extent),
+            max_src_extent: extent(capabilities.max_src_extent),
+            min_dst_position: offset(capabilities.min_dst_position),
+            max_dst_position: offset(capabilities.max_dst_position),
+            min_dst_extent: extent(capabilities.min_dst_extent),
+            max_dst_extent: extent(capabilities.max_dst_extent),
+        })
+    }
 }
 
 impl FmtDisplay for DisplayMode {
@@ -448,3 +656,310 @@ unsafe impl VulkanObject for DisplayMode {
         self.display_mode
     }
 }
+
+/// The parameters of a display mode.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct DisplayModeParameters {
+    /// The dimensions of the region that is visible on the monitor.
+    pub visible_region: [u32; 2],
+
+    /// The refresh rate, in millihertz. For example, 60 Hz is `60_000`.
+    pub refresh_rate: u32,
+}
+
+impl From<DisplayModeParameters> for ash::vk::DisplayModeParametersKHR {
+    #[inline]
+    fn from(val: DisplayModeParameters) -> Self {
+        ash::vk::DisplayModeParametersKHR {
+            visible_region: ash::vk::Extent2D {
+                width: val.visible_region[0],
+                height: val.visible_region[1],
+            },
+            refresh_rate: val.refresh_rate,
+        }
+    }
+}
+
+impl From<ash::vk::DisplayModeParametersKHR> for DisplayModeParameters {
+    #[inline]
+    fn from(val: ash::vk::DisplayModeParametersKHR) -> Self {
+        DisplayModeParameters {
+            visible_region: [val.visible_region.width, val.visible_region.height],
+            refresh_rate: val.refresh_rate,
+        }
+    }
+}
+
+vulkan_bitflags! {
+    /// The alpha blending modes that a display plane supports.
+    #[non_exhaustive]
+    DisplayPlaneAlphaFlags = DisplayPlaneAlphaFlagsKHR(u32);
+
+    /// The source image is treated as opaque.
+    opaque = OPAQUE,
+
+    /// A global alpha value, given when creating the surface, is applied to the whole image.
+    global = GLOBAL,
+
+    /// The alpha component of the image is used, and the color is not premultiplied.
+    per_pixel = PER_PIXEL,
+
+    /// The alpha component of the image is used, and the color is premultiplied.
+    per_pixel_premultiplied = PER_PIXEL_PREMULTIPLIED,
+}
+
+/// The capabilities of a display plane when used with a particular display mode.
+///
+/// Positions are in pixels and can be negative. The source rectangle is the part of the
+/// presented image that is shown, and the destination rectangle is where it is shown on the
+/// display.
+#[derive(Clone, Debug)]
+#[non_exhaustive]
+pub struct DisplayPlaneCapabilities {
+    /// The supported alpha blending modes.
+    pub supported_alpha: DisplayPlaneAlphaFlags,
+
+    /// The minimum source rectangle offset.
+    pub min_src_position: [i32; 2],
+
+    /// The maximum source rectangle offset.
+    pub max_src_position: [i32; 2],
+
+    /// The minimum source rectangle size.
+    pub min_src_extent: [u32; 2],
+
+    /// The maximum source rectangle size.
+    pub max_src_extent: [u32; 2],
+
+    /// The minimum destination rectangle offset.
+    pub min_dst_position: [i32; 2],
+
+    /// The maximum destination rectangle offset.
+    pub max_dst_position: [i32; 2],
+
+    /// The minimum destination rectangle size.
+    pub min_dst_extent: [u32; 2],
+
+    /// The maximum destination rectangle size.
+    pub max_dst_extent: [u32; 2],
+}
+
+vulkan_enum! {
+    /// The power state of a display.
+    #[non_exhaustive]
+    DisplayPowerState = DisplayPowerStateEXT(i32);
+
+    /// The display is powered down.
+    Off = OFF,
+
+    /// The display is put in a low power mode, from which it can return quickly.
+    Suspend = SUSPEND,
+
+    /// The display is powered on.
+    On = ON,
+}
+
+vulkan_enum! {
+    /// An event on a display that can signal a fence.
+    #[non_exhaustive]
+    DisplayEventType = DisplayEventTypeEXT(i32);
+
+    /// The first pixel of a new frame is scanned out of the display.
+    FirstPixelOut = FIRST_PIXEL_OUT,
+}
+
+/// Error that can happen when using displays and display modes.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum DisplayError {
+    /// Not enough memory.
+    OomError(OomError),
+
+    /// The implementation rejected the operation, for example because the display mode
+    /// parameters are not supported or the display could not be acquired.
+    InitializationFailed,
+
+    RequirementNotMet {
+        required_for: &'static str,
+        requires_one_of: RequiresOneOf,
+    },
+
+    /// The display does not belong to the physical device of the device.
+    DisplayPhysicalDeviceMismatch,
+
+    /// There is no display corresponding to the given DRM connector.
+    NoDisplayForConnector,
+
+    /// The visible region of the display mode parameters has a zero width or height.
+    VisibleRegionZero,
+
+    /// The refresh rate of the display mode parameters is zero.
+    RefreshRateZero,
+}
+
+impl Error for DisplayError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            Self::OomError(err) => Some(err),
+            _ => None,
+        }
+    }
+}
+
+impl FmtDisplay for DisplayError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result<(), FmtError> {
+        match self {
+            Self::OomError(_) => write!(f, "not enough memory available"),
+            Self::InitializationFailed => write!(f, "the implementation rejected the operation"),
+            Self::RequirementNotMet {
+                required_for,
+                requires_one_of,
+            } => write!(
+                f,
+                "a requirement was not met for: {}; requires one of: {}",
+                required_for, requires_one_of,
+            ),
+            Self::DisplayPhysicalDeviceMismatch => write!(
+                f,
+                "the display does not belong to the physical device of the device",
+            ),
+            Self::NoDisplayForConnector => write!(
+                f,
+                "there is no display corresponding to the given DRM connector",
+            ),
+            Self::VisibleRegionZero => write!(
+                f,
+                "the visible region of the display mode parameters has a zero width or height",
+            ),
+            Self::RefreshRateZero => {
+                write!(f, "the refresh rate of the display mode parameters is zero",)
+            }
+        }
+    }
+}
+
+impl From<VulkanError> for DisplayError {
+    fn from(err: VulkanError) -> Self {
+        match err {
+            e @ VulkanError::OutOfHostMemory | e @ VulkanError::OutOfDeviceMemory => {
+                Self::OomError(e.into())
+            }
+            VulkanError::InitializationFailed => Self::InitializationFailed,
+            _ => panic!("unexpected error: {:?}", err),
+        }
+    }
+}
+
+impl From<OomError> for DisplayError {
+    fn from(err: OomError) -> Self {
+        Self::OomError(err)
+    }
+}
+
+impl From<RequirementNotMet> for DisplayError {
+    fn from(err: RequirementNotMet) -> Self {
+        Self::RequirementNotMet {
+            required_for: err.required_for,
+            requires_one_of: err.requires_one_of,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Display, DisplayError, DisplayMode, DisplayModeParameters};
+    use crate::{
+        device::{Device, DeviceCreateInfo, QueueCreateInfo},
+        instance::{Instance, InstanceCreateInfo, InstanceExtensions},
+        swapchain::display::{DisplayEventType, DisplayPowerState},
+        VulkanLibrary,
+    };
+    use std::fs::File;
+
+    fn drm_fd() -> File {
+        File::open(concat!(env!("CARGO_MANIFEST_DIR"), "/Cargo.toml")).unwrap()
+    }
+
+    #[test]
+    fn from_drm_connector_extension_missing() {
+        let instance = instance!();
+        let physical_device = match instance.enumerate_physical_devices().unwrap().next() {
+            Some(x) => x,
+            None => return,
+        };
+
+        assert!(matches!(
+            Display::from_drm_connector(physical_device, &drm_fd(), 0),
+            Err(DisplayError::RequirementNotMet { .. })
+        ));
+    }
+
+    #[test]
+    fn display_control_extension_missing() {
+        let library = match VulkanLibrary::new() {
+            Ok(x) => x,
+            Err(_) => return,
+        };
+
+        if !library.supported_extensions().khr_display {
+            return;
+        }
+
+        let instance = match Instance::new(
+            library,
+            InstanceCreateInfo {
+                enabled_extensions: InstanceExtensions {
+                    khr_display: true,
+                    ..InstanceExtensions::empty()
+                },
+                ..Default::default()
+            },
+        ) {
+            Ok(x) => x,
+            Err(_) => return,
+        };
+        let physical_device = match instance.enumerate_physical_devices().unwrap().next() {
+            Some(x) => x,
+            None => return,
+        };
+        let display = match Display::enumerate(physical_device.clone()).next() {
+            Some(x) => x,
+            None => return,
+        };
+        let (device, _) = match Device::new(
+            physical_device,
+            DeviceCreateInfo {
+                queue_create_infos: vec![QueueCreateInfo {
+                    queue_family_index: 0,
+                    ..Default::default()
+                }],
+                ..Default::default()
+            },
+        ) {
+            Ok(x) => x,
+            Err(_) => return,
+        };
+
+        assert!(matches!(
+            device.display_power_control(&display, DisplayPowerState::Off),
+            Err(DisplayError::RequirementNotMet { .. })
+        ));
+        assert!(matches!(
+            device.register_display_event(&display, DisplayEventType::FirstPixelOut),
+            Err(DisplayError::RequirementNotMet { .. })
+        ));
+        assert!(matches!(
+            display.acquire_drm(&drm_fd()),
+            Err(DisplayError::RequirementNotMet { .. })
+        ));
+        assert!(matches!(
+            DisplayMode::new(
+                &display,
+                DisplayModeParameters {
+                    visible_region: [0, 0],
+                    refresh_rate: 60_000,
+                },
+            ),
+            Err(DisplayError::VisibleRegionZero)
+        ));
+    }
+}
@@ -328,13 +328,17 @@ pub use self::{
     surface::{
         ColorSpace, CompositeAlpha, PresentMode, SupportedCompositeAlpha,
         SupportedSurfaceTransforms, Surface, SurfaceApi, SurfaceCapabilities, SurfaceCreationError,
-        SurfaceInfo, SurfaceTransform,
+        SurfaceFormatSelector, SurfaceInfo, SurfaceTransform,
     },
     swapchain::{
         acquire_next_image, acquire_next_image_raw, present, wait_for_present, AcquireError,
-        AcquiredImage, FullScreenExclusive, FullScreenExclusiveError, PresentFuture,
-        PresentWaitError, Swapchain, SwapchainAbstract, SwapchainAcquireFuture,
-        SwapchainCreateInfo, SwapchainCreationError, Win32Monitor,
+        AcquiredImage, FullScreenExclusive, FullScreenExclusiveError, HdrMetadata,
+        HdrMetadataError, PresentFuture, PresentWaitError, Swapchain, SwapchainAbstract,
+        SwapchainAcquireFuture, SwapchainCreateInfo, SwapchainCreationError, Win32Monitor,
+    },
+    virtual_swapchain::{
+        PresentedFrame, VirtualSwapchain, VirtualSwapchainAcquireFuture,
+        VirtualSwapchainCreateInfo, VirtualSwapchainError, VirtualSwapchainPresentFuture,
     },
 };
 #[cfg(target_os = "ios")]
@@ -349,6 +353,7 @@ use std::{
 pub mod display;
 mod surface;
 mod swapchain;
+mod virtual_swapchain;
 
 /// Parameters to execute present operations on a queue.
 #[derive(Clone, Debug)]
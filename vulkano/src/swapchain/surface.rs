@@ -10,7 +10,7 @@
 use super::{FullScreenExclusive, Win32Monitor};
 use crate::{
     cache::OnceCache,
-    format::Format,
+    format::{Format, NumericType},
     image::ImageUsage,
     instance::Instance,
     macros::{vulkan_bitflags, vulkan_enum},
@@ -1941,6 +1941,109 @@ impl Default for SurfaceInfo {
     }
 }
 
+/// Chooses a surface format among those returned by [`PhysicalDevice::surface_formats`], based
+/// on a list of preferred color spaces.
+///
+/// Within a color space, formats with more bits per color component are preferred if
+/// `prefer_high_bit_depth` is set, and formats with an sRGB numeric type are preferred for
+/// [`ColorSpace::SrgbNonLinear`], so that the hardware applies the transfer function. If none of
+/// the preferred color spaces are available, the selector falls back to
+/// [`ColorSpace::SrgbNonLinear`], then to the first available format.
+///
+/// ```no_run
+/// # use vulkano::{device::physical::PhysicalDevice, swapchain::{Surface, SurfaceFormatSelector}};
+/// # fn example<W>(physical_device: &PhysicalDevice, surface: &Surface<W>) {
+/// let formats = physical_device
+///     .surface_formats(surface, Default::default())
+///     .unwrap();
+/// let (image_format, image_color_space) = SurfaceFormatSelector::hdr().select(&formats).unwrap();
+/// # }
+/// ```
+///
+/// [`PhysicalDevice::surface_formats`]: crate::device::physical::PhysicalDevice::surface_formats
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct SurfaceFormatSelector {
+    /// The color spaces to look for, from most to least preferred.
+    ///
+    /// The default value is `[ColorSpace::SrgbNonLinear]`.
+    pub color_spaces: Vec<ColorSpace>,
+
+    /// Whether to prefer formats with more bits per color component within a color space.
+    ///
+    /// The default value is `false`.
+    pub prefer_high_bit_depth: bool,
+
+    pub _ne: crate::NonExhaustive,
+}
+
+impl Default for SurfaceFormatSelector {
+    #[inline]
+    fn default() -> Self {
+        Self {
+            color_spaces: vec![ColorSpace::SrgbNonLinear],
+            prefer_high_bit_depth: false,
+            _ne: crate::NonExhaustive(()),
+        }
+    }
+}
+
+impl SurfaceFormatSelector {
+    /// Returns a selector that prefers HDR10 (`Hdr10St2084`), then scRGB (`ExtendedSrgbLinear`),
+    /// then sRGB, with high bit depth formats.
+    #[inline]
+    pub fn hdr() -> Self {
+        Self {
+            color_spaces: vec![
+                ColorSpace::Hdr10St2084,
+                ColorSpace::ExtendedSrgbLinear,
+                ColorSpace::SrgbNonLinear,
+            ],
+            prefer_high_bit_depth: true,
+            ..Default::default()
+        }
+    }
+
+    /// Selects the best format and color space among `formats`.
+    ///
+    /// Returns `None` only if `formats` is empty.
+    pub fn select(&self, formats: &[(Format, ColorSpace)]) -> Option<(Format, ColorSpace)> {
+        self.color_spaces
+            .iter()
+            .chain(std::iter::once(&ColorSpace::SrgbNonLinear))
+            .find_map(|&color_space| self.select_in_color_space(formats, color_space))
+            .or_else(|| formats.first().copied())
+    }
+
+    fn select_in_color_space(
+        &self,
+        formats: &[(Format, ColorSpace)],
+        color_space: ColorSpace,
+    ) -> Option<(Format, ColorSpace)> {
+        let rank = |format: Format| {
+            let color_bits: u32 = if self.prefer_high_bit_depth {
+                format.components()[..3]
+                    .iter()
+                    .map(|&bits| bits as u32)
+                    .sum()
+            } else {
+                0
+            };
+            let srgb = color_space == ColorSpace::SrgbNonLinear
+                && format.type_color() == Some(NumericType::SRGB);
+
+            (color_bits, srgb)
+        };
+
+        // Among equally ranked formats, keep the one that the implementation listed first.
+        formats
+            .iter()
+            .filter(|&&(_, cs)| cs == color_space)
+            .rev()
+            .max_by_key(|&&(format, _)| rank(format))
+            .copied()
+    }
+}
+
 #[cfg(target_os = "ios")]
 struct LayerHandle(*mut Object);
 
@@ -2024,11 +2127,46 @@ pub struct SurfaceCapabilities {
 #[cfg(test)]
 mod tests {
     use crate::{
-        swapchain::{Surface, SurfaceCreationError},
+        format::Format,
+        swapchain::{ColorSpace, Surface, SurfaceCreationError, SurfaceFormatSelector},
         RequiresOneOf,
     };
     use std::ptr;
 
+    #[test]
+    fn format_selector() {
+        let formats = [
+            (Format::B8G8R8A8_UNORM, ColorSpace::SrgbNonLinear),
+            (Format::B8G8R8A8_SRGB, ColorSpace::SrgbNonLinear),
+            (Format::A2B10G10R10_UNORM_PACK32, ColorSpace::SrgbNonLinear),
+            (Format::A2B10G10R10_UNORM_PACK32, ColorSpace::Hdr10St2084),
+            (Format::R16G16B16A16_SFLOAT, ColorSpace::ExtendedSrgbLinear),
+        ];
+
+        assert_eq!(
+            SurfaceFormatSelector::default().select(&formats),
+            Some((Format::B8G8R8A8_SRGB, ColorSpace::SrgbNonLinear)),
+        );
+        assert_eq!(
+            SurfaceFormatSelector::hdr().select(&formats),
+            Some((Format::A2B10G10R10_UNORM_PACK32, ColorSpace::Hdr10St2084)),
+        );
+        assert_eq!(
+            SurfaceFormatSelector::hdr().select(&formats[..3]),
+            Some((Format::A2B10G10R10_UNORM_PACK32, ColorSpace::SrgbNonLinear)),
+        );
+
+        let selector = SurfaceFormatSelector {
+            color_spaces: vec![ColorSpace::DisplayP3NonLinear],
+            ..Default::default()
+        };
+        assert_eq!(
+            selector.select(&formats[3..]),
+            Some((Format::A2B10G10R10_UNORM_PACK32, ColorSpace::Hdr10St2084)),
+        );
+        assert_eq!(selector.select(&[]), None);
+    }
+
     #[test]
     fn khr_win32_surface_ext_missing() {
         let instance = instance!();
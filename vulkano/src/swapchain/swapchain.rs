@@ -27,6 +27,7 @@ use crate::{
     },
     DeviceSize, OomError, RequirementNotMet, RequiresOneOf, VulkanError, VulkanObject,
 };
+use ash::vk::Handle;
 use parking_lot::Mutex;
 use smallvec::{smallvec, SmallVec};
 use std::{
@@ -63,6 +64,7 @@ pub struct Swapchain<W> {
     clipped: bool,
     full_screen_exclusive: FullScreenExclusive,
     win32_monitor: Option<Win32Monitor>,
+    name: Option<String>,
     prev_present_id: AtomicU64,
 
     // Whether full-screen exclusive is currently held.
@@ -136,6 +138,7 @@ where
             clipped,
             full_screen_exclusive,
             win32_monitor,
+            name,
             _ne: _,
         } = create_info;
 
@@ -157,6 +160,7 @@ where
             clipped,
             full_screen_exclusive,
             win32_monitor,
+            name,
             prev_present_id: Default::default(),
 
             full_screen_exclusive_held: AtomicBool::new(false),
@@ -164,6 +168,16 @@ where
             retired: Mutex::new(false),
         });
 
+        if let Some(name) = swapchain.name.as_deref() {
+            unsafe {
+                swapchain.device.set_debug_utils_object_name_if_enabled(
+                    ash::vk::ObjectType::SWAPCHAIN_KHR,
+                    swapchain.handle.as_raw(),
+                    Some(name),
+                )?;
+            }
+        }
+
         let swapchain_images = (0..swapchain.images.len())
             .map(|n| unsafe { SwapchainImage::from_raw(swapchain.clone(), n as u32) })
             .collect::<Result<_, _>>()?;
@@ -230,6 +244,7 @@ where
             clipped,
             full_screen_exclusive,
             win32_monitor,
+            name,
             _ne: _,
         } = create_info;
 
@@ -251,6 +266,7 @@ where
             clipped,
             full_screen_exclusive,
             win32_monitor,
+            name,
             prev_present_id: Default::default(),
 
             full_screen_exclusive_held: AtomicBool::new(full_screen_exclusive_held),
@@ -258,6 +274,16 @@ where
             retired: Mutex::new(false),
         });
 
+        if let Some(name) = swapchain.name.as_deref() {
+            unsafe {
+                swapchain.device.set_debug_utils_object_name_if_enabled(
+                    ash::vk::ObjectType::SWAPCHAIN_KHR,
+                    swapchain.handle.as_raw(),
+                    Some(name),
+                )?;
+            }
+        }
+
         let swapchain_images = (0..swapchain.images.len())
             .map(|n| unsafe { SwapchainImage::from_raw(swapchain.clone(), n as u32) })
             .collect::<Result<_, _>>()?;
@@ -284,6 +310,7 @@ where
             clipped: _,
             full_screen_exclusive,
             win32_monitor,
+            name: _,
             _ne: _,
         } = create_info;
 
@@ -572,6 +599,7 @@ where
             clipped,
             full_screen_exclusive,
             win32_monitor,
+            name: _,
             _ne: _,
         } = create_info;
 
@@ -691,7 +719,7 @@ where
             image_extent,
             image_array_layers,
             image_usage,
-            image_sharing: _, // TODO: put this in the image too
+            ref image_sharing,
             ..
         } = create_info;
 
@@ -709,6 +737,7 @@ where
                         device.clone(),
                         handle,
                         image_usage,
+                        image_sharing.clone(),
                         image_format.unwrap(),
                         ImageCreateFlags::empty(),
                         dims,
@@ -741,6 +770,7 @@ where
             clipped: self.clipped,
             full_screen_exclusive: self.full_screen_exclusive,
             win32_monitor: self.win32_monitor,
+            name: self.name.clone(),
             _ne: crate::NonExhaustive(()),
         }
     }
@@ -775,6 +805,12 @@ where
         self.full_screen_exclusive
     }
 
+    /// Returns the name that the swapchain was created with, if any.
+    #[inline]
+    pub fn name(&self) -> Option<&str> {
+        self.name.as_deref()
+    }
+
     /// Acquires full-screen exclusivity.
     ///
     /// The swapchain must have been created with [`FullScreenExclusive::ApplicationControlled`],
@@ -846,6 +882,90 @@ where
         }
     }
 
+    /// Sets the HDR metadata of the swapchain, which describes the mastering display and the
+    /// content of the images to the presentation engine.
+    ///
+    /// The metadata applies to the images presented after this call. It is only meaningful if
+    /// the swapchain uses an HDR color space, such as [`ColorSpace::Hdr10St2084`].
+    ///
+    /// The [`ext_hdr_metadata`](crate::device::DeviceExtensions::ext_hdr_metadata) extension must
+    /// be enabled on the device. The values of `metadata` must not be negative, and
+    /// `min_luminance` must not be greater than `max_luminance`.
+    pub fn set_hdr_metadata(&self, metadata: &HdrMetadata) -> Result<(), HdrMetadataError> {
+        self.validate_set_hdr_metadata(metadata)?;
+
+        unsafe {
+            self.set_hdr_metadata_unchecked(metadata);
+        }
+
+        Ok(())
+    }
+
+    fn validate_set_hdr_metadata(&self, metadata: &HdrMetadata) -> Result<(), HdrMetadataError> {
+        if !self.device.enabled_extensions().ext_hdr_metadata {
+            return Err(HdrMetadataError::RequirementNotMet {
+                required_for: "`set_hdr_metadata`",
+                requires_one_of: RequiresOneOf {
+                    device_extensions: &["ext_hdr_metadata"],
+                    ..Default::default()
+                },
+            });
+        }
+
+        let &HdrMetadata {
+            display_primary_red,
+            display_primary_green,
+            display_primary_blue,
+            white_point,
+            max_luminance,
+            min_luminance,
+            max_content_light_level,
+            max_frame_average_light_level,
+            _ne: _,
+        } = metadata;
+
+        for (field, value) in [
+            ("display_primary_red", display_primary_red[0]),
+            ("display_primary_red", display_primary_red[1]),
+            ("display_primary_green", display_primary_green[0]),
+            ("display_primary_green", display_primary_green[1]),
+            ("display_primary_blue", display_primary_blue[0]),
+            ("display_primary_blue", display_primary_blue[1]),
+            ("white_point", white_point[0]),
+            ("white_point", white_point[1]),
+            ("max_luminance", max_luminance),
+            ("min_luminance", min_luminance),
+            ("max_content_light_level", max_content_light_level),
+            (
+                "max_frame_average_light_level",
+                max_frame_average_light_level,
+            ),
+        ] {
+            if value.is_nan() || value < 0.0 {
+                return Err(HdrMetadataError::ValueNegative { field });
+            }
+        }
+
+        if min_luminance > max_luminance {
+            return Err(HdrMetadataError::MinLuminanceGreaterThanMaxLuminance);
+        }
+
+        Ok(())
+    }
+
+    #[cfg_attr(not(feature = "document_unchecked"), doc(hidden))]
+    pub unsafe fn set_hdr_metadata_unchecked(&self, metadata: &HdrMetadata) {
+        let metadata = ash::vk::HdrMetadataEXT::from(metadata);
+
+        let fns = self.device.fns();
+        (fns.ext_hdr_metadata.set_hdr_metadata_ext)(
+            self.device.internal_object(),
+            1,
+            &self.handle,
+            &metadata,
+        );
+    }
+
     // This method is necessary to allow `SwapchainImage`s to signal when they have been
     // transitioned out of their initial `undefined` image layout.
     //
@@ -929,6 +1049,7 @@ impl<W> Debug for Swapchain<W> {
             clipped,
             full_screen_exclusive,
             win32_monitor,
+            name,
             prev_present_id,
             full_screen_exclusive_held,
             images,
@@ -952,6 +1073,7 @@ impl<W> Debug for Swapchain<W> {
             .field("clipped", &clipped)
             .field("full_screen_exclusive", &full_screen_exclusive)
             .field("win32_monitor", &win32_monitor)
+            .field("name", &name)
             .field("prev_present_id", &prev_present_id)
             .field("full_screen_exclusive_held", &full_screen_exclusive_held)
             .field("images", &images)
@@ -1141,6 +1263,18 @@ pub struct SwapchainCreateInfo {
     /// The default value is `None`.
     pub win32_monitor: Option<Win32Monitor>,
 
+    /// A name for the swapchain.
+    ///
+    /// If the `ext_debug_utils` extension is enabled on the instance, the name is assigned to the
+    /// swapchain with [`Device::set_debug_utils_object_name`], so that debugging tools show it.
+    /// Otherwise, this doesn't require the extension.
+    ///
+    /// When the swapchain is recreated with the value returned by [`Swapchain::create_info`],
+    /// the new swapchain keeps the name.
+    ///
+    /// The default value is `None`.
+    pub name: Option<String>,
+
     pub _ne: crate::NonExhaustive,
 }
 
@@ -1161,6 +1295,7 @@ impl Default for SwapchainCreateInfo {
             clipped: true,
             full_screen_exclusive: FullScreenExclusive::Default,
             win32_monitor: None,
+            name: None,
             _ne: crate::NonExhaustive(()),
         }
     }
@@ -1526,6 +1661,151 @@ impl From<OomError> for FullScreenExclusiveError {
     }
 }
 
+/// HDR metadata of a swapchain, as defined by the SMPTE ST 2086 and CTA-861.3 standards.
+///
+/// The chromaticity coordinates are given in the CIE 1931 xy color space, and the light levels
+/// in nits (candelas per square meter).
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct HdrMetadata {
+    /// The chromaticity of the red primary of the mastering display.
+    ///
+    /// The default value is `[0.708, 0.292]`, the red primary of BT.2020.
+    pub display_primary_red: [f32; 2],
+
+    /// The chromaticity of the green primary of the mastering display.
+    ///
+    /// The default value is `[0.170, 0.797]`, the green primary of BT.2020.
+    pub display_primary_green: [f32; 2],
+
+    /// The chromaticity of the blue primary of the mastering display.
+    ///
+    /// The default value is `[0.131, 0.046]`, the blue primary of BT.2020.
+    pub display_primary_blue: [f32; 2],
+
+    /// The chromaticity of the white point of the mastering display.
+    ///
+    /// The default value is `[0.3127, 0.3290]`, the D65 white point.
+    pub white_point: [f32; 2],
+
+    /// The maximum luminance of the mastering display.
+    ///
+    /// The default value is `1000.0`.
+    pub max_luminance: f32,
+
+    /// The minimum luminance of the mastering display.
+    ///
+    /// The default value is `0.001`.
+    pub min_luminance: f32,
+
+    /// The maximum content light level (MaxCLL): the light level of the brightest pixel of the
+    /// content.
+    ///
+    /// A value of `0.0` means that it is unknown. The default value is `0.0`.
+    pub max_content_light_level: f32,
+
+    /// The maximum frame-average light level (MaxFALL) of the content.
+    ///
+    /// A value of `0.0` means that it is unknown. The default value is `0.0`.
+    pub max_frame_average_light_level: f32,
+
+    pub _ne: crate::NonExhaustive,
+}
+
+impl Default for HdrMetadata {
+    #[inline]
+    fn default() -> Self {
+        Self {
+            display_primary_red: [0.708, 0.292],
+            display_primary_green: [0.170, 0.797],
+            display_primary_blue: [0.131, 0.046],
+            white_point: [0.3127, 0.3290],
+            max_luminance: 1000.0,
+            min_luminance: 0.001,
+            max_content_light_level: 0.0,
+            max_frame_average_light_level: 0.0,
+            _ne: crate::NonExhaustive(()),
+        }
+    }
+}
+
+impl From<&HdrMetadata> for ash::vk::HdrMetadataEXT {
+    #[inline]
+    fn from(val: &HdrMetadata) -> Self {
+        let &HdrMetadata {
+            display_primary_red,
+            display_primary_green,
+            display_primary_blue,
+            white_point,
+            max_luminance,
+            min_luminance,
+            max_content_light_level,
+            max_frame_average_light_level,
+            _ne: _,
+        } = val;
+
+        let xy = |[x, y]: [f32; 2]| ash::vk::XYColorEXT { x, y };
+
+        ash::vk::HdrMetadataEXT {
+            display_primary_red: xy(display_primary_red),
+            display_primary_green: xy(display_primary_green),
+            display_primary_blue: xy(display_primary_blue),
+            white_point: xy(white_point),
+            max_luminance,
+            min_luminance,
+            max_content_light_level,
+            max_frame_average_light_level,
+            ..Default::default()
+        }
+    }
+}
+
+/// Error that can happen when calling `Swapchain::set_hdr_metadata`.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum HdrMetadataError {
+    RequirementNotMet {
+        required_for: &'static str,
+        requires_one_of: RequiresOneOf,
+    },
+
+    /// `min_luminance` is greater than `max_luminance`.
+    MinLuminanceGreaterThanMaxLuminance,
+
+    /// A value of the metadata is negative or NaN.
+    ValueNegative { field: &'static str },
+}
+
+impl Error for HdrMetadataError {}
+
+impl Display for HdrMetadataError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result<(), FmtError> {
+        match self {
+            Self::RequirementNotMet {
+                required_for,
+                requires_one_of,
+            } => write!(
+                f,
+                "a requirement was not met for: {}; requires one of: {}",
+                required_for, requires_one_of,
+            ),
+            Self::MinLuminanceGreaterThanMaxLuminance => {
+                write!(f, "`min_luminance` is greater than `max_luminance`",)
+            }
+            Self::ValueNegative { field } => {
+                write!(f, "the value of `{}` is negative or NaN", field)
+            }
+        }
+    }
+}
+
+impl From<RequirementNotMet> for HdrMetadataError {
+    fn from(err: RequirementNotMet) -> Self {
+        Self::RequirementNotMet {
+            required_for: err.required_for,
+            requires_one_of: err.requires_one_of,
+        }
+    }
+}
+
 /// Tries to take ownership of an image in order to draw on it.
 ///
 /// The function returns the index of the image in the array of images that was returned
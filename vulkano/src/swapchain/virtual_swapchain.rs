@@ -0,0 +1,981 @@
+// Copyright (c) 2022 The vulkano developers
+// Licensed under the Apache License, Version 2.0
+// <LICENSE-APACHE or
+// https://www.apache.org/licenses/LICENSE-2.0> or the MIT
+// license <LICENSE-MIT or https://opensource.org/licenses/MIT>,
+// at your option. All files in the project carrying such
+// notice may not be copied, modified, or distributed except
+// according to those terms.
+
+use crate::{
+    buffer::{sys::UnsafeBuffer, BufferUsage, CpuAccessibleBuffer},
+    command_buffer::{
+        AutoCommandBufferBuilder, BuildError, CommandBufferBeginError, CommandBufferExecError,
+        CommandBufferExecFuture, CommandBufferUsage, CopyError, CopyImageToBufferInfo,
+    },
+    device::{Device, DeviceOwned, Queue},
+    format::Format,
+    image::{
+        sys::UnsafeImage, ImageCreateFlags, ImageCreationError, ImageDimensions, ImageLayout,
+        ImageUsage, StorageImage,
+    },
+    memory::DeviceMemoryError,
+    sync::{
+        AccessCheckError, AccessFlags, FenceSignalFuture, FlushError, GpuFuture, PipelineStages,
+        SubmitAnyBuilder,
+    },
+    DeviceSize, OomError,
+};
+use parking_lot::{Condvar, Mutex};
+use std::{
+    collections::VecDeque,
+    error::Error,
+    fmt::{Debug, Display, Error as FmtError, Formatter},
+    ops::Range,
+    sync::Arc,
+    time::{Duration, Instant},
+};
+
+/// A swapchain that is not backed by a surface, but by a ring of ordinary images.
+///
+/// This follows the same acquire/present flow as a [`Swapchain`](super::Swapchain), so rendering
+/// code can run unchanged in environments without a window system, such as CI or a video encoding
+/// server. Each presented image is copied into a host-visible buffer, which is handed to the
+/// `on_present` callback once the GPU has finished writing it.
+///
+/// ```no_run
+/// # use std::sync::{mpsc, Arc};
+/// # use vulkano::{
+/// #     device::{Device, Queue},
+/// #     format::Format,
+/// #     swapchain::{VirtualSwapchain, VirtualSwapchainCreateInfo},
+/// #     sync::{self, GpuFuture},
+/// # };
+/// # fn example(device: Arc<Device>, queue: Arc<Queue>) {
+/// let (sender, receiver) = mpsc::channel();
+/// let (swapchain, images) = VirtualSwapchain::new(
+///     device.clone(),
+///     VirtualSwapchainCreateInfo {
+///         image_format: Format::R8G8B8A8_UNORM,
+///         image_extent: [1280, 720],
+///         ..Default::default()
+///     },
+///     move |frame| sender.send(frame).unwrap(),
+/// )
+/// .unwrap();
+///
+/// let (image_index, _suboptimal, acquire_future) =
+///     swapchain.acquire_next_image(None).unwrap();
+/// // Draw to `images[image_index]`...
+/// let future = acquire_future
+///     .then_virtual_swapchain_present(queue, swapchain.clone(), image_index)
+///     .unwrap()
+///     .then_signal_fence_and_flush()
+///     .unwrap();
+/// future.wait(None).unwrap();
+///
+/// // Frames are delivered when the swapchain is next used.
+/// swapchain.acquire_next_image(None).unwrap();
+/// let frame = receiver.recv().unwrap();
+/// let pixels = frame.buffer.read().unwrap();
+/// # }
+/// ```
+pub struct VirtualSwapchain {
+    device: Arc<Device>,
+    image_format: Format,
+    image_extent: [u32; 2],
+    image_array_layers: u32,
+    image_usage: ImageUsage,
+    images: Vec<Arc<StorageImage>>,
+
+    state: Mutex<VirtualSwapchainState>,
+    // Notified when a frame is presented or delivered, to wake up threads that wait to acquire.
+    state_changed: Condvar,
+    on_present: Mutex<Box<dyn FnMut(PresentedFrame) + Send>>,
+}
+
+struct VirtualSwapchainState {
+    available: VecDeque<u32>,
+    acquired: Vec<bool>,
+    pending: VecDeque<PendingFrame>,
+    frame_count: u64,
+}
+
+struct PendingFrame {
+    image_index: u32,
+    frame_number: u64,
+    buffer: Arc<CpuAccessibleBuffer<[u8]>>,
+    future: Box<dyn PresentFence + Send + Sync>,
+}
+
+// Type-erased access to the fence of a present operation.
+trait PresentFence {
+    fn is_signaled(&self) -> Result<bool, OomError>;
+    fn wait(&self, timeout: Option<Duration>) -> Result<(), FlushError>;
+}
+
+impl<F> PresentFence for Arc<FenceSignalFuture<F>>
+where
+    F: GpuFuture,
+{
+    fn is_signaled(&self) -> Result<bool, OomError> {
+        FenceSignalFuture::is_signaled(self)
+    }
+
+    fn wait(&self, timeout: Option<Duration>) -> Result<(), FlushError> {
+        FenceSignalFuture::wait(self, timeout)
+    }
+}
+
+impl VirtualSwapchain {
+    /// Creates a new `VirtualSwapchain`, and returns it along with its images.
+    ///
+    /// `on_present` is called with every presented frame, in presentation order, from within
+    /// `acquire_next_image` or `deliver_presented_frames`. To receive the frames elsewhere, send
+    /// them over a channel from the callback.
+    pub fn new(
+        device: Arc<Device>,
+        create_info: VirtualSwapchainCreateInfo,
+        on_present: impl FnMut(PresentedFrame) + Send + 'static,
+    ) -> Result<(Arc<VirtualSwapchain>, Vec<Arc<StorageImage>>), VirtualSwapchainError> {
+        let VirtualSwapchainCreateInfo {
+            image_count,
+            image_format,
+            image_extent,
+            image_array_layers,
+            image_usage,
+            _ne: _,
+        } = create_info;
+
+        if image_count == 0 {
+            return Err(VirtualSwapchainError::ImageCountZero);
+        }
+
+        // The presented images must be copyable into a tightly packed buffer.
+        if !image_format.aspects().color
+            || !image_format.planes().is_empty()
+            || image_format.compression().is_some()
+        {
+            return Err(VirtualSwapchainError::FormatNotSupported {
+                format: image_format,
+            });
+        }
+
+        let image_usage = ImageUsage {
+            transfer_src: true,
+            ..image_usage
+        };
+
+        let images = (0..image_count)
+            .map(|_| {
+                StorageImage::with_usage(
+                    device.clone(),
+                    ImageDimensions::Dim2d {
+                        width: image_extent[0],
+                        height: image_extent[1],
+                        array_layers: image_array_layers,
+                    },
+                    image_format,
+                    image_usage,
+                    ImageCreateFlags::empty(),
+                    device.active_queue_family_indices().iter().copied(),
+                )
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+
+        let swapchain = Arc::new(VirtualSwapchain {
+            device,
+            image_format,
+            image_extent,
+            image_array_layers,
+            image_usage,
+            images: images.clone(),
+
+            state: Mutex::new(VirtualSwapchainState {
+                available: (0..image_count).collect(),
+                acquired: vec![false; image_count as usize],
+                pending: VecDeque::new(),
+                frame_count: 0,
+            }),
+            state_changed: Condvar::new(),
+            on_present: Mutex::new(Box::new(on_present)),
+        });
+
+        Ok((swapchain, images))
+    }
+
+    /// Returns the images of the swapchain.
+    #[inline]
+    pub fn images(&self) -> &[Arc<StorageImage>] {
+        &self.images
+    }
+
+    /// Returns the format of the images of the swapchain.
+    #[inline]
+    pub fn image_format(&self) -> Format {
+        self.image_format
+    }
+
+    /// Returns the extent of the images of the swapchain.
+    #[inline]
+    pub fn image_extent(&self) -> [u32; 2] {
+        self.image_extent
+    }
+
+    /// Returns the number of array layers of the images of the swapchain.
+    #[inline]
+    pub fn image_array_layers(&self) -> u32 {
+        self.image_array_layers
+    }
+
+    /// Returns the usage of the images of the swapchain.
+    ///
+    /// This always includes `transfer_src`, which is needed to read back presented images.
+    #[inline]
+    pub fn image_usage(&self) -> ImageUsage {
+        self.image_usage
+    }
+
+    /// Acquires an image to draw on, waiting for a previously presented image to be delivered if
+    /// none are available.
+    ///
+    /// Returns the index of the image, whether the swapchain is suboptimal (always `false`), and
+    /// a future representing the point at which the image is available. If `timeout` is `None`,
+    /// the wait is infinite. If all images are acquired, this waits for one of them to be
+    /// presented by another thread.
+    ///
+    /// Before acquiring, this delivers all presented frames that the GPU has finished with.
+    pub fn acquire_next_image(
+        self: &Arc<Self>,
+        timeout: Option<Duration>,
+    ) -> Result<(u32, bool, VirtualSwapchainAcquireFuture), VirtualSwapchainError> {
+        let deadline = timeout.map(|timeout| Instant::now() + timeout);
+
+        loop {
+            self.deliver_presented_frames()?;
+
+            let frame = {
+                let mut state = self.state.lock();
+
+                loop {
+                    if let Some(image_index) = state.available.pop_front() {
+                        state.acquired[image_index as usize] = true;
+
+                        return Ok((
+                            image_index,
+                            false,
+                            VirtualSwapchainAcquireFuture {
+                                swapchain: self.clone(),
+                                image_index,
+                            },
+                        ));
+                    }
+
+                    if let Some(frame) = state.pending.pop_front() {
+                        break frame;
+                    }
+
+                    // Every image is acquired, so wait for one to be presented.
+                    match deadline {
+                        Some(deadline) => {
+                            if self
+                                .state_changed
+                                .wait_until(&mut state, deadline)
+                                .timed_out()
+                            {
+                                return Err(VirtualSwapchainError::Timeout);
+                            }
+                        }
+                        None => self.state_changed.wait(&mut state),
+                    }
+                }
+            };
+
+            let timeout =
+                deadline.map(|deadline| deadline.saturating_duration_since(Instant::now()));
+
+            // If the wait times out, the future can still be waited on later.
+            if let Err(err) = frame.future.wait(timeout) {
+                self.state.lock().pending.push_front(frame);
+                return Err(err.into());
+            }
+
+            self.deliver(frame);
+        }
+    }
+
+    /// Presents an image after `before`, copying it into a buffer that is delivered to the
+    /// `on_present` callback once the GPU has finished.
+    ///
+    /// The image must have been acquired with `acquire_next_image`. The returned future is not
+    /// flushed.
+    ///
+    /// > **Note**: This is also available as
+    /// > [`GpuFuture::then_virtual_swapchain_present`].
+    pub fn present<P>(
+        self: &Arc<Self>,
+        before: P,
+        queue: Arc<Queue>,
+        image_index: u32,
+    ) -> Result<VirtualSwapchainPresentFuture<P>, VirtualSwapchainError>
+    where
+        P: GpuFuture + Send + 'static,
+    {
+        {
+            let state = self.state.lock();
+
+            match state.acquired.get(image_index as usize) {
+                Some(true) => (),
+                Some(false) => return Err(VirtualSwapchainError::ImageNotAcquired { image_index }),
+                None => {
+                    return Err(VirtualSwapchainError::ImageIndexOutOfRange {
+                        image_index,
+                        image_count: self.images.len() as u32,
+                    })
+                }
+            }
+        }
+
+        let image = self.images[image_index as usize].clone();
+
+        let [block_width, block_height, _] = self.image_format.block_extent();
+        let size = self.image_format.block_size().unwrap()
+            * ((self.image_extent[0] + block_width - 1) / block_width) as DeviceSize
+            * ((self.image_extent[1] + block_height - 1) / block_height) as DeviceSize
+            * self.image_array_layers as DeviceSize;
+
+        let buffer = unsafe {
+            CpuAccessibleBuffer::uninitialized_array(
+                self.device.clone(),
+                size,
+                BufferUsage {
+                    transfer_dst: true,
+                    ..BufferUsage::empty()
+                },
+                true,
+            )?
+        };
+
+        let mut cbb = AutoCommandBufferBuilder::primary(
+            self.device.clone(),
+            queue.queue_family_index(),
+            CommandBufferUsage::OneTimeSubmit,
+        )?;
+        cbb.copy_image_to_buffer(CopyImageToBufferInfo::image_buffer(image, buffer.clone()))?;
+        let cb = cbb.build()?;
+
+        let future = Arc::new(before.then_execute(queue, cb)?.then_signal_fence());
+
+        let mut state = self.state.lock();
+        state.acquired[image_index as usize] = false;
+        let frame_number = state.frame_count;
+        state.frame_count += 1;
+        state.pending.push_back(PendingFrame {
+            image_index,
+            frame_number,
+            buffer,
+            future: Box::new(future.clone()),
+        });
+        self.state_changed.notify_all();
+
+        Ok(VirtualSwapchainPresentFuture {
+            swapchain: self.clone(),
+            image_index,
+            inner: future,
+        })
+    }
+
+    /// Delivers all presented frames that the GPU has finished with to the `on_present` callback,
+    /// without blocking.
+    pub fn deliver_presented_frames(&self) -> Result<(), VirtualSwapchainError> {
+        loop {
+            let frame = {
+                let mut state = self.state.lock();
+
+                match state.pending.front() {
+                    Some(frame) if frame.future.is_signaled()? => {
+                        state.pending.pop_front().unwrap()
+                    }
+                    _ => return Ok(()),
+                }
+            };
+
+            // Cleans up the resources locked by the submission, including the buffer.
+            frame.future.wait(None)?;
+            self.deliver(frame);
+        }
+    }
+
+    fn deliver(&self, frame: PendingFrame) {
+        let PendingFrame {
+            image_index,
+            frame_number,
+            buffer,
+            future,
+        } = frame;
+        drop(future);
+
+        self.state.lock().available.push_back(image_index);
+        self.state_changed.notify_all();
+
+        (self.on_present.lock())(PresentedFrame {
+            image_index,
+            frame_number,
+            format: self.image_format,
+            extent: self.image_extent,
+            array_layers: self.image_array_layers,
+            buffer,
+        });
+    }
+}
+
+unsafe impl DeviceOwned for VirtualSwapchain {
+    #[inline]
+    fn device(&self) -> &Arc<Device> {
+        &self.device
+    }
+}
+
+impl Debug for VirtualSwapchain {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result<(), FmtError> {
+        f.debug_struct("VirtualSwapchain")
+            .field("device", &self.device)
+            .field("image_format", &self.image_format)
+            .field("image_extent", &self.image_extent)
+            .field("image_array_layers", &self.image_array_layers)
+            .field("image_usage", &self.image_usage)
+            .field("images", &self.images)
+            .finish_non_exhaustive()
+    }
+}
+
+/// Represents the moment when an image of a `VirtualSwapchain` is available to draw on.
+///
+/// This is returned by [`VirtualSwapchain::acquire_next_image`]. The image is already available
+/// when it is acquired, so this future doesn't wait for anything.
+pub struct VirtualSwapchainAcquireFuture {
+    swapchain: Arc<VirtualSwapchain>,
+    image_index: u32,
+}
+
+impl VirtualSwapchainAcquireFuture {
+    /// Returns the index of the image in the list of images returned when creating the swapchain.
+    #[inline]
+    pub fn image_index(&self) -> u32 {
+        self.image_index
+    }
+
+    /// Returns the corresponding swapchain.
+    #[inline]
+    pub fn swapchain(&self) -> &Arc<VirtualSwapchain> {
+        &self.swapchain
+    }
+}
+
+unsafe impl GpuFuture for VirtualSwapchainAcquireFuture {
+    #[inline]
+    fn cleanup_finished(&mut self) {}
+
+    #[inline]
+    unsafe fn build_submission(&self) -> Result<SubmitAnyBuilder, FlushError> {
+        Ok(SubmitAnyBuilder::Empty)
+    }
+
+    #[inline]
+    fn flush(&self) -> Result<(), FlushError> {
+        Ok(())
+    }
+
+    #[inline]
+    unsafe fn signal_finished(&self) {}
+
+    #[inline]
+    fn queue_change_allowed(&self) -> bool {
+        true
+    }
+
+    #[inline]
+    fn queue(&self) -> Option<Arc<Queue>> {
+        None
+    }
+
+    #[inline]
+    fn check_buffer_access(
+        &self,
+        _buffer: &UnsafeBuffer,
+        _range: Range<DeviceSize>,
+        _exclusive: bool,
+        _queue: &Queue,
+    ) -> Result<Option<(PipelineStages, AccessFlags)>, AccessCheckError> {
+        Err(AccessCheckError::Unknown)
+    }
+
+    #[inline]
+    fn check_image_access(
+        &self,
+        _image: &UnsafeImage,
+        _range: Range<DeviceSize>,
+        _exclusive: bool,
+        _expected_layout: ImageLayout,
+        _queue: &Queue,
+    ) -> Result<Option<(PipelineStages, AccessFlags)>, AccessCheckError> {
+        Err(AccessCheckError::Unknown)
+    }
+
+    #[inline]
+    fn check_swapchain_image_acquired(
+        &self,
+        _image: &UnsafeImage,
+        _before: bool,
+    ) -> Result<(), AccessCheckError> {
+        Err(AccessCheckError::Unknown)
+    }
+}
+
+unsafe impl DeviceOwned for VirtualSwapchainAcquireFuture {
+    #[inline]
+    fn device(&self) -> &Arc<Device> {
+        &self.swapchain.device
+    }
+}
+
+/// Represents an image of a `VirtualSwapchain` being presented.
+///
+/// This is returned by [`VirtualSwapchain::present`]. The copy of the image is submitted when
+/// the future is flushed.
+pub struct VirtualSwapchainPresentFuture<P>
+where
+    P: GpuFuture,
+{
+    swapchain: Arc<VirtualSwapchain>,
+    image_index: u32,
+    inner: Arc<FenceSignalFuture<CommandBufferExecFuture<P>>>,
+}
+
+impl<P> VirtualSwapchainPresentFuture<P>
+where
+    P: GpuFuture,
+{
+    /// Returns the index of the image in the list of images returned when creating the swapchain.
+    #[inline]
+    pub fn image_index(&self) -> u32 {
+        self.image_index
+    }
+
+    /// Returns the corresponding swapchain.
+    #[inline]
+    pub fn swapchain(&self) -> &Arc<VirtualSwapchain> {
+        &self.swapchain
+    }
+}
+
+unsafe impl<P> GpuFuture for VirtualSwapchainPresentFuture<P>
+where
+    P: GpuFuture,
+{
+    #[inline]
+    fn cleanup_finished(&mut self) {
+        self.inner.cleanup_finished();
+    }
+
+    #[inline]
+    unsafe fn build_submission(&self) -> Result<SubmitAnyBuilder, FlushError> {
+        self.inner.build_submission()
+    }
+
+    #[inline]
+    fn flush(&self) -> Result<(), FlushError> {
+        self.inner.flush()
+    }
+
+    #[inline]
+    unsafe fn signal_finished(&self) {
+        self.inner.signal_finished()
+    }
+
+    #[inline]
+    fn queue_change_allowed(&self) -> bool {
+        self.inner.queue_change_allowed()
+    }
+
+    #[inline]
+    fn queue(&self) -> Option<Arc<Queue>> {
+        self.inner.queue()
+    }
+
+    #[inline]
+    fn check_buffer_access(
+        &self,
+        buffer: &UnsafeBuffer,
+        range: Range<DeviceSize>,
+        exclusive: bool,
+        queue: &Queue,
+    ) -> Result<Option<(PipelineStages, AccessFlags)>, AccessCheckError> {
+        self.inner
+            .check_buffer_access(buffer, range, exclusive, queue)
+    }
+
+    #[inline]
+    fn check_image_access(
+        &self,
+        image: &UnsafeImage,
+        range: Range<DeviceSize>,
+        exclusive: bool,
+        expected_layout: ImageLayout,
+        queue: &Queue,
+    ) -> Result<Option<(PipelineStages, AccessFlags)>, AccessCheckError> {
+        self.inner
+            .check_image_access(image, range, exclusive, expected_layout, queue)
+    }
+
+    #[inline]
+    fn check_swapchain_image_acquired(
+        &self,
+        image: &UnsafeImage,
+        before: bool,
+    ) -> Result<(), AccessCheckError> {
+        self.inner.check_swapchain_image_acquired(image, before)
+    }
+}
+
+unsafe impl<P> DeviceOwned for VirtualSwapchainPresentFuture<P>
+where
+    P: GpuFuture,
+{
+    #[inline]
+    fn device(&self) -> &Arc<Device> {
+        &self.swapchain.device
+    }
+}
+
+/// Parameters to create a new `VirtualSwapchain`.
+#[derive(Clone, Debug)]
+pub struct VirtualSwapchainCreateInfo {
+    /// The number of images in the swapchain.
+    ///
+    /// The default value is `2`.
+    pub image_count: u32,
+
+    /// The format of the images.
+    ///
+    /// The format must have a color aspect, and must not be compressed or multi-planar.
+    ///
+    /// The default value is `Format::B8G8R8A8_SRGB`.
+    pub image_format: Format,
+
+    /// The size of the images.
+    ///
+    /// The default value is `[0, 0]`, which must be overridden.
+    pub image_extent: [u32; 2],
+
+    /// The number of array layers of the images.
+    ///
+    /// The default value is `1`.
+    pub image_array_layers: u32,
+
+    /// How the images will be used.
+    ///
+    /// `transfer_src` is always added, as the images are copied when presented.
+    ///
+    /// The default value is `ImageUsage::empty()`, which must be overridden.
+    pub image_usage: ImageUsage,
+
+    pub _ne: crate::NonExhaustive,
+}
+
+impl Default for VirtualSwapchainCreateInfo {
+    #[inline]
+    fn default() -> Self {
+        Self {
+            image_count: 2,
+            image_format: Format::B8G8R8A8_SRGB,
+            image_extent: [0, 0],
+            image_array_layers: 1,
+            image_usage: ImageUsage::empty(),
+            _ne: crate::NonExhaustive(()),
+        }
+    }
+}
+
+/// A frame that was presented to a `VirtualSwapchain`.
+#[derive(Clone, Debug)]
+#[non_exhaustive]
+pub struct PresentedFrame {
+    /// The index of the swapchain image that was presented.
+    pub image_index: u32,
+
+    /// The number of frames that were presented before this one.
+    pub frame_number: u64,
+
+    /// The format of the image.
+    pub format: Format,
+
+    /// The size of the image.
+    pub extent: [u32; 2],
+
+    /// The number of array layers of the image.
+    pub array_layers: u32,
+
+    /// The contents of the image, tightly packed, with the array layers one after another.
+    pub buffer: Arc<CpuAccessibleBuffer<[u8]>>,
+}
+
+/// Error that can happen when using a `VirtualSwapchain`.
+#[derive(Clone, Debug)]
+pub enum VirtualSwapchainError {
+    /// Not enough memory.
+    OomError(OomError),
+
+    ImageCreationError(ImageCreationError),
+    DeviceMemoryAllocationError(DeviceMemoryError),
+    CommandBufferBeginError(CommandBufferBeginError),
+    CopyError(CopyError),
+    BuildError(BuildError),
+    CommandBufferExecError(CommandBufferExecError),
+    FlushError(FlushError),
+
+    /// The timeout was reached before an image became available, or all images are acquired.
+    Timeout,
+
+    /// `image_count` is zero.
+    ImageCountZero,
+
+    /// The image format is not supported by virtual swapchains.
+    FormatNotSupported {
+        format: Format,
+    },
+
+    /// The image index is not less than the number of images in the swapchain.
+    ImageIndexOutOfRange {
+        image_index: u32,
+        image_count: u32,
+    },
+
+    /// The image was not acquired before being presented.
+    ImageNotAcquired {
+        image_index: u32,
+    },
+}
+
+impl Error for VirtualSwapchainError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            Self::OomError(err) => Some(err),
+            Self::ImageCreationError(err) => Some(err),
+            Self::DeviceMemoryAllocationError(err) => Some(err),
+            Self::CommandBufferBeginError(err) => Some(err),
+            Self::CopyError(err) => Some(err),
+            Self::BuildError(err) => Some(err),
+            Self::CommandBufferExecError(err) => Some(err),
+            Self::FlushError(err) => Some(err),
+            _ => None,
+        }
+    }
+}
+
+impl Display for VirtualSwapchainError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result<(), FmtError> {
+        match self {
+            Self::OomError(_) => write!(f, "not enough memory available"),
+            Self::ImageCreationError(err) => err.fmt(f),
+            Self::DeviceMemoryAllocationError(err) => err.fmt(f),
+            Self::CommandBufferBeginError(err) => err.fmt(f),
+            Self::CopyError(err) => err.fmt(f),
+            Self::BuildError(err) => err.fmt(f),
+            Self::CommandBufferExecError(err) => err.fmt(f),
+            Self::FlushError(err) => err.fmt(f),
+            Self::Timeout => write!(f, "no image is available for acquiring yet"),
+            Self::ImageCountZero => write!(f, "`image_count` is zero"),
+            Self::FormatNotSupported { format } => write!(
+                f,
+                "the image format {:?} is not supported by virtual swapchains",
+                format,
+            ),
+            Self::ImageIndexOutOfRange {
+                image_index,
+                image_count,
+            } => write!(
+                f,
+                "the image index ({}) is not less than the number of images in the swapchain ({})",
+                image_index, image_count,
+            ),
+            Self::ImageNotAcquired { image_index } => write!(
+                f,
+                "the image at index {} was not acquired before being presented",
+                image_index,
+            ),
+        }
+    }
+}
+
+impl From<OomError> for VirtualSwapchainError {
+    fn from(err: OomError) -> Self {
+        Self::OomError(err)
+    }
+}
+
+impl From<ImageCreationError> for VirtualSwapchainError {
+    fn from(err: ImageCreationError) -> Self {
+        Self::ImageCreationError(err)
+    }
+}
+
+impl From<DeviceMemoryError> for VirtualSwapchainError {
+    fn from(err: DeviceMemoryError) -> Self {
+        Self::DeviceMemoryAllocationError(err)
+    }
+}
+
+impl From<CommandBufferBeginError> for VirtualSwapchainError {
+    fn from(err: CommandBufferBeginError) -> Self {
+        Self::CommandBufferBeginError(err)
+    }
+}
+
+impl From<CopyError> for VirtualSwapchainError {
+    fn from(err: CopyError) -> Self {
+        Self::CopyError(err)
+    }
+}
+
+impl From<BuildError> for VirtualSwapchainError {
+    fn from(err: BuildError) -> Self {
+        Self::BuildError(err)
+    }
+}
+
+impl From<CommandBufferExecError> for VirtualSwapchainError {
+    fn from(err: CommandBufferExecError) -> Self {
+        Self::CommandBufferExecError(err)
+    }
+}
+
+impl From<FlushError> for VirtualSwapchainError {
+    fn from(err: FlushError) -> Self {
+        match err {
+            FlushError::Timeout => Self::Timeout,
+            err => Self::FlushError(err),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{VirtualSwapchain, VirtualSwapchainCreateInfo, VirtualSwapchainError};
+    use crate::{
+        buffer::TypedBufferAccess,
+        device::DeviceOwned,
+        format::Format,
+        image::ImageUsage,
+        sync::{self, GpuFuture},
+    };
+    use std::{sync::mpsc, thread, time::Duration};
+
+    #[test]
+    fn present_delivers_frames() {
+        let (device, queue) = gfx_dev_and_queue!();
+
+        let (sender, receiver) = mpsc::channel();
+        let (swapchain, images) = VirtualSwapchain::new(
+            device,
+            VirtualSwapchainCreateInfo {
+                image_format: Format::R8G8B8A8_UNORM,
+                image_extent: [4, 4],
+                image_usage: ImageUsage {
+                    color_attachment: true,
+                    ..ImageUsage::empty()
+                },
+                ..Default::default()
+            },
+            move |frame| sender.send(frame).unwrap(),
+        )
+        .unwrap();
+        assert_eq!(images.len(), 2);
+
+        let (image_index, suboptimal, acquire_future) = swapchain.acquire_next_image(None).unwrap();
+        assert!(!suboptimal);
+
+        assert_eq!(acquire_future.image_index(), image_index);
+
+        let future = acquire_future
+            .then_virtual_swapchain_present(queue.clone(), swapchain.clone(), image_index)
+            .unwrap()
+            .then_signal_fence_and_flush()
+            .unwrap();
+        future.wait(None).unwrap();
+        assert!(receiver.try_recv().is_err());
+
+        swapchain.deliver_presented_frames().unwrap();
+        let frame = receiver.try_recv().unwrap();
+        assert_eq!(frame.image_index, image_index);
+        assert_eq!(frame.frame_number, 0);
+        assert_eq!(frame.buffer.len(), 4 * 4 * 4);
+
+        assert!(matches!(
+            swapchain.present(sync::now(swapchain.device().clone()), queue, image_index),
+            Err(VirtualSwapchainError::ImageNotAcquired { .. })
+        ));
+    }
+
+    #[test]
+    fn acquire_all_images() {
+        let (device, _) = gfx_dev_and_queue!();
+
+        let (swapchain, _) = VirtualSwapchain::new(
+            device,
+            VirtualSwapchainCreateInfo {
+                image_count: 1,
+                image_format: Format::R8G8B8A8_UNORM,
+                image_extent: [4, 4],
+                ..Default::default()
+            },
+            |_| (),
+        )
+        .unwrap();
+
+        swapchain.acquire_next_image(None).unwrap();
+        assert!(matches!(
+            swapchain.acquire_next_image(Some(Duration::from_millis(10))),
+            Err(VirtualSwapchainError::Timeout)
+        ));
+    }
+
+    #[test]
+    fn acquire_waits_for_present() {
+        fn assert_send_sync<T: Send + Sync>() {}
+        assert_send_sync::<VirtualSwapchain>();
+
+        let (device, queue) = gfx_dev_and_queue!();
+
+        let (swapchain, _) = VirtualSwapchain::new(
+            device,
+            VirtualSwapchainCreateInfo {
+                image_count: 1,
+                image_format: Format::R8G8B8A8_UNORM,
+                image_extent: [4, 4],
+                ..Default::default()
+            },
+            |_| (),
+        )
+        .unwrap();
+
+        let (image_index, _, acquire_future) = swapchain.acquire_next_image(None).unwrap();
+
+        let present_thread = {
+            let swapchain = swapchain.clone();
+            thread::spawn(move || {
+                thread::sleep(Duration::from_millis(10));
+                acquire_future
+                    .then_virtual_swapchain_present(queue, swapchain, image_index)
+                    .unwrap()
+                    .flush()
+                    .unwrap();
+            })
+        };
+
+        // Blocks until the other thread has presented the image and the copy has finished.
+        let (next_image_index, _, _) = swapchain.acquire_next_image(None).unwrap();
+        assert_eq!(next_image_index, image_index);
+        present_thread.join().unwrap();
+    }
+}
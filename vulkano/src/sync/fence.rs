@@ -17,6 +17,7 @@ use smallvec::SmallVec;
 use std::{
     error::Error,
     fmt::{Display, Error as FmtError, Formatter},
+    fs::File,
     hash::{Hash, Hasher},
     mem::MaybeUninit,
     ptr,
@@ -56,7 +57,7 @@ pub struct Fence {
     // If true, will be put back into fence pool on drop.
     must_put_in_pool: bool,
 
-    _export_handle_types: ExternalFenceHandleTypes,
+    export_handle_types: ExternalFenceHandleTypes,
 
     state: Mutex<FenceState>,
 }
@@ -158,7 +159,7 @@ impl Fence {
             device,
             must_put_in_pool: false,
 
-            _export_handle_types: export_handle_types,
+            export_handle_types,
 
             state: Mutex::new(FenceState {
                 is_signaled: signaled,
@@ -191,7 +192,7 @@ impl Fence {
                     device,
                     must_put_in_pool: true,
 
-                    _export_handle_types: ExternalFenceHandleTypes::empty(),
+                    export_handle_types: ExternalFenceHandleTypes::empty(),
 
                     state: Mutex::new(Default::default()),
                 }
@@ -230,7 +231,7 @@ impl Fence {
             device,
             must_put_in_pool: false,
 
-            _export_handle_types: export_handle_types,
+            export_handle_types,
 
             state: Mutex::new(FenceState {
                 is_signaled: signaled,
@@ -549,6 +550,110 @@ impl Fence {
         Ok(())
     }
 
+    /// Exports the fence into a POSIX file descriptor. The caller owns the returned `File`.
+    ///
+    /// If `handle_type` is [`ExternalFenceHandleType::SyncFd`], the fence must be signaled or have
+    /// a pending signal operation, and exporting has the same effect on the fence as resetting it:
+    /// the returned file descriptor takes over the pending signal operation, and the fence should
+    /// not be waited on until it is submitted again.
+    #[inline]
+    pub fn export_fd(&self, handle_type: ExternalFenceHandleType) -> Result<File, FenceError> {
+        let mut state = self.state.lock();
+        self.validate_export_fd(handle_type, &state)?;
+
+        unsafe { Ok(self.export_fd_unchecked_locked(handle_type, &mut state)?) }
+    }
+
+    fn validate_export_fd(
+        &self,
+        handle_type: ExternalFenceHandleType,
+        state: &FenceState,
+    ) -> Result<(), FenceError> {
+        if !self.device.enabled_extensions().khr_external_fence_fd {
+            return Err(FenceError::RequirementNotMet {
+                required_for: "`export_fd`",
+                requires_one_of: RequiresOneOf {
+                    device_extensions: &["khr_external_fence_fd"],
+                    ..Default::default()
+                },
+            });
+        }
+
+        // VUID-VkFenceGetFdInfoKHR-handleType-parameter
+        handle_type.validate_device(&self.device)?;
+
+        // VUID-VkFenceGetFdInfoKHR-handleType-01453
+        if !self.export_handle_types.intersects(&handle_type.into()) {
+            return Err(FenceError::HandleTypeNotSupported { handle_type });
+        }
+
+        // VUID-VkFenceGetFdInfoKHR-handleType-01456
+        if !matches!(
+            handle_type,
+            ExternalFenceHandleType::OpaqueFd | ExternalFenceHandleType::SyncFd
+        ) {
+            return Err(FenceError::HandleTypeNotSupported { handle_type });
+        }
+
+        // VUID-VkFenceGetFdInfoKHR-handleType-01454
+        if handle_type == ExternalFenceHandleType::SyncFd && state.status() == Some(false) {
+            return Err(FenceError::HandleTypeCopyNotSignaled);
+        }
+
+        // VUID-VkFenceGetFdInfoKHR-fence-01455
+        // TODO: imported payloads are not tracked yet.
+
+        Ok(())
+    }
+
+    #[cfg_attr(not(feature = "document_unchecked"), doc(hidden))]
+    #[inline]
+    pub unsafe fn export_fd_unchecked(
+        &self,
+        handle_type: ExternalFenceHandleType,
+    ) -> Result<File, VulkanError> {
+        let mut state = self.state.lock();
+        self.export_fd_unchecked_locked(handle_type, &mut state)
+    }
+
+    #[cfg(not(unix))]
+    unsafe fn export_fd_unchecked_locked(
+        &self,
+        _handle_type: ExternalFenceHandleType,
+        _state: &mut FenceState,
+    ) -> Result<File, VulkanError> {
+        unreachable!("`khr_external_fence_fd` was somehow enabled on a non-Unix system");
+    }
+
+    #[cfg(unix)]
+    unsafe fn export_fd_unchecked_locked(
+        &self,
+        handle_type: ExternalFenceHandleType,
+        state: &mut FenceState,
+    ) -> Result<File, VulkanError> {
+        use std::os::unix::io::FromRawFd;
+
+        let info = ash::vk::FenceGetFdInfoKHR {
+            fence: self.handle,
+            handle_type: handle_type.into(),
+            ..Default::default()
+        };
+
+        let mut output = MaybeUninit::uninit();
+        let fns = self.device.fns();
+        (fns.khr_external_fence_fd.get_fence_fd_khr)(
+            self.device.internal_object(),
+            &info,
+            output.as_mut_ptr(),
+        )
+        .result()
+        .map_err(VulkanError::from)?;
+
+        state.export(handle_type);
+
+        Ok(File::from_raw_fd(output.assume_init()))
+    }
+
     pub(crate) fn lock(&self) -> MutexGuard<'_, FenceState> {
         self.state.lock()
     }
@@ -640,6 +745,15 @@ impl FenceState {
         debug_assert!(self.in_use_by.is_none());
         self.is_signaled = false;
     }
+
+    /// Called when the payload of the fence has been exported.
+    pub(crate) unsafe fn export(&mut self, handle_type: ExternalFenceHandleType) {
+        // Exporting a payload with copy transference has the same effect as a reset.
+        // If a queue operation is still pending, the queue keeps tracking its resources.
+        if handle_type == ExternalFenceHandleType::SyncFd {
+            self.is_signaled = false;
+        }
+    }
 }
 
 /// Parameters to create a new `Fence`.
@@ -789,6 +903,16 @@ pub enum FenceError {
 
     /// The fence is currently in use by a queue.
     InUse,
+
+    /// The requested export handle type was not provided in `export_handle_types` when creating the
+    /// fence, or is not a file descriptor handle type.
+    HandleTypeNotSupported {
+        handle_type: ExternalFenceHandleType,
+    },
+
+    /// A handle type with copy transference was requested, but the fence is not signaled and does
+    /// not have a pending signal operation.
+    HandleTypeCopyNotSignaled,
 }
 
 impl Error for FenceError {
@@ -815,6 +939,17 @@ impl Display for FenceError {
                 required_for, requires_one_of,
             ),
             Self::InUse => write!(f, "the fence is currently in use by a queue"),
+            Self::HandleTypeNotSupported { handle_type } => write!(
+                f,
+                "the requested export handle type ({:?}) was not provided in `export_handle_types` \
+                when creating the fence, or is not a file descriptor handle type",
+                handle_type,
+            ),
+            Self::HandleTypeCopyNotSignaled => write!(
+                f,
+                "a handle type with copy transference was requested, but the fence is not \
+                signaled and does not have a pending signal operation",
+            ),
         }
     }
 }
@@ -849,7 +984,7 @@ impl From<RequirementNotMet> for FenceError {
 #[cfg(test)]
 mod tests {
     use crate::{
-        sync::{fence::FenceCreateInfo, Fence},
+        sync::{fence::FenceCreateInfo, ExternalFenceHandleType, Fence, FenceError},
         VulkanObject,
     };
     use std::time::Duration;
@@ -981,4 +1116,16 @@ mod tests {
         assert_eq!(device.fence_pool().lock().len(), 0);
         assert_eq!(fence2.internal_object(), fence1_internal_obj);
     }
+
+    #[test]
+    fn export_fd_missing_extension() {
+        let (device, _) = gfx_dev_and_queue!();
+
+        let fence = Fence::new(device, Default::default()).unwrap();
+
+        assert!(matches!(
+            fence.export_fd(ExternalFenceHandleType::SyncFd),
+            Err(FenceError::RequirementNotMet { .. })
+        ));
+    }
 }
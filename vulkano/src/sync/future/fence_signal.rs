@@ -133,11 +133,24 @@ where
         }
     }
 
+    /// Returns the fence that the future signals, or `None` if the submission has already
+    /// finished and the fence was released.
+    pub(crate) fn fence(&self) -> Option<Arc<Fence>> {
+        match &*self.state.lock() {
+            FenceSignalFutureState::Pending(_, fence)
+            | FenceSignalFutureState::PartiallyFlushed(_, fence)
+            | FenceSignalFutureState::Flushed(_, fence) => Some(fence.clone()),
+            FenceSignalFutureState::Cleaned => None,
+            FenceSignalFutureState::Poisoned => unreachable!(),
+        }
+    }
+
     /// Blocks the current thread until the fence is signaled by the GPU. Performs a flush if
     /// necessary.
     ///
     /// If `timeout` is `None`, then the wait is infinite. Otherwise the thread will unblock after
-    /// the specified timeout has elapsed and an error will be returned.
+    /// the specified timeout has elapsed and an error will be returned. The future can then be
+    /// waited on again.
     ///
     /// If the wait is successful, this function also cleans any resource locked by previous
     /// submissions.
@@ -148,7 +161,11 @@ where
 
         match replace(&mut *state, FenceSignalFutureState::Cleaned) {
             FenceSignalFutureState::Flushed(previous, fence) => {
-                fence.wait(timeout)?;
+                if let Err(err) = fence.wait(timeout) {
+                    *state = FenceSignalFutureState::Flushed(previous, fence);
+                    return Err(err.into());
+                }
+
                 unsafe {
                     previous.signal_finished();
                 }
@@ -13,7 +13,10 @@ pub use self::{
     now::{now, NowFuture},
     semaphore_signal::SemaphoreSignalFuture,
 };
-use super::{AccessFlags, Fence, FenceError, PipelineStages, Semaphore};
+use super::{
+    AccessFlags, ExternalSemaphoreHandleTypes, Fence, FenceError, PipelineStages, Semaphore,
+    SemaphoreError,
+};
 use crate::{
     buffer::sys::UnsafeBuffer,
     command_buffer::{
@@ -22,7 +25,10 @@ use crate::{
     device::{DeviceOwned, Queue},
     image::{sys::UnsafeImage, ImageLayout},
     memory::BindSparseInfo,
-    swapchain::{self, PresentFuture, PresentInfo, SwapchainPresentInfo},
+    swapchain::{
+        self, PresentFuture, PresentInfo, SwapchainPresentInfo, VirtualSwapchain,
+        VirtualSwapchainError, VirtualSwapchainPresentFuture,
+    },
     DeviceSize, OomError, VulkanError,
 };
 use smallvec::SmallVec;
@@ -230,6 +236,23 @@ pub unsafe trait GpuFuture: DeviceOwned {
         Ok(f)
     }
 
+    /// Same as `then_signal_semaphore`, but the semaphore can be exported as any of
+    /// `export_handle_types` with [`SemaphoreSignalFuture::export_fd`].
+    ///
+    /// This is useful for sharing the completion of GPU work with other APIs. For example,
+    /// exporting a [`SyncFd`](crate::sync::ExternalSemaphoreHandleType::SyncFd) after flushing
+    /// gives a sync file that DRM/KMS or a V4L2 device can wait on.
+    #[inline]
+    fn then_signal_semaphore_exportable(
+        self,
+        export_handle_types: ExternalSemaphoreHandleTypes,
+    ) -> Result<SemaphoreSignalFuture<Self>, SemaphoreError>
+    where
+        Self: Sized,
+    {
+        semaphore_signal::then_signal_semaphore_exportable(self, export_handle_types)
+    }
+
     /// Signals a fence after this future. Returns another future that represents the signal.
     ///
     /// > **Note**: More often than not you want to immediately flush the future after calling this
@@ -274,6 +297,22 @@ pub unsafe trait GpuFuture: DeviceOwned {
         swapchain::present(self, queue, swapchain_info)
     }
 
+    /// Presents an image of a `VirtualSwapchain` after this future.
+    ///
+    /// > **Note**: This is just a shortcut for the `VirtualSwapchain::present()` function.
+    #[inline]
+    fn then_virtual_swapchain_present(
+        self,
+        queue: Arc<Queue>,
+        swapchain: Arc<VirtualSwapchain>,
+        image_index: u32,
+    ) -> Result<VirtualSwapchainPresentFuture<Self>, VirtualSwapchainError>
+    where
+        Self: Sized + Send + 'static,
+    {
+        swapchain.present(self, queue, image_index)
+    }
+
     /// Turn the current future into a `Box<dyn GpuFuture>`.
     ///
     /// This is a helper function that calls `Box::new(yourFuture) as Box<dyn GpuFuture>`.
@@ -579,7 +618,10 @@ impl From<FenceError> for FlushError {
             FenceError::OomError(err) => FlushError::OomError(err),
             FenceError::Timeout => FlushError::Timeout,
             FenceError::DeviceLost => FlushError::DeviceLost,
-            FenceError::RequirementNotMet { .. } | FenceError::InUse => unreachable!(),
+            FenceError::RequirementNotMet { .. }
+            | FenceError::InUse
+            | FenceError::HandleTypeNotSupported { .. }
+            | FenceError::HandleTypeCopyNotSignaled => unreachable!(),
         }
     }
 }
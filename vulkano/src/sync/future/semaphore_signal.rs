@@ -13,12 +13,16 @@ use crate::{
     command_buffer::{SemaphoreSubmitInfo, SubmitInfo},
     device::{Device, DeviceOwned, Queue},
     image::{sys::UnsafeImage, ImageLayout},
-    sync::{AccessError, AccessFlags, PipelineStages, Semaphore},
+    sync::{
+        AccessError, AccessFlags, ExternalSemaphoreHandleType, ExternalSemaphoreHandleTypes,
+        PipelineStages, Semaphore, SemaphoreCreateInfo, SemaphoreError,
+    },
     DeviceSize,
 };
 use parking_lot::Mutex;
 use smallvec::smallvec;
 use std::{
+    fs::File,
     ops::Range,
     sync::{
         atomic::{AtomicBool, Ordering},
@@ -39,10 +43,41 @@ where
         previous: future,
         semaphore: Arc::new(Semaphore::from_pool(device).unwrap()),
         wait_submitted: Mutex::new(false),
+        consumed: AtomicBool::new(false),
         finished: AtomicBool::new(false),
     }
 }
 
+/// Builds a new semaphore signal future, whose semaphore can be exported as any of
+/// `export_handle_types`.
+pub fn then_signal_semaphore_exportable<F>(
+    future: F,
+    export_handle_types: ExternalSemaphoreHandleTypes,
+) -> Result<SemaphoreSignalFuture<F>, SemaphoreError>
+where
+    F: GpuFuture,
+{
+    let device = future.device().clone();
+
+    assert!(future.queue().is_some()); // TODO: document
+
+    let semaphore = Semaphore::new(
+        device,
+        SemaphoreCreateInfo {
+            export_handle_types,
+            ..Default::default()
+        },
+    )?;
+
+    Ok(SemaphoreSignalFuture {
+        previous: future,
+        semaphore: Arc::new(semaphore),
+        wait_submitted: Mutex::new(false),
+        consumed: AtomicBool::new(false),
+        finished: AtomicBool::new(false),
+    })
+}
+
 /// Represents a semaphore being signaled after a previous event.
 #[must_use = "Dropping this object will immediately block the thread until the GPU has finished \
               processing the submission"]
@@ -56,9 +91,50 @@ where
     // If flush is called multiple times, we want to block so that only one flushing is executed.
     // Therefore we use a `Mutex<bool>` and not an `AtomicBool`.
     wait_submitted: Mutex<bool>,
+    // True if the signal was consumed by exporting the semaphore with copy transference.
+    // Later submissions then no longer wait on the semaphore.
+    consumed: AtomicBool,
     finished: AtomicBool,
 }
 
+impl<F> SemaphoreSignalFuture<F>
+where
+    F: GpuFuture,
+{
+    /// Exports the semaphore of this future into a POSIX file descriptor. The caller owns the
+    /// returned `File`.
+    ///
+    /// The future must have been created with
+    /// [`then_signal_semaphore_exportable`](GpuFuture::then_signal_semaphore_exportable), with
+    /// `handle_type` among the export handle types.
+    ///
+    /// If `handle_type` is [`ExternalSemaphoreHandleType::SyncFd`], the future must have been
+    /// flushed, and the returned file descriptor takes over the signal: it can only be exported
+    /// once, and operations submitted after this future no longer wait on the semaphore.
+    pub fn export_fd(
+        &self,
+        handle_type: ExternalSemaphoreHandleType,
+    ) -> Result<File, SemaphoreError> {
+        let wait_submitted = self.wait_submitted.lock();
+
+        if handle_type == ExternalSemaphoreHandleType::SyncFd {
+            // VUID-VkSemaphoreGetFdInfoKHR-handleType-03254
+            if !*wait_submitted || self.consumed.load(Ordering::SeqCst) {
+                return Err(SemaphoreError::HandleTypeCopyNotSignaled);
+            }
+        }
+
+        // Safe because the semaphore of this future is never used to acquire a swapchain image.
+        let file = unsafe { self.semaphore.export_fd(handle_type)? };
+
+        if handle_type == ExternalSemaphoreHandleType::SyncFd {
+            self.consumed.store(true, Ordering::SeqCst);
+        }
+
+        Ok(file)
+    }
+}
+
 unsafe impl<F> GpuFuture for SemaphoreSignalFuture<F>
 where
     F: GpuFuture,
@@ -70,6 +146,11 @@ where
     unsafe fn build_submission(&self) -> Result<SubmitAnyBuilder, FlushError> {
         // Flushing the signaling part, since it must always be submitted before the waiting part.
         self.flush()?;
+
+        if self.consumed.load(Ordering::SeqCst) {
+            return Ok(SubmitAnyBuilder::Empty);
+        }
+
         let sem = smallvec![self.semaphore.clone()];
 
         Ok(SubmitAnyBuilder::SemaphoresWait(sem))
@@ -134,11 +215,17 @@ where
 
                     queue.with(|mut q| q.submit_unchecked([submit_info], fence))?;
                 }
-                SubmitAnyBuilder::BindSparse(_, _) => {
-                    unimplemented!() // TODO: how to do that?
-                                     /*debug_assert_eq!(builder.num_signal_semaphores(), 0);
-                                     builder.add_signal_semaphore(&self.semaphore);
-                                     builder.submit(&queue)?;*/
+                SubmitAnyBuilder::BindSparse(mut bind_infos, fence) => {
+                    debug_assert!(!bind_infos.is_empty());
+                    debug_assert!(bind_infos.last().unwrap().signal_semaphores.is_empty());
+
+                    bind_infos
+                        .last_mut()
+                        .unwrap()
+                        .signal_semaphores
+                        .push(self.semaphore.clone());
+
+                    queue.with(|mut q| q.bind_sparse_unchecked(bind_infos, fence))?;
                 }
                 SubmitAnyBuilder::QueuePresent(present_info) => {
                     // VUID-VkPresentIdKHR-presentIds-04999
@@ -197,7 +284,7 @@ where
     }
 
     fn queue_change_allowed(&self) -> bool {
-        true
+        !self.consumed.load(Ordering::SeqCst)
     }
 
     fn queue(&self) -> Option<Arc<Queue>> {
@@ -507,6 +507,10 @@ pub enum SemaphoreError {
     HandleTypeNotSupported {
         handle_type: ExternalSemaphoreHandleType,
     },
+
+    /// A handle type with copy transference was requested, but the semaphore is not signaled and
+    /// does not have a pending signal operation.
+    HandleTypeCopyNotSignaled,
 }
 
 impl Error for SemaphoreError {
@@ -536,6 +540,11 @@ impl Display for SemaphoreError {
                 when creating the semaphore",
                 handle_type,
             ),
+            Self::HandleTypeCopyNotSignaled => write!(
+                f,
+                "a handle type with copy transference was requested, but the semaphore is not \
+                signaled and does not have a pending signal operation",
+            ),
         }
     }
 }
@@ -27,14 +27,22 @@ macro_rules! instance {
 }
 
 /// Creates a device and a queue for graphics operations.
+///
+/// The device extensions to enable can be given with
+/// `gfx_dev_and_queue!(extensions: [...], features: [...])`.
 macro_rules! gfx_dev_and_queue {
-    ($($feature:ident),*) => ({
+    (extensions: [$($extension:ident),*], features: [$($feature:ident),*]) => ({
         use crate::device::physical::PhysicalDeviceType;
         use crate::device::{Device, DeviceCreateInfo, DeviceExtensions, QueueCreateInfo};
         use crate::device::Features;
 
         let instance = instance!();
-        let enabled_extensions = DeviceExtensions::empty();
+        let enabled_extensions = DeviceExtensions {
+            $(
+                $extension: true,
+            )*
+            .. DeviceExtensions::empty()
+        };
         let enabled_features = Features {
             $(
                 $feature: true,
@@ -88,6 +96,9 @@ macro_rules! gfx_dev_and_queue {
 
         (device, queues.next().unwrap())
     });
+    ($($feature:ident),*) => ({
+        gfx_dev_and_queue!(extensions: [], features: [$($feature),*])
+    });
 }
 
 macro_rules! assert_should_panic {